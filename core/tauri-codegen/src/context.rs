@@ -272,6 +272,23 @@ pub fn context_codegen(data: ContextData) -> Result<TokenStream, EmbeddedAssetsE
     }
   );
 
+  // lets the `dev-dynamic-assets` feature read the assets from disk
+  let with_frontend_dist_code = match &config.build.frontend_dist {
+    Some(FrontendDist::Directory(path)) if dev && config.build.dev_url.is_none() => {
+      let dir = config_parent.join(path).display().to_string();
+      quote!(context.set_frontend_dist_dir(::std::path::PathBuf::from(#dir));)
+    }
+    _ => quote!(),
+  };
+
+  // lets the `dev-config-reload` feature watch the config file
+  let with_config_dir_code = if dev {
+    let dir = config_parent.display().to_string();
+    quote!(context.set_config_dir(::std::path::PathBuf::from(#dir));)
+  } else {
+    quote!()
+  };
+
   let with_tray_icon_code = if target.is_desktop() {
     if let Some(tray) = &config.app.tray_icon {
       let tray_icon_icon_path = config_parent.join(&tray.icon_path);
@@ -404,6 +421,8 @@ pub fn context_codegen(data: ContextData) -> Result<TokenStream, EmbeddedAssetsE
       #resolved_acl
     );
     #with_tray_icon_code
+    #with_frontend_dist_code
+    #with_config_dir_code
     context
   }))
 }
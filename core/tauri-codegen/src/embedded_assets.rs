@@ -23,8 +23,8 @@ use brotli::enc::backward_references::BrotliEncoderParams;
 /// The subdirectory inside the target directory we want to place assets.
 const TARGET_PATH: &str = "tauri-codegen-assets";
 
-/// (key, (original filepath, compressed bytes))
-type Asset = (AssetKey, (PathBuf, PathBuf));
+/// (key, (original filepath, compressed filepath, original size, content hash))
+type Asset = (AssetKey, (PathBuf, PathBuf, usize, String));
 
 /// All possible errors while reading and compressing an [`EmbeddedAssets`] directory
 #[derive(Debug, Error)]
@@ -72,7 +72,7 @@ pub enum EmbeddedAssetsError {
 /// the compressed assets in that application's binary.
 #[derive(Default)]
 pub struct EmbeddedAssets {
-  assets: HashMap<AssetKey, (PathBuf, PathBuf)>,
+  assets: HashMap<AssetKey, (PathBuf, PathBuf, usize, String)>,
   csp_hashes: CspHashes,
 }
 
@@ -260,7 +260,7 @@ impl EmbeddedAssets {
 
     struct CompressState {
       csp_hashes: CspHashes,
-      assets: HashMap<AssetKey, (PathBuf, PathBuf)>,
+      assets: HashMap<AssetKey, (PathBuf, PathBuf, usize, String)>,
     }
 
     let CompressState { assets, csp_hashes } = paths.into_iter().try_fold(
@@ -325,6 +325,9 @@ impl EmbeddedAssets {
     // perform any caller-requested input manipulation
     map(&key, path, &mut input, csp_hashes)?;
 
+    // the size of the embedded asset once decompressed
+    let original_size = input.len();
+
     // we must canonicalize the base of our paths to allow long paths on windows
     let out_dir = std::env::var("OUT_DIR")
       .map_err(|_| EmbeddedAssetsError::OutDir)
@@ -354,7 +357,7 @@ impl EmbeddedAssets {
     let out_path = if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
       out_dir.join(format!("{hash}.{ext}"))
     } else {
-      out_dir.join(hash)
+      out_dir.join(&hash)
     };
 
     // only compress and write to the file if it doesn't already exist.
@@ -390,14 +393,16 @@ impl EmbeddedAssets {
       }
     }
 
-    Ok((key, (path.into(), out_path)))
+    Ok((key, (path.into(), out_path, original_size, hash)))
   }
 }
 
 impl ToTokens for EmbeddedAssets {
   fn to_tokens(&self, tokens: &mut TokenStream) {
     let mut assets = TokenStream::new();
-    for (key, (input, output)) in &self.assets {
+    let mut original_sizes = TokenStream::new();
+    let mut content_hashes = TokenStream::new();
+    for (key, (input, output, original_size, content_hash)) in &self.assets {
       let key: &str = key.as_ref();
       let input = input.display().to_string();
       let output = output.display().to_string();
@@ -407,6 +412,8 @@ impl ToTokens for EmbeddedAssets {
         const _: &[u8] = include_bytes!(#input);
         include_bytes!(#output)
       },));
+      original_sizes.append_all(quote!(#key => #original_size,));
+      content_hashes.append_all(quote!(#key => #content_hash,));
     }
 
     let mut global_hashes = TokenStream::new();
@@ -436,6 +443,8 @@ impl ToTokens for EmbeddedAssets {
         #[allow(unused_imports)]
         use ::tauri::utils::assets::{CspHash, EmbeddedAssets, phf, phf::phf_map};
         EmbeddedAssets::new(phf_map! { #assets }, &[#global_hashes], phf_map! { #html_hashes })
+          .with_original_sizes(phf_map! { #original_sizes })
+          .with_content_hashes(phf_map! { #content_hashes })
     }});
   }
 }
@@ -27,6 +27,7 @@ pub struct Handler {
   command_defs: Vec<CommandDef>,
   commands: Vec<Ident>,
   wrappers: Vec<Path>,
+  manifests: Vec<Path>,
 }
 
 impl Parse for Handler {
@@ -34,26 +35,34 @@ impl Parse for Handler {
     let command_defs = input.parse_terminated(CommandDef::parse, Token![,])?;
 
     // parse the command names and wrappers from the passed paths
-    let (commands, wrappers) = command_defs
-      .iter()
-      .map(|command_def| {
-        let mut wrapper = command_def.path.clone();
-        let last = super::path_to_command(&mut wrapper);
+    let mut commands = Vec::new();
+    let mut wrappers = Vec::new();
+    let mut manifests = Vec::new();
+    for command_def in &command_defs {
+      let mut wrapper = command_def.path.clone();
+      let last = super::path_to_command(&mut wrapper);
 
-        // the name of the actual command function
-        let command = last.ident.clone();
+      // the name of the actual command function
+      let command = last.ident.clone();
 
-        // set the path to the command function wrapper
-        last.ident = super::format_command_wrapper(&command);
+      // set the path to the command function wrapper
+      last.ident = super::format_command_wrapper(&command);
 
-        (command, wrapper)
-      })
-      .unzip();
+      let mut manifest = command_def.path.clone();
+      manifest
+        .segments
+        .push(super::command_manifest_ident().into());
+
+      commands.push(command);
+      wrappers.push(wrapper);
+      manifests.push(manifest);
+    }
 
     Ok(Self {
       command_defs: command_defs.into_iter().collect(), // remove punctuation separators
       commands,
       wrappers,
+      manifests,
     })
   }
 }
@@ -64,6 +73,7 @@ impl From<Handler> for proc_macro::TokenStream {
       command_defs,
       commands,
       wrappers,
+      manifests,
     }: Handler,
   ) -> Self {
     let cmd = format_ident!("__tauri_cmd__");
@@ -72,7 +82,7 @@ impl From<Handler> for proc_macro::TokenStream {
       .into_iter()
       .map(|def| (def.path, def.attrs))
       .unzip();
-    quote::quote!(move |#invoke| {
+    let handler = quote::quote!(move |#invoke| {
       let #cmd = #invoke.message.command();
       match #cmd {
         #(#(#attrs)* stringify!(#commands) => #wrappers!(#paths, #invoke),)*
@@ -80,7 +90,15 @@ impl From<Handler> for proc_macro::TokenStream {
           return false;
         },
       }
-    })
+    });
+
+    match wrappers.first() {
+      // the wrapper of the first command knows the path to the tauri crate
+      Some(wrapper) if cfg!(feature = "command-manifest") => quote::quote!(
+        #wrapper!(@manifest [#(#(#attrs)* #manifests),*], #handler)
+      ),
+      _ => handler,
+    }
     .into()
   }
 }
@@ -15,6 +15,12 @@ fn format_command_wrapper(function: &Ident) -> Ident {
   quote::format_ident!("__cmd__{}", function)
 }
 
+/// The ident of the autogenerated command manifest function,
+/// associated to the struct named after the command.
+fn command_manifest_ident() -> Ident {
+  quote::format_ident!("__cmd_manifest__")
+}
+
 /// This function will panic if the passed [`syn::Path`] does not have any segments.
 fn path_to_command(path: &mut Path) -> &mut PathSegment {
   path
@@ -14,7 +14,9 @@ use syn::{
   parse_macro_input,
   punctuated::Punctuated,
   spanned::Spanned,
-  Expr, ExprLit, FnArg, ItemFn, Lit, Meta, Pat, Token, Visibility,
+  visit::{self, Visit},
+  visit_mut::VisitMut,
+  Expr, ExprLit, FnArg, ItemFn, Lit, Meta, Pat, Token, Type, Visibility,
 };
 
 enum WrapperAttributeKind {
@@ -36,16 +38,22 @@ impl Parse for WrapperAttributeKind {
 
 struct WrapperAttributes {
   root: TokenStream2,
+  // the root path used by items generated outside of the wrapper macro, where `$crate` is not available
+  item_root: TokenStream2,
   execution_context: ExecutionContext,
   argument_case: ArgumentCase,
+  /// The time to live of the cached responses, in milliseconds.
+  cache: Option<u64>,
 }
 
 impl Parse for WrapperAttributes {
   fn parse(input: ParseStream) -> syn::Result<Self> {
     let mut wrapper_attributes = WrapperAttributes {
       root: quote!(::tauri),
+      item_root: quote!(::tauri),
       execution_context: ExecutionContext::Blocking,
       argument_case: ArgumentCase::Camel,
+      cache: None,
     };
 
     let attrs = Punctuated::<WrapperAttributeKind, Token![,]>::parse_terminated(input)?;
@@ -80,19 +88,32 @@ impl Parse for WrapperAttributes {
             {
               let lit = s.value();
 
-              wrapper_attributes.root = if lit == "crate" {
-                quote!($crate)
+              (wrapper_attributes.root, wrapper_attributes.item_root) = if lit == "crate" {
+                (quote!($crate), quote!(crate))
               } else {
                 let ident = Ident::new(&lit, Span::call_site());
-                quote!(#ident)
+                (quote!(#ident), quote!(#ident))
               };
             }
+          } else if v.path.is_ident("cache") {
+            if let Expr::Lit(ExprLit {
+              lit: Lit::Str(s),
+              attrs: _,
+            }) = v.value
+            {
+              wrapper_attributes.cache = Some(parse_duration(&s.value()).ok_or_else(|| {
+                syn::Error::new(
+                  s.span(),
+                  "expected a duration like \"500ms\", \"30s\", \"5m\" or \"1h\"",
+                )
+              })?);
+            }
           }
         }
         WrapperAttributeKind::Meta(Meta::Path(_)) => {
           return Err(syn::Error::new(
             input.span(),
-            "unexpected input, expected one of `rename_all`, `root`, `async`",
+            "unexpected input, expected one of `rename_all`, `root`, `async`, `cache`",
           ));
         }
         WrapperAttributeKind::Async => {
@@ -105,6 +126,21 @@ impl Parse for WrapperAttributes {
   }
 }
 
+/// Parses a duration like `30s` to milliseconds, the supported units being `ms`, `s`, `m` and `h`.
+fn parse_duration(duration: &str) -> Option<u64> {
+  let unit_start = duration.find(|c: char| !c.is_ascii_digit())?;
+  let (value, unit) = duration.split_at(unit_start);
+  let value: u64 = value.parse().ok()?;
+  let millis = match unit {
+    "ms" => 1,
+    "s" => 1000,
+    "m" => 60 * 1000,
+    "h" => 60 * 60 * 1000,
+    _ => return None,
+  };
+  value.checked_mul(millis)
+}
+
 /// The execution context of the command.
 enum ExecutionContext {
   Async,
@@ -224,7 +260,58 @@ pub fn wrapper(attributes: TokenStream, item: TokenStream) -> TokenStream {
     acl,
   } = invoke;
 
+  let manifest = if cfg!(feature = "command-manifest") {
+    command_manifest(&function, &attrs).unwrap_or_else(syn::Error::into_compile_error)
+  } else {
+    TokenStream2::new()
+  };
+  let manifest_arm = if cfg!(feature = "command-manifest") {
+    let root = &attrs.root;
+    quote! {
+      // used by `generate_handler!` to record the manifest of its commands with the root of this crate
+      (@manifest [$($(#[$attr:meta])* $manifest:path),*], $handler:expr) => {
+        #root::ipc::private::record_command_manifest(
+          || {
+            #[allow(unused_mut)]
+            let mut commands = ::std::vec::Vec::new();
+            $(
+              $(#[$attr])*
+              {
+                commands.push($manifest());
+              }
+            )*
+            commands
+          },
+          $handler,
+        )
+      };
+    }
+  } else {
+    TokenStream2::new()
+  };
+
+  // commands taking the webview or window return responses specific to it, which are never cached
+  let takes_webview = function.sig.inputs.iter().any(|arg| match arg {
+    FnArg::Typed(pat) => match &*pat.ty {
+      Type::Path(path) => path.path.segments.last().is_some_and(|segment| {
+        ["Webview", "Window", "WebviewWindow"].contains(&segment.ident.to_string().as_str())
+      }),
+      _ => false,
+    },
+    FnArg::Receiver(_) => false,
+  });
   let root = attrs.root;
+  let maybe_cache = if takes_webview {
+    quote!(#root::ipc::private::bypass_command_cache(&#message, #plugin_name);)
+  } else if let Some(millis) = attrs.cache {
+    quote!(#root::ipc::private::register_command_cache(
+      &#message,
+      #plugin_name,
+      ::std::time::Duration::from_millis(#millis),
+    );)
+  } else {
+    quote!()
+  };
 
   let kind = match attrs.execution_context {
     ExecutionContext::Async if function.sig.asyncness.is_none() => "sync_threadpool",
@@ -261,6 +348,8 @@ pub fn wrapper(attributes: TokenStream, item: TokenStream) -> TokenStream {
     #maybe_macro_export
     #[doc(hidden)]
     macro_rules! #wrapper {
+        #manifest_arm
+
         // double braces because the item is expected to be a block expression
         ($path:path, $invoke:ident) => {{
           #[allow(unused_imports)]
@@ -271,6 +360,8 @@ pub fn wrapper(attributes: TokenStream, item: TokenStream) -> TokenStream {
 
           #maybe_span
 
+          #maybe_cache
+
           #body
       }};
     }
@@ -278,10 +369,142 @@ pub fn wrapper(attributes: TokenStream, item: TokenStream) -> TokenStream {
     // allow the macro to be resolved with the same path as the command function
     #[allow(unused_imports)]
     #visibility use #wrapper;
+
+    #manifest
   )
   .into()
 }
 
+/// Generates the function describing the command in the manifest recorded by `generate_handler!`.
+///
+/// The arguments are described in the module of the command, where their types are in scope.
+/// Arguments whose type depends on the generic parameters of the command can't be described
+/// and are assumed to be injected by Tauri, like `AppHandle<R>`.
+fn command_manifest(
+  function: &ItemFn,
+  attributes: &WrapperAttributes,
+) -> syn::Result<TokenStream2> {
+  let root = &attributes.item_root;
+  let visibility = &function.vis;
+  let command = &function.sig.ident;
+  let manifest = super::command_manifest_ident();
+
+  let type_params = function
+    .sig
+    .generics
+    .type_params()
+    .map(|param| param.ident.clone())
+    .collect::<Vec<_>>();
+  let lifetimes = function
+    .sig
+    .generics
+    .lifetimes()
+    .map(|param| param.lifetime.ident.clone())
+    .collect::<Vec<_>>();
+
+  let mut args = Vec::new();
+  for arg in &function.sig.inputs {
+    let FnArg::Typed(typed) = arg else {
+      continue;
+    };
+    let mut ty = (*typed.ty).clone();
+    if uses_type_params(&ty, &type_params) {
+      continue;
+    }
+    ElideLifetimes(&lifetimes).visit_type_mut(&mut ty);
+
+    let key = arg_key(arg, attributes.argument_case)?;
+    let rust_type = type_name(&ty);
+    args.push(quote! {
+      if (&ArgTag::<#ty>::new()).command_arg_is_payload() {
+        args.push(CommandArgDescriptor {
+          name: #key,
+          rust_type: #rust_type,
+          schema: (&ArgTag::<#ty>::new()).command_arg_schema(),
+        });
+      }
+    });
+  }
+
+  // the struct shares the name of the command in the type namespace,
+  // so it's imported with the command and `generate_handler!` can resolve it from the command path
+  Ok(quote! {
+    #[doc(hidden)]
+    #[allow(dead_code, non_camel_case_types)]
+    #visibility struct #command {}
+
+    impl #command {
+      #[doc(hidden)]
+      #[allow(dead_code)]
+      #visibility fn #manifest() -> #root::ipc::private::CommandDescriptor {
+        #[allow(unused_imports)]
+        use #root::ipc::private::*;
+
+        #[allow(unused_mut)]
+        let mut args = ::std::vec::Vec::new();
+        #(#args)*
+        CommandDescriptor {
+          name: stringify!(#command),
+          args,
+        }
+      }
+    }
+  })
+}
+
+/// Whether the type mentions one of the given generic type parameters.
+fn uses_type_params(ty: &Type, type_params: &[Ident]) -> bool {
+  struct Finder<'a> {
+    type_params: &'a [Ident],
+    found: bool,
+  }
+
+  impl<'ast> Visit<'ast> for Finder<'_> {
+    fn visit_path(&mut self, path: &'ast syn::Path) {
+      if let Some(ident) = path.get_ident() {
+        self.found |= self.type_params.contains(ident);
+      }
+      visit::visit_path(self, path);
+    }
+
+    fn visit_type_impl_trait(&mut self, _: &'ast syn::TypeImplTrait) {
+      // `impl Trait` can't be named outside of the function signature
+      self.found = true;
+    }
+  }
+
+  let mut finder = Finder {
+    type_params,
+    found: false,
+  };
+  finder.visit_type(ty);
+  finder.found
+}
+
+/// Replaces the lifetime parameters of the command with `'_`, so its argument types can be named in another function.
+struct ElideLifetimes<'a>(&'a [Ident]);
+
+impl VisitMut for ElideLifetimes<'_> {
+  fn visit_lifetime_mut(&mut self, lifetime: &mut syn::Lifetime) {
+    if self.0.contains(&lifetime.ident) {
+      *lifetime = syn::Lifetime::new("'_", lifetime.span());
+    }
+  }
+}
+
+/// The type as written in the command signature, e.g. `Option<Vec<String>>`.
+fn type_name(ty: &Type) -> String {
+  quote!(#ty)
+    .to_string()
+    .replace(" :: ", "::")
+    .replace(":: ", "::")
+    .replace(" <", "<")
+    .replace("< ", "<")
+    .replace(" >", ">")
+    .replace(" ,", ",")
+    .replace("& ", "&")
+}
+
 /// Generates an asynchronous command response from the arguments and return value of a function.
 ///
 /// See the [`tauri::command`] module for all the items and traits that make this possible.
@@ -398,9 +621,25 @@ fn parse_arg(
   acl: &Ident,
   attributes: &WrapperAttributes,
 ) -> syn::Result<TokenStream2> {
+  let key = arg_key(arg, attributes.argument_case)?;
+  let root = &attributes.root;
+
+  Ok(quote!(#root::ipc::CommandArg::from_command(
+    #root::ipc::CommandItem {
+      plugin: #plugin_name,
+      name: stringify!(#command),
+      key: #key,
+      message: &#message,
+      acl: &#acl,
+    }
+  )))
+}
+
+/// The key of the argument in the command payload.
+fn arg_key(arg: &FnArg, case: ArgumentCase) -> syn::Result<String> {
   // we have no use for self arguments
-  let mut arg = match arg {
-    FnArg::Typed(arg) => arg.pat.as_ref().clone(),
+  let arg = match arg {
+    FnArg::Typed(arg) => arg.pat.as_ref(),
     FnArg::Receiver(arg) => {
       return Err(syn::Error::new(
         arg.span(),
@@ -410,11 +649,11 @@ fn parse_arg(
   };
 
   // we only support patterns that allow us to extract some sort of keyed identifier
-  let mut key = match &mut arg {
+  let key = match arg {
     Pat::Ident(arg) => arg.ident.unraw().to_string(),
     Pat::Wild(_) => "".into(), // we always convert to camelCase, so "_" will end up empty anyways
-    Pat::Struct(s) => super::path_to_command(&mut s.path).ident.to_string(),
-    Pat::TupleStruct(s) => super::path_to_command(&mut s.path).ident.to_string(),
+    Pat::Struct(s) => last_segment(&s.path).to_string(),
+    Pat::TupleStruct(s) => last_segment(&s.path).to_string(),
     err => {
       return Err(syn::Error::new(
         err.span(),
@@ -431,24 +670,16 @@ fn parse_arg(
     ));
   }
 
-  match attributes.argument_case {
-    ArgumentCase::Camel => {
-      key = key.to_lower_camel_case();
-    }
-    ArgumentCase::Snake => {
-      key = key.to_snake_case();
-    }
-  }
-
-  let root = &attributes.root;
+  Ok(match case {
+    ArgumentCase::Camel => key.to_lower_camel_case(),
+    ArgumentCase::Snake => key.to_snake_case(),
+  })
+}
 
-  Ok(quote!(#root::ipc::CommandArg::from_command(
-    #root::ipc::CommandItem {
-      plugin: #plugin_name,
-      name: stringify!(#command),
-      key: #key,
-      message: &#message,
-      acl: &#acl,
-    }
-  )))
+fn last_segment(path: &syn::Path) -> &Ident {
+  &path
+    .segments
+    .last()
+    .expect("parsed syn::Path has no segment")
+    .ident
 }
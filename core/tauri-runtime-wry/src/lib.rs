@@ -13,16 +13,20 @@
 
 use raw_window_handle::{DisplayHandle, HasDisplayHandle, HasWindowHandle};
 use tauri_runtime::{
-  monitor::Monitor,
-  webview::{DetachedWebview, DownloadEvent, PendingWebview, WebviewIpcHandler},
+  monitor::{Monitor, PhysicalRect},
+  webview::{
+    AuthChallenge, AuthResponse, CertDecision, CertError, CertErrorKind, ContextMenuRequest,
+    DetachedWebview, DownloadEvent, Insets, PendingWebview, RequestSummary, ResourceType,
+    WebviewAttributes, WebviewIpcHandler,
+  },
   window::{
     dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize, Position, Size},
-    CursorIcon, DetachedWindow, FileDropEvent, PendingWindow, RawWindow, WindowBuilder,
-    WindowBuilderBase, WindowEvent, WindowId,
+    CursorIcon, DetachedWindow, FileDropEvent, PendingWindow, ProcessCrashReason, RawWindow,
+    WebviewEvent as RuntimeWebviewEvent, WindowBuilder, WindowBuilderBase, WindowEvent, WindowId,
   },
-  DeviceEventFilter, Error, EventLoopProxy, ExitRequestedEventAction, Icon, Result, RunEvent,
-  Runtime, RuntimeHandle, RuntimeInitArgs, UserAttentionType, UserEvent, WebviewDispatch,
-  WindowDispatch, WindowEventId,
+  DeviceEventFilter, Error, EventLoopProxy, ExitRequestedEventAction, Icon, NativeViewId, Result,
+  RunEvent, Runtime, RuntimeHandle, RuntimeInitArgs, UserAttentionType, UserEvent, WebviewDispatch,
+  WebviewEventId, WindowDispatch, WindowEventId,
 };
 
 #[cfg(target_os = "macos")]
@@ -32,9 +36,18 @@ use tao::platform::unix::{WindowBuilderExtUnix, WindowExtUnix};
 #[cfg(windows)]
 use tao::platform::windows::{WindowBuilderExtWindows, WindowExtWindows};
 #[cfg(windows)]
-use webview2_com::FocusChangedEventHandler;
+use webview2_com::{
+  FocusChangedEventHandler, IsDocumentPlayingAudioChangedEventHandler,
+  Microsoft::Web::WebView2::Win32::ICoreWebView2_8,
+};
 #[cfg(windows)]
-use windows::Win32::{Foundation::HWND, System::WinRT::EventRegistrationToken};
+use windows::{
+  core::ComInterface,
+  Win32::{
+    Foundation::{BOOL, HWND},
+    System::WinRT::EventRegistrationToken,
+  },
+};
 #[cfg(windows)]
 use wry::WebViewBuilderExtWindows;
 
@@ -59,7 +72,8 @@ use tao::{
 #[cfg(target_os = "macos")]
 use tauri_utils::TitleBarStyle;
 use tauri_utils::{
-  config::WindowConfig, debug_eprintln, ProgressBarState, ProgressBarStatus, Theme,
+  config::{BackgroundThrottlingPolicy, WindowConfig},
+  debug_eprintln, ProgressBarState, ProgressBarStatus, Theme,
 };
 use wry::{
   FileDropEvent as WryFileDropEvent, ProxyConfig, ProxyEndpoint, Url, WebContext, WebView,
@@ -94,7 +108,8 @@ pub use tao::platform::macos::{
 use tauri_runtime::ActivationPolicy;
 
 use std::{
-  cell::RefCell,
+  borrow::Cow,
+  cell::{Cell, RefCell},
   collections::{
     hash_map::Entry::{Occupied, Vacant},
     HashMap,
@@ -114,6 +129,7 @@ use std::{
 pub type WebviewId = u32;
 type IpcHandler = dyn Fn(String) + 'static;
 
+mod native_view;
 mod webview;
 pub use webview::Webview;
 
@@ -121,6 +137,8 @@ pub type WebContextStore = Arc<Mutex<HashMap<Option<PathBuf>, WebContext>>>;
 // window
 pub type WindowEventHandler = Box<dyn Fn(&WindowEvent) + Send>;
 pub type WindowEventListeners = Arc<Mutex<HashMap<WindowEventId, WindowEventHandler>>>;
+pub type WebviewEventHandler = Box<dyn Fn(&RuntimeWebviewEvent) + Send>;
+pub type WebviewEventListeners = Arc<Mutex<HashMap<WebviewEventId, WebviewEventHandler>>>;
 
 #[derive(Debug, Clone, Default)]
 pub struct WindowIdStore(Arc<Mutex<HashMap<TaoWindowId, WindowId>>>);
@@ -133,6 +151,10 @@ impl WindowIdStore {
   fn get(&self, w: &TaoWindowId) -> Option<WindowId> {
     self.0.lock().unwrap().get(w).copied()
   }
+
+  fn remove(&self, w: &TaoWindowId) {
+    self.0.lock().unwrap().remove(w);
+  }
 }
 
 #[macro_export]
@@ -158,7 +180,11 @@ macro_rules! webview_getter {
     getter!(
       $self,
       rx,
-      Message::Webview($self.window_id, $self.webview_id, $message(tx))
+      Message::Webview(
+        *$self.window_id.lock().unwrap(),
+        $self.webview_id,
+        $message(tx),
+      )
     )
   }};
 }
@@ -195,7 +221,9 @@ pub struct Context<T: UserEvent> {
   next_window_id: Arc<AtomicU32>,
   next_webview_id: Arc<AtomicU32>,
   next_window_event_id: Arc<AtomicU32>,
+  next_webview_event_id: Arc<AtomicU32>,
   next_webcontext_id: Arc<AtomicU32>,
+  next_native_view_id: Arc<AtomicU32>,
 }
 
 impl<T: UserEvent> Context<T> {
@@ -218,10 +246,18 @@ impl<T: UserEvent> Context<T> {
     self.next_webview_id.fetch_add(1, Ordering::Relaxed)
   }
 
+  fn next_native_view_id(&self) -> NativeViewId {
+    self.next_native_view_id.fetch_add(1, Ordering::Relaxed)
+  }
+
   fn next_window_event_id(&self) -> u32 {
     self.next_window_event_id.fetch_add(1, Ordering::Relaxed)
   }
 
+  fn next_webview_event_id(&self) -> u32 {
+    self.next_webview_event_id.fetch_add(1, Ordering::Relaxed)
+  }
+
   fn next_webcontext_id(&self) -> u32 {
     self.next_webcontext_id.fetch_add(1, Ordering::Relaxed)
   }
@@ -238,22 +274,40 @@ impl<T: UserEvent> Context<T> {
     let window_id = self.next_window_id();
     let webview_id = pending.webview.as_ref().map(|_| context.next_webview_id());
 
-    send_user_message(
-      self,
-      Message::CreateWindow(
+    if current_thread().id() == self.main_thread_id {
+      // created right away so a webview creation failure is returned to the caller
+      let window = create_window(
         window_id,
-        Box::new(move |event_loop| {
-          create_window(
-            window_id,
-            webview_id.unwrap_or_default(),
-            event_loop,
-            &context,
-            pending,
-            after_window_creation,
-          )
-        }),
-      ),
-    )?;
+        webview_id.unwrap_or_default(),
+        &self.main_thread.window_target,
+        self,
+        pending,
+        after_window_creation,
+      )?;
+      self
+        .main_thread
+        .windows
+        .borrow_mut()
+        .insert(window_id, window);
+    } else {
+      send_user_message(
+        self,
+        Message::CreateWindow(
+          window_id,
+          label.clone(),
+          Box::new(move |event_loop| {
+            create_window(
+              window_id,
+              webview_id.unwrap_or_default(),
+              event_loop,
+              &context,
+              pending,
+              after_window_creation,
+            )
+          }),
+        ),
+      )?;
+    }
 
     let dispatcher = WryWindowDispatcher {
       window_id,
@@ -263,7 +317,7 @@ impl<T: UserEvent> Context<T> {
     let detached_webview = webview_id.map(|id| DetachedWebview {
       label: label.clone(),
       dispatcher: WryWebviewDispatcher {
-        window_id,
+        window_id: Arc::new(Mutex::new(window_id)),
         webview_id: id,
         context: self.clone(),
       },
@@ -286,6 +340,8 @@ impl<T: UserEvent> Context<T> {
     let context = self.clone();
 
     let webview_id = self.next_webview_id();
+    let webview_window_id = Arc::new(Mutex::new(window_id));
+    let webview_window_id_ = webview_window_id.clone();
 
     send_user_message(
       self,
@@ -295,7 +351,7 @@ impl<T: UserEvent> Context<T> {
           create_webview(
             WebviewKind::WindowChild,
             window,
-            window_id,
+            webview_window_id_,
             webview_id,
             &context,
             pending,
@@ -305,7 +361,7 @@ impl<T: UserEvent> Context<T> {
     )?;
 
     let dispatcher = WryWebviewDispatcher {
-      window_id,
+      window_id: webview_window_id,
       webview_id,
       context: self.clone(),
     };
@@ -468,6 +524,7 @@ impl From<WebviewEvent> for WindowEventWrapper {
     let event = match event {
       WebviewEvent::Focused(focused) => WindowEvent::Focused(focused),
       WebviewEvent::FileDrop(event) => WindowEvent::FileDrop(event),
+      WebviewEvent::MediaPlaybackChanged(_) | WebviewEvent::ProcessCrashed(_) => return Self(None),
     };
     Self(Some(event))
   }
@@ -477,15 +534,101 @@ pub struct MonitorHandleWrapper(pub MonitorHandle);
 
 impl From<MonitorHandleWrapper> for Monitor {
   fn from(monitor: MonitorHandleWrapper) -> Monitor {
+    let position = PhysicalPositionWrapper(monitor.0.position()).into();
+    let size = PhysicalSizeWrapper(monitor.0.size()).into();
     Self {
       name: monitor.0.name(),
-      position: PhysicalPositionWrapper(monitor.0.position()).into(),
-      size: PhysicalSizeWrapper(monitor.0.size()).into(),
+      position,
+      size,
       scale_factor: monitor.0.scale_factor(),
+      work_area: monitor_work_area(&monitor.0).unwrap_or(PhysicalRect { position, size }),
     }
   }
 }
 
+/// The area of the monitor not covered by the taskbar.
+#[cfg(windows)]
+fn monitor_work_area(monitor: &MonitorHandle) -> Option<PhysicalRect> {
+  use tao::platform::windows::MonitorHandleExtWindows;
+  use windows::Win32::Graphics::Gdi::{GetMonitorInfoW, HMONITOR, MONITORINFO};
+
+  let mut info = MONITORINFO {
+    cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+    ..Default::default()
+  };
+  unsafe { GetMonitorInfoW(HMONITOR(monitor.hmonitor()), &mut info) }
+    .as_bool()
+    .then(|| PhysicalRect {
+      position: PhysicalPosition::new(info.rcWork.left, info.rcWork.top),
+      size: PhysicalSize::new(
+        (info.rcWork.right - info.rcWork.left) as u32,
+        (info.rcWork.bottom - info.rcWork.top) as u32,
+      ),
+    })
+}
+
+/// The area of the monitor not covered by the dock and the menu bar.
+#[cfg(target_os = "macos")]
+fn monitor_work_area(monitor: &MonitorHandle) -> Option<PhysicalRect> {
+  use cocoa::{appkit::NSScreen, base::id};
+  use tao::platform::macos::MonitorHandleExtMacOS;
+
+  let screen = monitor.ns_screen()? as id;
+  let (frame, visible) = unsafe { (NSScreen::frame(screen), NSScreen::visibleFrame(screen)) };
+  // the screen coordinates start at the bottom-left corner, the monitor position at the top-left one
+  let left = visible.origin.x - frame.origin.x;
+  let top = (frame.origin.y + frame.size.height) - (visible.origin.y + visible.size.height);
+  let scale_factor = monitor.scale_factor();
+  let position = monitor.position();
+  Some(PhysicalRect {
+    position: PhysicalPosition::new(
+      position.x + (left * scale_factor).round() as i32,
+      position.y + (top * scale_factor).round() as i32,
+    ),
+    size: PhysicalSize::new(
+      (visible.size.width * scale_factor).round() as u32,
+      (visible.size.height * scale_factor).round() as u32,
+    ),
+  })
+}
+
+/// The area of the monitor not covered by the panels.
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+))]
+fn monitor_work_area(monitor: &MonitorHandle) -> Option<PhysicalRect> {
+  use gtk::prelude::MonitorExt;
+
+  let scale_factor = monitor.scale_factor();
+  let position = monitor.position();
+  let size = monitor.size();
+  // GDK uses logical coordinates
+  let center_x = (position.x as f64 + size.width as f64 / 2.0) / scale_factor;
+  let center_y = (position.y as f64 + size.height as f64 / 2.0) / scale_factor;
+  let area = gtk::gdk::Display::default()?
+    .monitor_at_point(center_x as i32, center_y as i32)?
+    .workarea();
+  Some(PhysicalRect {
+    position: PhysicalPosition::new(
+      (area.x() as f64 * scale_factor).round() as i32,
+      (area.y() as f64 * scale_factor).round() as i32,
+    ),
+    size: PhysicalSize::new(
+      (area.width() as f64 * scale_factor).round() as u32,
+      (area.height() as f64 * scale_factor).round() as u32,
+    ),
+  })
+}
+
+#[cfg(any(target_os = "ios", target_os = "android"))]
+fn monitor_work_area(_monitor: &MonitorHandle) -> Option<PhysicalRect> {
+  None
+}
+
 pub struct PhysicalPositionWrapper<T>(pub TaoPhysicalPosition<T>);
 
 impl<T> From<PhysicalPositionWrapper<T>> for PhysicalPosition<T> {
@@ -1166,6 +1309,15 @@ pub enum WindowMessage {
   DragWindow,
   ResizeDragWindow(tauri_runtime::ResizeDirection),
   RequestRedraw,
+  CreateNativeView(NativeViewId, Position, Size, Sender<Result<()>>),
+  NativeViewHandle(
+    NativeViewId,
+    Sender<std::result::Result<SendRawWindowHandle, raw_window_handle::HandleError>>,
+  ),
+  SetNativeViewBounds(NativeViewId, Position, Size),
+  SetNativeViewVisible(NativeViewId, bool),
+  SetNativeViewZOrder(NativeViewId, usize),
+  RemoveNativeView(NativeViewId),
 }
 
 pub enum WebviewMessage {
@@ -1173,18 +1325,65 @@ pub enum WebviewMessage {
   EvaluateScript(String),
   #[cfg(all(feature = "tracing", not(target_os = "android")))]
   EvaluateScript(String, Sender<()>, tracing::Span),
+  EvaluateScriptWithCallback(String, Box<dyn FnOnce(String) + Send>),
+  EvaluateScriptInWorld(String, String, Box<dyn FnOnce(String) + Send>),
   #[allow(dead_code)]
   WebviewEvent(WebviewEvent),
   Navigate(Url),
+  NavigateWithHeaders(Url, http::HeaderMap),
   Print,
   Close,
   SetPosition(Position),
   SetSize(Size),
+  SetBounds(Position, Size),
+  SetZOrder(usize),
   SetFocus,
+  SetIgnoreCursorEvents(bool),
+  SetContentInsets(Insets),
+  SetTheme(Option<Theme>),
+  SetSuspended(bool),
+  #[cfg(target_os = "macos")]
+  SetBackgroundThrottling(BackgroundThrottlingPolicy),
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  SetSpellcheck(bool),
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  SetSpellcheckLanguages(Vec<String>),
+  SetMuted(bool),
+  PauseAllMedia,
+  Screenshot(Box<dyn FnOnce(Result<Vec<u8>>) + Send>),
+  #[cfg(feature = "devtools-protocol")]
+  CallDevtoolsProtocolMethod(
+    String,
+    serde_json::Value,
+    Box<dyn FnOnce(Result<serde_json::Value>) + Send>,
+  ),
+  #[cfg(feature = "devtools-protocol")]
+  SubscribeDevtoolsProtocolEvent(String, Box<dyn Fn(serde_json::Value) + Send>),
+  Reparent(WindowId, Sender<Result<()>>),
+  Recover(Sender<Result<()>>),
+  AddInitializationScript(String),
+  AddIsolatedInitializationScript(String, String),
+  AddEventListener(WebviewEventId, Box<dyn Fn(&RuntimeWebviewEvent) + Send>),
   // Getters
   Url(Sender<Url>),
   Position(Sender<PhysicalPosition<i32>>),
   Size(Sender<PhysicalSize<u32>>),
+  ScaleFactor(Sender<f64>),
+  Theme(Sender<Theme>),
+  ProcessId(Sender<u32>),
+  IsMuted(Sender<bool>),
   WithWebview(Box<dyn FnOnce(Webview) + Send>),
   // Devtools
   #[cfg(any(debug_assertions, feature = "devtools"))]
@@ -1200,6 +1399,8 @@ pub enum WebviewMessage {
 pub enum WebviewEvent {
   FileDrop(FileDropEvent),
   Focused(bool),
+  MediaPlaybackChanged(bool),
+  ProcessCrashed(ProcessCrashReason),
 }
 
 pub type CreateWindowClosure<T> =
@@ -1217,7 +1418,7 @@ pub enum Message<T: 'static> {
   Window(WindowId, WindowMessage),
   Webview(WindowId, WebviewId, WebviewMessage),
   CreateWebview(WindowId, CreateWebviewClosure),
-  CreateWindow(WindowId, CreateWindowClosure<T>),
+  CreateWindow(WindowId, String, CreateWindowClosure<T>),
   CreateRawWindow(
     WindowId,
     Box<dyn FnOnce() -> (String, TaoWindowBuilder) + Send>,
@@ -1238,7 +1439,8 @@ impl<T: UserEvent> Clone for Message<T> {
 /// The Tauri [`WebviewDispatch`] for [`Wry`].
 #[derive(Debug, Clone)]
 pub struct WryWebviewDispatcher<T: UserEvent> {
-  window_id: WindowId,
+  // shared with the webview so it follows [`WebviewMessage::Reparent`]
+  window_id: Arc<Mutex<WindowId>>,
   webview_id: WebviewId,
   context: Context<T>,
 }
@@ -1254,19 +1456,29 @@ impl<T: UserEvent> WebviewDispatch<T> for WryWebviewDispatcher<T> {
     send_user_message(
       &self.context,
       Message::Webview(
-        self.window_id,
+        *self.window_id.lock().unwrap(),
         self.webview_id,
         WebviewMessage::WithWebview(Box::new(move |webview| f(Box::new(webview)))),
       ),
     )
   }
 
+  fn on_webview_event<F: Fn(&RuntimeWebviewEvent) + Send + 'static>(&self, f: F) -> WebviewEventId {
+    let id = self.context.next_webview_event_id();
+    let _ = self.context.proxy.send_event(Message::Webview(
+      *self.window_id.lock().unwrap(),
+      self.webview_id,
+      WebviewMessage::AddEventListener(id, Box::new(f)),
+    ));
+    id
+  }
+
   #[cfg(any(debug_assertions, feature = "devtools"))]
   fn open_devtools(&self) {
     let _ = send_user_message(
       &self.context,
       Message::Webview(
-        self.window_id,
+        *self.window_id.lock().unwrap(),
         self.webview_id,
         WebviewMessage::OpenDevTools,
       ),
@@ -1278,7 +1490,7 @@ impl<T: UserEvent> WebviewDispatch<T> for WryWebviewDispatcher<T> {
     let _ = send_user_message(
       &self.context,
       Message::Webview(
-        self.window_id,
+        *self.window_id.lock().unwrap(),
         self.webview_id,
         WebviewMessage::CloseDevTools,
       ),
@@ -1305,30 +1517,68 @@ impl<T: UserEvent> WebviewDispatch<T> for WryWebviewDispatcher<T> {
     webview_getter!(self, WebviewMessage::Size)
   }
 
+  fn scale_factor(&self) -> Result<f64> {
+    webview_getter!(self, WebviewMessage::ScaleFactor)
+  }
+
+  fn theme(&self) -> Result<Theme> {
+    webview_getter!(self, WebviewMessage::Theme)
+  }
+
+  fn process_id(&self) -> Result<u32> {
+    webview_getter!(self, WebviewMessage::ProcessId)
+  }
+
+  fn is_muted(&self) -> Result<bool> {
+    if !MUTE_SUPPORTED {
+      return Err(Error::Unsupported);
+    }
+    webview_getter!(self, WebviewMessage::IsMuted)
+  }
+
   // Setters
 
   fn navigate(&self, url: Url) -> Result<()> {
     send_user_message(
       &self.context,
       Message::Webview(
-        self.window_id,
+        *self.window_id.lock().unwrap(),
         self.webview_id,
         WebviewMessage::Navigate(url),
       ),
     )
   }
 
+  fn navigate_with_headers(&self, url: Url, headers: http::HeaderMap) -> Result<()> {
+    send_user_message(
+      &self.context,
+      Message::Webview(
+        *self.window_id.lock().unwrap(),
+        self.webview_id,
+        WebviewMessage::NavigateWithHeaders(url, headers),
+      ),
+    )
+  }
+
   fn print(&self) -> Result<()> {
     send_user_message(
       &self.context,
-      Message::Webview(self.window_id, self.webview_id, WebviewMessage::Print),
+      Message::Webview(
+        *self.window_id.lock().unwrap(),
+        self.webview_id,
+        WebviewMessage::Print,
+      ),
     )
   }
 
   fn close(&self) -> Result<()> {
     send_user_message(
       &self.context,
-      Message::Webview(self.window_id, self.webview_id, WebviewMessage::Close),
+      Message::Webview(
+        *self.window_id.lock().unwrap(),
+        self.webview_id,
+        WebviewMessage::Close,
+      ),
     )
   }
 
@@ -1336,7 +1586,7 @@ impl<T: UserEvent> WebviewDispatch<T> for WryWebviewDispatcher<T> {
     send_user_message(
       &self.context,
       Message::Webview(
-        self.window_id,
+        *self.window_id.lock().unwrap(),
         self.webview_id,
         WebviewMessage::SetSize(size),
       ),
@@ -1347,117 +1597,456 @@ impl<T: UserEvent> WebviewDispatch<T> for WryWebviewDispatcher<T> {
     send_user_message(
       &self.context,
       Message::Webview(
-        self.window_id,
+        *self.window_id.lock().unwrap(),
         self.webview_id,
         WebviewMessage::SetPosition(position),
       ),
     )
   }
 
-  fn set_focus(&self) -> Result<()> {
+  fn set_bounds(&self, position: Position, size: Size) -> Result<()> {
     send_user_message(
       &self.context,
-      Message::Webview(self.window_id, self.webview_id, WebviewMessage::SetFocus),
+      Message::Webview(
+        *self.window_id.lock().unwrap(),
+        self.webview_id,
+        WebviewMessage::SetBounds(position, size),
+      ),
     )
   }
 
-  #[cfg(all(feature = "tracing", not(target_os = "android")))]
-  fn eval_script<S: Into<String>>(&self, script: S) -> Result<()> {
-    // use a channel so the EvaluateScript task uses the current span as parent
-    let (tx, rx) = channel();
-    getter!(
-      self,
-      rx,
+  fn set_z_order(&self, index: usize) -> Result<()> {
+    send_user_message(
+      &self.context,
       Message::Webview(
-        self.window_id,
+        *self.window_id.lock().unwrap(),
         self.webview_id,
-        WebviewMessage::EvaluateScript(script.into(), tx, tracing::Span::current()),
-      )
+        WebviewMessage::SetZOrder(index),
+      ),
     )
   }
 
-  #[cfg(not(all(feature = "tracing", not(target_os = "android"))))]
-  fn eval_script<S: Into<String>>(&self, script: S) -> Result<()> {
+  fn set_focus(&self) -> Result<()> {
     send_user_message(
       &self.context,
       Message::Webview(
-        self.window_id,
+        *self.window_id.lock().unwrap(),
         self.webview_id,
-        WebviewMessage::EvaluateScript(script.into()),
+        WebviewMessage::SetFocus,
       ),
     )
   }
-}
-
-/// The Tauri [`WindowDispatch`] for [`Wry`].
-#[derive(Debug, Clone)]
-pub struct WryWindowDispatcher<T: UserEvent> {
-  window_id: WindowId,
-  context: Context<T>,
-}
-
-// SAFETY: this is safe since the `Context` usage is guarded on `send_user_message`.
-#[allow(clippy::non_send_fields_in_send_ty)]
-unsafe impl<T: UserEvent> Sync for WryWindowDispatcher<T> {}
-
-fn get_raw_window_handle<T: UserEvent>(
-  dispatcher: &WryWindowDispatcher<T>,
-) -> Result<std::result::Result<SendRawWindowHandle, raw_window_handle::HandleError>> {
-  window_getter!(dispatcher, WindowMessage::RawWindowHandle)
-}
 
-impl<T: UserEvent> WindowDispatch<T> for WryWindowDispatcher<T> {
-  type Runtime = Wry<T>;
-  type WindowBuilder = WindowBuilderWrapper;
+  fn set_ignore_cursor_events(&self, ignore: bool) -> Result<()> {
+    send_user_message(
+      &self.context,
+      Message::Webview(
+        *self.window_id.lock().unwrap(),
+        self.webview_id,
+        WebviewMessage::SetIgnoreCursorEvents(ignore),
+      ),
+    )
+  }
 
-  fn run_on_main_thread<F: FnOnce() + Send + 'static>(&self, f: F) -> Result<()> {
-    send_user_message(&self.context, Message::Task(Box::new(f)))
+  fn set_content_insets(&self, insets: Insets) -> Result<()> {
+    send_user_message(
+      &self.context,
+      Message::Webview(
+        *self.window_id.lock().unwrap(),
+        self.webview_id,
+        WebviewMessage::SetContentInsets(insets),
+      ),
+    )
   }
 
-  fn on_window_event<F: Fn(&WindowEvent) + Send + 'static>(&self, f: F) -> WindowEventId {
-    let id = self.context.next_window_event_id();
-    let _ = self.context.proxy.send_event(Message::Window(
-      self.window_id,
-      WindowMessage::AddEventListener(id, Box::new(f)),
-    ));
-    id
+  fn set_theme(&self, theme: Option<Theme>) -> Result<()> {
+    send_user_message(
+      &self.context,
+      Message::Webview(
+        *self.window_id.lock().unwrap(),
+        self.webview_id,
+        WebviewMessage::SetTheme(theme),
+      ),
+    )
   }
 
-  // Getters
+  fn set_suspended(&self, suspended: bool) -> Result<()> {
+    #[cfg(not(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd"
+    )))]
+    return send_user_message(
+      &self.context,
+      Message::Webview(
+        *self.window_id.lock().unwrap(),
+        self.webview_id,
+        WebviewMessage::SetSuspended(suspended),
+      ),
+    );
 
-  fn scale_factor(&self) -> Result<f64> {
-    window_getter!(self, WindowMessage::ScaleFactor)
+    // webkit2gtk has no API to suspend a webview
+    #[cfg(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd"
+    ))]
+    {
+      let _ = suspended;
+      Err(Error::Unsupported)
+    }
   }
 
-  fn inner_position(&self) -> Result<PhysicalPosition<i32>> {
-    window_getter!(self, WindowMessage::InnerPosition)?
-  }
+  fn set_spellcheck(&self, enabled: bool) -> Result<()> {
+    #[cfg(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd"
+    ))]
+    return send_user_message(
+      &self.context,
+      Message::Webview(
+        *self.window_id.lock().unwrap(),
+        self.webview_id,
+        WebviewMessage::SetSpellcheck(enabled),
+      ),
+    );
 
-  fn outer_position(&self) -> Result<PhysicalPosition<i32>> {
-    window_getter!(self, WindowMessage::OuterPosition)?
+    // the attribute set by the initialization script can't be changed for the next page loads
+    #[cfg(not(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd"
+    )))]
+    {
+      let _ = enabled;
+      Err(Error::Unsupported)
+    }
   }
 
-  fn inner_size(&self) -> Result<PhysicalSize<u32>> {
-    window_getter!(self, WindowMessage::InnerSize)
-  }
+  fn set_spellcheck_languages(&self, languages: Vec<String>) -> Result<()> {
+    #[cfg(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd"
+    ))]
+    return send_user_message(
+      &self.context,
+      Message::Webview(
+        *self.window_id.lock().unwrap(),
+        self.webview_id,
+        WebviewMessage::SetSpellcheckLanguages(languages),
+      ),
+    );
 
-  fn outer_size(&self) -> Result<PhysicalSize<u32>> {
-    window_getter!(self, WindowMessage::OuterSize)
+    #[cfg(not(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd"
+    )))]
+    {
+      let _ = languages;
+      Err(Error::Unsupported)
+    }
   }
 
-  fn is_fullscreen(&self) -> Result<bool> {
-    window_getter!(self, WindowMessage::IsFullscreen)
+  fn set_muted(&self, muted: bool) -> Result<()> {
+    if !MUTE_SUPPORTED {
+      return Err(Error::Unsupported);
+    }
+    send_user_message(
+      &self.context,
+      Message::Webview(
+        *self.window_id.lock().unwrap(),
+        self.webview_id,
+        WebviewMessage::SetMuted(muted),
+      ),
+    )
   }
 
-  fn is_minimized(&self) -> Result<bool> {
-    window_getter!(self, WindowMessage::IsMinimized)
+  fn pause_all_media(&self) -> Result<()> {
+    send_user_message(
+      &self.context,
+      Message::Webview(
+        *self.window_id.lock().unwrap(),
+        self.webview_id,
+        WebviewMessage::PauseAllMedia,
+      ),
+    )
   }
 
-  fn is_maximized(&self) -> Result<bool> {
-    window_getter!(self, WindowMessage::IsMaximized)
+  fn screenshot<F: FnOnce(Result<Vec<u8>>) + Send + 'static>(&self, f: F) -> Result<()> {
+    send_user_message(
+      &self.context,
+      Message::Webview(
+        *self.window_id.lock().unwrap(),
+        self.webview_id,
+        WebviewMessage::Screenshot(Box::new(f)),
+      ),
+    )
   }
 
-  fn is_focused(&self) -> Result<bool> {
+  #[cfg(feature = "devtools-protocol")]
+  fn call_devtools_protocol_method<F: FnOnce(Result<serde_json::Value>) + Send + 'static>(
+    &self,
+    method: String,
+    params: serde_json::Value,
+    f: F,
+  ) -> Result<()> {
+    send_user_message(
+      &self.context,
+      Message::Webview(
+        *self.window_id.lock().unwrap(),
+        self.webview_id,
+        WebviewMessage::CallDevtoolsProtocolMethod(method, params, Box::new(f)),
+      ),
+    )
+  }
+
+  #[cfg(feature = "devtools-protocol")]
+  fn subscribe_devtools_protocol_event<F: Fn(serde_json::Value) + Send + 'static>(
+    &self,
+    event: String,
+    f: F,
+  ) -> Result<()> {
+    send_user_message(
+      &self.context,
+      Message::Webview(
+        *self.window_id.lock().unwrap(),
+        self.webview_id,
+        WebviewMessage::SubscribeDevtoolsProtocolEvent(event, Box::new(f)),
+      ),
+    )
+  }
+
+  fn reparent(&self, window_id: WindowId) -> Result<()> {
+    let (tx, rx) = channel();
+    send_user_message(
+      &self.context,
+      Message::Webview(
+        *self.window_id.lock().unwrap(),
+        self.webview_id,
+        WebviewMessage::Reparent(window_id, tx),
+      ),
+    )?;
+    rx.recv().map_err(|_| Error::FailedToReceiveMessage)?
+  }
+
+  fn recover(&self) -> Result<()> {
+    let (tx, rx) = channel();
+    send_user_message(
+      &self.context,
+      Message::Webview(
+        *self.window_id.lock().unwrap(),
+        self.webview_id,
+        WebviewMessage::Recover(tx),
+      ),
+    )?;
+    rx.recv().map_err(|_| Error::FailedToReceiveMessage)?
+  }
+
+  fn add_initialization_script(&self, script: String) -> Result<()> {
+    send_user_message(
+      &self.context,
+      Message::Webview(
+        *self.window_id.lock().unwrap(),
+        self.webview_id,
+        WebviewMessage::AddInitializationScript(script),
+      ),
+    )
+  }
+
+  fn set_background_throttling(&self, policy: BackgroundThrottlingPolicy) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    if inactive_scheduling_policy_supported() {
+      return send_user_message(
+        &self.context,
+        Message::Webview(
+          *self.window_id.lock().unwrap(),
+          self.webview_id,
+          WebviewMessage::SetBackgroundThrottling(policy),
+        ),
+      );
+    }
+
+    let _ = policy;
+    Err(Error::Unsupported)
+  }
+
+  #[cfg(all(feature = "tracing", not(target_os = "android")))]
+  fn eval_script<S: Into<String>>(&self, script: S) -> Result<()> {
+    // use a channel so the EvaluateScript task uses the current span as parent
+    let (tx, rx) = channel();
+    getter!(
+      self,
+      rx,
+      Message::Webview(
+        *self.window_id.lock().unwrap(),
+        self.webview_id,
+        WebviewMessage::EvaluateScript(script.into(), tx, tracing::Span::current()),
+      )
+    )
+  }
+
+  #[cfg(not(all(feature = "tracing", not(target_os = "android"))))]
+  fn eval_script<S: Into<String>>(&self, script: S) -> Result<()> {
+    send_user_message(
+      &self.context,
+      Message::Webview(
+        *self.window_id.lock().unwrap(),
+        self.webview_id,
+        WebviewMessage::EvaluateScript(script.into()),
+      ),
+    )
+  }
+
+  fn eval_script_with_callback<S: Into<String>, F: FnOnce(String) + Send + 'static>(
+    &self,
+    script: S,
+    callback: F,
+  ) -> Result<()> {
+    // wry does not call the callback on Android
+    #[cfg(target_os = "android")]
+    {
+      let _ = (script, callback);
+      Err(Error::Unsupported)
+    }
+
+    #[cfg(not(target_os = "android"))]
+    send_user_message(
+      &self.context,
+      Message::Webview(
+        *self.window_id.lock().unwrap(),
+        self.webview_id,
+        WebviewMessage::EvaluateScriptWithCallback(script.into(), Box::new(callback)),
+      ),
+    )
+  }
+
+  fn add_initialization_script_in_world(&self, world: String, script: String) -> Result<()> {
+    send_user_message(
+      &self.context,
+      Message::Webview(
+        *self.window_id.lock().unwrap(),
+        self.webview_id,
+        WebviewMessage::AddIsolatedInitializationScript(world, script),
+      ),
+    )
+  }
+
+  fn eval_script_in_world<S: Into<String>, F: FnOnce(String) + Send + 'static>(
+    &self,
+    world: String,
+    script: S,
+    callback: F,
+  ) -> Result<()> {
+    // wry does not call the evaluation callbacks on Android
+    #[cfg(target_os = "android")]
+    {
+      let _ = (world, script, callback);
+      Err(Error::Unsupported)
+    }
+
+    #[cfg(not(target_os = "android"))]
+    send_user_message(
+      &self.context,
+      Message::Webview(
+        *self.window_id.lock().unwrap(),
+        self.webview_id,
+        WebviewMessage::EvaluateScriptInWorld(world, script.into(), Box::new(callback)),
+      ),
+    )
+  }
+}
+
+/// The Tauri [`WindowDispatch`] for [`Wry`].
+#[derive(Debug, Clone)]
+pub struct WryWindowDispatcher<T: UserEvent> {
+  window_id: WindowId,
+  context: Context<T>,
+}
+
+// SAFETY: this is safe since the `Context` usage is guarded on `send_user_message`.
+#[allow(clippy::non_send_fields_in_send_ty)]
+unsafe impl<T: UserEvent> Sync for WryWindowDispatcher<T> {}
+
+fn get_raw_window_handle<T: UserEvent>(
+  dispatcher: &WryWindowDispatcher<T>,
+) -> Result<std::result::Result<SendRawWindowHandle, raw_window_handle::HandleError>> {
+  window_getter!(dispatcher, WindowMessage::RawWindowHandle)
+}
+
+fn get_native_view_handle<T: UserEvent>(
+  dispatcher: &WryWindowDispatcher<T>,
+  id: NativeViewId,
+) -> Result<std::result::Result<SendRawWindowHandle, raw_window_handle::HandleError>> {
+  window_getter!(dispatcher, |tx| WindowMessage::NativeViewHandle(id, tx))
+}
+
+impl<T: UserEvent> WindowDispatch<T> for WryWindowDispatcher<T> {
+  type Runtime = Wry<T>;
+  type WindowBuilder = WindowBuilderWrapper;
+
+  fn run_on_main_thread<F: FnOnce() + Send + 'static>(&self, f: F) -> Result<()> {
+    send_user_message(&self.context, Message::Task(Box::new(f)))
+  }
+
+  fn on_window_event<F: Fn(&WindowEvent) + Send + 'static>(&self, f: F) -> WindowEventId {
+    let id = self.context.next_window_event_id();
+    let _ = self.context.proxy.send_event(Message::Window(
+      self.window_id,
+      WindowMessage::AddEventListener(id, Box::new(f)),
+    ));
+    id
+  }
+
+  // Getters
+
+  fn scale_factor(&self) -> Result<f64> {
+    window_getter!(self, WindowMessage::ScaleFactor)
+  }
+
+  fn inner_position(&self) -> Result<PhysicalPosition<i32>> {
+    window_getter!(self, WindowMessage::InnerPosition)?
+  }
+
+  fn outer_position(&self) -> Result<PhysicalPosition<i32>> {
+    window_getter!(self, WindowMessage::OuterPosition)?
+  }
+
+  fn inner_size(&self) -> Result<PhysicalSize<u32>> {
+    window_getter!(self, WindowMessage::InnerSize)
+  }
+
+  fn outer_size(&self) -> Result<PhysicalSize<u32>> {
+    window_getter!(self, WindowMessage::OuterSize)
+  }
+
+  fn is_fullscreen(&self) -> Result<bool> {
+    window_getter!(self, WindowMessage::IsFullscreen)
+  }
+
+  fn is_minimized(&self) -> Result<bool> {
+    window_getter!(self, WindowMessage::IsMinimized)
+  }
+
+  fn is_maximized(&self) -> Result<bool> {
+    window_getter!(self, WindowMessage::IsMaximized)
+  }
+
+  fn is_focused(&self) -> Result<bool> {
     window_getter!(self, WindowMessage::IsFocused)
   }
 
@@ -1849,16 +2438,120 @@ impl<T: UserEvent> WindowDispatch<T> for WryWindowDispatcher<T> {
       ),
     )
   }
+
+  fn create_native_view(&self, position: Position, size: Size) -> Result<NativeViewId> {
+    let id = self.context.next_native_view_id();
+    window_getter!(self, |tx| WindowMessage::CreateNativeView(
+      id, position, size, tx
+    ))??;
+    Ok(id)
+  }
+
+  fn native_view_handle(
+    &self,
+    id: NativeViewId,
+  ) -> std::result::Result<raw_window_handle::WindowHandle<'_>, raw_window_handle::HandleError> {
+    get_native_view_handle(self, id)
+      .map_err(|_| raw_window_handle::HandleError::Unavailable)
+      .and_then(|r| r.map(|h| unsafe { raw_window_handle::WindowHandle::borrow_raw(h.0) }))
+  }
+
+  fn set_native_view_bounds(&self, id: NativeViewId, position: Position, size: Size) -> Result<()> {
+    send_user_message(
+      &self.context,
+      Message::Window(
+        self.window_id,
+        WindowMessage::SetNativeViewBounds(id, position, size),
+      ),
+    )
+  }
+
+  fn set_native_view_visible(&self, id: NativeViewId, visible: bool) -> Result<()> {
+    send_user_message(
+      &self.context,
+      Message::Window(
+        self.window_id,
+        WindowMessage::SetNativeViewVisible(id, visible),
+      ),
+    )
+  }
+
+  fn set_native_view_z_order(&self, id: NativeViewId, index: usize) -> Result<()> {
+    send_user_message(
+      &self.context,
+      Message::Window(
+        self.window_id,
+        WindowMessage::SetNativeViewZOrder(id, index),
+      ),
+    )
+  }
+
+  fn remove_native_view(&self, id: NativeViewId) -> Result<()> {
+    send_user_message(
+      &self.context,
+      Message::Window(self.window_id, WindowMessage::RemoveNativeView(id)),
+    )
+  }
 }
 
 #[derive(Clone)]
 pub struct WebviewWrapper {
   id: WebviewId,
+  // the window the webview is attached to, shared with its dispatchers
+  window_id: Arc<Mutex<WindowId>>,
   inner: Rc<WebView>,
   context_store: WebContextStore,
   // the key of the WebContext if it's not shared
   context_key: Option<PathBuf>,
   bounds: Option<Arc<Mutex<WebviewBounds>>>,
+  // the physical size last reported with `WebviewEvent::Resized`
+  size: Rc<Cell<PhysicalSize<u32>>>,
+  theme: Rc<Cell<Option<Theme>>>,
+  ignore_cursor_events: Rc<Cell<bool>>,
+  kind: WebviewKind,
+  content_insets: Rc<Cell<Insets>>,
+  webview_event_listeners: WebviewEventListeners,
+  // `None` when the webview cannot be recreated
+  recreate: Option<Rc<RecreateWebview>>,
+  // the scripts added with `WebviewMessage::AddInitializationScript` and `WebviewMessage::AddIsolatedInitializationScript`,
+  // run again when the webview is recreated
+  initialization_scripts: Rc<RefCell<Vec<InitializationScript>>>,
+}
+
+impl WebviewWrapper {
+  /// The logical bounds of the webview in its window, including the content insets.
+  fn outer_bounds(&self) -> wry::Rect {
+    let bounds = self.inner.bounds();
+    let (top, left, bottom, right) = inset_lengths(self.content_insets.get());
+    wry::Rect {
+      x: bounds.x - left as i32,
+      y: bounds.y - top as i32,
+      width: bounds.width + left + right,
+      height: bounds.height + top + bottom,
+    }
+  }
+
+  /// Sets the logical bounds of the webview in its window, the native webview being inset by the content insets.
+  fn set_outer_bounds(&self, bounds: wry::Rect) {
+    let (top, left, bottom, right) = inset_lengths(self.content_insets.get());
+    self.inner.set_bounds(wry::Rect {
+      x: bounds.x + left as i32,
+      y: bounds.y + top as i32,
+      width: bounds.width.saturating_sub(left + right),
+      height: bounds.height.saturating_sub(top + bottom),
+    });
+  }
+}
+
+/// The `(top, left, bottom, right)` insets in whole logical pixels.
+fn inset_lengths(insets: Insets) -> (u32, u32, u32, u32) {
+  let length = |inset: f64| inset.max(0.0).round() as u32;
+  (
+    length(insets.top),
+    length(insets.left),
+    length(insets.bottom),
+    length(insets.right),
+  )
 }
 
 impl Deref for WebviewWrapper {
@@ -1874,6 +2567,39 @@ impl Drop for WebviewWrapper {
   fn drop(&mut self) {
     if Rc::get_mut(&mut self.inner).is_some() {
       self.context_store.lock().unwrap().remove(&self.context_key);
+
+      #[cfg(target_os = "macos")]
+      {
+        use wry::WebViewExtMacOS;
+        let key = self.inner.webview() as usize;
+        let _ = CONTEXT_MENU_HANDLERS.try_with(|handlers| handlers.borrow_mut().remove(&key));
+        let _ = PROCESS_CRASHED_HANDLERS.try_with(|handlers| handlers.borrow_mut().remove(&key));
+        let _ = REQUEST_OBSERVERS.try_with(|observers| observers.borrow_mut().remove(&key));
+        let _ = IGNORED_CURSOR_EVENTS.try_with(|webviews| webviews.borrow_mut().remove(&key));
+      }
+
+      #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+      ))]
+      {
+        let webview = Rc::as_ptr(&self.inner) as usize;
+        let _ = SESSION_WEBVIEWS.try_with(|webviews| {
+          webviews
+            .borrow_mut()
+            .retain(|_, (_, session_webview)| *session_webview != webview)
+        });
+        let _ = FORCED_THEMES.try_with(|themes| {
+          let mut themes = themes.borrow_mut();
+          if themes.webviews.iter().any(|(id, _)| *id == self.id) {
+            themes.webviews.retain(|(id, _)| *id != self.id);
+            themes.apply();
+          }
+        });
+      }
     }
   }
 }
@@ -1885,6 +2611,10 @@ pub struct WindowWrapper {
   // or it's just a container for a single webview
   has_children: AtomicBool,
   webviews: Vec<WebviewWrapper>,
+  native_views: Vec<(NativeViewId, native_view::NativeView)>,
+  // the stacking order of the children, from the bottom-most,
+  // the children created after the last update are on top of them in creation order
+  z_order: Vec<ChildView>,
   window_event_listeners: WindowEventListeners,
   is_window_transparent: bool,
   #[cfg(windows)]
@@ -2079,6 +2809,16 @@ impl<T: UserEvent> RuntimeHandle<T> for WryHandle<T> {
       .collect()
   }
 
+  fn cursor_position(&self) -> Result<PhysicalPosition<f64>> {
+    self
+      .context
+      .main_thread
+      .window_target
+      .cursor_position()
+      .map(|position| PhysicalPositionWrapper(position).into())
+      .map_err(|_| Error::FailedToGetCursorPosition)
+  }
+
   #[cfg(target_os = "macos")]
   fn show(&self) -> tauri_runtime::Result<()> {
     send_user_message(
@@ -2149,7 +2889,9 @@ impl<T: UserEvent> Wry<T> {
       next_window_id: Default::default(),
       next_webview_id: Default::default(),
       next_window_event_id: Default::default(),
+      next_webview_event_id: Default::default(),
       next_webcontext_id: Default::default(),
+      next_native_view_id: Default::default(),
     };
 
     Ok(Self {
@@ -2166,6 +2908,22 @@ impl<T: UserEvent> Runtime<T> for Wry<T> {
 
   type EventLoopProxy = EventProxy<T>;
 
+  const NAME: &'static str = "wry";
+
+  fn webview_version() -> Result<String> {
+    let version = wry::webview_version().map_err(|e| Error::WebviewVersion(e.to_string()))?;
+    // the ABI version of the webkit2gtk library the app is linked against
+    #[cfg(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd"
+    ))]
+    let version = format!("{version} (libwebkit2gtk-4.1.so.0)");
+    Ok(version)
+  }
+
   fn new(args: RuntimeInitArgs) -> Result<Self> {
     Self::init_with_builder(EventLoopBuilder::<Message<T>>::with_user_event(), args)
   }
@@ -2237,7 +2995,7 @@ impl<T: UserEvent> Runtime<T> for Wry<T> {
     let detached_webview = webview_id.map(|id| DetachedWebview {
       label: label.clone(),
       dispatcher: WryWebviewDispatcher {
-        window_id,
+        window_id: Arc::new(Mutex::new(window_id)),
         webview_id: id,
         context: self.context.clone(),
       },
@@ -2267,11 +3025,12 @@ impl<T: UserEvent> Runtime<T> for Wry<T> {
       .and_then(|w| w.inner.clone());
     if let Some(window) = window {
       let webview_id = self.context.next_webview_id();
+      let webview_window_id = Arc::new(Mutex::new(window_id));
 
       let webview = create_webview(
         WebviewKind::WindowChild,
         &window,
-        window_id,
+        webview_window_id.clone(),
         webview_id,
         &self.context,
         pending,
@@ -2290,7 +3049,7 @@ impl<T: UserEvent> Runtime<T> for Wry<T> {
         });
 
       let dispatcher = WryWebviewDispatcher {
-        window_id,
+        window_id: webview_window_id,
         webview_id,
         context: self.context.clone(),
       };
@@ -2671,6 +3430,68 @@ fn handle_user_message<T: UserEvent>(
           WindowMessage::SetProgressBar(progress_state) => {
             window.set_progress_bar(ProgressBarStateWrapper::from(progress_state).0);
           }
+          WindowMessage::CreateNativeView(view_id, position, size, tx) => {
+            let scale_factor = window.scale_factor();
+            let result = native_view::NativeView::new(
+              &window,
+              position.to_physical(scale_factor),
+              size.to_physical(scale_factor),
+            )
+            .map(|view| {
+              if let Some(w) = windows.borrow_mut().get_mut(&id) {
+                // the children created before the view stay below it
+                w.z_order = current_z_order(w);
+                w.z_order.push(ChildView::Native(view_id));
+                w.native_views.push((view_id, view));
+              }
+            });
+            tx.send(result).unwrap();
+          }
+          WindowMessage::NativeViewHandle(view_id, tx) => {
+            let handle = windows
+              .borrow()
+              .get(&id)
+              .and_then(|w| w.native_views.iter().find(|(i, _)| *i == view_id))
+              .and_then(|(_, view)| view.raw_window_handle())
+              .map(SendRawWindowHandle)
+              .ok_or(raw_window_handle::HandleError::Unavailable);
+            tx.send(handle).unwrap();
+          }
+          WindowMessage::SetNativeViewBounds(view_id, position, size) => {
+            let scale_factor = window.scale_factor();
+            if let Some((_, view)) = windows
+              .borrow()
+              .get(&id)
+              .and_then(|w| w.native_views.iter().find(|(i, _)| *i == view_id))
+            {
+              view.set_bounds(
+                &window,
+                position.to_physical(scale_factor),
+                size.to_physical(scale_factor),
+              );
+            }
+          }
+          WindowMessage::SetNativeViewVisible(view_id, visible) => {
+            if let Some((_, view)) = windows
+              .borrow()
+              .get(&id)
+              .and_then(|w| w.native_views.iter().find(|(i, _)| *i == view_id))
+            {
+              view.set_visible(visible);
+            }
+          }
+          WindowMessage::SetNativeViewZOrder(view_id, index) => {
+            if let Some(w) = windows.borrow_mut().get_mut(&id) {
+              set_child_z_order(&window, w, ChildView::Native(view_id), index);
+            }
+          }
+          WindowMessage::RemoveNativeView(view_id) => {
+            if let Some(w) = windows.borrow_mut().get_mut(&id) {
+              w.native_views.retain(|(i, _)| *i != view_id);
+              w.z_order
+                .retain(|child| *child != ChildView::Native(view_id));
+            }
+          }
         }
       }
     }
@@ -2698,7 +3519,24 @@ fn handle_user_message<T: UserEvent>(
               debug_eprintln!("{}", e);
             }
           }
+          WebviewMessage::EvaluateScriptWithCallback(script, callback) => {
+            // wry takes a `Fn` callback but calls it once
+            let callback = Mutex::new(Some(callback));
+            if let Err(e) = webview.evaluate_script_with_callback(&script, move |result| {
+              if let Some(callback) = callback.lock().unwrap().take() {
+                callback(result);
+              }
+            }) {
+              debug_eprintln!("{}", e);
+            }
+          }
+          WebviewMessage::EvaluateScriptInWorld(world, script, callback) => {
+            evaluate_script_in_world(&webview, &world, &script, callback);
+          }
           WebviewMessage::Navigate(url) => webview.load_url(url.as_str()),
+          WebviewMessage::NavigateWithHeaders(url, headers) => {
+            webview.load_url_with_headers(url.as_str(), headers)
+          }
           WebviewMessage::Print => {
             let _ = webview.print();
           }
@@ -2711,7 +3549,7 @@ fn handle_user_message<T: UserEvent>(
             });
           }
           WebviewMessage::SetSize(size) => {
-            let mut bounds = webview.bounds();
+            let mut bounds = webview.outer_bounds();
             let size = size.to_logical(window.scale_factor());
             bounds.width = size.width;
             bounds.height = size.height;
@@ -2723,10 +3561,11 @@ fn handle_user_message<T: UserEvent>(
               bounds.height_rate = size.height as f32 / window_size.height as f32;
             }
 
-            webview.set_bounds(bounds);
+            webview.set_outer_bounds(bounds);
+            notify_webview_resized(&webview, window.scale_factor());
           }
           WebviewMessage::SetPosition(position) => {
-            let mut bounds = webview.bounds();
+            let mut bounds = webview.outer_bounds();
             let position = position.to_logical(window.scale_factor());
             bounds.x = position.x;
             bounds.y = position.y;
@@ -2738,11 +3577,167 @@ fn handle_user_message<T: UserEvent>(
               bounds.height_rate = position.y as f32 / window_size.height as f32;
             }
 
-            webview.set_bounds(bounds);
+            webview.set_outer_bounds(bounds);
+          }
+          WebviewMessage::SetBounds(position, size) => {
+            let scale_factor = window.scale_factor();
+            let position = position.to_logical::<i32>(scale_factor);
+            let size = size.to_logical::<u32>(scale_factor);
+            let bounds = wry::Rect {
+              x: position.x,
+              y: position.y,
+              width: size.width,
+              height: size.height,
+            };
+
+            if let Some(b) = &webview.bounds {
+              let window_size = window.inner_size();
+              let mut b = b.lock().unwrap();
+              b.x_rate = position.x as f32 / window_size.width as f32;
+              b.y_rate = position.y as f32 / window_size.height as f32;
+              b.width_rate = size.width as f32 / window_size.width as f32;
+              b.height_rate = size.height as f32 / window_size.height as f32;
+            }
+
+            webview.set_outer_bounds(bounds);
+            notify_webview_resized(&webview, scale_factor);
+          }
+          WebviewMessage::SetZOrder(index) => {
+            if let Some(w) = windows.borrow_mut().get_mut(&window_id) {
+              if w.has_children.load(Ordering::Relaxed) {
+                set_child_z_order(&window, w, ChildView::Webview(webview_id), index);
+              }
+            }
           }
           WebviewMessage::SetFocus => {
             webview.focus();
           }
+          WebviewMessage::SetTheme(theme) => {
+            webview.theme.set(theme);
+            set_webview_theme(&webview, theme);
+          }
+          WebviewMessage::SetSuspended(suspended) => {
+            set_webview_suspended(&webview, suspended);
+          }
+          #[cfg(target_os = "macos")]
+          WebviewMessage::SetBackgroundThrottling(policy) => {
+            set_webview_background_throttling(&webview, policy);
+          }
+          #[cfg(any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+          ))]
+          WebviewMessage::SetSpellcheck(enabled) => {
+            set_webview_spellcheck(&webview, enabled);
+          }
+          #[cfg(any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+          ))]
+          WebviewMessage::SetSpellcheckLanguages(languages) => {
+            set_webview_spellcheck_languages(&webview, &languages);
+          }
+          WebviewMessage::SetMuted(muted) => {
+            set_webview_muted(&webview, muted);
+          }
+          WebviewMessage::SetIgnoreCursorEvents(ignore) => {
+            webview.ignore_cursor_events.set(ignore);
+            set_webview_ignore_cursor_events(&webview, ignore);
+          }
+          WebviewMessage::SetContentInsets(insets) => {
+            set_webview_content_insets(&webview, insets);
+            notify_webview_resized(&webview, window.scale_factor());
+          }
+          WebviewMessage::PauseAllMedia => {
+            pause_webview_media(&webview);
+          }
+          WebviewMessage::Screenshot(f) => {
+            capture_webview_screenshot(&webview, f);
+          }
+          #[cfg(feature = "devtools-protocol")]
+          WebviewMessage::CallDevtoolsProtocolMethod(method, params, f) => {
+            call_devtools_protocol_method(&webview, method, params, f);
+          }
+          #[cfg(feature = "devtools-protocol")]
+          WebviewMessage::SubscribeDevtoolsProtocolEvent(event, f) => {
+            subscribe_devtools_protocol_event(&webview, event, f);
+          }
+          WebviewMessage::Reparent(new_window_id, tx) => {
+            let new_window = windows
+              .borrow()
+              .get(&new_window_id)
+              .and_then(|w| w.inner.clone());
+            let result = match new_window {
+              Some(new_window) => reparent_webview(&webview, &new_window).map(|()| {
+                if let Some(b) = &webview.bounds {
+                  let bounds = webview.outer_bounds();
+                  let window_size = new_window.inner_size();
+                  let mut b = b.lock().unwrap();
+                  b.x_rate = bounds.x as f32 / window_size.width as f32;
+                  b.y_rate = bounds.y as f32 / window_size.height as f32;
+                  b.width_rate = bounds.width as f32 / window_size.width as f32;
+                  b.height_rate = bounds.height as f32 / window_size.height as f32;
+                }
+
+                let mut windows = windows.borrow_mut();
+                if let Some(w) = windows.get_mut(&window_id) {
+                  w.webviews.retain(|w| w.id != webview.id);
+                }
+                if let Some(w) = windows.get_mut(&new_window_id) {
+                  w.webviews.push(webview.clone());
+                  w.has_children.store(true, Ordering::Relaxed);
+                }
+                *webview.window_id.lock().unwrap() = new_window_id;
+              }),
+              None => Err(Error::WindowNotFound),
+            };
+            tx.send(result).unwrap();
+          }
+          WebviewMessage::Recover(tx) => {
+            let result = recover_webview(&window, &webview).map(|recovered| {
+              if let Some(w) = windows.borrow_mut().get_mut(&window_id) {
+                if let Some(slot) = w.webviews.iter_mut().find(|w| w.id == webview.id) {
+                  // applied once the previous webview is dropped since both share the forced theme entry on Linux
+                  *slot = recovered;
+                  if let Some(theme) = slot.theme.get() {
+                    set_webview_theme(slot, Some(theme));
+                  }
+                }
+              }
+            });
+            tx.send(result).unwrap();
+          }
+          WebviewMessage::AddInitializationScript(script) => {
+            if let Err(e) = add_webview_initialization_script(&webview, &script, None) {
+              debug_eprintln!("failed to add the initialization script: {}", e);
+            }
+            webview
+              .initialization_scripts
+              .borrow_mut()
+              .push((None, script));
+          }
+          WebviewMessage::AddIsolatedInitializationScript(world, script) => {
+            if let Err(e) = add_webview_initialization_script(&webview, &script, Some(&world)) {
+              debug_eprintln!("failed to add the initialization script: {}", e);
+            }
+            webview
+              .initialization_scripts
+              .borrow_mut()
+              .push((Some(world), script));
+          }
+          WebviewMessage::AddEventListener(id, listener) => {
+            webview
+              .webview_event_listeners
+              .lock()
+              .unwrap()
+              .insert(id, listener);
+          }
           WebviewMessage::WebviewEvent(_event) => { /* already handled */ }
           WebviewMessage::WithWebview(f) => {
             #[cfg(any(
@@ -2805,17 +3800,44 @@ fn handle_user_message<T: UserEvent>(
             tx.send(webview.url()).unwrap();
           }
           WebviewMessage::Position(tx) => {
-            let bounds = webview.bounds();
+            let bounds = webview.outer_bounds();
             let position =
               LogicalPosition::new(bounds.x, bounds.y).to_physical(window.scale_factor());
             tx.send(position).unwrap();
           }
           WebviewMessage::Size(tx) => {
-            let bounds = webview.bounds();
+            let bounds = webview.outer_bounds();
             let size =
               LogicalSize::new(bounds.width, bounds.height).to_physical(window.scale_factor());
             tx.send(size).unwrap();
           }
+          WebviewMessage::ScaleFactor(tx) => {
+            tx.send(window.scale_factor()).unwrap();
+          }
+          WebviewMessage::Theme(tx) => {
+            let theme = webview
+              .theme
+              .get()
+              .unwrap_or_else(|| map_theme(&window.theme()));
+            tx.send(theme).unwrap();
+          }
+          WebviewMessage::ProcessId(tx) => {
+            #[cfg(windows)]
+            let process_id = {
+              use wry::WebViewExtWindows;
+              let mut process_id = 0;
+              unsafe { webview.controller().CoreWebView2() }
+                .and_then(|core| unsafe { core.BrowserProcessId(&mut process_id) })
+                .map(|_| process_id)
+                .unwrap_or_else(|_| std::process::id())
+            };
+            #[cfg(not(windows))]
+            let process_id = std::process::id();
+            tx.send(process_id).unwrap();
+          }
+          WebviewMessage::IsMuted(tx) => {
+            tx.send(is_webview_muted(&webview)).unwrap();
+          }
         }
       }
     }
@@ -2838,7 +3860,7 @@ fn handle_user_message<T: UserEvent>(
         }
       }
     }
-    Message::CreateWindow(window_id, handler) => match handler(event_loop) {
+    Message::CreateWindow(window_id, _label, handler) => match handler(event_loop) {
       Ok(webview) => {
         windows.borrow_mut().insert(window_id, webview);
       }
@@ -2878,6 +3900,8 @@ fn handle_user_message<T: UserEvent>(
             inner: Some(window.clone()),
             window_event_listeners: Default::default(),
             webviews: Vec::new(),
+            native_views: Vec::new(),
+            z_order: Vec::new(),
             is_window_transparent,
             #[cfg(windows)]
             surface,
@@ -2949,9 +3973,34 @@ fn handle_event_loop<T: UserEvent>(
 
     Event::UserEvent(Message::Webview(
       window_id,
-      _webview_id,
+      webview_id,
       WebviewMessage::WebviewEvent(event),
     )) => {
+      let webview_event = match &event {
+        WebviewEvent::MediaPlaybackChanged(playing) => {
+          Some(RuntimeWebviewEvent::MediaPlaybackChanged(*playing))
+        }
+        WebviewEvent::Focused(focused) => Some(RuntimeWebviewEvent::Focused(*focused)),
+        WebviewEvent::ProcessCrashed(reason) => {
+          Some(RuntimeWebviewEvent::ProcessCrashed { reason: *reason })
+        }
+        _ => None,
+      };
+      if let Some(webview_event) = webview_event {
+        let listeners = windows.borrow().get(&window_id).and_then(|window| {
+          window
+            .webviews
+            .iter()
+            .find(|w| w.id == webview_id)
+            .map(|w| w.webview_event_listeners.clone())
+        });
+        if let Some(listeners) = listeners {
+          for handler in listeners.lock().unwrap().values() {
+            handler(&webview_event);
+          }
+        }
+      }
+
       if let Some(event) = WindowEventWrapper::from(event).0 {
         let windows = windows.borrow();
         let window = windows.get(&window_id);
@@ -3031,17 +4080,23 @@ fn handle_event_loop<T: UserEvent>(
             }
           }
           TaoWindowEvent::Resized(size) => {
-            if let Some(webviews) = windows.borrow().get(&window_id).map(|w| w.webviews.clone()) {
+            let window = windows
+              .borrow()
+              .get(&window_id)
+              .and_then(|w| w.inner.clone().map(|inner| (inner, w.webviews.clone())));
+            if let Some((window, webviews)) = window {
               for webview in webviews {
                 if let Some(bounds) = &webview.bounds {
-                  let b = bounds.lock().unwrap();
-                  webview.set_bounds(wry::Rect {
+                  let b = bounds.lock().unwrap().clone();
+                  webview.set_outer_bounds(wry::Rect {
                     x: (size.width as f32 * b.x_rate) as i32,
                     y: (size.height as f32 * b.y_rate) as i32,
                     width: (size.width as f32 * b.width_rate) as u32,
                     height: (size.height as f32 * b.height_rate) as u32,
                   });
                 }
+                // webviews filling the window are resized with it
+                notify_webview_resized(&webview, window.scale_factor());
               }
             }
           }
@@ -3071,6 +4126,19 @@ fn handle_event_loop<T: UserEvent>(
         on_window_close(id, windows.clone());
       }
       Message::UserEvent(t) => callback(RunEvent::UserEvent(t)),
+      Message::CreateWindow(window_id, label, handler) => match handler(event_loop) {
+        Ok(window) => {
+          windows.borrow_mut().insert(window_id, window);
+        }
+        Err(e) => {
+          debug_eprintln!("failed to create the `{}` window: {}", label, e);
+          // the window handle was already returned, let the app know it is gone
+          callback(RunEvent::WindowEvent {
+            label,
+            event: WindowEvent::Destroyed,
+          });
+        }
+      },
       message => {
         handle_user_message(
           event_loop,
@@ -3250,14 +4318,21 @@ fn create_window<T: UserEvent, F: Fn(RawWindow) + Send + 'static>(
   let mut webviews = Vec::new();
 
   if let Some(webview) = webview {
-    webviews.push(create_webview(
+    match create_webview(
       WebviewKind::WindowContent,
       &window,
-      window_id,
+      Arc::new(Mutex::new(window_id)),
       webview_id,
       context,
       webview,
-    )?);
+    ) {
+      Ok(webview) => webviews.push(webview),
+      Err(e) => {
+        // the window is closed when dropped, it must not be left behind without its webview
+        context.webview_id_map.remove(&window.id());
+        return Err(e);
+      }
+    }
   }
 
   let window = Arc::new(window);
@@ -3283,6 +4358,8 @@ fn create_window<T: UserEvent, F: Fn(RawWindow) + Send + 'static>(
     has_children: AtomicBool::new(false),
     inner: Some(window),
     webviews,
+    native_views: Vec::new(),
+    z_order: Vec::new(),
     window_event_listeners,
     is_window_transparent,
     #[cfg(windows)]
@@ -3291,6 +4368,7 @@ fn create_window<T: UserEvent, F: Fn(RawWindow) + Send + 'static>(
 }
 
 // the kind of the webview
+#[derive(Clone, Copy)]
 enum WebviewKind {
   // webview is the entire window content
   WindowContent,
@@ -3298,6 +4376,74 @@ enum WebviewKind {
   WindowChild,
 }
 
+/// A child of a window in its stacking order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChildView {
+  Webview(WebviewId),
+  Native(NativeViewId),
+}
+
+/// The stacking order of the children of the window, from the bottom-most.
+fn current_z_order(window: &WindowWrapper) -> Vec<ChildView> {
+  let exists = |child: &ChildView| match child {
+    ChildView::Webview(id) => window.webviews.iter().any(|w| w.id == *id),
+    ChildView::Native(id) => window.native_views.iter().any(|(i, _)| i == id),
+  };
+  let mut order = window
+    .z_order
+    .iter()
+    .copied()
+    .filter(exists)
+    .collect::<Vec<_>>();
+  // native views are added to the order when they are created, so only webviews can be missing
+  for webview in &window.webviews {
+    if !order.contains(&ChildView::Webview(webview.id)) {
+      order.push(ChildView::Webview(webview.id));
+    }
+  }
+  order
+}
+
+/// Moves a child of the window to the given index of its stacking order and restacks the children.
+fn set_child_z_order(window: &Window, wrapper: &mut WindowWrapper, child: ChildView, index: usize) {
+  let mut order = current_z_order(wrapper);
+  let Some(current) = order.iter().position(|c| *c == child) else {
+    return;
+  };
+  order.remove(current);
+  order.insert(index.min(order.len()), child);
+
+  let views = order
+    .iter()
+    .filter_map(|child| match child {
+      ChildView::Webview(id) => wrapper
+        .webviews
+        .iter()
+        .find(|w| w.id == *id)
+        .map(|w| native_view::StackedView::Webview(&w.inner)),
+      ChildView::Native(id) => wrapper
+        .native_views
+        .iter()
+        .find(|(i, _)| i == id)
+        .map(|(_, view)| native_view::StackedView::Native(view)),
+    })
+    .collect::<Vec<_>>();
+  native_view::restack(window, &views);
+  wrapper.z_order = order;
+}
+
+/// Emits [`RuntimeWebviewEvent::Resized`] if the webview size changed since it was last emitted.
+fn notify_webview_resized(webview: &WebviewWrapper, scale_factor: f64) {
+  let bounds = webview.outer_bounds();
+  let size = LogicalSize::new(bounds.width, bounds.height).to_physical(scale_factor);
+  if webview.size.replace(size) != size {
+    let event = RuntimeWebviewEvent::Resized(size);
+    for handler in webview.webview_event_listeners.lock().unwrap().values() {
+      handler(&event);
+    }
+  }
+}
+
 #[derive(Clone)]
 struct WebviewBounds {
   x_rate: f32,
@@ -3306,23 +4452,135 @@ struct WebviewBounds {
   height_rate: f32,
 }
 
+type UriSchemeProtocol = dyn Fn(http::Request<Vec<u8>>, Box<dyn FnOnce(http::Response<Cow<'static, [u8]>>) + Send>)
+  + Send
+  + Sync;
+type NavigationHandler = Box<dyn Fn(&Url) -> bool + Send>;
+type OnPageLoadHandler = Box<dyn Fn(Url, tauri_runtime::webview::PageLoadEvent) + Send>;
+type DownloadHandler = dyn Fn(DownloadEvent) -> bool + Send + Sync;
+
+/// An initialization script and the isolated world it runs in, `None` for the page world.
+type InitializationScript = (Option<String>, String);
+
+/// Recreates the native webview at the given URL and bounds, with additional initialization scripts.
+type RecreateWebview =
+  dyn Fn(&Window, &str, wry::Rect, &[InitializationScript]) -> Result<WebviewWrapper>;
+
+/// Everything needed to create the native webview of a [`PendingWebview`],
+/// kept to recreate it with [`WebviewMessage::Recover`].
+struct WebviewRecipe<T: UserEvent> {
+  kind: WebviewKind,
+  window_id: Arc<Mutex<WindowId>>,
+  id: WebviewId,
+  context: Context<T>,
+  label: String,
+  webview_attributes: WebviewAttributes,
+  uri_scheme_protocols: Vec<(String, Arc<UriSchemeProtocol>)>,
+  ipc_handler: Option<Rc<WebviewIpcHandler<T, Wry<T>>>>,
+  navigation_handler: Option<Rc<NavigationHandler>>,
+  download_handler: Option<Arc<DownloadHandler>>,
+  on_page_load_handler: Option<Rc<OnPageLoadHandler>>,
+  context_menu_handler: Option<ContextMenuHandler>,
+  auth_challenge_handler: Option<AuthChallengeHandler>,
+  cert_error_handler: Option<CertErrorHandler>,
+  request_observer: Option<RequestObserver>,
+  // only used when the webview is first created
+  #[cfg(target_os = "android")]
+  #[allow(clippy::type_complexity)]
+  on_webview_created: RefCell<
+    Option<
+      Box<
+        dyn Fn(
+            tauri_runtime::webview::CreationContext<'_, '_>,
+          ) -> std::result::Result<(), jni::errors::Error>
+          + Send,
+      >,
+    >,
+  >,
+}
+
 fn create_webview<T: UserEvent>(
   kind: WebviewKind,
   window: &Window,
-  window_id: WindowId,
+  window_id: Arc<Mutex<WindowId>>,
   id: WebviewId,
   context: &Context<T>,
-  pending: PendingWebview<T, Wry<T>>,
+  mut pending: PendingWebview<T, Wry<T>>,
 ) -> Result<WebviewWrapper> {
+  // only sent with the first load, not when the webview is recreated at its current URL
+  let initial_headers = pending.webview_attributes.initial_headers.take();
+  let recipe = Rc::new(WebviewRecipe {
+    kind,
+    window_id,
+    id,
+    context: context.clone(),
+    label: pending.label,
+    webview_attributes: pending.webview_attributes,
+    uri_scheme_protocols: pending
+      .uri_scheme_protocols
+      .into_iter()
+      .map(|(scheme, protocol)| (scheme, Arc::from(protocol)))
+      .collect(),
+    ipc_handler: pending.ipc_handler.map(Rc::new),
+    navigation_handler: pending.navigation_handler.map(Rc::new),
+    download_handler: pending.download_handler,
+    on_page_load_handler: pending.on_page_load_handler.map(Rc::new),
+    context_menu_handler: pending.context_menu_handler.map(Rc::from),
+    auth_challenge_handler: pending.auth_challenge_handler.map(Rc::from),
+    cert_error_handler: pending.cert_error_handler.map(Rc::from),
+    request_observer: pending.request_observer.map(Rc::from),
+    #[cfg(target_os = "android")]
+    on_webview_created: RefCell::new(pending.on_webview_created),
+  });
+
   #[allow(unused_mut)]
-  let PendingWebview {
-    webview_attributes,
-    uri_scheme_protocols,
+  let mut webview = build_webview(&recipe, window, &pending.url, initial_headers, None, &[])?;
+  #[cfg(desktop)]
+  {
+    webview.recreate = Some(Rc::new(move |window, url, bounds, scripts| {
+      // the content webview always fills its window
+      let bounds = matches!(recipe.kind, WebviewKind::WindowChild).then_some(bounds);
+      build_webview(&recipe, window, url, None, bounds, scripts)
+    }));
+  }
+  Ok(webview)
+}
+
+fn build_webview<T: UserEvent>(
+  recipe: &WebviewRecipe<T>,
+  window: &Window,
+  url: &str,
+  headers: Option<http::HeaderMap>,
+  bounds: Option<wry::Rect>,
+  initialization_scripts: &[InitializationScript],
+) -> Result<WebviewWrapper> {
+  let WebviewRecipe {
+    kind,
+    window_id,
+    id,
+    context,
     label,
-    ipc_handler,
-    url,
     ..
-  } = pending;
+  } = recipe;
+  let (kind, id) = (*kind, *id);
+
+  let mut webview_attributes = recipe.webview_attributes.clone();
+  if let Some(bounds) = bounds {
+    webview_attributes.bounds = Some((
+      LogicalPosition::new(bounds.x, bounds.y).into(),
+      LogicalSize::new(bounds.width, bounds.height).into(),
+    ));
+  }
+  for (world, script) in initialization_scripts {
+    match world {
+      Some(world) => webview_attributes
+        .isolated_initialization_scripts
+        .push((world.clone(), script.clone())),
+      None => webview_attributes
+        .initialization_scripts
+        .push(script.clone()),
+    }
+  }
 
   let builder = match kind {
     #[cfg(not(any(
@@ -3366,18 +4624,25 @@ fn create_webview<T: UserEvent>(
   };
 
   let mut webview_builder = builder
-    .with_focused(window.is_focused())
-    .with_url(&url)
-    .unwrap() // safe to unwrap because we validate the URL beforehand
+    // focusing the webview restores a minimized window
+    .with_focused(webview_attributes.focus && window.is_focused() && !window.is_minimized())
     .with_transparent(webview_attributes.transparent)
     .with_accept_first_mouse(webview_attributes.accept_first_mouse);
 
+  // safe to unwrap because we validate the URL beforehand
+  webview_builder = match headers {
+    Some(headers) => webview_builder.with_url_and_headers(url, headers),
+    None => webview_builder.with_url(url),
+  }
+  .unwrap();
+
   if webview_attributes.file_drop_handler_enabled {
     let proxy = context.proxy.clone();
+    let window_id = window_id.clone();
     webview_builder = webview_builder.with_file_drop_handler(move |event| {
       let event: FileDropEvent = FileDropEventWrapper(event).into();
       let _ = proxy.send_event(Message::Webview(
-        window_id,
+        *window_id.lock().unwrap(),
         id,
         WebviewMessage::WebviewEvent(WebviewEvent::FileDrop(event)),
       ));
@@ -3385,7 +4650,7 @@ fn create_webview<T: UserEvent>(
     });
   }
 
-  if let Some(navigation_handler) = pending.navigation_handler {
+  if let Some(navigation_handler) = recipe.navigation_handler.clone() {
     webview_builder = webview_builder.with_navigation_handler(move |url| {
       Url::parse(&url)
         .map(|url| navigation_handler(&url))
@@ -3419,7 +4684,7 @@ fn create_webview<T: UserEvent>(
     None
   };
 
-  if let Some(download_handler) = pending.download_handler {
+  if let Some(download_handler) = recipe.download_handler.clone() {
     let download_handler_ = download_handler.clone();
     webview_builder = webview_builder.with_download_started_handler(move |url, path| {
       if let Ok(url) = url.parse() {
@@ -3438,7 +4703,7 @@ fn create_webview<T: UserEvent>(
     });
   }
 
-  if let Some(page_load_handler) = pending.on_page_load_handler {
+  if let Some(page_load_handler) = recipe.on_page_load_handler.clone() {
     webview_builder = webview_builder.with_on_page_load_handler(move |event, url| {
       let _ = Url::parse(&url).map(|url| {
         page_load_handler(
@@ -3452,9 +4717,21 @@ fn create_webview<T: UserEvent>(
     });
   }
 
-  if let Some(user_agent) = webview_attributes.user_agent {
-    webview_builder = webview_builder.with_user_agent(&user_agent);
-  }
+  // without a user agent, the suffix is appended to the platform default once the webview is created
+  let user_agent_suffix = match (
+    webview_attributes.user_agent,
+    webview_attributes.user_agent_suffix,
+  ) {
+    (Some(user_agent), Some(suffix)) => {
+      webview_builder = webview_builder.with_user_agent(&format!("{user_agent} {suffix}"));
+      None
+    }
+    (Some(user_agent), None) => {
+      webview_builder = webview_builder.with_user_agent(&user_agent);
+      None
+    }
+    (None, suffix) => suffix,
+  };
 
   if let Some(proxy_url) = webview_attributes.proxy_url {
     let config = parse_proxy_url(&proxy_url)?;
@@ -3464,7 +4741,38 @@ fn create_webview<T: UserEvent>(
 
   #[cfg(windows)]
   {
-    if let Some(additional_browser_args) = webview_attributes.additional_browser_args {
+    // the default arguments of wry, which are replaced when custom ones are set
+    const DEFAULT_BROWSER_ARGS: &str =
+      "--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection";
+
+    #[allow(unused_mut)]
+    let mut additional_browser_args = match webview_attributes.background_throttling {
+      Some(BackgroundThrottlingPolicy::Disabled) => Some(format!(
+        "{} --disable-background-timer-throttling --disable-renderer-backgrounding --disable-backgrounding-occluded-windows",
+        webview_attributes
+          .additional_browser_args
+          .as_deref()
+          .unwrap_or(DEFAULT_BROWSER_ARGS)
+      )),
+      Some(policy) => {
+        debug_eprintln!(
+          "the {:?} background throttling policy is not supported on Windows",
+          policy
+        );
+        webview_attributes.additional_browser_args
+      }
+      None => webview_attributes.additional_browser_args,
+    };
+    #[cfg(feature = "devtools-protocol")]
+    if let Some(port) = webview_attributes.remote_debugging_port {
+      additional_browser_args = Some(format!(
+        "{} --remote-debugging-port={port}",
+        additional_browser_args
+          .as_deref()
+          .unwrap_or(DEFAULT_BROWSER_ARGS)
+      ));
+    }
+    if let Some(additional_browser_args) = additional_browser_args {
       webview_builder = webview_builder.with_additional_browser_args(&additional_browser_args);
     }
 
@@ -3480,9 +4788,9 @@ fn create_webview<T: UserEvent>(
     webview_builder = webview_builder.with_https_scheme(false);
   }
 
-  if let Some(handler) = ipc_handler {
+  if let Some(handler) = recipe.ipc_handler.clone() {
     webview_builder = webview_builder.with_ipc_handler(create_ipc_handler(
-      window_id,
+      window_id.clone(),
       id,
       context.clone(),
       label.clone(),
@@ -3490,14 +4798,29 @@ fn create_webview<T: UserEvent>(
     ));
   }
 
-  for (scheme, protocol) in uri_scheme_protocols {
-    webview_builder =
-      webview_builder.with_asynchronous_custom_protocol(scheme, move |request, responder| {
+  for (scheme, protocol) in &recipe.uri_scheme_protocols {
+    let protocol = protocol.clone();
+    webview_builder = webview_builder.with_asynchronous_custom_protocol(
+      scheme.clone(),
+      move |request, responder| {
         protocol(
           request,
           Box::new(move |response| responder.respond(response)),
         )
-      });
+      },
+    );
+  }
+
+  // webkit2gtk has a native setting, applied once the webview is created
+  #[cfg(not(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  )))]
+  if let Some(enabled) = webview_attributes.spellcheck {
+    webview_builder = webview_builder.with_initialization_script(&spellcheck_script(enabled));
   }
 
   for script in webview_attributes.initialization_scripts {
@@ -3511,18 +4834,58 @@ fn create_webview<T: UserEvent>(
     .expect("poisoned WebContext store");
   let is_first_context = web_context.is_empty();
   let automation_enabled = std::env::var("TAURI_WEBVIEW_AUTOMATION").as_deref() == Ok("true");
+  // webviews on the same shared session use the same WebContext, which lives as long as the app
+  let shared_session = webview_attributes
+    .session
+    .as_ref()
+    .and_then(|session| session.id())
+    .map(|id| PathBuf::from(format!("session:{id}")));
   let web_context_key = // force a unique WebContext when automation is false;
     // the context must be stored on the HashMap because it must outlive the WebView on macOS
-    if automation_enabled {
+    if shared_session.is_some() {
+      shared_session.clone()
+    } else if automation_enabled {
       webview_attributes.data_directory.clone()
     } else {
       // unique key
       let key = context.next_webcontext_id().to_string().into();
       Some(key)
     };
+  // the custom protocols are registered on the WebContext, so webkit2gtk would route
+  // the IPC and asset requests of every webview of the session to a single webview
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  if let Some(key) = &shared_session {
+    let in_use = SESSION_WEBVIEWS.with(|webviews| {
+      webviews
+        .borrow()
+        .get(key)
+        .is_some_and(|(webview_id, _)| *webview_id != id)
+    });
+    if in_use {
+      return Err(Error::InvalidSession(
+        "a shared session can only be used by one webview at a time on Linux".into(),
+      ));
+    }
+  }
+
   let entry = web_context.entry(web_context_key.clone());
   let web_context = match entry {
-    Occupied(occupied) => occupied.into_mut(),
+    Occupied(occupied) => {
+      if shared_session.is_some()
+        && occupied.get().data_directory() != webview_attributes.data_directory.as_deref()
+      {
+        return Err(Error::InvalidSession(
+          "webviews sharing a session must use the same data directory".into(),
+        ));
+      }
+      occupied.into_mut()
+    }
     Vacant(vacant) => {
       let mut web_context = WebContext::new(webview_attributes.data_directory);
       web_context.set_allows_automation(if automation_enabled {
@@ -3538,7 +4901,12 @@ fn create_webview<T: UserEvent>(
     webview_builder.attrs.clipboard = true;
   }
 
-  if webview_attributes.incognito {
+  if webview_attributes.incognito
+    || webview_attributes
+      .session
+      .as_ref()
+      .is_some_and(|session| session.is_ephemeral())
+  {
     webview_builder.attrs.incognito = true;
   }
 
@@ -3547,9 +4915,28 @@ fn create_webview<T: UserEvent>(
     webview_builder = webview_builder.with_devtools(true);
   }
 
+  #[cfg(all(
+    feature = "devtools-protocol",
+    any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd"
+    )
+  ))]
+  if let Some(port) = webview_attributes.remote_debugging_port {
+    // read by webkit2gtk when it spawns the web process of the first webview
+    std::env::set_var("WEBKIT_INSPECTOR_SERVER", format!("127.0.0.1:{port}"));
+  }
+  #[cfg(all(feature = "devtools-protocol", target_os = "macos"))]
+  if webview_attributes.remote_debugging_port.is_some() {
+    debug_eprintln!("the remote debugging server is not supported on macOS");
+  }
+
   #[cfg(target_os = "android")]
   {
-    if let Some(on_webview_created) = pending.on_webview_created {
+    if let Some(on_webview_created) = recipe.on_webview_created.borrow_mut().take() {
       webview_builder = webview_builder.on_webview_created(move |ctx| {
         on_webview_created(tauri_runtime::webview::CreationContext {
           env: ctx.env,
@@ -3565,17 +4952,75 @@ fn create_webview<T: UserEvent>(
     .build()
     .map_err(|e| Error::CreateWebview(Box::new(e)))?;
 
+  // wry only injects scripts in the page world, the initial page is loaded asynchronously so it still gets these
+  for (world, script) in &webview_attributes.isolated_initialization_scripts {
+    if let Err(e) = add_webview_initialization_script(&webview, script, Some(world)) {
+      debug_eprintln!("failed to add the initialization script: {}", e);
+    }
+  }
+
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  if let Some(enabled) = webview_attributes.spellcheck {
+    set_webview_spellcheck(&webview, enabled);
+  }
+  if let Some(suffix) = user_agent_suffix {
+    append_webview_user_agent(&webview, &suffix);
+  }
+  #[cfg(all(
+    feature = "devtools-protocol",
+    any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd"
+    )
+  ))]
+  if webview_attributes.remote_debugging_port.is_some() {
+    use webkit2gtk::{SettingsExt, WebViewExt};
+
+    // the inspector server only lists the webviews with the developer extras
+    if let Some(settings) = webview.webview().settings() {
+      settings.set_enable_developer_extras(true);
+    }
+  }
+
+  // the policy is applied with the browser arguments on Windows
+  #[cfg(not(windows))]
+  if let Some(policy) = webview_attributes.background_throttling {
+    #[cfg(target_os = "macos")]
+    if inactive_scheduling_policy_supported() {
+      set_webview_background_throttling(&webview, policy);
+    } else {
+      debug_eprintln!("background throttling policies require macOS 14+");
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    debug_eprintln!(
+      "background throttling policies are not supported on this platform, ignoring {:?}",
+      policy
+    );
+  }
+
   #[cfg(windows)]
   {
     let controller = webview.controller();
     let proxy = context.proxy.clone();
     let proxy_ = proxy.clone();
+    let window_id_ = window_id.clone();
+    let window_id__ = window_id.clone();
     let mut token = EventRegistrationToken::default();
     unsafe {
       controller.add_GotFocus(
         &FocusChangedEventHandler::create(Box::new(move |_, _| {
           let _ = proxy.send_event(Message::Webview(
-            window_id,
+            *window_id_.lock().unwrap(),
             id,
             WebviewMessage::WebviewEvent(WebviewEvent::Focused(true)),
           ));
@@ -3589,7 +5034,7 @@ fn create_webview<T: UserEvent>(
       controller.add_LostFocus(
         &FocusChangedEventHandler::create(Box::new(move |_, _| {
           let _ = proxy_.send_event(Message::Webview(
-            window_id,
+            *window_id__.lock().unwrap(),
             id,
             WebviewMessage::WebviewEvent(WebviewEvent::Focused(false)),
           ));
@@ -3601,33 +5046,2584 @@ fn create_webview<T: UserEvent>(
     .unwrap();
   }
 
-  Ok(WebviewWrapper {
+  #[cfg(windows)]
+  if let Ok(core_webview) = unsafe { webview.controller().CoreWebView2() }
+    .and_then(|webview| webview.cast::<ICoreWebView2_8>())
+  {
+    let proxy = context.proxy.clone();
+    let window_id = window_id.clone();
+    let mut token = EventRegistrationToken::default();
+    let res = unsafe {
+      core_webview.add_IsDocumentPlayingAudioChanged(
+        &IsDocumentPlayingAudioChangedEventHandler::create(Box::new(move |webview, _| {
+          if let Some(webview) = webview.and_then(|w| w.cast::<ICoreWebView2_8>().ok()) {
+            let mut playing = BOOL::default();
+            webview.IsDocumentPlayingAudio(&mut playing)?;
+            let _ = proxy.send_event(Message::Webview(
+              *window_id.lock().unwrap(),
+              id,
+              WebviewMessage::WebviewEvent(WebviewEvent::MediaPlaybackChanged(playing.as_bool())),
+            ));
+          }
+          Ok(())
+        })),
+        &mut token,
+      )
+    };
+    if let Err(e) = res {
+      debug_eprintln!("failed to listen to the webview audio playback: {}", e);
+    }
+  }
+
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  {
+    use webkit2gtk::WebViewExt;
+    use wry::WebViewExtUnix;
+
+    let proxy = context.proxy.clone();
+    let window_id = window_id.clone();
+    webview
+      .webview()
+      .connect_is_playing_audio_notify(move |webview| {
+        let _ = proxy.send_event(Message::Webview(
+          *window_id.lock().unwrap(),
+          id,
+          WebviewMessage::WebviewEvent(WebviewEvent::MediaPlaybackChanged(
+            webview.is_playing_audio(),
+          )),
+        ));
+      });
+  }
+
+  if let Some(context_menu_handler) = recipe.context_menu_handler.clone() {
+    set_context_menu_handler(&webview, context_menu_handler);
+  }
+
+  if let Some(auth_challenge_handler) = recipe.auth_challenge_handler.clone() {
+    set_auth_challenge_handler(&webview, auth_challenge_handler);
+  }
+
+  if let Some(cert_error_handler) = recipe.cert_error_handler.clone() {
+    set_cert_error_handler(&webview, cert_error_handler);
+  }
+
+  if let Some(request_observer) = recipe.request_observer.clone() {
+    let protocols = recipe
+      .uri_scheme_protocols
+      .iter()
+      .map(|(scheme, _)| scheme.clone())
+      .collect();
+    set_request_observer(&webview, protocols, request_observer);
+  }
+
+  {
+    let proxy = context.proxy.clone();
+    let window_id = window_id.clone();
+    set_process_crashed_handler(
+      &webview,
+      Box::new(move |reason| {
+        let _ = proxy.send_event(Message::Webview(
+          *window_id.lock().unwrap(),
+          id,
+          WebviewMessage::WebviewEvent(WebviewEvent::ProcessCrashed(reason)),
+        ));
+      }),
+    );
+  }
+
+  if webview_attributes.ignore_cursor_events {
+    set_webview_ignore_cursor_events(&webview, true);
+  }
+
+  let bounds = webview.bounds();
+  let size = LogicalSize::new(bounds.width, bounds.height).to_physical(window.scale_factor());
+
+  let wrapper = WebviewWrapper {
     id,
+    window_id: window_id.clone(),
     inner: Rc::new(webview),
     context_store: context.main_thread.web_context.clone(),
-    context_key: if automation_enabled {
+    context_key: if automation_enabled || shared_session.is_some() {
       None
     } else {
       web_context_key
     },
     bounds: webview_bounds.map(|b| Arc::new(Mutex::new(b))),
-  })
-}
+    size: Rc::new(Cell::new(size)),
+    theme: Default::default(),
+    ignore_cursor_events: Rc::new(Cell::new(webview_attributes.ignore_cursor_events)),
+    kind,
+    content_insets: Default::default(),
+    webview_event_listeners: Default::default(),
+    recreate: None,
+    initialization_scripts: Default::default(),
+  };
+  if webview_attributes.content_insets != Insets::default() {
+    set_webview_content_insets(&wrapper, webview_attributes.content_insets);
+  }
+
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  if let Some(key) = shared_session {
+    // replaces the previous webview when it is recovered
+    let webview = Rc::as_ptr(&wrapper.inner) as usize;
+    SESSION_WEBVIEWS.with(|webviews| webviews.borrow_mut().insert(key, (id, webview)));
+  }
+
+  Ok(wrapper)
+}
+
+/// Insets the native webview from its bounds, see [`WebviewDispatch::set_content_insets`].
+fn set_webview_content_insets(webview: &WebviewWrapper, insets: Insets) {
+  match webview.kind {
+    WebviewKind::WindowChild => {
+      let bounds = webview.outer_bounds();
+      webview.content_insets.set(insets);
+      webview.set_outer_bounds(bounds);
+    }
+    #[cfg(target_os = "macos")]
+    WebviewKind::WindowContent => unsafe {
+      use cocoa::{
+        appkit::{NSViewHeightSizable, NSViewWidthSizable},
+        base::id,
+        foundation::{NSPoint, NSRect, NSSize},
+      };
+      use objc::{msg_send, sel, sel_impl};
+      use wry::WebViewExtMacOS;
+
+      webview.content_insets.set(insets);
+      let (top, left, bottom, right) = inset_lengths(insets);
+      let (top, left, bottom, right) = (top as f64, left as f64, bottom as f64, right as f64);
+      let ns_webview = webview.webview();
+      let superview: id = msg_send![ns_webview, superview];
+      let superview_bounds: NSRect = msg_send![superview, bounds];
+      // the superview is not flipped, the origin is the bottom-left corner
+      let frame = NSRect::new(
+        NSPoint::new(left, bottom),
+        NSSize::new(
+          (superview_bounds.size.width - left - right).max(0.0),
+          (superview_bounds.size.height - top - bottom).max(0.0),
+        ),
+      );
+      let () = msg_send![ns_webview, setFrame: frame];
+      // keeps the insets when the window is resized
+      let () = msg_send![ns_webview, setAutoresizingMask: NSViewWidthSizable | NSViewHeightSizable];
+    },
+    // the content webview fills the window on the other platforms
+    #[cfg(not(target_os = "macos"))]
+    WebviewKind::WindowContent => {}
+  }
+}
+
+/// Sets the color scheme preferred by the webview content.
+fn set_webview_theme(webview: &WebviewWrapper, theme: Option<Theme>) {
+  #[cfg(windows)]
+  {
+    use wry::WebViewExtWindows;
+    webview.set_theme(match theme {
+      Some(Theme::Dark) => wry::Theme::Dark,
+      Some(Theme::Light) => wry::Theme::Light,
+      _ => wry::Theme::Auto,
+    });
+  }
+
+  #[cfg(target_os = "macos")]
+  unsafe {
+    use cocoa::{
+      appkit::NSAppearance,
+      base::{id, nil},
+      foundation::NSString,
+    };
+    use objc::{msg_send, sel, sel_impl};
+    use wry::WebViewExtMacOS;
+
+    let appearance: id = match theme {
+      Some(Theme::Dark) => NSAppearance(NSString::alloc(nil).init_str("NSAppearanceNameDarkAqua")),
+      Some(Theme::Light) => NSAppearance(NSString::alloc(nil).init_str("NSAppearanceNameAqua")),
+      _ => nil,
+    };
+    let _: () = msg_send![webview.webview(), setAppearance: appearance];
+  }
+
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  FORCED_THEMES.with(|themes| {
+    let mut themes = themes.borrow_mut();
+    themes.webviews.retain(|(id, _)| *id != webview.id);
+    if let Some(theme) = theme {
+      themes.webviews.push((webview.id, theme == Theme::Dark));
+    }
+    themes.apply();
+  });
+
+  #[cfg(any(target_os = "ios", target_os = "android"))]
+  let _ = (webview, theme);
+}
+
+/// The themes forced with [`set_webview_theme`] on webkit2gtk.
+///
+/// The GTK dark theme preference applies to the whole application,
+/// so the theme forced last wins and the system preference is restored once no webview forces a theme.
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+))]
+#[derive(Default)]
+struct ForcedThemes {
+  /// The preference of the system, saved when the first theme is forced.
+  system_prefers_dark: Option<bool>,
+  /// Whether the webviews prefer the dark theme, in the order their theme was forced.
+  webviews: Vec<(WebviewId, bool)>,
+}
+
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+))]
+impl ForcedThemes {
+  fn apply(&mut self) {
+    use gtk::prelude::GtkSettingsExt;
+
+    let Some(settings) = gtk::Settings::default() else {
+      return;
+    };
+    match self.webviews.last() {
+      Some((_, prefers_dark)) => {
+        if self.system_prefers_dark.is_none() {
+          self.system_prefers_dark = Some(settings.is_gtk_application_prefer_dark_theme());
+        }
+        settings.set_gtk_application_prefer_dark_theme(*prefers_dark);
+      }
+      None => {
+        if let Some(prefers_dark) = self.system_prefers_dark.take() {
+          settings.set_gtk_application_prefer_dark_theme(prefers_dark);
+        }
+      }
+    }
+  }
+}
+
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+))]
+thread_local! {
+  static FORCED_THEMES: RefCell<ForcedThemes> = Default::default();
+}
+
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+))]
+thread_local! {
+  /// The webview using each shared session, keyed by the WebContext key of the session,
+  /// with the pointer of its [`WebviewWrapper::inner`] webview.
+  static SESSION_WEBVIEWS: RefCell<HashMap<PathBuf, (WebviewId, usize)>> = Default::default();
+}
+
+#[cfg(target_os = "macos")]
+thread_local! {
+  /// The webviews ignoring cursor events, keyed by the `WKWebView` pointer.
+  static IGNORED_CURSOR_EVENTS: RefCell<std::collections::HashSet<usize>> = Default::default();
+}
+
+/// Sets whether mouse and touch input goes through the webview to the content beneath it,
+/// without affecting its keyboard focus.
+fn set_webview_ignore_cursor_events(webview: &WebView, ignore: bool) {
+  // the webview container is click-through when it is a transparent layered window
+  #[cfg(windows)]
+  unsafe {
+    use windows::Win32::{
+      Foundation::COLORREF,
+      UI::WindowsAndMessaging::{
+        GetWindowLongW, SetLayeredWindowAttributes, SetWindowLongW, GWL_EXSTYLE, GWL_STYLE,
+        LWA_ALPHA, WS_CHILD, WS_EX_LAYERED, WS_EX_TRANSPARENT,
+      },
+    };
+
+    let mut hwnd = HWND::default();
+    if let Err(e) = webview.controller().ParentWindow(&mut hwnd) {
+      debug_eprintln!("failed to get the webview window: {}", e);
+      return;
+    }
+    // the content webview is hosted by the window itself, which is managed by tao
+    if GetWindowLongW(hwnd, GWL_STYLE) as u32 & WS_CHILD.0 == 0 {
+      debug_eprintln!("only child webviews can ignore cursor events on Windows");
+      return;
+    }
+
+    let style = GetWindowLongW(hwnd, GWL_EXSTYLE) as u32;
+    let flags = WS_EX_LAYERED.0 | WS_EX_TRANSPARENT.0;
+    let style = if ignore {
+      style | flags
+    } else {
+      style & !flags
+    };
+    SetWindowLongW(hwnd, GWL_EXSTYLE, style as i32);
+    if ignore {
+      // layered windows are not drawn until their attributes are set
+      let _ = SetLayeredWindowAttributes(hwnd, COLORREF(0), 255, LWA_ALPHA);
+    }
+  }
+
+  #[cfg(target_os = "macos")]
+  unsafe {
+    use objc::{
+      runtime::{class_addMethod, object_getClass, Class, Object, Sel},
+      sel, sel_impl,
+    };
+    use wry::WebViewExtMacOS;
+
+    let webview = webview.webview();
+    if ignore {
+      // adding the method fails if the webview class already has it, which is fine
+      class_addMethod(
+        object_getClass(webview as *const Object) as *mut Class,
+        sel!(hitTest:),
+        std::mem::transmute::<
+          extern "C" fn(&Object, Sel, cocoa::foundation::NSPoint) -> cocoa::base::id,
+          objc::runtime::Imp,
+        >(webview_hit_test),
+        b"@@:{CGPoint=dd}\0".as_ptr() as *const std::os::raw::c_char,
+      );
+    }
+    IGNORED_CURSOR_EVENTS.with(|webviews| {
+      let mut webviews = webviews.borrow_mut();
+      if ignore {
+        webviews.insert(webview as usize);
+      } else {
+        webviews.remove(&(webview as usize));
+      }
+    });
+  }
+
+  // an empty input shape lets the events reach the widgets beneath the webview
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  {
+    use gtk::prelude::WidgetExt;
+    use wry::WebViewExtUnix;
+
+    let region = ignore.then(cairo::Region::create);
+    webview
+      .webview()
+      .input_shape_combine_region(region.as_ref());
+  }
+
+  #[cfg(any(target_os = "ios", target_os = "android"))]
+  let _ = (webview, ignore);
+}
+
+/// The `hitTest:` implementation added to the webview class, see [`set_webview_ignore_cursor_events`].
+#[cfg(target_os = "macos")]
+extern "C" fn webview_hit_test(
+  this: &objc::runtime::Object,
+  _cmd: objc::runtime::Sel,
+  point: cocoa::foundation::NSPoint,
+) -> cocoa::base::id {
+  use cocoa::base::nil;
+  use objc::{class, msg_send, sel, sel_impl};
+
+  let ignored =
+    IGNORED_CURSOR_EVENTS.with(|webviews| webviews.borrow().contains(&(this as *const _ as usize)));
+  if ignored {
+    nil
+  } else {
+    unsafe { msg_send![super(this, class!(WKWebView)), hitTest: point] }
+  }
+}
+
+/// Whether `WKPreferences.inactiveSchedulingPolicy` is available (macOS 14+).
+#[cfg(target_os = "macos")]
+fn inactive_scheduling_policy_supported() -> bool {
+  use objc::{
+    class, msg_send,
+    runtime::{BOOL, YES},
+    sel, sel_impl,
+  };
+
+  let supported: BOOL = unsafe {
+    msg_send![class!(WKPreferences), instancesRespondToSelector: sel!(setInactiveSchedulingPolicy:)]
+  };
+  supported == YES
+}
+
+/// Sets the `WKInactiveSchedulingPolicy` of the webview.
+#[cfg(target_os = "macos")]
+fn set_webview_background_throttling(webview: &WebView, policy: BackgroundThrottlingPolicy) {
+  use cocoa::base::id;
+  use objc::{msg_send, sel, sel_impl};
+  use wry::WebViewExtMacOS;
+
+  // WKInactiveSchedulingPolicy values
+  let policy: isize = match policy {
+    BackgroundThrottlingPolicy::Suspend => 0,
+    BackgroundThrottlingPolicy::Throttle => 1,
+    BackgroundThrottlingPolicy::Disabled => 2,
+  };
+  unsafe {
+    let configuration: id = msg_send![webview.webview(), configuration];
+    let preferences: id = msg_send![configuration, preferences];
+    let _: () = msg_send![preferences, setInactiveSchedulingPolicy: policy];
+  }
+}
+
+type ContextMenuHandler = Rc<dyn Fn(ContextMenuRequest) -> bool + Send>;
+
+#[cfg(target_os = "macos")]
+thread_local! {
+  /// The context menu handlers of the webviews, keyed by the `WKWebView` pointer.
+  static CONTEXT_MENU_HANDLERS: RefCell<HashMap<usize, ContextMenuHandler>> = Default::default();
+}
+
+/// Calls the handler when the webview requests a context menu, hiding the default menu if it returns `false`.
+fn set_context_menu_handler(webview: &WebView, handler: ContextMenuHandler) {
+  #[cfg(windows)]
+  {
+    use webview2_com::{
+      take_pwstr, ContextMenuRequestedEventHandler,
+      Microsoft::Web::WebView2::Win32::{
+        ICoreWebView2_11, COREWEBVIEW2_CONTEXT_MENU_TARGET_KIND,
+        COREWEBVIEW2_CONTEXT_MENU_TARGET_KIND_IMAGE,
+      },
+    };
+    use windows::{core::PWSTR, Win32::Foundation::POINT};
+    use wry::WebViewExtWindows;
+
+    let res = unsafe { webview.controller().CoreWebView2() }
+      .and_then(|webview| webview.cast::<ICoreWebView2_11>())
+      .and_then(|webview| unsafe {
+        let mut token = EventRegistrationToken::default();
+        webview.add_ContextMenuRequested(
+          &ContextMenuRequestedEventHandler::create(Box::new(move |_, args| {
+            let Some(args) = args else {
+              return Ok(());
+            };
+
+            let mut location = POINT::default();
+            args.Location(&mut location)?;
+            let mut request =
+              ContextMenuRequest::new(PhysicalPosition::new(location.x, location.y).into());
+
+            let target = args.ContextMenuTarget()?;
+            let mut flag = BOOL::default();
+            let mut value = PWSTR::null();
+            target.HasLinkUri(&mut flag)?;
+            if flag.as_bool() {
+              target.LinkUri(&mut value)?;
+              request.link_url = Some(take_pwstr(value));
+            }
+            let mut kind = COREWEBVIEW2_CONTEXT_MENU_TARGET_KIND::default();
+            target.Kind(&mut kind)?;
+            target.HasSourceUri(&mut flag)?;
+            if kind == COREWEBVIEW2_CONTEXT_MENU_TARGET_KIND_IMAGE && flag.as_bool() {
+              target.SourceUri(&mut value)?;
+              request.image_src = Some(take_pwstr(value));
+            }
+            target.HasSelection(&mut flag)?;
+            if flag.as_bool() {
+              target.SelectionText(&mut value)?;
+              request.selected_text = Some(take_pwstr(value));
+            }
+            target.IsEditable(&mut flag)?;
+            request.is_editable = flag.as_bool();
+
+            if !handler(request) {
+              args.SetHandled(true)?;
+            }
+            Ok(())
+          })),
+          &mut token,
+        )
+      });
+    if let Err(e) = res {
+      debug_eprintln!(
+        "failed to listen to the webview context menu requests: {}",
+        e
+      );
+    }
+  }
+
+  #[cfg(target_os = "macos")]
+  unsafe {
+    use cocoa::base::id;
+    use objc::{
+      runtime::{class_addMethod, object_getClass, Class, Object, Sel},
+      sel, sel_impl,
+    };
+    use wry::WebViewExtMacOS;
+
+    let webview = webview.webview();
+    // adding the method fails if the webview class already has it, which is fine
+    class_addMethod(
+      object_getClass(webview as *const Object) as *mut Class,
+      sel!(willOpenMenu:withEvent:),
+      std::mem::transmute::<extern "C" fn(&Object, Sel, id, id), objc::runtime::Imp>(
+        webview_will_open_menu,
+      ),
+      b"v@:@@\0".as_ptr() as *const std::os::raw::c_char,
+    );
+    CONTEXT_MENU_HANDLERS.with(|handlers| {
+      handlers.borrow_mut().insert(webview as usize, handler);
+    });
+  }
+
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  {
+    use webkit2gtk::{HitTestResultExt, WebViewExt};
+    use wry::WebViewExtUnix;
+
+    webview
+      .webview()
+      .connect_context_menu(move |_, _, event, hit_test| {
+        // the menu can also be requested with the keyboard, without coordinates
+        let (x, y) = event.coords().unwrap_or_default();
+        let mut request = ContextMenuRequest::new(LogicalPosition::new(x, y).into());
+        request.link_url = hit_test.link_uri().map(Into::into);
+        if hit_test.context_is_image() {
+          request.image_src = hit_test.image_uri().map(Into::into);
+        }
+        request.is_editable = hit_test.context_is_editable();
+        // returning true hides the default menu
+        !handler(request)
+      });
+  }
+
+  #[cfg(any(target_os = "ios", target_os = "android"))]
+  let _ = (webview, handler);
+}
+
+/// The `willOpenMenu:withEvent:` implementation added to the webview class, see [`set_context_menu_handler`].
+#[cfg(target_os = "macos")]
+extern "C" fn webview_will_open_menu(
+  this: &objc::runtime::Object,
+  _cmd: objc::runtime::Sel,
+  menu: cocoa::base::id,
+  event: cocoa::base::id,
+) {
+  use cocoa::{
+    base::nil,
+    foundation::{NSPoint, NSRect},
+  };
+  use objc::{
+    class, msg_send,
+    runtime::{BOOL, YES},
+    sel, sel_impl,
+  };
+
+  unsafe {
+    let _: () = msg_send![super(this, class!(WKWebView)), willOpenMenu: menu withEvent: event];
+
+    let show_default = CONTEXT_MENU_HANDLERS.with(|handlers| {
+      handlers
+        .borrow()
+        .get(&(this as *const _ as usize))
+        .map(|handler| {
+          let location: NSPoint = msg_send![event, locationInWindow];
+          let point: NSPoint = msg_send![this, convertPoint: location fromView: nil];
+          let flipped: BOOL = msg_send![this, isFlipped];
+          let y = if flipped == YES {
+            point.y
+          } else {
+            let bounds: NSRect = msg_send![this, bounds];
+            bounds.size.height - point.y
+          };
+          handler(ContextMenuRequest::new(
+            LogicalPosition::new(point.x, y).into(),
+          ))
+        })
+        .unwrap_or(true)
+    });
+    if !show_default {
+      // an empty menu is not shown
+      let _: () = msg_send![menu, removeAllItems];
+    }
+  }
+}
+
+type AuthChallengeHandler = Rc<dyn Fn(AuthChallenge) -> AuthResponse + Send>;
+
+#[cfg(target_os = "macos")]
+thread_local! {
+  /// The authentication challenge handlers of the webviews, keyed by the `WKWebView` pointer.
+  static AUTH_CHALLENGE_HANDLERS: RefCell<HashMap<usize, AuthChallengeHandler>> = Default::default();
+}
+
+/// Counts the rejected credentials per host on the webviews that do not report them.
+///
+/// A new challenge for a host we answered with credentials means they were rejected.
+#[cfg(not(any(target_os = "macos", target_os = "ios", target_os = "android")))]
+#[derive(Default)]
+struct AuthFailures(HashMap<String, u32>);
+
+#[cfg(not(any(target_os = "macos", target_os = "ios", target_os = "android")))]
+impl AuthFailures {
+  fn get(&self, host: &str) -> u32 {
+    self.0.get(host).copied().unwrap_or_default()
+  }
+
+  fn record(&mut self, host: &str, response: &AuthResponse) {
+    if let AuthResponse::Credentials { .. } = response {
+      *self.0.entry(host.to_string()).or_default() += 1;
+    } else {
+      self.0.remove(host);
+    }
+  }
+}
+
+/// Reads the realm of a `WWW-Authenticate` challenge, e.g. `Basic realm="Admin"`.
+#[cfg(windows)]
+fn parse_auth_realm(challenge: &str) -> Option<String> {
+  let (_, rest) = challenge.split_once("realm=")?;
+  let realm = match rest.strip_prefix('"') {
+    Some(quoted) => quoted.split('"').next()?,
+    None => rest.split(',').next()?.trim(),
+  };
+  Some(realm.to_string())
+}
+
+/// Calls the handler when a page requests HTTP authentication.
+fn set_auth_challenge_handler(webview: &WebView, handler: AuthChallengeHandler) {
+  #[cfg(windows)]
+  {
+    use webview2_com::{
+      take_pwstr, BasicAuthenticationRequestedEventHandler,
+      Microsoft::Web::WebView2::Win32::ICoreWebView2_10,
+    };
+    use windows::core::{HSTRING, PWSTR};
+
+    let mut failures = AuthFailures::default();
+    let res = unsafe { webview.controller().CoreWebView2() }
+      .and_then(|webview| webview.cast::<ICoreWebView2_10>())
+      .and_then(|webview| unsafe {
+        let mut token = EventRegistrationToken::default();
+        webview.add_BasicAuthenticationRequested(
+          &BasicAuthenticationRequestedEventHandler::create(Box::new(move |_, args| {
+            let Some(args) = args else {
+              return Ok(());
+            };
+
+            let mut value = PWSTR::null();
+            args.Uri(&mut value)?;
+            let host = Url::parse(&take_pwstr(value))
+              .ok()
+              .and_then(|url| url.host_str().map(String::from))
+              .unwrap_or_default();
+            let mut challenge = AuthChallenge::new(host);
+            args.Challenge(&mut value)?;
+            challenge.realm = parse_auth_realm(&take_pwstr(value));
+            challenge.previous_failures = failures.get(&challenge.host);
+
+            let host = challenge.host.clone();
+            let response = handler(challenge);
+            failures.record(&host, &response);
+            match response {
+              AuthResponse::Credentials { username, password } => {
+                let response = args.Response()?;
+                response.SetUserName(&HSTRING::from(username))?;
+                response.SetPassword(&HSTRING::from(password))?;
+              }
+              AuthResponse::Cancel => args.SetCancel(true)?,
+              // WebView2 shows its login dialog
+              _ => (),
+            }
+            Ok(())
+          })),
+          &mut token,
+        )
+      });
+    if let Err(e) = res {
+      debug_eprintln!(
+        "failed to listen to the webview authentication requests: {}",
+        e
+      );
+    }
+  }
+
+  #[cfg(target_os = "macos")]
+  {
+    use wry::WebViewExtMacOS;
+
+    let webview = webview.webview();
+    add_auth_challenge_method(webview);
+    AUTH_CHALLENGE_HANDLERS.with(|handlers| {
+      handlers.borrow_mut().insert(webview as usize, handler);
+    });
+  }
+
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  {
+    use gtk::glib::{translate::ToGlibPtrMut, ObjectType};
+    use webkit2gtk::{AuthenticationRequestExt, Credential, CredentialPersistence, WebViewExt};
+    use wry::WebViewExtUnix;
+
+    let failures = RefCell::new(AuthFailures::default());
+    webview.webview().connect_authenticate(move |_, request| {
+      let mut challenge = AuthChallenge::new(request.host().map(String::from).unwrap_or_default());
+      challenge.realm = request.realm().map(Into::into);
+      challenge.is_proxy = request.is_for_proxy();
+
+      let mut failures = failures.borrow_mut();
+      if !request.is_retry() {
+        failures.record(&challenge.host, &AuthResponse::Default);
+      }
+      challenge.previous_failures = failures.get(&challenge.host);
+
+      let host = challenge.host.clone();
+      let response = handler(challenge);
+      failures.record(&host, &response);
+      match response {
+        AuthResponse::Credentials { username, password } => {
+          let mut credential =
+            Credential::new(&username, &password, CredentialPersistence::ForSession);
+          unsafe {
+            webkit2gtk::ffi::webkit_authentication_request_authenticate(
+              request.as_ptr(),
+              credential.to_glib_none_mut().0,
+            );
+          }
+          true
+        }
+        AuthResponse::Cancel => {
+          request.cancel();
+          true
+        }
+        // webkit2gtk shows its login dialog
+        _ => false,
+      }
+    });
+  }
+
+  #[cfg(any(target_os = "ios", target_os = "android"))]
+  let _ = (webview, handler);
+}
+
+type CertErrorHandler = Rc<dyn Fn(CertError) -> CertDecision + Send>;
+
+#[cfg(target_os = "macos")]
+thread_local! {
+  /// The certificate error handlers of the webviews, keyed by the `WKWebView` pointer.
+  static CERT_ERROR_HANDLERS: RefCell<HashMap<usize, CertErrorHandler>> = Default::default();
+}
+
+/// Decodes the first certificate of a PEM document.
+#[cfg(windows)]
+fn pem_to_der(pem: &str) -> Vec<u8> {
+  use base64::Engine;
+
+  let body = pem
+    .lines()
+    .skip_while(|line| !line.starts_with("-----BEGIN"))
+    .skip(1)
+    .take_while(|line| !line.starts_with("-----END"))
+    .collect::<String>();
+  base64::engine::general_purpose::STANDARD
+    .decode(body)
+    .unwrap_or_default()
+}
+
+/// Calls the handler when the webview rejects the certificate of a server.
+fn set_cert_error_handler(webview: &WebView, handler: CertErrorHandler) {
+  #[cfg(windows)]
+  {
+    use webview2_com::{
+      take_pwstr,
+      Microsoft::Web::WebView2::Win32::{
+        ICoreWebView2_14, COREWEBVIEW2_SERVER_CERTIFICATE_ERROR_ACTION_ALWAYS_ALLOW,
+        COREWEBVIEW2_SERVER_CERTIFICATE_ERROR_ACTION_DEFAULT, COREWEBVIEW2_WEB_ERROR_STATUS,
+        COREWEBVIEW2_WEB_ERROR_STATUS_CERTIFICATE_COMMON_NAME_IS_INCORRECT,
+        COREWEBVIEW2_WEB_ERROR_STATUS_CERTIFICATE_EXPIRED,
+        COREWEBVIEW2_WEB_ERROR_STATUS_CERTIFICATE_REVOKED,
+      },
+      ServerCertificateErrorDetectedEventHandler,
+    };
+    use windows::core::PWSTR;
+
+    let res = unsafe { webview.controller().CoreWebView2() }
+      .and_then(|webview| webview.cast::<ICoreWebView2_14>())
+      .and_then(|webview| unsafe {
+        let mut token = EventRegistrationToken::default();
+        webview.add_ServerCertificateErrorDetected(
+          &ServerCertificateErrorDetectedEventHandler::create(Box::new(move |_, args| {
+            let Some(args) = args else {
+              return Ok(());
+            };
+
+            let mut value = PWSTR::null();
+            args.RequestUri(&mut value)?;
+            let Ok(url) = Url::parse(&take_pwstr(value)) else {
+              return Ok(());
+            };
+            let mut status = COREWEBVIEW2_WEB_ERROR_STATUS::default();
+            args.ErrorStatus(&mut status)?;
+            let error = match status {
+              COREWEBVIEW2_WEB_ERROR_STATUS_CERTIFICATE_COMMON_NAME_IS_INCORRECT => {
+                CertErrorKind::HostMismatch
+              }
+              COREWEBVIEW2_WEB_ERROR_STATUS_CERTIFICATE_EXPIRED => CertErrorKind::Expired,
+              COREWEBVIEW2_WEB_ERROR_STATUS_CERTIFICATE_REVOKED => CertErrorKind::Revoked,
+              _ => CertErrorKind::Invalid,
+            };
+            args.ServerCertificate()?.ToPemEncoding(&mut value)?;
+            let certificate_der = pem_to_der(&take_pwstr(value));
+
+            let action = match handler(CertError::new(url, certificate_der, error)) {
+              CertDecision::Deny => COREWEBVIEW2_SERVER_CERTIFICATE_ERROR_ACTION_DEFAULT,
+              // WebView2 remembers the decision for the host until the webview is closed
+              _ => COREWEBVIEW2_SERVER_CERTIFICATE_ERROR_ACTION_ALWAYS_ALLOW,
+            };
+            args.SetAction(action)?;
+            Ok(())
+          })),
+          &mut token,
+        )
+      });
+    if let Err(e) = res {
+      debug_eprintln!("failed to listen to the webview certificate errors: {}", e);
+    }
+  }
+
+  #[cfg(target_os = "macos")]
+  {
+    use wry::WebViewExtMacOS;
+
+    let webview = webview.webview();
+    add_auth_challenge_method(webview);
+    CERT_ERROR_HANDLERS.with(|handlers| {
+      handlers.borrow_mut().insert(webview as usize, handler);
+    });
+  }
+
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  {
+    use gtk::gio::{prelude::TlsCertificateExt, TlsCertificateFlags};
+    use webkit2gtk::{WebContextExt, WebViewExt};
+    use wry::WebViewExtUnix;
+
+    webview.webview().connect_load_failed_with_tls_errors(
+      move |webview, uri, certificate, flags| {
+        let Ok(url) = Url::parse(uri) else {
+          return false;
+        };
+        let error = if flags.contains(TlsCertificateFlags::UNKNOWN_CA) {
+          CertErrorKind::UnknownIssuer
+        } else if flags.contains(TlsCertificateFlags::BAD_IDENTITY) {
+          CertErrorKind::HostMismatch
+        } else if flags
+          .intersects(TlsCertificateFlags::EXPIRED | TlsCertificateFlags::NOT_ACTIVATED)
+        {
+          CertErrorKind::Expired
+        } else if flags.contains(TlsCertificateFlags::REVOKED) {
+          CertErrorKind::Revoked
+        } else {
+          CertErrorKind::Invalid
+        };
+        let certificate_der = certificate
+          .certificate()
+          .map(|bytes| bytes.to_vec())
+          .unwrap_or_default();
+
+        match handler(CertError::new(url.clone(), certificate_der, error)) {
+          // the default error page is shown
+          CertDecision::Deny => false,
+          // webkit2gtk only allows certificates per host
+          _ => match (webview.context(), url.host_str()) {
+            (Some(context), Some(host)) => {
+              context.allow_tls_certificate_for_host(certificate, host);
+              webview.load_uri(uri);
+              true
+            }
+            _ => false,
+          },
+        }
+      },
+    );
+  }
+
+  #[cfg(any(target_os = "ios", target_os = "android"))]
+  let _ = (webview, handler);
+}
+
+/// Reads an `NSString`.
+#[cfg(target_os = "macos")]
+unsafe fn ns_string_to_string(value: cocoa::base::id) -> Option<String> {
+  use objc::{msg_send, sel, sel_impl};
+
+  if value == cocoa::base::nil {
+    return None;
+  }
+  let utf8: *const std::os::raw::c_char = msg_send![value, UTF8String];
+  Some(
+    std::ffi::CStr::from_ptr(utf8)
+      .to_string_lossy()
+      .into_owned(),
+  )
+}
+
+/// Adds [`webview_did_receive_auth_challenge`] to the navigation delegate class of the webview.
+#[cfg(target_os = "macos")]
+fn add_auth_challenge_method(webview: cocoa::base::id) {
+  use cocoa::base::id;
+  use objc::{
+    msg_send,
+    runtime::{class_addMethod, object_getClass, Class, Object, Sel},
+    sel, sel_impl,
+  };
+
+  unsafe {
+    let delegate: id = msg_send![webview, navigationDelegate];
+    // adding the method fails if the delegate class already has it, which is fine
+    class_addMethod(
+      object_getClass(delegate as *const Object) as *mut Class,
+      sel!(webView:didReceiveAuthenticationChallenge:completionHandler:),
+      std::mem::transmute::<extern "C" fn(&Object, Sel, id, id, id), objc::runtime::Imp>(
+        webview_did_receive_auth_challenge,
+      ),
+      b"v@:@@@\0".as_ptr() as *const std::os::raw::c_char,
+    );
+  }
+}
+
+#[cfg(target_os = "macos")]
+#[link(name = "Security", kind = "framework")]
+extern "C" {
+  fn SecTrustEvaluateWithError(
+    trust: *const std::ffi::c_void,
+    error: *mut *const std::ffi::c_void,
+  ) -> bool;
+  fn SecTrustGetCertificateAtIndex(
+    trust: *const std::ffi::c_void,
+    index: isize,
+  ) -> *const std::ffi::c_void;
+  fn SecCertificateCopyData(certificate: *const std::ffi::c_void) -> *const std::ffi::c_void;
+}
+
+#[cfg(target_os = "macos")]
+#[link(name = "CoreFoundation", kind = "framework")]
+extern "C" {
+  fn CFDataGetLength(data: *const std::ffi::c_void) -> isize;
+  fn CFDataGetBytePtr(data: *const std::ffi::c_void) -> *const u8;
+  fn CFErrorGetCode(error: *const std::ffi::c_void) -> isize;
+  fn CFRelease(value: *const std::ffi::c_void);
+}
+
+/// Evaluates the server trust of a challenge, asking the certificate error handler when it is not trusted.
+///
+/// Returns `None` when the certificate is trusted or the webview has no handler.
+#[cfg(target_os = "macos")]
+unsafe fn server_trust_decision(
+  webview: cocoa::base::id,
+  challenge: cocoa::base::id,
+) -> Option<(CertDecision, cocoa::base::id)> {
+  use cocoa::base::id;
+  use objc::{msg_send, sel, sel_impl};
+
+  let space: id = msg_send![challenge, protectionSpace];
+  let trust: *const std::ffi::c_void = msg_send![space, serverTrust];
+  if trust.is_null() {
+    return None;
+  }
+  let mut error: *const std::ffi::c_void = std::ptr::null();
+  if SecTrustEvaluateWithError(trust, &mut error) {
+    return None;
+  }
+  let code = if error.is_null() {
+    0
+  } else {
+    let code = CFErrorGetCode(error);
+    CFRelease(error);
+    code
+  };
+  let error = match code {
+    // errSecNotTrusted
+    -67843 => CertErrorKind::UnknownIssuer,
+    // errSecHostNameMismatch
+    -67602 => CertErrorKind::HostMismatch,
+    // errSecCertificateExpired, errSecCertificateNotValidYet
+    -67818 | -67819 => CertErrorKind::Expired,
+    // errSecCertificateRevoked
+    -67820 => CertErrorKind::Revoked,
+    _ => CertErrorKind::Invalid,
+  };
+
+  let certificate = SecTrustGetCertificateAtIndex(trust, 0);
+  let certificate_der = if certificate.is_null() {
+    Vec::new()
+  } else {
+    let data = SecCertificateCopyData(certificate);
+    let bytes =
+      std::slice::from_raw_parts(CFDataGetBytePtr(data), CFDataGetLength(data) as usize).to_vec();
+    CFRelease(data);
+    bytes
+  };
+
+  let url: id = msg_send![webview, URL];
+  let url: id = msg_send![url, absoluteString];
+  let host = ns_string_to_string(msg_send![space, host]).unwrap_or_default();
+  let url = ns_string_to_string(url)
+    .and_then(|url| Url::parse(&url).ok())
+    // the challenge can come before the webview URL is updated
+    .filter(|url| url.host_str() == Some(host.as_str()))
+    .or_else(|| Url::parse(&format!("https://{host}/")).ok())?;
+
+  CERT_ERROR_HANDLERS.with(|handlers| {
+    handlers.borrow().get(&(webview as usize)).map(|handler| {
+      (
+        handler(CertError::new(url, certificate_der, error)),
+        trust as id,
+      )
+    })
+  })
+}
+
+/// The `webView:didReceiveAuthenticationChallenge:completionHandler:` implementation
+/// added to the navigation delegate class, see [`set_auth_challenge_handler`] and [`set_cert_error_handler`].
+#[cfg(target_os = "macos")]
+extern "C" fn webview_did_receive_auth_challenge(
+  _this: &objc::runtime::Object,
+  _cmd: objc::runtime::Sel,
+  webview: cocoa::base::id,
+  challenge: cocoa::base::id,
+  completion_handler: cocoa::base::id,
+) {
+  use block::Block;
+  use cocoa::{
+    base::{id, nil, NO},
+    foundation::{NSInteger, NSString},
+  };
+  use objc::{class, msg_send, runtime::BOOL, sel, sel_impl};
+
+  // NSURLSessionAuthChallengeDisposition
+  const USE_CREDENTIAL: NSInteger = 0;
+  const PERFORM_DEFAULT_HANDLING: NSInteger = 1;
+  const CANCEL_AUTHENTICATION_CHALLENGE: NSInteger = 2;
+  // NSURLCredentialPersistenceForSession
+  const PERSISTENCE_FOR_SESSION: NSInteger = 1;
+
+  unsafe {
+    let completion_handler = &*(completion_handler as *const Block<(NSInteger, id), ()>);
+    let space: id = msg_send![challenge, protectionSpace];
+    let method = ns_string_to_string(msg_send![space, authenticationMethod]).unwrap_or_default();
+
+    if method == "NSURLAuthenticationMethodServerTrust" {
+      match server_trust_decision(webview, challenge) {
+        Some((CertDecision::Deny, _)) => {
+          completion_handler.call((CANCEL_AUTHENTICATION_CHALLENGE, nil))
+        }
+        Some((_, trust)) => {
+          let credential: id = msg_send![class!(NSURLCredential), credentialForTrust: trust];
+          completion_handler.call((USE_CREDENTIAL, credential));
+        }
+        None => completion_handler.call((PERFORM_DEFAULT_HANDLING, nil)),
+      }
+      return;
+    }
+
+    // the client certificate challenges are not HTTP authentication
+    let is_http_auth = matches!(
+      method.as_str(),
+      "NSURLAuthenticationMethodDefault"
+        | "NSURLAuthenticationMethodHTTPBasic"
+        | "NSURLAuthenticationMethodHTTPDigest"
+        | "NSURLAuthenticationMethodNTLM"
+    );
+
+    let response = if is_http_auth {
+      AUTH_CHALLENGE_HANDLERS.with(|handlers| {
+        handlers.borrow().get(&(webview as usize)).map(|handler| {
+          let mut request =
+            AuthChallenge::new(ns_string_to_string(msg_send![space, host]).unwrap_or_default());
+          request.realm = ns_string_to_string(msg_send![space, realm]);
+          let is_proxy: BOOL = msg_send![space, isProxy];
+          request.is_proxy = is_proxy != NO;
+          let failures: NSInteger = msg_send![challenge, previousFailureCount];
+          request.previous_failures = failures.max(0) as u32;
+          handler(request)
+        })
+      })
+    } else {
+      None
+    };
+
+    match response {
+      Some(AuthResponse::Credentials { username, password }) => {
+        let credential: id = msg_send![
+          class!(NSURLCredential),
+          credentialWithUser: NSString::alloc(nil).init_str(&username)
+          password: NSString::alloc(nil).init_str(&password)
+          persistence: PERSISTENCE_FOR_SESSION
+        ];
+        completion_handler.call((USE_CREDENTIAL, credential));
+      }
+      Some(AuthResponse::Cancel) => completion_handler.call((CANCEL_AUTHENTICATION_CHALLENGE, nil)),
+      _ => completion_handler.call((PERFORM_DEFAULT_HANDLING, nil)),
+    }
+  }
+}
+
+/// Whether the webview audio can be muted on this platform.
+const MUTE_SUPPORTED: bool = cfg!(any(
+  windows,
+  all(target_os = "macos", feature = "macos-private-api"),
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+));
+
+/// Mutes or unmutes the webview audio.
+fn set_webview_muted(webview: &WebView, muted: bool) {
+  #[cfg(windows)]
+  {
+    use wry::WebViewExtWindows;
+
+    if let Err(e) = unsafe { webview.controller().CoreWebView2() }
+      .and_then(|webview| webview.cast::<ICoreWebView2_8>())
+      .and_then(|webview| unsafe { webview.SetIsMuted(muted) })
+    {
+      debug_eprintln!("failed to mute webview: {}", e);
+    }
+  }
+
+  // uses the private `_setPageMuted:` API, the muted state is kept across navigations
+  #[cfg(all(target_os = "macos", feature = "macos-private-api"))]
+  unsafe {
+    use objc::{msg_send, sel, sel_impl};
+    use wry::WebViewExtMacOS;
+
+    // _WKMediaAudioMuted
+    let state: usize = if muted { 1 } else { 0 };
+    let _: () = msg_send![webview.webview(), _setPageMuted: state];
+  }
+
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  {
+    use webkit2gtk::WebViewExt;
+    use wry::WebViewExtUnix;
+
+    webview.webview().set_is_muted(muted);
+  }
+
+  #[cfg(not(any(
+    windows,
+    all(target_os = "macos", feature = "macos-private-api"),
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  )))]
+  let _ = (webview, muted);
+}
+
+/// Whether the webview audio is muted.
+fn is_webview_muted(webview: &WebView) -> bool {
+  #[cfg(windows)]
+  {
+    use wry::WebViewExtWindows;
+
+    let mut muted = BOOL::default();
+    unsafe { webview.controller().CoreWebView2() }
+      .and_then(|webview| webview.cast::<ICoreWebView2_8>())
+      .and_then(|webview| unsafe { webview.IsMuted(&mut muted) })
+      .map(|_| muted.as_bool())
+      .unwrap_or_default()
+  }
+
+  #[cfg(all(target_os = "macos", feature = "macos-private-api"))]
+  unsafe {
+    use objc::{msg_send, sel, sel_impl};
+    use wry::WebViewExtMacOS;
+
+    let state: usize = msg_send![webview.webview(), _mediaMutedState];
+    // _WKMediaAudioMuted
+    state & 1 != 0
+  }
+
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  {
+    use webkit2gtk::WebViewExt;
+    use wry::WebViewExtUnix;
+
+    webview.webview().is_muted()
+  }
+
+  #[cfg(not(any(
+    windows,
+    all(target_os = "macos", feature = "macos-private-api"),
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  )))]
+  {
+    let _ = webview;
+    false
+  }
+}
+
+/// Pauses the media playing in the webview.
+fn pause_webview_media(webview: &WebView) {
+  #[cfg(target_os = "macos")]
+  unsafe {
+    use cocoa::base::nil;
+    use objc::{
+      msg_send,
+      runtime::{BOOL, YES},
+      sel, sel_impl,
+    };
+    use wry::WebViewExtMacOS;
+
+    let webview = webview.webview();
+    // macOS 11.3+
+    let supported: BOOL = msg_send![webview, respondsToSelector: sel!(pauseAllMediaPlayback:)];
+    if supported == YES {
+      let _: () = msg_send![webview, pauseAllMediaPlayback: nil];
+      return;
+    }
+  }
+
+  // the other platforms do not expose such API, so the media elements of the document are paused instead
+  if let Err(e) = webview
+    .evaluate_script("document.querySelectorAll('audio, video').forEach((media) => media.pause())")
+  {
+    debug_eprintln!("failed to pause the webview media: {}", e);
+  }
+}
+
+/// Captures the visible webview content as a PNG image.
+fn capture_webview_screenshot(
+  webview: &WebView,
+  callback: Box<dyn FnOnce(Result<Vec<u8>>) + Send>,
+) {
+  #[cfg(windows)]
+  {
+    use webview2_com::{
+      CapturePreviewCompletedHandler,
+      Microsoft::Web::WebView2::Win32::COREWEBVIEW2_CAPTURE_PREVIEW_IMAGE_FORMAT_PNG,
+    };
+    use windows::Win32::{
+      System::Com::{IStream, STATFLAG_NONAME, STATSTG, STREAM_SEEK_SET},
+      UI::Shell::SHCreateMemStream,
+    };
+    use wry::WebViewExtWindows;
+
+    fn read_stream(stream: &IStream) -> windows::core::Result<Vec<u8>> {
+      unsafe {
+        let mut stat = STATSTG::default();
+        stream.Stat(&mut stat, STATFLAG_NONAME)?;
+        stream.Seek(0, STREAM_SEEK_SET, None)?;
+        let mut bytes = vec![0u8; stat.cbSize as usize];
+        let mut read = 0;
+        stream
+          .Read(
+            bytes.as_mut_ptr().cast(),
+            bytes.len() as u32,
+            Some(&mut read),
+          )
+          .ok()?;
+        bytes.truncate(read as usize);
+        Ok(bytes)
+      }
+    }
+
+    let Some(stream) = (unsafe { SHCreateMemStream(None) }) else {
+      callback(Err(Error::Screenshot(
+        "failed to allocate the image stream".into(),
+      )));
+      return;
+    };
+    let webview = match unsafe { webview.controller().CoreWebView2() } {
+      Ok(webview) => webview,
+      Err(e) => {
+        callback(Err(Error::Screenshot(e.to_string())));
+        return;
+      }
+    };
+
+    // the handler is only called once, but the callback must be available if the capture fails to start
+    let callback = std::rc::Rc::new(std::cell::Cell::new(Some(callback)));
+    let callback_ = callback.clone();
+    let stream_ = stream.clone();
+    let handler = CapturePreviewCompletedHandler::create(Box::new(move |result| {
+      if let Some(callback) = callback_.take() {
+        callback(
+          result
+            .and_then(|_| read_stream(&stream_))
+            .map_err(|e| Error::Screenshot(e.to_string())),
+        );
+      }
+      Ok(())
+    }));
+    if let Err(e) = unsafe {
+      webview.CapturePreview(
+        COREWEBVIEW2_CAPTURE_PREVIEW_IMAGE_FORMAT_PNG,
+        &stream,
+        &handler,
+      )
+    } {
+      if let Some(callback) = callback.take() {
+        callback(Err(Error::Screenshot(e.to_string())));
+      }
+    }
+  }
+
+  #[cfg(target_os = "macos")]
+  unsafe {
+    use block::ConcreteBlock;
+    use cocoa::base::{id, nil};
+    use objc::{class, msg_send, sel, sel_impl};
+    use wry::WebViewExtMacOS;
+
+    let callback = std::cell::Cell::new(Some(callback));
+    let handler = ConcreteBlock::new(move |image: id, _error: id| {
+      let Some(callback) = callback.take() else {
+        return;
+      };
+      if image == nil {
+        callback(Err(Error::Screenshot("the snapshot failed".into())));
+        return;
+      }
+      let tiff: id = msg_send![image, TIFFRepresentation];
+      let representation: id = msg_send![class!(NSBitmapImageRep), imageRepWithData: tiff];
+      let properties: id = msg_send![class!(NSDictionary), dictionary];
+      // NSBitmapImageFileTypePNG
+      let png: id =
+        msg_send![representation, representationUsingType: 4usize properties: properties];
+      if png == nil {
+        callback(Err(Error::Screenshot(
+          "failed to encode the snapshot".into(),
+        )));
+        return;
+      }
+      let length: usize = msg_send![png, length];
+      let bytes: *const u8 = msg_send![png, bytes];
+      callback(Ok(std::slice::from_raw_parts(bytes, length).to_vec()));
+    })
+    .copy();
+    // macOS 10.13+, a nil configuration captures the visible bounds
+    let _: () = msg_send![
+      webview.webview(),
+      takeSnapshotWithConfiguration: nil
+      completionHandler: &*handler
+    ];
+  }
+
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  {
+    use webkit2gtk::{gio::Cancellable, SnapshotOptions, SnapshotRegion, WebViewExt};
+    use wry::WebViewExtUnix;
+
+    webview.webview().snapshot(
+      SnapshotRegion::Visible,
+      SnapshotOptions::NONE,
+      None::<&Cancellable>,
+      move |result| {
+        let result = result
+          .map_err(|e| e.to_string())
+          .and_then(|surface| {
+            let surface = cairo::ImageSurface::try_from(surface)
+              .map_err(|_| "the snapshot is not an image".to_string())?;
+            let mut png = Vec::new();
+            surface.write_to_png(&mut png).map_err(|e| e.to_string())?;
+            Ok(png)
+          })
+          .map_err(Error::Screenshot);
+        callback(result);
+      },
+    );
+  }
+
+  #[cfg(any(target_os = "ios", target_os = "android"))]
+  {
+    let _ = webview;
+    callback(Err(Error::Unsupported));
+  }
+}
+
+/// Calls a Chrome DevTools Protocol method of the webview.
+#[cfg(feature = "devtools-protocol")]
+fn call_devtools_protocol_method(
+  webview: &WebView,
+  method: String,
+  params: serde_json::Value,
+  callback: Box<dyn FnOnce(Result<serde_json::Value>) + Send>,
+) {
+  #[cfg(windows)]
+  {
+    use webview2_com::CallDevToolsProtocolMethodCompletedHandler;
+    use windows::core::HSTRING;
+    use wry::WebViewExtWindows;
+
+    let webview = match unsafe { webview.controller().CoreWebView2() } {
+      Ok(webview) => webview,
+      Err(e) => {
+        callback(Err(Error::DevtoolsProtocol(e.to_string())));
+        return;
+      }
+    };
+
+    // the handler is only called once, but the callback must be available if the call fails to start
+    let callback = std::rc::Rc::new(std::cell::Cell::new(Some(callback)));
+    let callback_ = callback.clone();
+    let handler =
+      CallDevToolsProtocolMethodCompletedHandler::create(Box::new(move |result, json| {
+        if let Some(callback) = callback_.take() {
+          callback(
+            result
+              .map_err(|e| Error::DevtoolsProtocol(e.to_string()))
+              .and_then(|_| serde_json::from_str(&json).map_err(Into::into)),
+          );
+        }
+        Ok(())
+      }));
+    if let Err(e) = unsafe {
+      webview.CallDevToolsProtocolMethod(
+        &HSTRING::from(method),
+        &HSTRING::from(params.to_string()),
+        &handler,
+      )
+    } {
+      if let Some(callback) = callback.take() {
+        callback(Err(Error::DevtoolsProtocol(e.to_string())));
+      }
+    }
+  }
+
+  #[cfg(not(windows))]
+  {
+    let _ = (webview, method, params);
+    callback(Err(Error::Unsupported));
+  }
+}
+
+/// Calls `handler` with the parameters of the Chrome DevTools Protocol events with the given name.
+#[cfg(feature = "devtools-protocol")]
+fn subscribe_devtools_protocol_event(
+  webview: &WebView,
+  event: String,
+  handler: Box<dyn Fn(serde_json::Value) + Send>,
+) {
+  #[cfg(windows)]
+  {
+    use webview2_com::{take_pwstr, DevToolsProtocolEventReceivedEventHandler};
+    use windows::core::{HSTRING, PWSTR};
+    use wry::WebViewExtWindows;
+
+    let res = unsafe { webview.controller().CoreWebView2() }
+      .and_then(|webview| unsafe {
+        webview.GetDevToolsProtocolEventReceiver(&HSTRING::from(event))
+      })
+      .and_then(|receiver| unsafe {
+        let mut token = EventRegistrationToken::default();
+        receiver.add_DevToolsProtocolEventReceived(
+          &DevToolsProtocolEventReceivedEventHandler::create(Box::new(move |_, args| {
+            if let Some(args) = args {
+              let mut json = PWSTR::null();
+              args.ParameterObjectAsJson(&mut json)?;
+              match serde_json::from_str(&take_pwstr(json)) {
+                Ok(params) => handler(params),
+                Err(e) => {
+                  debug_eprintln!("invalid devtools protocol event parameters: {}", e);
+                }
+              }
+            }
+            Ok(())
+          })),
+          &mut token,
+        )
+      });
+    if let Err(e) = res {
+      debug_eprintln!("failed to subscribe to the devtools protocol event: {}", e);
+    }
+  }
+
+  #[cfg(not(windows))]
+  {
+    let _ = (webview, event, handler);
+    debug_eprintln!("the devtools protocol is not supported on this platform");
+  }
+}
+
+type RequestObserver = Rc<dyn Fn(RequestSummary) + Send>;
+
+/// The observer of the navigations of a webview and the start of its pending navigation.
+#[cfg(target_os = "macos")]
+struct NavigationObserver {
+  observer: RequestObserver,
+  protocols: Vec<String>,
+  pending: Option<(std::time::Instant, String)>,
+}
+
+#[cfg(target_os = "macos")]
+thread_local! {
+  /// The request observers of the webviews, keyed by the `WKWebView` pointer.
+  static REQUEST_OBSERVERS: RefCell<HashMap<usize, NavigationObserver>> = Default::default();
+}
+
+/// Whether the request is reported to the request observer:
+/// only the network requests are, not the ones handled by the custom protocols.
+fn is_observed_url(url: &Url, protocols: &[String]) -> bool {
+  matches!(url.scheme(), "http" | "https" | "ws" | "wss")
+    && !protocols.iter().any(|protocol| {
+      // the custom protocols are served from `http://<protocol>.localhost` on Windows
+      url
+        .host_str()
+        .and_then(|host| host.strip_suffix(".localhost"))
+        == Some(protocol.as_str())
+    })
+}
+
+/// Calls the observer with the summary of each network request made by the webview.
+///
+/// WebKit on Apple platforms only lets a `WKURLSchemeHandler` see the custom protocol requests,
+/// so on macOS the navigation delegate is used instead, which only sees the main frame navigations.
+fn set_request_observer(webview: &WebView, protocols: Vec<String>, observer: RequestObserver) {
+  #[cfg(windows)]
+  {
+    use std::{collections::VecDeque, time::Instant};
+    use webview2_com::{
+      take_pwstr,
+      Microsoft::Web::WebView2::Win32::{
+        ICoreWebView2_2, COREWEBVIEW2_WEB_RESOURCE_CONTEXT, COREWEBVIEW2_WEB_RESOURCE_CONTEXT_ALL,
+        COREWEBVIEW2_WEB_RESOURCE_CONTEXT_DOCUMENT, COREWEBVIEW2_WEB_RESOURCE_CONTEXT_FETCH,
+        COREWEBVIEW2_WEB_RESOURCE_CONTEXT_FONT, COREWEBVIEW2_WEB_RESOURCE_CONTEXT_IMAGE,
+        COREWEBVIEW2_WEB_RESOURCE_CONTEXT_MEDIA, COREWEBVIEW2_WEB_RESOURCE_CONTEXT_SCRIPT,
+        COREWEBVIEW2_WEB_RESOURCE_CONTEXT_STYLESHEET, COREWEBVIEW2_WEB_RESOURCE_CONTEXT_WEBSOCKET,
+        COREWEBVIEW2_WEB_RESOURCE_CONTEXT_XML_HTTP_REQUEST,
+      },
+      WebResourceRequestedEventHandler, WebResourceResponseReceivedEventHandler,
+    };
+    use windows::core::{HSTRING, PWSTR};
+
+    // the requests that never get a response are dropped after this delay
+    const PENDING_REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(300);
+
+    // WebView2 does not identify the requests,
+    // so a response is matched with the oldest pending request with the same method and URL
+    let pending: Rc<RefCell<HashMap<(String, String), VecDeque<(Instant, ResourceType)>>>> =
+      Default::default();
+    let pending_ = pending.clone();
+
+    let res = unsafe { webview.controller().CoreWebView2() }
+      .and_then(|webview| webview.cast::<ICoreWebView2_2>())
+      .and_then(|webview| unsafe {
+        webview.AddWebResourceRequestedFilter(
+          &HSTRING::from("*"),
+          COREWEBVIEW2_WEB_RESOURCE_CONTEXT_ALL,
+        )?;
+
+        let mut token = EventRegistrationToken::default();
+        webview.add_WebResourceRequested(
+          &WebResourceRequestedEventHandler::create(Box::new(move |_, args| {
+            let Some(args) = args else {
+              return Ok(());
+            };
+
+            let request = args.Request()?;
+            let mut value = PWSTR::null();
+            request.Uri(&mut value)?;
+            let url = take_pwstr(value);
+            if !Url::parse(&url).is_ok_and(|url| is_observed_url(&url, &protocols)) {
+              return Ok(());
+            }
+            request.Method(&mut value)?;
+            let method = take_pwstr(value);
+            let mut context = COREWEBVIEW2_WEB_RESOURCE_CONTEXT::default();
+            args.ResourceContext(&mut context)?;
+            let resource_type = match context {
+              COREWEBVIEW2_WEB_RESOURCE_CONTEXT_DOCUMENT => ResourceType::Document,
+              COREWEBVIEW2_WEB_RESOURCE_CONTEXT_STYLESHEET => ResourceType::Stylesheet,
+              COREWEBVIEW2_WEB_RESOURCE_CONTEXT_SCRIPT => ResourceType::Script,
+              COREWEBVIEW2_WEB_RESOURCE_CONTEXT_IMAGE => ResourceType::Image,
+              COREWEBVIEW2_WEB_RESOURCE_CONTEXT_FONT => ResourceType::Font,
+              COREWEBVIEW2_WEB_RESOURCE_CONTEXT_MEDIA => ResourceType::Media,
+              COREWEBVIEW2_WEB_RESOURCE_CONTEXT_FETCH
+              | COREWEBVIEW2_WEB_RESOURCE_CONTEXT_XML_HTTP_REQUEST => ResourceType::Fetch,
+              COREWEBVIEW2_WEB_RESOURCE_CONTEXT_WEBSOCKET => ResourceType::WebSocket,
+              _ => ResourceType::Other,
+            };
+
+            let mut pending = pending_.borrow_mut();
+            pending.retain(|_, requests| {
+              requests.retain(|(start, _)| start.elapsed() < PENDING_REQUEST_TIMEOUT);
+              !requests.is_empty()
+            });
+            pending
+              .entry((method, url))
+              .or_default()
+              .push_back((Instant::now(), resource_type));
+            Ok(())
+          })),
+          &mut token,
+        )?;
+
+        webview.add_WebResourceResponseReceived(
+          &WebResourceResponseReceivedEventHandler::create(Box::new(move |_, args| {
+            let Some(args) = args else {
+              return Ok(());
+            };
+
+            let request = args.Request()?;
+            let mut value = PWSTR::null();
+            request.Uri(&mut value)?;
+            let url = take_pwstr(value);
+            request.Method(&mut value)?;
+            let method = take_pwstr(value);
+            let Some((start, resource_type)) = pending
+              .borrow_mut()
+              .get_mut(&(method.clone(), url.clone()))
+              .and_then(VecDeque::pop_front)
+            else {
+              return Ok(());
+            };
+            let Ok(url) = Url::parse(&url) else {
+              return Ok(());
+            };
+
+            let response = args.Response()?;
+            let mut status = 0;
+            response.StatusCode(&mut status)?;
+            let length = response
+              .Headers()?
+              .GetHeader(&HSTRING::from("Content-Length"), &mut value)
+              .ok()
+              .and_then(|()| take_pwstr(value).parse().ok());
+
+            let mut summary = RequestSummary::new(url, method, start.elapsed());
+            summary.resource_type = resource_type;
+            summary.status = u16::try_from(status).ok();
+            summary.length = length;
+            observer(summary);
+            Ok(())
+          })),
+          &mut token,
+        )
+      });
+    if let Err(e) = res {
+      debug_eprintln!("failed to observe the webview requests: {}", e);
+    }
+  }
+
+  #[cfg(target_os = "macos")]
+  {
+    use wry::WebViewExtMacOS;
+
+    let webview = webview.webview();
+    unsafe { observe_navigation_policies(webview) };
+    REQUEST_OBSERVERS.with(|observers| {
+      observers.borrow_mut().insert(
+        webview as usize,
+        NavigationObserver {
+          observer,
+          protocols,
+          pending: None,
+        },
+      );
+    });
+  }
+
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  {
+    use std::time::Instant;
+    use webkit2gtk::{URIRequestExt, URIResponseExt, WebResourceExt, WebViewExt};
+    use wry::WebViewExtUnix;
+
+    webview
+      .webview()
+      .connect_resource_load_started(move |webview, resource, request| {
+        let Some(url) = request.uri().and_then(|uri| Url::parse(&uri).ok()) else {
+          return;
+        };
+        if !is_observed_url(&url, &protocols) {
+          return;
+        }
+        let method = request
+          .http_method()
+          .map(|method| method.to_string())
+          .unwrap_or_else(|| "GET".into());
+        // webkit2gtk does not report the type of the other resources
+        let resource_type = if webview.main_resource().as_ref() == Some(resource) {
+          ResourceType::Document
+        } else {
+          ResourceType::Other
+        };
+        let start = Instant::now();
+        let observer = observer.clone();
+
+        // also emitted when the load fails
+        resource.connect_finished(move |resource| {
+          let mut summary = RequestSummary::new(url.clone(), method.clone(), start.elapsed());
+          summary.resource_type = resource_type;
+          if let Some(response) = resource.response() {
+            // the status and length are 0 when the request failed or they are unknown
+            summary.status = u16::try_from(response.status_code())
+              .ok()
+              .filter(|status| *status != 0);
+            summary.length = Some(response.content_length()).filter(|length| *length != 0);
+          }
+          observer(summary);
+        });
+      });
+  }
+
+  #[cfg(any(target_os = "ios", target_os = "android"))]
+  let _ = (webview, protocols, observer);
+}
+
+/// Moves the navigation policy methods of the navigation delegate class of the webview
+/// to prefixed selectors and replaces them with [`observe_navigation_action`] and [`observe_navigation_response`].
+#[cfg(target_os = "macos")]
+unsafe fn observe_navigation_policies(webview: cocoa::base::id) {
+  use cocoa::base::{id, NO};
+  use objc::{
+    msg_send,
+    runtime::{
+      class_addMethod, class_getInstanceMethod, method_exchangeImplementations, object_getClass,
+      Class, Method, Object, Sel,
+    },
+    sel, sel_impl,
+  };
+
+  let delegate: id = msg_send![webview, navigationDelegate];
+  let class = object_getClass(delegate as *const Object) as *mut Class;
+  let methods = [
+    (
+      sel!(webView:decidePolicyForNavigationAction:decisionHandler:),
+      sel!(tauriWebView:decidePolicyForNavigationAction:decisionHandler:),
+      std::mem::transmute::<extern "C" fn(&Object, Sel, id, id, id), objc::runtime::Imp>(
+        observe_navigation_action,
+      ),
+    ),
+    (
+      sel!(webView:decidePolicyForNavigationResponse:decisionHandler:),
+      sel!(tauriWebView:decidePolicyForNavigationResponse:decisionHandler:),
+      std::mem::transmute::<extern "C" fn(&Object, Sel, id, id, id), objc::runtime::Imp>(
+        observe_navigation_response,
+      ),
+    ),
+  ];
+  for (selector, prefixed, imp) in methods {
+    let original = class_getInstanceMethod(class, selector);
+    // adding the method fails if the class was already wrapped for another webview
+    if original.is_null()
+      || class_addMethod(
+        class,
+        prefixed,
+        imp,
+        b"v@:@@@\0".as_ptr() as *const std::os::raw::c_char,
+      ) == NO
+    {
+      continue;
+    }
+    method_exchangeImplementations(
+      original as *mut Method,
+      class_getInstanceMethod(class, prefixed) as *mut Method,
+    );
+  }
+}
+
+/// Records the start of the main frame navigations before calling the original
+/// `webView:decidePolicyForNavigationAction:decisionHandler:`, see [`observe_navigation_policies`].
+#[cfg(target_os = "macos")]
+extern "C" fn observe_navigation_action(
+  this: &objc::runtime::Object,
+  _cmd: objc::runtime::Sel,
+  webview: cocoa::base::id,
+  action: cocoa::base::id,
+  decision_handler: cocoa::base::id,
+) {
+  use cocoa::base::{id, nil, NO};
+  use objc::{msg_send, runtime::BOOL, sel, sel_impl};
+
+  unsafe {
+    let frame: id = msg_send![action, targetFrame];
+    // the navigations opening a new window have no target frame
+    let is_main_frame: BOOL = if frame == nil {
+      NO
+    } else {
+      msg_send![frame, isMainFrame]
+    };
+    if is_main_frame != NO {
+      let request: id = msg_send![action, request];
+      let method =
+        ns_string_to_string(msg_send![request, HTTPMethod]).unwrap_or_else(|| "GET".into());
+      REQUEST_OBSERVERS.with(|observers| {
+        if let Some(observer) = observers.borrow_mut().get_mut(&(webview as usize)) {
+          observer.pending = Some((std::time::Instant::now(), method));
+        }
+      });
+    }
+
+    // the implementations were exchanged, so this calls the original method
+    let () = msg_send![this, tauriWebView: webview decidePolicyForNavigationAction: action decisionHandler: decision_handler];
+  }
+}
+
+/// Reports the response of the main frame navigations before calling the original
+/// `webView:decidePolicyForNavigationResponse:decisionHandler:`, see [`observe_navigation_policies`].
+///
+/// The duration is the time until the response headers are received.
+#[cfg(target_os = "macos")]
+extern "C" fn observe_navigation_response(
+  this: &objc::runtime::Object,
+  _cmd: objc::runtime::Sel,
+  webview: cocoa::base::id,
+  navigation_response: cocoa::base::id,
+  decision_handler: cocoa::base::id,
+) {
+  use cocoa::base::{id, NO};
+  use objc::{class, msg_send, runtime::BOOL, sel, sel_impl};
+
+  unsafe {
+    let is_main_frame: BOOL = msg_send![navigation_response, isForMainFrame];
+    let observed = if is_main_frame != NO {
+      REQUEST_OBSERVERS.with(|observers| {
+        let mut observers = observers.borrow_mut();
+        let observer = observers.get_mut(&(webview as usize))?;
+        let (start, method) = observer.pending.take()?;
+        let response: id = msg_send![navigation_response, response];
+        let url: id = msg_send![response, URL];
+        let url = Url::parse(&ns_string_to_string(msg_send![url, absoluteString])?).ok()?;
+        if !is_observed_url(&url, &observer.protocols) {
+          return None;
+        }
+
+        let mut summary = RequestSummary::new(url, method, start.elapsed());
+        summary.resource_type = ResourceType::Document;
+        let is_http: BOOL = msg_send![response, isKindOfClass: class!(NSHTTPURLResponse)];
+        if is_http != NO {
+          let status: cocoa::foundation::NSInteger = msg_send![response, statusCode];
+          summary.status = u16::try_from(status).ok();
+        }
+        // -1 when the length is unknown
+        let length: i64 = msg_send![response, expectedContentLength];
+        summary.length = u64::try_from(length).ok();
+        Some((observer.observer.clone(), summary))
+      })
+    } else {
+      None
+    };
+    // called once the observers are released since it can create or close webviews
+    if let Some((observer, summary)) = observed {
+      observer(summary);
+    }
+
+    // the implementations were exchanged, so this calls the original method
+    let () = msg_send![this, tauriWebView: webview decidePolicyForNavigationResponse: navigation_response decisionHandler: decision_handler];
+  }
+}
+
+type ProcessCrashedHandler = Box<dyn Fn(ProcessCrashReason)>;
+
+#[cfg(target_os = "macos")]
+thread_local! {
+  /// The process crash handlers of the webviews, keyed by the `WKWebView` pointer.
+  static PROCESS_CRASHED_HANDLERS: RefCell<HashMap<usize, ProcessCrashedHandler>> = Default::default();
+}
+
+/// Calls the handler when the process rendering the webview content terminates unexpectedly.
+fn set_process_crashed_handler(webview: &WebView, handler: ProcessCrashedHandler) {
+  #[cfg(windows)]
+  {
+    use webview2_com::{
+      Microsoft::Web::WebView2::Win32::{
+        ICoreWebView2ProcessFailedEventArgs2, COREWEBVIEW2_PROCESS_FAILED_KIND,
+        COREWEBVIEW2_PROCESS_FAILED_KIND_BROWSER_PROCESS_EXITED,
+        COREWEBVIEW2_PROCESS_FAILED_KIND_RENDER_PROCESS_EXITED, COREWEBVIEW2_PROCESS_FAILED_REASON,
+        COREWEBVIEW2_PROCESS_FAILED_REASON_CRASHED,
+        COREWEBVIEW2_PROCESS_FAILED_REASON_OUT_OF_MEMORY,
+        COREWEBVIEW2_PROCESS_FAILED_REASON_TERMINATED,
+      },
+      ProcessFailedEventHandler,
+    };
+
+    let res = unsafe { webview.controller().CoreWebView2() }.and_then(|webview| unsafe {
+      let mut token = EventRegistrationToken::default();
+      webview.add_ProcessFailed(
+        &ProcessFailedEventHandler::create(Box::new(move |_, args| {
+          let Some(args) = args else {
+            return Ok(());
+          };
+
+          let mut kind = COREWEBVIEW2_PROCESS_FAILED_KIND::default();
+          args.ProcessFailedKind(&mut kind)?;
+          // WebView2 restarts the other processes without affecting the content
+          if kind != COREWEBVIEW2_PROCESS_FAILED_KIND_RENDER_PROCESS_EXITED
+            && kind != COREWEBVIEW2_PROCESS_FAILED_KIND_BROWSER_PROCESS_EXITED
+          {
+            return Ok(());
+          }
+
+          let mut reason = COREWEBVIEW2_PROCESS_FAILED_REASON::default();
+          let reason = match args
+            .cast::<ICoreWebView2ProcessFailedEventArgs2>()
+            .and_then(|args| args.Reason(&mut reason))
+            .map(|()| reason)
+          {
+            Ok(COREWEBVIEW2_PROCESS_FAILED_REASON_CRASHED) => ProcessCrashReason::Crashed,
+            Ok(COREWEBVIEW2_PROCESS_FAILED_REASON_OUT_OF_MEMORY) => ProcessCrashReason::OutOfMemory,
+            Ok(COREWEBVIEW2_PROCESS_FAILED_REASON_TERMINATED) => ProcessCrashReason::Terminated,
+            _ => ProcessCrashReason::Unknown,
+          };
+          handler(reason);
+          Ok(())
+        })),
+        &mut token,
+      )
+    });
+    if let Err(e) = res {
+      debug_eprintln!("failed to listen to the webview process failures: {}", e);
+    }
+  }
+
+  #[cfg(target_os = "macos")]
+  unsafe {
+    use cocoa::base::id;
+    use objc::{
+      msg_send,
+      runtime::{class_addMethod, object_getClass, Class, Object, Sel},
+      sel, sel_impl,
+    };
+    use wry::WebViewExtMacOS;
+
+    let webview = webview.webview();
+    let delegate: id = msg_send![webview, navigationDelegate];
+    // adding the method fails if the delegate class already has it, which is fine
+    class_addMethod(
+      object_getClass(delegate as *const Object) as *mut Class,
+      sel!(webViewWebContentProcessDidTerminate:),
+      std::mem::transmute::<extern "C" fn(&Object, Sel, id), objc::runtime::Imp>(
+        webview_content_process_did_terminate,
+      ),
+      b"v@:@\0".as_ptr() as *const std::os::raw::c_char,
+    );
+    PROCESS_CRASHED_HANDLERS.with(|handlers| {
+      handlers.borrow_mut().insert(webview as usize, handler);
+    });
+  }
+
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  {
+    use webkit2gtk::{WebProcessTerminationReason, WebViewExt};
+    use wry::WebViewExtUnix;
+
+    webview
+      .webview()
+      .connect_web_process_terminated(move |_, reason| {
+        handler(match reason {
+          WebProcessTerminationReason::Crashed => ProcessCrashReason::Crashed,
+          WebProcessTerminationReason::ExceededMemoryLimit => ProcessCrashReason::OutOfMemory,
+          _ => ProcessCrashReason::Unknown,
+        })
+      });
+  }
+
+  #[cfg(any(target_os = "ios", target_os = "android"))]
+  let _ = (webview, handler);
+}
+
+/// The `webViewWebContentProcessDidTerminate:` implementation added to the navigation delegate class,
+/// see [`set_process_crashed_handler`].
+#[cfg(target_os = "macos")]
+extern "C" fn webview_content_process_did_terminate(
+  _this: &objc::runtime::Object,
+  _sel: objc::runtime::Sel,
+  webview: cocoa::base::id,
+) {
+  PROCESS_CRASHED_HANDLERS.with(|handlers| {
+    // WebKit does not report why the process terminated
+    if let Some(handler) = handlers.borrow().get(&(webview as usize)) {
+      handler(ProcessCrashReason::Unknown);
+    }
+  });
+}
+
+/// Creates the native webview again at its current URL and bounds, see [`WebviewDispatch::recover`].
+fn recover_webview(window: &Window, webview: &WebviewWrapper) -> Result<WebviewWrapper> {
+  let recreate = webview.recreate.as_ref().ok_or(Error::Unsupported)?;
+  let scripts = webview.initialization_scripts.borrow().clone();
+  let mut recovered = recreate(
+    window,
+    webview.url().as_str(),
+    webview.outer_bounds(),
+    &scripts,
+  )?;
+
+  // the new webview is controlled by the same dispatchers and listeners
+  recovered.bounds = webview.bounds.clone();
+  recovered.webview_event_listeners = webview.webview_event_listeners.clone();
+  recovered.initialization_scripts = webview.initialization_scripts.clone();
+  recovered.recreate = webview.recreate.clone();
+  recovered.theme = webview.theme.clone();
+  set_webview_ignore_cursor_events(&recovered, webview.ignore_cursor_events.get());
+  recovered.ignore_cursor_events = webview.ignore_cursor_events.clone();
+  set_webview_content_insets(&recovered, webview.content_insets.get());
+  recovered.content_insets = webview.content_insets.clone();
+  Ok(recovered)
+}
+
+/// Moves a child webview to the given window, keeping its bounds.
+fn reparent_webview(webview: &WebView, window: &Window) -> Result<()> {
+  #[cfg(windows)]
+  unsafe {
+    use windows::Win32::{Foundation::HWND, UI::WindowsAndMessaging::SetParent};
+    use wry::WebViewExtWindows;
+
+    // the controller is hosted by a child window wry creates for the webview
+    let mut hwnd = HWND::default();
+    webview
+      .controller()
+      .ParentWindow(&mut hwnd)
+      .map_err(|e| Error::Reparent(e.to_string()))?;
+    if SetParent(hwnd, HWND(window.hwnd() as _)).0 == 0 {
+      return Err(Error::Reparent(
+        windows::core::Error::from_win32().to_string(),
+      ));
+    }
+    Ok(())
+  }
+
+  #[cfg(target_os = "macos")]
+  unsafe {
+    use cocoa::base::id;
+    use objc::{msg_send, sel, sel_impl};
+    use tao::platform::macos::WindowExtMacOS;
+    use wry::WebViewExtMacOS;
+
+    let bounds = webview.bounds();
+    // removes the webview from its current superview
+    let _: () = msg_send![window.ns_view() as id, addSubview: webview.webview()];
+    // the frame origin is flipped against the superview height
+    webview.set_bounds(bounds);
+    Ok(())
+  }
+
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  {
+    use gtk::prelude::*;
+    use wry::WebViewExtUnix;
+
+    let vbox = window
+      .default_vbox()
+      .ok_or_else(|| Error::Reparent("the window has no container".into()))?;
+    let widget = webview.webview();
+    if let Some(parent) = widget.parent() {
+      parent
+        .dynamic_cast::<gtk::Container>()
+        .map_err(|_| Error::Reparent("the webview parent is not a container".into()))?
+        .remove(&widget);
+    }
+    vbox.pack_start(&widget, true, true, 0);
+    widget.show_all();
+    Ok(())
+  }
+
+  #[cfg(any(target_os = "ios", target_os = "android"))]
+  {
+    let _ = (webview, window);
+    Err(Error::Unsupported)
+  }
+}
+
+/// Wraps a script in a function so its top-level declarations are not visible to the page,
+/// used where isolated worlds are not supported.
+#[cfg(any(windows, target_os = "macos"))]
+fn scope_script(script: &str) -> String {
+  format!("(function () {{\n{script}\n}})();")
+}
+
+/// Adds a script running at the start of every page load, after the scripts the webview was created with,
+/// in the given isolated world if any.
+fn add_webview_initialization_script(
+  webview: &WebView,
+  script: &str,
+  world: Option<&str>,
+) -> std::result::Result<(), String> {
+  #[cfg(windows)]
+  {
+    use webview2_com::AddScriptToExecuteOnDocumentCreatedCompletedHandler;
+    use wry::WebViewExtWindows;
+
+    let core_webview = unsafe { webview.controller().CoreWebView2() }.map_err(|e| e.to_string())?;
+    // WebView2 has no isolated worlds
+    let script = match world {
+      Some(_) => windows::core::HSTRING::from(scope_script(script)),
+      None => windows::core::HSTRING::from(script),
+    };
+    // waits for the script to be registered so it applies to the next navigation
+    AddScriptToExecuteOnDocumentCreatedCompletedHandler::wait_for_async_operation(
+      Box::new(move |handler| unsafe {
+        core_webview
+          .AddScriptToExecuteOnDocumentCreated(&script, &handler)
+          .map_err(webview2_com::Error::WindowsError)
+      }),
+      Box::new(|_, _| Ok(())),
+    )
+    .map_err(|e| e.to_string())
+  }
+
+  #[cfg(target_os = "macos")]
+  unsafe {
+    use cocoa::{
+      base::{id, nil},
+      foundation::NSString,
+    };
+    use objc::{class, msg_send, sel, sel_impl};
+    use wry::WebViewExtMacOS;
+
+    let user_script: id = msg_send![class!(WKUserScript), alloc];
+    // WKUserScriptInjectionTimeAtDocumentStart, injected in all frames like the wry initialization scripts
+    let user_script: id = match world {
+      Some(world) if content_world_supported() => {
+        let world: id = msg_send![
+          class!(WKContentWorld),
+          worldWithName: NSString::alloc(nil).init_str(world)
+        ];
+        msg_send![
+          user_script,
+          initWithSource: NSString::alloc(nil).init_str(script)
+          injectionTime: 0
+          forMainFrameOnly: 0
+          inContentWorld: world
+        ]
+      }
+      Some(_) => msg_send![
+        user_script,
+        initWithSource: NSString::alloc(nil).init_str(&scope_script(script))
+        injectionTime: 0
+        forMainFrameOnly: 0
+      ],
+      None => msg_send![
+        user_script,
+        initWithSource: NSString::alloc(nil).init_str(script)
+        injectionTime: 0
+        forMainFrameOnly: 0
+      ],
+    };
+    let _: () = msg_send![webview.manager(), addUserScript: user_script];
+    Ok(())
+  }
+
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  {
+    use webkit2gtk::{
+      UserContentInjectedFrames, UserContentManagerExt, UserScript, UserScriptInjectionTime,
+      WebViewExt,
+    };
+    use wry::WebViewExtUnix;
+
+    let manager = webview
+      .webview()
+      .user_content_manager()
+      .ok_or("the webview has no user content manager")?;
+    let user_script = match world {
+      Some(world) => UserScript::for_world(
+        script,
+        UserContentInjectedFrames::TopFrame,
+        UserScriptInjectionTime::Start,
+        world,
+        &[],
+        &[],
+      ),
+      None => UserScript::new(
+        script,
+        UserContentInjectedFrames::TopFrame,
+        UserScriptInjectionTime::Start,
+        &[],
+        &[],
+      ),
+    };
+    manager.add_script(&user_script);
+    Ok(())
+  }
+
+  #[cfg(any(target_os = "ios", target_os = "android"))]
+  {
+    let _ = (webview, script, world);
+    Err("this operation is not supported on the current platform".into())
+  }
+}
+
+/// Evaluates a script in an isolated world, see [`WebviewDispatch::eval_script_in_world`].
+fn evaluate_script_in_world(
+  webview: &WebView,
+  world: &str,
+  script: &str,
+  callback: Box<dyn FnOnce(String) + Send>,
+) {
+  #[cfg(target_os = "macos")]
+  if content_world_supported() {
+    unsafe {
+      use block::ConcreteBlock;
+      use cocoa::{
+        base::{id, nil},
+        foundation::NSString,
+      };
+      use objc::{class, msg_send, sel, sel_impl};
+      use wry::WebViewExtMacOS;
+
+      let callback = Cell::new(Some(callback));
+      let handler = ConcreteBlock::new(move |result: id, _error: id| {
+        let Some(callback) = callback.take() else {
+          return;
+        };
+        let is_string: bool = result != nil && msg_send![result, isKindOfClass: class!(NSString)];
+        callback(if is_string {
+          ns_string_to_string(result).unwrap_or_default()
+        } else {
+          String::new()
+        });
+      })
+      .copy();
+      let world: id = msg_send![
+        class!(WKContentWorld),
+        worldWithName: NSString::alloc(nil).init_str(world)
+      ];
+      // macOS 11+, evaluated in the main frame
+      let _: () = msg_send![
+        webview.webview(),
+        evaluateJavaScript: NSString::alloc(nil).init_str(script)
+        inFrame: nil
+        inContentWorld: world
+        completionHandler: &*handler
+      ];
+    }
+    return;
+  }
+
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  {
+    use javascriptcore::ValueExt;
+    use webkit2gtk::WebViewExt;
+    use wry::WebViewExtUnix;
+
+    // deprecated in favor of `evaluate_javascript` with the `linux-protocol-body` feature only
+    #[allow(deprecated)]
+    webview.webview().run_javascript_in_world(
+      script,
+      world,
+      None::<&webkit2gtk::gio::Cancellable>,
+      move |result| {
+        let result = result
+          .ok()
+          .and_then(|result| result.js_value())
+          .filter(|value| value.is_string())
+          .map(|value| value.to_str().to_string());
+        callback(result.unwrap_or_default());
+      },
+    );
+  }
+
+  #[cfg(not(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  )))]
+  {
+    let _ = world;
+    // wry takes a `Fn` callback but calls it once
+    let callback = Mutex::new(Some(callback));
+    if let Err(e) = webview.evaluate_script_with_callback(script, move |result| {
+      if let Some(callback) = callback.lock().unwrap().take() {
+        callback(serde_json::from_str::<String>(&result).unwrap_or_default());
+      }
+    }) {
+      debug_eprintln!("{}", e);
+    }
+  }
+}
+
+/// Whether `WKContentWorld` is available, macOS 11+.
+#[cfg(target_os = "macos")]
+fn content_world_supported() -> bool {
+  objc::runtime::Class::get("WKContentWorld").is_some()
+}
+
+/// Sets the `spellcheck` attribute of the document element unless the page sets it.
+#[cfg(not(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+)))]
+fn spellcheck_script(enabled: bool) -> String {
+  format!(
+    r#"(function () {{
+  function apply() {{
+    const root = document.documentElement
+    if (root && !root.hasAttribute('spellcheck')) root.spellcheck = {enabled}
+  }}
+  if (document.documentElement) {{
+    apply()
+  }}
+  document.addEventListener('DOMContentLoaded', apply, {{ once: true }})
+}})()"#
+  )
+}
+
+/// Enables or disables the spell checker of the webview context.
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+))]
+fn set_webview_spellcheck(webview: &WebView, enabled: bool) {
+  use webkit2gtk::{WebContextExt, WebViewExt};
+  use wry::WebViewExtUnix;
+
+  if let Some(context) = webview.webview().context() {
+    context.set_spell_checking_enabled(enabled);
+  }
+}
+
+/// Sets the spell checker languages of the webview context.
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+))]
+fn set_webview_spellcheck_languages(webview: &WebView, languages: &[String]) {
+  use webkit2gtk::{WebContextExt, WebViewExt};
+  use wry::WebViewExtUnix;
+
+  if let Some(context) = webview.webview().context() {
+    let languages = languages.iter().map(String::as_str).collect::<Vec<_>>();
+    context.set_spell_checking_languages(&languages);
+  }
+}
+
+/// Appends to the default user agent of the webview.
+fn append_webview_user_agent(webview: &WebView, suffix: &str) {
+  #[cfg(windows)]
+  {
+    use webview2_com::{take_pwstr, Microsoft::Web::WebView2::Win32::ICoreWebView2Settings2};
+    use windows::core::{ComInterface, HSTRING, PWSTR};
+    use wry::WebViewExtWindows;
+
+    let res = unsafe { webview.controller().CoreWebView2() }
+      .and_then(|webview| unsafe { webview.Settings() })
+      .and_then(|settings| settings.cast::<ICoreWebView2Settings2>())
+      .and_then(|settings| unsafe {
+        let mut user_agent = PWSTR::null();
+        settings.UserAgent(&mut user_agent)?;
+        let user_agent = take_pwstr(user_agent);
+        settings.SetUserAgent(&HSTRING::from(format!("{user_agent} {suffix}")))
+      });
+    if let Err(e) = res {
+      debug_eprintln!("failed to append to the webview user agent: {}", e);
+    }
+  }
+
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  {
+    use webkit2gtk::{SettingsExt, WebViewExt};
+    use wry::WebViewExtUnix;
+
+    if let Some(settings) = webview.webview().settings() {
+      let user_agent = settings.user_agent().unwrap_or_default();
+      settings.set_user_agent(Some(&format!("{user_agent} {suffix}")));
+    }
+  }
+
+  #[cfg(not(any(
+    windows,
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  )))]
+  {
+    let _ = webview;
+    debug_eprintln!(
+      "appending to the default user agent is not supported on this platform, ignoring `{}`",
+      suffix
+    );
+  }
+}
+
+/// Suspends or resumes the webview content.
+fn set_webview_suspended(webview: &WebView, suspended: bool) {
+  #[cfg(windows)]
+  {
+    use webview2_com::{
+      Microsoft::Web::WebView2::Win32::ICoreWebView2_3, TrySuspendCompletedHandler,
+    };
+    use windows::core::ComInterface;
+    use wry::WebViewExtWindows;
+
+    if let Ok(webview) = unsafe { webview.controller().CoreWebView2() }
+      .and_then(|webview| webview.cast::<ICoreWebView2_3>())
+    {
+      let res = unsafe {
+        if suspended {
+          webview.TrySuspend(&TrySuspendCompletedHandler::create(Box::new(|_, _| Ok(()))))
+        } else {
+          webview.Resume()
+        }
+      };
+      if let Err(e) = res {
+        debug_eprintln!("failed to suspend webview: {}", e);
+      }
+    }
+  }
+
+  #[cfg(target_os = "macos")]
+  unsafe {
+    use cocoa::base::nil;
+    use objc::{
+      msg_send,
+      runtime::{BOOL, NO, YES},
+      sel, sel_impl,
+    };
+    use wry::WebViewExtMacOS;
+
+    let webview = webview.webview();
+    // macOS 12+
+    let supported: BOOL =
+      msg_send![webview, respondsToSelector: sel!(setAllMediaPlaybackSuspended:completionHandler:)];
+    if supported == YES {
+      let _: () = msg_send![
+        webview,
+        setAllMediaPlaybackSuspended: if suspended { YES } else { NO }
+        completionHandler: nil
+      ];
+    }
+  }
+
+  #[cfg(not(any(windows, target_os = "macos")))]
+  let _ = (webview, suspended);
+}
 
 /// Create a wry ipc handler from a tauri ipc handler.
 fn create_ipc_handler<T: UserEvent>(
-  window_id: WindowId,
+  window_id: Arc<Mutex<WindowId>>,
   webview_id: WebviewId,
   context: Context<T>,
   label: String,
-  handler: WebviewIpcHandler<T, Wry<T>>,
+  handler: Rc<WebviewIpcHandler<T, Wry<T>>>,
 ) -> Box<IpcHandler> {
   Box::new(move |request| {
     handler(
       DetachedWebview {
         label: label.clone(),
         dispatcher: WryWebviewDispatcher {
-          window_id,
+          window_id: window_id.clone(),
           webview_id,
           context: context.clone(),
         },
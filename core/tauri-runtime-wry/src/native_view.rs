@@ -0,0 +1,440 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Native child views of a window, drawn by the app instead of a webview.
+
+use tao::window::Window;
+use tauri_runtime::window::dpi::{PhysicalPosition, PhysicalSize};
+use wry::WebView;
+
+/// A child of a window, in the order it is stacked with [`restack`].
+pub enum StackedView<'a> {
+  Webview(&'a WebView),
+  Native(&'a NativeView),
+}
+
+#[cfg(windows)]
+mod imp {
+  use std::num::NonZeroIsize;
+
+  use tao::platform::windows::WindowExtWindows;
+  use tauri_runtime::{Error, Result};
+  use windows::{
+    core::PCWSTR,
+    Win32::{
+      Foundation::{HINSTANCE, HWND, LPARAM, LRESULT, WPARAM},
+      System::LibraryLoader::GetModuleHandleW,
+      UI::WindowsAndMessaging::{
+        CreateWindowExW, DefWindowProcW, DestroyWindow, GetWindowLongW, RegisterClassExW,
+        SetWindowPos, ShowWindow, CS_HREDRAW, CS_VREDRAW, GWL_STYLE, HMENU, HWND_TOP,
+        SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE, SWP_NOZORDER, SW_HIDE, SW_SHOW, WINDOW_EX_STYLE,
+        WNDCLASSEXW, WS_CHILD, WS_CLIPSIBLINGS, WS_VISIBLE,
+      },
+    },
+  };
+  use wry::WebViewExtWindows;
+
+  use super::*;
+
+  pub struct NativeView {
+    hwnd: HWND,
+  }
+
+  unsafe extern "system" fn window_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+  ) -> LRESULT {
+    DefWindowProcW(hwnd, msg, wparam, lparam)
+  }
+
+  impl NativeView {
+    pub fn new(
+      window: &Window,
+      position: PhysicalPosition<i32>,
+      size: PhysicalSize<u32>,
+    ) -> Result<Self> {
+      let class_name = "TAURI_NATIVE_VIEW\0".encode_utf16().collect::<Vec<u16>>();
+      unsafe {
+        let hinstance = GetModuleHandleW(PCWSTR::null()).unwrap_or_default();
+        let class = WNDCLASSEXW {
+          cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
+          style: CS_HREDRAW | CS_VREDRAW,
+          lpfnWndProc: Some(window_proc),
+          hInstance: HINSTANCE(hinstance.0),
+          lpszClassName: PCWSTR::from_raw(class_name.as_ptr()),
+          ..Default::default()
+        };
+        // fails once the class is registered, which is fine
+        RegisterClassExW(&class);
+
+        let hwnd = CreateWindowExW(
+          WINDOW_EX_STYLE::default(),
+          PCWSTR::from_raw(class_name.as_ptr()),
+          PCWSTR::null(),
+          WS_CHILD | WS_CLIPSIBLINGS | WS_VISIBLE,
+          position.x,
+          position.y,
+          size.width as i32,
+          size.height as i32,
+          HWND(window.hwnd() as _),
+          HMENU::default(),
+          hinstance,
+          None,
+        );
+        if hwnd.0 == 0 {
+          return Err(Error::CreateNativeView(
+            windows::core::Error::from_win32().to_string(),
+          ));
+        }
+        Ok(Self { hwnd })
+      }
+    }
+
+    pub fn set_bounds(
+      &self,
+      _window: &Window,
+      position: PhysicalPosition<i32>,
+      size: PhysicalSize<u32>,
+    ) {
+      unsafe {
+        let _ = SetWindowPos(
+          self.hwnd,
+          HWND::default(),
+          position.x,
+          position.y,
+          size.width as i32,
+          size.height as i32,
+          SWP_NOACTIVATE | SWP_NOZORDER,
+        );
+      }
+    }
+
+    pub fn set_visible(&self, visible: bool) {
+      unsafe {
+        ShowWindow(self.hwnd, if visible { SW_SHOW } else { SW_HIDE });
+      }
+    }
+
+    pub fn raw_window_handle(&self) -> Option<raw_window_handle::RawWindowHandle> {
+      let mut handle = raw_window_handle::Win32WindowHandle::new(NonZeroIsize::new(self.hwnd.0)?);
+      handle.hinstance = unsafe { GetModuleHandleW(PCWSTR::null()) }
+        .ok()
+        .and_then(|h| NonZeroIsize::new(h.0));
+      Some(handle.into())
+    }
+  }
+
+  impl Drop for NativeView {
+    fn drop(&mut self) {
+      unsafe {
+        let _ = DestroyWindow(self.hwnd);
+      }
+    }
+  }
+
+  fn raise(hwnd: HWND) {
+    unsafe {
+      let _ = SetWindowPos(
+        hwnd,
+        HWND_TOP,
+        0,
+        0,
+        0,
+        0,
+        SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+      );
+    }
+  }
+
+  pub fn restack(_window: &Window, views: &[StackedView<'_>]) {
+    for view in views {
+      match view {
+        StackedView::Webview(webview) => {
+          let mut hwnd = HWND::default();
+          unsafe {
+            // the content webview is hosted by the window itself and cannot be ordered
+            if webview.controller().ParentWindow(&mut hwnd).is_ok()
+              && GetWindowLongW(hwnd, GWL_STYLE) as u32 & WS_CHILD.0 != 0
+            {
+              raise(hwnd);
+            }
+          }
+        }
+        StackedView::Native(view) => raise(view.hwnd),
+      }
+    }
+  }
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+  use std::ptr::NonNull;
+
+  use cocoa::{
+    appkit::{NSViewMinYMargin, NSWindowOrderingMode},
+    base::{id, nil, YES},
+    foundation::{NSPoint, NSRect, NSSize},
+  };
+  use objc::{class, msg_send, sel, sel_impl};
+  use tao::platform::macos::WindowExtMacOS;
+  use tauri_runtime::{Error, Result};
+  use wry::WebViewExtMacOS;
+
+  use super::*;
+
+  pub struct NativeView {
+    view: id,
+  }
+
+  /// The frame of a view in its superview, which has a flipped y axis.
+  unsafe fn frame(
+    superview: id,
+    scale_factor: f64,
+    position: PhysicalPosition<i32>,
+    size: PhysicalSize<u32>,
+  ) -> NSRect {
+    let position = position.to_logical::<f64>(scale_factor);
+    let size = size.to_logical::<f64>(scale_factor);
+    let superview_frame: NSRect = msg_send![superview, frame];
+    NSRect::new(
+      NSPoint::new(
+        position.x,
+        superview_frame.size.height - position.y - size.height,
+      ),
+      NSSize::new(size.width, size.height),
+    )
+  }
+
+  impl NativeView {
+    pub fn new(
+      window: &Window,
+      position: PhysicalPosition<i32>,
+      size: PhysicalSize<u32>,
+    ) -> Result<Self> {
+      unsafe {
+        let ns_view = window.ns_view() as id;
+        let view: id = msg_send![class!(NSView), alloc];
+        let view: id = msg_send![
+          view,
+          initWithFrame: frame(ns_view, window.scale_factor(), position, size)
+        ];
+        if view == nil {
+          return Err(Error::CreateNativeView(
+            "failed to create the NSView".into(),
+          ));
+        }
+        // Metal and OpenGL render to the layer of the view
+        let _: () = msg_send![view, setWantsLayer: YES];
+        // keeps the distance to the top of the window, like the child webviews
+        let _: () = msg_send![view, setAutoresizingMask: NSViewMinYMargin];
+        let _: () = msg_send![ns_view, addSubview: view];
+        Ok(Self { view })
+      }
+    }
+
+    pub fn set_bounds(
+      &self,
+      window: &Window,
+      position: PhysicalPosition<i32>,
+      size: PhysicalSize<u32>,
+    ) {
+      unsafe {
+        let superview: id = msg_send![self.view, superview];
+        if superview != nil {
+          let frame = frame(superview, window.scale_factor(), position, size);
+          let _: () = msg_send![self.view, setFrame: frame];
+        }
+      }
+    }
+
+    pub fn set_visible(&self, visible: bool) {
+      unsafe {
+        let _: () = msg_send![self.view, setHidden: !visible];
+      }
+    }
+
+    pub fn raw_window_handle(&self) -> Option<raw_window_handle::RawWindowHandle> {
+      let view = NonNull::new(self.view as *mut std::ffi::c_void)?;
+      Some(raw_window_handle::AppKitWindowHandle::new(view).into())
+    }
+  }
+
+  impl Drop for NativeView {
+    fn drop(&mut self) {
+      unsafe {
+        let _: () = msg_send![self.view, removeFromSuperview];
+        let _: () = msg_send![self.view, release];
+      }
+    }
+  }
+
+  unsafe fn raise(view: id) {
+    let superview: id = msg_send![view, superview];
+    if superview == nil {
+      return;
+    }
+    // keeps the view alive while it is moved
+    let _: id = msg_send![view, retain];
+    let _: () = msg_send![
+      superview,
+      addSubview: view
+      positioned: NSWindowOrderingMode::NSWindowAbove.bits()
+      relativeTo: nil
+    ];
+    let _: () = msg_send![view, release];
+  }
+
+  pub fn restack(_window: &Window, views: &[StackedView<'_>]) {
+    for view in views {
+      unsafe {
+        match view {
+          StackedView::Webview(webview) => raise(webview.webview()),
+          StackedView::Native(view) => raise(view.view),
+        }
+      }
+    }
+  }
+}
+
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+))]
+mod imp {
+  use gtk::prelude::*;
+  use tao::platform::unix::WindowExtUnix;
+  use tauri_runtime::{Error, Result};
+  use wry::WebViewExtUnix;
+
+  use super::*;
+
+  /// A `GtkDrawingArea` with a native window.
+  ///
+  /// Like the child webviews, it is packed in the window container,
+  /// so its position is not used and its size is only requested.
+  pub struct NativeView {
+    area: gtk::DrawingArea,
+  }
+
+  impl NativeView {
+    pub fn new(
+      window: &Window,
+      position: PhysicalPosition<i32>,
+      size: PhysicalSize<u32>,
+    ) -> Result<Self> {
+      let vbox = window
+        .default_vbox()
+        .ok_or_else(|| Error::CreateNativeView("the window has no container".into()))?;
+      let area = gtk::DrawingArea::new();
+      // the app draws the content
+      area.set_app_paintable(true);
+      area.connect_realize(|area| {
+        if let Some(window) = area.window() {
+          window.ensure_native();
+        }
+      });
+      let view = Self { area };
+      view.set_bounds(window, position, size);
+      vbox.pack_start(&view.area, false, false, 0);
+      view.area.show();
+      Ok(view)
+    }
+
+    pub fn set_bounds(
+      &self,
+      window: &Window,
+      _position: PhysicalPosition<i32>,
+      size: PhysicalSize<u32>,
+    ) {
+      let size = size.to_logical::<i32>(window.scale_factor());
+      self.area.set_size_request(size.width, size.height);
+    }
+
+    pub fn set_visible(&self, visible: bool) {
+      if visible {
+        self.area.show();
+      } else {
+        self.area.hide();
+      }
+    }
+
+    pub fn raw_window_handle(&self) -> Option<raw_window_handle::RawWindowHandle> {
+      let window = self.area.window()?;
+      let xid = window.downcast_ref::<gdkx11::X11Window>()?.xid();
+      Some(raw_window_handle::XlibWindowHandle::new(xid).into())
+    }
+  }
+
+  impl Drop for NativeView {
+    fn drop(&mut self) {
+      if let Some(parent) = self
+        .area
+        .parent()
+        .and_then(|parent| parent.downcast::<gtk::Container>().ok())
+      {
+        parent.remove(&self.area);
+      }
+    }
+  }
+
+  /// Child views are packed in the window container, so they are ordered by moving them to the end of it.
+  pub fn restack(window: &Window, views: &[StackedView<'_>]) {
+    let Some(vbox) = window.default_vbox() else {
+      return;
+    };
+    for view in views {
+      match view {
+        StackedView::Webview(webview) => {
+          let widget = webview.webview();
+          if widget.parent().as_ref() == Some(vbox.upcast_ref::<gtk::Widget>()) {
+            vbox.reorder_child(&widget, -1);
+          }
+        }
+        StackedView::Native(view) => vbox.reorder_child(&view.area, -1),
+      }
+    }
+  }
+}
+
+#[cfg(any(target_os = "ios", target_os = "android"))]
+mod imp {
+  use tauri_runtime::{Error, Result};
+
+  use super::*;
+
+  pub struct NativeView;
+
+  impl NativeView {
+    pub fn new(
+      _window: &Window,
+      _position: PhysicalPosition<i32>,
+      _size: PhysicalSize<u32>,
+    ) -> Result<Self> {
+      Err(Error::Unsupported)
+    }
+
+    pub fn set_bounds(
+      &self,
+      _window: &Window,
+      _position: PhysicalPosition<i32>,
+      _size: PhysicalSize<u32>,
+    ) {
+    }
+
+    pub fn set_visible(&self, _visible: bool) {}
+
+    pub fn raw_window_handle(&self) -> Option<raw_window_handle::RawWindowHandle> {
+      None
+    }
+  }
+
+  pub fn restack(_window: &Window, _views: &[StackedView<'_>]) {}
+}
+
+pub use imp::{restack, NativeView};
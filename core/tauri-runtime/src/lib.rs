@@ -15,7 +15,7 @@
 use raw_window_handle::DisplayHandle;
 use serde::Deserialize;
 use std::{fmt::Debug, sync::mpsc::Sender};
-use tauri_utils::{ProgressBarState, Theme};
+use tauri_utils::{config::BackgroundThrottlingPolicy, ProgressBarState, Theme};
 use url::Url;
 use webview::{DetachedWebview, PendingWebview};
 
@@ -27,7 +27,7 @@ pub mod window;
 use monitor::Monitor;
 use window::{
   dpi::{PhysicalPosition, PhysicalSize, Position, Size},
-  CursorIcon, DetachedWindow, PendingWindow, RawWindow, WindowEvent,
+  CursorIcon, DetachedWindow, PendingWindow, RawWindow, WebviewEvent, WindowEvent,
 };
 use window::{WindowBuilder, WindowId};
 
@@ -38,6 +38,7 @@ use http::{
 };
 
 pub type WindowEventId = u32;
+pub type WebviewEventId = u32;
 
 /// Type of user attention requested on a window.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
@@ -92,9 +93,12 @@ pub enum Error {
   /// Failed to create window.
   #[error("failed to create window")]
   CreateWindow,
-  /// The given window label is invalid.
-  #[error("Window labels must only include alphanumeric characters, `-`, `/`, `:` and `_`.")]
-  InvalidWindowLabel,
+  /// The given window or webview label includes a character that is not allowed, see [`window::validate_label`].
+  #[error("invalid label {0:?}: the character {1:?} is not allowed, labels must only include ASCII alphanumeric characters, `-`, `_`, `:` and `.`")]
+  InvalidLabel(String, char),
+  /// The given window or webview label starts with [`window::RESERVED_LABEL_PREFIX`].
+  #[error("invalid label {0:?}: the `tauri:` prefix is reserved")]
+  ReservedLabel(String),
   /// Failed to send message to webview.
   #[error("failed to send message to the webview")]
   FailedToSendMessage,
@@ -110,6 +114,9 @@ pub enum Error {
   /// Failed to get monitor on window operation.
   #[error("failed to get monitor")]
   FailedToGetMonitor,
+  /// Failed to get the cursor position.
+  #[error("failed to get the cursor position")]
+  FailedToGetCursorPosition,
   #[error("Invalid header name: {0}")]
   InvalidHeaderName(#[from] InvalidHeaderName),
   #[error("Invalid header value: {0}")]
@@ -126,8 +133,35 @@ pub enum Error {
   InvalidProxyUrl,
   #[error("window not found")]
   WindowNotFound,
+  /// The webview session conflicts with other webview attributes.
+  #[error("invalid webview session: {0}")]
+  InvalidSession(String),
+  /// The operation is not supported on the current platform.
+  #[error("this operation is not supported on the current platform")]
+  Unsupported,
+  /// Failed to capture the webview content.
+  #[error("failed to capture the webview: {0}")]
+  Screenshot(String),
+  /// Failed to move the webview to another window.
+  #[error("failed to reparent the webview: {0}")]
+  Reparent(String),
+  /// Failed to get the version of the webview installed on the system.
+  #[error("failed to get the webview version: {0}")]
+  WebviewVersion(String),
+  /// Failed to create a native view.
+  #[error("failed to create the native view: {0}")]
+  CreateNativeView(String),
+  /// The native view was removed or belongs to another window.
+  #[error("native view not found")]
+  NativeViewNotFound,
+  /// A devtools protocol method failed.
+  #[error("devtools protocol error: {0}")]
+  DevtoolsProtocol(String),
 }
 
+/// Identifier of a native view, see [`WindowDispatch::create_native_view`].
+pub type NativeViewId = u32;
+
 /// Result type.
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -238,6 +272,9 @@ pub trait RuntimeHandle<T: UserEvent>: Debug + Clone + Send + Sync + Sized + 'st
   fn primary_monitor(&self) -> Option<Monitor>;
   fn available_monitors(&self) -> Vec<Monitor>;
 
+  /// Returns the position of the cursor on the screen.
+  fn cursor_position(&self) -> Result<PhysicalPosition<f64>>;
+
   /// Shows the application, but does not automatically focus it.
   #[cfg(target_os = "macos")]
   #[cfg_attr(docsrs, doc(cfg(target_os = "macos")))]
@@ -287,6 +324,12 @@ pub trait Runtime<T: UserEvent>: Debug + Sized + 'static {
   /// The proxy type.
   type EventLoopProxy: EventLoopProxy<T>;
 
+  /// The name of the runtime, for diagnostics.
+  const NAME: &'static str;
+
+  /// Gets the version of the webview installed on the system.
+  fn webview_version() -> Result<String>;
+
   /// Creates a new webview runtime. Must be used on the main thread.
   fn new(args: RuntimeInitArgs) -> Result<Self>;
 
@@ -365,6 +408,9 @@ pub trait WebviewDispatch<T: UserEvent>: Debug + Clone + Send + Sync + Sized + '
   /// Runs a closure with the platform webview object as argument.
   fn with_webview<F: FnOnce(Box<dyn std::any::Any>) + Send + 'static>(&self, f: F) -> Result<()>;
 
+  /// Registers a webview event handler.
+  fn on_webview_event<F: Fn(&WebviewEvent) + Send + 'static>(&self, f: F) -> WebviewEventId;
+
   /// Open the web inspector which is usually called devtools.
   #[cfg(any(debug_assertions, feature = "devtools"))]
   fn open_devtools(&self);
@@ -388,11 +434,31 @@ pub trait WebviewDispatch<T: UserEvent>: Debug + Clone + Send + Sync + Sized + '
   /// Returns the physical size of the webviews's client area.
   fn size(&self) -> Result<PhysicalSize<u32>>;
 
+  /// Returns the scale factor of the webview content, mapping its logical pixels to physical pixels.
+  fn scale_factor(&self) -> Result<f64>;
+
+  /// Returns the webview's theme, either the one set with [`WebviewDispatch::set_theme`] or the system theme.
+  fn theme(&self) -> Result<Theme>;
+
+  /// Returns the identifier of the process rendering the webview content,
+  /// or the current process identifier if the platform does not use a dedicated process.
+  fn process_id(&self) -> Result<u32>;
+
+  /// Whether the webview audio is muted.
+  ///
+  /// Returns [`Error::Unsupported`] if the platform cannot mute the webview.
+  fn is_muted(&self) -> Result<bool>;
+
   // SETTER
 
   /// Naviagte to the given URL.
   fn navigate(&self, url: Url) -> Result<()>;
 
+  /// Navigates to the given URL, sending the headers with the request loading it.
+  ///
+  /// Only the main frame request receives the headers, not the requests of the subresources.
+  fn navigate_with_headers(&self, url: Url, headers: http::HeaderMap) -> Result<()>;
+
   /// Opens the dialog to prints the contents of the webview.
   fn print(&self) -> Result<()>;
 
@@ -405,11 +471,168 @@ pub trait WebviewDispatch<T: UserEvent>: Debug + Clone + Send + Sync + Sized + '
   /// Updates the webview position.
   fn set_position(&self, position: Position) -> Result<()>;
 
+  /// Updates the webview position and size at once.
+  fn set_bounds(&self, position: Position, size: Size) -> Result<()>;
+
+  /// Moves the webview to the given position in the stacking order of the webviews and native views of its window,
+  /// where `0` is the bottom-most. Indices past the top-most child move the webview to the top.
+  ///
+  /// Only the child webviews of a window are ordered, the content webview of a webview window is not affected.
+  fn set_z_order(&self, index: usize) -> Result<()>;
+
   /// Bring the window to front and focus the webview.
   fn set_focus(&self) -> Result<()>;
 
+  /// Sets whether mouse and touch input goes through the webview to the content beneath it.
+  ///
+  /// The webview can still be focused programmatically while it ignores cursor events.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** Only supported by child webviews, the content webview of a window is not affected.
+  /// - **iOS / Android:** Unsupported.
+  fn set_ignore_cursor_events(&self, ignore: bool) -> Result<()>;
+
+  /// Insets the webview content from the webview bounds, without reloading the page.
+  ///
+  /// The bounds set with [`Self::set_bounds`] and returned by [`Self::position`] and [`Self::size`] include the insets.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS:** The content webview of a window is inset from the window content view.
+  /// - **Windows / Linux:** Only supported by child webviews, the content webview of a window is not affected.
+  /// - **iOS / Android:** Unsupported.
+  fn set_content_insets(&self, insets: webview::Insets) -> Result<()>;
+
+  /// Sets the color scheme preferred by the webview content. `None` follows the system theme.
+  fn set_theme(&self, theme: Option<Theme>) -> Result<()>;
+
+  /// Sets how timers and rendering behave while the webview is hidden or in the background.
+  ///
+  /// Returns [`Error::Unsupported`] if the platform cannot change the policy of a running webview.
+  fn set_background_throttling(&self, policy: BackgroundThrottlingPolicy) -> Result<()>;
+
+  /// Enables or disables the spell checker.
+  ///
+  /// Returns [`Error::Unsupported`] if the platform cannot change the spell checker of a running webview.
+  fn set_spellcheck(&self, enabled: bool) -> Result<()>;
+
+  /// Sets the languages of the spell checker, as language codes such as `en_US`.
+  ///
+  /// Returns [`Error::Unsupported`] if the platform does not support choosing the languages.
+  fn set_spellcheck_languages(&self, languages: Vec<String>) -> Result<()>;
+
+  /// Suspends or resumes the webview content, pausing its timers, animations and media where supported.
+  ///
+  /// Returns [`Error::Unsupported`] if the platform cannot suspend the webview.
+  fn set_suspended(&self, suspended: bool) -> Result<()>;
+
+  /// Mutes or unmutes the webview audio. The state is kept across navigations.
+  ///
+  /// Returns [`Error::Unsupported`] if the platform cannot mute the webview.
+  fn set_muted(&self, muted: bool) -> Result<()>;
+
+  /// Pauses all media playing in the webview.
+  fn pause_all_media(&self) -> Result<()>;
+
+  /// Captures the visible content of the webview as a PNG image,
+  /// calling `f` with the encoded image once the capture completes.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **iOS / Android:** Unsupported, `f` is called with [`Error::Unsupported`].
+  fn screenshot<F: FnOnce(Result<Vec<u8>>) + Send + 'static>(&self, f: F) -> Result<()>;
+
+  /// Calls a Chrome DevTools Protocol method, calling `f` with its JSON result once it completes.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux / macOS / iOS / Android:** Unsupported, `f` is called with [`Error::Unsupported`].
+  #[cfg(feature = "devtools-protocol")]
+  fn call_devtools_protocol_method<F: FnOnce(Result<serde_json::Value>) + Send + 'static>(
+    &self,
+    method: String,
+    params: serde_json::Value,
+    f: F,
+  ) -> Result<()>;
+
+  /// Calls `f` with the parameters of every Chrome DevTools Protocol event with the given name,
+  /// for as long as the webview lives. The events of a domain are only sent once it is enabled, e.g. with `Network.enable`.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux / macOS / iOS / Android:** Unsupported.
+  #[cfg(feature = "devtools-protocol")]
+  fn subscribe_devtools_protocol_event<F: Fn(serde_json::Value) + Send + 'static>(
+    &self,
+    event: String,
+    f: F,
+  ) -> Result<()>;
+
+  /// Moves the webview to the window with the given identifier, keeping its position and size.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **iOS / Android:** Unsupported.
+  fn reparent(&self, window_id: WindowId) -> Result<()>;
+
+  /// Recreates the native webview in place, typically after [`WebviewEvent::ProcessCrashed`].
+  ///
+  /// The new webview keeps the attributes, bounds and event listeners of the current one,
+  /// runs its initialization scripts again and loads its current URL.
+  /// The dispatcher keeps controlling the webview after it is recreated.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **iOS / Android:** Unsupported.
+  fn recover(&self) -> Result<()>;
+
+  /// Adds a script to run on every page load, after the scripts of [`webview::WebviewAttributes::initialization_scripts`].
+  ///
+  /// The script does not run on the current page, see [`Self::eval_script`].
+  fn add_initialization_script(&self, script: String) -> Result<()>;
+
   /// Executes javascript on the window this [`WindowDispatch`] represents.
   fn eval_script<S: Into<String>>(&self, script: S) -> Result<()>;
+
+  /// Executes javascript on the webview, calling `callback` with the JSON serialized result of the script.
+  ///
+  /// An exception thrown by the script is not reported, the script must catch it and return it instead.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android:** Unsupported.
+  fn eval_script_with_callback<S: Into<String>, F: FnOnce(String) + Send + 'static>(
+    &self,
+    script: S,
+    callback: F,
+  ) -> Result<()>;
+
+  /// Adds a script to run on every page load in the given isolated world, see [`Self::eval_script_in_world`].
+  ///
+  /// Where isolated worlds are not supported, the script runs in the page world wrapped in a function,
+  /// so its top-level declarations are not visible to the page.
+  fn add_initialization_script_in_world(&self, world: String, script: String) -> Result<()>;
+
+  /// Executes javascript in an isolated world, calling `callback` with the result of the script,
+  /// which must evaluate to a string. The callback receives an empty string if the evaluation failed.
+  ///
+  /// An isolated world shares the DOM with the page but has its own globals,
+  /// so the page cannot observe or tamper with the script. Worlds are created on first use and identified by name.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS:** Uses a `WKContentWorld`, requires macOS 11+. Older versions use the page world.
+  /// - **Linux:** Uses a webkit2gtk script world.
+  /// - **Windows / iOS:** Isolated worlds are not supported, the script is evaluated in the page world.
+  ///   The caller is responsible for scoping the script so it does not leak declarations to the page.
+  /// - **Android:** Unsupported.
+  fn eval_script_in_world<S: Into<String>, F: FnOnce(String) + Send + 'static>(
+    &self,
+    world: String,
+    script: S,
+    callback: F,
+  ) -> Result<()>;
 }
 
 /// Window dispatcher. A thread-safe handle to the window APIs.
@@ -684,4 +907,34 @@ pub trait WindowDispatch<T: UserEvent>: Debug + Clone + Send + Sync + Sized + 's
   /// - **Linux / macOS**: Progress bar is app-wide and not specific to this window. Only supported desktop environments with `libunity` (e.g. GNOME).
   /// - **iOS / Android:** Unsupported.
   fn set_progress_bar(&self, progress_state: ProgressBarState) -> Result<()>;
+
+  /// Creates a native child view in the window, which is drawn by the app instead of a webview.
+  ///
+  /// The view is added on top of the webviews and native views of the window.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** A child `HWND`.
+  /// - **macOS:** A layer-backed `NSView`.
+  /// - **Linux:** A native child `GdkWindow`, X11 only.
+  /// - **iOS / Android:** Unsupported.
+  fn create_native_view(&self, position: Position, size: Size) -> Result<NativeViewId>;
+
+  /// Raw handle of a native view.
+  fn native_view_handle(
+    &self,
+    id: NativeViewId,
+  ) -> std::result::Result<raw_window_handle::WindowHandle<'_>, raw_window_handle::HandleError>;
+
+  /// Updates the position and size of a native view.
+  fn set_native_view_bounds(&self, id: NativeViewId, position: Position, size: Size) -> Result<()>;
+
+  /// Shows or hides a native view.
+  fn set_native_view_visible(&self, id: NativeViewId, visible: bool) -> Result<()>;
+
+  /// Moves a native view in the stacking order of the window children, see [`WebviewDispatch::set_z_order`].
+  fn set_native_view_z_order(&self, id: NativeViewId, index: usize) -> Result<()>;
+
+  /// Removes a native view from the window and destroys it.
+  fn remove_native_view(&self, id: NativeViewId) -> Result<()>;
 }
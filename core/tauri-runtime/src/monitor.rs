@@ -2,6 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
+use serde::Serialize;
+
 use super::window::dpi::{PhysicalPosition, PhysicalSize};
 
 /// Monitor descriptor.
@@ -16,4 +18,16 @@ pub struct Monitor {
   pub position: PhysicalPosition<i32>,
   /// Returns the scale factor that can be used to map logical pixels to physical pixels, and vice versa.
   pub scale_factor: f64,
+  /// The area of the monitor not covered by the taskbar, the dock or the menu bar,
+  /// or the whole monitor if the platform does not report it.
+  pub work_area: PhysicalRect,
+}
+
+/// A rectangle in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub struct PhysicalRect {
+  /// The position of the top-left corner.
+  pub position: PhysicalPosition<i32>,
+  /// The size of the rectangle.
+  pub size: PhysicalSize<u32>,
 }
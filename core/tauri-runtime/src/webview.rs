@@ -7,12 +7,15 @@
 use crate::{
   window::{
     dpi::{Position, Size},
-    is_label_valid,
+    validate_label,
   },
   Runtime, UserEvent,
 };
 
-use tauri_utils::config::{WebviewUrl, WindowConfig, WindowEffectsConfig};
+use serde::Deserialize;
+use tauri_utils::config::{
+  BackgroundThrottlingPolicy, WebviewConfig, WebviewUrl, WindowConfig, WindowEffectsConfig,
+};
 use url::Url;
 
 use std::{
@@ -21,6 +24,7 @@ use std::{
   hash::{Hash, Hasher},
   path::PathBuf,
   sync::Arc,
+  time::Duration,
 };
 
 type UriSchemeProtocol = dyn Fn(http::Request<Vec<u8>>, Box<dyn FnOnce(http::Response<Cow<'static, [u8]>>) + Send>)
@@ -37,6 +41,210 @@ type OnPageLoadHandler = dyn Fn(Url, PageLoadEvent) + Send;
 
 type DownloadHandler = dyn Fn(DownloadEvent) -> bool + Send + Sync;
 
+type ContextMenuHandler = dyn Fn(ContextMenuRequest) -> bool + Send;
+
+type AuthChallengeHandler = dyn Fn(AuthChallenge) -> AuthResponse + Send;
+
+/// An HTTP authentication challenge received by the webview, e.g. a page behind basic auth.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct AuthChallenge {
+  /// The host requesting the credentials.
+  pub host: String,
+  /// The authentication realm, if the server sent one.
+  pub realm: Option<String>,
+  /// Whether the credentials are requested by a proxy.
+  pub is_proxy: bool,
+  /// How many times the credentials given for this host were rejected.
+  pub previous_failures: u32,
+}
+
+impl AuthChallenge {
+  /// Creates a first challenge for the given host.
+  pub fn new(host: impl Into<String>) -> Self {
+    Self {
+      host: host.into(),
+      realm: None,
+      is_proxy: false,
+      previous_failures: 0,
+    }
+  }
+}
+
+type CertErrorHandler = dyn Fn(CertError) -> CertDecision + Send;
+
+/// Why the certificate of a server was rejected, see [`CertError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CertErrorKind {
+  /// The certificate is not signed by a trusted authority, e.g. a self-signed certificate.
+  UnknownIssuer,
+  /// The certificate does not match the host.
+  HostMismatch,
+  /// The certificate is expired or not valid yet.
+  Expired,
+  /// The certificate was revoked.
+  Revoked,
+  /// The certificate is invalid for another reason.
+  Invalid,
+}
+
+/// A TLS certificate error raised when loading a page.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct CertError {
+  /// The URL of the request.
+  pub url: Url,
+  /// The DER encoded certificate of the server, to compare with a pinned certificate.
+  pub certificate_der: Vec<u8>,
+  /// The reason the certificate was rejected.
+  pub error: CertErrorKind,
+}
+
+impl CertError {
+  /// Creates a certificate error.
+  pub fn new(url: Url, certificate_der: Vec<u8>, error: CertErrorKind) -> Self {
+    Self {
+      url,
+      certificate_der,
+      error,
+    }
+  }
+}
+
+type RequestObserver = dyn Fn(RequestSummary) + Send;
+
+/// The kind of resource requested by a page, see [`RequestSummary`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ResourceType {
+  /// A page loaded in the webview or in a frame.
+  Document,
+  /// A stylesheet.
+  Stylesheet,
+  /// A script.
+  Script,
+  /// An image.
+  Image,
+  /// A font.
+  Font,
+  /// An audio or video resource.
+  Media,
+  /// A `fetch` or `XMLHttpRequest` call.
+  Fetch,
+  /// A WebSocket connection.
+  WebSocket,
+  /// Any other resource, or a resource of unknown type.
+  Other,
+}
+
+/// A request made by a page, observed once it completes.
+///
+/// The request and response bodies are never included.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct RequestSummary {
+  /// The URL of the request.
+  pub url: Url,
+  /// The HTTP method of the request.
+  pub method: String,
+  /// The kind of resource requested.
+  pub resource_type: ResourceType,
+  /// The HTTP status of the response, `None` if the request failed.
+  pub status: Option<u16>,
+  /// The length of the response body, if known.
+  pub length: Option<u64>,
+  /// The time between the start of the request and its completion.
+  pub duration: Duration,
+}
+
+impl RequestSummary {
+  /// Creates a summary of a request of unknown type that got no response.
+  pub fn new(url: Url, method: impl Into<String>, duration: Duration) -> Self {
+    Self {
+      url,
+      method: method.into(),
+      resource_type: ResourceType::Other,
+      status: None,
+      length: None,
+      duration,
+    }
+  }
+}
+
+/// The decision on a [`CertError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum CertDecision {
+  /// Reject the certificate, the page fails to load.
+  #[default]
+  Deny,
+  /// Accept the certificate for this request.
+  AllowOnce,
+  /// Accept the certificate for all the requests to this host during the session.
+  AllowForHost,
+}
+
+/// The response to an [`AuthChallenge`].
+#[derive(Clone)]
+#[non_exhaustive]
+pub enum AuthResponse {
+  /// Authenticate with the given credentials.
+  Credentials {
+    /// The user name.
+    username: String,
+    /// The password.
+    password: String,
+  },
+  /// Cancel the request, the page gets the server error response.
+  Cancel,
+  /// Let the webview handle the challenge, which usually means cancelling it
+  /// or using credentials it already knows.
+  Default,
+}
+
+impl std::fmt::Debug for AuthResponse {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      // never expose the credentials in logs
+      Self::Credentials { .. } => f.write_str("Credentials"),
+      Self::Cancel => f.write_str("Cancel"),
+      Self::Default => f.write_str("Default"),
+    }
+  }
+}
+
+/// A context menu request, usually triggered by a right click on the webview.
+///
+/// The hit-test data is only set on platforms that provide it.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ContextMenuRequest {
+  /// The position of the request relative to the top-left corner of the webview.
+  pub position: Position,
+  /// The URL of the link under the cursor.
+  pub link_url: Option<String>,
+  /// The source URL of the image under the cursor.
+  pub image_src: Option<String>,
+  /// The selected text.
+  pub selected_text: Option<String>,
+  /// Whether the request targets an editable element.
+  pub is_editable: bool,
+}
+
+impl ContextMenuRequest {
+  /// Creates a request at the given position without hit-test data.
+  pub fn new(position: Position) -> Self {
+    Self {
+      position,
+      link_url: None,
+      image_src: None,
+      selected_text: None,
+      is_editable: false,
+    }
+  }
+}
+
 /// Download event.
 pub enum DownloadEvent<'a> {
   /// Download requested.
@@ -104,6 +312,18 @@ pub struct PendingWebview<T: UserEvent, R: Runtime<T>> {
   pub on_page_load_handler: Option<Box<OnPageLoadHandler>>,
 
   pub download_handler: Option<Arc<DownloadHandler>>,
+
+  /// A handler called when a context menu is requested, returning whether the default menu is shown.
+  pub context_menu_handler: Option<Box<ContextMenuHandler>>,
+
+  /// A handler called when a page requests HTTP authentication.
+  pub auth_challenge_handler: Option<Box<AuthChallengeHandler>>,
+
+  /// A handler deciding whether a server certificate rejected by the webview is accepted.
+  pub cert_error_handler: Option<Box<CertErrorHandler>>,
+
+  /// A handler called with the summary of each request made by the page, except for the custom protocols.
+  pub request_observer: Option<Box<RequestObserver>>,
 }
 
 impl<T: UserEvent, R: Runtime<T>> PendingWebview<T, R> {
@@ -113,23 +333,26 @@ impl<T: UserEvent, R: Runtime<T>> PendingWebview<T, R> {
     label: impl Into<String>,
   ) -> crate::Result<Self> {
     let label = label.into();
-    if !is_label_valid(&label) {
-      Err(crate::Error::InvalidWindowLabel)
-    } else {
-      Ok(Self {
-        webview_attributes,
-        uri_scheme_protocols: Default::default(),
-        label,
-        ipc_handler: None,
-        navigation_handler: None,
-        url: "tauri://localhost".to_string(),
-        #[cfg(target_os = "android")]
-        on_webview_created: None,
-        web_resource_request_handler: None,
-        on_page_load_handler: None,
-        download_handler: None,
-      })
-    }
+    validate_label(&label)?;
+    webview_attributes.validate_session()?;
+
+    Ok(Self {
+      webview_attributes,
+      uri_scheme_protocols: Default::default(),
+      label,
+      ipc_handler: None,
+      navigation_handler: None,
+      url: "tauri://localhost".to_string(),
+      #[cfg(target_os = "android")]
+      on_webview_created: None,
+      web_resource_request_handler: None,
+      on_page_load_handler: None,
+      download_handler: None,
+      context_menu_handler: None,
+      auth_challenge_handler: None,
+      cert_error_handler: None,
+      request_observer: None,
+    })
   }
 
   pub fn register_uri_scheme_protocol<
@@ -195,23 +418,143 @@ impl<T: UserEvent, R: Runtime<T>> PartialEq for DetachedWebview<T, R> {
   }
 }
 
+/// A browsing session shared by webviews, see [`WebviewAttributes::session`].
+///
+/// Webviews created with the same session share cookies, cache and local storage.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SessionId(SessionKind);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum SessionKind {
+  Shared(String),
+  Ephemeral,
+}
+
+impl SessionId {
+  /// A session shared by every webview created with the same identifier.
+  pub fn new(id: impl Into<String>) -> Self {
+    Self(SessionKind::Shared(id.into()))
+  }
+
+  /// A session that is not persisted to disk nor shared with other webviews, equivalent to the incognito mode.
+  pub fn ephemeral() -> Self {
+    Self(SessionKind::Ephemeral)
+  }
+
+  /// The identifier of a shared session, or `None` for an ephemeral session.
+  pub fn id(&self) -> Option<&str> {
+    match &self.0 {
+      SessionKind::Shared(id) => Some(id),
+      SessionKind::Ephemeral => None,
+    }
+  }
+
+  /// Whether this is an ephemeral session.
+  pub fn is_ephemeral(&self) -> bool {
+    matches!(self.0, SessionKind::Ephemeral)
+  }
+}
+
+/// Insets of the webview content from the webview bounds, in logical pixels.
+///
+/// See [`crate::WebviewDispatch::set_content_insets`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Insets {
+  /// The inset from the top edge.
+  pub top: f64,
+  /// The inset from the left edge.
+  pub left: f64,
+  /// The inset from the bottom edge.
+  pub bottom: f64,
+  /// The inset from the right edge.
+  pub right: f64,
+}
+
 /// The attributes used to create an webview.
+///
+/// New attributes are added to this struct as soon as the runtime supports them,
+/// so it is marked as non exhaustive: create it with [`WebviewAttributes::new`] and mutate its fields directly.
+/// Fields are only removed or changed in breaking releases.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct WebviewAttributes {
+  /// The URL to load.
   pub url: WebviewUrl,
+  /// The user agent.
   pub user_agent: Option<String>,
+  /// Appended to the user agent, or to the default user agent of the platform when [`Self::user_agent`] is not set.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS / Android**: Only appended to [`Self::user_agent`].
+  pub user_agent_suffix: Option<String>,
+  /// Scripts injected before the page scripts are executed.
   pub initialization_scripts: Vec<String>,
+  /// Scripts injected before the page scripts are executed in the given isolated worlds, as `(world, script)` pairs.
+  ///
+  /// See [`crate::WebviewDispatch::eval_script_in_world`] for the platform support of isolated worlds.
+  pub isolated_initialization_scripts: Vec<(String, String)>,
+  /// The webview data directory.
   pub data_directory: Option<PathBuf>,
+  /// Whether the file drop handler is enabled.
   pub file_drop_handler_enabled: bool,
+  /// Whether the page can access the clipboard.
   pub clipboard: bool,
+  /// Whether clicking an inactive window also clicks through to the webview.
   pub accept_first_mouse: bool,
+  /// Additional browser arguments. **Windows Only**
   pub additional_browser_args: Option<String>,
+  /// The window effects.
   pub window_effects: Option<WindowEffectsConfig>,
+  /// Whether the webview is launched in incognito mode.
   pub incognito: bool,
+  /// Whether the webview is transparent.
   pub transparent: bool,
+  /// The position and size of the webview in its window.
   pub bounds: Option<(Position, Size)>,
+  /// Whether the webview grows and shrinks with its window.
   pub auto_resize: bool,
+  /// The proxy URL for all network requests.
   pub proxy_url: Option<Url>,
+  /// The browsing session. The webview uses the default session of the process if not set.
+  pub session: Option<SessionId>,
+  /// How timers and rendering behave in the background. The platform default is used if not set.
+  pub background_throttling: Option<BackgroundThrottlingPolicy>,
+  /// Whether the spell checker is enabled. The platform default is used if not set.
+  pub spellcheck: Option<bool>,
+  /// Whether the webview is focused when created.
+  pub focus: bool,
+  /// Whether mouse and touch input goes through the webview to the content beneath it.
+  pub ignore_cursor_events: bool,
+  /// The insets of the webview content from the webview bounds.
+  pub content_insets: Insets,
+  /// The headers of the request loading the initial URL. Subresource requests do not receive them.
+  pub initial_headers: Option<http::HeaderMap>,
+  /// The local port of the remote debugging server, see [`crate::WebviewDispatch::call_devtools_protocol_method`].
+  #[cfg(feature = "devtools-protocol")]
+  pub remote_debugging_port: Option<u16>,
+}
+
+/// Attributes that can be overridden with [`WebviewAttributes::apply_overrides`].
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct WebviewAttributesOverrides {
+  user_agent: Option<String>,
+  initialization_scripts: Option<Vec<String>>,
+  data_directory: Option<PathBuf>,
+  file_drop_enabled: Option<bool>,
+  clipboard: Option<bool>,
+  accept_first_mouse: Option<bool>,
+  additional_browser_args: Option<String>,
+  window_effects: Option<WindowEffectsConfig>,
+  incognito: Option<bool>,
+  transparent: Option<bool>,
+  auto_resize: Option<bool>,
+  proxy_url: Option<Url>,
+  background_throttling: Option<BackgroundThrottlingPolicy>,
+  spellcheck: Option<bool>,
+  focus: Option<bool>,
+  ignore_cursor_events: Option<bool>,
 }
 
 impl From<&WindowConfig> for WebviewAttributes {
@@ -223,6 +566,7 @@ impl From<&WindowConfig> for WebviewAttributes {
       builder = builder.transparent(config.transparent);
     }
     builder = builder.accept_first_mouse(config.accept_first_mouse);
+    builder = builder.focused(config.focus);
     if !config.file_drop_enabled {
       builder = builder.disable_file_drop_handler();
     }
@@ -238,6 +582,44 @@ impl From<&WindowConfig> for WebviewAttributes {
     if let Some(url) = &config.proxy_url {
       builder = builder.proxy_url(url.to_owned());
     }
+    if let Some(policy) = config.background_throttling {
+      builder = builder.background_throttling(policy);
+    }
+    if let Some(spellcheck) = config.spellcheck {
+      builder = builder.spellcheck(spellcheck);
+    }
+    builder
+  }
+}
+
+impl From<&WebviewConfig> for WebviewAttributes {
+  fn from(config: &WebviewConfig) -> Self {
+    let mut builder = Self::new(config.url.clone());
+    builder = builder.incognito(config.incognito);
+    #[cfg(any(not(target_os = "macos"), feature = "macos-private-api"))]
+    {
+      builder = builder.transparent(config.transparent);
+    }
+    builder = builder.accept_first_mouse(config.accept_first_mouse);
+    builder = builder.focused(config.focus);
+    if !config.file_drop_enabled {
+      builder = builder.disable_file_drop_handler();
+    }
+    if let Some(user_agent) = &config.user_agent {
+      builder = builder.user_agent(user_agent);
+    }
+    if let Some(additional_browser_args) = &config.additional_browser_args {
+      builder = builder.additional_browser_args(additional_browser_args);
+    }
+    if let Some(url) = &config.proxy_url {
+      builder = builder.proxy_url(url.to_owned());
+    }
+    if let Some(policy) = config.background_throttling {
+      builder = builder.background_throttling(policy);
+    }
+    if let Some(spellcheck) = config.spellcheck {
+      builder = builder.spellcheck(spellcheck);
+    }
     builder
   }
 }
@@ -248,7 +630,9 @@ impl WebviewAttributes {
     Self {
       url,
       user_agent: None,
+      user_agent_suffix: None,
       initialization_scripts: Vec::new(),
+      isolated_initialization_scripts: Vec::new(),
       data_directory: None,
       file_drop_handler_enabled: true,
       clipboard: false,
@@ -260,6 +644,15 @@ impl WebviewAttributes {
       bounds: None,
       auto_resize: false,
       proxy_url: None,
+      session: None,
+      background_throttling: None,
+      spellcheck: None,
+      focus: true,
+      ignore_cursor_events: false,
+      content_insets: Insets::default(),
+      initial_headers: None,
+      #[cfg(feature = "devtools-protocol")]
+      remote_debugging_port: None,
     }
   }
 
@@ -270,6 +663,16 @@ impl WebviewAttributes {
     self
   }
 
+  /// Appends to the user agent, separated by a space, see [`Self::user_agent_suffix`].
+  #[must_use]
+  pub fn append_user_agent(mut self, suffix: &str) -> Self {
+    self.user_agent_suffix = Some(match self.user_agent_suffix.take() {
+      Some(user_agent_suffix) => format!("{user_agent_suffix} {suffix}"),
+      None => suffix.to_string(),
+    });
+    self
+  }
+
   /// Sets the init script.
   #[must_use]
   pub fn initialization_script(mut self, script: &str) -> Self {
@@ -277,6 +680,15 @@ impl WebviewAttributes {
     self
   }
 
+  /// Adds a script injected in the given isolated world.
+  #[must_use]
+  pub fn isolated_initialization_script(mut self, world: &str, script: &str) -> Self {
+    self
+      .isolated_initialization_scripts
+      .push((world.to_string(), script.to_string()));
+    self
+  }
+
   /// Data directory for the webview.
   #[must_use]
   pub fn data_directory(mut self, data_directory: PathBuf) -> Self {
@@ -308,6 +720,37 @@ impl WebviewAttributes {
     self
   }
 
+  /// Sets whether the webview is focused when created, `true` by default.
+  ///
+  /// The webview is never focused if its window is not focused or is minimized.
+  #[must_use]
+  pub fn focused(mut self, focus: bool) -> Self {
+    self.focus = focus;
+    self
+  }
+
+  /// Sets whether mouse and touch input goes through the webview to the content beneath it, `false` by default.
+  #[must_use]
+  pub fn ignore_cursor_events(mut self, ignore: bool) -> Self {
+    self.ignore_cursor_events = ignore;
+    self
+  }
+
+  /// Sets the insets of the webview content from the webview bounds.
+  #[must_use]
+  pub fn content_insets(mut self, insets: Insets) -> Self {
+    self.content_insets = insets;
+    self
+  }
+
+  /// Starts the remote debugging server on the given local port.
+  #[cfg(feature = "devtools-protocol")]
+  #[must_use]
+  pub fn remote_debugging_port(mut self, port: u16) -> Self {
+    self.remote_debugging_port = Some(port);
+    self
+  }
+
   /// Sets additional browser arguments. **Windows Only**
   #[must_use]
   pub fn additional_browser_args(mut self, additional_args: &str) -> Self {
@@ -350,6 +793,112 @@ impl WebviewAttributes {
     self.proxy_url = Some(url);
     self
   }
+
+  /// Sets how timers and rendering behave while the webview is hidden or in the background.
+  #[must_use]
+  pub fn background_throttling(mut self, policy: BackgroundThrottlingPolicy) -> Self {
+    self.background_throttling = Some(policy);
+    self
+  }
+
+  /// Enables or disables the spell checker.
+  #[must_use]
+  pub fn spellcheck(mut self, enabled: bool) -> Self {
+    self.spellcheck = Some(enabled);
+    self
+  }
+
+  /// Sets the headers of the request loading the initial URL.
+  #[must_use]
+  pub fn initial_headers(mut self, headers: http::HeaderMap) -> Self {
+    self.initial_headers.replace(headers);
+    self
+  }
+
+  /// Sets the browsing session, see [`SessionId`].
+  ///
+  /// An ephemeral session cannot be used with a data directory,
+  /// and a shared session cannot be used with the incognito mode.
+  /// Webviews sharing a session must use the same data directory.
+  #[must_use]
+  pub fn session(mut self, session: SessionId) -> Self {
+    self.session.replace(session);
+    self
+  }
+
+  /// Checks that the session does not conflict with the data directory and incognito attributes.
+  pub fn validate_session(&self) -> crate::Result<()> {
+    match &self.session {
+      Some(session) if session.is_ephemeral() && self.data_directory.is_some() => Err(
+        crate::Error::InvalidSession("an ephemeral session cannot use a data directory".into()),
+      ),
+      Some(session) if !session.is_ephemeral() && self.incognito => {
+        Err(crate::Error::InvalidSession(
+          "a shared session cannot be incognito, use an ephemeral session instead".into(),
+        ))
+      }
+      _ => Ok(()),
+    }
+  }
+
+  /// Overrides the attributes set in the given JSON object.
+  ///
+  /// The keys are the camelCase attribute names, e.g. `{ "userAgent": "...", "fileDropEnabled": false }`.
+  /// Unknown keys are rejected.
+  pub fn apply_overrides(&mut self, overrides: serde_json::Value) -> serde_json::Result<()> {
+    let overrides: WebviewAttributesOverrides = serde_json::from_value(overrides)?;
+
+    if let Some(user_agent) = overrides.user_agent {
+      self.user_agent.replace(user_agent);
+    }
+    if let Some(scripts) = overrides.initialization_scripts {
+      self.initialization_scripts = scripts;
+    }
+    if let Some(data_directory) = overrides.data_directory {
+      self.data_directory.replace(data_directory);
+    }
+    if let Some(enabled) = overrides.file_drop_enabled {
+      self.file_drop_handler_enabled = enabled;
+    }
+    if let Some(clipboard) = overrides.clipboard {
+      self.clipboard = clipboard;
+    }
+    if let Some(accept) = overrides.accept_first_mouse {
+      self.accept_first_mouse = accept;
+    }
+    if let Some(args) = overrides.additional_browser_args {
+      self.additional_browser_args.replace(args);
+    }
+    if let Some(effects) = overrides.window_effects {
+      self.window_effects.replace(effects);
+    }
+    if let Some(incognito) = overrides.incognito {
+      self.incognito = incognito;
+    }
+    if let Some(transparent) = overrides.transparent {
+      self.transparent = transparent;
+    }
+    if let Some(auto_resize) = overrides.auto_resize {
+      self.auto_resize = auto_resize;
+    }
+    if let Some(url) = overrides.proxy_url {
+      self.proxy_url.replace(url);
+    }
+    if let Some(policy) = overrides.background_throttling {
+      self.background_throttling.replace(policy);
+    }
+    if let Some(spellcheck) = overrides.spellcheck {
+      self.spellcheck.replace(spellcheck);
+    }
+    if let Some(focus) = overrides.focus {
+      self.focus = focus;
+    }
+    if let Some(ignore) = overrides.ignore_cursor_events {
+      self.ignore_cursor_events = ignore;
+    }
+
+    Ok(())
+  }
 }
 
 /// IPC handler.
@@ -65,6 +65,44 @@ pub enum WindowEvent {
   ThemeChanged(Theme),
 }
 
+/// An event from a webview.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum WebviewEvent {
+  /// The webview content started or stopped playing audio.
+  MediaPlaybackChanged(bool),
+  /// The webview bounds changed, either directly or because its window was resized.
+  /// Contains the new physical size of the webview.
+  Resized(dpi::PhysicalSize<u32>),
+  /// The webview gained or lost focus.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux / macOS / iOS / Android**: Unsupported.
+  Focused(bool),
+  /// The process rendering the webview content terminated unexpectedly, leaving the webview blank.
+  ///
+  /// The webview can be recreated with [`crate::WebviewDispatch::recover`].
+  ProcessCrashed {
+    /// Why the process terminated.
+    reason: ProcessCrashReason,
+  },
+}
+
+/// Why the process rendering a webview terminated, see [`WebviewEvent::ProcessCrashed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ProcessCrashReason {
+  /// The process crashed.
+  Crashed,
+  /// The process exceeded its memory limit.
+  OutOfMemory,
+  /// The process was terminated by the system or another application.
+  Terminated,
+  /// The platform did not report why the process terminated.
+  Unknown,
+}
+
 /// The file drop event payload.
 #[derive(Debug, Clone)]
 #[non_exhaustive]
@@ -414,17 +452,40 @@ pub struct PendingWindow<T: UserEvent, R: Runtime<T>> {
   pub webview: Option<PendingWebview<T, R>>,
 }
 
+/// The prefix of the window and webview labels reserved for Tauri.
+pub const RESERVED_LABEL_PREFIX: &str = "tauri:";
+
+/// Checks that a window or webview label only includes ASCII alphanumeric characters, `-`, `_`, `:` and `.`,
+/// and does not start with [`RESERVED_LABEL_PREFIX`].
+///
+/// The `lenient-labels` feature also allows the labels accepted by previous versions,
+/// which can include any alphanumeric character and `/`.
+pub fn validate_label(label: &str) -> crate::Result<()> {
+  if let Some(c) = label.chars().find(|c| !is_label_char(*c)) {
+    return Err(crate::Error::InvalidLabel(label.to_string(), c));
+  }
+  if label.starts_with(RESERVED_LABEL_PREFIX) {
+    return Err(crate::Error::ReservedLabel(label.to_string()));
+  }
+  Ok(())
+}
+
+fn is_label_char(c: char) -> bool {
+  c.is_ascii_alphanumeric()
+    || matches!(c, '-' | '_' | ':' | '.')
+    || (cfg!(feature = "lenient-labels") && (c.is_alphanumeric() || c == '/'))
+}
+
+/// Whether the label is valid, see [`validate_label`].
 pub fn is_label_valid(label: &str) -> bool {
-  label
-    .chars()
-    .all(|c| char::is_alphanumeric(c) || c == '-' || c == '/' || c == ':' || c == '_')
+  validate_label(label).is_ok()
 }
 
+/// Panics if the label is not valid, see [`validate_label`].
 pub fn assert_label_is_valid(label: &str) {
-  assert!(
-    is_label_valid(label),
-    "Window label must include only alphanumeric characters, `-`, `/`, `:` and `_`."
-  );
+  if let Err(e) = validate_label(label) {
+    panic!("{e}");
+  }
 }
 
 impl<T: UserEvent, R: Runtime<T>> PendingWindow<T, R> {
@@ -434,15 +495,12 @@ impl<T: UserEvent, R: Runtime<T>> PendingWindow<T, R> {
     label: impl Into<String>,
   ) -> crate::Result<Self> {
     let label = label.into();
-    if !is_label_valid(&label) {
-      Err(crate::Error::InvalidWindowLabel)
-    } else {
-      Ok(Self {
-        window_builder,
-        label,
-        webview: None,
-      })
-    }
+    validate_label(&label)?;
+    Ok(Self {
+      window_builder,
+      label,
+      webview: None,
+    })
   }
 
   /// Sets a webview to be created on the window.
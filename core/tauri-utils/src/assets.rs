@@ -114,6 +114,26 @@ pub trait Assets: Send + Sync + 'static {
 
   /// Gets the hashes for the CSP tag of the HTML on the given path.
   fn csp_hashes(&self, html_path: &AssetKey) -> Box<dyn Iterator<Item = CspHash<'_>> + '_>;
+
+  /// Whether the data returned by [`Self::iter`] is compressed, in which case [`Self::get`] decompresses it.
+  fn is_compressed(&self) -> bool {
+    false
+  }
+
+  /// Gets the size of the passed [`AssetKey`] once decompressed.
+  ///
+  /// The default implementation reads the whole asset with [`Self::get`].
+  fn original_size(&self, key: &AssetKey) -> Option<usize> {
+    self.get(key).map(|asset| asset.len())
+  }
+
+  /// Gets the content hash of the passed [`AssetKey`], computed when the assets were bundled.
+  ///
+  /// Assets with a hash are considered immutable: the hash is served as their `ETag` and their content may be cached.
+  fn content_hash(&self, key: &AssetKey) -> Option<&str> {
+    let _ = key;
+    None
+  }
 }
 
 /// [`Assets`] implementation that only contains compile-time compressed and embedded assets.
@@ -124,6 +144,10 @@ pub struct EmbeddedAssets {
   global_hashes: &'static [CspHash<'static>],
   // Hashes that are associated to the CSP of the HTML file identified by the map key (the HTML asset key).
   html_hashes: phf::Map<&'static str, &'static [CspHash<'static>]>,
+  // Sizes of the assets before compression.
+  original_sizes: phf::Map<&'static str, usize>,
+  // Hashes of the asset contents.
+  content_hashes: phf::Map<&'static str, &'static str>,
 }
 
 impl EmbeddedAssets {
@@ -137,6 +161,27 @@ impl EmbeddedAssets {
       assets: map,
       global_hashes,
       html_hashes,
+      original_sizes: phf::Map::new(),
+      content_hashes: phf::Map::new(),
+    }
+  }
+
+  /// Sets the size of each asset before compression, so it can be known without decompressing the asset.
+  pub const fn with_original_sizes(self, original_sizes: phf::Map<&'static str, usize>) -> Self {
+    Self {
+      original_sizes,
+      ..self
+    }
+  }
+
+  /// Sets the hash of each asset content, served as the asset `ETag`.
+  pub const fn with_content_hashes(
+    self,
+    content_hashes: phf::Map<&'static str, &'static str>,
+  ) -> Self {
+    Self {
+      content_hashes,
+      ..self
     }
   }
 }
@@ -186,4 +231,74 @@ impl Assets for EmbeddedAssets {
         .copied(),
     )
   }
+
+  fn is_compressed(&self) -> bool {
+    cfg!(feature = "compression")
+  }
+
+  fn original_size(&self, key: &AssetKey) -> Option<usize> {
+    let asset = self.assets.get(key.as_ref())?;
+    if !self.is_compressed() {
+      return Some(asset.len());
+    }
+    match self.original_sizes.get(key.as_ref()) {
+      Some(size) => Some(*size),
+      None => self.get(key).map(|asset| asset.len()),
+    }
+  }
+
+  fn content_hash(&self, key: &AssetKey) -> Option<&str> {
+    self.content_hashes.get(key.as_ref()).copied()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{AssetKey, Assets, EmbeddedAssets};
+  use phf::phf_map;
+
+  #[test]
+  fn original_size() {
+    let assets = EmbeddedAssets::new(
+      phf_map! {
+        "/index.html" => b"<html></html>",
+        "/main.js" => b"console.log('hello')",
+      },
+      &[],
+      phf_map! {},
+    )
+    .with_original_sizes(phf_map! {
+      "/index.html" => 42usize,
+    });
+
+    let expected = if assets.is_compressed() { 42 } else { 13 };
+    assert_eq!(
+      assets.original_size(&AssetKey::from("index.html")),
+      Some(expected)
+    );
+    assert_eq!(assets.original_size(&AssetKey::from("missing.js")), None);
+    #[cfg(not(feature = "compression"))]
+    assert_eq!(assets.original_size(&AssetKey::from("main.js")), Some(20));
+  }
+
+  #[test]
+  fn content_hash() {
+    let assets = EmbeddedAssets::new(
+      phf_map! {
+        "/index.html" => b"<html></html>",
+        "/main.js" => b"console.log('hello')",
+      },
+      &[],
+      phf_map! {},
+    )
+    .with_content_hashes(phf_map! {
+      "/index.html" => "af1349b9",
+    });
+
+    assert_eq!(
+      assets.content_hash(&AssetKey::from("index.html")),
+      Some("af1349b9")
+    );
+    assert_eq!(assets.content_hash(&AssetKey::from("main.js")), None);
+  }
 }
@@ -43,9 +43,9 @@ fn default_true() -> bool {
 }
 
 /// An URL to open on a Tauri webview window.
-#[derive(PartialEq, Eq, Debug, Clone, Serialize)]
+#[derive(PartialEq, Eq, Debug, Clone)]
 #[cfg_attr(feature = "schema", derive(JsonSchema))]
-#[serde(untagged)]
+#[cfg_attr(feature = "schema", schemars(untagged))]
 #[non_exhaustive]
 pub enum WebviewUrl {
   /// An external URL. Must use either the `http` or `https` schemes.
@@ -54,8 +54,30 @@ pub enum WebviewUrl {
   /// For instance, to load `tauri://localhost/users/john`,
   /// you can simply provide `users/john` in this configuration.
   App(PathBuf),
-  /// A custom protocol url, for example, `doom://index.html`
-  CustomProtocol(Url),
+  /// A page served by a registered custom protocol, for example, `doom://index.html`.
+  ///
+  /// The URL is resolved to the origin of the protocol on the current platform,
+  /// `http://<scheme>.localhost` on Windows and Android and `<scheme>://localhost` on the other platforms.
+  #[cfg_attr(feature = "schema", schemars(with = "Url"))]
+  CustomProtocol {
+    /// The name of the custom protocol.
+    scheme: String,
+    /// The path of the page, relative to the protocol origin.
+    path: String,
+  },
+}
+
+impl Serialize for WebviewUrl {
+  fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    match self {
+      Self::External(url) => url.serialize(serializer),
+      Self::App(path) => path.serialize(serializer),
+      Self::CustomProtocol { .. } => serializer.collect_str(self),
+    }
+  }
 }
 
 impl<'de> Deserialize<'de> for WebviewUrl {
@@ -71,13 +93,20 @@ impl<'de> Deserialize<'de> for WebviewUrl {
     }
 
     match WebviewUrlDeserializer::deserialize(deserializer)? {
-      WebviewUrlDeserializer::Url(u) => {
-        if u.scheme() == "https" || u.scheme() == "http" {
-          Ok(Self::External(u))
-        } else {
-          Ok(Self::CustomProtocol(u))
+      WebviewUrlDeserializer::Url(u) => match u.scheme() {
+        "https" | "http" | "data" => Ok(Self::External(u)),
+        scheme => {
+          // `doom://index.html` uses the host as the path
+          let path = match u.host_str() {
+            Some("localhost") | None => u.path().to_string(),
+            Some(host) => format!("{host}{}", u.path()),
+          };
+          Ok(Self::CustomProtocol {
+            scheme: scheme.into(),
+            path: path.trim_start_matches('/').into(),
+          })
         }
-      }
+      },
       WebviewUrlDeserializer::Path(p) => Ok(Self::App(p)),
     }
   }
@@ -86,8 +115,9 @@ impl<'de> Deserialize<'de> for WebviewUrl {
 impl fmt::Display for WebviewUrl {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     match self {
-      Self::External(url) | Self::CustomProtocol(url) => write!(f, "{url}"),
+      Self::External(url) => write!(f, "{url}"),
       Self::App(path) => write!(f, "{}", path.display()),
+      Self::CustomProtocol { scheme, path } => write!(f, "{scheme}://localhost/{path}"),
     }
   }
 }
@@ -1025,6 +1055,19 @@ pub struct WindowEffectsConfig {
   pub color: Option<Color>,
 }
 
+/// How the webview timers and rendering behave while the webview is hidden or in the background.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum BackgroundThrottlingPolicy {
+  /// Timers and rendering keep running at full speed in the background.
+  Disabled,
+  /// The webview is suspended while hidden: no timers fire and nothing is rendered.
+  Suspend,
+  /// Timers and rendering are throttled in the background, the platform default.
+  Throttle,
+}
+
 /// The window configuration object.
 ///
 /// See more: <https://tauri.app/v1/api/config#windowconfig>
@@ -1106,7 +1149,7 @@ pub struct WindowConfig {
   /// Whether the window starts as fullscreen or not.
   #[serde(default)]
   pub fullscreen: bool,
-  /// Whether the window will be initially focused or not.
+  /// Whether the window and its webview will be initially focused or not.
   #[serde(default = "default_true")]
   pub focus: bool,
   /// Whether the window is transparent or not.
@@ -1214,6 +1257,283 @@ pub struct WindowConfig {
   ///
   /// - **macOS**: Requires the `macos-proxy` feature flag and only compiles for macOS 14+.
   pub proxy_url: Option<Url>,
+  /// The list of commands the webview is allowed to invoke, on top of the capabilities.
+  ///
+  /// Entries are either plain command names or `plugin:<name>|<command>` patterns,
+  /// and a trailing `*` matches any suffix e.g. `plugin:event|*`.
+  /// All commands are allowed if not set.
+  #[serde(default)]
+  pub allowlist: Option<Vec<String>>,
+  /// How the webview timers and rendering behave while the webview is hidden or in the background.
+  ///
+  /// Uses the platform default if not set.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Only `disabled` has an effect, disabling the background timer throttling and renderer backgrounding.
+  /// - **macOS**: Requires macOS 14+.
+  /// - **Linux / iOS / Android**: Unsupported.
+  #[serde(default, alias = "background-throttling")]
+  pub background_throttling: Option<BackgroundThrottlingPolicy>,
+  /// Whether the spell checker marks the misspelled words of editable content.
+  ///
+  /// Uses the platform default if not set.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: Applies to all webviews sharing the data directory of the webview.
+  /// - **Windows / macOS / iOS / Android**: Sets the `spellcheck` attribute of the document element,
+  ///   unless the page sets it.
+  #[serde(default)]
+  pub spellcheck: Option<bool>,
+  /// The webviews of the window.
+  ///
+  /// When set, the window is created with these webviews instead of a single webview,
+  /// and the webview options of the window such as `url` and `userAgent` are ignored.
+  /// The webview labels must be unique across all windows.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android / iOS**: Only the first webview is created, filling the window.
+  #[serde(default)]
+  pub webviews: Vec<WebviewConfig>,
+}
+
+impl WindowConfig {
+  /// The labels of the webviews created for this window:
+  /// the labels of its [`Self::webviews`] or the window label for a single webview window.
+  pub fn webview_labels(&self) -> Vec<&str> {
+    if self.webviews.is_empty() {
+      vec![self.label.as_str()]
+    } else {
+      self.webviews.iter().map(|w| w.label.as_str()).collect()
+    }
+  }
+}
+
+/// A dimension of the [`WebviewBounds`], either in logical pixels or as a percentage of the window inner size e.g. `"50%"`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum WebviewDimension {
+  /// A size in logical pixels.
+  Logical(f64),
+  /// A percentage of the window inner size.
+  Percentage(f64),
+}
+
+impl WebviewDimension {
+  /// Resolves the dimension in logical pixels against the given window inner size in logical pixels.
+  pub fn resolve(&self, window_size: f64) -> f64 {
+    match self {
+      Self::Logical(value) => *value,
+      Self::Percentage(percentage) => window_size * percentage / 100.0,
+    }
+  }
+}
+
+impl Serialize for WebviewDimension {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    match self {
+      Self::Logical(value) => serializer.serialize_f64(*value),
+      Self::Percentage(percentage) => serializer.serialize_str(&format!("{percentage}%")),
+    }
+  }
+}
+
+impl<'de> Deserialize<'de> for WebviewDimension {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Value {
+      Number(f64),
+      String(String),
+    }
+
+    match Value::deserialize(deserializer)? {
+      Value::Number(value) => Ok(Self::Logical(value)),
+      Value::String(value) => value
+        .strip_suffix('%')
+        .and_then(|percentage| percentage.trim().parse::<f64>().ok())
+        .filter(|percentage| percentage.is_finite())
+        .map(Self::Percentage)
+        .ok_or_else(|| {
+          DeError::custom(format!(
+            "invalid webview dimension `{value}`, expected a number or a percentage e.g. `50%`"
+          ))
+        }),
+    }
+  }
+}
+
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for WebviewDimension {
+  fn schema_name() -> std::string::String {
+    "WebviewDimension".to_owned()
+  }
+
+  fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+    let any_of = vec![
+      schemars::_private::apply_metadata(
+        gen.subschema_for::<f64>(),
+        schemars::schema::Metadata {
+          description: Some("A size in logical pixels.".to_owned()),
+          ..Default::default()
+        },
+      ),
+      schemars::schema::SchemaObject {
+        instance_type: Some(schemars::schema::InstanceType::String.into()),
+        string: Some(Box::new(schemars::schema::StringValidation {
+          pattern: Some(r"^\s*-?\d+(\.\d+)?\s*%$".to_owned()),
+          ..Default::default()
+        })),
+        metadata: Some(Box::new(schemars::schema::Metadata {
+          description: Some("A percentage of the window inner size e.g. `50%`.".to_owned()),
+          ..Default::default()
+        })),
+        ..Default::default()
+      }
+      .into(),
+    ];
+
+    schemars::schema::SchemaObject {
+      subschemas: Some(Box::new(schemars::schema::SubschemaValidation {
+        any_of: Some(any_of),
+        ..Default::default()
+      })),
+      metadata: Some(Box::new(schemars::schema::Metadata {
+        description: Some("A dimension of the webview bounds, either in logical pixels or as a percentage of the window inner size e.g. `\"50%\"`.".to_owned()),
+        ..Default::default()
+      })),
+      ..Default::default()
+    }
+    .into()
+  }
+}
+
+/// The position and size of a webview in its window, see [`WebviewConfig::bounds`].
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct WebviewBounds {
+  /// The horizontal position of the webview's top left corner. Defaults to `0`.
+  #[serde(default = "default_webview_offset")]
+  pub x: WebviewDimension,
+  /// The vertical position of the webview's top left corner. Defaults to `0`.
+  #[serde(default = "default_webview_offset")]
+  pub y: WebviewDimension,
+  /// The webview width. Defaults to `"100%"`.
+  #[serde(default = "default_webview_extent")]
+  pub width: WebviewDimension,
+  /// The webview height. Defaults to `"100%"`.
+  #[serde(default = "default_webview_extent")]
+  pub height: WebviewDimension,
+}
+
+impl Default for WebviewBounds {
+  fn default() -> Self {
+    Self {
+      x: default_webview_offset(),
+      y: default_webview_offset(),
+      width: default_webview_extent(),
+      height: default_webview_extent(),
+    }
+  }
+}
+
+impl WebviewBounds {
+  /// Whether the bounds only depend on the window size, so the webview follows the window resizes:
+  /// the size is a percentage and the position is either a percentage or `0`.
+  pub fn is_relative(&self) -> bool {
+    let is_percentage =
+      |dimension: &WebviewDimension| matches!(dimension, WebviewDimension::Percentage(_));
+    [self.x, self.y]
+      .iter()
+      .all(|offset| is_percentage(offset) || *offset == WebviewDimension::Logical(0.0))
+      && [self.width, self.height].iter().all(is_percentage)
+  }
+}
+
+fn default_webview_offset() -> WebviewDimension {
+  WebviewDimension::Logical(0.0)
+}
+
+fn default_webview_extent() -> WebviewDimension {
+  WebviewDimension::Percentage(100.0)
+}
+
+/// A webview of a multi-webview window, see [`WindowConfig::webviews`].
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct WebviewConfig {
+  /// The webview identifier. It must be alphanumeric and unique across all webviews.
+  pub label: String,
+  /// The webview URL.
+  #[serde(default)]
+  pub url: WebviewUrl,
+  /// The position and size of the webview in its window.
+  ///
+  /// When the size is a percentage and the position a percentage or `0`, the webview is resized with its window.
+  #[serde(default)]
+  pub bounds: WebviewBounds,
+  /// The user agent for the webview.
+  #[serde(alias = "user-agent")]
+  pub user_agent: Option<String>,
+  /// Whether the file drop is enabled or not on the webview. By default it is enabled.
+  ///
+  /// Disabling it is required to use drag and drop on the frontend on Windows.
+  #[serde(default = "default_true", alias = "file-drop-enabled")]
+  pub file_drop_enabled: bool,
+  /// Whether the webview should be focused when created.
+  #[serde(default)]
+  pub focus: bool,
+  /// Whether the webview is transparent or not.
+  ///
+  /// Note that on `macOS` this requires the `macos-private-api` feature flag, enabled under `tauri > macOSPrivateApi`.
+  /// WARNING: Using private APIs on `macOS` prevents your application from being accepted to the `App Store`.
+  #[serde(default)]
+  pub transparent: bool,
+  /// Whether clicking an inactive window also clicks through to the webview on macOS.
+  #[serde(default, alias = "accept-first-mouse")]
+  pub accept_first_mouse: bool,
+  /// Defines additional browser arguments on Windows, see [`WindowConfig::additional_browser_args`].
+  #[serde(default, alias = "additional-browser-args")]
+  pub additional_browser_args: Option<String>,
+  /// Whether or not the webview should be launched in incognito mode.
+  ///
+  ///  ## Platform-specific:
+  ///
+  ///  - **Android**: Unsupported.
+  #[serde(default)]
+  pub incognito: bool,
+  /// The proxy URL for the WebView for all network requests, see [`WindowConfig::proxy_url`].
+  pub proxy_url: Option<Url>,
+  /// The list of commands the webview is allowed to invoke, see [`WindowConfig::allowlist`].
+  #[serde(default)]
+  pub allowlist: Option<Vec<String>>,
+  /// How the webview timers and rendering behave while the webview is hidden or in the background,
+  /// see [`WindowConfig::background_throttling`].
+  #[serde(default, alias = "background-throttling")]
+  pub background_throttling: Option<BackgroundThrottlingPolicy>,
+  /// Whether the spell checker marks the misspelled words of editable content, see [`WindowConfig::spellcheck`].
+  #[serde(default)]
+  pub spellcheck: Option<bool>,
+  /// Background effects shown behind the webview region.
+  ///
+  /// Requires the webview to be transparent.
+  ///
+  /// ## Platform-specific:
+  ///
+  /// - **Windows**: The effects are applied to the whole window.
+  /// - **Linux**: Unsupported
+  #[serde(default)]
+  pub effects: Option<WindowEffectsConfig>,
 }
 
 impl Default for WindowConfig {
@@ -1259,6 +1579,10 @@ impl Default for WindowConfig {
       incognito: false,
       parent: None,
       proxy_url: None,
+      allowlist: None,
+      background_throttling: None,
+      spellcheck: None,
+      webviews: Vec::new(),
     }
   }
 }
@@ -1513,6 +1837,32 @@ pub struct AssetProtocolConfig {
   /// Enables the asset protocol.
   #[serde(default)]
   pub enable: bool,
+  /// Constraints on the files served from some paths of the scope.
+  ///
+  /// When several entries match a file, the first one is used.
+  #[serde(default)]
+  pub limits: Vec<AssetScopeLimits>,
+}
+
+/// Constraints on the files served by the asset protocol from a path of its scope.
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AssetScopeLimits {
+  /// The glob pattern of the paths these constraints apply to,
+  /// which can start with the same variables as the scope entries.
+  pub path: PathBuf,
+  /// The maximum size of the files, in bytes.
+  ///
+  /// Larger files are rejected with a `413 Payload Too Large` response,
+  /// unless only ranges of the file totalling at most this size are requested.
+  #[serde(default, alias = "max-size")]
+  pub max_size: Option<u64>,
+  /// Whether range requests are served.
+  ///
+  /// When disabled, the `Range` header is ignored and the whole file is served. Defaults to `true`.
+  #[serde(default = "default_true", alias = "allow-ranges")]
+  pub allow_ranges: bool,
 }
 
 /// Security configuration.
@@ -1558,6 +1908,14 @@ pub struct SecurityConfig {
   /// The pattern to use.
   #[serde(default)]
   pub pattern: PatternKind,
+  /// Enables cross-origin isolation, required by `SharedArrayBuffer` and WebAssembly threads.
+  ///
+  /// The HTML documents served by the `tauri://localhost` protocol get the `Cross-Origin-Opener-Policy: same-origin`
+  /// and `Cross-Origin-Embedder-Policy: require-corp` headers, unless they already have them,
+  /// and the `asset` protocol responses get `Cross-Origin-Resource-Policy: cross-origin` so they can still be embedded.
+  /// Other cross-origin resources must be served with CORS or a `Cross-Origin-Resource-Policy` header.
+  #[serde(default, alias = "cross-origin-isolation")]
+  pub cross_origin_isolation: bool,
 }
 
 /// The application pattern.
@@ -1581,6 +1939,42 @@ impl Default for PatternKind {
   }
 }
 
+/// Caching of the frontend assets served by the `tauri://localhost` protocol.
+///
+/// Assets embedded in the binary are served with an `ETag` computed from their content at build time,
+/// so the webview can revalidate them without downloading them again.
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AssetCacheConfig {
+  /// The `Cache-Control` header sent with the assets that have an `ETag`. Defaults to `no-cache`,
+  /// meaning the webview revalidates its cached assets on every load.
+  #[serde(default = "default_cache_control", alias = "cache-control")]
+  pub cache_control: String,
+  /// The maximum size in bytes of the decompressed assets kept in memory. Defaults to 32 MiB.
+  ///
+  /// The least recently served assets are evicted first. Set to `0` to disable the memory cache.
+  #[serde(default = "default_memory_cache_size", alias = "memory-cache-size")]
+  pub memory_cache_size: usize,
+}
+
+impl Default for AssetCacheConfig {
+  fn default() -> Self {
+    Self {
+      cache_control: default_cache_control(),
+      memory_cache_size: default_memory_cache_size(),
+    }
+  }
+}
+
+fn default_cache_control() -> String {
+  "no-cache".into()
+}
+
+fn default_memory_cache_size() -> usize {
+  32 * 1024 * 1024
+}
+
 /// The App configuration object.
 ///
 /// See more: <https://tauri.app/v1/api/config#appconfig>
@@ -1604,6 +1998,9 @@ pub struct AppConfig {
   /// Whether we should inject the Tauri API on `window.__TAURI__` or not.
   #[serde(default, alias = "with-global-tauri")]
   pub with_global_tauri: bool,
+  /// Caching of the frontend assets.
+  #[serde(default, alias = "asset-cache")]
+  pub asset_cache: AssetCacheConfig,
 }
 
 impl AppConfig {
@@ -1637,6 +2034,16 @@ impl AppConfig {
     features.sort_unstable();
     features
   }
+
+  /// Returns the first webview label used more than once across the [`Self::windows`], if any.
+  pub fn duplicated_webview_label(&self) -> Option<&str> {
+    let mut labels = std::collections::HashSet::new();
+    self
+      .windows
+      .iter()
+      .flat_map(WindowConfig::webview_labels)
+      .find(|label| !labels.insert(*label))
+  }
 }
 
 /// Configuration for application tray icon.
@@ -1778,6 +2185,16 @@ pub struct BuildConfig {
   /// and point to a web assets directory, and Tauri CLI will run its built-in dev server and provide a simple hot-reload experience.
   #[serde(alias = "dev-url")]
   pub dev_url: Option<Url>,
+  /// Serve the [`devUrl`](BuildConfig::dev_url) through the `tauri` custom protocol in development
+  /// so the webview origin is the same in development and production, avoiding mixed-content and cookie issues.
+  ///
+  /// Requests are forwarded to the dev server, which must be reachable from the app.
+  /// Websocket connections such as the HMR client are not proxied and must connect to the dev server directly,
+  /// for instance with the `server.hmr.host` option in Vite.
+  ///
+  /// This is always enabled on mobile.
+  #[serde(default, alias = "dev-proxy")]
+  pub dev_proxy: bool,
   /// The path to the application assets (usually the `dist` folder of your javascript bundler)
   /// or a URL that could be either a custom protocol registered in the tauri app (for example: `myprotocol://`)
   /// or a remote URL (for example: `https://site.com/app`).
@@ -2000,6 +2417,7 @@ fn default_build() -> BuildConfig {
   BuildConfig {
     runner: None,
     dev_url: None,
+    dev_proxy: false,
     frontend_dist: None,
     before_dev_command: None,
     before_build_command: None,
@@ -2034,9 +2452,10 @@ mod build {
           let url = url_lit(url);
           quote! { #prefix::External(#url) }
         }
-        Self::CustomProtocol(url) => {
-          let url = url_lit(url);
-          quote! { #prefix::CustomProtocol(#url) }
+        Self::CustomProtocol { scheme, path } => {
+          let scheme = str_lit(scheme);
+          let path = str_lit(path);
+          quote! { #prefix::CustomProtocol { scheme: #scheme, path: #path } }
         }
       })
     }
@@ -2089,6 +2508,18 @@ mod build {
     }
   }
 
+  impl ToTokens for BackgroundThrottlingPolicy {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let prefix = quote! { ::tauri::utils::config::BackgroundThrottlingPolicy };
+
+      tokens.append_all(match self {
+        Self::Disabled => quote! { #prefix::Disabled },
+        Self::Suspend => quote! { #prefix::Suspend },
+        Self::Throttle => quote! { #prefix::Throttle },
+      })
+    }
+  }
+
   impl ToTokens for crate::WindowEffect {
     fn to_tokens(&self, tokens: &mut TokenStream) {
       let prefix = quote! { ::tauri::utils::WindowEffect };
@@ -2181,6 +2612,10 @@ mod build {
       let window_effects = opt_lit(self.window_effects.as_ref());
       let incognito = self.incognito;
       let parent = opt_str_lit(self.parent.as_ref());
+      let allowlist = opt_vec_lit(self.allowlist.as_ref(), str_lit);
+      let background_throttling = opt_lit(self.background_throttling.as_ref());
+      let spellcheck = opt_lit(self.spellcheck.as_ref());
+      let webviews = vec_lit(&self.webviews, |w| w);
 
       literal_struct!(
         tokens,
@@ -2224,7 +2659,80 @@ mod build {
         shadow,
         window_effects,
         incognito,
-        parent
+        parent,
+        allowlist,
+        background_throttling,
+        spellcheck,
+        webviews
+      );
+    }
+  }
+
+  impl ToTokens for WebviewDimension {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let prefix = quote! { ::tauri::utils::config::WebviewDimension };
+
+      tokens.append_all(match self {
+        Self::Logical(value) => quote! { #prefix::Logical(#value) },
+        Self::Percentage(percentage) => quote! { #prefix::Percentage(#percentage) },
+      })
+    }
+  }
+
+  impl ToTokens for WebviewBounds {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let x = &self.x;
+      let y = &self.y;
+      let width = &self.width;
+      let height = &self.height;
+
+      literal_struct!(
+        tokens,
+        ::tauri::utils::config::WebviewBounds,
+        x,
+        y,
+        width,
+        height
+      );
+    }
+  }
+
+  impl ToTokens for WebviewConfig {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let label = str_lit(&self.label);
+      let url = &self.url;
+      let bounds = &self.bounds;
+      let user_agent = opt_str_lit(self.user_agent.as_ref());
+      let file_drop_enabled = self.file_drop_enabled;
+      let focus = self.focus;
+      let transparent = self.transparent;
+      let accept_first_mouse = self.accept_first_mouse;
+      let additional_browser_args = opt_str_lit(self.additional_browser_args.as_ref());
+      let incognito = self.incognito;
+      let proxy_url = opt_lit(self.proxy_url.as_ref().map(url_lit).as_ref());
+      let allowlist = opt_vec_lit(self.allowlist.as_ref(), str_lit);
+      let background_throttling = opt_lit(self.background_throttling.as_ref());
+      let spellcheck = opt_lit(self.spellcheck.as_ref());
+      let effects = opt_lit(self.effects.as_ref());
+
+      literal_struct!(
+        tokens,
+        ::tauri::utils::config::WebviewConfig,
+        label,
+        url,
+        bounds,
+        user_agent,
+        file_drop_enabled,
+        focus,
+        transparent,
+        accept_first_mouse,
+        additional_browser_args,
+        incognito,
+        proxy_url,
+        allowlist,
+        background_throttling,
+        spellcheck,
+        effects
       );
     }
   }
@@ -2356,6 +2864,7 @@ mod build {
   impl ToTokens for BuildConfig {
     fn to_tokens(&self, tokens: &mut TokenStream) {
       let dev_url = opt_lit(self.dev_url.as_ref().map(url_lit).as_ref());
+      let dev_proxy = self.dev_proxy;
       let frontend_dist = opt_lit(self.frontend_dist.as_ref());
       let runner = quote!(None);
       let before_dev_command = quote!(None);
@@ -2368,6 +2877,7 @@ mod build {
         ::tauri::utils::config::BuildConfig,
         runner,
         dev_url,
+        dev_proxy,
         frontend_dist,
         before_dev_command,
         before_build_command,
@@ -2458,6 +2968,7 @@ mod build {
       let dangerous_disable_asset_csp_modification = &self.dangerous_disable_asset_csp_modification;
       let asset_protocol = &self.asset_protocol;
       let pattern = &self.pattern;
+      let cross_origin_isolation = self.cross_origin_isolation;
 
       literal_struct!(
         tokens,
@@ -2467,7 +2978,8 @@ mod build {
         freeze_prototype,
         dangerous_disable_asset_csp_modification,
         asset_protocol,
-        pattern
+        pattern,
+        cross_origin_isolation
       );
     }
   }
@@ -2515,7 +3027,38 @@ mod build {
   impl ToTokens for AssetProtocolConfig {
     fn to_tokens(&self, tokens: &mut TokenStream) {
       let scope = &self.scope;
-      tokens.append_all(quote! { ::tauri::utils::config::AssetProtocolConfig { scope: #scope, ..Default::default() } })
+      let limits = vec_lit(&self.limits, |l| l);
+      tokens.append_all(quote! { ::tauri::utils::config::AssetProtocolConfig { scope: #scope, limits: #limits, ..Default::default() } })
+    }
+  }
+
+  impl ToTokens for AssetScopeLimits {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let path = path_buf_lit(&self.path);
+      let max_size = opt_lit(self.max_size.as_ref());
+      let allow_ranges = self.allow_ranges;
+
+      literal_struct!(
+        tokens,
+        ::tauri::utils::config::AssetScopeLimits,
+        path,
+        max_size,
+        allow_ranges
+      );
+    }
+  }
+
+  impl ToTokens for AssetCacheConfig {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let cache_control = str_lit(&self.cache_control);
+      let memory_cache_size = self.memory_cache_size;
+
+      literal_struct!(
+        tokens,
+        ::tauri::utils::config::AssetCacheConfig,
+        cache_control,
+        memory_cache_size
+      );
     }
   }
 
@@ -2526,6 +3069,7 @@ mod build {
       let tray_icon = opt_lit(self.tray_icon.as_ref());
       let macos_private_api = self.macos_private_api;
       let with_global_tauri = self.with_global_tauri;
+      let asset_cache = &self.asset_cache;
 
       literal_struct!(
         tokens,
@@ -2534,7 +3078,8 @@ mod build {
         security,
         tray_icon,
         macos_private_api,
-        with_global_tauri
+        with_global_tauri,
+        asset_cache
       );
     }
   }
@@ -2606,16 +3151,22 @@ mod test {
         dangerous_disable_asset_csp_modification: DisabledCspModificationKind::Flag(false),
         asset_protocol: AssetProtocolConfig::default(),
         pattern: Default::default(),
+        cross_origin_isolation: false,
       },
       tray_icon: None,
       macos_private_api: false,
       with_global_tauri: false,
+      asset_cache: AssetCacheConfig {
+        cache_control: "no-cache".into(),
+        memory_cache_size: 32 * 1024 * 1024,
+      },
     };
 
     // create a build config
     let build = BuildConfig {
       runner: None,
       dev_url: None,
+      dev_proxy: false,
       frontend_dist: None,
       before_dev_command: None,
       before_build_command: None,
@@ -2651,4 +3202,78 @@ mod test {
     assert_eq!(d_bundle, bundle);
     assert_eq!(d_windows, app.windows);
   }
+
+  #[test]
+  fn custom_protocol_url() {
+    let url = |value: &str| serde_json::from_value::<WebviewUrl>(serde_json::json!(value)).unwrap();
+    let doom = WebviewUrl::CustomProtocol {
+      scheme: "doom".into(),
+      path: "index.html".into(),
+    };
+
+    assert_eq!(url("doom://index.html"), doom);
+    assert_eq!(url("doom://localhost/index.html"), doom);
+    assert_eq!(
+      url("doom://localhost/levels/e1m1.html"),
+      WebviewUrl::CustomProtocol {
+        scheme: "doom".into(),
+        path: "levels/e1m1.html".into(),
+      }
+    );
+    assert!(matches!(
+      url("data:text/html,<p></p>"),
+      WebviewUrl::External(_)
+    ));
+    assert_eq!(
+      serde_json::to_value(&doom).unwrap(),
+      serde_json::json!("doom://localhost/index.html")
+    );
+  }
+
+  #[test]
+  fn window_webviews() {
+    let app: AppConfig = serde_json::from_value(serde_json::json!({
+      "windows": [
+        {
+          "label": "main",
+          "webviews": [
+            { "label": "sidebar", "bounds": { "width": 200 } },
+            { "label": "content", "url": "content.html", "bounds": { "x": 200, "width": "75%" } }
+          ]
+        },
+        { "label": "settings" }
+      ]
+    }))
+    .unwrap();
+
+    let main = &app.windows[0];
+    assert_eq!(main.webview_labels(), vec!["sidebar", "content"]);
+    assert_eq!(app.windows[1].webview_labels(), vec!["settings"]);
+
+    let sidebar = &main.webviews[0].bounds;
+    assert_eq!(sidebar.width, WebviewDimension::Logical(200.0));
+    assert_eq!(sidebar.height, WebviewDimension::Percentage(100.0));
+    assert!(!sidebar.is_relative());
+    let content = &main.webviews[1];
+    assert_eq!(content.url, WebviewUrl::App("content.html".into()));
+    assert_eq!(content.bounds.width.resolve(800.0), 600.0);
+    assert!(content.file_drop_enabled);
+    assert!(WebviewBounds::default().is_relative());
+    assert_eq!(app.duplicated_webview_label(), None);
+
+    let duplicated: AppConfig = serde_json::from_value(serde_json::json!({
+      "windows": [
+        { "label": "main", "webviews": [{ "label": "settings" }] },
+        { "label": "settings" }
+      ]
+    }))
+    .unwrap();
+    assert_eq!(duplicated.duplicated_webview_label(), Some("settings"));
+
+    assert!(serde_json::from_value::<WebviewDimension>(serde_json::json!("50px")).is_err());
+    assert_eq!(
+      serde_json::to_value(WebviewDimension::Percentage(50.0)).unwrap(),
+      serde_json::json!("50%")
+    );
+  }
 }
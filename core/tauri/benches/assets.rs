@@ -0,0 +1,95 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{borrow::Cow, collections::HashMap};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use tauri::{
+  test::{mock_builder, mock_context, MockRuntime},
+  utils::assets::{AssetKey, Assets, CspHash},
+  App,
+};
+
+const ASSET_COUNT: usize = 500;
+
+/// Brotli compressed assets with a content hash, like the assets embedded by `tauri::generate_context!`.
+struct CompressedAssets {
+  assets: HashMap<String, (Vec<u8>, String)>,
+}
+
+impl CompressedAssets {
+  fn new() -> Self {
+    let assets = (0..ASSET_COUNT)
+      .map(|i| {
+        let content = (0..500)
+          .map(|line| format!("export const value{line} = {};\n", i * line))
+          .collect::<String>();
+        let mut compressed = Vec::new();
+        brotli::BrotliCompress(
+          &mut content.as_bytes(),
+          &mut compressed,
+          &Default::default(),
+        )
+        .unwrap();
+        (format!("/chunk-{i}.js"), (compressed, format!("{i:064x}")))
+      })
+      .collect();
+    Self { assets }
+  }
+}
+
+impl Assets for CompressedAssets {
+  fn get(&self, key: &AssetKey) -> Option<Cow<'_, [u8]>> {
+    let (mut compressed, _) = self.assets.get(key.as_ref()).map(|(c, h)| (&c[..], h))?;
+    let mut decompressed = Vec::new();
+    brotli::BrotliDecompress(&mut compressed, &mut decompressed).ok()?;
+    Some(Cow::Owned(decompressed))
+  }
+
+  fn iter(&self) -> Box<dyn Iterator<Item = (&&str, &&[u8])> + '_> {
+    Box::new(std::iter::empty())
+  }
+
+  fn csp_hashes(&self, _html_path: &AssetKey) -> Box<dyn Iterator<Item = CspHash<'_>> + '_> {
+    Box::new(std::iter::empty())
+  }
+
+  fn is_compressed(&self) -> bool {
+    true
+  }
+
+  fn content_hash(&self, key: &AssetKey) -> Option<&str> {
+    self.assets.get(key.as_ref()).map(|(_, hash)| hash.as_str())
+  }
+}
+
+fn app(memory_cache_size: usize) -> App<MockRuntime> {
+  let mut context = mock_context(CompressedAssets::new());
+  context.config_mut().app.asset_cache.memory_cache_size = memory_cache_size;
+  mock_builder().build(context).unwrap()
+}
+
+/// Resolves every asset, as a page reload with many assets does.
+fn reload(app: &App<MockRuntime>) {
+  let resolver = app.asset_resolver();
+  for i in 0..ASSET_COUNT {
+    black_box(resolver.get(format!("/chunk-{i}.js")).unwrap());
+  }
+}
+
+fn assets(c: &mut Criterion) {
+  let uncached = app(0);
+  c.bench_function("reload assets without the memory cache", |b| {
+    b.iter(|| reload(&uncached))
+  });
+
+  let cached = app(64 * 1024 * 1024);
+  reload(&cached);
+  c.bench_function("reload assets from the memory cache", |b| {
+    b.iter(|| reload(&cached))
+  });
+}
+
+criterion_group!(benches, assets);
+criterion_main!(benches);
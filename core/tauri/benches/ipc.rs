@@ -0,0 +1,101 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use tauri::{
+  ipc::{CallbackFn, InvokeBody, RawBody, Request},
+  test::{get_ipc_response, mock_builder, mock_context, noop_assets},
+  webview::InvokeRequest,
+  WebviewWindowBuilder,
+};
+
+const LARGE_BODY_SIZE: usize = 100 * 1024 * 1024;
+
+#[tauri::command]
+fn noop() {}
+
+/// Reads a raw body through the low-level request, which needs a copy to own the bytes.
+#[tauri::command]
+fn copy_request_body(request: Request<'_>) -> usize {
+  match request.body() {
+    InvokeBody::Raw(body) => black_box(body.to_vec()).len(),
+    InvokeBody::Json(_) => 0,
+  }
+}
+
+/// Borrows the raw body from the request, without copying it.
+#[tauri::command]
+fn borrow_raw_body(body: RawBody<'_>) -> usize {
+  black_box(body.into_inner()).len()
+}
+
+fn request(body: InvokeBody) -> InvokeRequest {
+  command_request("noop", body)
+}
+
+fn command_request(cmd: &str, body: InvokeBody) -> InvokeRequest {
+  InvokeRequest {
+    cmd: cmd.into(),
+    callback: CallbackFn(0),
+    error: CallbackFn(1),
+    body,
+    headers: Default::default(),
+  }
+}
+
+fn invoke(c: &mut Criterion) {
+  let app = mock_builder()
+    .invoke_handler(tauri::generate_handler![
+      noop,
+      copy_request_body,
+      borrow_raw_body
+    ])
+    .build(mock_context(noop_assets()))
+    .unwrap();
+  let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+    .build()
+    .unwrap();
+
+  c.bench_function("invoke noop", |b| {
+    b.iter(|| get_ipc_response(&webview, black_box(request(InvokeBody::default()))).unwrap())
+  });
+
+  let payload = serde_json::json!({ "items": vec!["tauri"; 10_000] });
+  c.bench_function("invoke noop with a large payload", |b| {
+    b.iter(|| {
+      get_ipc_response(
+        &webview,
+        black_box(request(InvokeBody::Json(payload.clone()))),
+      )
+      .unwrap()
+    })
+  });
+
+  c.bench_function("invoke noop with a raw payload", |b| {
+    b.iter(|| {
+      get_ipc_response(
+        &webview,
+        black_box(request(InvokeBody::Raw(vec![0; 1024 * 1024]))),
+      )
+      .unwrap()
+    })
+  });
+
+  // the request is created outside of the measurement, so only the copies made by the IPC path are measured
+  let mut group = c.benchmark_group("100 MB raw body");
+  group.sample_size(10);
+  for cmd in ["copy_request_body", "borrow_raw_body"] {
+    group.bench_function(cmd, |b| {
+      b.iter_batched(
+        || command_request(cmd, InvokeBody::Raw(vec![1; LARGE_BODY_SIZE])),
+        |request| get_ipc_response(&webview, request).unwrap(),
+        BatchSize::PerIteration,
+      )
+    });
+  }
+  group.finish();
+}
+
+criterion_group!(benches, invoke);
+criterion_main!(benches);
@@ -38,6 +38,7 @@ const PLUGINS: &[(&str, &[(&str, bool)])] = &[
       ("unlisten", true),
       ("emit", true),
       ("emit_to", true),
+      ("poll_bridge", true),
     ],
   ),
   (
@@ -118,12 +119,18 @@ const PLUGINS: &[(&str, &[(&str, bool)])] = &[
       // getters
       ("webview_position", true),
       ("webview_size", true),
+      ("webview_theme", true),
       // setters
       ("webview_close", false),
       ("set_webview_size", false),
       ("set_webview_position", false),
       ("set_webview_focus", false),
+      ("set_webview_theme", false),
+      ("set_webview_background_throttling", false),
       ("print", false),
+      // messaging
+      ("post_webview_message", false),
+      ("take_webview_message", true),
       // internal
       ("internal_toggle_devtools", true),
     ],
@@ -7,10 +7,17 @@ package app.tauri
 import android.app.Activity
 import android.os.Environment
 import app.tauri.annotation.Command
+import app.tauri.annotation.InvokeArg
 import app.tauri.annotation.TauriPlugin
 import app.tauri.plugin.Plugin
 import app.tauri.plugin.Invoke
 import app.tauri.plugin.JSObject
+import java.io.File
+
+@InvokeArg
+class IsSharedStorageArgs {
+    lateinit var path: String
+}
 
 @TauriPlugin
 class PathPlugin(private val activity: Activity): Plugin(activity) {
@@ -20,6 +27,12 @@ class PathPlugin(private val activity: Activity): Plugin(activity) {
         invoke.resolve(obj)
     }
 
+    // the app-specific external files directory, falling back to the internal files directory when the external storage is not available
+    private fun appFilesDir(type: String): String {
+        val dir = activity.getExternalFilesDir(type) ?: File(activity.filesDir, type).apply { mkdirs() }
+        return dir.absolutePath
+    }
+
     @Command
     fun getAudioDir(invoke: Invoke) {
         resolvePath(invoke, activity.getExternalFilesDir(Environment.DIRECTORY_MUSIC)?.absolutePath)
@@ -42,17 +55,17 @@ class PathPlugin(private val activity: Activity): Plugin(activity) {
 
     @Command
     fun getDocumentDir(invoke: Invoke) {
-        resolvePath(invoke, activity.getExternalFilesDir(Environment.DIRECTORY_DOCUMENTS)?.absolutePath)
+        resolvePath(invoke, appFilesDir(Environment.DIRECTORY_DOCUMENTS))
     }
 
     @Command
     fun getDownloadDir(invoke: Invoke) {
-        resolvePath(invoke, activity.getExternalFilesDir(Environment.DIRECTORY_DOWNLOADS)?.absolutePath)
+        resolvePath(invoke, appFilesDir(Environment.DIRECTORY_DOWNLOADS))
     }
 
     @Command
     fun getPictureDir(invoke: Invoke) {
-        resolvePath(invoke, activity.getExternalFilesDir(Environment.DIRECTORY_PICTURES)?.absolutePath)
+        resolvePath(invoke, appFilesDir(Environment.DIRECTORY_PICTURES))
     }
 
     @Command
@@ -75,4 +88,23 @@ class PathPlugin(private val activity: Activity): Plugin(activity) {
     fun getCacheDir(invoke: Invoke) {
         resolvePath(invoke, activity.cacheDir.absolutePath)
     }
+
+    @Command
+    fun isSharedStorage(invoke: Invoke) {
+        val args = invoke.parseArgs(IsSharedStorageArgs::class.java)
+        val file = File(args.path).canonicalFile
+
+        // app-specific directories (`Android/data/<package>` on each storage volume) do not require any permission
+        val appDirs = (activity.getExternalFilesDirs(null) + activity.externalCacheDirs)
+            .filterNotNull()
+            .mapNotNull { it.canonicalFile.parentFile }
+        val isAppSpecific = file.startsWith(activity.dataDir.canonicalFile) || appDirs.any { file.startsWith(it) }
+
+        val volumeRoots = appDirs.map { File(it.path.substringBefore("/Android/data/")) }
+        val isShared = !isAppSpecific && volumeRoots.any { file.startsWith(it) }
+
+        val obj = JSObject()
+        obj.put("value", isShared)
+        invoke.resolve(obj)
+    }
 }
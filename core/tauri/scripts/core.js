@@ -24,12 +24,12 @@
       var prop = `_${identifier}`
 
       Object.defineProperty(window, prop, {
-        value: (result) => {
+        value: (result, meta) => {
           if (once) {
             Reflect.deleteProperty(window, prop)
           }
 
-          return callback && callback(result)
+          return callback && callback(result, meta)
         },
         writable: false,
         configurable: true
@@ -39,6 +39,91 @@
     }
   })
 
+  const STREAM_DESCRIPTOR_KEY = '__TAURI_STREAM__'
+
+  function isStreamDescriptor(response) {
+    return (
+      response !== null &&
+      typeof response === 'object' &&
+      typeof response[STREAM_DESCRIPTOR_KEY] === 'number'
+    )
+  }
+
+  // returns an async iterator of the items of a stream returned by a command
+  function receiveStream(id) {
+    const items = []
+    let received = 0
+    let end = null
+    let closed = false
+    let wake = null
+
+    const notify = () => {
+      if (wake) {
+        wake()
+        wake = null
+      }
+    }
+
+    const channel = window.__TAURI_INTERNALS__.transformCallback((item) => {
+      items.push(item)
+      received += 1
+      notify()
+    })
+
+    const close = () => {
+      closed = true
+      Reflect.deleteProperty(window, `_${channel}`)
+    }
+
+    window.__TAURI_INTERNALS__
+      .invoke('plugin:__TAURI_CHANNEL__|stream', {
+        id,
+        channel: `__CHANNEL__:${channel}`
+      })
+      .then((result) => {
+        end = result
+      })
+      .catch((error) => {
+        end = { count: received, error }
+      })
+      .finally(notify)
+
+    return {
+      [Symbol.asyncIterator]() {
+        return this
+      },
+      async next() {
+        for (;;) {
+          if (items.length > 0) {
+            return { value: items.shift(), done: false }
+          }
+          // items are delivered asynchronously, so wait for all of them before ending
+          if (closed || (end !== null && received >= end.count)) {
+            const error = closed ? null : end.error
+            close()
+            if (error !== null && error !== undefined) {
+              throw error
+            }
+            return { value: undefined, done: true }
+          }
+          await new Promise((resolve) => {
+            wake = resolve
+          })
+        }
+      },
+      async return() {
+        if (!closed && end === null) {
+          window.__TAURI_INTERNALS__
+            .invoke('plugin:__TAURI_CHANNEL__|cancel_stream', { id })
+            .catch(console.error)
+        }
+        close()
+        items.length = 0
+        return { value: undefined, done: true }
+      }
+    }
+  }
+
   const ipcQueue = []
   let isWaitingForIpc = false
 
@@ -52,43 +137,165 @@
     }
   }
 
+  function sendIpcMessage(cmd, payload, options) {
+    const { signal, ...ipcOptions } = options || {}
+    return new Promise(function (resolve, reject) {
+      if (signal?.aborted) {
+        reject(signal.reason)
+        return
+      }
+
+      const onAbort = () => {
+        delete window[`_${callback}`]
+        delete window[`_${error}`]
+        reject(signal.reason)
+        // trips the cancellation token of the command
+        window.__TAURI_INTERNALS__
+          .invoke('plugin:__TAURI_CHANNEL__|cancel_invoke', { callback })
+          .catch(console.error)
+      }
+
+      const callback = window.__TAURI_INTERNALS__.transformCallback(function (
+        r
+      ) {
+        signal?.removeEventListener('abort', onAbort)
+        resolve(
+          isStreamDescriptor(r) ? receiveStream(r[STREAM_DESCRIPTOR_KEY]) : r
+        )
+        delete window[`_${error}`]
+      },
+      true)
+      const error = window.__TAURI_INTERNALS__.transformCallback(function (
+        e
+      ) {
+        signal?.removeEventListener('abort', onAbort)
+        reject(e)
+        delete window[`_${callback}`]
+      },
+      true)
+
+      signal?.addEventListener('abort', onAbort, { once: true })
+
+      const action = () => {
+        window.window.__TAURI_INTERNALS__.ipc({
+          cmd,
+          callback,
+          error,
+          payload,
+          options: ipcOptions
+        })
+      }
+      if ('ipc' in window.__TAURI_INTERNALS__) {
+        action()
+      } else {
+        ipcQueue.push(action)
+        if (!isWaitingForIpc) {
+          waitForIpc()
+          isWaitingForIpc = true
+        }
+      }
+    })
+  }
+
+  const INVOKE_KEY_HEADER = 'Tauri-Invoke-Key'
+  const INVOKE_HANDSHAKE_COMMAND = '__TAURI_INVOKE_HANDSHAKE__'
+
+  // authenticates the handshake of this document, kept out of reach of the page scripts
+  const invokeBootstrap = __TEMPLATE_invoke_bootstrap__
+  // the key authenticating the requests of this page, kept out of reach of the page scripts
+  let invokeKey = null
+  let handshake = null
+
+  function requestInvokeKey() {
+    if (handshake === null) {
+      handshake = sendIpcMessage(
+        INVOKE_HANDSHAKE_COMMAND,
+        {},
+        { headers: { [INVOKE_KEY_HEADER]: invokeBootstrap } }
+      )
+        .then((key) => {
+          invokeKey = key
+        })
+        .finally(() => {
+          handshake = null
+        })
+    }
+    return handshake
+  }
+
+  function isInvalidInvokeKeyError(e) {
+    if (typeof e === 'string') {
+      try {
+        e = JSON.parse(e)
+      } catch {
+        return false
+      }
+    }
+    return e !== null && typeof e === 'object' && e.kind === 'InvalidInvokeKey'
+  }
+
+  function sendWithInvokeKey(cmd, payload, options) {
+    const headers =
+      options?.headers instanceof Headers
+        ? Object.fromEntries(options.headers.entries())
+        : { ...options?.headers }
+    headers[INVOKE_KEY_HEADER] = invokeKey
+    return sendIpcMessage(cmd, payload, { ...options, headers })
+  }
+
+  // request the key before any page script runs
+  requestInvokeKey().catch(console.error)
+
   Object.defineProperty(window.__TAURI_INTERNALS__, 'invoke', {
-    value: function (cmd, payload = {}, options) {
-      return new Promise(function (resolve, reject) {
-        const callback = window.__TAURI_INTERNALS__.transformCallback(function (
-          r
-        ) {
-          resolve(r)
-          delete window[`_${error}`]
-        },
-        true)
-        const error = window.__TAURI_INTERNALS__.transformCallback(function (
-          e
-        ) {
-          reject(e)
-          delete window[`_${callback}`]
-        },
-        true)
-
-        const action = () => {
-          window.window.__TAURI_INTERNALS__.ipc({
-            cmd,
-            callback,
-            error,
-            payload,
-            options
-          })
+    value: async function (cmd, payload = {}, options) {
+      if (invokeKey === null) {
+        await requestInvokeKey()
+      }
+      const key = invokeKey
+      try {
+        return await sendWithInvokeKey(cmd, payload, options)
+      } catch (e) {
+        if (!isInvalidInvokeKeyError(e)) {
+          throw e
         }
-        if ('ipc' in window.__TAURI_INTERNALS__) {
-          action()
-        } else {
-          ipcQueue.push(action)
-          if (!isWaitingForIpc) {
-            waitForIpc()
-            isWaitingForIpc = true
-          }
+        // the key is replaced when a navigation starts, even if it does not load a new page (e.g. a download);
+        // the handshake hands the key to every document, so the retry does not take it from the next page
+        if (invokeKey === key) {
+          await requestInvokeKey()
         }
-      })
+        return sendWithInvokeKey(cmd, payload, options)
+      }
+    }
+  })
+
+  const SYNC_INVOKE_HEADER = 'Tauri-Sync'
+
+  Object.defineProperty(window.__TAURI_INTERNALS__, 'invokeSync', {
+    value: function (cmd, args = null) {
+      if (invokeKey === null) {
+        throw { kind: 'InvalidInvokeKey' }
+      }
+      // the arguments are sent in the query string since the request body is not available on all platforms
+      const url = `${window.__TAURI_INTERNALS__.convertFileSrc(
+        cmd,
+        'ipc'
+      )}?args=${encodeURIComponent(JSON.stringify(args))}`
+      const xhr = new XMLHttpRequest()
+      xhr.open('POST', url, false)
+      xhr.setRequestHeader(SYNC_INVOKE_HEADER, 'true')
+      xhr.setRequestHeader(INVOKE_KEY_HEADER, invokeKey)
+      xhr.send()
+
+      let response
+      try {
+        response = JSON.parse(xhr.responseText)
+      } catch {
+        response = xhr.responseText
+      }
+      if (xhr.status === 200) {
+        return response
+      }
+      throw response
     }
   })
 })()
@@ -0,0 +1,68 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+;(function () {
+  const emitFunction = __TEMPLATE_function__
+  const listenersObject = __TEMPLATE_listeners__
+  const bridgeCommand = __TEMPLATE_command__
+
+  function eventListeners(event) {
+    if (window[listenersObject] === void 0) {
+      Object.defineProperty(window, listenersObject, {
+        value: Object.create(null)
+      })
+    }
+    if (window[listenersObject][event] === void 0) {
+      Object.defineProperty(window[listenersObject], event, { value: [] })
+    }
+    return window[listenersObject][event]
+  }
+
+  function handle(message) {
+    switch (message.kind) {
+      case 'listen':
+        eventListeners(message.event).push({
+          id: message.id,
+          target: message.target,
+          handler: window[`_${message.handler}`]
+        })
+        break
+      case 'unlisten': {
+        const listeners = eventListeners(message.event)
+        const index = listeners.findIndex((l) => l.id === message.id)
+        if (index > -1) {
+          listeners.splice(index, 1)
+        }
+        break
+      }
+      case 'emit':
+        window[emitFunction](message.eventData, message.target)
+        break
+      case 'call': {
+        const handler = window[`_${message.handler}`]
+        handler && handler(message.eventData)
+        break
+      }
+    }
+  }
+
+  function poll() {
+    window.__TAURI_INTERNALS__.invoke(bridgeCommand).then(
+      (messages) => {
+        for (const message of messages) {
+          try {
+            handle(message)
+          } catch (e) {
+            console.error(e)
+          }
+        }
+        poll()
+      },
+      // the page is not allowed to receive the events
+      (error) => console.error(`[TAURI] the event bridge stopped: ${error}`)
+    )
+  }
+
+  poll()
+})()
@@ -6,6 +6,7 @@
   const processIpcMessage = __RAW_process_ipc_message_fn__
   const osName = __TEMPLATE_os_name__
   const fetchChannelDataCommand = __TEMPLATE_fetch_channel_data_command__
+  const eventBridgeCommand = __TEMPLATE_event_bridge_command__
   const useCustomProtocol = __TEMPLATE_use_custom_protocol__
 
   Object.defineProperty(window.__TAURI_INTERNALS__, 'postMessage', {
@@ -15,12 +16,14 @@
       // use custom protocol for IPC if:
       // - the flag is set to true or
       // - the command is the fetch data command or
+      // - the command is the event bridge command or
       // - when not on Linux/Android
       // AND
       // - when not on macOS with an https URL
       if (
         (useCustomProtocol ||
           cmd === fetchChannelDataCommand ||
+          cmd === eventBridgeCommand ||
           !(osName === 'linux' || osName === 'android')) &&
         !(
           (osName === 'macos' || osName === 'ios') &&
@@ -39,22 +42,36 @@
           }
         })
           .then((response) => {
+            // the invoke was cancelled, its promise is already rejected
+            if (response.status === 204) {
+              return null
+            }
             const cb = response.ok ? callback : error
+            const metaHeader = response.headers.get('Tauri-Response-Meta')
+            const meta = metaHeader ? JSON.parse(metaHeader) : undefined
             // we need to split here because on Android the content-type gets duplicated
             switch (
               (response.headers.get('content-type') || '').split(',')[0]
             ) {
               case 'application/json':
-                return response.json().then((r) => [cb, r])
+                return response.json().then((r) => [cb, r, meta])
               case 'text/plain':
-                return response.text().then((r) => [cb, r])
+                return response.text().then((r) => [cb, r, meta])
               default:
-                return response.arrayBuffer().then((r) => [cb, r])
+                return response.arrayBuffer().then((r) => [cb, r, meta])
             }
           })
-          .then(([cb, data]) => {
+          .then((result) => {
+            if (result === null) {
+              return
+            }
+            const [cb, data, meta] = result
             if (window[`_${cb}`]) {
-              window[`_${cb}`](data)
+              if (meta === undefined) {
+                window[`_${cb}`](data)
+              } else {
+                window[`_${cb}`](data, meta)
+              }
             } else {
               console.warn(
                 `[TAURI] Couldn't find callback id {cb} in window. This might happen when the app is reloaded while Rust is running an asynchronous operation.`
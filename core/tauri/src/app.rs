@@ -4,8 +4,9 @@
 
 use crate::{
   ipc::{
-    channel::ChannelDataIpcQueue, CallbackFn, CommandArg, CommandItem, Invoke, InvokeError,
-    InvokeHandler, InvokeResponder, InvokeResponse,
+    channel::ChannelDataIpcQueue, limits::IpcLimits, sync::SyncCommands, CallbackFn, CommandArg,
+    CommandItem, Invoke, InvokeError, InvokeHandler, InvokeResponder, InvokeResponse,
+    IpcOriginValidator, ResponseMeta, ScopePolicy,
   },
   manager::{
     webview::{UriSchemeProtocol, WebviewLabelDef},
@@ -13,13 +14,22 @@ use crate::{
   },
   plugin::{Plugin, PluginStore},
   runtime::{
-    window::WindowEvent as RuntimeWindowEvent, ExitRequestedEventAction,
-    RunEvent as RuntimeRunEvent,
+    window::{
+      ProcessCrashReason, WebviewEvent as RuntimeWebviewEvent, WindowEvent as RuntimeWindowEvent,
+    },
+    ExitRequestedEventAction, RunEvent as RuntimeRunEvent,
   },
   sealed::{ManagerBase, RuntimeOrDispatch},
   utils::config::Config,
-  utils::{assets::Assets, Env},
-  webview::PageLoadPayload,
+  utils::{
+    assets::{AssetKey, Assets},
+    mime_type::MimeType,
+    Env,
+  },
+  webview::{
+    runtime_status::WebviewRuntimeMissingHandler, PageLoadEvent, PageLoadPayload, RuntimeStatus,
+    WebviewRuntimeAction,
+  },
   Context, DeviceEventFilter, EventLoopMessage, Icon, Manager, Monitor, Runtime, Scopes,
   StateManager, Theme, Webview, WebviewWindowBuilder, Window,
 };
@@ -28,6 +38,8 @@ use crate::{
 use crate::menu::{Menu, MenuEvent};
 #[cfg(all(desktop, feature = "tray-icon"))]
 use crate::tray::{TrayIcon, TrayIconBuilder, TrayIconEvent, TrayIconId};
+use futures_util::future::BoxFuture;
+use http::HeaderMap;
 use raw_window_handle::HasDisplayHandle;
 use serialize_to_javascript::{default_template, DefaultTemplate, Template};
 use tauri_macros::default_runtime;
@@ -41,20 +53,31 @@ use tauri_runtime::{
   RuntimeInitArgs,
 };
 use tauri_utils::{debug_eprintln, PackageInfo};
+use url::Url;
 
 use std::{
   borrow::Cow,
   collections::HashMap,
   fmt,
-  sync::{mpsc::Sender, Arc},
+  future::Future,
+  sync::{
+    mpsc::{channel, Sender},
+    Arc,
+  },
+  time::Duration,
 };
 
-use crate::{event::EventId, runtime::RuntimeHandle, Event, EventTarget};
+use crate::{event::EventId, runtime::RuntimeHandle, Event, EventTarget, EventTransport};
 
 #[cfg(target_os = "macos")]
 use crate::ActivationPolicy;
 
+pub(crate) mod exit;
+mod instance_id;
 pub(crate) mod plugin;
+pub(crate) mod state_init;
+
+use state_init::StateInitError;
 
 #[cfg(desktop)]
 pub(crate) type GlobalMenuEventListener<T> = Box<dyn Fn(&T, crate::menu::MenuEvent) + Send + Sync>;
@@ -164,6 +187,64 @@ impl From<RuntimeWindowEvent> for WindowEvent {
   }
 }
 
+/// An event from a webview, see [`Webview::on_webview_event`] and [`Webview::subscribe_events`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum WebviewEvent {
+  /// The webview bounds changed, either directly or because its window was resized.
+  /// Contains the new physical size of the webview.
+  Resized(PhysicalSize<u32>),
+  /// The webview gained or lost focus.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux / macOS / iOS / Android**: Unsupported.
+  Focused(bool),
+  /// The webview started or finished loading a page.
+  #[non_exhaustive]
+  PageLoad {
+    /// The page URL.
+    url: Url,
+    /// Whether the page started or finished loading.
+    event: PageLoadEvent,
+  },
+  /// The webview navigates to a URL allowed by the navigation handlers.
+  #[non_exhaustive]
+  Navigation {
+    /// The navigation URL.
+    url: Url,
+  },
+  /// The process rendering the webview content terminated unexpectedly, leaving the webview blank.
+  ///
+  /// The webview can be recreated with [`Webview::recover`].
+  #[non_exhaustive]
+  ProcessCrashed {
+    /// Why the process terminated.
+    reason: ProcessCrashReason,
+  },
+  /// The webview content started or stopped playing audio.
+  MediaPlaybackChanged(bool),
+  /// The webview has been destroyed. No event is emitted after this one.
+  Destroyed,
+}
+
+impl WebviewEvent {
+  /// Converts an event of the runtime, which may not be supported by this version of Tauri.
+  pub(crate) fn from_runtime(event: &RuntimeWebviewEvent) -> Option<Self> {
+    match event {
+      RuntimeWebviewEvent::Resized(size) => Some(Self::Resized(*size)),
+      RuntimeWebviewEvent::Focused(focused) => Some(Self::Focused(*focused)),
+      RuntimeWebviewEvent::ProcessCrashed { reason } => {
+        Some(Self::ProcessCrashed { reason: *reason })
+      }
+      RuntimeWebviewEvent::MediaPlaybackChanged(playing) => {
+        Some(Self::MediaPlaybackChanged(*playing))
+      }
+      _ => None,
+    }
+  }
+}
+
 /// An application event, triggered from the event loop.
 ///
 /// See [`App::run`](crate::App#method.run) for usage examples.
@@ -238,9 +319,98 @@ impl<R: Runtime> AssetResolver<R> {
     self.manager.get_asset(path).ok()
   }
 
-  /// Iterate on all assets.
-  pub fn iter(&self) -> Box<dyn Iterator<Item = (&&str, &&[u8])> + '_> {
-    self.manager.assets.iter()
+  /// Iterates on all assets, yielding their path, mime type and size.
+  ///
+  /// The assets are not decompressed, the size is the one the asset has once read with [`Self::read`].
+  pub fn iter(&self) -> Box<dyn Iterator<Item = (&str, String, usize)> + '_> {
+    Box::new(self.manager.assets.iter().map(|(&path, data)| {
+      let size = self
+        .manager
+        .assets
+        .original_size(&AssetKey::from(path))
+        .unwrap_or(data.len());
+      (path, MimeType::parse_from_uri(path).to_string(), size)
+    }))
+  }
+
+  /// Reads the content of the asset at the given path, decompressing it if needed.
+  ///
+  /// Unlike [`Self::get`], this does not fall back to `index.html` and does not inject the CSP.
+  pub fn read(&self, path: &str) -> Option<Cow<'static, [u8]>> {
+    self
+      .manager
+      .assets
+      .get(&AssetKey::from(path))
+      .map(|asset| Cow::Owned(asset.into_owned()))
+  }
+
+  /// Whether an asset exists at the given path.
+  pub fn exists(&self, path: &str) -> bool {
+    let key = AssetKey::from(path);
+    self
+      .manager
+      .assets
+      .iter()
+      .any(|(&asset_path, _)| asset_path == key.as_ref())
+  }
+
+  /// Whether the assets are embedded compressed.
+  pub fn is_compressed(&self) -> bool {
+    self.manager.assets.is_compressed()
+  }
+
+  /// The size of the asset at the given path once decompressed.
+  pub fn original_size(&self, path: &str) -> Option<usize> {
+    self.manager.assets.original_size(&AssetKey::from(path))
+  }
+}
+
+/// The environment the application runs in, meant to be included in bug reports.
+///
+/// The fields that cannot be determined are set to `unknown`.
+/// Also available to the frontend as `getRuntimeInfo` in the `@tauri-apps/api/app` module.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct RuntimeInfo {
+  /// The version of the webview installed on the system.
+  ///
+  /// On Linux, it includes the webkit2gtk library the app is linked against, e.g. `2.42.4 (libwebkit2gtk-4.1.so.0)`.
+  pub webview_version: String,
+  /// The name of the runtime, e.g. `wry`.
+  pub runtime: &'static str,
+  /// The operating system, see [`std::env::consts::OS`].
+  pub os: &'static str,
+  /// The CPU architecture, see [`std::env::consts::ARCH`].
+  pub arch: &'static str,
+  /// The version of Tauri.
+  pub tauri_version: &'static str,
+  /// The local endpoint of the remote debugging server started with [`Builder::remote_debugging_port`],
+  /// e.g. `http://127.0.0.1:9222`.
+  ///
+  /// On Windows it serves the Chrome DevTools Protocol: `/json/version` lists the `webSocketDebuggerUrl` of the browser,
+  /// and the endpoint can be passed to Playwright's `chromium.connectOverCDP` or as Puppeteer's `browserURL`.
+  /// On Linux it serves the WebKit remote inspector instead. It is `None` on the other platforms.
+  #[cfg(feature = "devtools-protocol")]
+  #[cfg_attr(docsrs, doc(cfg(feature = "devtools-protocol")))]
+  pub devtools_protocol_url: Option<String>,
+}
+
+impl RuntimeInfo {
+  pub(crate) fn current<R: Runtime>(#[allow(unused_variables)] manager: &AppManager<R>) -> Self {
+    Self {
+      webview_version: R::webview_version().unwrap_or_else(|_| "unknown".into()),
+      runtime: R::NAME,
+      os: std::env::consts::OS,
+      arch: std::env::consts::ARCH,
+      tauri_version: crate::VERSION,
+      #[cfg(feature = "devtools-protocol")]
+      devtools_protocol_url: manager
+        .webview
+        .remote_debugging_port
+        .filter(|_| cfg!(any(windows, target_os = "linux")))
+        .map(|port| format!("http://127.0.0.1:{port}")),
+    }
   }
 }
 
@@ -300,6 +470,174 @@ impl<'de, R: Runtime> CommandArg<'de, R> for AppHandle<R> {
 }
 
 impl<R: Runtime> AppHandle<R> {
+  /// Gets the environment the application runs in, see [`RuntimeInfo`].
+  pub fn runtime_info(&self) -> RuntimeInfo {
+    RuntimeInfo::current(&self.manager)
+  }
+
+  /// Takes a snapshot of the application state to attach to bug reports,
+  /// see [`Builder::diagnostics`] to redact the URLs and capture the recent panics.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// #[tauri::command]
+  /// fn export_diagnostics(app: tauri::AppHandle) -> Result<String, String> {
+  ///   serde_json::to_string_pretty(&app.diagnostics()).map_err(|e| e.to_string())
+  /// }
+  /// ```
+  pub fn diagnostics(&self) -> crate::diagnostics::Diagnostics {
+    crate::diagnostics::collect(self)
+  }
+
+  /// Saves a snapshot of the application state to `diagnostics-<timestamp>.json`
+  /// in the [log directory](crate::path::PathResolver::app_log_dir) and returns its path, see [`Self::diagnostics`].
+  pub fn save_diagnostics(&self) -> crate::Result<std::path::PathBuf> {
+    let diagnostics = self.diagnostics();
+    let dir = self.path().app_log_dir()?;
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("diagnostics-{}.json", diagnostics.timestamp));
+    std::fs::write(&path, serde_json::to_vec_pretty(&diagnostics)?)?;
+    Ok(path)
+  }
+
+  /// An anonymous id of the app installation, for instance to group telemetry events.
+  ///
+  /// The id is a random UUID created on the first call and persisted to the `.instance-id` file
+  /// of the [app data directory](crate::path::PathResolver::app_data_dir), so it stays the same across launches
+  /// until it is reset with [`Self::reset_instance_id`]. No hardware identifier is read.
+  ///
+  /// All the threads and processes of the app get the same id, and a corrupted file is replaced with a new id.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// #[tauri::command]
+  /// fn telemetry_id(app: tauri::AppHandle) -> Result<String, String> {
+  ///   app.instance_id().map_err(|e| e.to_string())
+  /// }
+  /// ```
+  pub fn instance_id(&self) -> crate::Result<String> {
+    self
+      .state::<instance_id::InstanceId>()
+      .get(&self.path().app_data_dir()?)
+  }
+
+  /// Deletes the [instance id](Self::instance_id), so the next call creates a new one,
+  /// for instance when the user opts out of telemetry.
+  pub fn reset_instance_id(&self) -> crate::Result<()> {
+    self
+      .state::<instance_id::InstanceId>()
+      .reset(&self.path().app_data_dir()?)
+  }
+
+  /// The commands registered with [`crate::generate_handler!`] in [`Builder::invoke_handler`]
+  /// and the arguments they read from the payload, to generate typed bindings for the frontend.
+  ///
+  /// ```json
+  /// {
+  ///   "commands": [
+  ///     {
+  ///       "name": "greet",
+  ///       "args": [{ "name": "name", "type": "String", "schema": { "type": "string" } }]
+  ///     }
+  ///   ]
+  /// }
+  /// ```
+  ///
+  /// The arguments injected by Tauri, like the [`crate::State`] or the [`crate::Webview`], are not included.
+  /// Argument schemas are `null` for types that do not implement [`crate::ipc::CommandArgSchema`].
+  ///
+  /// The manifest is also written to the file at the `TAURI_EXPORT_COMMANDS` environment variable path when the app is built.
+  #[cfg(feature = "command-manifest")]
+  #[cfg_attr(docsrs, doc(cfg(feature = "command-manifest")))]
+  pub fn command_manifest(&self) -> serde_json::Value {
+    self
+      .state::<crate::ipc::manifest::CommandManifest>()
+      .0
+      .clone()
+  }
+
+  /// The events recorded with [`Builder::record_events`], oldest first.
+  ///
+  /// The log is empty if the events are not recorded. It can be exported to JSON with `serde_json`.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// #[tauri::command]
+  /// fn export_event_log(app: tauri::AppHandle) -> Result<String, String> {
+  ///   serde_json::to_string_pretty(&app.event_log()).map_err(|e| e.to_string())
+  /// }
+  /// ```
+  pub fn event_log(&self) -> Vec<crate::RecordedEvent> {
+    self
+      .manager
+      .event_recorder
+      .as_ref()
+      .map(|recorder| recorder.entries())
+      .unwrap_or_default()
+  }
+
+  /// Removes the recorded events, see [`Builder::record_events`].
+  pub fn clear_event_log(&self) {
+    if let Some(recorder) = &self.manager.event_recorder {
+      recorder.clear();
+    }
+  }
+
+  /// Emits the recorded events matching `filter` again, in order and with their original relative timing
+  /// divided by `speed`: `2.0` replays twice as fast, `0.0` without any delay. Returns the number of replayed events.
+  ///
+  /// Only the events recorded with their payload are replayed, see [`crate::RecorderConfig::include_payloads`],
+  /// except the events emitted with [`Manager::emit_filter`] and the replayed events.
+  /// The replayed events are recorded again with [`crate::RecordedEvent::replayed`] set.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// #[tauri::command]
+  /// async fn replay_downloads(app: tauri::AppHandle) -> Result<usize, String> {
+  ///   app
+  ///     .replay_events(|event| event.event.starts_with("download-"), 1.0)
+  ///     .await
+  ///     .map_err(|e| e.to_string())
+  /// }
+  /// ```
+  pub async fn replay_events<F: Fn(&crate::RecordedEvent) -> bool>(
+    &self,
+    filter: F,
+    speed: f64,
+  ) -> crate::Result<usize> {
+    let events = self
+      .event_log()
+      .into_iter()
+      .filter(|event| {
+        !event.replayed && event.target.is_some() && event.payload.is_some() && filter(event)
+      })
+      .collect::<Vec<_>>();
+
+    let mut previous = None;
+    for event in &events {
+      if let Some(previous) = previous {
+        let delay = event.timestamp.saturating_sub(previous) as f64 / 1000.0 / speed;
+        if delay.is_finite() && delay > 0.0 {
+          tokio::time::sleep(Duration::from_secs_f64(delay)).await;
+        }
+      }
+      previous.replace(event.timestamp);
+      self.manager.replay_event(event)?;
+    }
+    Ok(events.len())
+  }
+
+  /// Removes the cached responses of a command, see [`Builder::cache_command`].
+  pub fn invalidate_command_cache(&self, command: &str) {
+    if let Some(cache) = self.try_state::<crate::ipc::cache::CommandCache>() {
+      cache.invalidate(command);
+    }
+  }
+
   /// Runs the given closure on the main thread.
   pub fn run_on_main_thread<F: FnOnce() + Send + 'static>(&self, f: F) -> crate::Result<()> {
     self
@@ -312,6 +650,8 @@ impl<R: Runtime> AppHandle<R> {
   /// This function can be used to register a plugin that is loaded dynamically e.g. after login.
   /// For plugins that are created when the app is started, prefer [`Builder::plugin`].
   ///
+  /// The plugins it requires must be initialized already.
+  ///
   /// See [`Builder::plugin`] for more information.
   ///
   /// # Examples
@@ -370,9 +710,28 @@ impl<R: Runtime> AppHandle<R> {
   ///   });
   /// ```
   pub fn remove_plugin(&self, plugin: &'static str) -> bool {
+    self
+      .manager
+      .initialized_plugins
+      .lock()
+      .unwrap()
+      .remove(plugin);
     self.manager().plugins.lock().unwrap().unregister(plugin)
   }
 
+  /// Whether the plugin with the given name is initialized.
+  ///
+  /// Plugins emit the `tauri://plugin-initialized` event with their name once they are initialized,
+  /// which can be listened to when the plugin is not initialized yet.
+  pub fn plugin_initialized(&self, plugin: &str) -> bool {
+    self
+      .manager
+      .initialized_plugins
+      .lock()
+      .unwrap()
+      .contains(plugin)
+  }
+
   /// Exits the app by triggering [`RunEvent::ExitRequested`] and [`RunEvent::Exit`].
   pub fn exit(&self, exit_code: i32) {
     if let Err(e) = self.runtime_handle.request_exit(exit_code) {
@@ -389,6 +748,14 @@ impl<R: Runtime> AppHandle<R> {
     }
     crate::process::restart(&self.env());
   }
+
+  /// Emits `tauri://before-exit` and waits until every webview listening to it acknowledged the event.
+  ///
+  /// Meant to be called in a [`Builder::on_before_exit`] hook so the frontend can save its state,
+  /// see `onBeforeExit` in the `@tauri-apps/api/app` module.
+  pub async fn flush_webviews(&self) {
+    exit::flush_webviews(self).await
+  }
 }
 
 impl<R: Runtime> Manager<R> for AppHandle<R> {}
@@ -946,10 +1313,7 @@ impl<R: Runtime> App<R> {
         callback(&app_handle, event);
         app_handle.cleanup_before_exit();
       }
-      _ => {
-        let event = on_event_loop_event(&app_handle, event, &manager);
-        callback(&app_handle, event);
-      }
+      _ => on_event_loop_event_with_callback(&app_handle, event, &manager, &mut callback),
     });
   }
 
@@ -987,8 +1351,7 @@ impl<R: Runtime> App<R> {
     }
 
     self.runtime.as_mut().unwrap().run_iteration(move |event| {
-      let event = on_event_loop_event(&app_handle, event, &manager);
-      callback(&app_handle, event);
+      on_event_loop_event_with_callback(&app_handle, event, &manager, &mut callback)
     })
   }
 }
@@ -1014,6 +1377,57 @@ pub struct Builder<R: Runtime> {
   /// The JS message responder.
   invoke_responder: Option<Arc<InvokeResponder<R>>>,
 
+  /// The IPC request origin validator.
+  ipc_origin_validator: Option<Arc<IpcOriginValidator<R>>>,
+
+  /// The policy deciding whether the webviews can run the commands they invoke.
+  scope_policy: Option<Box<dyn ScopePolicy<R>>>,
+
+  /// The commands the frontend can call synchronously.
+  sync_commands: SyncCommands,
+
+  /// The manifest recorded by `generate_handler!` for the invoke handler.
+  #[cfg(feature = "command-manifest")]
+  command_manifest: Option<fn() -> Vec<crate::ipc::private::CommandDescriptor>>,
+
+  /// Additional origins treated as the app origin.
+  local_origins: Vec<Url>,
+
+  /// The size limits of the IPC requests and responses.
+  ipc_limits: IpcLimits,
+
+  /// The flush intervals of the batched events.
+  event_batching: HashMap<String, Duration>,
+
+  /// How the emitted events are recorded.
+  event_recorder: Option<crate::RecorderConfig>,
+
+  /// How the events reach the frontend.
+  event_transport: EventTransport,
+
+  /// The cache policies of the commands, by command name.
+  command_cache: HashMap<String, crate::ipc::CachePolicy>,
+
+  /// How the diagnostics are collected.
+  diagnostics: Option<crate::diagnostics::DiagnosticsConfig>,
+
+  /// The hook called when a command panics.
+  on_command_panic: Option<Box<crate::ipc::panic::OnCommandPanic>>,
+
+  /// The handler called when the webview runtime is missing or outdated.
+  webview_runtime_missing: Option<Box<WebviewRuntimeMissingHandler>>,
+
+  /// The minimum version of the webview runtime.
+  minimum_webview_version: Option<String>,
+
+  /// Whether the IPC requests are traced.
+  #[cfg(feature = "tracing")]
+  trace_ipc: bool,
+
+  /// The local port of the remote debugging server.
+  #[cfg(feature = "devtools-protocol")]
+  remote_debugging_port: Option<u16>,
+
   /// The script that initializes the `window.__TAURI_INTERNALS__.postMessage` function.
   invoke_initialization_script: String,
 
@@ -1045,6 +1459,18 @@ pub struct Builder<R: Runtime> {
 
   /// The device event filter.
   device_event_filter: DeviceEventFilter,
+
+  /// Hooks that run before the app exits.
+  before_exit: Vec<exit::BeforeExitHook<R>>,
+
+  /// How long the exit waits for the before exit hooks.
+  before_exit_timeout: Duration,
+
+  /// Whether the changes of the fs scope are persisted.
+  persist_fs_scope: bool,
+
+  /// The asynchronous state initializers.
+  state_initializers: Vec<state_init::StateInitializer<R>>,
 }
 
 #[derive(Template)]
@@ -1055,6 +1481,7 @@ struct InvokeInitializationScript<'a> {
   process_ipc_message_fn: &'a str,
   os_name: &'a str,
   fetch_channel_data_command: &'a str,
+  event_bridge_command: &'a str,
   use_custom_protocol: bool,
 }
 
@@ -1084,10 +1511,30 @@ impl<R: Runtime> Builder<R> {
       setup: Box::new(|_| Ok(())),
       invoke_handler: Box::new(|_| false),
       invoke_responder: None,
+      ipc_origin_validator: None,
+      scope_policy: None,
+      sync_commands: SyncCommands::default(),
+      #[cfg(feature = "command-manifest")]
+      command_manifest: None,
+      local_origins: Vec::new(),
+      ipc_limits: IpcLimits::default(),
+      event_batching: HashMap::new(),
+      event_recorder: None,
+      event_transport: EventTransport::default(),
+      command_cache: HashMap::new(),
+      diagnostics: None,
+      on_command_panic: None,
+      webview_runtime_missing: None,
+      minimum_webview_version: crate::webview::MINIMUM_WEBVIEW_VERSION.map(Into::into),
+      #[cfg(feature = "tracing")]
+      trace_ipc: false,
+      #[cfg(feature = "devtools-protocol")]
+      remote_debugging_port: None,
       invoke_initialization_script: InvokeInitializationScript {
         process_ipc_message_fn: crate::manager::webview::PROCESS_IPC_MESSAGE_FN,
         os_name: std::env::consts::OS,
         fetch_channel_data_command: crate::ipc::channel::FETCH_CHANNEL_DATA_COMMAND,
+        event_bridge_command: crate::event::EVENT_BRIDGE_COMMAND,
         use_custom_protocol: cfg!(ipc_custom_protocol),
       }
       .render_default(&Default::default())
@@ -1102,6 +1549,10 @@ impl<R: Runtime> Builder<R> {
       enable_macos_default_menu: true,
       window_event_listeners: Vec::new(),
       device_event_filter: Default::default(),
+      before_exit: Vec::new(),
+      before_exit_timeout: exit::DEFAULT_BEFORE_EXIT_TIMEOUT,
+      persist_fs_scope: false,
+      state_initializers: Vec::new(),
     }
   }
 }
@@ -1139,6 +1590,10 @@ impl<R: Runtime> Builder<R> {
   where
     F: Fn(Invoke<R>) -> bool + Send + Sync + 'static,
   {
+    #[cfg(feature = "command-manifest")]
+    {
+      self.command_manifest = crate::ipc::manifest::take_handler_manifest::<F>();
+    }
     self.invoke_handler = Box::new(invoke_handler);
     self
   }
@@ -1146,126 +1601,563 @@ impl<R: Runtime> Builder<R> {
   /// Defines a custom JS message system.
   ///
   /// The `responder` is a function that will be called when a command has been executed and must send a response to the JS layer.
+  /// It also receives the [`ResponseMeta`] of the response, empty unless the command responded with metadata.
   ///
   /// The `initialization_script` is a script that initializes `window.__TAURI_INTERNALS__.postMessage`.
   /// That function must take the `(message: object, options: object)` arguments and send it to the backend.
   #[must_use]
   pub fn invoke_system<F>(mut self, initialization_script: String, responder: F) -> Self
   where
-    F: Fn(&Webview<R>, &str, &InvokeResponse, CallbackFn, CallbackFn) + Send + Sync + 'static,
+    F: Fn(&Webview<R>, &str, &InvokeResponse, &ResponseMeta, CallbackFn, CallbackFn)
+      + Send
+      + Sync
+      + 'static,
   {
     self.invoke_initialization_script = initialization_script;
     self.invoke_responder.replace(Arc::new(responder));
     self
   }
 
-  /// Defines the setup hook.
+  /// Defines a validator for the headers of every IPC request,
+  /// evaluated before the command is checked against the ACL.
+  ///
+  /// Requests are rejected with an [`crate::ipc::IpcError::OriginDenied`] error when the validator returns an error.
   ///
   /// # Examples
-  #[cfg_attr(
-    feature = "unstable",
-    doc = r####"
-```
-use tauri::Manager;
-tauri::Builder::default()
-  .setup(|app| {
-    let main_window = app.get_window("main").unwrap();
-    main_window.set_title("Tauri!");
-    Ok(())
-  });
-```
-  "####
-  )]
+  ///
+  /// ```
+  /// use tauri::http::header::ORIGIN;
+  ///
+  /// tauri::Builder::default()
+  ///   .ipc_origin_validator(|headers, _webview| {
+  ///     match headers.get(ORIGIN).and_then(|o| o.to_str().ok()) {
+  ///       Some("tauri://localhost") | Some("http://tauri.localhost") => Ok(()),
+  ///       origin => Err(format!("unexpected origin {origin:?}")),
+  ///     }
+  ///   });
+  /// ```
   #[must_use]
-  pub fn setup<F>(mut self, setup: F) -> Self
+  pub fn ipc_origin_validator<F>(mut self, validator: F) -> Self
   where
-    F: FnOnce(&mut App<R>) -> Result<(), Box<dyn std::error::Error>> + Send + 'static,
+    F: Fn(&HeaderMap, &Webview<R>) -> Result<(), String> + Send + Sync + 'static,
   {
-    self.setup = Box::new(setup);
+    self.ipc_origin_validator.replace(Arc::new(validator));
     self
   }
 
-  /// Defines the page load hook.
+  /// Replaces the policy deciding whether the webviews can run the commands they invoke,
+  /// which checks the plugin commands against the Access Control List by default.
+  ///
+  /// The [`Self::ipc_origin_validator`], the invoke key and the [`crate::webview::WebviewBuilder::allowed_commands`]
+  /// list are always checked before the policy. See [`ScopePolicy`] for more information.
   #[must_use]
-  pub fn on_page_load<F>(mut self, on_page_load: F) -> Self
-  where
-    F: Fn(&Webview<R>, &PageLoadPayload<'_>) + Send + Sync + 'static,
-  {
-    self.on_page_load.replace(Arc::new(on_page_load));
+  pub fn scope_policy<P: ScopePolicy<R>>(mut self, policy: P) -> Self {
+    self.scope_policy.replace(Box::new(policy));
     self
   }
 
-  /// Adds a Tauri application plugin.
+  /// Registers an additional origin serving the app frontend.
   ///
-  /// A plugin is created using the [`crate::plugin::Builder`] struct.Check its documentation for more information.
+  /// Pages loaded from this origin are treated like the pages of the app URL and custom protocols:
+  /// their IPC requests are checked against the local capabilities instead of the remote ones
+  /// and they are accepted by the [`crate::ipc::default_origin_validator`].
+  /// Only the scheme, host and port of the URL are compared.
   ///
   /// # Examples
   ///
   /// ```
-  /// mod plugin {
-  ///   use tauri::{plugin::{Builder as PluginBuilder, TauriPlugin}, RunEvent, Runtime};
+  /// tauri::Builder::default()
+  ///   .add_local_origin("https://app.local:8443".parse().unwrap());
+  /// ```
+  #[must_use]
+  pub fn add_local_origin(mut self, origin: Url) -> Self {
+    self.local_origins.push(origin);
+    self
+  }
+
+  /// Sets the maximum size in bytes of an IPC request payload, [`crate::ipc::DEFAULT_MAX_IPC_PAYLOAD_BYTES`] by default.
   ///
-  ///   // this command can be called in the frontend using `invoke('plugin:window|do_something')`.
-  ///   #[tauri::command]
-  ///   async fn do_something<R: Runtime>(app: tauri::AppHandle<R>, window: tauri::Window<R>) -> Result<(), String> {
-  ///     println!("command called");
-  ///     Ok(())
-  ///   }
-  ///   pub fn init<R: Runtime>() -> TauriPlugin<R> {
-  ///     PluginBuilder::new("window")
-  ///       .setup(|app, api| {
-  ///         // initialize the plugin here
-  ///         Ok(())
-  ///       })
-  ///       .on_event(|app, event| {
-  ///         match event {
-  ///           RunEvent::Ready => {
-  ///             println!("app is ready");
-  ///           }
-  ///           RunEvent::WindowEvent { label, event, .. } => {
-  ///             println!("window {} received an event: {:?}", label, event);
-  ///           }
-  ///           _ => (),
-  ///         }
-  ///       })
-  ///       .invoke_handler(tauri::generate_handler![do_something])
-  ///       .build()
-  ///   }
-  /// }
+  /// The size is checked when the request body is received, before it is parsed.
+  /// Larger requests are rejected with an [`crate::ipc::IpcError::PayloadTooLarge`] error,
+  /// which the frontend receives as `{ kind: 'PayloadTooLarge', limit: number, actual: number }`.
   ///
+  /// # Examples
+  ///
+  /// ```
   /// tauri::Builder::default()
-  ///   .plugin(plugin::init());
+  ///   .max_ipc_payload_bytes(8 * 1024 * 1024);
   /// ```
   #[must_use]
-  pub fn plugin<P: Plugin<R> + 'static>(mut self, plugin: P) -> Self {
-    self.plugins.register(Box::new(plugin));
+  pub fn max_ipc_payload_bytes(mut self, limit: usize) -> Self {
+    self.ipc_limits.max_payload_bytes = limit;
     self
   }
 
-  /// Add `state` to the state managed by the application.
+  /// Sets the maximum size in bytes of a serialized command response, [`crate::ipc::DEFAULT_MAX_IPC_RESPONSE_BYTES`] by default.
   ///
-  /// This method can be called any number of times as long as each call
-  /// refers to a different `T`.
+  /// Larger responses are replaced with an [`crate::ipc::IpcError::ResponseTooLarge`] error.
+  /// Measuring a JSON response serializes it once more, so only the raw responses are checked
+  /// against the default limit, and JSON responses are checked once a limit is set.
+  /// The items of a [`crate::ipc::Channel`] or a [`crate::ipc::StreamResponse`] are not subject to the limit.
   ///
-  /// Managed state can be retrieved by any command handler via the
-  /// [`crate::State`] guard. In particular, if a value of type `T`
-  /// is managed by Tauri, adding `State<T>` to the list of arguments in a
-  /// command handler instructs Tauri to retrieve the managed value.
-  /// Additionally, [`state`](crate::Manager#method.state) can be used to retrieve the value manually.
+  /// # Examples
   ///
-  /// # Panics
+  /// ```
+  /// tauri::Builder::default()
+  ///   .max_ipc_response_bytes(512 * 1024 * 1024);
+  /// ```
+  #[must_use]
+  pub fn max_ipc_response_bytes(mut self, limit: usize) -> Self {
+    self.ipc_limits.max_response_bytes = limit;
+    self.ipc_limits.measure_json_responses = true;
+    self
+  }
+
+  /// Caches the successful responses of a command, keyed by its arguments,
+  /// so invoking it again with the same arguments responds without running the command.
   ///
-  /// Panics if state of type `T` is already being managed.
+  /// The command is still subject to the ACL. Its cache can be cleared with [`AppHandle::invalidate_command_cache`].
+  /// Plugin commands are named `plugin:<plugin>|<command>`.
+  /// Commands taking the [`crate::Webview`], [`crate::Window`] or [`crate::WebviewWindow`] as argument are never cached.
   ///
-  /// # Mutability
+  /// This overrides the policy set with `#[tauri::command(cache = "30s")]`,
+  /// which is only registered when the command is first invoked and does not cache that first response.
   ///
-  /// Since the managed state is global and must be [`Send`] + [`Sync`], mutations can only happen through interior mutability:
+  /// # Examples
   ///
-  /// ```,no_run
-  /// use std::{collections::HashMap, sync::Mutex};
-  /// use tauri::State;
-  /// // here we use Mutex to achieve interior mutability
-  /// struct Storage {
+  /// ```
+  /// use std::time::Duration;
+  /// use tauri::ipc::{CacheKey, CachePolicy};
+  ///
+  /// #[tauri::command]
+  /// fn parse_file(path: std::path::PathBuf) -> Result<String, String> {
+  ///   std::fs::read_to_string(path).map_err(|e| e.to_string())
+  /// }
+  ///
+  /// tauri::Builder::default()
+  ///   .invoke_handler(tauri::generate_handler![parse_file])
+  ///   .cache_command(
+  ///     "parse_file",
+  ///     CachePolicy {
+  ///       ttl: Duration::from_secs(30),
+  ///       max_entries: 16,
+  ///       key: CacheKey::ArgsHash,
+  ///     },
+  ///   );
+  /// ```
+  #[must_use]
+  pub fn cache_command(
+    mut self,
+    command: impl Into<String>,
+    policy: crate::ipc::CachePolicy,
+  ) -> Self {
+    self.command_cache.insert(command.into(), policy);
+    self
+  }
+
+  /// Sets the interval at which the payloads of an event emitted with [`Manager::emit_batched`] are flushed,
+  /// 16 milliseconds by default.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// tauri::Builder::default()
+  ///   .event_batching("download-progress", std::time::Duration::from_millis(100));
+  /// ```
+  #[must_use]
+  pub fn event_batching(mut self, event: impl Into<String>, interval: Duration) -> Self {
+    self.event_batching.insert(event.into(), interval);
+    self
+  }
+
+  /// Sets how the listen, unlisten and emit operations of the event system reach the frontend,
+  /// [`EventTransport::Eval`] by default.
+  ///
+  /// [`EventTransport::Bridge`] sends them as messages instead of evaluating scripts,
+  /// for apps whose Content Security Policy does not allow `unsafe-eval`.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// tauri::Builder::default()
+  ///   .event_transport(tauri::EventTransport::Bridge);
+  /// ```
+  #[must_use]
+  pub fn event_transport(mut self, transport: EventTransport) -> Self {
+    self.event_transport = transport;
+    self
+  }
+
+  /// Records the events emitted from Rust and from the frontend in a ring buffer,
+  /// to debug the races between the listeners. See [`AppHandle::event_log`] and [`AppHandle::replay_events`].
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use tauri::RecorderConfig;
+  ///
+  /// tauri::Builder::default().record_events(
+  ///   RecorderConfig::new(500)
+  ///     .include_payloads(1024)
+  ///     .redact(|event, payload| (event != "auth-token").then(|| payload.to_string())),
+  /// );
+  /// ```
+  #[must_use]
+  pub fn record_events(mut self, config: crate::RecorderConfig) -> Self {
+    self.event_recorder.replace(config);
+    self
+  }
+
+  /// Configures the snapshots taken with [`AppHandle::diagnostics`] and captures the recent panics in them,
+  /// calling the previous panic hook afterwards.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use tauri::diagnostics::DiagnosticsConfig;
+  ///
+  /// tauri::Builder::default().diagnostics(DiagnosticsConfig::default().redact_urls());
+  /// ```
+  #[must_use]
+  pub fn diagnostics(mut self, config: crate::diagnostics::DiagnosticsConfig) -> Self {
+    self.diagnostics.replace(config);
+    self
+  }
+
+  /// Calls the given hook when a command panics, with the command name, the [`crate::diagnostics::PanicReport`]
+  /// and the label of the webview that invoked it, for instance to send the panic to an error reporting service.
+  ///
+  /// The panics of the commands are always caught and the frontend promise is rejected with
+  /// [`crate::ipc::IpcError::CommandPanicked`], and the panic is still printed by the panic hook,
+  /// with its backtrace if `RUST_BACKTRACE` is set.
+  ///
+  /// Panics can only be caught with `panic = "unwind"`, the default.
+  /// With `panic = "abort"` in the Cargo profile, a panicking command aborts the app before the hook runs.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// tauri::Builder::default().on_command_panic(|command, report, webview| {
+  ///   eprintln!(
+  ///     "command {command} invoked by {webview} panicked at {:?}: {}",
+  ///     report.location, report.message
+  ///   );
+  /// });
+  /// ```
+  #[must_use]
+  pub fn on_command_panic<F>(mut self, hook: F) -> Self
+  where
+    F: Fn(&str, &crate::diagnostics::PanicReport, &str) + Send + Sync + 'static,
+  {
+    self.on_command_panic.replace(Box::new(hook));
+    self
+  }
+
+  /// Detects the webview runtime when the app is built,
+  /// calling the handler if it is missing or older than the [minimum version](Self::minimum_webview_version).
+  ///
+  /// The handler can install the runtime, e.g. with a bundled bootstrapper, or show a message to the user,
+  /// and decides whether to build the app anyway, abort with [`crate::Error::WebviewRuntimeUnavailable`]
+  /// or detect the runtime again.
+  /// The runtime is not checked without a handler.
+  ///
+  /// See [`crate::webview_runtime_status`] for the platform support.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use tauri::webview::{RuntimeStatus, WebviewRuntimeAction};
+  ///
+  /// tauri::Builder::default()
+  ///   .on_webview_runtime_missing(|status| {
+  ///     eprintln!("the webview runtime is not available: {status}");
+  ///     match status {
+  ///       // run a bundled installer here and detect the runtime again
+  ///       RuntimeStatus::Missing => WebviewRuntimeAction::Retry,
+  ///       _ => WebviewRuntimeAction::Abort,
+  ///     }
+  ///   });
+  /// ```
+  #[must_use]
+  pub fn on_webview_runtime_missing<F>(mut self, handler: F) -> Self
+  where
+    F: FnMut(&RuntimeStatus) -> WebviewRuntimeAction + Send + 'static,
+  {
+    self.webview_runtime_missing.replace(Box::new(handler));
+    self
+  }
+
+  /// Sets the minimum version of the webview runtime checked by [`Self::on_webview_runtime_missing`],
+  /// [`crate::webview::MINIMUM_WEBVIEW_VERSION`] by default.
+  #[must_use]
+  pub fn minimum_webview_version(mut self, version: impl Into<String>) -> Self {
+    self.minimum_webview_version.replace(version.into());
+    self
+  }
+
+  /// Traces the IPC requests with `ipc::invoke` spans, disabled by default.
+  ///
+  /// The spans are created for every request, including the rejected ones and the unknown commands,
+  /// and carry the command name, the webview label and a request id.
+  /// The command handler and the response are traced in child spans.
+  ///
+  /// The request id is sent to the frontend with the response, in the `tauri-request-id` header
+  /// of the [`crate::ipc::ResponseMeta`] passed to the invoke callback.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// tauri::Builder::default()
+  ///   .trace_ipc(cfg!(debug_assertions));
+  /// ```
+  #[cfg(feature = "tracing")]
+  #[cfg_attr(docsrs, doc(cfg(feature = "tracing")))]
+  #[must_use]
+  pub fn trace_ipc(mut self, enabled: bool) -> Self {
+    self.trace_ipc = enabled;
+    self
+  }
+
+  /// Starts a remote debugging server on the given local port, so tools like Playwright or Puppeteer can attach to the webviews.
+  ///
+  /// Its endpoint is available with [`AppHandle::runtime_info`]. Never enable it on production builds.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** Passes `--remote-debugging-port` to WebView2, which serves the Chrome DevTools Protocol.
+  ///   All the webviews must share the same browser arguments.
+  /// - **Linux:** Starts the WebKit inspector server with the `WEBKIT_INSPECTOR_SERVER` environment variable
+  ///   when the first webview is created.
+  /// - **macOS / iOS / Android:** Unsupported.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// tauri::Builder::default()
+  ///   .remote_debugging_port(9222);
+  /// ```
+  #[cfg(feature = "devtools-protocol")]
+  #[cfg_attr(docsrs, doc(cfg(feature = "devtools-protocol")))]
+  #[must_use]
+  pub fn remote_debugging_port(mut self, port: u16) -> Self {
+    self.remote_debugging_port = Some(port);
+    self
+  }
+
+  /// Only accepts IPC requests from the app origin, see [`crate::ipc::default_origin_validator`].
+  #[must_use]
+  pub fn default_ipc_origin_validator(self) -> Self {
+    self.ipc_origin_validator(crate::ipc::default_origin_validator)
+  }
+
+  /// Registers a command the frontend can call synchronously with `invokeSync`,
+  /// avoiding the async round trip for tiny hot-path queries such as reading a cached value.
+  ///
+  /// The handler runs on a dedicated thread pool so the main thread is never blocked,
+  /// but the webview is blocked until it responds, so it must return quickly:
+  /// calls taking longer than 100 milliseconds are rejected with [`crate::ipc::IpcError::SyncCommandTimeout`]
+  /// and responses larger than 64 KiB are rejected with [`crate::ipc::IpcError::SyncResponseTooLarge`].
+  ///
+  /// The handler only receives the deserialized arguments and must return a serializable value,
+  /// so async commands cannot be registered.
+  /// Sync commands are subject to the same origin validator, invoke key and [`crate::ipc::AllowList`] checks as regular commands.
+  ///
+  /// # Panics
+  ///
+  /// Panics if a sync command with the same name is already registered.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use std::sync::atomic::{AtomicBool, Ordering};
+  ///
+  /// static SPELLCHECK: AtomicBool = AtomicBool::new(true);
+  ///
+  /// tauri::Builder::default()
+  ///   .sync_command("spellcheck_enabled", |()| {
+  ///     Ok::<_, String>(SPELLCHECK.load(Ordering::Relaxed))
+  ///   });
+  /// ```
+  ///
+  /// ```javascript
+  /// import { invokeSync } from '@tauri-apps/api/core'
+  /// const enabled = invokeSync('spellcheck_enabled')
+  /// ```
+  #[must_use]
+  pub fn sync_command<A, T, E, F>(mut self, name: impl Into<String>, handler: F) -> Self
+  where
+    A: serde::de::DeserializeOwned,
+    T: serde::Serialize,
+    E: Into<InvokeError>,
+    F: Fn(A) -> Result<T, E> + Send + Sync + 'static,
+  {
+    self.sync_commands.register(name.into(), handler);
+    self
+  }
+
+  /// Defines the setup hook.
+  ///
+  /// # Examples
+  #[cfg_attr(
+    feature = "unstable",
+    doc = r####"
+```
+use tauri::Manager;
+tauri::Builder::default()
+  .setup(|app| {
+    let main_window = app.get_window("main").unwrap();
+    main_window.set_title("Tauri!");
+    Ok(())
+  });
+```
+  "####
+  )]
+  #[must_use]
+  pub fn setup<F>(mut self, setup: F) -> Self
+  where
+    F: FnOnce(&mut App<R>) -> Result<(), Box<dyn std::error::Error>> + Send + 'static,
+  {
+    self.setup = Box::new(setup);
+    self
+  }
+
+  /// Registers a state initialized asynchronously when the app is built,
+  /// before any window is created, so commands can take a [`State<T>`](crate::State) without wrapping it in an `Option`.
+  ///
+  /// The initializers run concurrently unless an ordering is declared with [`Self::after`].
+  /// The app fails to build if an initializer fails.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use futures_util::FutureExt;
+  ///
+  /// struct Db(String);
+  /// struct Cache(usize);
+  ///
+  /// tauri::Builder::default()
+  ///   .setup_state(|_app| async { Ok(Db("postgres://localhost".into())) }.boxed())
+  ///   .setup_state(|app| {
+  ///     async move {
+  ///       use tauri::Manager;
+  ///       let db = app.state::<Db>();
+  ///       Ok(Cache(db.0.len()))
+  ///     }
+  ///     .boxed()
+  ///   })
+  ///   .after::<Db>();
+  /// ```
+  #[must_use]
+  pub fn setup_state<T, F>(mut self, f: F) -> Self
+  where
+    T: Send + Sync + 'static,
+    F: FnOnce(&AppHandle<R>) -> BoxFuture<'_, Result<T, StateInitError>> + Send + 'static,
+  {
+    self
+      .state_initializers
+      .push(state_init::StateInitializer::new(f));
+    self
+  }
+
+  /// Runs the last initializer registered with [`Self::setup_state`] after the one of the `T` state.
+  ///
+  /// # Panics
+  ///
+  /// Panics if no state initializer was registered.
+  #[must_use]
+  pub fn after<T: Send + Sync + 'static>(mut self) -> Self {
+    self
+      .state_initializers
+      .last_mut()
+      .expect("`Builder::after` must be called after `Builder::setup_state`")
+      .after::<T>();
+    self
+  }
+
+  /// Defines the page load hook.
+  #[must_use]
+  pub fn on_page_load<F>(mut self, on_page_load: F) -> Self
+  where
+    F: Fn(&Webview<R>, &PageLoadPayload<'_>) + Send + Sync + 'static,
+  {
+    self.on_page_load.replace(Arc::new(on_page_load));
+    self
+  }
+
+  /// Adds a Tauri application plugin.
+  ///
+  /// A plugin is created using the [`crate::plugin::Builder`] struct.Check its documentation for more information.
+  ///
+  /// Plugins are initialized in registration order, except that the plugins declared with
+  /// [`crate::plugin::Builder::requires`] are initialized before the plugins requiring them.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// mod plugin {
+  ///   use tauri::{plugin::{Builder as PluginBuilder, TauriPlugin}, RunEvent, Runtime};
+  ///
+  ///   // this command can be called in the frontend using `invoke('plugin:window|do_something')`.
+  ///   #[tauri::command]
+  ///   async fn do_something<R: Runtime>(app: tauri::AppHandle<R>, window: tauri::Window<R>) -> Result<(), String> {
+  ///     println!("command called");
+  ///     Ok(())
+  ///   }
+  ///   pub fn init<R: Runtime>() -> TauriPlugin<R> {
+  ///     PluginBuilder::new("window")
+  ///       .setup(|app, api| {
+  ///         // initialize the plugin here
+  ///         Ok(())
+  ///       })
+  ///       .on_event(|app, event| {
+  ///         match event {
+  ///           RunEvent::Ready => {
+  ///             println!("app is ready");
+  ///           }
+  ///           RunEvent::WindowEvent { label, event, .. } => {
+  ///             println!("window {} received an event: {:?}", label, event);
+  ///           }
+  ///           _ => (),
+  ///         }
+  ///       })
+  ///       .invoke_handler(tauri::generate_handler![do_something])
+  ///       .build()
+  ///   }
+  /// }
+  ///
+  /// tauri::Builder::default()
+  ///   .plugin(plugin::init());
+  /// ```
+  #[must_use]
+  pub fn plugin<P: Plugin<R> + 'static>(mut self, plugin: P) -> Self {
+    self.plugins.register(Box::new(plugin));
+    self
+  }
+
+  /// Add `state` to the state managed by the application.
+  ///
+  /// This method can be called any number of times as long as each call
+  /// refers to a different `T`.
+  ///
+  /// Managed state can be retrieved by any command handler via the
+  /// [`crate::State`] guard. In particular, if a value of type `T`
+  /// is managed by Tauri, adding `State<T>` to the list of arguments in a
+  /// command handler instructs Tauri to retrieve the managed value.
+  /// Additionally, [`state`](crate::Manager#method.state) can be used to retrieve the value manually.
+  ///
+  /// # Panics
+  ///
+  /// Panics if state of type `T` is already being managed.
+  ///
+  /// # Mutability
+  ///
+  /// Since the managed state is global and must be [`Send`] + [`Sync`], mutations can only happen through interior mutability:
+  ///
+  /// ```,no_run
+  /// use std::{collections::HashMap, sync::Mutex};
+  /// use tauri::State;
+  /// // here we use Mutex to achieve interior mutability
+  /// struct Storage {
   ///   store: Mutex<HashMap<u64, String>>,
   /// }
   /// struct Connection;
@@ -1441,7 +2333,7 @@ tauri::Builder::default()
     self.uri_scheme_protocols.insert(
       uri_scheme.into(),
       Arc::new(UriSchemeProtocol {
-        protocol: Box::new(move |app, request, responder| {
+        protocol: Box::new(move |app, _context, request, responder| {
           responder.respond(protocol(app, request))
         }),
       }),
@@ -1449,6 +2341,42 @@ tauri::Builder::default()
     self
   }
 
+  /// Similar to [`Self::register_uri_scheme_protocol`] but the handler also receives
+  /// the [`ProtocolRequestContext`] of the webview that made the request.
+  ///
+  /// # Examples
+  /// ```
+  /// tauri::Builder::default()
+  ///   .register_uri_scheme_protocol_with_context("app-files", |_app, context, _request| {
+  ///     http::Response::builder()
+  ///       .body(format!("requested by {}", context.webview_label).into_bytes())
+  ///       .unwrap()
+  ///   });
+  /// ```
+  #[must_use]
+  pub fn register_uri_scheme_protocol_with_context<
+    N: Into<String>,
+    T: Into<Cow<'static, [u8]>>,
+    H: Fn(&AppHandle<R>, &ProtocolRequestContext, http::Request<Vec<u8>>) -> http::Response<T>
+      + Send
+      + Sync
+      + 'static,
+  >(
+    mut self,
+    uri_scheme: N,
+    protocol: H,
+  ) -> Self {
+    self.uri_scheme_protocols.insert(
+      uri_scheme.into(),
+      Arc::new(UriSchemeProtocol {
+        protocol: Box::new(move |app, context, request, responder| {
+          responder.respond(protocol(app, context, request))
+        }),
+      }),
+    );
+    self
+  }
+
   /// Similar to [`Self::register_uri_scheme_protocol`] but with an asynchronous responder that allows you
   /// to process the request in a separate thread and respond asynchronously.
   ///
@@ -1490,6 +2418,31 @@ tauri::Builder::default()
     mut self,
     uri_scheme: N,
     protocol: H,
+  ) -> Self {
+    self.uri_scheme_protocols.insert(
+      uri_scheme.into(),
+      Arc::new(UriSchemeProtocol {
+        protocol: Box::new(move |app, _context, request, responder| {
+          protocol(app, request, responder)
+        }),
+      }),
+    );
+    self
+  }
+
+  /// Similar to [`Self::register_asynchronous_uri_scheme_protocol`] but the handler also receives
+  /// the [`ProtocolRequestContext`] of the webview that made the request.
+  #[must_use]
+  pub fn register_asynchronous_uri_scheme_protocol_with_context<
+    N: Into<String>,
+    H: Fn(&AppHandle<R>, &ProtocolRequestContext, http::Request<Vec<u8>>, UriSchemeResponder)
+      + Send
+      + Sync
+      + 'static,
+  >(
+    mut self,
+    uri_scheme: N,
+    protocol: H,
   ) -> Self {
     self.uri_scheme_protocols.insert(
       uri_scheme.into(),
@@ -1522,6 +2475,50 @@ tauri::Builder::default()
     self
   }
 
+  /// Registers a hook that runs when the app is about to exit.
+  ///
+  /// When an exit request is not prevented by [`RunEvent::ExitRequested`], the exit is deferred
+  /// until all hooks finish or the [`Self::before_exit_timeout`] elapses,
+  /// and [`RunEvent::ExitRequested`] is triggered again with the original exit code.
+  /// A second exit request while the hooks run exits immediately.
+  ///
+  /// The hooks do not run on [`AppHandle::restart`].
+  ///
+  /// # Examples
+  /// ```
+  /// tauri::Builder::default()
+  ///   .on_before_exit(|app| async move {
+  ///     // let the webviews save their state
+  ///     app.flush_webviews().await;
+  ///   });
+  /// ```
+  #[must_use]
+  pub fn on_before_exit<F, Fut>(mut self, hook: F) -> Self
+  where
+    F: Fn(AppHandle<R>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+  {
+    self
+      .before_exit
+      .push(Box::new(move |app| Box::pin(hook(app))));
+    self
+  }
+
+  /// Sets how long the exit waits for the [`Self::on_before_exit`] hooks. Defaults to 5 seconds.
+  #[must_use]
+  pub fn before_exit_timeout(mut self, timeout: Duration) -> Self {
+    self.before_exit_timeout = timeout;
+    self
+  }
+
+  /// Persists the changes made to the [`Manager::fs_scope`] to `scope.json` in the app data directory
+  /// and restores them when the app starts. Disabled by default.
+  #[must_use]
+  pub fn persist_fs_scope(mut self, persist: bool) -> Self {
+    self.persist_fs_scope = persist;
+    self
+  }
+
   /// Builds the application.
   #[allow(clippy::type_complexity, unused_mut)]
   #[cfg_attr(
@@ -1529,6 +2526,11 @@ tauri::Builder::default()
     tracing::instrument(name = "app::build", skip_all)
   )]
   pub fn build<A: Assets>(mut self, context: Context<A>) -> crate::Result<App<R>> {
+    if let Some(handler) = &mut self.webview_runtime_missing {
+      let minimum = self.minimum_webview_version.as_deref();
+      crate::webview::runtime_status::check(handler, || RuntimeStatus::detect::<R>(minimum))?;
+    }
+
     #[cfg(target_os = "macos")]
     if self.menu.is_none() && self.enable_macos_default_menu {
       self.menu = Some(Box::new(|app_handle| {
@@ -1536,6 +2538,11 @@ tauri::Builder::default()
       }));
     }
 
+    #[cfg(all(dev, feature = "dev-dynamic-assets"))]
+    let frontend_dist_dir = context.frontend_dist_dir.clone();
+    #[cfg(all(dev, desktop, feature = "dev-config-reload"))]
+    let config_dir = context.config_dir.clone();
+
     let manager = Arc::new(AppManager::with_handlers(
       context,
       self.plugins,
@@ -1547,6 +2554,18 @@ tauri::Builder::default()
       #[cfg(desktop)]
       HashMap::new(),
       (self.invoke_responder, self.invoke_initialization_script),
+      self.ipc_origin_validator,
+      self.scope_policy,
+      self.sync_commands,
+      self.local_origins,
+      self.ipc_limits,
+      self.event_batching,
+      self.event_recorder,
+      self.event_transport,
+      #[cfg(feature = "tracing")]
+      self.trace_ipc,
+      #[cfg(feature = "devtools-protocol")]
+      self.remote_debugging_port,
     ));
 
     let runtime_args = RuntimeInitArgs {
@@ -1634,15 +2653,68 @@ tauri::Builder::default()
     let env = Env::default();
     app.manage(env);
 
+    #[cfg(feature = "command-manifest")]
+    {
+      let manifest = crate::ipc::manifest::CommandManifest::new(
+        self.command_manifest.map(|f| f()).unwrap_or_default(),
+      );
+      if let Ok(path) = std::env::var("TAURI_EXPORT_COMMANDS") {
+        std::fs::write(path, serde_json::to_vec_pretty(&manifest.0)?)?;
+      }
+      app.manage(manifest);
+    }
+
+    app.manage(exit::BeforeExit::new(
+      self.before_exit,
+      self.before_exit_timeout,
+    ));
+    app.manage(instance_id::InstanceId::default());
+
+    #[cfg(all(dev, feature = "dev-dynamic-assets"))]
+    if let Some(dir) = frontend_dist_dir {
+      crate::manager::dynamic_assets::watch(&app.handle, &dir)?;
+    }
+
+    #[cfg(all(dev, desktop, feature = "dev-config-reload"))]
+    if let Some(dir) = config_dir {
+      crate::manager::config_reload::watch(&app.handle, &dir)?;
+    }
+
     app.manage(Scopes {
+      fs: crate::scope::app_fs_scope(&app.handle, self.persist_fs_scope)?,
       #[cfg(feature = "protocol-asset")]
-      asset_protocol: crate::scope::fs::Scope::new(
-        &app,
-        &app.config().app.security.asset_protocol.scope,
-      )?,
+      asset_protocol: {
+        let config = &app.config().app.security.asset_protocol;
+        let scope = crate::scope::fs::Scope::new(&app, &config.scope)?;
+        scope.add_config_limits(&app, &config.limits)?;
+        scope
+      },
     });
 
     app.manage(ChannelDataIpcQueue::default());
+    app.manage(crate::ipc::stream::ActiveStreams::default());
+    app.manage(crate::ipc::cancellation::ActiveInvokes::default());
+    app.manage(crate::ipc::cache::CommandCache::new(self.command_cache));
+
+    let capture_panics = self.diagnostics.is_some();
+    let diagnostics =
+      crate::diagnostics::DiagnosticsState::new(self.diagnostics.unwrap_or_default());
+    if capture_panics {
+      diagnostics.capture_panics();
+    }
+    app.manage(diagnostics);
+
+    if let Some(hook) = self.on_command_panic {
+      crate::ipc::panic::record_panics();
+      app.manage(crate::ipc::panic::CommandPanicHook(hook));
+    }
+
+    #[cfg(feature = "remote-automation")]
+    {
+      let endpoint = crate::automation::start(app.handle())?;
+      app.manage(endpoint);
+      app.manage(crate::automation::PendingEvals::default());
+    }
     app.handle.plugin(crate::ipc::channel::plugin())?;
 
     #[cfg(windows)]
@@ -1691,6 +2763,13 @@ tauri::Builder::default()
 
     app.manager.initialize_plugins(handle)?;
 
+    if !self.state_initializers.is_empty() {
+      crate::async_runtime::safe_block_on(state_init::run(
+        handle.clone(),
+        self.state_initializers,
+      ))?;
+    }
+
     Ok(app)
   }
 
@@ -1701,6 +2780,25 @@ tauri::Builder::default()
   }
 }
 
+/// Information about the webview that made a custom protocol request,
+/// see [`Builder::register_uri_scheme_protocol_with_context`].
+///
+/// ## Platform-specific
+///
+/// - **Linux**: The protocol handlers are registered on the webkit2gtk web context, which doesn't tell which webview made a request.
+///   Each webview gets its own web context, except the webviews of a [`crate::webview::WebviewBuilder::shared_session`],
+///   which can only be used by one webview at a time, and the webviews sharing a data directory
+///   when the `TAURI_WEBVIEW_AUTOMATION` environment variable is set to `true`.
+///   The context of their requests is the one of the last webview created with that data directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ProtocolRequestContext {
+  /// The label of the webview that made the request.
+  pub webview_label: String,
+  /// The label of the window hosting the webview.
+  pub window_label: String,
+}
+
 pub(crate) type UriSchemeResponderFn = Box<dyn FnOnce(http::Response<Cow<'static, [u8]>>) + Send>;
 pub struct UriSchemeResponder(pub(crate) UriSchemeResponderFn);
 
@@ -1750,6 +2848,10 @@ impl<R: Runtime> HasDisplayHandle for App<R> {
 fn setup<R: Runtime>(app: &mut App<R>) -> crate::Result<()> {
   app.ran_setup = true;
 
+  if let Some(label) = app.config().app.duplicated_webview_label() {
+    return Err(crate::Error::WebviewLabelAlreadyExists(label.into()));
+  }
+
   let window_labels = app
     .config()
     .app
@@ -1757,17 +2859,34 @@ fn setup<R: Runtime>(app: &mut App<R>) -> crate::Result<()> {
     .iter()
     .map(|p| p.label.clone())
     .collect::<Vec<_>>();
-  let webview_labels = window_labels
+  let webview_labels = app
+    .config()
+    .app
+    .windows
     .iter()
-    .map(|label| WebviewLabelDef {
-      window_label: label.clone(),
-      label: label.clone(),
+    .flat_map(|window| {
+      window
+        .webview_labels()
+        .into_iter()
+        .map(|label| WebviewLabelDef {
+          window_label: window.label.clone(),
+          label: label.into(),
+        })
     })
     .collect::<Vec<_>>();
 
   for window_config in app.config().app.windows.clone() {
-    WebviewWindowBuilder::from_config(app.handle(), &window_config)?
-      .build_internal(&window_labels, &webview_labels)?;
+    if window_config.webviews.is_empty() {
+      WebviewWindowBuilder::from_config(app.handle(), &window_config)?
+        .build_internal(&window_labels, &webview_labels)?;
+    } else {
+      crate::webview::build_config_webviews(
+        app.handle(),
+        &window_config,
+        &window_labels,
+        &webview_labels,
+      )?;
+    }
   }
 
   if let Some(setup) = app.setup.take() {
@@ -1777,6 +2896,37 @@ fn setup<R: Runtime>(app: &mut App<R>) -> crate::Result<()> {
   Ok(())
 }
 
+/// Runs the app callback, deferring the exit for the [`Builder::on_before_exit`] hooks
+/// if the callback did not prevent it.
+fn on_event_loop_event_with_callback<R: Runtime, F: FnMut(&AppHandle<R>, RunEvent)>(
+  app_handle: &AppHandle<R>,
+  event: RuntimeRunEvent<EventLoopMessage>,
+  manager: &AppManager<R>,
+  callback: &mut F,
+) {
+  if let RuntimeRunEvent::ExitRequested { code, tx } = event {
+    let (api_tx, api_rx) = channel();
+    let event = on_event_loop_event(
+      app_handle,
+      RuntimeRunEvent::ExitRequested { code, tx: api_tx },
+      manager,
+    );
+    callback(app_handle, event);
+
+    let prevented = matches!(api_rx.try_recv(), Ok(ExitRequestedEventAction::Prevent));
+    if prevented
+      || app_handle
+        .state::<exit::BeforeExit<R>>()
+        .defer(app_handle, code)
+    {
+      let _ = tx.send(ExitRequestedEventAction::Prevent);
+    }
+  } else {
+    let event = on_event_loop_event(app_handle, event, manager);
+    callback(app_handle, event);
+  }
+}
+
 fn on_event_loop_event<R: Runtime>(
   app_handle: &AppHandle<R>,
   event: RuntimeRunEvent<EventLoopMessage>,
@@ -1791,7 +2941,10 @@ fn on_event_loop_event<R: Runtime>(
   }
 
   let event = match event {
-    RuntimeRunEvent::Exit => RunEvent::Exit,
+    RuntimeRunEvent::Exit => {
+      manager.begin_shutdown();
+      RunEvent::Exit
+    }
     RuntimeRunEvent::ExitRequested { code, tx } => RunEvent::ExitRequested {
       code,
       api: ExitRequestApi(tx),
@@ -1825,10 +2978,12 @@ fn on_event_loop_event<R: Runtime>(
     }
     RuntimeRunEvent::Resumed => RunEvent::Resumed,
     RuntimeRunEvent::MainEventsCleared => RunEvent::MainEventsCleared,
-    RuntimeRunEvent::UserEvent(t) => {
+    #[allow(unused_mut)]
+    RuntimeRunEvent::UserEvent(mut t) => {
       match t {
         #[cfg(desktop)]
-        EventLoopMessage::MenuEvent(ref e) => {
+        EventLoopMessage::MenuEvent(ref mut e) => {
+          e.webview = app_handle.manager.menu_event_target();
           for listener in &*app_handle
             .manager
             .menu
@@ -1843,6 +2998,20 @@ fn on_event_loop_event<R: Runtime>(
               listener(&w, e.clone());
             }
           }
+          if let Some(label) = &e.webview {
+            if let Some(listener) = app_handle
+              .manager
+              .menu
+              .webview_event_listeners
+              .lock()
+              .unwrap()
+              .get(label)
+            {
+              if let Some(webview) = app_handle.manager().get_webview(label) {
+                listener(&webview, e.clone());
+              }
+            }
+          }
         }
         #[cfg(all(desktop, feature = "tray-icon"))]
         EventLoopMessage::TrayIconEvent(ref e) => {
@@ -1896,4 +3065,219 @@ mod tests {
       crate::test_utils::assert_sync::<super::AssetResolver<crate::Wry>>();
     }
   }
+
+  #[cfg(desktop)]
+  #[test]
+  fn menu_events_target_focused_webview() {
+    use std::sync::{Arc, Mutex};
+
+    use crate::{
+      menu::MenuEvent, runtime::RunEvent as RuntimeRunEvent, sealed::ManagerBase, test::mock_app,
+      EventLoopMessage, WebviewWindowBuilder,
+    };
+
+    let app = mock_app();
+    let first = WebviewWindowBuilder::new(&app, "first", Default::default())
+      .build()
+      .unwrap();
+    let second = WebviewWindowBuilder::new(&app, "second", Default::default())
+      .build()
+      .unwrap();
+
+    let activations = Arc::new(Mutex::new(Vec::new()));
+    for webview in [&first.webview, &second.webview] {
+      let activations = activations.clone();
+      webview.on_menu_event(move |webview, event| {
+        activations
+          .lock()
+          .unwrap()
+          .push((webview.label().to_string(), event.webview));
+      });
+    }
+
+    let activate = || {
+      super::on_event_loop_event(
+        app.handle(),
+        RuntimeRunEvent::UserEvent(EventLoopMessage::MenuEvent(MenuEvent {
+          id: "copy".into(),
+          webview: None,
+        })),
+        app.manager(),
+      );
+    };
+
+    // no webview focus is known yet
+    activate();
+    assert!(activations.lock().unwrap().is_empty());
+
+    second.webview.set_focus().unwrap();
+    activate();
+    assert_eq!(
+      *activations.lock().unwrap(),
+      [("second".to_string(), Some("second".to_string()))]
+    );
+
+    // the listener is removed with the webview
+    app.manager().on_webview_close("second");
+    assert!(app
+      .manager()
+      .menu
+      .webview_event_listeners
+      .lock()
+      .unwrap()
+      .get("second")
+      .is_none());
+    assert_eq!(app.manager().menu_event_target(), None);
+  }
+
+  #[test]
+  fn asset_resolver() {
+    use crate::{
+      test::{mock_builder, mock_context},
+      utils::assets::{phf, phf::phf_map, EmbeddedAssets},
+    };
+
+    // brotli compressed `console.log('hello')`
+    #[cfg(feature = "compression")]
+    const MAIN_JS: &[u8] = &[
+      139, 9, 128, 99, 111, 110, 115, 111, 108, 101, 46, 108, 111, 103, 40, 39, 104, 101, 108, 108,
+      111, 39, 41, 3,
+    ];
+    #[cfg(not(feature = "compression"))]
+    const MAIN_JS: &[u8] = b"console.log('hello')";
+
+    let assets = EmbeddedAssets::new(
+      phf_map! {
+        "/main.js" => MAIN_JS,
+      },
+      &[],
+      phf_map! {},
+    )
+    .with_original_sizes(phf_map! {
+      "/main.js" => 20usize,
+    });
+    let app = mock_builder().build(mock_context(assets)).unwrap();
+    let resolver = app.asset_resolver();
+
+    assert_eq!(resolver.is_compressed(), cfg!(feature = "compression"));
+    assert_eq!(
+      resolver.iter().collect::<Vec<_>>(),
+      vec![("/main.js", "text/javascript".to_string(), 20)]
+    );
+    assert!(resolver.exists("main.js"));
+    assert!(resolver.exists("/main.js"));
+    assert!(!resolver.exists("index.html"));
+    assert_eq!(
+      resolver.read("main.js").as_deref(),
+      Some(&b"console.log('hello')"[..])
+    );
+    assert_eq!(resolver.read("index.html"), None);
+    assert_eq!(resolver.original_size("main.js"), Some(20));
+  }
+
+  #[test]
+  fn runtime_info() {
+    let app = crate::test::mock_app();
+    let info = app.handle().runtime_info();
+    // the mock runtime does not have a webview
+    assert_eq!(info.webview_version, "unknown");
+    assert_eq!(info.runtime, "mock");
+    assert_eq!(info.os, std::env::consts::OS);
+    assert_eq!(info.tauri_version, crate::VERSION);
+    assert_eq!(
+      serde_json::to_value(&info).unwrap()["webviewVersion"],
+      "unknown"
+    );
+  }
+
+  #[test]
+  fn plugin_dependencies() {
+    use crate::{
+      plugin::Builder as PluginBuilder,
+      test::{mock_builder, mock_context, noop_assets},
+    };
+    use std::sync::{Arc, Mutex};
+
+    let initialized = Arc::new(Mutex::new(Vec::new()));
+    let plugin = |name: &'static str, dependencies: &[&'static str]| {
+      let initialized = initialized.clone();
+      let mut builder =
+        PluginBuilder::<crate::test::MockRuntime>::new(name).setup(move |app, _api| {
+          let dependencies_initialized = app.plugin_initialized("store");
+          initialized
+            .lock()
+            .unwrap()
+            .push((name, dependencies_initialized));
+          Ok(())
+        });
+      for dependency in dependencies {
+        builder = builder.requires(dependency);
+      }
+      builder.build()
+    };
+
+    let app = mock_builder()
+      .plugin(plugin("window-state", &["store"]))
+      .plugin(plugin("store", &[]))
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    assert_eq!(
+      *initialized.lock().unwrap(),
+      vec![("store", false), ("window-state", true)]
+    );
+    assert!(app.handle().plugin_initialized("window-state"));
+
+    // a plugin added at runtime requires its dependencies to be initialized
+    assert!(matches!(
+      app.handle().plugin(plugin("sync", &["fs"])),
+      Err(crate::Error::PluginDependencyMissing(chain)) if chain == ["sync", "fs"]
+    ));
+    app.handle().plugin(plugin("sync", &["store"])).unwrap();
+    assert!(app.handle().plugin_initialized("sync"));
+    assert!(app.handle().remove_plugin("sync"));
+    assert!(!app.handle().plugin_initialized("sync"));
+
+    assert!(matches!(
+      mock_builder()
+        .plugin(plugin("window-state", &["store"]))
+        .build(mock_context(noop_assets())),
+      Err(crate::Error::PluginDependencyMissing(chain)) if chain == ["window-state", "store"]
+    ));
+  }
+
+  #[test]
+  fn protocol_handlers_receive_the_webview_context() {
+    use std::sync::mpsc::channel;
+
+    use crate::{
+      test::{mock_builder, mock_context, noop_assets},
+      webview::WebviewBuilder,
+    };
+
+    let app = mock_builder()
+      .register_uri_scheme_protocol_with_context("context", |_app, context, _request| {
+        http::Response::new(
+          format!("{}/{}", context.window_label, context.webview_label).into_bytes(),
+        )
+      })
+      .build(mock_context(noop_assets()))
+      .unwrap();
+
+    // the handlers are registered for each webview, on its own web context on webkit2gtk
+    // except under automation, see `ProtocolRequestContext`
+    for (window, webview) in [("main", "first"), ("main", "second"), ("other", "third")] {
+      let pending = WebviewBuilder::<crate::test::MockRuntime>::new(webview, Default::default())
+        .into_pending_webview(&app, window, &[], &[])
+        .unwrap();
+      let (tx, rx) = channel();
+      (pending.uri_scheme_protocols["context"])(
+        http::Request::new(Vec::new()),
+        Box::new(move |response| tx.send(response.into_body().into_owned()).unwrap()),
+      );
+      assert_eq!(
+        rx.recv().unwrap(),
+        format!("{window}/{webview}").into_bytes()
+      );
+    }
+  }
 }
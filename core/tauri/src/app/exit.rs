@@ -0,0 +1,268 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Deferring the app exit until the hooks registered with [`crate::Builder::on_before_exit`] finish.
+
+use std::{
+  collections::HashSet,
+  future::Future,
+  pin::Pin,
+  sync::{Arc, Mutex},
+  time::Duration,
+};
+
+use crate::{sealed::ManagerBase, AppHandle, Manager, Runtime};
+
+/// The event emitted to the webviews by [`AppHandle::flush_webviews`].
+pub(crate) const BEFORE_EXIT_EVENT: &str = "tauri://before-exit";
+/// The event a webview emits with its label once it handled [`BEFORE_EXIT_EVENT`].
+pub(crate) const BEFORE_EXIT_ACK_EVENT: &str = "tauri://before-exit-ack";
+/// How long the exit is deferred by default.
+pub(crate) const DEFAULT_BEFORE_EXIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A hook that runs before the app exits, see [`crate::Builder::on_before_exit`].
+pub(crate) type BeforeExitHook<R> =
+  Box<dyn Fn(AppHandle<R>) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+  Idle,
+  TearingDown,
+  Done,
+}
+
+pub(crate) struct BeforeExit<R: Runtime> {
+  hooks: Vec<BeforeExitHook<R>>,
+  timeout: Duration,
+  state: Arc<Mutex<State>>,
+}
+
+impl<R: Runtime> BeforeExit<R> {
+  pub(crate) fn new(hooks: Vec<BeforeExitHook<R>>, timeout: Duration) -> Self {
+    Self {
+      hooks,
+      timeout,
+      state: Arc::new(Mutex::new(State::Idle)),
+    }
+  }
+
+  /// Called when an exit request was not prevented by the app,
+  /// returns whether the exit must be prevented while the hooks run.
+  pub(crate) fn defer(&self, app: &AppHandle<R>, code: Option<i32>) -> bool {
+    if self.hooks.is_empty() || code == Some(super::RESTART_EXIT_CODE) {
+      return false;
+    }
+
+    let mut state = self.state.lock().unwrap();
+    match *state {
+      State::Idle => {
+        *state = State::TearingDown;
+        self.teardown(app.clone(), code.unwrap_or(0));
+        true
+      }
+      // the hooks finished, or a second exit request forces the exit while they run
+      State::TearingDown | State::Done => {
+        *state = State::Idle;
+        false
+      }
+    }
+  }
+
+  fn teardown(&self, app: AppHandle<R>, code: i32) {
+    let hooks = futures_util::future::join_all(self.hooks.iter().map(|hook| hook(app.clone())));
+    // the hooks keep running if the timeout elapses
+    let hooks = crate::async_runtime::spawn(hooks);
+    crate::async_runtime::spawn(wait_for_hooks(
+      hooks,
+      self.timeout,
+      self.state.clone(),
+      move || app.exit(code),
+    ));
+  }
+}
+
+/// Waits for the hooks to finish or the timeout to elapse,
+/// then exits unless a second exit request already forced the exit.
+async fn wait_for_hooks<F: FnOnce()>(
+  hooks: impl Future,
+  timeout: Duration,
+  state: Arc<Mutex<State>>,
+  exit: F,
+) {
+  let _ = tokio::time::timeout(timeout, hooks).await;
+  let mut state = state.lock().unwrap();
+  if *state == State::TearingDown {
+    *state = State::Done;
+    drop(state);
+    exit();
+  }
+}
+
+pub(crate) async fn flush_webviews<R: Runtime>(app: &AppHandle<R>) {
+  let mut pending = app
+    .manager()
+    .listeners()
+    .js_listener_webviews(BEFORE_EXIT_EVENT)
+    .into_iter()
+    .collect::<HashSet<_>>();
+  if pending.is_empty() {
+    return;
+  }
+
+  let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+  let id = app.listen_any(BEFORE_EXIT_ACK_EVENT, move |event| {
+    if let Ok(label) = serde_json::from_str::<String>(event.payload()) {
+      let _ = tx.send(label);
+    }
+  });
+
+  if app.emit(BEFORE_EXIT_EVENT, ()).is_ok() {
+    while !pending.is_empty() {
+      match rx.recv().await {
+        Some(label) => {
+          pending.remove(&label);
+        }
+        None => break,
+      }
+    }
+  }
+
+  app.unlisten(id);
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    sync::{
+      atomic::{AtomicBool, AtomicUsize, Ordering},
+      Arc, Mutex,
+    },
+    time::Duration,
+  };
+
+  use tokio::time::Instant;
+
+  use super::{wait_for_hooks, State};
+  use crate::{
+    sealed::ManagerBase,
+    test::{mock_app, mock_builder, mock_context, noop_assets},
+    Manager, RunEvent,
+  };
+
+  fn run_app(hook_duration: Duration, timeout: Duration, exit_requests: usize) -> usize {
+    let hook_runs = Arc::new(AtomicUsize::new(0));
+    let hook_runs_ = hook_runs.clone();
+    let app = mock_builder()
+      .on_before_exit(move |_app| {
+        // counted when the hook is called, the forced exit does not wait for the future to be polled
+        hook_runs_.fetch_add(1, Ordering::Relaxed);
+        tokio::time::sleep(hook_duration)
+      })
+      .before_exit_timeout(timeout)
+      .build(mock_context(noop_assets()))
+      .unwrap();
+
+    app.run(move |app, event| {
+      if let RunEvent::Ready = event {
+        for _ in 0..exit_requests {
+          app.exit(0);
+        }
+      }
+    });
+    hook_runs.load(Ordering::Relaxed)
+  }
+
+  #[test]
+  fn runs_hooks_before_exit() {
+    assert_eq!(run_app(Duration::ZERO, Duration::from_secs(60), 1), 1);
+  }
+
+  #[test]
+  fn exits_on_timeout() {
+    assert_eq!(
+      run_app(Duration::from_secs(60), Duration::from_millis(10), 1),
+      1
+    );
+  }
+
+  #[test]
+  fn second_request_forces_exit() {
+    assert_eq!(
+      run_app(Duration::from_secs(60), Duration::from_secs(60), 2),
+      1
+    );
+  }
+
+  async fn wait(hook_duration: Duration, timeout: Duration, state: State) -> (bool, Duration) {
+    let state = Arc::new(Mutex::new(state));
+    let exited = AtomicBool::new(false);
+    let start = Instant::now();
+    wait_for_hooks(tokio::time::sleep(hook_duration), timeout, state, || {
+      exited.store(true, Ordering::Relaxed)
+    })
+    .await;
+    (exited.load(Ordering::Relaxed), start.elapsed())
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn waits_for_hooks() {
+    let (exited, elapsed) = wait(
+      Duration::from_secs(2),
+      Duration::from_secs(30),
+      State::TearingDown,
+    )
+    .await;
+    assert!(exited);
+    assert_eq!(elapsed, Duration::from_secs(2));
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn waits_until_timeout() {
+    let (exited, elapsed) = wait(
+      Duration::from_secs(60),
+      Duration::from_secs(1),
+      State::TearingDown,
+    )
+    .await;
+    assert!(exited);
+    assert_eq!(elapsed, Duration::from_secs(1));
+  }
+
+  #[tokio::test(start_paused = true)]
+  async fn forced_exit_is_not_repeated() {
+    // a second exit request resets the state while the hooks run
+    let (exited, _) = wait(Duration::ZERO, Duration::from_secs(1), State::Idle).await;
+    assert!(!exited);
+  }
+
+  #[test]
+  fn flush_webviews_waits_for_acks() {
+    let app = mock_app();
+    let webview = crate::WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    app
+      .manager()
+      .listeners()
+      .listen_js(
+        super::BEFORE_EXIT_EVENT,
+        "main",
+        crate::EventTarget::Any,
+        1,
+        |_| Ok(()),
+      )
+      .unwrap();
+
+    let handle = app.handle().clone();
+    let flush = crate::async_runtime::spawn(async move { handle.flush_webviews().await });
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(!flush.inner().is_finished());
+
+    // what the frontend sends once it handled the event
+    webview
+      .emit(super::BEFORE_EXIT_ACK_EVENT, webview.label())
+      .unwrap();
+    crate::async_runtime::block_on(flush).unwrap();
+  }
+}
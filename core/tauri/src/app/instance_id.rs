@@ -0,0 +1,279 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The anonymous id of the app installation, see [`crate::AppHandle::instance_id`].
+
+use std::{
+  io::ErrorKind,
+  path::{Path, PathBuf},
+  sync::Mutex,
+  time::Duration,
+};
+
+/// The file in the app data directory where the instance id is persisted.
+const INSTANCE_ID_FILE: &str = ".instance-id";
+/// How many times an empty id file created by another process is read again.
+const CREATED_READ_ATTEMPTS: usize = 50;
+/// The delay between the reads of an empty id file.
+const CREATED_READ_INTERVAL: Duration = Duration::from_millis(10);
+
+/// The instance id read or created by the app, managed by the app.
+#[derive(Default)]
+pub(crate) struct InstanceId(Mutex<Option<String>>);
+
+impl InstanceId {
+  /// Returns the id persisted in the directory, creating it on the first call.
+  ///
+  /// The lock serializes the threads of the app, and the file is created with a hard link
+  /// that fails if another process created it first, so all callers get the same id.
+  /// The file is created then replaced on the file systems without hard links.
+  pub(crate) fn get(&self, dir: &Path) -> crate::Result<String> {
+    let mut cached = self.0.lock().unwrap();
+    if let Some(id) = &*cached {
+      return Ok(id.clone());
+    }
+
+    let path = dir.join(INSTANCE_ID_FILE);
+    let id = match read(&path)? {
+      Persisted::Valid(id) => id,
+      existing => {
+        std::fs::create_dir_all(dir)?;
+        let temp = write_temp(dir)?;
+        let created = if let Persisted::Corrupted = existing {
+          // replaced atomically, a process recovering it concurrently may replace it again
+          std::fs::rename(&temp, &path)
+        } else {
+          link(&temp, &path)
+        };
+        let _ = std::fs::remove_file(&temp);
+        created?;
+        // the id of the process that created the file first
+        read_created(&path)?
+      }
+    };
+
+    cached.replace(id.clone());
+    Ok(id)
+  }
+
+  /// Deletes the id persisted in the directory, so the next call to [`Self::get`] creates a new one.
+  pub(crate) fn reset(&self, dir: &Path) -> crate::Result<()> {
+    let mut cached = self.0.lock().unwrap();
+    match std::fs::remove_file(dir.join(INSTANCE_ID_FILE)) {
+      Ok(()) => (),
+      Err(e) if e.kind() == ErrorKind::NotFound => (),
+      Err(e) => return Err(e.into()),
+    }
+    cached.take();
+    Ok(())
+  }
+}
+
+/// The content of the instance id file.
+enum Persisted {
+  Missing,
+  Corrupted,
+  Valid(String),
+}
+
+fn read(path: &Path) -> crate::Result<Persisted> {
+  match std::fs::read_to_string(path) {
+    Ok(content) if is_valid(content.trim()) => Ok(Persisted::Valid(content.trim().to_string())),
+    Ok(_) => Ok(Persisted::Corrupted),
+    // not UTF-8
+    Err(e) if e.kind() == ErrorKind::InvalidData => Ok(Persisted::Corrupted),
+    Err(e) if e.kind() == ErrorKind::NotFound => Ok(Persisted::Missing),
+    Err(e) => Err(e.into()),
+  }
+}
+
+/// Creates the id file from the temporary file, unless another process created it first.
+fn link(temp: &Path, path: &Path) -> std::io::Result<()> {
+  match std::fs::hard_link(temp, path) {
+    Err(e) if e.kind() == ErrorKind::AlreadyExists => Ok(()),
+    // FAT, exFAT and some network file systems do not support hard links
+    Err(_) => create_and_replace(temp, path),
+    linked => linked,
+  }
+}
+
+/// Creates the id file to claim it, then replaces it with the temporary file.
+fn create_and_replace(temp: &Path, path: &Path) -> std::io::Result<()> {
+  match std::fs::OpenOptions::new()
+    .write(true)
+    .create_new(true)
+    .open(path)
+  {
+    Ok(file) => {
+      drop(file);
+      std::fs::rename(temp, path)
+    }
+    Err(e) if e.kind() == ErrorKind::AlreadyExists => Ok(()),
+    Err(e) => Err(e),
+  }
+}
+
+/// Reads the id file once it was created,
+/// waiting for the file claimed by [`create_and_replace`] in another process to be replaced.
+fn read_created(path: &Path) -> crate::Result<String> {
+  for _ in 0..CREATED_READ_ATTEMPTS {
+    match read(path)? {
+      Persisted::Valid(id) => return Ok(id),
+      Persisted::Corrupted if std::fs::metadata(path).is_ok_and(|m| m.len() == 0) => {
+        std::thread::sleep(CREATED_READ_INTERVAL)
+      }
+      _ => break,
+    }
+  }
+  Err(crate::Error::InstanceId(format!(
+    "{} is not a valid instance id file",
+    path.display()
+  )))
+}
+
+/// Writes a new id to a temporary file of the directory.
+fn write_temp(dir: &Path) -> crate::Result<PathBuf> {
+  let id = generate();
+  let temp = dir.join(format!("{INSTANCE_ID_FILE}.{id}.tmp"));
+  std::fs::write(&temp, &id)?;
+  Ok(temp)
+}
+
+/// Generates a random (version 4) UUID.
+fn generate() -> String {
+  uuid::Uuid::new_v4().to_string()
+}
+
+/// Whether the id is a lowercase hyphenated UUID.
+fn is_valid(id: &str) -> bool {
+  id.len() == 36
+    && id.char_indices().all(|(i, c)| match i {
+      8 | 13 | 18 | 23 => c == '-',
+      _ => matches!(c, '0'..='9' | 'a'..='f'),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    path::PathBuf,
+    sync::{Arc, Barrier},
+  };
+
+  use super::{create_and_replace, generate, is_valid, InstanceId, INSTANCE_ID_FILE};
+
+  fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("tauri-instance-id-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+  }
+
+  #[test]
+  fn generates_uuids() {
+    let id = generate();
+    assert!(is_valid(&id));
+    assert_eq!(&id[14..15], "4");
+    assert!(matches!(&id[19..20], "8" | "9" | "a" | "b"));
+    assert_ne!(id, generate());
+    assert!(!is_valid("not an id"));
+  }
+
+  #[test]
+  fn creates_and_reuses_the_id() {
+    let dir = temp_dir("reuse");
+    let id = InstanceId::default().get(&dir).unwrap();
+    assert!(is_valid(&id));
+    assert_eq!(
+      std::fs::read_to_string(dir.join(INSTANCE_ID_FILE)).unwrap(),
+      id
+    );
+    // read back by the next launches
+    assert_eq!(InstanceId::default().get(&dir).unwrap(), id);
+    // no temporary file is left
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+    let _ = std::fs::remove_dir_all(dir);
+  }
+
+  #[test]
+  fn concurrent_calls_get_the_same_id() {
+    let dir = temp_dir("concurrent");
+    let barrier = Arc::new(Barrier::new(8));
+    let threads = (0..8)
+      .map(|_| {
+        let dir = dir.clone();
+        let barrier = barrier.clone();
+        // separate states, like separate processes
+        std::thread::spawn(move || {
+          barrier.wait();
+          InstanceId::default().get(&dir).unwrap()
+        })
+      })
+      .collect::<Vec<_>>();
+    let ids = threads
+      .into_iter()
+      .map(|thread| thread.join().unwrap())
+      .collect::<Vec<_>>();
+    assert!(ids.iter().all(|id| *id == ids[0]));
+
+    let _ = std::fs::remove_dir_all(dir);
+  }
+
+  #[test]
+  fn creates_the_id_without_hard_links() {
+    let dir = temp_dir("no-hard-links");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(INSTANCE_ID_FILE);
+    let write = |name: &str, id: &str| {
+      let temp = dir.join(name);
+      std::fs::write(&temp, id).unwrap();
+      temp
+    };
+
+    let id = generate();
+    let temp = write("first.tmp", &id);
+    create_and_replace(&temp, &path).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), id);
+    assert!(!temp.exists());
+
+    // the file created first is kept
+    create_and_replace(&write("second.tmp", &generate()), &path).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), id);
+    assert_eq!(InstanceId::default().get(&dir).unwrap(), id);
+
+    let _ = std::fs::remove_dir_all(dir);
+  }
+
+  #[test]
+  fn replaces_corrupted_ids() {
+    let dir = temp_dir("corrupted");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join(INSTANCE_ID_FILE), "garbage").unwrap();
+    let id = InstanceId::default().get(&dir).unwrap();
+    assert!(is_valid(&id));
+    assert_eq!(InstanceId::default().get(&dir).unwrap(), id);
+
+    std::fs::write(dir.join(INSTANCE_ID_FILE), [0xff, 0xfe]).unwrap();
+    assert_ne!(InstanceId::default().get(&dir).unwrap(), id);
+
+    let _ = std::fs::remove_dir_all(dir);
+  }
+
+  #[test]
+  fn reset_creates_a_new_id() {
+    let dir = temp_dir("reset");
+    let instance_id = InstanceId::default();
+    let id = instance_id.get(&dir).unwrap();
+    instance_id.reset(&dir).unwrap();
+    assert!(!dir.join(INSTANCE_ID_FILE).exists());
+    let new_id = instance_id.get(&dir).unwrap();
+    assert_ne!(new_id, id);
+    assert_eq!(instance_id.get(&dir).unwrap(), new_id);
+    // resetting without an id is not an error
+    instance_id.reset(&dir).unwrap();
+    instance_id.reset(&dir).unwrap();
+
+    let _ = std::fs::remove_dir_all(dir);
+  }
+}
@@ -0,0 +1,189 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Asynchronous initialization of the managed state, see [`crate::Builder::setup_state`].
+
+use std::any::{type_name, TypeId};
+
+use futures_util::{future::BoxFuture, stream::FuturesUnordered, StreamExt};
+
+use crate::{AppHandle, Manager, Runtime};
+
+/// The error returned by a state initializer.
+pub type StateInitError = Box<dyn std::error::Error + Send + Sync>;
+
+type Initializer<R> =
+  Box<dyn for<'a> FnOnce(&'a AppHandle<R>) -> BoxFuture<'a, Result<(), StateInitError>> + Send>;
+
+/// A state initializer registered with [`crate::Builder::setup_state`].
+pub(crate) struct StateInitializer<R: Runtime> {
+  type_id: TypeId,
+  type_name: &'static str,
+  /// The states that must be initialized first.
+  after: Vec<TypeId>,
+  init: Initializer<R>,
+}
+
+impl<R: Runtime> StateInitializer<R> {
+  pub(crate) fn new<T, F>(f: F) -> Self
+  where
+    T: Send + Sync + 'static,
+    F: FnOnce(&AppHandle<R>) -> BoxFuture<'_, Result<T, StateInitError>> + Send + 'static,
+  {
+    Self {
+      type_id: TypeId::of::<T>(),
+      type_name: type_name::<T>(),
+      after: Vec::new(),
+      init: Box::new(move |app| {
+        let state = f(app);
+        Box::pin(async move {
+          if app.manage(state.await?) {
+            Ok(())
+          } else {
+            Err("the state is already managed".into())
+          }
+        })
+      }),
+    }
+  }
+
+  pub(crate) fn after<T: Send + Sync + 'static>(&mut self) {
+    self.after.push(TypeId::of::<T>());
+  }
+}
+
+/// Runs the initializers, concurrently unless one depends on the state of another.
+///
+/// Dependencies on states that are not initialized by this function are ignored.
+pub(crate) async fn run<R: Runtime>(
+  app: AppHandle<R>,
+  initializers: Vec<StateInitializer<R>>,
+) -> crate::Result<()> {
+  let mut pending = initializers;
+  let mut running_types = Vec::new();
+  let mut running = FuturesUnordered::new();
+
+  loop {
+    // start the initializers whose dependencies are ready
+    let mut i = 0;
+    while i < pending.len() {
+      let blocked = pending[i].after.iter().any(|dependency| {
+        pending.iter().any(|p| p.type_id == *dependency) || running_types.contains(dependency)
+      });
+      if blocked {
+        i += 1;
+      } else {
+        let initializer = pending.remove(i);
+        let StateInitializer {
+          type_id,
+          type_name,
+          init,
+          ..
+        } = initializer;
+        running_types.push(type_id);
+        let future = init(&app);
+        running.push(async move { (type_id, type_name, future.await) });
+      }
+    }
+
+    match running.next().await {
+      Some((type_id, type_name, result)) => {
+        result.map_err(|e| crate::Error::StateInitialization(type_name.into(), e.to_string()))?;
+        running_types.retain(|t| *t != type_id);
+      }
+      None => break,
+    }
+  }
+
+  match pending.first() {
+    Some(initializer) => Err(crate::Error::StateInitialization(
+      initializer.type_name.into(),
+      "cyclic dependency between the state initializers".into(),
+    )),
+    None => Ok(()),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, Mutex};
+
+  use futures_util::FutureExt;
+
+  use crate::{
+    test::{mock_builder, mock_context, noop_assets},
+    Manager,
+  };
+
+  struct Db(&'static str);
+  struct Cache(&'static str);
+  struct Config;
+
+  #[test]
+  fn runs_in_dependency_order() {
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let (cache_order, db_order, config_order) = (order.clone(), order.clone(), order.clone());
+
+    let app = mock_builder()
+      .setup_state(move |app| {
+        async move {
+          // the database is ready before the cache is initialized
+          let db = app.state::<Db>();
+          cache_order.lock().unwrap().push("cache");
+          Ok(Cache(db.0))
+        }
+        .boxed()
+      })
+      .after::<Db>()
+      .setup_state(move |_| {
+        async move {
+          // let the other initializers run first
+          for _ in 0..10 {
+            tokio::task::yield_now().await;
+          }
+          db_order.lock().unwrap().push("db");
+          Ok(Db("pool"))
+        }
+        .boxed()
+      })
+      .setup_state(move |_| {
+        async move {
+          config_order.lock().unwrap().push("config");
+          Ok(Config)
+        }
+        .boxed()
+      })
+      .build(mock_context(noop_assets()))
+      .unwrap();
+
+    assert_eq!(*order.lock().unwrap(), ["config", "db", "cache"]);
+    assert_eq!(app.state::<Cache>().0, "pool");
+    assert!(app.try_state::<Config>().is_some());
+  }
+
+  #[test]
+  fn failures_abort_startup() {
+    let result = mock_builder()
+      .setup_state(|_| async { Err::<Db, _>("connection refused".into()) }.boxed())
+      .build(mock_context(noop_assets()));
+    match result {
+      Err(crate::Error::StateInitialization(name, error)) => {
+        assert!(name.ends_with("Db"));
+        assert_eq!(error, "connection refused");
+      }
+      _ => panic!("expected a state initialization error"),
+    }
+  }
+
+  #[test]
+  fn cycles_are_rejected() {
+    let result = mock_builder()
+      .setup_state(|_| async { Ok(Db("pool")) }.boxed())
+      .after::<Cache>()
+      .setup_state(|_| async { Ok(Cache("cache")) }.boxed())
+      .after::<Db>()
+      .build(mock_context(noop_assets()));
+    assert!(matches!(result, Err(crate::Error::StateInitialization(..))));
+  }
+}
@@ -26,6 +26,11 @@ use std::{
   task::{Context, Poll},
 };
 
+mod local;
+
+pub(crate) use local::spawn as spawn_local;
+pub use local::spawn_local_on_main;
+
 static RUNTIME: OnceLock<GlobalRuntime> = OnceLock::new();
 
 struct GlobalRuntime {
@@ -0,0 +1,321 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! An executor for the futures that must stay on the main thread, driven by the event loop.
+
+use std::{
+  cell::RefCell,
+  collections::{HashMap, VecDeque},
+  future::Future,
+  panic::AssertUnwindSafe,
+  pin::Pin,
+  sync::{Arc, Mutex},
+  task::{Context, Waker},
+};
+
+use futures_util::{
+  task::{waker, ArcWake},
+  FutureExt,
+};
+use tokio::sync::oneshot;
+
+use crate::{AppHandle, Runtime};
+
+/// Maximum number of tasks polled in a single event loop iteration,
+/// the remaining ready tasks are polled on the next iteration so the event loop is never starved.
+const POLL_BUDGET: usize = 32;
+
+thread_local! {
+  static EXECUTOR: RefCell<LocalExecutor> = Default::default();
+}
+
+type Schedule = Arc<dyn Fn() + Send + Sync>;
+
+#[derive(Default)]
+struct LocalExecutor {
+  next_id: u64,
+  tasks: HashMap<u64, LocalTask>,
+  ready: Arc<Mutex<ReadyTasks>>,
+}
+
+struct LocalTask {
+  future: Pin<Box<dyn Future<Output = ()>>>,
+  waker: Waker,
+  schedule: Schedule,
+}
+
+/// The tasks woken since the last poll, shared with the wakers of every thread.
+#[derive(Default)]
+struct ReadyTasks {
+  ids: VecDeque<u64>,
+  /// Whether a poll of the ready tasks is already queued on the main thread.
+  scheduled: bool,
+}
+
+struct TaskWaker {
+  id: u64,
+  ready: Arc<Mutex<ReadyTasks>>,
+  schedule: Schedule,
+}
+
+impl ArcWake for TaskWaker {
+  fn wake_by_ref(arc_self: &Arc<Self>) {
+    let mut ready = arc_self.ready.lock().unwrap();
+    if !ready.ids.contains(&arc_self.id) {
+      ready.ids.push_back(arc_self.id);
+    }
+    if !ready.scheduled {
+      ready.scheduled = true;
+      drop(ready);
+      (arc_self.schedule)();
+    }
+  }
+}
+
+/// Polls the ready tasks of the main thread, up to the [`POLL_BUDGET`].
+fn poll_ready_tasks() {
+  let ready = EXECUTOR.with(|executor| executor.borrow().ready.clone());
+
+  for _ in 0..POLL_BUDGET {
+    let Some(id) = ready.lock().unwrap().ids.pop_front() else {
+      break;
+    };
+    // the task is taken out of the executor while polled so it can spawn other tasks
+    let Some(mut task) = EXECUTOR.with(|executor| executor.borrow_mut().tasks.remove(&id)) else {
+      continue;
+    };
+    let waker = task.waker.clone();
+    if task
+      .future
+      .as_mut()
+      .poll(&mut Context::from_waker(&waker))
+      .is_pending()
+    {
+      EXECUTOR.with(|executor| executor.borrow_mut().tasks.insert(id, task));
+    }
+  }
+
+  let mut ready = ready.lock().unwrap();
+  if ready.ids.is_empty() {
+    ready.scheduled = false;
+    return;
+  }
+  // the budget is exhausted, the remaining tasks are polled on the next iteration
+  let schedule = EXECUTOR.with(|executor| {
+    executor
+      .borrow()
+      .tasks
+      .values()
+      .next()
+      .map(|task| task.schedule.clone())
+  });
+  if let Some(schedule) = schedule {
+    drop(ready);
+    schedule();
+  } else {
+    ready.ids.clear();
+    ready.scheduled = false;
+  }
+}
+
+/// The output of a future spawned on the main thread executor.
+pub(crate) struct LocalJoinHandle<T>(oneshot::Receiver<crate::Result<T>>);
+
+impl<T> LocalJoinHandle<T> {
+  /// Waits for the output of the future.
+  pub(crate) async fn join(self) -> crate::Result<T> {
+    self
+      .0
+      .await
+      .map_err(|_| crate::Error::Runtime(tauri_runtime::Error::FailedToReceiveMessage))?
+  }
+}
+
+/// Spawns a future on the executor of the current thread, which must be the main thread.
+///
+/// A panic of the future is returned as a [`crate::Error::LocalTaskPanicked`] error.
+pub(crate) fn spawn<R: Runtime, F>(app: &AppHandle<R>, future: F) -> LocalJoinHandle<F::Output>
+where
+  F: Future + 'static,
+  F::Output: Send + 'static,
+{
+  let (tx, rx) = oneshot::channel();
+  let future = async move {
+    let output = AssertUnwindSafe(future)
+      .catch_unwind()
+      .await
+      .map_err(|payload| {
+        let message = payload
+          .downcast_ref::<&str>()
+          .map(|message| message.to_string())
+          .or_else(|| payload.downcast_ref::<String>().cloned())
+          .unwrap_or_default();
+        crate::Error::LocalTaskPanicked(message)
+      });
+    let _ = tx.send(output);
+  };
+
+  let app = app.clone();
+  let schedule: Schedule = Arc::new(move || {
+    let _ = app.run_on_main_thread(poll_ready_tasks);
+  });
+
+  let waker = EXECUTOR.with(|executor| {
+    let mut executor = executor.borrow_mut();
+    let id = executor.next_id;
+    executor.next_id += 1;
+    let waker = waker(Arc::new(TaskWaker {
+      id,
+      ready: executor.ready.clone(),
+      schedule: schedule.clone(),
+    }));
+    executor.tasks.insert(
+      id,
+      LocalTask {
+        future: Box::pin(future),
+        waker: waker.clone(),
+        schedule,
+      },
+    );
+    waker
+  });
+  waker.wake();
+
+  LocalJoinHandle(rx)
+}
+
+/// Runs a future that is not [`Send`] on the main thread and returns its output.
+///
+/// The `task` closure is called on the main thread and the future it returns is polled there,
+/// interleaved with the event loop: a woken future is polled on the next event loop iteration
+/// and a single iteration polls a limited number of futures.
+///
+/// A panic of the future does not abort the app, it is returned as a [`crate::Error::LocalTaskPanicked`] error.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::rc::Rc;
+///
+/// tauri::Builder::default()
+///   .setup(|app| {
+///     let handle = app.handle().clone();
+///     tauri::async_runtime::spawn(async move {
+///       let answer = tauri::async_runtime::spawn_local_on_main(&handle, || async {
+///         // `Rc` is not `Send`, but the future never leaves the main thread
+///         let value = Rc::new(42);
+///         *value
+///       })
+///       .await;
+///       println!("{answer:?}");
+///     });
+///     Ok(())
+///   });
+/// ```
+pub fn spawn_local_on_main<R, F, Fut>(
+  app: &AppHandle<R>,
+  task: F,
+) -> impl Future<Output = crate::Result<Fut::Output>> + Send + 'static
+where
+  R: Runtime,
+  F: FnOnce() -> Fut + Send + 'static,
+  Fut: Future + 'static,
+  Fut::Output: Send + 'static,
+{
+  let (tx, rx) = oneshot::channel();
+  let app_ = app.clone();
+  let dispatched = app.run_on_main_thread(move || {
+    let _ = tx.send(spawn(&app_, task()));
+  });
+
+  async move {
+    dispatched?;
+    rx.await
+      .map_err(|_| crate::Error::Runtime(tauri_runtime::Error::FailedToReceiveMessage))?
+      .join()
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+  };
+
+  use super::spawn_local_on_main;
+
+  /// Pending once, waking itself.
+  struct YieldNow(bool);
+
+  impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+      if self.0 {
+        Poll::Ready(())
+      } else {
+        self.0 = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+      }
+    }
+  }
+
+  #[test]
+  fn runs_local_futures() {
+    let app = crate::test::mock_app();
+
+    let output = crate::async_runtime::block_on(spawn_local_on_main(app.handle(), || async {
+      let value = Rc::new(1);
+      YieldNow(false).await;
+      *value + 1
+    }));
+    assert_eq!(output.unwrap(), 2);
+
+    // more tasks than the poll budget
+    let handle = app.handle().clone();
+    let outputs =
+      crate::async_runtime::block_on(spawn_local_on_main(app.handle(), move || async move {
+        let tasks = (0..super::POLL_BUDGET * 2)
+          .map(|i| {
+            super::spawn(&handle, async move {
+              YieldNow(false).await;
+              i
+            })
+          })
+          .collect::<Vec<_>>();
+        let mut outputs = Vec::new();
+        for task in tasks {
+          outputs.push(task.join().await.unwrap());
+        }
+        outputs
+      }));
+    assert_eq!(
+      outputs.unwrap(),
+      (0..super::POLL_BUDGET * 2).collect::<Vec<_>>()
+    );
+  }
+
+  #[test]
+  fn panics_are_errors() {
+    let app = crate::test::mock_app();
+
+    let output = crate::async_runtime::block_on(spawn_local_on_main(app.handle(), || async {
+      YieldNow(false).await;
+      panic!("local task failure");
+    }));
+    assert!(matches!(
+      output,
+      Err(crate::Error::LocalTaskPanicked(message)) if message == "local task failure"
+    ));
+
+    // the executor keeps running the other tasks
+    let output = crate::async_runtime::block_on(spawn_local_on_main(app.handle(), || async { 1 }));
+    assert_eq!(output.unwrap(), 1);
+  }
+}
@@ -0,0 +1,687 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Remote automation of the app webviews for end-to-end tests, enabled with the `remote-automation` feature.
+//!
+//! When the feature is enabled the app starts a WebSocket server on `127.0.0.1`
+//! accepting the [`AutomationCommand`]s as JSON text messages:
+//!
+//! ```json
+//! { "id": 1, "command": "eval", "webview": "main", "script": "return document.title" }
+//! ```
+//!
+//! Each request is answered with an [`AutomationResponse`] with the same `id`:
+//!
+//! ```json
+//! { "id": 1, "ok": { "command": "eval", "value": "My App" } }
+//! { "id": 1, "error": { "kind": "webviewNotFound", "label": "main" } }
+//! ```
+//!
+//! Connections must provide the [`AutomationEndpoint::token`] in the `token` query parameter of the URL,
+//! and connections from web pages (requests with an `Origin` header) are always refused.
+//! The port and token are random unless the `TAURI_AUTOMATION_PORT` and `TAURI_AUTOMATION_TOKEN`
+//! environment variables are set, and the endpoint is available as the [`AutomationEndpoint`] state.
+//!
+//! This gives full control of the webviews to any process knowing the token,
+//! so the feature must never be enabled on production builds.
+
+use std::{
+  collections::HashMap,
+  net::Ipv4Addr,
+  sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, Mutex,
+  },
+  time::Duration,
+};
+
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use tokio::{net::TcpStream, sync::oneshot};
+use tokio_tungstenite::tungstenite::{
+  handshake::server::{ErrorResponse, Request, Response},
+  http::{header::ORIGIN, StatusCode},
+  Message,
+};
+
+use crate::{
+  ipc::{CallbackFn, InvokeBody, InvokeError, InvokeResponse},
+  webview::{invoke_key::INVOKE_KEY_HEADER, InvokeRequest},
+  AppHandle, EventTarget, Manager, Runtime, Webview,
+};
+
+/// The internal command the [`AutomationCommand::Eval`] script sends its result with.
+pub(crate) const EVAL_RESULT_COMMAND: &str = "__TAURI_AUTOMATION_EVAL_RESULT__";
+/// The environment variable setting the port of the automation server.
+const PORT_ENV: &str = "TAURI_AUTOMATION_PORT";
+/// The environment variable setting the token of the automation server.
+const TOKEN_ENV: &str = "TAURI_AUTOMATION_TOKEN";
+/// How long the commands waiting on a webview can run before failing with [`AutomationError::Timeout`].
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A command sent by the automation client.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum AutomationCommand {
+  /// Lists the webviews of the app.
+  ListWebviews,
+  /// Runs a script on a webview and returns its result.
+  ///
+  /// The script is the body of an async function, so its result must be returned with `return`.
+  /// The result must be serializable to JSON, `undefined` is returned as `null`.
+  Eval {
+    /// The webview label.
+    webview: String,
+    /// The function body.
+    script: String,
+  },
+  /// Captures the visible content of a webview, see [`Webview::screenshot`].
+  Screenshot {
+    /// The webview label.
+    webview: String,
+  },
+  /// Emits an event, to all targets or to the given target.
+  Emit {
+    /// The event name.
+    event: String,
+    /// The event payload.
+    #[serde(default)]
+    payload: JsonValue,
+    /// The event target, all targets if not set.
+    #[serde(default)]
+    target: Option<EventTarget>,
+  },
+  /// Calls a command as if the webview frontend invoked it,
+  /// applying the same checks as the IPC so the command must be allowed for the webview.
+  Invoke {
+    /// The webview label.
+    webview: String,
+    /// The command name.
+    cmd: String,
+    /// The command arguments.
+    #[serde(default)]
+    args: JsonValue,
+  },
+}
+
+/// The response to each [`AutomationCommand`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum CommandResponse {
+  /// The response to [`AutomationCommand::ListWebviews`].
+  ListWebviews {
+    /// The webviews sorted by label.
+    webviews: Vec<WebviewInfo>,
+  },
+  /// The response to [`AutomationCommand::Eval`].
+  Eval {
+    /// The value the script returned.
+    value: JsonValue,
+  },
+  /// The response to [`AutomationCommand::Screenshot`].
+  Screenshot {
+    /// The base64 encoded PNG image.
+    png: String,
+  },
+  /// The response to [`AutomationCommand::Emit`].
+  Emit,
+  /// The response to [`AutomationCommand::Invoke`].
+  Invoke {
+    /// The command response.
+    response: JsonValue,
+  },
+}
+
+/// A webview listed by [`AutomationCommand::ListWebviews`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebviewInfo {
+  /// The webview label.
+  pub label: String,
+  /// The label of the window hosting the webview.
+  pub window: String,
+  /// The current webview URL.
+  pub url: String,
+}
+
+/// The error of an [`AutomationCommand`].
+#[derive(Debug, Clone, Serialize, Deserialize, thiserror::Error)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum AutomationError {
+  /// The request is not a valid [`AutomationRequest`].
+  #[error("invalid request: {message}")]
+  InvalidRequest {
+    /// The deserialization error.
+    message: String,
+  },
+  /// No webview has the given label.
+  #[error("webview `{label}` not found")]
+  WebviewNotFound {
+    /// The requested label.
+    label: String,
+  },
+  /// The [`AutomationCommand::Eval`] script threw an error.
+  #[error("script error: {message}")]
+  Script {
+    /// The thrown error converted to a string.
+    message: String,
+  },
+  /// The [`AutomationCommand::Invoke`] command returned an error.
+  #[error("command error: {error}")]
+  Invoke {
+    /// The command error.
+    error: JsonValue,
+  },
+  /// The webview did not respond in time.
+  #[error("the webview did not respond in time")]
+  Timeout,
+  /// The command is not supported on the current platform.
+  #[error("this command is not supported on the current platform")]
+  Unsupported,
+  /// The command failed.
+  #[error("{message}")]
+  Failed {
+    /// The error message.
+    message: String,
+  },
+}
+
+impl AutomationError {
+  fn failed(message: impl ToString) -> Self {
+    Self::Failed {
+      message: message.to_string(),
+    }
+  }
+}
+
+impl From<crate::Error> for AutomationError {
+  fn from(error: crate::Error) -> Self {
+    match error {
+      crate::Error::Runtime(tauri_runtime::Error::Unsupported) => Self::Unsupported,
+      error => Self::failed(error),
+    }
+  }
+}
+
+/// A request sent by the automation client.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutomationRequest {
+  /// The request identifier, sent back in the [`AutomationResponse`].
+  pub id: u64,
+  /// The command to run.
+  #[serde(flatten)]
+  pub command: AutomationCommand,
+}
+
+/// The response to an [`AutomationRequest`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutomationResponse {
+  /// The identifier of the request.
+  pub id: u64,
+  /// The command result.
+  #[serde(flatten)]
+  pub result: AutomationResult,
+}
+
+/// The result of an [`AutomationCommand`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AutomationResult {
+  /// The command succeeded.
+  Ok(CommandResponse),
+  /// The command failed.
+  Error(AutomationError),
+}
+
+impl From<AutomationResult> for Result<CommandResponse, AutomationError> {
+  fn from(result: AutomationResult) -> Self {
+    match result {
+      AutomationResult::Ok(response) => Ok(response),
+      AutomationResult::Error(error) => Err(error),
+    }
+  }
+}
+
+impl From<Result<CommandResponse, AutomationError>> for AutomationResult {
+  fn from(result: Result<CommandResponse, AutomationError>) -> Self {
+    match result {
+      Ok(response) => Self::Ok(response),
+      Err(error) => Self::Error(error),
+    }
+  }
+}
+
+/// The address of the automation server, managed as a state of the app.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tauri::{automation::AutomationEndpoint, Manager};
+///
+/// tauri::Builder::default()
+///   .setup(|app| {
+///     let endpoint = app.state::<AutomationEndpoint>();
+///     println!("automation server listening on port {}", endpoint.port());
+///     Ok(())
+///   });
+/// ```
+#[derive(Debug, Clone)]
+pub struct AutomationEndpoint {
+  port: u16,
+  token: String,
+}
+
+impl AutomationEndpoint {
+  /// The port the server listens on.
+  pub fn port(&self) -> u16 {
+    self.port
+  }
+
+  /// The token the clients must connect with.
+  pub fn token(&self) -> &str {
+    &self.token
+  }
+
+  /// The URL the clients connect to, including the token.
+  pub fn url(&self) -> String {
+    format!("ws://127.0.0.1:{}/?token={}", self.port, self.token)
+  }
+}
+
+type EvalSender = oneshot::Sender<Result<JsonValue, String>>;
+
+/// The [`AutomationCommand::Eval`] scripts waiting for their result, by request.
+#[derive(Default)]
+pub(crate) struct PendingEvals {
+  next_id: AtomicU64,
+  senders: Mutex<HashMap<u64, (String, EvalSender)>>,
+}
+
+#[derive(Deserialize)]
+struct EvalResult {
+  id: u64,
+  value: JsonValue,
+  error: Option<String>,
+}
+
+/// Starts the automation server, returning its endpoint.
+pub(crate) fn start<R: Runtime>(app: &AppHandle<R>) -> crate::Result<AutomationEndpoint> {
+  let port = std::env::var(PORT_ENV)
+    .ok()
+    .and_then(|port| port.parse().ok())
+    .unwrap_or(0);
+  let token = std::env::var(TOKEN_ENV)
+    .ok()
+    .filter(|token| !token.is_empty())
+    .unwrap_or_else(generate_token);
+
+  let listener = std::net::TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+  listener.set_nonblocking(true)?;
+  let endpoint = AutomationEndpoint {
+    port: listener.local_addr()?.port(),
+    token,
+  };
+  tauri_utils::debug_eprintln!(
+    "remote automation server listening on ws://127.0.0.1:{}",
+    endpoint.port
+  );
+
+  let app = app.clone();
+  let token: Arc<str> = endpoint.token.clone().into();
+  crate::async_runtime::spawn(async move {
+    let listener = match tokio::net::TcpListener::from_std(listener) {
+      Ok(listener) => listener,
+      Err(e) => {
+        tauri_utils::debug_eprintln!("failed to start the remote automation server: {}", e);
+        return;
+      }
+    };
+    loop {
+      match listener.accept().await {
+        Ok((stream, _)) => {
+          crate::async_runtime::spawn(serve(app.clone(), stream, token.clone()));
+        }
+        Err(e) => {
+          tauri_utils::debug_eprintln!("failed to accept an automation connection: {}", e);
+        }
+      }
+    }
+  });
+
+  Ok(endpoint)
+}
+
+fn generate_token() -> String {
+  let mut bytes = [0u8; 32];
+  getrandom::getrandom(&mut bytes).expect("failed to generate the automation token");
+  bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Whether the handshake request comes from a client knowing the token.
+fn authorize(request: &Request, token: &str) -> bool {
+  if request.headers().contains_key(ORIGIN) {
+    return false;
+  }
+  let Some(query) = request.uri().query() else {
+    return false;
+  };
+  url::form_urlencoded::parse(query.as_bytes())
+    .find(|(key, _)| key == "token")
+    .map(|(_, value)| {
+      // compares the whole token so the comparison time does not leak the matching prefix
+      value.len() == token.len()
+        && value
+          .bytes()
+          .zip(token.bytes())
+          .fold(0, |acc, (a, b)| acc | (a ^ b))
+          == 0
+    })
+    .unwrap_or(false)
+}
+
+async fn serve<R: Runtime>(app: AppHandle<R>, stream: TcpStream, token: Arc<str>) {
+  // the error response type is defined by tungstenite
+  #[allow(clippy::result_large_err)]
+  let callback = |request: &Request, response: Response| {
+    if authorize(request, &token) {
+      Ok(response)
+    } else {
+      let mut error = ErrorResponse::new(Some("invalid automation token".into()));
+      *error.status_mut() = StatusCode::UNAUTHORIZED;
+      Err(error)
+    }
+  };
+  let Ok(socket) = tokio_tungstenite::accept_hdr_async(stream, callback).await else {
+    return;
+  };
+
+  let (mut sink, mut stream) = socket.split();
+  let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+  // the requests run concurrently, so the responses are written as they complete
+  crate::async_runtime::spawn(async move {
+    while let Some(response) = rx.recv().await {
+      if sink.send(Message::Text(response)).await.is_err() {
+        break;
+      }
+    }
+  });
+
+  while let Some(message) = stream.next().await {
+    let text = match message {
+      Ok(Message::Text(text)) => text,
+      Ok(Message::Close(_)) | Err(_) => break,
+      Ok(_) => continue,
+    };
+    let app = app.clone();
+    let tx = tx.clone();
+    crate::async_runtime::spawn(async move {
+      let response = handle_request(&app, &text).await;
+      if let Ok(response) = serde_json::to_string(&response) {
+        let _ = tx.send(response);
+      }
+    });
+  }
+}
+
+async fn handle_request<R: Runtime>(app: &AppHandle<R>, text: &str) -> AutomationResponse {
+  let value = serde_json::from_str::<JsonValue>(text).unwrap_or_default();
+  // answer invalid requests with their id when possible
+  let id = value.get("id").and_then(JsonValue::as_u64).unwrap_or(0);
+  let result = match serde_json::from_value::<AutomationRequest>(value) {
+    Ok(request) => run(app, request.command).await,
+    Err(e) => Err(AutomationError::InvalidRequest {
+      message: e.to_string(),
+    }),
+  };
+  AutomationResponse {
+    id,
+    result: result.into(),
+  }
+}
+
+async fn run<R: Runtime>(
+  app: &AppHandle<R>,
+  command: AutomationCommand,
+) -> Result<CommandResponse, AutomationError> {
+  match command {
+    AutomationCommand::ListWebviews => {
+      let mut webviews = app
+        .manager
+        .webviews()
+        .into_values()
+        .map(|webview| WebviewInfo {
+          label: webview.label().to_string(),
+          window: webview.window().label().to_string(),
+          url: webview.url().map(|url| url.to_string()).unwrap_or_default(),
+        })
+        .collect::<Vec<_>>();
+      webviews.sort_by(|a, b| a.label.cmp(&b.label));
+      Ok(CommandResponse::ListWebviews { webviews })
+    }
+    AutomationCommand::Eval { webview, script } => {
+      let webview = get_webview(app, &webview)?;
+      let value = eval(&webview, &script).await?;
+      Ok(CommandResponse::Eval { value })
+    }
+    AutomationCommand::Screenshot { webview } => {
+      use base64::Engine;
+
+      let webview = get_webview(app, &webview)?;
+      let (tx, rx) = oneshot::channel();
+      webview.screenshot(move |result| {
+        let _ = tx.send(result);
+      })?;
+      let png = wait(rx).await??;
+      Ok(CommandResponse::Screenshot {
+        png: base64::engine::general_purpose::STANDARD.encode(png),
+      })
+    }
+    AutomationCommand::Emit {
+      event,
+      payload,
+      target,
+    } => {
+      match target {
+        Some(target) => app.emit_to(target, &event, payload)?,
+        None => app.emit(&event, payload)?,
+      }
+      Ok(CommandResponse::Emit)
+    }
+    AutomationCommand::Invoke { webview, cmd, args } => {
+      let webview = get_webview(app, &webview)?;
+      let response = invoke(webview, cmd, args).await?;
+      Ok(CommandResponse::Invoke { response })
+    }
+  }
+}
+
+fn get_webview<R: Runtime>(app: &AppHandle<R>, label: &str) -> Result<Webview<R>, AutomationError> {
+  app
+    .manager
+    .get_webview(label)
+    .ok_or_else(|| AutomationError::WebviewNotFound {
+      label: label.to_string(),
+    })
+}
+
+async fn wait<T>(rx: oneshot::Receiver<T>) -> Result<T, AutomationError> {
+  match tokio::time::timeout(COMMAND_TIMEOUT, rx).await {
+    Ok(Ok(value)) => Ok(value),
+    Ok(Err(_)) => Err(AutomationError::failed("the webview was closed")),
+    Err(_) => Err(AutomationError::Timeout),
+  }
+}
+
+async fn eval<R: Runtime>(
+  webview: &Webview<R>,
+  script: &str,
+) -> Result<JsonValue, AutomationError> {
+  let pending = webview.state::<PendingEvals>();
+  let id = pending.next_id.fetch_add(1, Ordering::Relaxed);
+  let (tx, rx) = oneshot::channel();
+  pending
+    .senders
+    .lock()
+    .unwrap()
+    .insert(id, (webview.label().to_string(), tx));
+
+  let script = format!(
+    r#"(async function () {{
+{script}
+}})()
+  .then(
+    (value) => ({{ value: value === undefined ? null : value, error: null }}),
+    (error) => ({{ value: null, error: String(error) }})
+  )
+  .then((result) =>
+    window.__TAURI_INTERNALS__.invoke('{EVAL_RESULT_COMMAND}', {{ id: {id}, ...result }})
+  )
+  .catch((error) =>
+    window.__TAURI_INTERNALS__.invoke('{EVAL_RESULT_COMMAND}', {{ id: {id}, value: null, error: String(error) }})
+  )"#
+  );
+
+  let result = match webview.eval(&script) {
+    Ok(()) => wait(rx).await,
+    Err(e) => Err(e.into()),
+  };
+  pending.senders.lock().unwrap().remove(&id);
+  result?.map_err(|message| AutomationError::Script { message })
+}
+
+/// Handles an [`EVAL_RESULT_COMMAND`] request.
+pub(crate) fn handle_eval_result<R: Runtime>(
+  webview: &Webview<R>,
+  body: InvokeBody,
+) -> Result<(), String> {
+  let result = serde_json::from_value::<EvalResult>(body.into_json()).map_err(|e| e.to_string())?;
+  let pending = webview.state::<PendingEvals>();
+  let mut senders = pending.senders.lock().unwrap();
+  // only the webview the script was sent to can resolve it
+  match senders.get(&result.id) {
+    Some((label, _)) if label == webview.label() => {
+      let (_, tx) = senders.remove(&result.id).unwrap();
+      let _ = tx.send(match result.error {
+        Some(error) => Err(error),
+        None => Ok(result.value),
+      });
+      Ok(())
+    }
+    _ => Err("unknown automation script".into()),
+  }
+}
+
+async fn invoke<R: Runtime>(
+  webview: Webview<R>,
+  cmd: String,
+  args: JsonValue,
+) -> Result<JsonValue, AutomationError> {
+  let key = webview.invoke_key.lock().unwrap().current().to_string();
+  let mut headers = http::HeaderMap::new();
+  headers.insert(
+    INVOKE_KEY_HEADER,
+    key.parse().map_err(AutomationError::failed)?,
+  );
+
+  let (tx, rx) = oneshot::channel();
+  webview.on_message(
+    InvokeRequest {
+      cmd,
+      callback: CallbackFn(0),
+      error: CallbackFn(1),
+      body: InvokeBody::Json(args),
+      headers,
+    },
+    Box::new(move |_webview, _cmd, response, _meta, _callback, _error| {
+      let _ = tx.send(response);
+    }),
+  );
+
+  match wait(rx).await? {
+    InvokeResponse::Ok(body) => Ok(body.into_json()),
+    InvokeResponse::Err(InvokeError(error)) => Err(AutomationError::Invoke { error }),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    io::{Read, Write},
+    net::TcpStream,
+  };
+
+  use serde_json::json;
+
+  use super::{AutomationCommand, AutomationRequest, AutomationResponse, CommandResponse};
+  use crate::{test::mock_app, Manager};
+
+  #[test]
+  fn serializes_messages() {
+    let request: AutomationRequest = serde_json::from_value(
+      json!({ "id": 1, "command": "eval", "webview": "main", "script": "return 1" }),
+    )
+    .unwrap();
+    assert_eq!(request.id, 1);
+    assert!(matches!(
+      request.command,
+      AutomationCommand::Eval { webview, script } if webview == "main" && script == "return 1"
+    ));
+
+    let request: AutomationRequest =
+      serde_json::from_value(json!({ "id": 2, "command": "listWebviews" })).unwrap();
+    assert!(matches!(request.command, AutomationCommand::ListWebviews));
+
+    let response = AutomationResponse {
+      id: 3,
+      result: Ok(CommandResponse::Emit).into(),
+    };
+    assert_eq!(
+      serde_json::to_value(response).unwrap(),
+      json!({ "id": 3, "ok": { "command": "emit" } })
+    );
+    let response = AutomationResponse {
+      id: 4,
+      result: Err(super::AutomationError::WebviewNotFound {
+        label: "main".into(),
+      })
+      .into(),
+    };
+    assert_eq!(
+      serde_json::to_value(response).unwrap(),
+      json!({ "id": 4, "error": { "kind": "webviewNotFound", "label": "main" } })
+    );
+  }
+
+  fn handshake(port: u16, path: &str, origin: Option<&str>) -> String {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    let origin = origin
+      .map(|origin| format!("Origin: {origin}\r\n"))
+      .unwrap_or_default();
+    write!(
+      stream,
+      "GET {path} HTTP/1.1\r\nHost: 127.0.0.1:{port}\r\nConnection: Upgrade\r\nUpgrade: websocket\r\nSec-WebSocket-Version: 13\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n{origin}\r\n"
+    )
+    .unwrap();
+    let mut response = [0; 1024];
+    let len = stream.read(&mut response).unwrap();
+    String::from_utf8_lossy(&response[..len]).to_string()
+  }
+
+  #[test]
+  fn refuses_connections_without_token() {
+    let app = mock_app();
+    let endpoint = app.state::<super::AutomationEndpoint>();
+    let port = endpoint.port();
+
+    assert!(handshake(port, "/", None).starts_with("HTTP/1.1 401"));
+    assert!(handshake(port, "/?token=invalid", None).starts_with("HTTP/1.1 401"));
+    let path = format!("/?token={}", endpoint.token());
+    assert!(handshake(port, &path, Some("https://example.com")).starts_with("HTTP/1.1 401"));
+    assert!(handshake(port, &path, None).starts_with("HTTP/1.1 101"));
+  }
+}
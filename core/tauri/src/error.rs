@@ -40,6 +40,10 @@ pub enum Error {
   /// Webview label must be unique.
   #[error("a webview with label `{0}` already exists")]
   WebviewLabelAlreadyExists(String),
+  /// The label was reserved by another thread with [`crate::Manager::unique_label`],
+  /// or by another builder with [`crate::WebviewWindowBuilder::new_unique`].
+  #[error("the `{0}` label is reserved by another thread or builder, build the window or webview on the thread that called `Manager::unique_label` or with the builder returned by `new_unique`")]
+  LabelReserved(String),
   /// Embedded asset not found.
   #[error("asset not found: {0}")]
   AssetNotFound(String),
@@ -61,6 +65,17 @@ pub enum Error {
   /// Error initializing plugin.
   #[error("failed to initialize plugin `{0}`: {1}")]
   PluginInitialization(String, String),
+  /// A plugin requires a plugin that is not registered, see [`crate::plugin::Builder::requires`].
+  ///
+  /// Lists the chain of plugins leading to the missing plugin.
+  #[error("plugin `{}` is not registered: {}", .0.last().map(String::as_str).unwrap_or_default(), .0.join(" requires "))]
+  PluginDependencyMissing(Vec<String>),
+  /// The plugin dependencies form a cycle, see [`crate::plugin::Builder::requires`].
+  #[error("plugin dependency cycle: {}", .0.join(" requires "))]
+  PluginDependencyCycle(Vec<String>),
+  /// Error initializing a state registered with [`crate::Builder::setup_state`].
+  #[error("failed to initialize the `{0}` state: {1}")]
+  StateInitialization(String, String),
   /// A part of the URL is malformed or invalid. This may occur when parsing and combining
   /// user-provided URLs and paths.
   #[error("invalid url: {0}")]
@@ -119,6 +134,12 @@ pub enum Error {
   /// Path does not have a basename.
   #[error("path does not have a basename")]
   NoBasename,
+  /// The path resolves outside of its base directory.
+  #[error("path `{0}` escapes its base directory")]
+  PathEscapesBase(std::path::PathBuf),
+  /// The path starts with a variable that is not a [`crate::path::BaseDirectory`] variable.
+  #[error("unknown path variable `{0}`")]
+  UnknownPathVariable(String),
   /// Cannot resolve current directory.
   #[error("failed to read current dir: {0}")]
   CurrentDir(std::io::Error),
@@ -126,6 +147,10 @@ pub enum Error {
   #[cfg(not(target_os = "android"))]
   #[error("unknown path")]
   UnknownPath,
+  /// File system watcher error.
+  #[cfg(feature = "path-watch")]
+  #[error("file watcher error: {0}")]
+  Watch(#[from] notify::Error),
   /// Failed to invoke mobile plugin.
   #[cfg(target_os = "android")]
   #[error(transparent)]
@@ -133,15 +158,69 @@ pub enum Error {
   /// window not found.
   #[error("window not found")]
   WindowNotFound,
+  /// The window has no parent window, see [`crate::window::Window::reposition_relative`].
+  #[error("the `{0}` window has no parent window")]
+  NoParentWindow(String),
   /// The resource id is invalid.
   #[error("The resource id {0} is invalid.")]
   BadResourceId(crate::resources::ResourceId),
   /// The anyhow crate error.
   #[error(transparent)]
   Anyhow(#[from] anyhow::Error),
+  /// The payload of a sticky event is too large to be stored.
+  #[error("the payload of the sticky event `{0}` is {1} bytes, over the {max} bytes limit", max = crate::MAX_STICKY_PAYLOAD_SIZE)]
+  StickyPayloadTooLarge(String, usize),
   /// webview not found.
   #[error("webview not found")]
   WebviewNotFound,
+  /// The webview was closed or the app is exiting, see [`crate::Webview::is_alive`].
+  #[error("the `{0}` webview was destroyed")]
+  WebviewDestroyed(String),
+  /// The messages waiting to be received by the webview exceed [`crate::webview::MAX_WEBVIEW_MESSAGE_QUEUE_SIZE`].
+  #[error("the message queue of the `{0}` webview is full")]
+  WebviewMessageQueueFull(String),
+  /// The initial `file://` URL of a webview is outside of its [`crate::webview::FileAccessScope`].
+  #[error("the `{0}` webview is not allowed to load {1}")]
+  FileAccessDenied(String, url::Url),
+  /// Failed to read or create the instance id, see [`crate::AppHandle::instance_id`].
+  #[error("failed to read or create the instance id: {0}")]
+  InstanceId(String),
+  /// Background effects were requested for a webview that is not transparent.
+  #[error("the `{0}` webview must be transparent to show background effects")]
+  WebviewNotTransparent(String),
+  /// Failed to create a window with its webview, the window is not kept.
+  #[error("failed to create the `{1}` webview of the `{0}` window: {2}")]
+  WebviewWindowCreation(String, String, Box<Error>),
+  /// A future spawned on the main thread panicked, see [`crate::async_runtime::spawn_local_on_main`].
+  #[error("the main thread task panicked: {0}")]
+  LocalTaskPanicked(String),
+  /// The channel was created by a page the webview navigated away from.
+  #[error("the channel belongs to a page the webview navigated away from")]
+  ChannelExpired,
+  /// The accelerator is already registered on the window or used by a menu item.
+  #[error("the `{0}` accelerator is already in use")]
+  #[cfg(desktop)]
+  AcceleratorConflict(String),
+  /// Failed to access the web storage of a webview, see [`crate::AppHandle::webview_storage`].
+  #[cfg(feature = "webview-storage")]
+  #[error("webview storage error: {0}")]
+  WebviewStorage(String),
+  /// Failed to check for an update, see [`crate::AppHandle::check_update`].
+  #[cfg(feature = "update-check")]
+  #[error("update check failed: {0}")]
+  UpdateCheck(String),
+  /// A script evaluated in an isolated world failed, see [`crate::webview::Webview::eval_isolated_with_result`].
+  #[error("isolated script error: {0}")]
+  IsolatedScript(String),
+  /// The profile name is not valid, see [`crate::AppHandle::create_profile`].
+  #[error("invalid profile name `{0}`, it must only include alphanumeric characters, `-` and `_`")]
+  InvalidProfileName(String),
+  /// The profile cannot be deleted while a webview bound to it is alive.
+  #[error("the `{0}` profile is used by a webview")]
+  ProfileInUse(String),
+  /// The webview runtime is missing or outdated, see [`crate::Builder::on_webview_runtime_missing`].
+  #[error("the webview runtime is not available: {0}")]
+  WebviewRuntimeUnavailable(crate::webview::RuntimeStatus),
   /// API requires the unstable feature flag.
   #[error("this feature requires the `unstable` flag on Cargo.toml")]
   UnstableFeatureNotSupported,
@@ -0,0 +1,81 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Batching of high-frequency events, see [`crate::Manager::emit_batched`].
+
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+use super::EmitArgs;
+
+/// The flush interval of the events without a configured interval, see [`crate::Builder::event_batching`].
+pub(crate) const DEFAULT_BATCH_INTERVAL: Duration = Duration::from_millis(16);
+
+#[derive(Default)]
+struct Batch {
+  payloads: Vec<EmitArgs>,
+  /// Incremented on each flush so a scheduled flush skips the batches that were flushed earlier.
+  generation: u64,
+}
+
+/// The queued payloads of the batched events.
+#[derive(Default)]
+pub(crate) struct EventBatches {
+  intervals: HashMap<String, Duration>,
+  batches: Mutex<HashMap<String, Batch>>,
+}
+
+impl EventBatches {
+  pub(crate) fn new(intervals: HashMap<String, Duration>) -> Self {
+    Self {
+      intervals,
+      batches: Default::default(),
+    }
+  }
+
+  /// The flush interval of the event.
+  pub(crate) fn interval(&self, event: &str) -> Duration {
+    self
+      .intervals
+      .get(event)
+      .copied()
+      .unwrap_or(DEFAULT_BATCH_INTERVAL)
+  }
+
+  /// Queues a payload, returning the generation of the batch if the flush must be scheduled,
+  /// which is the case for the first payload of a batch.
+  pub(crate) fn push(&self, emit_args: EmitArgs) -> Option<u64> {
+    let mut batches = self.batches.lock().unwrap();
+    let batch = batches.entry(emit_args.event_name.clone()).or_default();
+    batch.payloads.push(emit_args);
+    (batch.payloads.len() == 1).then_some(batch.generation)
+  }
+
+  /// Takes the queued payloads of the event in emit order.
+  ///
+  /// Nothing is taken if a `generation` is given and the batch was flushed since.
+  pub(crate) fn take(&self, event: &str, generation: Option<u64>) -> Vec<EmitArgs> {
+    let mut batches = self.batches.lock().unwrap();
+    match batches.get_mut(event) {
+      Some(batch)
+        if !batch.payloads.is_empty() && generation.map_or(true, |g| g == batch.generation) =>
+      {
+        batch.generation += 1;
+        std::mem::take(&mut batch.payloads)
+      }
+      _ => Vec::new(),
+    }
+  }
+
+  /// The events with queued payloads.
+  pub(crate) fn pending_events(&self) -> Vec<String> {
+    self
+      .batches
+      .lock()
+      .unwrap()
+      .iter()
+      .filter(|(_, batch)| !batch.payloads.is_empty())
+      .map(|(event, _)| event.clone())
+      .collect()
+  }
+}
@@ -0,0 +1,225 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Delivery of the events to the frontend without evaluating scripts, see [`EventTransport::Bridge`].
+
+use std::{pin::pin, sync::Mutex};
+
+use serde_json::value::RawValue;
+use serialize_to_javascript::{default_template, DefaultTemplate, Template};
+use tokio::sync::Notify;
+
+use super::{EmitArgs, EventId};
+use crate::{ipc::CallbackFn, webview::event_sequence::EventSequence};
+
+/// The command the initialization script polls to receive the event bridge messages.
+pub(crate) const EVENT_BRIDGE_COMMAND: &str = "plugin:event|poll_bridge";
+
+/// How the listen, unlisten and emit operations reach the frontend, see [`crate::Builder::event_transport`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EventTransport {
+  /// Evaluates a script in the webview for each operation.
+  #[default]
+  Eval,
+  /// Sends the operations as JSON messages over a bridge the initialization script opens on the IPC custom protocol,
+  /// so the event system does not evaluate any script.
+  ///
+  /// The bridge is a request to the `plugin:event|poll_bridge` command, answered with the queued messages
+  /// and sent again by the frontend, so pages that cannot call it (see the `event:allow-poll-bridge` permission) do not receive events.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS:** The IPC custom protocol is not used on `https` pages, where the bridge messages
+  ///   are delivered by evaluating the IPC responses.
+  Bridge,
+}
+
+/// The messages waiting to be received by the frontend of a webview, see [`EventTransport::Bridge`].
+#[derive(Default)]
+pub(crate) struct EventBridge {
+  state: Mutex<BridgeState>,
+  notify: Notify,
+}
+
+#[derive(Default)]
+struct BridgeState {
+  messages: Vec<Box<RawValue>>,
+  /// Incremented on each main frame navigation, ending the polls of the previous page.
+  generation: u64,
+}
+
+impl EventBridge {
+  /// Queues a message, waking the pending poll.
+  pub(crate) fn push(&self, message: String) -> crate::Result<()> {
+    let message = RawValue::from_string(message)?;
+    self.state.lock().unwrap().messages.push(message);
+    self.notify.notify_waiters();
+    Ok(())
+  }
+
+  /// Called when the main frame starts a navigation, dropping the messages of the previous page.
+  pub(crate) fn on_navigation(&self) {
+    let mut state = self.state.lock().unwrap();
+    state.messages.clear();
+    state.generation += 1;
+    drop(state);
+    self.notify.notify_waiters();
+  }
+
+  /// Waits for messages and takes them, or returns no messages if the page navigates away.
+  pub(crate) async fn poll(&self) -> Vec<Box<RawValue>> {
+    let generation = self.state.lock().unwrap().generation;
+    loop {
+      // registered before reading the queue so a message pushed in between still wakes the poll
+      let mut notified = pin!(self.notify.notified());
+      notified.as_mut().enable();
+      {
+        let mut state = self.state.lock().unwrap();
+        if state.generation != generation {
+          return Vec::new();
+        }
+        if !state.messages.is_empty() {
+          return std::mem::take(&mut state.messages);
+        }
+      }
+      notified.await;
+    }
+  }
+}
+
+/// The script receiving the event bridge messages, appended to the event initialization script.
+#[derive(Template)]
+#[default_template("../../scripts/event-bridge.js")]
+struct EventBridgeJavascript<'a> {
+  function: &'a str,
+  listeners: &'a str,
+  command: &'a str,
+}
+
+pub(crate) fn event_bridge_script(function: &str, listeners: &str) -> String {
+  EventBridgeJavascript {
+    function,
+    listeners,
+    command: EVENT_BRIDGE_COMMAND,
+  }
+  .render_default(&Default::default())
+  .map(|script| script.into_string())
+  .expect("failed to render the event bridge script")
+}
+
+pub(crate) fn listen_message(
+  serialized_target: &str,
+  event: &str,
+  event_id: EventId,
+  handler: CallbackFn,
+) -> crate::Result<String> {
+  Ok(format!(
+    r#"{{"kind":"listen","event":{},"target":{serialized_target},"id":{event_id},"handler":{}}}"#,
+    serde_json::to_string(event)?,
+    handler.0,
+  ))
+}
+
+pub(crate) fn unlisten_message(event: &str, event_id: EventId) -> crate::Result<String> {
+  Ok(format!(
+    r#"{{"kind":"unlisten","event":{},"id":{event_id}}}"#,
+    serde_json::to_string(event)?,
+  ))
+}
+
+pub(crate) fn emit_message(
+  emit_args: &EmitArgs,
+  serialized_target: &str,
+  sequence: &EventSequence,
+) -> crate::Result<String> {
+  Ok(format!(
+    r#"{{"kind":"emit","eventData":{{"event":{},"payload":{}{},"sequence":{}}},"target":{serialized_target}}}"#,
+    emit_args.event,
+    emit_args.payload,
+    profile_field(emit_args)?,
+    serde_json::to_string(sequence)?,
+  ))
+}
+
+pub(crate) fn emit_batch_message(
+  batch: &[&EmitArgs],
+  serialized_target: &str,
+  sequence: &EventSequence,
+) -> crate::Result<String> {
+  let event = batch
+    .first()
+    .map(|emit_args| emit_args.event.as_str())
+    .unwrap_or("null");
+  let payloads = batch
+    .iter()
+    .map(|emit_args| emit_args.payload.as_str())
+    .collect::<Vec<_>>()
+    .join(",");
+  Ok(format!(
+    r#"{{"kind":"emit","eventData":{{"event":{event},"batch":[{payloads}],"sequence":{}}},"target":{serialized_target}}}"#,
+    serde_json::to_string(sequence)?,
+  ))
+}
+
+/// Calls a single listener, used to deliver sticky events to new listeners.
+pub(crate) fn listener_message(
+  emit_args: &EmitArgs,
+  event_id: EventId,
+  handler: CallbackFn,
+) -> crate::Result<String> {
+  Ok(format!(
+    r#"{{"kind":"call","handler":{},"eventData":{{"event":{},"id":{event_id},"payload":{}{}}}}}"#,
+    handler.0,
+    emit_args.event,
+    emit_args.payload,
+    profile_field(emit_args)?,
+  ))
+}
+
+/// The `profile` field of the event data, only set for the events of a profile.
+fn profile_field(emit_args: &EmitArgs) -> crate::Result<String> {
+  Ok(match &emit_args.profile {
+    Some(profile) => format!(r#","profile":{}"#, serde_json::to_string(profile)?),
+    None => String::new(),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{sync::Arc, time::Duration};
+
+  use super::EventBridge;
+
+  #[test]
+  fn poll_waits_for_messages() {
+    let bridge = Arc::new(EventBridge::default());
+    bridge
+      .push(r#"{"kind":"unlisten","event":"a","id":1}"#.into())
+      .unwrap();
+    let messages = crate::async_runtime::block_on(bridge.poll());
+    assert_eq!(messages.len(), 1);
+
+    let bridge_ = bridge.clone();
+    let poll = crate::async_runtime::spawn(async move { bridge_.poll().await });
+    std::thread::sleep(Duration::from_millis(50));
+    bridge
+      .push(r#"{"kind":"unlisten","event":"b","id":2}"#.into())
+      .unwrap();
+    let messages = crate::async_runtime::block_on(poll).unwrap();
+    assert_eq!(
+      messages[0].get(),
+      r#"{"kind":"unlisten","event":"b","id":2}"#
+    );
+
+    // a navigation ends the pending poll without messages
+    let bridge_ = bridge.clone();
+    let poll = crate::async_runtime::spawn(async move { bridge_.poll().await });
+    std::thread::sleep(Duration::from_millis(50));
+    bridge.on_navigation();
+    assert!(crate::async_runtime::block_on(poll).unwrap().is_empty());
+
+    assert!(bridge.push("not json".into()).is_err());
+  }
+}
@@ -9,7 +9,7 @@ use super::{EmitArgs, Event, EventId, EventTarget};
 use std::{
   boxed::Box,
   cell::Cell,
-  collections::{HashMap, HashSet},
+  collections::{BTreeMap, HashMap, HashSet},
   sync::{
     atomic::{AtomicU32, Ordering},
     Arc, Mutex,
@@ -56,6 +56,8 @@ struct InnerListeners {
   pending: Mutex<Vec<Pending>>,
   handlers: Mutex<HashMap<EventName, HashMap<EventId, Handler>>>,
   js_event_listeners: Mutex<HashMap<WebviewLabel, HashMap<EventName, HashSet<JsHandler>>>>,
+  /// The last payload of the sticky events, delivered to the JS listeners registered later.
+  sticky: Mutex<HashMap<EventName, EmitArgs>>,
   function_name: &'static str,
   listeners_object_name: &'static str,
   next_event_id: Arc<AtomicU32>,
@@ -74,6 +76,7 @@ impl Default for Listeners {
         pending: Mutex::default(),
         handlers: Mutex::default(),
         js_event_listeners: Mutex::default(),
+        sticky: Mutex::default(),
         function_name: "__internal_unstable_listeners_function_id__",
         listeners_object_name: "__internal_unstable_listeners_object_id__",
         next_event_id: Default::default(),
@@ -183,6 +186,18 @@ impl Listeners {
     }
   }
 
+  #[cfg(test)]
+  pub(crate) fn listener_count(&self) -> usize {
+    self
+      .inner
+      .handlers
+      .lock()
+      .unwrap()
+      .values()
+      .map(HashMap::len)
+      .sum()
+  }
+
   /// Emits the given event with its payload based on a filter.
   pub(crate) fn emit_filter<F>(&self, emit_args: EmitArgs, filter: Option<F>) -> crate::Result<()>
   where
@@ -204,7 +219,12 @@ impl Listeners {
           if !handlers.is_empty() {
             maybe_pending = true;
             for (&id, Handler { callback, .. }) in handlers {
-              (callback)(Event::new(id, emit_args.payload.clone()))
+              (callback)(Event::new(
+                id,
+                emit_args.event_name.clone(),
+                emit_args.payload.clone(),
+                emit_args.profile.clone(),
+              ))
             }
           }
         }
@@ -223,13 +243,18 @@ impl Listeners {
     self.emit_filter(emit_args, None::<&dyn Fn(&EventTarget) -> bool>)
   }
 
-  pub(crate) fn listen_js(
+  /// Registers a JS listener, calling `deliver_sticky` with the stored payload if the event is sticky.
+  pub(crate) fn listen_js<F>(
     &self,
     event: &str,
     source_webview_label: &str,
     target: EventTarget,
     id: EventId,
-  ) {
+    deliver_sticky: F,
+  ) -> crate::Result<()>
+  where
+    F: FnOnce(&EmitArgs) -> crate::Result<()>,
+  {
     let mut listeners = self.inner.js_event_listeners.lock().unwrap();
     listeners
       .entry(source_webview_label.to_string())
@@ -237,6 +262,13 @@ impl Listeners {
       .entry(event.to_string())
       .or_default()
       .insert(JsHandler { id, target });
+
+    // delivered while holding the listeners lock so it can't be reordered with a new emit
+    let sticky = self.inner.sticky.lock().unwrap().get(event).cloned();
+    match sticky {
+      Some(emit_args) => deliver_sticky(&emit_args),
+      None => Ok(()),
+    }
   }
 
   pub(crate) fn unlisten_js(&self, id: EventId) {
@@ -271,6 +303,21 @@ impl Listeners {
     }
   }
 
+  /// The number of Rust and JS listeners registered for each event.
+  pub(crate) fn listener_counts(&self) -> BTreeMap<EventName, usize> {
+    let mut counts = BTreeMap::new();
+    for (event, handlers) in self.inner.handlers.lock().unwrap().iter() {
+      *counts.entry(event.clone()).or_default() += handlers.len();
+    }
+    for events in self.inner.js_event_listeners.lock().unwrap().values() {
+      for (event, handlers) in events {
+        *counts.entry(event.clone()).or_default() += handlers.len();
+      }
+    }
+    counts.retain(|_, count| *count > 0);
+    counts
+  }
+
   pub(crate) fn has_js_listener<F: Fn(&EventTarget) -> bool>(
     &self,
     event: &str,
@@ -285,10 +332,20 @@ impl Listeners {
     })
   }
 
+  /// The labels of the webviews with a JS listener for the given event.
+  pub(crate) fn js_listener_webviews(&self, event: &str) -> Vec<WebviewLabel> {
+    let listeners = self.inner.js_event_listeners.lock().unwrap();
+    listeners
+      .iter()
+      .filter(|(_, events)| events.contains_key(event))
+      .map(|(label, _)| label.clone())
+      .collect()
+  }
+
   pub(crate) fn try_for_each_js<'a, R, I, F>(
     &self,
     event: &str,
-    mut webviews: I,
+    webviews: I,
     callback: F,
   ) -> crate::Result<()>
   where
@@ -297,10 +354,58 @@ impl Listeners {
     F: Fn(&Webview<R>, &EventTarget) -> crate::Result<()>,
   {
     let listeners = self.inner.js_event_listeners.lock().unwrap();
+    Self::try_for_each_js_locked(&listeners, event, webviews, callback)
+  }
+
+  /// Stores the sticky event and calls `callback` for each of its current JS listeners.
+  ///
+  /// Both happen while holding the listeners lock, so a listener registered concurrently
+  /// receives the event exactly once, either here or in [`Self::listen_js`].
+  pub(crate) fn try_for_each_js_sticky<'a, R, I, F>(
+    &self,
+    emit_args: &EmitArgs,
+    webviews: I,
+    callback: F,
+  ) -> crate::Result<()>
+  where
+    R: Runtime,
+    I: Iterator<Item = &'a Webview<R>>,
+    F: Fn(&Webview<R>, &EventTarget) -> crate::Result<()>,
+  {
+    let listeners = self.inner.js_event_listeners.lock().unwrap();
+    self
+      .inner
+      .sticky
+      .lock()
+      .unwrap()
+      .insert(emit_args.event_name.clone(), emit_args.clone());
+    Self::try_for_each_js_locked(&listeners, &emit_args.event_name, webviews, callback)
+  }
+
+  /// Removes the stored payload of a sticky event.
+  pub(crate) fn clear_sticky(&self, event: &str) {
+    self.inner.sticky.lock().unwrap().remove(event);
+  }
+
+  fn try_for_each_js_locked<'a, R, I, F>(
+    listeners: &HashMap<WebviewLabel, HashMap<EventName, HashSet<JsHandler>>>,
+    event: &str,
+    mut webviews: I,
+    callback: F,
+  ) -> crate::Result<()>
+  where
+    R: Runtime,
+    I: Iterator<Item = &'a Webview<R>>,
+    F: Fn(&Webview<R>, &EventTarget) -> crate::Result<()>,
+  {
     webviews.try_for_each(|webview| {
       if let Some(handlers) = listeners.get(webview.label()).and_then(|s| s.get(event)) {
+        // the JS side calls all listeners registered on the target, so each target is only called once
+        let mut targets = HashSet::new();
         for JsHandler { target, .. } in handlers {
-          callback(webview, target)?;
+          if targets.insert(target) {
+            callback(webview, target)?;
+          }
         }
       }
 
@@ -375,7 +480,11 @@ mod test {
       listeners.emit(EmitArgs {
         event_name: key.clone(),
         event: serde_json::to_string(&key).unwrap(),
-        payload: serde_json::to_string(&d).unwrap()
+        payload: serde_json::to_string(&d).unwrap(),
+        profile: None,
+        source: None,
+        target: Some(EventTarget::Any),
+        replayed: false,
       })?;
 
       // lock the mutex
@@ -385,4 +494,84 @@ mod test {
       assert!(l.contains_key(&key));
     }
   }
+
+  fn store_sticky(listeners: &Listeners, event: &str, payload: &str) {
+    listeners
+      .try_for_each_js_sticky(
+        &EmitArgs::new(event, payload).unwrap(),
+        std::iter::empty::<&Webview<crate::test::MockRuntime>>(),
+        |_, _| Ok(()),
+      )
+      .unwrap();
+  }
+
+  fn listen_js_recording(listeners: &Listeners, event: &str, id: EventId) -> Option<String> {
+    let mut delivered = None;
+    listeners
+      .listen_js(event, "main", EventTarget::Any, id, |emit_args| {
+        delivered.replace(emit_args.payload.clone());
+        Ok(())
+      })
+      .unwrap();
+    delivered
+  }
+
+  #[test]
+  fn sticky_delivered_to_late_listeners() {
+    let listeners: Listeners = Default::default();
+    assert_eq!(listen_js_recording(&listeners, "ready", 1), None);
+
+    store_sticky(&listeners, "ready", "first");
+    store_sticky(&listeners, "ready", "second");
+    assert_eq!(
+      listen_js_recording(&listeners, "ready", 2).as_deref(),
+      Some("\"second\"")
+    );
+    assert_eq!(listen_js_recording(&listeners, "other", 3), None);
+
+    listeners.clear_sticky("ready");
+    assert_eq!(listen_js_recording(&listeners, "ready", 4), None);
+  }
+
+  #[test]
+  fn js_listener_targets() {
+    let listeners: Listeners = Default::default();
+    let listen = |webview: &str, target: EventTarget, id: EventId| {
+      listeners
+        .listen_js("close", webview, target, id, |_| Ok(()))
+        .unwrap()
+    };
+    listen("child", EventTarget::webview("child"), 1);
+    listen("other", EventTarget::labeled("other"), 2);
+
+    let has_listener = |target: EventTarget| {
+      let window_webviews = ["main".to_string(), "child".to_string()];
+      listeners.has_js_listener("close", |listener| {
+        target.matches(listener, &window_webviews)
+      })
+    };
+
+    assert!(has_listener(EventTarget::webview_window("main")));
+    assert!(!has_listener(EventTarget::window("main")));
+    assert!(has_listener(EventTarget::webview("child")));
+    assert!(has_listener(EventTarget::window("other")));
+    assert!(!has_listener(EventTarget::App));
+
+    listeners.unlisten_js(1);
+    assert!(!has_listener(EventTarget::webview_window("main")));
+  }
+
+  #[test]
+  fn sticky_delivered_before_new_emits() {
+    let listeners: Listeners = Default::default();
+    store_sticky(&listeners, "ready", "sticky");
+
+    listeners
+      .listen_js("ready", "main", EventTarget::Any, 1, |_| {
+        // emits wait on this lock, so they reach the listener after the sticky payload
+        assert!(listeners.inner.js_event_listeners.try_lock().is_err());
+        Ok(())
+      })
+      .unwrap();
+  }
 }
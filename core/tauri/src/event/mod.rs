@@ -2,12 +2,30 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
+mod batch;
+mod bridge;
 mod listener;
 pub(crate) mod plugin;
+mod recorder;
 use std::{convert::Infallible, str::FromStr};
 
+pub(crate) use batch::EventBatches;
+pub use bridge::EventTransport;
+pub(crate) use bridge::{
+  emit_batch_message, emit_message, listen_message, listener_message, unlisten_message,
+  EventBridge, EVENT_BRIDGE_COMMAND,
+};
 pub(crate) use listener::Listeners;
-use serde::{Deserialize, Serialize};
+pub(crate) use recorder::EventRecorder;
+pub use recorder::{
+  RecordedEvent, RecorderConfig, DEFAULT_MAX_RECORDED_EVENTS, DEFAULT_MAX_RECORDED_PAYLOAD_BYTES,
+};
+
+/// The maximum size of the serialized payload of a sticky event, see [`crate::Manager::emit_sticky`].
+pub const MAX_STICKY_PAYLOAD_SIZE: usize = 64 * 1024;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::webview::event_sequence::EventSequence;
 
 /// Checks if an event name is valid.
 pub fn is_event_name_valid(event: &str) -> bool {
@@ -26,7 +44,66 @@ pub fn assert_event_name_is_valid(event: &str) {
 /// Unique id of an event.
 pub type EventId = u32;
 
+/// An event listener that is removed when the guard is dropped,
+/// see [`crate::Manager::listen_scoped`] and [`crate::Webview::listen_scoped`].
+#[must_use = "the listener is removed when the guard is dropped"]
+pub struct ListenerGuard {
+  id: EventId,
+  unlisten: Option<Box<dyn FnOnce() + Send + Sync>>,
+}
+
+impl ListenerGuard {
+  pub(crate) fn new<F: FnOnce() + Send + Sync + 'static>(id: EventId, unlisten: F) -> Self {
+    Self {
+      id,
+      unlisten: Some(Box::new(unlisten)),
+    }
+  }
+
+  /// The id of the listener.
+  pub fn id(&self) -> EventId {
+    self.id
+  }
+
+  /// Keeps the listener registered after the guard is dropped, returning its id
+  /// so it can still be removed with [`crate::Manager::unlisten`].
+  pub fn forget(mut self) -> EventId {
+    self.unlisten.take();
+    self.id
+  }
+}
+
+impl std::fmt::Debug for ListenerGuard {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("ListenerGuard")
+      .field("id", &self.id)
+      .finish()
+  }
+}
+
+impl Drop for ListenerGuard {
+  fn drop(&mut self) {
+    if let Some(unlisten) = self.unlisten.take() {
+      unlisten();
+    }
+  }
+}
+
 /// Event Target
+///
+/// Listeners are registered on a target (e.g. [`crate::Window::listen`] uses [`Self::Window`]),
+/// and [`crate::Manager::emit_to`] delivers the event to the listeners registered on:
+///
+/// | Emit target | Listeners |
+/// |---|---|
+/// | [`Self::Any`] | all targets |
+/// | [`Self::App`] | the app |
+/// | [`Self::AnyLabel`] | the windows, webviews and webview windows with the label |
+/// | [`Self::Window`] | the window, but not its webviews |
+/// | [`Self::Webview`] | the webview |
+/// | [`Self::WebviewWindow`] | the webview window, the window and all webviews hosted by the window |
+///
+/// Listeners registered on [`Self::Any`] receive every event, and [`Self::AnyLabel`] listeners receive events emitted to targets with their label.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Hash)]
 #[serde(tag = "kind")]
 #[non_exhaustive]
@@ -44,6 +121,9 @@ pub enum EventTarget {
   App,
 
   /// [`Window`](crate::Window) target.
+  ///
+  /// Emitting to this target does not reach the listeners of the webviews in the window,
+  /// use [`Self::WebviewWindow`] for that.
   Window {
     /// window label.
     label: String,
@@ -56,6 +136,8 @@ pub enum EventTarget {
   },
 
   /// [`WebviewWindow`](crate::WebviewWindow) target.
+  ///
+  /// Emitting to this target also reaches the listeners of the window with this label and of all webviews in it.
   WebviewWindow {
     /// webview window label.
     label: String,
@@ -100,6 +182,33 @@ impl EventTarget {
       label: label.into(),
     }
   }
+
+  /// The label of the target, if any.
+  pub(crate) fn label(&self) -> Option<&str> {
+    match self {
+      Self::AnyLabel { label }
+      | Self::Window { label }
+      | Self::Webview { label }
+      | Self::WebviewWindow { label } => Some(label),
+      Self::Any | Self::App => None,
+    }
+  }
+
+  /// Whether an event emitted to this target is delivered to a listener registered on the `listener` target.
+  ///
+  /// `window_webviews` are the labels of the webviews hosted by the window of a [`Self::WebviewWindow`] target.
+  /// Listeners registered on [`Self::Any`] receive all events, which is handled by the callers.
+  pub(crate) fn matches(&self, listener: &EventTarget, window_webviews: &[String]) -> bool {
+    match (self, listener) {
+      (Self::Any, _) => true,
+      (Self::App, listener) => *listener == Self::App,
+      (Self::AnyLabel { label }, listener) => listener.label() == Some(label),
+      (target, Self::AnyLabel { label }) => target.label() == Some(label),
+      (Self::WebviewWindow { label }, Self::Window { label: window }) => label == window,
+      (Self::WebviewWindow { .. }, Self::Webview { label }) => window_webviews.contains(label),
+      (target, listener) => target == listener,
+    }
+  }
 }
 
 impl<T: AsRef<str>> From<T> for EventTarget {
@@ -129,6 +238,14 @@ pub struct EmitArgs {
   pub event: String,
   /// Serialized payload.
   pub payload: String,
+  /// The profile the event belongs to, see [`crate::profile::Profile::emit`].
+  pub profile: Option<String>,
+  /// The label of the webview that emitted the event, recorded by [`EventRecorder`].
+  pub source: Option<String>,
+  /// The target the event was emitted to, recorded by [`EventRecorder`].
+  pub target: Option<EventTarget>,
+  /// Whether the event is emitted by [`crate::AppHandle::replay_events`].
+  pub replayed: bool,
 }
 
 impl EmitArgs {
@@ -139,6 +256,10 @@ impl EmitArgs {
       event_name: event.into(),
       event: serde_json::to_string(event)?,
       payload: serde_json::to_string(&payload)?,
+      profile: None,
+      source: None,
+      target: Some(EventTarget::Any),
+      replayed: false,
     })
   }
 }
@@ -147,12 +268,19 @@ impl EmitArgs {
 #[derive(Debug, Clone)]
 pub struct Event {
   id: EventId,
+  name: String,
   data: String,
+  profile: Option<String>,
 }
 
 impl Event {
-  fn new(id: EventId, data: String) -> Self {
-    Self { id, data }
+  fn new(id: EventId, name: String, data: String, profile: Option<String>) -> Self {
+    Self {
+      id,
+      name,
+      data,
+      profile,
+    }
   }
 
   /// The [`EventId`] of the handler that was triggered.
@@ -164,6 +292,89 @@ impl Event {
   pub fn payload(&self) -> &str {
     &self.data
   }
+
+  /// The profile the event was emitted for, see [`crate::profile::Profile::emit`].
+  ///
+  /// Events emitted by the frontend of a webview bound to a profile carry the profile of the webview.
+  pub fn profile(&self) -> Option<&str> {
+    self.profile.as_deref()
+  }
+
+  /// Deserializes the event payload.
+  ///
+  /// # Examples
+  /// ```
+  /// use tauri::Manager;
+  ///
+  /// #[derive(serde::Deserialize)]
+  /// struct Progress {
+  ///   downloaded: u64,
+  /// }
+  ///
+  /// tauri::Builder::default()
+  ///   .setup(|app| {
+  ///     app.listen_any("download-progress", |event| {
+  ///       match event.typed::<Progress>() {
+  ///         Ok(progress) => println!("downloaded {} bytes", progress.downloaded),
+  ///         Err(e) => eprintln!("{e}"),
+  ///       }
+  ///     });
+  ///     Ok(())
+  ///   });
+  /// ```
+  pub fn typed<T: DeserializeOwned>(&self) -> Result<T, EventDecodeError> {
+    serde_json::from_str(&self.data).map_err(|error| EventDecodeError {
+      event: self.name.clone(),
+      error,
+    })
+  }
+}
+
+/// An event with a known name and payload type, see [`crate::Manager::emit_typed`] and [`crate::Manager::listen_typed`].
+///
+/// The trait is implemented by the payload type, which can be a unit struct for events without payload.
+///
+/// # Examples
+/// ```
+/// #[derive(Clone, serde::Serialize, serde::Deserialize)]
+/// struct DownloadProgress {
+///   downloaded: u64,
+/// }
+///
+/// impl tauri::EventName for DownloadProgress {
+///   const NAME: &'static str = "download-progress";
+/// }
+/// ```
+pub trait EventName {
+  /// The event name. It must include only alphanumeric characters, `-`, `/`, `:` and `_`.
+  const NAME: &'static str;
+}
+
+/// The error returned when an event payload does not match the expected type.
+#[derive(Debug, thiserror::Error)]
+// serde_json errors already describe the position of the error in the payload
+#[error("failed to decode the payload of the `{event}` event: {error}")]
+pub struct EventDecodeError {
+  event: String,
+  #[source]
+  error: serde_json::Error,
+}
+
+impl EventDecodeError {
+  /// The name of the event.
+  pub fn event(&self) -> &str {
+    &self.event
+  }
+
+  /// The line of the payload where the error occurred.
+  pub fn line(&self) -> usize {
+    self.error.line()
+  }
+
+  /// The column of the payload where the error occurred.
+  pub fn column(&self) -> usize {
+    self.error.column()
+  }
 }
 
 pub fn listen_js_script(
@@ -196,20 +407,67 @@ pub fn listen_js_script(
   )
 }
 
-pub fn emit_js_script(
+pub(crate) fn emit_js_script(
   event_emit_function_name: &str,
   emit_args: &EmitArgs,
   serialized_target: &str,
+  sequence: &EventSequence,
 ) -> crate::Result<String> {
   Ok(format!(
-    "(function () {{ const fn = window['{}']; fn && fn({{event: {}, payload: {}}}, {target}) }})()",
+    "(function () {{ const fn = window['{}']; fn && fn({{event: {}, payload: {}{}, sequence: {}}}, {target}) }})()",
     event_emit_function_name,
     emit_args.event,
     emit_args.payload,
+    js_profile_field(emit_args)?,
+    serde_json::to_string(sequence)?,
     target = serialized_target,
   ))
 }
 
+/// Delivers the payloads of a batched event in a single script, see [`crate::Manager::emit_batched`].
+pub(crate) fn emit_js_batch_script(
+  event_emit_function_name: &str,
+  batch: &[&EmitArgs],
+  serialized_target: &str,
+  sequence: &EventSequence,
+) -> crate::Result<String> {
+  let event = batch
+    .first()
+    .map(|emit_args| emit_args.event.as_str())
+    .unwrap_or_default();
+  let payloads = batch
+    .iter()
+    .map(|emit_args| emit_args.payload.as_str())
+    .collect::<Vec<_>>()
+    .join(",");
+  Ok(format!(
+    "(function () {{ const fn = window['{event_emit_function_name}']; fn && fn({{event: {event}, batch: [{payloads}], sequence: {}}}, {serialized_target}) }})()",
+    serde_json::to_string(sequence)?,
+  ))
+}
+
+/// Calls a single JS listener, used to deliver sticky events to new listeners.
+pub fn emit_js_listener_script(
+  emit_args: &EmitArgs,
+  event_id: EventId,
+  handler: &str,
+) -> crate::Result<String> {
+  Ok(format!(
+    "(function () {{ const fn = {handler}; fn && fn({{event: {}, id: {event_id}, payload: {}{}}}) }})()",
+    emit_args.event,
+    emit_args.payload,
+    js_profile_field(emit_args)?,
+  ))
+}
+
+/// The `profile` field of the JS event object, only set for the events of a profile.
+fn js_profile_field(emit_args: &EmitArgs) -> crate::Result<String> {
+  Ok(match &emit_args.profile {
+    Some(profile) => format!(", profile: {}", serde_json::to_string(profile)?),
+    None => String::new(),
+  })
+}
+
 pub fn unlisten_js_script(
   listeners_object_name: &str,
   event_name: &str,
@@ -230,11 +488,21 @@ pub fn unlisten_js_script(
   )
 }
 
-pub fn event_initialization_script(function: &str, listeners: &str) -> String {
-  format!(
+pub fn event_initialization_script(
+  function: &str,
+  listeners: &str,
+  transport: EventTransport,
+) -> String {
+  let script = format!(
     "
-    Object.defineProperty(window, '{function}', {{
-      value: function (eventData, target) {{
+    (function () {{
+      function deliver(eventData, target) {{
+        if (eventData.batch) {{
+          for (const payload of eventData.batch) {{
+            deliver({{ event: eventData.event, payload }}, target)
+          }}
+          return
+        }}
         const listeners = (window['{listeners}'] && window['{listeners}'][eventData.event]) || []
         for (let i = listeners.length - 1; i >= 0; i--) {{
           const listener = listeners[i]
@@ -244,7 +512,133 @@ pub fn event_initialization_script(function: &str, listeners: &str) -> String {
           }}
         }}
       }}
-    }});
+
+      // the events that arrived before the previous events of their stream, keyed by stream
+      const streams = Object.create(null)
+      Object.defineProperty(window, '{function}', {{
+        value: function (eventData, target) {{
+          const sequence = eventData.sequence
+          if (!sequence) {{
+            deliver(eventData, target)
+            return
+          }}
+          delete eventData.sequence
+          let stream = streams[sequence.key]
+          if (!stream || stream.epoch < sequence.epoch) {{
+            // the stream starts at the first event the page receives, the previous ones were evaluated before the page was created
+            stream = streams[sequence.key] = {{ epoch: sequence.epoch, next: sequence.index, pending: new Map() }}
+          }}
+          if (sequence.epoch < stream.epoch || sequence.index < stream.next) {{
+            // events of a previous navigation or evaluated late at the start of the stream are not held back
+            deliver(eventData, target)
+            return
+          }}
+          stream.pending.set(sequence.index, [eventData, target])
+          while (stream.pending.has(stream.next)) {{
+            const [data, dataTarget] = stream.pending.get(stream.next)
+            stream.pending.delete(stream.next)
+            stream.next++
+            deliver(data, dataTarget)
+          }}
+        }}
+      }});
+    }})();
   "
-  )
+  );
+  match transport {
+    EventTransport::Eval => script,
+    EventTransport::Bridge => script + &bridge::event_bridge_script(function, listeners),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::EventTarget;
+  use crate::{sealed::ManagerBase, Manager};
+
+  #[test]
+  fn scoped_listener() {
+    use std::sync::{
+      atomic::{AtomicUsize, Ordering},
+      Arc,
+    };
+
+    let app = crate::test::mock_app();
+    let listeners = app.manager().listeners();
+    let count = listeners.listener_count();
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    let calls_ = calls.clone();
+    let guard = app.listen_scoped("scoped", move |_| {
+      calls_.fetch_add(1, Ordering::Relaxed);
+    });
+    assert_eq!(listeners.listener_count(), count + 1);
+    app.emit("scoped", ()).unwrap();
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+    drop(guard);
+    assert_eq!(listeners.listener_count(), count);
+    app.emit("scoped", ()).unwrap();
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+    // a forgotten guard keeps the listener
+    let id = app.listen_scoped("scoped", |_| {}).forget();
+    assert_eq!(listeners.listener_count(), count + 1);
+    app.unlisten(id);
+    assert_eq!(listeners.listener_count(), count);
+  }
+
+  #[test]
+  fn target_matches_listeners() {
+    let listeners = [
+      EventTarget::App,
+      EventTarget::window("main"),
+      EventTarget::webview("main"),
+      EventTarget::webview("child"),
+      EventTarget::webview_window("main"),
+      EventTarget::labeled("main"),
+      EventTarget::labeled("child"),
+      EventTarget::window("other"),
+    ];
+    let window_webviews = ["main".to_string(), "child".to_string()];
+
+    let matched = |target: EventTarget| {
+      listeners
+        .iter()
+        .filter(|listener| target.matches(listener, &window_webviews))
+        .cloned()
+        .collect::<Vec<_>>()
+    };
+
+    assert_eq!(matched(EventTarget::Any), listeners);
+    assert_eq!(matched(EventTarget::App), [EventTarget::App]);
+    assert_eq!(
+      matched(EventTarget::labeled("main")),
+      [
+        EventTarget::window("main"),
+        EventTarget::webview("main"),
+        EventTarget::webview_window("main"),
+        EventTarget::labeled("main"),
+      ]
+    );
+    assert_eq!(
+      matched(EventTarget::window("main")),
+      [EventTarget::window("main"), EventTarget::labeled("main")]
+    );
+    assert_eq!(
+      matched(EventTarget::webview("child")),
+      [EventTarget::webview("child"), EventTarget::labeled("child")]
+    );
+    assert_eq!(
+      matched(EventTarget::webview_window("main")),
+      [
+        EventTarget::window("main"),
+        EventTarget::webview("main"),
+        EventTarget::webview("child"),
+        EventTarget::webview_window("main"),
+        EventTarget::labeled("main"),
+      ]
+    );
+    assert_eq!(matched(EventTarget::webview("other")), []);
+  }
 }
@@ -5,14 +5,14 @@
 use std::ops::Deref;
 
 use serde::{Deserialize, Deserializer};
-use serde_json::Value as JsonValue;
-use tauri_runtime::window::is_label_valid;
+use serde_json::{value::RawValue, Value as JsonValue};
+use tauri_runtime::window::validate_label;
 
 use crate::plugin::{Builder, TauriPlugin};
-use crate::{command, ipc::CallbackFn, EventId, Manager, Result, Runtime};
-use crate::{AppHandle, Webview};
+use crate::Webview;
+use crate::{command, ipc::CallbackFn, sealed::ManagerBase, EventId, Result, Runtime};
 
-use super::{is_event_name_valid, EventTarget};
+use super::{is_event_name_valid, EmitArgs, EventTarget};
 
 pub struct EventName(String);
 
@@ -54,13 +54,9 @@ impl<'de> Deserialize<'de> for WebviewLabel {
     D: Deserializer<'de>,
   {
     let event_id = String::deserialize(deserializer)?;
-    if is_label_valid(&event_id) {
-      Ok(WebviewLabel(event_id))
-    } else {
-      Err(serde::de::Error::custom(
-        "Webview label must include only alphanumeric characters, `-`, `/`, `:` and `_`.",
-      ))
-    }
+    validate_label(&event_id)
+      .map(|()| WebviewLabel(event_id))
+      .map_err(serde::de::Error::custom)
   }
 }
 
@@ -83,28 +79,63 @@ pub fn unlisten<R: Runtime>(
   webview.unlisten_js(&event, event_id)
 }
 
+/// Serializes an event emitted by the frontend, carrying the profile of the webview.
+fn emit_args<R: Runtime>(
+  webview: &Webview<R>,
+  event: &str,
+  payload: Option<JsonValue>,
+) -> Result<EmitArgs> {
+  let mut emit_args = EmitArgs::new(event, payload)?;
+  emit_args.profile = webview
+    .manager()
+    .webview
+    .profiles
+    .lock()
+    .unwrap()
+    .get(webview.label())
+    .cloned();
+  emit_args.source = Some(webview.label().into());
+  Ok(emit_args)
+}
+
 #[command(root = "crate")]
 pub fn emit<R: Runtime>(
-  app: AppHandle<R>,
+  webview: Webview<R>,
   event: EventName,
   payload: Option<JsonValue>,
 ) -> Result<()> {
-  app.emit(&event, payload)
+  let emit_args = emit_args(&webview, &event, payload)?;
+  webview.manager().emit_args_filter(emit_args, |_| true)
 }
 
 #[command(root = "crate")]
 pub fn emit_to<R: Runtime>(
-  app: AppHandle<R>,
+  webview: Webview<R>,
   target: EventTarget,
   event: EventName,
   payload: Option<JsonValue>,
 ) -> Result<()> {
-  app.emit_to(target, &event, payload)
+  let mut emit_args = emit_args(&webview, &event, payload)?;
+  emit_args.target = Some(target.clone());
+  let manager = webview.manager();
+  manager.emit_args_filter(emit_args, manager.event_target_filter(target))
+}
+
+/// Waits for the event bridge messages of the webview, see [`crate::EventTransport::Bridge`].
+#[command(root = "crate")]
+pub async fn poll_bridge<R: Runtime>(webview: Webview<R>) -> Result<Vec<Box<RawValue>>> {
+  Ok(webview.event_bridge.poll().await)
 }
 
 /// Initializes the event plugin.
 pub(crate) fn init<R: Runtime>() -> TauriPlugin<R> {
   Builder::new("event")
-    .invoke_handler(crate::generate_handler![listen, unlisten, emit, emit_to])
+    .invoke_handler(crate::generate_handler![
+      listen,
+      unlisten,
+      emit,
+      emit_to,
+      poll_bridge
+    ])
     .build()
 }
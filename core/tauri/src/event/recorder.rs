@@ -0,0 +1,295 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Recording of the emitted events for debugging, see [`crate::Builder::record_events`].
+
+use std::{
+  collections::VecDeque,
+  fmt,
+  sync::{Arc, Mutex},
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{EmitArgs, EventTarget};
+
+/// The default value of [`RecorderConfig::max_entries`].
+pub const DEFAULT_MAX_RECORDED_EVENTS: usize = 1000;
+
+/// The default value of [`RecorderConfig::max_payload_bytes`].
+pub const DEFAULT_MAX_RECORDED_PAYLOAD_BYTES: usize = 4 * 1024;
+
+type Redactor = dyn Fn(&str, &str) -> Option<String> + Send + Sync;
+
+/// How the emitted events are recorded, see [`crate::Builder::record_events`].
+#[derive(Clone)]
+pub struct RecorderConfig {
+  /// The maximum number of recorded events, the oldest one is dropped first.
+  pub max_entries: usize,
+  /// Whether the serialized payloads are recorded. Only the events recorded with their payload can be replayed.
+  pub include_payloads: bool,
+  /// The maximum size of a recorded payload, larger payloads are not recorded.
+  pub max_payload_bytes: usize,
+  redactor: Option<Arc<Redactor>>,
+}
+
+impl fmt::Debug for RecorderConfig {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("RecorderConfig")
+      .field("max_entries", &self.max_entries)
+      .field("include_payloads", &self.include_payloads)
+      .field("max_payload_bytes", &self.max_payload_bytes)
+      .field("redactor", &self.redactor.is_some())
+      .finish()
+  }
+}
+
+impl Default for RecorderConfig {
+  fn default() -> Self {
+    Self {
+      max_entries: DEFAULT_MAX_RECORDED_EVENTS,
+      include_payloads: false,
+      max_payload_bytes: DEFAULT_MAX_RECORDED_PAYLOAD_BYTES,
+      redactor: None,
+    }
+  }
+}
+
+impl RecorderConfig {
+  /// Records up to `max_entries` events, without their payloads.
+  pub fn new(max_entries: usize) -> Self {
+    Self {
+      max_entries,
+      ..Default::default()
+    }
+  }
+
+  /// Records the payloads of up to `max_payload_bytes`.
+  #[must_use]
+  pub fn include_payloads(mut self, max_payload_bytes: usize) -> Self {
+    self.include_payloads = true;
+    self.max_payload_bytes = max_payload_bytes;
+    self
+  }
+
+  /// Sets a function redacting the payloads of sensitive events before they are recorded,
+  /// called with the event name and the serialized payload.
+  ///
+  /// It returns the serialized payload to record, or `None` to not record the payload.
+  #[must_use]
+  pub fn redact<F: Fn(&str, &str) -> Option<String> + Send + Sync + 'static>(
+    mut self,
+    redactor: F,
+  ) -> Self {
+    self.redactor.replace(Arc::new(redactor));
+    self
+  }
+}
+
+/// An event recorded by [`crate::Builder::record_events`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct RecordedEvent {
+  /// When the event was emitted, in milliseconds since the Unix epoch.
+  pub timestamp: u64,
+  /// The event name.
+  pub event: String,
+  /// The label of the webview that emitted the event, `None` for the events emitted from Rust.
+  pub source: Option<String>,
+  /// The target the event was emitted to, `None` for the events emitted with [`crate::Manager::emit_filter`].
+  pub target: Option<EventTarget>,
+  /// The size of the serialized payload.
+  pub payload_size: usize,
+  /// The serialized payload, see [`RecorderConfig::include_payloads`].
+  pub payload: Option<String>,
+  /// The labels of the webviews whose JavaScript listeners received the event.
+  pub delivered_to: Vec<String>,
+  /// Whether the event was emitted by [`crate::AppHandle::replay_events`].
+  pub replayed: bool,
+}
+
+/// The ring buffer of the recorded events.
+pub(crate) struct EventRecorder {
+  config: RecorderConfig,
+  entries: Mutex<VecDeque<RecordedEvent>>,
+}
+
+impl EventRecorder {
+  pub(crate) fn new(config: RecorderConfig) -> Self {
+    Self {
+      entries: Mutex::new(VecDeque::with_capacity(config.max_entries.min(1024))),
+      config,
+    }
+  }
+
+  pub(crate) fn record(&self, emit_args: &EmitArgs, mut delivered_to: Vec<String>) {
+    if self.config.max_entries == 0 {
+      return;
+    }
+
+    let payload = if self.config.include_payloads {
+      match &self.config.redactor {
+        Some(redactor) => redactor(&emit_args.event_name, &emit_args.payload),
+        None => Some(emit_args.payload.clone()),
+      }
+      .filter(|payload| payload.len() <= self.config.max_payload_bytes)
+    } else {
+      None
+    };
+    delivered_to.sort();
+    delivered_to.dedup();
+
+    let event = RecordedEvent {
+      timestamp: SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default(),
+      event: emit_args.event_name.clone(),
+      source: emit_args.source.clone(),
+      target: emit_args.target.clone(),
+      payload_size: emit_args.payload.len(),
+      payload,
+      delivered_to,
+      replayed: emit_args.replayed,
+    };
+
+    let mut entries = self.entries.lock().unwrap();
+    while entries.len() >= self.config.max_entries {
+      entries.pop_front();
+    }
+    entries.push_back(event);
+  }
+
+  /// The recorded events, oldest first.
+  pub(crate) fn entries(&self) -> Vec<RecordedEvent> {
+    self.entries.lock().unwrap().iter().cloned().collect()
+  }
+
+  pub(crate) fn clear(&self) {
+    self.entries.lock().unwrap().clear();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+  };
+
+  use super::{EventRecorder, RecorderConfig};
+  use crate::{
+    event::{EmitArgs, EventTarget},
+    test::{mock_builder, mock_context, noop_assets},
+    Manager,
+  };
+
+  fn emit_args(event: &str, payload: &str) -> EmitArgs {
+    let mut emit_args = EmitArgs::new(event, payload).unwrap();
+    emit_args.target = Some(EventTarget::Any);
+    emit_args
+  }
+
+  #[test]
+  fn ring_buffer() {
+    let recorder = EventRecorder::new(RecorderConfig::new(2));
+    recorder.record(&emit_args("a", "1"), vec!["main".into(), "main".into()]);
+    recorder.record(&emit_args("b", "2"), Vec::new());
+    recorder.record(&emit_args("c", "3"), Vec::new());
+
+    let entries = recorder.entries();
+    assert_eq!(
+      entries
+        .iter()
+        .map(|entry| entry.event.as_str())
+        .collect::<Vec<_>>(),
+      ["b", "c"]
+    );
+    assert_eq!(entries[0].payload_size, 3);
+    assert_eq!(entries[0].payload, None);
+
+    recorder.clear();
+    assert!(recorder.entries().is_empty());
+  }
+
+  #[test]
+  fn payload_capture() {
+    let recorder = EventRecorder::new(RecorderConfig::new(10).include_payloads(16).redact(
+      |event, payload| match event {
+        "login" => Some("\"<redacted>\"".into()),
+        "secret" => None,
+        _ => Some(payload.into()),
+      },
+    ));
+    recorder.record(&emit_args("progress", "50"), vec!["main".into()]);
+    recorder.record(&emit_args("progress", "0123456789abcdef"), Vec::new());
+    recorder.record(&emit_args("login", "hunter2"), Vec::new());
+    recorder.record(&emit_args("secret", "42"), Vec::new());
+
+    let payloads = recorder
+      .entries()
+      .into_iter()
+      .map(|entry| entry.payload)
+      .collect::<Vec<_>>();
+    assert_eq!(
+      payloads,
+      [
+        Some("\"50\"".into()),
+        // larger than the cap once serialized
+        None,
+        Some("\"<redacted>\"".into()),
+        None
+      ]
+    );
+    assert_eq!(recorder.entries()[0].delivered_to, ["main"]);
+  }
+
+  #[test]
+  fn record_and_replay() {
+    let app = mock_builder()
+      .record_events(RecorderConfig::new(10).include_payloads(64))
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    let received = Arc::new(AtomicUsize::new(0));
+    let received_ = received.clone();
+    app.listen_any("progress", move |_| {
+      received_.fetch_add(1, Ordering::SeqCst);
+    });
+
+    app.emit("progress", 1).unwrap();
+    app.emit_to(EventTarget::app(), "progress", 2).unwrap();
+    app.emit_filter("progress", 3, |_| true).unwrap();
+    assert_eq!(received.load(Ordering::SeqCst), 3);
+
+    let log = app.handle().event_log();
+    assert_eq!(
+      log
+        .iter()
+        .map(|event| (event.target.clone(), event.payload.clone()))
+        .collect::<Vec<_>>(),
+      [
+        (Some(EventTarget::Any), Some("1".into())),
+        (Some(EventTarget::app()), Some("2".into())),
+        (None, Some("3".into())),
+      ]
+    );
+    assert!(log.iter().all(|event| event.source.is_none()));
+
+    // the events emitted with a filter are not replayed
+    let replayed =
+      crate::async_runtime::block_on(app.handle().replay_events(|_| true, 0.0)).unwrap();
+    assert_eq!(replayed, 2);
+    assert_eq!(received.load(Ordering::SeqCst), 5);
+
+    let log = app.handle().event_log();
+    assert_eq!(log.len(), 5);
+    assert!(log[3].replayed && log[4].replayed);
+    assert_eq!(log[4].payload.as_deref(), Some("2"));
+
+    app.handle().clear_event_log();
+    assert!(app.handle().event_log().is_empty());
+  }
+}
@@ -0,0 +1,86 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+/// A list of commands a webview is allowed to invoke, see [`crate::WebviewWindowBuilder::allowed_commands`].
+///
+/// Entries are either plain command names such as `greet` or plugin commands such as `plugin:event|listen`.
+/// A trailing `*` matches any suffix, so `plugin:event|*` allows every command of the event plugin.
+///
+/// # Examples
+///
+/// ```
+/// use tauri::ipc::AllowList;
+///
+/// let list = AllowList::new().allow("plugin:event|*").allow("greet");
+/// assert!(list.is_allowed("plugin:event|listen"));
+/// assert!(list.is_allowed("greet"));
+/// assert!(!list.is_allowed("plugin:window|close"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AllowList {
+  patterns: Vec<String>,
+}
+
+impl AllowList {
+  /// Creates an empty list, which denies every command.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Allows the commands matching the given pattern.
+  #[must_use]
+  pub fn allow(mut self, pattern: impl Into<String>) -> Self {
+    self.patterns.push(pattern.into());
+    self
+  }
+
+  /// The patterns of this list.
+  pub fn patterns(&self) -> &[String] {
+    &self.patterns
+  }
+
+  /// Whether the given command matches one of the patterns.
+  pub fn is_allowed(&self, command: &str) -> bool {
+    self
+      .patterns
+      .iter()
+      .any(|pattern| match pattern.strip_suffix('*') {
+        Some(prefix) => command.starts_with(prefix),
+        None => command == pattern,
+      })
+  }
+}
+
+impl<S: Into<String>> FromIterator<S> for AllowList {
+  fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+    Self {
+      patterns: iter.into_iter().map(Into::into).collect(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::AllowList;
+
+  #[test]
+  fn matches_patterns() {
+    let list: AllowList = ["plugin:event|*", "greet"].into_iter().collect();
+
+    assert!(list.is_allowed("plugin:event|listen"));
+    assert!(list.is_allowed("plugin:event|emit"));
+    assert!(list.is_allowed("greet"));
+
+    assert!(!list.is_allowed("greeting"));
+    assert!(!list.is_allowed("plugin:event"));
+    assert!(!list.is_allowed("plugin:eventual|listen"));
+    assert!(!list.is_allowed("plugin:window|close"));
+  }
+
+  #[test]
+  fn empty_list_denies_everything() {
+    assert!(!AllowList::new().is_allowed("greet"));
+    assert!(AllowList::new().allow("*").is_allowed("greet"));
+  }
+}
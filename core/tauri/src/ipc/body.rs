@@ -0,0 +1,207 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Command arguments reading the raw IPC request body.
+
+use std::ops::Deref;
+
+use super::{CommandArg, CommandItem, InvokeBody, InvokeError};
+use crate::Runtime;
+
+/// The raw body of the IPC request, sent by the frontend as an `ArrayBuffer` or `Uint8Array`.
+///
+/// The body is borrowed from the request instead of being copied, so it can only be used in synchronous commands,
+/// see [`RawBodyBuf`] to own it.
+/// The command fails if the request has a JSON body.
+///
+/// # Examples
+///
+/// ```rust
+/// use tauri::ipc::RawBody;
+///
+/// #[tauri::command]
+/// fn checksum(body: RawBody<'_>) -> u32 {
+///   body.iter().map(|byte| *byte as u32).sum()
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct RawBody<'a>(&'a [u8]);
+
+impl<'a> RawBody<'a> {
+  /// The body bytes, with the lifetime of the request.
+  pub fn into_inner(self) -> &'a [u8] {
+    self.0
+  }
+}
+
+impl Deref for RawBody<'_> {
+  type Target = [u8];
+
+  fn deref(&self) -> &Self::Target {
+    self.0
+  }
+}
+
+impl AsRef<[u8]> for RawBody<'_> {
+  fn as_ref(&self) -> &[u8] {
+    self.0
+  }
+}
+
+impl<'a, R: Runtime> CommandArg<'a, R> for RawBody<'a> {
+  /// Borrows the raw body of the invoke request, failing if the request has a JSON body.
+  fn from_command(command: CommandItem<'a, R>) -> Result<Self, InvokeError> {
+    match command.message.payload() {
+      InvokeBody::Raw(body) => Ok(Self(body)),
+      InvokeBody::Json(_) => Err(
+        format!(
+          "command {} expected a raw body for argument {} but the IPC call used a JSON payload",
+          command.name, command.key
+        )
+        .into(),
+      ),
+    }
+  }
+}
+
+/// An owned copy of the raw body of the IPC request, see [`RawBody`].
+///
+/// The body is copied once, so it can be used in asynchronous commands.
+/// The command fails if the request has a JSON body.
+///
+/// # Examples
+///
+/// ```rust
+/// use tauri::ipc::RawBodyBuf;
+///
+/// #[tauri::command]
+/// async fn upload(body: RawBodyBuf) -> usize {
+///   body.len()
+/// }
+/// ```
+///
+/// The body converts into [`bytes::Bytes`] without another copy:
+///
+/// ```rust
+/// use tauri::ipc::RawBodyBuf;
+///
+/// #[tauri::command]
+/// async fn upload(body: RawBodyBuf) -> usize {
+///   let bytes = bytes::Bytes::from(body);
+///   let (header, _payload) = bytes.split_at(bytes.len().min(4));
+///   header.len()
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawBodyBuf(Vec<u8>);
+
+impl RawBodyBuf {
+  /// The body bytes.
+  pub fn into_inner(self) -> Vec<u8> {
+    self.0
+  }
+}
+
+impl Deref for RawBodyBuf {
+  type Target = [u8];
+
+  fn deref(&self) -> &Self::Target {
+    &self.0
+  }
+}
+
+impl AsRef<[u8]> for RawBodyBuf {
+  fn as_ref(&self) -> &[u8] {
+    &self.0
+  }
+}
+
+impl From<RawBodyBuf> for bytes::Bytes {
+  fn from(body: RawBodyBuf) -> Self {
+    body.0.into()
+  }
+}
+
+impl<'a, R: Runtime> CommandArg<'a, R> for RawBodyBuf {
+  /// Copies the raw body of the invoke request, failing if the request has a JSON body.
+  fn from_command(command: CommandItem<'a, R>) -> Result<Self, InvokeError> {
+    RawBody::from_command(command).map(|body| Self(body.to_vec()))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::{json, Value as JsonValue};
+
+  use super::{RawBody, RawBodyBuf};
+  use crate::{
+    ipc::InvokeBody,
+    test::{get_ipc_json_response, mock_builder, mock_context, noop_assets},
+    WebviewWindowBuilder,
+  };
+
+  #[crate::command(root = "crate")]
+  fn borrowed(body: RawBody<'_>) -> usize {
+    body.len()
+  }
+
+  #[crate::command(root = "crate")]
+  async fn owned(body: RawBodyBuf) -> Vec<u8> {
+    body.into_inner()
+  }
+
+  #[crate::command(root = "crate")]
+  fn bytes(body: Vec<u8>) -> Vec<u8> {
+    body
+  }
+
+  #[crate::command(root = "crate")]
+  fn optional(body: Option<Vec<u8>>) -> Option<usize> {
+    body.map(|body| body.len())
+  }
+
+  #[test]
+  fn raw_body_extractors() {
+    let app = mock_builder()
+      .invoke_handler(crate::generate_handler![borrowed, owned, bytes, optional])
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    let body = || InvokeBody::Raw(vec![1, 2, 3]);
+
+    assert_eq!(
+      get_ipc_json_response(&webview, "borrowed", body(), Default::default()),
+      Ok(json!(3))
+    );
+    assert_eq!(
+      get_ipc_json_response(&webview, "owned", body(), Default::default()),
+      Ok(json!([1, 2, 3]))
+    );
+    assert_eq!(
+      get_ipc_json_response(&webview, "borrowed", json!({ "body": [1, 2] }), Default::default()),
+      Err(json!(
+        "command borrowed expected a raw body for argument body but the IPC call used a JSON payload"
+      ))
+    );
+
+    // deserialized arguments are only read from a JSON payload
+    assert_eq!(
+      get_ipc_json_response(
+        &webview,
+        "bytes",
+        json!({ "body": [1, 2] }),
+        Default::default()
+      ),
+      Ok(json!([1, 2]))
+    );
+    assert!(get_ipc_json_response(&webview, "bytes", body(), Default::default()).is_err());
+    assert!(get_ipc_json_response(&webview, "optional", body(), Default::default()).is_err());
+    assert_eq!(
+      get_ipc_json_response(&webview, "optional", json!({}), Default::default()),
+      Ok(JsonValue::Null)
+    );
+  }
+}
@@ -0,0 +1,548 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Caching of the command responses, see [`crate::Builder::cache_command`].
+
+use std::{
+  collections::HashMap,
+  sync::Mutex,
+  time::{Duration, Instant},
+};
+
+use serde_json::Value as JsonValue;
+
+use super::InvokeBody;
+
+/// The default value of [`CachePolicy::max_entries`].
+pub const DEFAULT_MAX_CACHE_ENTRIES: usize = 128;
+
+/// How the cached responses of a command are keyed, see [`CachePolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CacheKey {
+  /// The canonicalized command arguments, the responses are shared by all webviews.
+  ArgsHash,
+  /// The canonicalized command arguments and the webview label, the responses are local to each webview.
+  WebviewArgsHash,
+}
+
+/// How the responses of a command are cached, see [`crate::Builder::cache_command`].
+///
+/// Only successful responses serialized to JSON are cached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CachePolicy {
+  /// How long a response is reused.
+  pub ttl: Duration,
+  /// The maximum number of cached responses of the command, the oldest one is evicted first.
+  pub max_entries: usize,
+  /// How the responses are keyed.
+  pub key: CacheKey,
+}
+
+impl CachePolicy {
+  /// Caches the responses for `ttl`, keyed by the arguments, with up to [`DEFAULT_MAX_CACHE_ENTRIES`] responses.
+  pub fn new(ttl: Duration) -> Self {
+    Self {
+      ttl,
+      max_entries: DEFAULT_MAX_CACHE_ENTRIES,
+      key: CacheKey::ArgsHash,
+    }
+  }
+}
+
+/// The key of a cached response.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct CacheEntryKey {
+  webview: Option<String>,
+  args: Vec<u8>,
+}
+
+struct CachedResponse {
+  stored_at: Instant,
+  body: JsonValue,
+}
+
+struct CommandEntries {
+  policy: CachePolicy,
+  /// Set for the commands taking the webview or window as argument, whose responses are never cached.
+  bypass: bool,
+  entries: HashMap<CacheEntryKey, CachedResponse>,
+}
+
+impl CommandEntries {
+  fn new(policy: CachePolicy) -> Self {
+    Self {
+      policy,
+      bypass: false,
+      entries: HashMap::new(),
+    }
+  }
+
+  fn is_fresh(&self, response: &CachedResponse, now: Instant) -> bool {
+    now.duration_since(response.stored_at) < self.policy.ttl
+  }
+}
+
+/// The cached command responses, managed as state.
+#[derive(Default)]
+pub(crate) struct CommandCache(Mutex<HashMap<String, CommandEntries>>);
+
+impl CommandCache {
+  pub(crate) fn new(policies: HashMap<String, CachePolicy>) -> Self {
+    Self(Mutex::new(
+      policies
+        .into_iter()
+        .map(|(command, policy)| (command, CommandEntries::new(policy)))
+        .collect(),
+    ))
+  }
+
+  /// Registers the policy of a command unless it already has one.
+  pub(crate) fn register(&self, command: &str, policy: CachePolicy) {
+    let mut commands = self.0.lock().unwrap();
+    if !commands.contains_key(command) {
+      commands.insert(command.into(), CommandEntries::new(policy));
+    }
+  }
+
+  /// Never caches the responses of the command, used for the commands taking the webview or window as argument.
+  pub(crate) fn bypass(&self, command: &str) {
+    if let Some(entries) = self.0.lock().unwrap().get_mut(command) {
+      entries.bypass = true;
+      entries.entries.clear();
+    }
+  }
+
+  /// The key of the response to a command invocation, `None` if the command is not cached.
+  pub(crate) fn key(
+    &self,
+    command: &str,
+    webview: &str,
+    body: &InvokeBody,
+  ) -> Option<CacheEntryKey> {
+    let key = match self.0.lock().unwrap().get(command) {
+      Some(entries) if !entries.bypass => entries.policy.key,
+      _ => return None,
+    };
+
+    let mut args = Vec::new();
+    match body {
+      InvokeBody::Json(value) => {
+        args.push(b'j');
+        write_canonical_json(value, &mut args);
+      }
+      InvokeBody::Raw(bytes) => {
+        args.push(b'r');
+        args.extend_from_slice(bytes);
+      }
+    }
+
+    Some(CacheEntryKey {
+      webview: match key {
+        CacheKey::ArgsHash => None,
+        CacheKey::WebviewArgsHash => Some(webview.into()),
+      },
+      args,
+    })
+  }
+
+  /// The cached response, if it has not expired.
+  pub(crate) fn get(&self, command: &str, key: &CacheEntryKey) -> Option<JsonValue> {
+    let mut commands = self.0.lock().unwrap();
+    let entries = commands
+      .get_mut(command)
+      .filter(|entries| !entries.bypass)?;
+    let response = entries.entries.get(key)?;
+    if entries.is_fresh(response, Instant::now()) {
+      Some(response.body.clone())
+    } else {
+      entries.entries.remove(key);
+      None
+    }
+  }
+
+  /// Caches a successful response, keeping the response already cached for the key if it has not expired.
+  pub(crate) fn store(&self, command: &str, key: CacheEntryKey, body: &JsonValue) {
+    let mut commands = self.0.lock().unwrap();
+    let Some(entries) = commands.get_mut(command).filter(|entries| !entries.bypass) else {
+      return;
+    };
+
+    let now = Instant::now();
+    let ttl = entries.policy.ttl;
+    entries
+      .entries
+      .retain(|_, response| now.duration_since(response.stored_at) < ttl);
+    if entries.entries.contains_key(&key) || entries.policy.max_entries == 0 {
+      return;
+    }
+    if entries.entries.len() >= entries.policy.max_entries {
+      let oldest = entries
+        .entries
+        .iter()
+        .min_by_key(|(_, response)| response.stored_at)
+        .map(|(key, _)| key.clone());
+      if let Some(oldest) = oldest {
+        entries.entries.remove(&oldest);
+      }
+    }
+    entries.entries.insert(
+      key,
+      CachedResponse {
+        stored_at: now,
+        body: body.clone(),
+      },
+    );
+  }
+
+  /// Removes the cached responses of a command.
+  pub(crate) fn invalidate(&self, command: &str) {
+    if let Some(entries) = self.0.lock().unwrap().get_mut(command) {
+      entries.entries.clear();
+    }
+  }
+
+  #[cfg(test)]
+  fn len(&self, command: &str) -> usize {
+    self
+      .0
+      .lock()
+      .unwrap()
+      .get(command)
+      .map(|entries| entries.entries.len())
+      .unwrap_or_default()
+  }
+}
+
+/// Writes the value as JSON with the object keys sorted, so equal arguments have the same key.
+fn write_canonical_json(value: &JsonValue, out: &mut Vec<u8>) {
+  match value {
+    JsonValue::Object(map) => {
+      let mut fields = map.iter().collect::<Vec<_>>();
+      fields.sort_by_key(|&(name, _)| name);
+      out.push(b'{');
+      for (i, (name, value)) in fields.into_iter().enumerate() {
+        if i > 0 {
+          out.push(b',');
+        }
+        // serializing to a `Vec` cannot fail
+        let _ = serde_json::to_writer(&mut *out, name);
+        out.push(b':');
+        write_canonical_json(value, out);
+      }
+      out.push(b'}');
+    }
+    JsonValue::Array(items) => {
+      out.push(b'[');
+      for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+          out.push(b',');
+        }
+        write_canonical_json(item, out);
+      }
+      out.push(b']');
+    }
+    _ => {
+      let _ = serde_json::to_writer(&mut *out, value);
+    }
+  }
+}
+
+/// The full name of a command, as invoked by the frontend.
+fn command_name(plugin: Option<&str>, command: &str) -> String {
+  match plugin {
+    Some(plugin) => format!("plugin:{plugin}|{command}"),
+    None => command.into(),
+  }
+}
+
+/// Items used by the code generated by `#[command(cache = "...")]`.
+pub mod private {
+  use std::time::Duration;
+
+  use super::{command_name, CachePolicy, CommandCache};
+  use crate::{ipc::InvokeMessage, Manager, Runtime};
+
+  /// Registers the cache policy of the command when it is first invoked,
+  /// unless one was registered with [`crate::Builder::cache_command`].
+  pub fn register_command_cache<R: Runtime>(
+    message: &InvokeMessage<R>,
+    plugin: Option<&str>,
+    ttl: Duration,
+  ) {
+    if let Some(cache) = message.webview_ref().try_state::<CommandCache>() {
+      cache.register(
+        &command_name(plugin, message.command()),
+        CachePolicy::new(ttl),
+      );
+    }
+  }
+
+  /// Never caches the responses of the command, which takes the webview or window as argument.
+  pub fn bypass_command_cache<R: Runtime>(message: &InvokeMessage<R>, plugin: Option<&str>) {
+    if let Some(cache) = message.webview_ref().try_state::<CommandCache>() {
+      cache.bypass(&command_name(plugin, message.command()));
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+  };
+
+  use serde_json::json;
+
+  use super::{CacheKey, CachePolicy, CommandCache};
+  use crate::{
+    ipc::InvokeBody,
+    test::{get_ipc_json_response, mock_builder, mock_context, noop_assets, MockRuntime},
+    Manager, Webview, WebviewWindowBuilder,
+  };
+
+  static PARSE_CALLS: AtomicUsize = AtomicUsize::new(0);
+  static FAIL_CALLS: AtomicUsize = AtomicUsize::new(0);
+  static CACHED_CALLS: AtomicUsize = AtomicUsize::new(0);
+  static LABEL_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+  #[crate::command(root = "crate")]
+  fn parse(path: String) -> String {
+    PARSE_CALLS.fetch_add(1, Ordering::SeqCst);
+    format!("parsed {path}")
+  }
+
+  #[crate::command(root = "crate")]
+  fn fail() -> Result<(), String> {
+    FAIL_CALLS.fetch_add(1, Ordering::SeqCst);
+    Err("failed".into())
+  }
+
+  #[crate::command(root = "crate", cache = "1m")]
+  fn cached() -> usize {
+    CACHED_CALLS.fetch_add(1, Ordering::SeqCst)
+  }
+
+  #[crate::command(root = "crate")]
+  fn label(webview: Webview<MockRuntime>) -> String {
+    LABEL_CALLS.fetch_add(1, Ordering::SeqCst);
+    webview.label().into()
+  }
+
+  fn cache(policy: CachePolicy) -> CommandCache {
+    CommandCache::new(HashMap::from([("parse".to_string(), policy)]))
+  }
+
+  #[test]
+  fn canonicalized_args() {
+    let cache = cache(CachePolicy::new(Duration::from_secs(60)));
+    let key = |body: InvokeBody| cache.key("parse", "main", &body).unwrap();
+
+    assert_eq!(
+      key(InvokeBody::Json(
+        json!({ "a": 1, "b": { "c": [1, 2], "d": null } })
+      )),
+      key(InvokeBody::Json(
+        json!({ "b": { "d": null, "c": [1, 2] }, "a": 1 })
+      ))
+    );
+    assert_ne!(
+      key(InvokeBody::Json(json!({ "a": 1 }))),
+      key(InvokeBody::Json(json!({ "a": 2 })))
+    );
+    assert_ne!(
+      key(InvokeBody::Json(json!("a"))),
+      key(InvokeBody::Raw(b"\"a\"".to_vec()))
+    );
+    assert!(cache
+      .key("other", "main", &InvokeBody::Json(json!({})))
+      .is_none());
+  }
+
+  #[test]
+  fn webview_scope() {
+    let args = InvokeBody::Json(json!({ "path": "a.txt" }));
+
+    let global = cache(CachePolicy::new(Duration::from_secs(60)));
+    assert_eq!(
+      global.key("parse", "main", &args),
+      global.key("parse", "other", &args)
+    );
+
+    let local = cache(CachePolicy {
+      key: CacheKey::WebviewArgsHash,
+      ..CachePolicy::new(Duration::from_secs(60))
+    });
+    let main = local.key("parse", "main", &args).unwrap();
+    local.store("parse", main.clone(), &json!(1));
+    assert_eq!(local.get("parse", &main), Some(json!(1)));
+    assert!(local
+      .get("parse", &local.key("parse", "other", &args).unwrap())
+      .is_none());
+  }
+
+  #[test]
+  fn eviction() {
+    let cache = cache(CachePolicy {
+      max_entries: 2,
+      ..CachePolicy::new(Duration::from_secs(60))
+    });
+    let key = |n: u32| {
+      cache
+        .key("parse", "main", &InvokeBody::Json(json!(n)))
+        .unwrap()
+    };
+
+    cache.store("parse", key(1), &json!(1));
+    std::thread::sleep(Duration::from_millis(2));
+    cache.store("parse", key(2), &json!(2));
+    std::thread::sleep(Duration::from_millis(2));
+    cache.store("parse", key(3), &json!(3));
+    assert_eq!(cache.len("parse"), 2);
+    assert!(cache.get("parse", &key(1)).is_none());
+    assert_eq!(cache.get("parse", &key(3)), Some(json!(3)));
+
+    // an existing response is not replaced before it expires
+    cache.store("parse", key(3), &json!(4));
+    assert_eq!(cache.get("parse", &key(3)), Some(json!(3)));
+
+    cache.invalidate("parse");
+    assert_eq!(cache.len("parse"), 0);
+  }
+
+  #[test]
+  fn expiration() {
+    let cache = cache(CachePolicy::new(Duration::from_millis(20)));
+    let key = cache
+      .key("parse", "main", &InvokeBody::Json(json!({})))
+      .unwrap();
+
+    cache.store("parse", key.clone(), &json!("parsed"));
+    assert_eq!(cache.get("parse", &key), Some(json!("parsed")));
+    std::thread::sleep(Duration::from_millis(30));
+    assert!(cache.get("parse", &key).is_none());
+    assert_eq!(cache.len("parse"), 0);
+  }
+
+  #[test]
+  fn bypass() {
+    let cache = cache(CachePolicy::new(Duration::from_secs(60)));
+    let args = InvokeBody::Json(json!({}));
+    let key = cache.key("parse", "main", &args).unwrap();
+    cache.store("parse", key.clone(), &json!(1));
+
+    cache.bypass("parse");
+    assert!(cache.key("parse", "main", &args).is_none());
+    assert!(cache.get("parse", &key).is_none());
+    cache.store("parse", key, &json!(1));
+    assert_eq!(cache.len("parse"), 0);
+
+    // a policy registered by the command macro does not replace an existing one
+    cache.register("parse", CachePolicy::new(Duration::from_secs(1)));
+    assert!(cache.key("parse", "main", &args).is_none());
+  }
+
+  #[test]
+  fn cached_commands() {
+    let policy = CachePolicy::new(Duration::from_secs(60));
+    let app = mock_builder()
+      .invoke_handler(crate::generate_handler![parse, cached, label])
+      .cache_command("parse", policy)
+      .cache_command("label", policy)
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+
+    let parsed = json!("parsed a.txt");
+    assert_eq!(
+      get_ipc_json_response(
+        &webview,
+        "parse",
+        json!({ "path": "a.txt" }),
+        Default::default()
+      ),
+      Ok(parsed.clone())
+    );
+    assert_eq!(
+      get_ipc_json_response(
+        &webview,
+        "parse",
+        json!({ "path": "a.txt" }),
+        Default::default()
+      ),
+      Ok(parsed.clone())
+    );
+    assert_eq!(PARSE_CALLS.load(Ordering::SeqCst), 1);
+    get_ipc_json_response(
+      &webview,
+      "parse",
+      json!({ "path": "b.txt" }),
+      Default::default(),
+    )
+    .unwrap();
+    assert_eq!(PARSE_CALLS.load(Ordering::SeqCst), 2);
+
+    app.handle().invalidate_command_cache("parse");
+    assert_eq!(
+      get_ipc_json_response(
+        &webview,
+        "parse",
+        json!({ "path": "a.txt" }),
+        Default::default()
+      ),
+      Ok(parsed)
+    );
+    assert_eq!(PARSE_CALLS.load(Ordering::SeqCst), 3);
+
+    // the policy of `#[command(cache)]` is registered on the first invocation
+    assert_eq!(
+      get_ipc_json_response(&webview, "cached", json!({}), Default::default()),
+      Ok(json!(0))
+    );
+    assert_eq!(
+      get_ipc_json_response(&webview, "cached", json!({}), Default::default()),
+      Ok(json!(1))
+    );
+    assert_eq!(
+      get_ipc_json_response(&webview, "cached", json!({}), Default::default()),
+      Ok(json!(1))
+    );
+
+    assert_eq!(
+      get_ipc_json_response(&webview, "label", json!({}), Default::default()),
+      Ok(json!("main"))
+    );
+    assert_eq!(
+      get_ipc_json_response(&webview, "label", json!({}), Default::default()),
+      Ok(json!("main"))
+    );
+    assert_eq!(LABEL_CALLS.load(Ordering::SeqCst), 2);
+    assert_eq!(app.state::<CommandCache>().len("label"), 0);
+  }
+
+  #[test]
+  fn errors_are_never_cached() {
+    let app = mock_builder()
+      .invoke_handler(crate::generate_handler![fail])
+      .cache_command("fail", CachePolicy::new(Duration::from_secs(60)))
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+
+    for calls in 1..=3 {
+      assert_eq!(
+        get_ipc_json_response(&webview, "fail", json!({}), Default::default()),
+        Err(json!("failed"))
+      );
+      assert_eq!(FAIL_CALLS.load(Ordering::SeqCst), calls);
+    }
+    assert_eq!(app.state::<CommandCache>().len("fail"), 0);
+  }
+}
@@ -0,0 +1,277 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Cancellation of the commands whose invoke is aborted by the frontend.
+
+use std::{
+  collections::HashMap,
+  future::Future,
+  pin::Pin,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+  },
+  task::{Context, Poll, Waker},
+};
+
+use super::{CallbackFn, CommandArg, CommandItem, InvokeError};
+use crate::Runtime;
+
+/// A token that is cancelled when the frontend no longer waits for the command response.
+///
+/// The token is cancelled when the `signal` given to `invoke` is aborted,
+/// or when the webview that invoked the command navigates or is destroyed.
+/// The response of a cancelled command is never sent to the frontend.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tauri::ipc::CancellationToken;
+///
+/// #[tauri::command]
+/// async fn crunch(token: CancellationToken) -> Result<u64, String> {
+///   let mut sum = 0u64;
+///   for i in 0..1_000_000u64 {
+///     if token.is_cancelled() {
+///       return Err("cancelled".into());
+///     }
+///     sum = sum.wrapping_add(i * i);
+///   }
+///   Ok(sum)
+/// }
+/// ```
+///
+/// ```javascript
+/// const controller = new AbortController()
+/// const result = invoke('crunch', {}, { signal: controller.signal })
+/// controller.abort() // `result` rejects with the abort reason
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<TokenInner>);
+
+#[derive(Debug, Default)]
+struct TokenInner {
+  cancelled: AtomicBool,
+  wakers: Mutex<Vec<Waker>>,
+}
+
+impl CancellationToken {
+  /// Creates a token that is not cancelled.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Whether the token was cancelled.
+  pub fn is_cancelled(&self) -> bool {
+    self.0.cancelled.load(Ordering::Acquire)
+  }
+
+  /// Cancels the token, waking the [`Self::cancelled`] futures.
+  pub fn cancel(&self) {
+    self.0.cancelled.store(true, Ordering::Release);
+    for waker in self.0.wakers.lock().unwrap().drain(..) {
+      waker.wake();
+    }
+  }
+
+  /// A future that completes when the token is cancelled.
+  ///
+  /// Useful to stop waiting on other futures, e.g. with `tokio::select!`.
+  pub fn cancelled(&self) -> Cancelled<'_> {
+    Cancelled(self)
+  }
+}
+
+/// The future returned by [`CancellationToken::cancelled`].
+#[derive(Debug)]
+pub struct Cancelled<'a>(&'a CancellationToken);
+
+impl Future for Cancelled<'_> {
+  type Output = ();
+
+  fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+    let inner = &(self.0).0;
+    if inner.cancelled.load(Ordering::Acquire) {
+      return Poll::Ready(());
+    }
+    let mut wakers = inner.wakers.lock().unwrap();
+    // checked again while holding the lock so a concurrent `cancel` can't be missed
+    if inner.cancelled.load(Ordering::Acquire) {
+      return Poll::Ready(());
+    }
+    if !wakers.iter().any(|w| w.will_wake(cx.waker())) {
+      wakers.push(cx.waker().clone());
+    }
+    Poll::Pending
+  }
+}
+
+impl<'a, R: Runtime> CommandArg<'a, R> for CancellationToken {
+  /// Returns the cancellation token of the invoke.
+  fn from_command(command: CommandItem<'a, R>) -> Result<Self, InvokeError> {
+    Ok(command.message.cancellation.clone())
+  }
+}
+
+/// The tokens of the invokes that were not responded yet, keyed by webview label and callback id.
+#[derive(Default)]
+pub(crate) struct ActiveInvokes(Mutex<HashMap<(String, u32), CancellationToken>>);
+
+impl ActiveInvokes {
+  /// Creates the token of a new invoke.
+  pub(crate) fn register(&self, webview: &str, callback: CallbackFn) -> CancellationToken {
+    let token = CancellationToken::new();
+    self
+      .0
+      .lock()
+      .unwrap()
+      .insert((webview.to_string(), callback.0), token.clone());
+    token
+  }
+
+  /// Forgets the token of a responded invoke.
+  pub(crate) fn finish(&self, webview: &str, callback: CallbackFn) {
+    self
+      .0
+      .lock()
+      .unwrap()
+      .remove(&(webview.to_string(), callback.0));
+  }
+
+  /// Cancels the invoke with the given callback, if it was not responded yet.
+  pub(crate) fn cancel(&self, webview: &str, callback: CallbackFn) {
+    if let Some(token) = self
+      .0
+      .lock()
+      .unwrap()
+      .remove(&(webview.to_string(), callback.0))
+    {
+      token.cancel();
+    }
+  }
+
+  /// Cancels all the invokes of the given webview.
+  pub(crate) fn cancel_webview(&self, label: &str) {
+    self.0.lock().unwrap().retain(|(webview, _), token| {
+      if webview == label {
+        token.cancel();
+        false
+      } else {
+        true
+      }
+    });
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::Duration;
+
+  use super::{ActiveInvokes, CancellationToken};
+  use crate::{ipc::CallbackFn, Manager};
+
+  #[test]
+  fn cancels_invokes() {
+    let invokes = ActiveInvokes::default();
+    let first = invokes.register("main", CallbackFn(1));
+    let second = invokes.register("main", CallbackFn(2));
+    let other = invokes.register("other", CallbackFn(1));
+
+    invokes.cancel("main", CallbackFn(1));
+    assert!(first.is_cancelled());
+    assert!(!second.is_cancelled());
+    assert!(!other.is_cancelled());
+
+    // responded invokes are no longer cancelled
+    invokes.finish("main", CallbackFn(2));
+    invokes.cancel("main", CallbackFn(2));
+    assert!(!second.is_cancelled());
+
+    invokes.cancel_webview("other");
+    assert!(other.is_cancelled());
+    assert!(invokes.0.lock().unwrap().is_empty());
+  }
+
+  #[test]
+  fn cancelled_future_wakes() {
+    let token = CancellationToken::new();
+    let token_ = token.clone();
+    let task = crate::async_runtime::spawn(async move { token_.cancelled().await });
+    std::thread::sleep(Duration::from_millis(50));
+    token.cancel();
+    crate::async_runtime::block_on(task).unwrap();
+    crate::async_runtime::block_on(token.cancelled());
+  }
+
+  #[crate::command(root = "crate")]
+  async fn wait_for_cancellation(token: CancellationToken) -> &'static str {
+    token.cancelled().await;
+    "cancelled"
+  }
+
+  #[test]
+  fn aborted_invoke_is_not_responded() {
+    use crate::{
+      ipc::InvokeBody,
+      test::{get_ipc_response, mock_builder, mock_context, noop_assets},
+      webview::{invoke_key::INVOKE_KEY_HEADER, InvokeRequest},
+    };
+    use std::sync::mpsc::{channel, RecvTimeoutError};
+
+    let app = mock_builder()
+      .invoke_handler(crate::generate_handler![wait_for_cancellation])
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    let window = crate::WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    let webview = window.as_ref().clone();
+
+    let key = webview.invoke_key.lock().unwrap().current().to_string();
+    let mut headers = http::HeaderMap::new();
+    headers.insert(INVOKE_KEY_HEADER, key.parse().unwrap());
+
+    let (tx, rx) = channel();
+    webview.clone().on_message(
+      InvokeRequest {
+        cmd: "wait_for_cancellation".into(),
+        callback: CallbackFn(1),
+        error: CallbackFn(2),
+        body: InvokeBody::default(),
+        headers,
+      },
+      Box::new(move |_webview, _cmd, response, _meta, _callback, _error| {
+        tx.send(response).unwrap();
+      }),
+    );
+    assert!(matches!(
+      rx.recv_timeout(Duration::from_millis(100)),
+      Err(RecvTimeoutError::Timeout)
+    ));
+
+    let cancel = get_ipc_response(
+      &window,
+      InvokeRequest {
+        cmd: "plugin:__TAURI_CHANNEL__|cancel_invoke".into(),
+        callback: CallbackFn(3),
+        error: CallbackFn(4),
+        body: serde_json::json!({ "callback": 1 }).into(),
+        headers: Default::default(),
+      },
+    );
+    assert!(cancel.is_ok());
+
+    // the command completes but its response is dropped instead of sent
+    assert!(matches!(
+      rx.recv_timeout(Duration::from_secs(5)),
+      Err(RecvTimeoutError::Disconnected)
+    ));
+    assert!(webview
+      .state::<ActiveInvokes>()
+      .0
+      .lock()
+      .unwrap()
+      .is_empty());
+  }
+}
@@ -17,15 +17,22 @@ use crate::{
   command,
   ipc::{CommandArg, CommandItem},
   plugin::{Builder as PluginBuilder, TauriPlugin},
+  webview::PageLoadEvent,
   Manager, Runtime, State, Webview,
 };
 
-use super::{CallbackFn, InvokeBody, InvokeError, IpcResponse, Request, Response};
+use super::{
+  cancellation::ActiveInvokes,
+  stream::{forward, ActiveStreams, StreamEnd},
+  CallbackFn, InvokeBody, InvokeError, IpcResponse, Request, Response,
+};
 
 pub const IPC_PAYLOAD_PREFIX: &str = "__CHANNEL__:";
 pub const CHANNEL_PLUGIN_NAME: &str = "__TAURI_CHANNEL__";
 // TODO: ideally this const references CHANNEL_PLUGIN_NAME
 pub const FETCH_CHANNEL_DATA_COMMAND: &str = "plugin:__TAURI_CHANNEL__|fetch";
+/// The prefix of the internal channel commands, which are not subject to the ACL.
+pub(crate) const CHANNEL_COMMAND_PREFIX: &str = "plugin:__TAURI_CHANNEL__|";
 pub(crate) const CHANNEL_ID_HEADER_NAME: &str = "Tauri-Channel-Id";
 
 static CHANNEL_COUNTER: AtomicU32 = AtomicU32::new(0);
@@ -132,7 +139,12 @@ impl Channel {
   }
 
   pub(crate) fn from_callback_fn<R: Runtime>(webview: Webview<R>, callback: CallbackFn) -> Self {
+    // the channel only lives as long as the page that created it
+    let generation = webview.invoke_key.lock().unwrap().generation();
     Channel::new_with_id(callback.0, move |body| {
+      if webview.invoke_key.lock().unwrap().generation() != generation {
+        return Err(crate::Error::ChannelExpired);
+      }
       let data_id = CHANNEL_DATA_COUNTER.fetch_add(1, Ordering::Relaxed);
       webview
         .state::<ChannelDataIpcQueue>()
@@ -140,10 +152,13 @@ impl Channel {
         .lock()
         .unwrap()
         .insert(data_id, body);
-      webview.eval(&format!(
-        "window.__TAURI_INTERNALS__.invoke('{FETCH_CHANNEL_DATA_COMMAND}', null, {{ headers: {{ '{CHANNEL_ID_HEADER_NAME}': '{data_id}' }} }}).then(window['_' + {}]).catch(console.error)",
-        callback.0
-      ))
+      webview.eval_channel_message(
+        data_id,
+        format!(
+          "window.__TAURI_INTERNALS__.invoke('{FETCH_CHANNEL_DATA_COMMAND}', null, {{ headers: {{ '{CHANNEL_ID_HEADER_NAME}': '{data_id}' }} }}).then(window['_' + {}]).catch(console.error)",
+          callback.0
+        ),
+      )
     })
   }
 
@@ -198,8 +213,48 @@ fn fetch(
   }
 }
 
+#[command(root = "crate")]
+async fn stream<R: Runtime>(
+  webview: Webview<R>,
+  id: u64,
+  channel: Channel,
+  streams: State<'_, ActiveStreams>,
+) -> Result<StreamEnd, &'static str> {
+  forward(id, channel, webview.label().to_string(), &streams).await
+}
+
+#[command(root = "crate")]
+fn cancel_stream<R: Runtime>(webview: Webview<R>, id: u64, streams: State<'_, ActiveStreams>) {
+  streams.cancel(id, webview.label());
+}
+
+#[command(root = "crate")]
+fn cancel_invoke<R: Runtime>(
+  webview: Webview<R>,
+  callback: CallbackFn,
+  invokes: State<'_, ActiveInvokes>,
+) {
+  invokes.cancel(webview.label(), callback);
+}
+
 pub fn plugin<R: Runtime>() -> TauriPlugin<R> {
   PluginBuilder::new(CHANNEL_PLUGIN_NAME)
-    .invoke_handler(crate::generate_handler![fetch])
+    .invoke_handler(crate::generate_handler![
+      fetch,
+      stream,
+      cancel_stream,
+      cancel_invoke
+    ])
+    .on_page_load(|webview, payload| {
+      // the page that consumed the streams and waits for the invokes is gone
+      if payload.event() == PageLoadEvent::Started {
+        webview
+          .state::<ActiveStreams>()
+          .cancel_webview(webview.label());
+        webview
+          .state::<ActiveInvokes>()
+          .cancel_webview(webview.label());
+      }
+    })
     .build()
 }
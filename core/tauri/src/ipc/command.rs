@@ -49,6 +49,7 @@ pub struct CommandItem<'a, R: Runtime> {
 /// Tauri implements [`CommandArg`] automatically for a number of types.
 /// * [`crate::Window`]
 /// * [`crate::State`]
+/// * [`crate::ipc::RawBody`] and [`crate::ipc::RawBodyBuf`]
 /// * `T where T: serde::Deserialize`
 ///   * Any type that implements `Deserialize` can automatically be used as a [`CommandArg`].
 pub trait CommandArg<'de, R: Runtime>: Sized {
@@ -69,38 +70,39 @@ impl<'de, D: Deserialize<'de>, R: Runtime> CommandArg<'de, R> for D {
   }
 }
 
+impl<'de, R: Runtime> CommandItem<'de, R> {
+  /// The value of the [`CommandItem`]'s key in the JSON payload.
+  fn value(&self) -> Result<&'de serde_json::Value, serde_json::Error> {
+    if self.key.is_empty() {
+      return Err(serde_json::Error::custom(format!(
+        "command {} has an argument with no name with a non-optional value",
+        self.name
+      )));
+    }
+
+    let message: &'de InvokeMessage<R> = self.message;
+    match &message.payload {
+      InvokeBody::Raw(_body) => Err(serde_json::Error::custom(format!(
+        "command {} expected a value for key {} but the IPC call used a bytes payload",
+        self.name, self.key
+      ))),
+      InvokeBody::Json(v) => v.get(self.key).ok_or_else(|| {
+        serde_json::Error::custom(format!(
+          "command {} missing required key {}",
+          self.name, self.key
+        ))
+      }),
+    }
+  }
+}
+
 /// Pass the result of [`serde_json::Value::get`] into [`serde_json::Value`]'s deserializer.
 ///
 /// Returns an error if the [`CommandItem`]'s key does not exist in the value.
 macro_rules! pass {
   ($fn:ident, $($arg:ident: $argt:ty),+) => {
     fn $fn<V: Visitor<'de>>(self, $($arg: $argt),*) -> Result<V::Value, Self::Error> {
-      if self.key.is_empty() {
-        return Err(serde_json::Error::custom(format!(
-            "command {} has an argument with no name with a non-optional value",
-            self.name
-          )))
-      }
-
-      match &self.message.payload {
-        InvokeBody::Raw(_body) => {
-          Err(serde_json::Error::custom(format!(
-            "command {} expected a value for key {} but the IPC call used a bytes payload",
-            self.name, self.key
-          )))
-        }
-        InvokeBody::Json(v) => {
-          match v.get(self.key) {
-            Some(value) => value.$fn($($arg),*),
-            None => {
-              Err(serde_json::Error::custom(format!(
-                "command {} missing required key {}",
-                self.name, self.key
-              )))
-            }
-          }
-        }
-      }
+      self.value()?.$fn($($arg),*)
     }
   }
 }
@@ -133,6 +135,7 @@ impl<'de, R: Runtime> Deserializer<'de> for CommandItem<'de, R> {
 
   fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
     match &self.message.payload {
+      // raw payloads are only read by `RawBody` and `RawBodyBuf`
       InvokeBody::Raw(_body) => Err(serde_json::Error::custom(format!(
         "command {} expected a value for key {} but the IPC call used a bytes payload",
         self.name, self.key
@@ -183,14 +186,18 @@ impl<'de, R: Runtime> Deserializer<'de> for CommandItem<'de, R> {
 #[doc(hidden)]
 pub mod private {
   use crate::{
-    ipc::{InvokeBody, InvokeError, InvokeResolver, IpcResponse},
+    ipc::{InvokeBody, InvokeError, InvokeResolver, IpcResponse, StreamResponse},
     Runtime,
   };
-  use futures_util::{FutureExt, TryFutureExt};
+  use futures_util::{FutureExt, Stream, TryFutureExt};
   use std::future::Future;
   #[cfg(feature = "tracing")]
   pub use tracing;
 
+  pub use crate::ipc::cache::private::*;
+  #[cfg(feature = "command-manifest")]
+  pub use crate::ipc::manifest::private::*;
+
   // ===== impl IpcResponse =====
 
   pub struct ResponseTag;
@@ -274,6 +281,49 @@ pub mod private {
     }
   }
 
+  // ===== impl Stream<Item = impl IpcResponse> =====
+
+  pub struct StreamTag;
+
+  pub trait StreamKind {
+    #[inline(always)]
+    fn blocking_kind(&self) -> StreamTag {
+      StreamTag
+    }
+
+    #[inline(always)]
+    fn async_kind(&self) -> StreamTag {
+      StreamTag
+    }
+  }
+
+  impl<T: IpcResponse, S: Stream<Item = T>> StreamKind for &S {}
+
+  impl StreamTag {
+    #[inline(always)]
+    pub fn block<R, T, S>(self, value: S, resolver: InvokeResolver<R>)
+    where
+      R: Runtime,
+      T: IpcResponse,
+      S: Stream<Item = T> + Send + 'static,
+    {
+      resolver.respond(Ok(StreamResponse::new(value)))
+    }
+
+    #[inline(always)]
+    pub fn future<T, S>(self, value: S) -> impl Future<Output = Result<InvokeBody, InvokeError>>
+    where
+      T: IpcResponse,
+      S: Stream<Item = T> + Send + 'static,
+    {
+      std::future::ready(
+        StreamResponse::new(value)
+          .body()
+          .map_err(InvokeError::from_error),
+      )
+    }
+  }
+
   // ===== Future<Output = impl IpcResponse> =====
 
   pub struct FutureTag;
@@ -0,0 +1,659 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Dispatching the IPC requests of the webviews to the command handlers, see [`ScopePolicy`].
+
+use std::sync::{Arc, Mutex};
+
+use http::HeaderMap;
+use tauri_utils::acl::resolved::ResolvedCommand;
+
+use super::{
+  cache::CommandCache, cancellation::ActiveInvokes, panic, CancellationToken, Invoke, InvokeBody,
+  InvokeMessage, InvokeResolver, IpcError, Origin, OwnedInvokeResponder,
+};
+use crate::{
+  sealed::ManagerBase,
+  webview::{invoke_key, parse_plugin_command, InvokeRequest, Webview},
+  Manager, Runtime,
+};
+
+/// Decides whether the webviews can run the commands they invoke, see [`crate::Builder::scope_policy`].
+///
+/// The policy replaces the Access Control List check. The [`crate::Builder::ipc_origin_validator`],
+/// the invoke key and the [`crate::webview::WebviewBuilder::allowed_commands`] list are always checked first
+/// and cannot be overridden. The internal channel commands do not go through the policy.
+///
+/// Wrap [`DefaultScopePolicy`] to keep the Access Control List check.
+///
+/// # Examples
+///
+/// ```rust
+/// use tauri::{
+///   ipc::{DefaultScopePolicy, ScopePolicy, ScopeRequest},
+///   utils::acl::resolved::ResolvedCommand,
+///   Runtime,
+/// };
+///
+/// /// Requires a capability token for the commands of the `admin` plugin.
+/// struct TokenPolicy;
+///
+/// impl<R: Runtime> ScopePolicy<R> for TokenPolicy {
+///   fn check(&self, request: &ScopeRequest<'_, R>) -> Result<Option<ResolvedCommand>, String> {
+///     if request.plugin() == Some("admin") && request.headers().get("X-Capability-Token").is_none() {
+///       return Err(format!("Command {} requires a capability token", request.command()));
+///     }
+///     DefaultScopePolicy.check(request)
+///   }
+/// }
+///
+/// tauri::Builder::default().scope_policy(TokenPolicy);
+/// ```
+pub trait ScopePolicy<R: Runtime>: Send + Sync + 'static {
+  /// Returns the resolved ACL passed to the command as [`Invoke::acl`],
+  /// or the message the request is rejected with.
+  fn check(&self, request: &ScopeRequest<'_, R>) -> Result<Option<ResolvedCommand>, String>;
+}
+
+/// A command checked by a [`ScopePolicy`].
+pub struct ScopeRequest<'a, R: Runtime> {
+  webview: &'a Webview<R>,
+  command: &'a str,
+  plugin: Option<(&'a str, &'a str)>,
+  origin: &'a Origin,
+  headers: &'a HeaderMap,
+}
+
+impl<'a, R: Runtime> ScopeRequest<'a, R> {
+  /// The webview that invoked the command.
+  pub fn webview(&self) -> &Webview<R> {
+    self.webview
+  }
+
+  /// The invoked command, `plugin:<name>|<command>` for plugin commands.
+  pub fn command(&self) -> &str {
+    self.command
+  }
+
+  /// The plugin name of a plugin command.
+  pub fn plugin(&self) -> Option<&str> {
+    self.plugin.map(|(plugin, _)| plugin)
+  }
+
+  /// The command name, without the plugin prefix for plugin commands.
+  pub fn command_name(&self) -> &str {
+    self.plugin.map_or(self.command, |(_, name)| name)
+  }
+
+  /// The origin of the page that invoked the command.
+  pub fn origin(&self) -> &Origin {
+    self.origin
+  }
+
+  /// The request headers.
+  pub fn headers(&self) -> &HeaderMap {
+    self.headers
+  }
+}
+
+/// The default [`ScopePolicy`], checking the plugin commands against the Access Control List.
+///
+/// The app commands are allowed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultScopePolicy;
+
+impl<R: Runtime> ScopePolicy<R> for DefaultScopePolicy {
+  fn check(&self, request: &ScopeRequest<'_, R>) -> Result<Option<ResolvedCommand>, String> {
+    let authority = &request.webview.manager().runtime_authority;
+    let label = request.webview.label();
+    let resolved_acl = authority
+      .resolve_access(request.command, label, request.origin)
+      .cloned();
+
+    if resolved_acl.is_none() {
+      #[cfg_attr(not(debug_assertions), allow(unused_variables))]
+      if let Some((plugin, command_name)) = request.plugin {
+        #[cfg(debug_assertions)]
+        return Err(authority.resolve_access_message(plugin, command_name, label, request.origin));
+        #[cfg(not(debug_assertions))]
+        return Err(format!("Command {} not allowed by ACL", request.command));
+      }
+    }
+    Ok(resolved_acl)
+  }
+}
+
+/// Runs the commands that passed the checks of the [`IpcDispatcher`].
+pub(crate) trait CommandRouter<R: Runtime>: Send + Sync + 'static {
+  /// Runs a plugin command or an app command without `plugin`, returning whether a handler accepted it.
+  ///
+  /// The router may reject the invoke itself and still return `true`.
+  fn route(&self, plugin: Option<&str>, invoke: Invoke<R>) -> bool;
+}
+
+/// Runs the plugin commands on the plugins and the app commands on the [`crate::Builder::invoke_handler`].
+pub(crate) struct DefaultCommandRouter;
+
+impl<R: Runtime> CommandRouter<R> for DefaultCommandRouter {
+  fn route(&self, plugin: Option<&str>, invoke: Invoke<R>) -> bool {
+    let manager = invoke.message.webview.manager_owned();
+    let Some(plugin) = plugin else {
+      return manager.run_invoke_handler(invoke);
+    };
+
+    // the message is only needed to forward the command to a mobile plugin
+    #[cfg(mobile)]
+    let message = crate::plugin::mobile::is_registered(plugin).then(|| invoke.message.clone());
+    #[cfg(mobile)]
+    let resolver = invoke.resolver.clone();
+
+    #[allow(unused_mut)]
+    let mut handled = manager.extend_api(plugin, invoke);
+
+    #[cfg(mobile)]
+    if let (false, Some(message)) = (handled, message) {
+      handled = true;
+
+      fn load_channels<R: Runtime>(payload: &serde_json::Value, webview: &Webview<R>) {
+        use std::str::FromStr;
+
+        if let serde_json::Value::Object(map) = payload {
+          for v in map.values() {
+            if let serde_json::Value::String(s) = v {
+              let _ = crate::ipc::JavaScriptChannelId::from_str(s)
+                .map(|id| id.channel_on(webview.clone()));
+            }
+          }
+        }
+      }
+
+      let app_handle = message.webview.app_handle().clone();
+      let payload = message.payload.into_json();
+      // initialize channels
+      load_channels(&payload, &message.webview);
+
+      let resolver_ = resolver.clone();
+      if let Err(e) = crate::plugin::mobile::run_command(
+        plugin,
+        &app_handle,
+        &*message.command,
+        payload,
+        move |response| match response {
+          Ok(r) => resolver_.resolve(r),
+          Err(e) => resolver_.reject(e),
+        },
+      ) {
+        resolver.reject(e.to_string());
+      }
+    }
+
+    handled
+  }
+}
+
+/// Checks the IPC requests of the webviews and routes them to the command handlers.
+pub(crate) struct IpcDispatcher<R: Runtime> {
+  policy: Box<dyn ScopePolicy<R>>,
+  router: Box<dyn CommandRouter<R>>,
+}
+
+impl<R: Runtime> Default for IpcDispatcher<R> {
+  fn default() -> Self {
+    Self::new(Box::new(DefaultScopePolicy), Box::new(DefaultCommandRouter))
+  }
+}
+
+impl<R: Runtime> IpcDispatcher<R> {
+  pub(crate) fn new(policy: Box<dyn ScopePolicy<R>>, router: Box<dyn CommandRouter<R>>) -> Self {
+    Self { policy, router }
+  }
+
+  /// Handles the `webview` receiving an [`InvokeRequest`].
+  pub(crate) fn dispatch(
+    &self,
+    webview: Webview<R>,
+    request: InvokeRequest,
+    responder: Box<OwnedInvokeResponder<R>>,
+  ) {
+    let manager = webview.manager_owned();
+    // a destroyed webview has no URL, its requests are treated as remote
+    let current_url = webview.url().ok();
+    let is_local = current_url
+      .as_ref()
+      .is_some_and(|url| webview.is_local_url(url));
+
+    let custom_responder = manager.webview.invoke_responder.clone();
+
+    let InvokeRequest {
+      cmd,
+      callback,
+      error,
+      body,
+      headers,
+    } = request;
+    // shared by the resolver and the message so the command name is only allocated once
+    let cmd: Arc<str> = cmd.into();
+
+    #[cfg(feature = "tracing")]
+    let (span, request_id) = if manager.webview.trace_ipc {
+      static REQUEST_ID: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+      let request_id = REQUEST_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+      let span = tracing::debug_span!(
+        "ipc::invoke",
+        cmd = &*cmd,
+        webview = webview.label(),
+        request_id,
+        result = tracing::field::Empty,
+      );
+      (span, Some(request_id))
+    } else {
+      (tracing::Span::none(), None)
+    };
+    #[cfg(feature = "tracing")]
+    let _enter = span.enter();
+    #[cfg(feature = "tracing")]
+    let span_ = span.clone();
+
+    let cancellation = match webview.try_state::<ActiveInvokes>() {
+      Some(invokes) => invokes.register(webview.label(), callback),
+      None => CancellationToken::new(),
+    };
+    let cancellation_ = cancellation.clone();
+
+    let cache_key = webview
+      .try_state::<CommandCache>()
+      .and_then(|cache| cache.key(&cmd, webview.label(), &body));
+    let cache_key_ = cache_key.clone();
+
+    #[cfg(all(debug_assertions, feature = "ipc-inspector"))]
+    let inspected =
+      crate::ipc::inspector::start_invoke(&manager.ipc_inspector, webview.label(), &body);
+
+    let resolver = InvokeResolver::new(
+      webview.clone(),
+      Arc::new(Mutex::new(Some(Box::new(
+        #[allow(unused_variables, unused_mut)]
+        move |webview: Webview<R>,
+              cmd: Arc<str>,
+              response,
+              mut meta: crate::ipc::ResponseMeta,
+              callback,
+              error| {
+          #[cfg(feature = "tracing")]
+          let _span = request_id.map(|request_id| {
+            span_.record(
+              "result",
+              if matches!(response, crate::ipc::InvokeResponse::Ok(_)) {
+                "ok"
+              } else {
+                "error"
+              },
+            );
+            meta.headers.insert(
+              crate::ipc::IPC_REQUEST_ID_HEADER,
+              http::HeaderValue::from(request_id),
+            );
+            tracing::debug_span!(parent: &span_, "ipc::invoke::respond").entered()
+          });
+
+          if let Some(invokes) = webview.try_state::<ActiveInvokes>() {
+            invokes.finish(webview.label(), callback);
+          }
+          #[cfg(all(debug_assertions, feature = "ipc-inspector"))]
+          if let Some(inspected) = inspected {
+            webview.manager().ipc_inspector.record_invoke(
+              webview.label(),
+              &cmd,
+              inspected,
+              &response,
+            );
+          }
+          if let (Some(key), crate::ipc::InvokeResponse::Ok(InvokeBody::Json(value))) =
+            (cache_key_, &response)
+          {
+            if let Some(cache) = webview.try_state::<CommandCache>() {
+              cache.store(&cmd, key, value);
+            }
+          }
+          // the frontend no longer waits for the response
+          if cancellation_.is_cancelled() {
+            return;
+          }
+
+          if let Some(responder) = &custom_responder {
+            (responder)(&webview, &cmd, &response, &meta, callback, error);
+          }
+
+          responder(webview, cmd, response, meta, callback, error);
+        },
+      )))),
+      cmd.clone(),
+      callback,
+      error,
+    );
+
+    if let Some(validator) = &manager.webview.ipc_origin_validator {
+      if let Err(message) = validator(&headers, &webview) {
+        resolver.reject(IpcError::OriginDenied { message });
+        return;
+      }
+    }
+
+    let key = headers
+      .get(invoke_key::INVOKE_KEY_HEADER)
+      .and_then(|key| key.to_str().ok());
+
+    // the handshake is authenticated by the bootstrap secret of the initialization script
+    if &*cmd == invoke_key::INVOKE_HANDSHAKE_COMMAND {
+      let key = webview
+        .invoke_key
+        .lock()
+        .unwrap()
+        .handshake(key)
+        .map(ToString::to_string);
+      match key {
+        Some(key) => resolver.resolve(key),
+        None => resolver.reject(IpcError::InvalidInvokeKey),
+      }
+      return;
+    }
+
+    if !webview.invoke_key.lock().unwrap().validate(key) {
+      resolver.reject(IpcError::InvalidInvokeKey);
+      return;
+    }
+
+    // internal command that never reaches the user command handlers
+    if let Some(handler) = Webview::internal_command(&cmd) {
+      match handler(&webview, body) {
+        Ok(()) => resolver.resolve(()),
+        Err(e) => resolver.reject(e),
+      }
+      return;
+    }
+
+    let is_channel_command = cmd.starts_with(crate::ipc::channel::CHANNEL_COMMAND_PREFIX);
+
+    if !is_channel_command && !webview.is_command_allowed(&cmd) {
+      resolver.reject(IpcError::CommandDenied {
+        command: cmd.to_string(),
+      });
+      return;
+    }
+
+    let origin = if is_local {
+      Origin::Local
+    } else {
+      Origin::Remote {
+        domain: current_url
+          .as_ref()
+          .and_then(|url| url.domain())
+          .map(|d| d.to_string())
+          .unwrap_or_default(),
+      }
+    };
+    let plugin_command = parse_plugin_command(&cmd);
+
+    let resolved_acl = if is_channel_command {
+      manager
+        .runtime_authority
+        .resolve_access(&cmd, webview.label(), &origin)
+        .cloned()
+    } else {
+      let request = ScopeRequest {
+        webview: &webview,
+        command: &cmd,
+        plugin: plugin_command,
+        origin: &origin,
+        headers: &headers,
+      };
+      match self.policy.check(&request) {
+        Ok(resolved_acl) => resolved_acl,
+        Err(message) => {
+          resolver.reject(message);
+          return;
+        }
+      }
+    };
+
+    // the command is allowed, respond with the cached response if any
+    let cached = cache_key
+      .as_ref()
+      .and_then(|key| webview.try_state::<CommandCache>()?.get(&cmd, key));
+    if let Some(cached) = cached {
+      resolver.resolve(cached);
+      return;
+    }
+
+    let (plugin, command) = match plugin_command {
+      Some((plugin, command_name)) => (Some(plugin), Arc::from(command_name)),
+      None => (None, cmd.clone()),
+    };
+
+    let invoke = Invoke {
+      message: InvokeMessage::new(
+        webview,
+        manager.state(),
+        command.clone(),
+        body,
+        headers,
+        cancellation,
+      ),
+      resolver: resolver.clone(),
+      acl: resolved_acl,
+    };
+
+    // a panicking command would otherwise unwind into the runtime and leave the frontend waiting
+    match panic::catch(|| self.router.route(plugin, invoke)) {
+      Ok(true) => (),
+      Ok(false) => resolver.reject(format!("Command {command} not found")),
+      Err(report) => {
+        let error = panic::on_panic(&resolver.webview, &cmd, report);
+        resolver.invoke_error(error);
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, Mutex};
+
+  use serde_json::{json, Value as JsonValue};
+  use tauri_utils::acl::resolved::ResolvedCommand;
+
+  use super::{CommandRouter, DefaultScopePolicy, IpcDispatcher, ScopePolicy, ScopeRequest};
+  use crate::{
+    ipc::{AllowList, CallbackFn, Invoke, InvokeResponse},
+    test::{mock_app, mock_builder, mock_context, noop_assets, MockRuntime},
+    webview::{invoke_key::INVOKE_KEY_HEADER, InvokeRequest},
+    WebviewWindow, WebviewWindowBuilder,
+  };
+
+  type Routed = Arc<Mutex<Vec<(Option<String>, String)>>>;
+
+  /// Records the routed commands and resolves them, or leaves them unhandled.
+  struct RecordingRouter {
+    routed: Routed,
+    handles: bool,
+  }
+
+  impl CommandRouter<MockRuntime> for RecordingRouter {
+    fn route(&self, plugin: Option<&str>, invoke: Invoke<MockRuntime>) -> bool {
+      self.routed.lock().unwrap().push((
+        plugin.map(ToString::to_string),
+        invoke.message.command().to_string(),
+      ));
+      if self.handles {
+        invoke.resolver.resolve("routed");
+      }
+      self.handles
+    }
+  }
+
+  /// Allows the plugin commands the default policy denies, unless the `x-deny` header is set.
+  struct HeaderPolicy;
+
+  impl ScopePolicy<MockRuntime> for HeaderPolicy {
+    fn check(
+      &self,
+      request: &ScopeRequest<'_, MockRuntime>,
+    ) -> Result<Option<ResolvedCommand>, String> {
+      match request.headers().get("x-deny") {
+        Some(_) => Err(format!("{} denied by header", request.command_name())),
+        None => Ok(None),
+      }
+    }
+  }
+
+  fn dispatcher(
+    policy: impl ScopePolicy<MockRuntime>,
+    handles: bool,
+  ) -> (IpcDispatcher<MockRuntime>, Routed) {
+    let routed = Routed::default();
+    let router = RecordingRouter {
+      routed: routed.clone(),
+      handles,
+    };
+    (
+      IpcDispatcher::new(Box::new(policy), Box::new(router)),
+      routed,
+    )
+  }
+
+  fn dispatch(
+    dispatcher: &IpcDispatcher<MockRuntime>,
+    webview: &WebviewWindow<MockRuntime>,
+    cmd: &str,
+    headers: &[(&'static str, &str)],
+  ) -> Result<JsonValue, JsonValue> {
+    let mut request = InvokeRequest {
+      cmd: cmd.into(),
+      callback: CallbackFn(0),
+      error: CallbackFn(1),
+      body: json!({}).into(),
+      headers: Default::default(),
+    };
+    for (name, value) in headers {
+      request.headers.insert(*name, value.parse().unwrap());
+    }
+    if !request.headers.contains_key(INVOKE_KEY_HEADER) {
+      let key = webview
+        .webview
+        .invoke_key
+        .lock()
+        .unwrap()
+        .current()
+        .to_string();
+      request
+        .headers
+        .insert(INVOKE_KEY_HEADER, key.parse().unwrap());
+    }
+
+    let (tx, rx) = std::sync::mpsc::sync_channel(1);
+    dispatcher.dispatch(
+      webview.webview.clone(),
+      request,
+      Box::new(move |_webview, _cmd, response, _meta, _callback, _error| {
+        tx.send(response).unwrap();
+      }),
+    );
+    match rx.recv().unwrap() {
+      InvokeResponse::Ok(body) => Ok(body.into_json()),
+      InvokeResponse::Err(e) => Err(e.0),
+    }
+  }
+
+  #[test]
+  fn checks_before_policy() {
+    let (dispatcher, routed) = dispatcher(HeaderPolicy, true);
+
+    let app = mock_builder()
+      .ipc_origin_validator(|_headers, _webview| Err("unexpected origin".into()))
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    let main = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    assert_eq!(
+      dispatch(&dispatcher, &main, "greet", &[]),
+      Err(json!({ "kind": "OriginDenied", "message": "unexpected origin" }))
+    );
+
+    let app = mock_app();
+    let main = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .allowed_commands(AllowList::new().allow("greet"))
+      .build()
+      .unwrap();
+    assert_eq!(
+      dispatch(
+        &dispatcher,
+        &main,
+        "greet",
+        &[(INVOKE_KEY_HEADER, "invalid")]
+      ),
+      Err(json!({ "kind": "InvalidInvokeKey" }))
+    );
+    assert_eq!(
+      dispatch(&dispatcher, &main, "delete", &[]),
+      Err(json!({ "kind": "CommandDenied", "command": "delete" }))
+    );
+    // internal commands never reach the router
+    assert_eq!(
+      dispatch(&dispatcher, &main, "__TAURI_CONSOLE_MESSAGE__", &[]),
+      Err(json!("the console message hook is not installed"))
+    );
+
+    assert!(routed.lock().unwrap().is_empty());
+    assert_eq!(
+      dispatch(&dispatcher, &main, "greet", &[]),
+      Ok(json!("routed"))
+    );
+  }
+
+  #[test]
+  fn default_policy() {
+    let (dispatcher, routed) = dispatcher(DefaultScopePolicy, false);
+    let app = mock_app();
+    let main = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+
+    // no capability allows the plugin command
+    assert!(matches!(
+      dispatch(&dispatcher, &main, "plugin:fs|read", &[]),
+      Err(JsonValue::String(_))
+    ));
+    assert!(routed.lock().unwrap().is_empty());
+
+    // the app commands are allowed
+    assert_eq!(
+      dispatch(&dispatcher, &main, "greet", &[]),
+      Err(json!("Command greet not found"))
+    );
+    assert_eq!(*routed.lock().unwrap(), [(None, "greet".to_string())]);
+  }
+
+  #[test]
+  fn custom_policy() {
+    let (dispatcher, routed) = dispatcher(HeaderPolicy, true);
+    let app = mock_app();
+    let main = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+
+    assert_eq!(
+      dispatch(&dispatcher, &main, "plugin:fs|read", &[]),
+      Ok(json!("routed"))
+    );
+    assert_eq!(
+      dispatch(&dispatcher, &main, "plugin:fs|write", &[("x-deny", "1")]),
+      Err(json!("write denied by header"))
+    );
+    assert_eq!(
+      *routed.lock().unwrap(),
+      [(Some("fs".to_string()), "read".to_string())]
+    );
+  }
+}
@@ -6,7 +6,7 @@ use serde::Serialize;
 use serde_json::value::RawValue;
 use serialize_to_javascript::Serialized;
 
-use super::CallbackFn;
+use super::{CallbackFn, ResponseMeta};
 
 /// The information about this is quite limited. On Chrome/Edge and Firefox, [the maximum string size is approximately 1 GB](https://stackoverflow.com/a/34958490).
 ///
@@ -85,16 +85,35 @@ fn serialize_js_with<T: Serialize, F: FnOnce(&str) -> String>(
 /// See [json-parse-benchmark](https://github.com/GoogleChromeLabs/json-parse-benchmark).
 pub fn format<T: Serialize>(function_name: CallbackFn, arg: &T) -> crate::Result<String> {
   serialize_js_with(arg, Default::default(), |arg| {
-    format!(
-      r#"
+    format_call(function_name, arg)
+  })
+}
+
+/// Formats a function name and argument to be evaluated as callback,
+/// passing the [`ResponseMeta`] as the second argument of the callback.
+///
+/// The argument is serialized like [`format`] does.
+pub fn format_with_meta<T: Serialize>(
+  function_name: CallbackFn,
+  arg: &T,
+  meta: &ResponseMeta,
+) -> crate::Result<String> {
+  let meta = serialize_js_with(meta, Default::default(), |meta| meta.into())?;
+  serialize_js_with(arg, Default::default(), |arg| {
+    format_call(function_name, &format!("{arg}, {meta}"))
+  })
+}
+
+fn format_call(function_name: CallbackFn, args: &str) -> String {
+  format!(
+    r#"
     if (window["_{fn}"]) {{
-      window["_{fn}"]({arg})
+      window["_{fn}"]({args})
     }} else {{
       console.warn("[TAURI] Couldn't find callback id {fn} in window. This happens when the app is reloaded while Rust is running an asynchronous operation.")
     }}"#,
-      fn = function_name.0
-    )
-  })
+    fn = function_name.0
+  )
 }
 
 /// Formats a Result type to its Promise response.
@@ -213,4 +232,25 @@ mod test {
       serde_json::Value::String(value),
     ))
   }
+
+  #[test]
+  fn format_meta() {
+    let mut meta = ResponseMeta {
+      status: Some(201),
+      ..Default::default()
+    };
+    meta.headers.insert("x-trace-id", "abc".parse().unwrap());
+    meta
+      .headers
+      .append("cache-control", "no-store".parse().unwrap());
+    meta
+      .headers
+      .append("cache-control", "private".parse().unwrap());
+
+    assert!(format_with_meta(CallbackFn(3), &"value", &meta)
+      .unwrap()
+      .contains(
+        r#"window["_3"]("value", {"headers":{"cache-control":"no-store, private","x-trace-id":"abc"},"status":201})"#
+      ));
+  }
 }
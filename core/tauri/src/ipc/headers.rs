@@ -0,0 +1,281 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Command arguments reading the IPC request headers.
+
+use std::{ops::Deref, sync::Arc};
+
+use http::{
+  header::{HeaderName, CONTENT_TYPE},
+  HeaderMap, HeaderValue,
+};
+
+use super::{CommandArg, CommandItem, InvokeError};
+use crate::Runtime;
+
+/// The headers of the IPC request, e.g. set with the `headers` option of the JavaScript `invoke` function.
+///
+/// The headers are shared with the request instead of being cloned.
+///
+/// # Examples
+///
+/// ```rust
+/// use tauri::ipc::Headers;
+///
+/// #[tauri::command]
+/// fn traced(headers: Headers) -> Option<String> {
+///   headers
+///     .get("X-Trace-Id")
+///     .and_then(|value| value.to_str().ok())
+///     .map(ToString::to_string)
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Headers(Arc<HeaderMap>);
+
+impl Deref for Headers {
+  type Target = HeaderMap;
+
+  fn deref(&self) -> &Self::Target {
+    &self.0
+  }
+}
+
+impl AsRef<HeaderMap> for Headers {
+  fn as_ref(&self) -> &HeaderMap {
+    &self.0
+  }
+}
+
+impl<'a, R: Runtime> CommandArg<'a, R> for Headers {
+  /// Returns the headers of the invoke request.
+  fn from_command(command: CommandItem<'a, R>) -> Result<Self, InvokeError> {
+    Ok(Self(command.message.headers.clone()))
+  }
+}
+
+/// A header decoded with [`TypedHeader`].
+pub trait Header: Sized {
+  /// The header name.
+  fn name() -> HeaderName;
+
+  /// Decodes the header value, returning a description of the error if it is not valid.
+  fn decode(value: &HeaderValue) -> Result<Self, String>;
+
+  /// The value used when the header is missing. Defaults to `None`, which fails the command.
+  fn missing() -> Option<Self> {
+    None
+  }
+}
+
+/// An optional header, `None` if it is missing.
+impl<T: Header> Header for Option<T> {
+  fn name() -> HeaderName {
+    T::name()
+  }
+
+  fn decode(value: &HeaderValue) -> Result<Self, String> {
+    T::decode(value).map(Some)
+  }
+
+  fn missing() -> Option<Self> {
+    Some(None)
+  }
+}
+
+/// Decodes a header value as UTF-8, so the values the frontend sets with non-ASCII characters are readable.
+fn decode_str(value: &HeaderValue) -> Result<&str, String> {
+  std::str::from_utf8(value.as_bytes()).map_err(|_| "the value is not valid UTF-8".into())
+}
+
+/// The `Content-Type` header of the request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentType(pub String);
+
+impl Header for ContentType {
+  fn name() -> HeaderName {
+    CONTENT_TYPE
+  }
+
+  fn decode(value: &HeaderValue) -> Result<Self, String> {
+    decode_str(value).map(|value| Self(value.into()))
+  }
+}
+
+/// A header of the IPC request decoded with its [`Header`] implementation.
+///
+/// The command fails if the header is missing or invalid.
+/// Use `TypedHeader<Option<T>>` for an optional header, which still fails if the header is invalid.
+///
+/// # Examples
+///
+/// ```rust
+/// use tauri::ipc::{Header, TypedHeader};
+/// use tauri::http::{HeaderName, HeaderValue};
+///
+/// struct Locale(String);
+///
+/// impl Header for Locale {
+///   fn name() -> HeaderName {
+///     HeaderName::from_static("x-locale")
+///   }
+///
+///   fn decode(value: &HeaderValue) -> Result<Self, String> {
+///     value
+///       .to_str()
+///       .map(|locale| Self(locale.into()))
+///       .map_err(|e| e.to_string())
+///   }
+/// }
+///
+/// #[tauri::command]
+/// fn greet(TypedHeader(locale): TypedHeader<Locale>) -> &'static str {
+///   if locale.0.starts_with("fr") {
+///     "Bonjour"
+///   } else {
+///     "Hello"
+///   }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypedHeader<T>(pub T);
+
+impl<'a, R: Runtime, T: Header> CommandArg<'a, R> for TypedHeader<T> {
+  /// Decodes the header, failing if it is invalid or missing and required.
+  fn from_command(command: CommandItem<'a, R>) -> Result<Self, InvokeError> {
+    let name = T::name();
+    match command.message.headers().get(&name) {
+      Some(value) => T::decode(value)
+        .map(Self)
+        .map_err(|e| format!("invalid `{name}` header: {e}").into()),
+      None => T::missing()
+        .map(Self)
+        .ok_or_else(|| format!("missing `{name}` header").into()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use http::{HeaderMap, HeaderName, HeaderValue};
+  use serde_json::{json, Value as JsonValue};
+
+  use super::{ContentType, Header, Headers, TypedHeader};
+  use crate::{
+    test::{get_ipc_json_response, mock_builder, mock_context, noop_assets},
+    WebviewWindowBuilder,
+  };
+
+  struct Locale(String);
+
+  impl Header for Locale {
+    fn name() -> HeaderName {
+      HeaderName::from_static("x-locale")
+    }
+
+    fn decode(value: &HeaderValue) -> Result<Self, String> {
+      super::decode_str(value).map(|locale| Self(locale.into()))
+    }
+  }
+
+  #[crate::command(root = "crate")]
+  fn trace_id(headers: Headers) -> Option<String> {
+    headers
+      .get("x-trace-id")
+      .and_then(|value| value.to_str().ok())
+      .map(ToString::to_string)
+  }
+
+  #[crate::command(root = "crate")]
+  fn locale(TypedHeader(locale): TypedHeader<Locale>) -> String {
+    locale.0
+  }
+
+  fn header_map(headers: &[(&'static str, &[u8])]) -> HeaderMap {
+    let mut header_map = HeaderMap::new();
+    for (name, value) in headers {
+      header_map.insert(*name, HeaderValue::from_bytes(value).unwrap());
+    }
+    header_map
+  }
+
+  #[crate::command(root = "crate")]
+  fn content_type(TypedHeader(content_type): TypedHeader<Option<ContentType>>) -> Option<String> {
+    content_type.map(|ContentType(content_type)| content_type)
+  }
+
+  #[test]
+  fn header_extractors() {
+    let app = mock_builder()
+      .invoke_handler(crate::generate_handler![trace_id, locale, content_type])
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+
+    assert_eq!(
+      get_ipc_json_response(
+        &webview,
+        "trace_id",
+        json!({}),
+        header_map(&[("x-trace-id", "abc".as_bytes())])
+      ),
+      Ok(json!("abc"))
+    );
+    assert_eq!(
+      get_ipc_json_response(&webview, "trace_id", json!({}), header_map(&[])),
+      Ok(JsonValue::Null)
+    );
+
+    assert_eq!(
+      get_ipc_json_response(
+        &webview,
+        "locale",
+        json!({}),
+        header_map(&[("x-locale", "fr-FR".as_bytes())])
+      ),
+      Ok(json!("fr-FR"))
+    );
+    // the UTF-8 values set by the frontend are decoded
+    assert_eq!(
+      get_ipc_json_response(
+        &webview,
+        "locale",
+        json!({}),
+        header_map(&[("x-locale", "français".as_bytes())])
+      ),
+      Ok(json!("français"))
+    );
+    assert_eq!(
+      get_ipc_json_response(&webview, "locale", json!({}), header_map(&[])),
+      Err(json!("missing `x-locale` header"))
+    );
+    assert_eq!(
+      get_ipc_json_response(
+        &webview,
+        "locale",
+        json!({}),
+        header_map(&[("x-locale", b"fran\xe7ais".as_slice())])
+      ),
+      Err(json!(
+        "invalid `x-locale` header: the value is not valid UTF-8"
+      ))
+    );
+
+    assert_eq!(
+      get_ipc_json_response(
+        &webview,
+        "content_type",
+        json!({}),
+        header_map(&[("content-type", "text/plain".as_bytes())])
+      ),
+      Ok(json!("text/plain"))
+    );
+    assert_eq!(
+      get_ipc_json_response(&webview, "content_type", json!({}), header_map(&[])),
+      Ok(JsonValue::Null)
+    );
+  }
+}
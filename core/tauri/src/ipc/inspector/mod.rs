@@ -0,0 +1,395 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The IPC inspector window, see [`AppHandle::open_ipc_inspector`].
+
+use std::{
+  borrow::Cow,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+  },
+  time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+
+use super::{limits, InvokeBody, InvokeResponse};
+use crate::{
+  event::EmitArgs, manager::webview::UriSchemeProtocol, sealed::ManagerBase, AppHandle, Manager,
+  Runtime, Webview, WebviewUrl, WebviewWindow, WebviewWindowBuilder, WindowEvent,
+};
+
+/// The label of the IPC inspector window.
+pub const IPC_INSPECTOR_LABEL: &str = "tauri-ipc-inspector";
+/// The URI scheme serving the inspector page.
+const SCHEME: &str = "ipc-inspector";
+/// The internal command the inspector page pauses and resumes the mirroring with.
+pub(crate) const INSPECTOR_COMMAND: &str = "__TAURI_IPC_INSPECTOR__";
+/// The maximum number of characters of the payload previews.
+const PREVIEW_LENGTH: usize = 256;
+
+const INDEX: &str = include_str!("index.html");
+const INIT_SCRIPT: &str = r#"
+  Object.defineProperty(window, '__TAURI_IPC_INSPECTOR__', {
+    value: { pending: [], push: function (records) { this.pending.push.apply(this.pending, records) } }
+  })
+"#;
+
+/// A mirrored invoke or event.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub(crate) enum Record {
+  #[serde(rename_all = "camelCase")]
+  Invoke {
+    timestamp: u128,
+    webview: String,
+    command: String,
+    args_size: usize,
+    duration_ms: f64,
+    ok: bool,
+    preview: String,
+  },
+  #[serde(rename_all = "camelCase")]
+  Event {
+    timestamp: u128,
+    event: String,
+    target: Option<String>,
+    payload_size: usize,
+    preview: String,
+  },
+}
+
+/// Mirrors the IPC traffic to the inspector window while it is open.
+#[derive(Default)]
+pub(crate) struct IpcInspector {
+  sender: Mutex<Option<UnboundedSender<Record>>>,
+  /// Whether the inspector is open and not paused, checked before collecting anything.
+  recording: AtomicBool,
+  paused: AtomicBool,
+}
+
+impl IpcInspector {
+  /// Whether the traffic of the given webview is mirrored, the inspector's own traffic never is.
+  pub(crate) fn is_recording(&self, webview: &str) -> bool {
+    self.recording.load(Ordering::Relaxed) && webview != IPC_INSPECTOR_LABEL
+  }
+
+  fn start(&self) -> tokio::sync::mpsc::UnboundedReceiver<Record> {
+    let (tx, rx) = unbounded_channel();
+    self.sender.lock().unwrap().replace(tx);
+    self.paused.store(false, Ordering::Relaxed);
+    self.recording.store(true, Ordering::Relaxed);
+    rx
+  }
+
+  fn stop(&self) {
+    self.recording.store(false, Ordering::Relaxed);
+    self.sender.lock().unwrap().take();
+  }
+
+  fn set_paused(&self, paused: bool) {
+    self.paused.store(paused, Ordering::Relaxed);
+    let open = self.sender.lock().unwrap().is_some();
+    self.recording.store(open && !paused, Ordering::Relaxed);
+  }
+
+  fn send(&self, record: Record) {
+    if let Some(sender) = &*self.sender.lock().unwrap() {
+      let _ = sender.send(record);
+    }
+  }
+
+  /// Mirrors a command response, `started` being the time and body size of the request.
+  pub(crate) fn record_invoke(
+    &self,
+    webview: &str,
+    command: &str,
+    (started, args_size): (Instant, usize),
+    response: &InvokeResponse,
+  ) {
+    if !self.is_recording(webview) {
+      return;
+    }
+    let (ok, preview) = match response {
+      InvokeResponse::Ok(InvokeBody::Json(value)) => (true, preview(value.to_string())),
+      InvokeResponse::Ok(InvokeBody::Raw(bytes)) => (true, format!("<{} bytes>", bytes.len())),
+      InvokeResponse::Err(error) => (false, preview(error.0.to_string())),
+    };
+    self.send(Record::Invoke {
+      timestamp: timestamp(),
+      webview: webview.into(),
+      command: command.into(),
+      args_size,
+      duration_ms: started.elapsed().as_secs_f64() * 1000.,
+      ok,
+      preview,
+    });
+  }
+
+  /// Mirrors an emitted event.
+  pub(crate) fn record_event(&self, emit_args: &EmitArgs) {
+    if !self.recording.load(Ordering::Relaxed)
+      || emit_args.source.as_deref() == Some(IPC_INSPECTOR_LABEL)
+    {
+      return;
+    }
+    self.send(Record::Event {
+      timestamp: timestamp(),
+      event: emit_args.event_name.clone(),
+      target: emit_args
+        .target
+        .as_ref()
+        .map(|target| format!("{target:?}")),
+      payload_size: emit_args.payload.len(),
+      preview: preview(emit_args.payload.clone()),
+    });
+  }
+}
+
+/// The time and body size of a request, when its webview is recorded.
+pub(crate) fn start_invoke(
+  inspector: &IpcInspector,
+  webview: &str,
+  body: &InvokeBody,
+) -> Option<(Instant, usize)> {
+  inspector
+    .is_recording(webview)
+    .then(|| (Instant::now(), limits::body_size(body)))
+}
+
+fn timestamp() -> u128 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_millis())
+    .unwrap_or_default()
+}
+
+/// Truncates a payload to [`PREVIEW_LENGTH`] characters.
+fn preview(mut payload: String) -> String {
+  if let Some((index, _)) = payload.char_indices().nth(PREVIEW_LENGTH) {
+    payload.truncate(index);
+    payload.push('…');
+  }
+  payload
+}
+
+#[derive(Deserialize)]
+struct InspectorMessage {
+  paused: bool,
+}
+
+/// Handles an [`INSPECTOR_COMMAND`] request.
+pub(crate) fn handle<R: Runtime>(webview: &Webview<R>, body: InvokeBody) -> Result<(), String> {
+  if webview.label() != IPC_INSPECTOR_LABEL {
+    return Err("only the IPC inspector can pause the mirroring".into());
+  }
+  let message =
+    serde_json::from_value::<InspectorMessage>(body.into_json()).map_err(|e| e.to_string())?;
+  webview.manager().ipc_inspector.set_paused(message.paused);
+  Ok(())
+}
+
+impl<R: Runtime> AppHandle<R> {
+  /// Opens the IPC inspector window, or focuses it if it is already open.
+  ///
+  /// The window lists every command invoke with its arguments size, duration and result,
+  /// and every emitted event, with the payloads truncated to 256 characters.
+  /// The traffic is only mirrored while the window is open and not paused,
+  /// and the window's own traffic is never mirrored.
+  ///
+  /// Only available in debug builds with the `ipc-inspector` feature.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// tauri::Builder::default()
+  ///   .setup(|app| {
+  ///     #[cfg(debug_assertions)]
+  ///     app.handle().open_ipc_inspector()?;
+  ///     Ok(())
+  ///   });
+  /// ```
+  pub fn open_ipc_inspector(&self) -> crate::Result<WebviewWindow<R>> {
+    if let Some(window) = self.get_webview_window(IPC_INSPECTOR_LABEL) {
+      window.set_focus()?;
+      return Ok(window);
+    }
+
+    self.manager.webview.register_uri_scheme_protocol(
+      SCHEME,
+      Arc::new(UriSchemeProtocol {
+        protocol: Box::new(|_app, context, _request, responder| {
+          let response = if context.webview_label == IPC_INSPECTOR_LABEL {
+            http::Response::builder()
+              .header(http::header::CONTENT_TYPE, "text/html")
+              .body(Cow::Borrowed(INDEX.as_bytes()))
+          } else {
+            http::Response::builder()
+              .status(http::StatusCode::FORBIDDEN)
+              .body(Cow::Borrowed(&[][..]))
+          };
+          responder.respond(response.unwrap());
+        }),
+      }),
+    );
+
+    let window = WebviewWindowBuilder::new(
+      self,
+      IPC_INSPECTOR_LABEL,
+      WebviewUrl::CustomProtocol {
+        scheme: SCHEME.into(),
+        path: "".into(),
+      },
+    )
+    .title("IPC Inspector")
+    .inner_size(960., 600.)
+    .initialization_script(INIT_SCRIPT)
+    .build()?;
+
+    let manager = self.manager.clone();
+    window.on_window_event(move |event| {
+      if let WindowEvent::Destroyed = event {
+        manager.ipc_inspector.stop();
+      }
+    });
+
+    let mut rx = self.manager.ipc_inspector.start();
+    let webview = window.clone();
+    crate::async_runtime::spawn(async move {
+      while let Some(record) = rx.recv().await {
+        // the records sent meanwhile are delivered with a single script
+        let mut records = vec![record];
+        while let Ok(record) = rx.try_recv() {
+          records.push(record);
+        }
+        let Ok(records) = serde_json::to_string(&records) else {
+          continue;
+        };
+        let _ = webview.eval(&format!("window.__TAURI_IPC_INSPECTOR__.push({records})"));
+      }
+    });
+
+    Ok(window)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::{Duration, Instant};
+
+  use serde_json::json;
+
+  use super::{preview, IPC_INSPECTOR_LABEL, PREVIEW_LENGTH};
+  use crate::{
+    ipc::{InvokeBody, InvokeError, InvokeResponse},
+    test::{get_ipc_response, mock_app, mock_builder, mock_context, noop_assets},
+    webview::InvokeRequest,
+    Manager, WebviewWindowBuilder,
+  };
+
+  #[crate::command(root = "crate")]
+  fn greet(name: String) -> String {
+    format!("Hello, {name}!")
+  }
+
+  #[test]
+  fn truncates_previews() {
+    assert_eq!(preview("short".into()), "short");
+    let long = "é".repeat(PREVIEW_LENGTH + 1);
+    let truncated = preview(long);
+    assert_eq!(truncated.chars().count(), PREVIEW_LENGTH + 1);
+    assert!(truncated.ends_with('…'));
+  }
+
+  #[test]
+  fn records_traffic() {
+    let app = mock_app();
+    let inspector = &app.handle().manager.ipc_inspector;
+    let started = (Instant::now(), 2);
+    let response = InvokeResponse::Ok(json!("pong").into());
+
+    // nothing is recorded until the inspector is open
+    assert!(!inspector.is_recording("main"));
+    let mut rx = inspector.start();
+    inspector.record_invoke("main", "ping", started, &response);
+    inspector.record_invoke(
+      "main",
+      "fail",
+      started,
+      &InvokeResponse::Err(InvokeError::from("failed")),
+    );
+    // the inspector's own traffic is excluded
+    inspector.record_invoke(IPC_INSPECTOR_LABEL, "ping", started, &response);
+    app.emit("ready", "payload").unwrap();
+
+    inspector.set_paused(true);
+    inspector.record_invoke("main", "ping", started, &response);
+    app.emit("ready", "payload").unwrap();
+    inspector.set_paused(false);
+
+    let mut records = Vec::new();
+    while let Ok(record) = rx.try_recv() {
+      records.push(serde_json::to_value(record).unwrap());
+    }
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[0]["kind"], "invoke");
+    assert_eq!(records[0]["command"], "ping");
+    assert_eq!(records[0]["argsSize"], 2);
+    assert_eq!(records[0]["ok"], true);
+    assert_eq!(records[0]["preview"], r#""pong""#);
+    assert_eq!(records[1]["ok"], false);
+    assert_eq!(records[1]["preview"], r#""failed""#);
+    assert_eq!(records[2]["kind"], "event");
+    assert_eq!(records[2]["event"], "ready");
+    assert_eq!(records[2]["payloadSize"], 9);
+
+    inspector.stop();
+    inspector.record_invoke("main", "ping", started, &response);
+    assert!(rx.try_recv().is_err());
+  }
+
+  #[test]
+  fn mirrors_invokes_to_window() {
+    let app = mock_builder()
+      .invoke_handler(crate::generate_handler![greet])
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    let main = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    let inspector = app.handle().open_ipc_inspector().unwrap();
+    // opening it again focuses the same window
+    assert_eq!(
+      app.handle().open_ipc_inspector().unwrap().label(),
+      IPC_INSPECTOR_LABEL
+    );
+
+    get_ipc_response(
+      &main,
+      InvokeRequest {
+        cmd: "greet".into(),
+        callback: crate::ipc::CallbackFn(0),
+        error: crate::ipc::CallbackFn(1),
+        body: InvokeBody::Json(json!({ "name": "Tauri" })),
+        headers: Default::default(),
+      },
+    )
+    .unwrap();
+
+    let dispatcher = &inspector.webview.webview.dispatcher;
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let script = loop {
+      match dispatcher.last_evaluated_script() {
+        Some(script) => break script,
+        None if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(10)),
+        None => panic!("the invoke was not mirrored"),
+      }
+    };
+    assert!(script.starts_with("window.__TAURI_IPC_INSPECTOR__.push("));
+    assert!(script.contains(r#""command":"greet""#));
+    assert!(script.contains(r#""preview":"\"Hello, Tauri!\"""#));
+  }
+}
@@ -0,0 +1,240 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Size limits of the IPC requests and responses, see [`crate::Builder::max_ipc_payload_bytes`].
+
+use std::io;
+
+use super::{InvokeBody, InvokeResponse, IpcError};
+
+/// The default value of [`crate::Builder::max_ipc_payload_bytes`], 64 MiB.
+pub const DEFAULT_MAX_IPC_PAYLOAD_BYTES: usize = 64 * 1024 * 1024;
+/// The default value of [`crate::Builder::max_ipc_response_bytes`], 256 MiB.
+pub const DEFAULT_MAX_IPC_RESPONSE_BYTES: usize = 256 * 1024 * 1024;
+
+/// The limits configured on the [`crate::Builder`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct IpcLimits {
+  pub(crate) max_payload_bytes: usize,
+  pub(crate) max_response_bytes: usize,
+  /// Whether the JSON responses are measured, only once a response limit is set.
+  pub(crate) measure_json_responses: bool,
+}
+
+impl Default for IpcLimits {
+  fn default() -> Self {
+    Self {
+      max_payload_bytes: DEFAULT_MAX_IPC_PAYLOAD_BYTES,
+      max_response_bytes: DEFAULT_MAX_IPC_RESPONSE_BYTES,
+      measure_json_responses: false,
+    }
+  }
+}
+
+impl IpcLimits {
+  /// Checks the size of a request body, before it is parsed.
+  pub(crate) fn check_payload(&self, actual: usize) -> Result<(), IpcError> {
+    if actual > self.max_payload_bytes {
+      Err(IpcError::PayloadTooLarge {
+        limit: self.max_payload_bytes,
+        actual,
+      })
+    } else {
+      Ok(())
+    }
+  }
+
+  /// Checks the size of a command response, as sent to the frontend.
+  ///
+  /// Measuring a JSON response serializes it once more before the responder does,
+  /// so they are only checked when a limit was set with [`crate::Builder::max_ipc_response_bytes`].
+  pub(crate) fn check_response(
+    &self,
+    command: &str,
+    response: &InvokeResponse,
+  ) -> Result<(), IpcError> {
+    let actual = match response {
+      InvokeResponse::Ok(InvokeBody::Raw(bytes)) => bytes.len(),
+      _ if !self.measure_json_responses => return Ok(()),
+      response => response_size(response),
+    };
+    if actual > self.max_response_bytes {
+      Err(IpcError::ResponseTooLarge {
+        command: command.to_string(),
+        limit: self.max_response_bytes,
+        actual,
+      })
+    } else {
+      Ok(())
+    }
+  }
+}
+
+/// Counts the bytes written to it, to measure a serialized value without allocating it.
+struct ByteCounter(usize);
+
+impl io::Write for ByteCounter {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    self.0 += buf.len();
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+}
+
+/// The size of a request or response body, as sent over the IPC.
+pub(crate) fn body_size(body: &InvokeBody) -> usize {
+  match body {
+    InvokeBody::Raw(bytes) => bytes.len(),
+    InvokeBody::Json(value) => json_size(value),
+  }
+}
+
+pub(crate) fn response_size(response: &InvokeResponse) -> usize {
+  match response {
+    InvokeResponse::Ok(body) => body_size(body),
+    InvokeResponse::Err(error) => json_size(&error.0),
+  }
+}
+
+fn json_size(value: &serde_json::Value) -> usize {
+  let mut counter = ByteCounter(0);
+  // serializing a JSON value to a writer that never fails cannot fail
+  let _ = serde_json::to_writer(&mut counter, value);
+  counter.0
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::IpcLimits;
+  use crate::ipc::{InvokeBody, InvokeError, InvokeResponse, IpcError};
+
+  const LIMIT: usize = 16;
+
+  fn limits() -> IpcLimits {
+    IpcLimits {
+      max_payload_bytes: LIMIT,
+      max_response_bytes: LIMIT,
+      measure_json_responses: true,
+    }
+  }
+
+  #[test]
+  fn payload_limit() {
+    let limits = limits();
+    assert!(limits.check_payload(LIMIT - 1).is_ok());
+    assert!(limits.check_payload(LIMIT).is_ok());
+    assert!(matches!(
+      limits.check_payload(LIMIT + 1),
+      Err(IpcError::PayloadTooLarge {
+        limit: LIMIT,
+        actual
+      }) if actual == LIMIT + 1
+    ));
+  }
+
+  #[test]
+  fn raw_response_limit() {
+    let limits = limits();
+    let response = |len: usize| InvokeResponse::Ok(InvokeBody::Raw(vec![0; len]));
+    assert!(limits.check_response("cmd", &response(LIMIT - 1)).is_ok());
+    assert!(limits.check_response("cmd", &response(LIMIT)).is_ok());
+    assert!(matches!(
+      limits.check_response("cmd", &response(LIMIT + 1)),
+      Err(IpcError::ResponseTooLarge { command, limit: LIMIT, actual })
+        if command == "cmd" && actual == LIMIT + 1
+    ));
+  }
+
+  #[test]
+  fn json_response_limit() {
+    let limits = limits();
+    // the quotes are part of the serialized size
+    let string = |len: usize| json!("a".repeat(len - 2));
+    let ok = |len: usize| InvokeResponse::Ok(InvokeBody::Json(string(len)));
+    assert!(limits.check_response("cmd", &ok(LIMIT - 1)).is_ok());
+    assert!(limits.check_response("cmd", &ok(LIMIT)).is_ok());
+    assert!(matches!(
+      limits.check_response("cmd", &ok(LIMIT + 1)),
+      Err(IpcError::ResponseTooLarge { actual, .. }) if actual == LIMIT + 1
+    ));
+
+    let err = InvokeResponse::Err(InvokeError(string(LIMIT + 1)));
+    assert!(limits.check_response("cmd", &err).is_err());
+  }
+
+  #[test]
+  fn default_response_limit() {
+    let limits = IpcLimits {
+      measure_json_responses: false,
+      ..limits()
+    };
+    // JSON responses are not measured until a limit is set
+    let json = InvokeResponse::Ok(InvokeBody::Json(json!("a".repeat(LIMIT + 1))));
+    assert!(limits.check_response("cmd", &json).is_ok());
+    let raw = InvokeResponse::Ok(InvokeBody::Raw(vec![0; LIMIT + 1]));
+    assert!(limits.check_response("cmd", &raw).is_err());
+  }
+
+  #[crate::command(root = "crate")]
+  fn text(len: usize) -> String {
+    "a".repeat(len)
+  }
+
+  #[test]
+  fn resolver_rejects_large_responses() {
+    use crate::{
+      ipc::CallbackFn,
+      test::{get_ipc_response, mock_builder, mock_context, noop_assets},
+      webview::InvokeRequest,
+      WebviewWindowBuilder,
+    };
+
+    let app = mock_builder()
+      .max_ipc_response_bytes(LIMIT)
+      .invoke_handler(crate::generate_handler![text])
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+
+    // the serialized string has two more bytes for the quotes
+    let invoke = |size: usize| {
+      get_ipc_response(
+        &webview,
+        InvokeRequest {
+          cmd: "text".into(),
+          callback: CallbackFn(0),
+          error: CallbackFn(1),
+          body: json!({ "len": size - 2 }).into(),
+          headers: Default::default(),
+        },
+      )
+    };
+
+    assert!(invoke(LIMIT - 1).is_ok());
+    assert!(invoke(LIMIT).is_ok());
+    assert_eq!(
+      invoke(LIMIT + 1).unwrap_err(),
+      json!({ "kind": "ResponseTooLarge", "command": "text", "limit": LIMIT, "actual": LIMIT + 1 })
+    );
+  }
+
+  #[test]
+  fn error_is_structured() {
+    assert_eq!(
+      serde_json::to_value(IpcError::PayloadTooLarge {
+        limit: LIMIT,
+        actual: LIMIT + 1
+      })
+      .unwrap(),
+      json!({ "kind": "PayloadTooLarge", "limit": LIMIT, "actual": LIMIT + 1 })
+    );
+  }
+}
@@ -0,0 +1,236 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The manifest of the commands registered with [`crate::generate_handler!`], see [`crate::AppHandle::command_manifest`].
+
+use std::{
+  any::TypeId,
+  borrow::Cow,
+  collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+  path::PathBuf,
+  sync::{Mutex, OnceLock},
+};
+
+use serde_json::{json, Value as JsonValue};
+
+use self::private::CommandDescriptor;
+
+/// Describes the JSON values accepted by a command argument in the [command manifest](crate::AppHandle::command_manifest).
+///
+/// Implemented for the standard types that can be deserialized from JSON.
+/// Arguments whose type does not implement this trait have a `null` schema in the manifest.
+///
+/// # Examples
+///
+/// ```
+/// use tauri::ipc::CommandArgSchema;
+///
+/// #[derive(serde::Deserialize)]
+/// struct User {
+///   name: String,
+///   age: Option<u8>,
+/// }
+///
+/// impl CommandArgSchema for User {
+///   fn schema() -> serde_json::Value {
+///     serde_json::json!({
+///       "type": "object",
+///       "properties": {
+///         "name": String::schema(),
+///         "age": Option::<u8>::schema(),
+///       },
+///       "required": ["name"],
+///     })
+///   }
+/// }
+/// ```
+pub trait CommandArgSchema {
+  /// The JSON schema of the argument.
+  fn schema() -> JsonValue;
+}
+
+macro_rules! impl_schema {
+  ($schema:tt => $($ty:ty),+) => {
+    $(
+      impl CommandArgSchema for $ty {
+        fn schema() -> JsonValue {
+          json!($schema)
+        }
+      }
+    )+
+  };
+}
+
+impl_schema!({ "type": "boolean" } => bool);
+impl_schema!({ "type": "integer" } => u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+impl_schema!({ "type": "number" } => f32, f64);
+impl_schema!({ "type": "string" } => String, &str, Cow<'_, str>, char, PathBuf);
+impl_schema!({ "type": "null" } => ());
+impl_schema!({} => JsonValue);
+
+impl<T: CommandArgSchema> CommandArgSchema for Option<T> {
+  fn schema() -> JsonValue {
+    json!({ "anyOf": [T::schema(), { "type": "null" }] })
+  }
+}
+
+impl<T: CommandArgSchema> CommandArgSchema for Box<T> {
+  fn schema() -> JsonValue {
+    T::schema()
+  }
+}
+
+macro_rules! impl_array_schema {
+  ($($ty:ident),+) => {
+    $(
+      impl<T: CommandArgSchema> CommandArgSchema for $ty<T> {
+        fn schema() -> JsonValue {
+          json!({ "type": "array", "items": T::schema() })
+        }
+      }
+    )+
+  };
+}
+
+impl_array_schema!(Vec, HashSet, BTreeSet);
+
+impl<T: CommandArgSchema, const N: usize> CommandArgSchema for [T; N] {
+  fn schema() -> JsonValue {
+    json!({ "type": "array", "items": T::schema(), "minItems": N, "maxItems": N })
+  }
+}
+
+macro_rules! impl_map_schema {
+  ($($ty:ident),+) => {
+    $(
+      impl<T: CommandArgSchema> CommandArgSchema for $ty<String, T> {
+        fn schema() -> JsonValue {
+          json!({ "type": "object", "additionalProperties": T::schema() })
+        }
+      }
+    )+
+  };
+}
+
+impl_map_schema!(HashMap, BTreeMap);
+
+/// The command manifest of the app, managed as state.
+pub(crate) struct CommandManifest(pub(crate) JsonValue);
+
+impl CommandManifest {
+  pub(crate) fn new(commands: Vec<CommandDescriptor>) -> Self {
+    Self(json!({ "commands": commands }))
+  }
+}
+
+type ManifestFn = fn() -> Vec<CommandDescriptor>;
+
+/// The manifests recorded by `generate_handler!`, keyed by the type of the generated handler.
+fn handler_manifests() -> &'static Mutex<HashMap<TypeId, ManifestFn>> {
+  static MANIFESTS: OnceLock<Mutex<HashMap<TypeId, ManifestFn>>> = OnceLock::new();
+  MANIFESTS.get_or_init(Default::default)
+}
+
+/// Takes the manifest recorded for the handler generated with `generate_handler!`.
+///
+/// Returns `None` for handlers that are not generated with the macro.
+pub(crate) fn take_handler_manifest<F: 'static>() -> Option<ManifestFn> {
+  handler_manifests()
+    .lock()
+    .unwrap()
+    .remove(&TypeId::of::<F>())
+}
+
+/// Items used by the code generated by `#[command]` and `generate_handler!`.
+///
+/// Nothing in this module is considered stable.
+#[doc(hidden)]
+pub mod private {
+  use std::marker::PhantomData;
+
+  use serde::{Deserialize, Serialize};
+  use serde_json::Value as JsonValue;
+
+  use super::CommandArgSchema;
+  use crate::{ipc::Invoke, Runtime};
+
+  #[derive(Debug, Clone, Serialize)]
+  pub struct CommandDescriptor {
+    pub name: &'static str,
+    pub args: Vec<CommandArgDescriptor>,
+  }
+
+  #[derive(Debug, Clone, Serialize)]
+  pub struct CommandArgDescriptor {
+    pub name: &'static str,
+    #[serde(rename = "type")]
+    pub rust_type: &'static str,
+    pub schema: Option<JsonValue>,
+  }
+
+  /// Records the manifest of the commands of the handler, see [`super::take_handler_manifest`].
+  pub fn record_command_manifest<R, F>(commands: super::ManifestFn, handler: F) -> F
+  where
+    R: Runtime,
+    F: Fn(Invoke<R>) -> bool + 'static,
+  {
+    super::handler_manifests()
+      .lock()
+      .unwrap()
+      .insert(std::any::TypeId::of::<F>(), commands);
+    handler
+  }
+
+  // ===== [Autoref-based stable specialization](https://github.com/dtolnay/case-studies/blob/master/autoref-specialization/README.md) =====
+
+  pub struct ArgTag<T: ?Sized>(PhantomData<T>);
+
+  impl<T: ?Sized> ArgTag<T> {
+    #[inline(always)]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+      Self(PhantomData)
+    }
+  }
+
+  /// Arguments deserialized from the command payload.
+  pub trait PayloadArgKind {
+    #[inline(always)]
+    fn command_arg_is_payload(&self) -> bool {
+      true
+    }
+  }
+
+  impl<'de, T: Deserialize<'de>> PayloadArgKind for ArgTag<T> {}
+
+  /// Arguments injected by Tauri, like the `State` or the `Webview`.
+  pub trait InjectedArgKind {
+    #[inline(always)]
+    fn command_arg_is_payload(&self) -> bool {
+      false
+    }
+  }
+
+  impl<T: ?Sized> InjectedArgKind for &ArgTag<T> {}
+
+  pub trait SchemaKind {
+    fn command_arg_schema(&self) -> Option<JsonValue>;
+  }
+
+  impl<T: CommandArgSchema> SchemaKind for ArgTag<T> {
+    #[inline(always)]
+    fn command_arg_schema(&self) -> Option<JsonValue> {
+      Some(T::schema())
+    }
+  }
+
+  pub trait NoSchemaKind {
+    #[inline(always)]
+    fn command_arg_schema(&self) -> Option<JsonValue> {
+      None
+    }
+  }
+
+  impl<T: ?Sized> NoSchemaKind for &ArgTag<T> {}
+}
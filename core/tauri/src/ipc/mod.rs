@@ -18,26 +18,62 @@ use tauri_utils::acl::resolved::ResolvedCommand;
 
 use crate::{webview::Webview, Runtime, StateManager};
 
+mod allowlist;
 mod authority;
+mod body;
+pub(crate) mod cache;
+pub(crate) mod cancellation;
 pub(crate) mod channel;
 mod command;
+pub(crate) mod dispatcher;
 #[cfg(any(target_os = "macos", target_os = "ios", not(ipc_custom_protocol)))]
 pub(crate) mod format_callback;
+mod headers;
+#[cfg(all(debug_assertions, feature = "ipc-inspector"))]
+pub(crate) mod inspector;
+pub(crate) mod limits;
+#[cfg(feature = "command-manifest")]
+pub(crate) mod manifest;
+pub(crate) mod origin;
+pub(crate) mod panic;
 pub(crate) mod protocol;
+pub(crate) mod stream;
+pub(crate) mod sync;
 
+pub use allowlist::AllowList;
 pub use authority::{CommandScope, GlobalScope, Origin, RuntimeAuthority, ScopeObject, ScopeValue};
+pub use body::{RawBody, RawBodyBuf};
+pub use cache::{CacheKey, CachePolicy, DEFAULT_MAX_CACHE_ENTRIES};
+pub use cancellation::{CancellationToken, Cancelled};
 pub use channel::{Channel, JavaScriptChannelId};
 pub use command::{private, CommandArg, CommandItem};
+pub use dispatcher::{DefaultScopePolicy, ScopePolicy, ScopeRequest};
+pub use headers::{ContentType, Header, Headers, TypedHeader};
+#[cfg(all(debug_assertions, feature = "ipc-inspector"))]
+#[cfg_attr(docsrs, doc(cfg(all(debug_assertions, feature = "ipc-inspector"))))]
+pub use inspector::IPC_INSPECTOR_LABEL;
+pub use limits::{DEFAULT_MAX_IPC_PAYLOAD_BYTES, DEFAULT_MAX_IPC_RESPONSE_BYTES};
+#[cfg(feature = "command-manifest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "command-manifest")))]
+pub use manifest::CommandArgSchema;
+pub use origin::default_origin_validator;
+pub use stream::StreamResponse;
 
 /// A closure that is run every time Tauri receives a message it doesn't explicitly handle.
 pub type InvokeHandler<R> = dyn Fn(Invoke<R>) -> bool + Send + Sync + 'static;
 
 /// A closure that is responsible for respond a JS message.
-pub type InvokeResponder<R> =
-  dyn Fn(&Webview<R>, &str, &InvokeResponse, CallbackFn, CallbackFn) + Send + Sync + 'static;
+pub type InvokeResponder<R> = dyn Fn(&Webview<R>, &str, &InvokeResponse, &ResponseMeta, CallbackFn, CallbackFn)
+  + Send
+  + Sync
+  + 'static;
 /// Similar to [`InvokeResponder`] but taking owned arguments.
-pub type OwnedInvokeResponder<R> =
-  dyn FnOnce(Webview<R>, String, InvokeResponse, CallbackFn, CallbackFn) + Send + 'static;
+pub type OwnedInvokeResponder<R> = dyn FnOnce(Webview<R>, Arc<str>, InvokeResponse, ResponseMeta, CallbackFn, CallbackFn)
+  + Send
+  + 'static;
+/// A closure that validates the headers of an IPC request before it is handled.
+pub type IpcOriginValidator<R> =
+  dyn Fn(&HeaderMap, &Webview<R>) -> Result<(), String> + Send + Sync + 'static;
 
 /// Possible values of an IPC payload.
 #[derive(Debug, Clone)]
@@ -166,6 +202,65 @@ pub struct Invoke<R: Runtime> {
   pub acl: Option<ResolvedCommand>,
 }
 
+/// A structured error used to reject IPC requests before they reach the command handler.
+///
+/// Serialized as an object with a `kind` tag, e.g. `{ "kind": "OriginDenied", "message": "..." }`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind")]
+#[non_exhaustive]
+pub enum IpcError {
+  /// The request was rejected by the [`crate::Builder::ipc_origin_validator`].
+  OriginDenied {
+    /// The reason returned by the validator.
+    message: String,
+  },
+  /// The command is not in the webview [`AllowList`].
+  CommandDenied {
+    /// The denied command.
+    command: String,
+  },
+  /// The request did not have the invoke key of the current page,
+  /// see [`crate::webview::WebviewBuilder::rotate_invoke_key`].
+  InvalidInvokeKey,
+  /// The [`crate::Builder::sync_command`] did not finish in its time budget.
+  SyncCommandTimeout {
+    /// The sync command.
+    command: String,
+    /// The time budget in milliseconds.
+    millis: u64,
+  },
+  /// The [`crate::Builder::sync_command`] response exceeded the size cap.
+  SyncResponseTooLarge {
+    /// The sync command.
+    command: String,
+    /// The size of the serialized response in bytes.
+    size: usize,
+    /// The maximum size of the serialized response in bytes.
+    limit: usize,
+  },
+  /// The request payload exceeded [`crate::Builder::max_ipc_payload_bytes`] and was not parsed.
+  PayloadTooLarge {
+    /// The maximum size of the payload in bytes.
+    limit: usize,
+    /// The size of the payload in bytes.
+    actual: usize,
+  },
+  /// The command handler panicked, see [`crate::Builder::on_command_panic`].
+  CommandPanicked {
+    /// The panic message.
+    message: String,
+  },
+  /// The command response exceeded [`crate::Builder::max_ipc_response_bytes`].
+  ResponseTooLarge {
+    /// The command.
+    command: String,
+    /// The maximum size of the serialized response in bytes.
+    limit: usize,
+    /// The size of the serialized response in bytes.
+    actual: usize,
+  },
+}
+
 /// Error response from an [`InvokeMessage`].
 #[derive(Debug)]
 pub struct InvokeError(pub JsonValue);
@@ -241,12 +336,65 @@ impl From<InvokeError> for InvokeResponse {
   }
 }
 
+/// The [`ResponseMeta`] header with the id of a traced request, see [`crate::Builder::trace_ipc`].
+#[cfg(feature = "tracing")]
+pub(crate) const IPC_REQUEST_ID_HEADER: &str = "tauri-request-id";
+
+/// Metadata delivered with an invoke response, see [`InvokeResolver::resolve_with_meta`].
+///
+/// The frontend receives it as the second argument of the response callback,
+/// serialized as `{ headers: Record<string, string>, status: number | null }`.
+/// Responses without metadata only pass the response to the callback.
+#[derive(Debug, Clone, Default)]
+pub struct ResponseMeta {
+  /// The response headers. Values that are not visible ASCII are not sent to the frontend.
+  pub headers: HeaderMap,
+  /// A status code of the response, e.g. for transports mapping the response to HTTP.
+  ///
+  /// This does not change whether the invoke promise is resolved or rejected.
+  pub status: Option<u16>,
+}
+
+impl ResponseMeta {
+  /// Whether there's no metadata to deliver.
+  pub fn is_empty(&self) -> bool {
+    self.headers.is_empty() && self.status.is_none()
+  }
+}
+
+impl Serialize for ResponseMeta {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer,
+  {
+    use serde::ser::SerializeStruct;
+
+    let mut headers = std::collections::BTreeMap::<&str, String>::new();
+    for (name, value) in &self.headers {
+      if let Ok(value) = value.to_str() {
+        headers
+          .entry(name.as_str())
+          .and_modify(|values| {
+            values.push_str(", ");
+            values.push_str(value);
+          })
+          .or_insert_with(|| value.to_string());
+      }
+    }
+
+    let mut meta = serializer.serialize_struct("ResponseMeta", 2)?;
+    meta.serialize_field("headers", &headers)?;
+    meta.serialize_field("status", &self.status)?;
+    meta.end()
+  }
+}
+
 /// Resolver of a invoke message.
 #[default_runtime(crate::Wry, wry)]
 pub struct InvokeResolver<R: Runtime> {
   webview: Webview<R>,
   responder: Arc<Mutex<Option<Box<OwnedInvokeResponder<R>>>>>,
-  cmd: String,
+  cmd: Arc<str>,
   pub(crate) callback: CallbackFn,
   pub(crate) error: CallbackFn,
 }
@@ -267,7 +415,7 @@ impl<R: Runtime> InvokeResolver<R> {
   pub(crate) fn new(
     webview: Webview<R>,
     responder: Arc<Mutex<Option<Box<OwnedInvokeResponder<R>>>>>,
-    cmd: String,
+    cmd: Arc<str>,
     callback: CallbackFn,
     error: CallbackFn,
   ) -> Self {
@@ -287,6 +435,13 @@ impl<R: Runtime> InvokeResolver<R> {
     F: Future<Output = Result<T, InvokeError>> + Send + 'static,
   {
     crate::async_runtime::spawn(async move {
+      let webview = self.webview.clone();
+      let cmd = self.cmd.clone();
+      let task = async move {
+        panic::catch_async(task)
+          .await
+          .unwrap_or_else(|report| Err(panic::on_panic(&webview, &cmd, report)))
+      };
       Self::return_task(
         self.webview,
         self.responder,
@@ -305,7 +460,10 @@ impl<R: Runtime> InvokeResolver<R> {
     F: Future<Output = Result<InvokeBody, InvokeError>> + Send + 'static,
   {
     crate::async_runtime::spawn(async move {
-      let response = match task.await {
+      let result = panic::catch_async(task)
+        .await
+        .unwrap_or_else(|report| Err(panic::on_panic(&self.webview, &self.cmd, report)));
+      let response = match result {
         Ok(ok) => InvokeResponse::Ok(ok),
         Err(err) => InvokeResponse::Err(err),
       };
@@ -332,11 +490,50 @@ impl<R: Runtime> InvokeResolver<R> {
     )
   }
 
+  /// Reply to the invoke promise with a serializable value and the metadata delivered with it.
+  pub fn respond_with_meta<T: IpcResponse>(
+    self,
+    value: Result<T, InvokeError>,
+    meta: ResponseMeta,
+  ) {
+    Self::return_result_with_meta(
+      self.webview,
+      self.responder,
+      value.into(),
+      meta,
+      self.cmd,
+      self.callback,
+      self.error,
+    )
+  }
+
+  /// Reply to the invoke promise with a serializable value and the given response headers.
+  ///
+  /// See [`ResponseMeta`] for how the headers are delivered to the frontend.
+  pub fn respond_with_headers<T: IpcResponse>(
+    self,
+    value: Result<T, InvokeError>,
+    headers: HeaderMap,
+  ) {
+    self.respond_with_meta(
+      value,
+      ResponseMeta {
+        headers,
+        status: None,
+      },
+    )
+  }
+
   /// Resolve the invoke promise with a value.
   pub fn resolve<T: IpcResponse>(self, value: T) {
     self.respond(Ok(value))
   }
 
+  /// Resolve the invoke promise with a value and the metadata delivered with it.
+  pub fn resolve_with_meta<T: IpcResponse>(self, value: T, meta: ResponseMeta) {
+    self.respond_with_meta(Ok(value), meta)
+  }
+
   /// Reject the invoke promise with a value.
   pub fn reject<T: Serialize>(self, value: T) {
     Self::return_result(
@@ -349,6 +546,19 @@ impl<R: Runtime> InvokeResolver<R> {
     )
   }
 
+  /// Reject the invoke promise with a value and the metadata delivered with it.
+  pub fn reject_with_meta<T: Serialize>(self, value: T, meta: ResponseMeta) {
+    Self::return_result_with_meta(
+      self.webview,
+      self.responder,
+      Result::<(), _>::Err(value).into(),
+      meta,
+      self.cmd,
+      self.callback,
+      self.error,
+    )
+  }
+
   /// Reject the invoke promise with an [`InvokeError`].
   pub fn invoke_error(self, error: InvokeError) {
     Self::return_result(
@@ -370,7 +580,7 @@ impl<R: Runtime> InvokeResolver<R> {
     webview: Webview<R>,
     responder: Arc<Mutex<Option<Box<OwnedInvokeResponder<R>>>>>,
     task: F,
-    cmd: String,
+    cmd: Arc<str>,
     success_callback: CallbackFn,
     error_callback: CallbackFn,
   ) where
@@ -392,7 +602,7 @@ impl<R: Runtime> InvokeResolver<R> {
     webview: Webview<R>,
     responder: Arc<Mutex<Option<Box<OwnedInvokeResponder<R>>>>>,
     f: F,
-    cmd: String,
+    cmd: Arc<str>,
     success_callback: CallbackFn,
     error_callback: CallbackFn,
   ) {
@@ -410,14 +620,61 @@ impl<R: Runtime> InvokeResolver<R> {
     webview: Webview<R>,
     responder: Arc<Mutex<Option<Box<OwnedInvokeResponder<R>>>>>,
     response: InvokeResponse,
-    cmd: String,
+    cmd: Arc<str>,
+    success_callback: CallbackFn,
+    error_callback: CallbackFn,
+  ) {
+    Self::return_result_with_meta(
+      webview,
+      responder,
+      response,
+      Default::default(),
+      cmd,
+      success_callback,
+      error_callback,
+    )
+  }
+
+  pub(crate) fn return_result_with_meta(
+    webview: Webview<R>,
+    responder: Arc<Mutex<Option<Box<OwnedInvokeResponder<R>>>>>,
+    response: InvokeResponse,
+    meta: ResponseMeta,
+    cmd: Arc<str>,
     success_callback: CallbackFn,
     error_callback: CallbackFn,
   ) {
+    use crate::sealed::ManagerBase;
+
+    if let InvokeResponse::Ok(body) = &response {
+      stream::claim(body, webview.label());
+    }
+
+    let response = match webview
+      .manager()
+      .webview
+      .ipc_limits
+      .check_response(&cmd, &response)
+    {
+      Ok(()) => response,
+      Err(error) => {
+        if let IpcError::ResponseTooLarge { limit, actual, .. } = &error {
+          tauri_utils::debug_eprintln!(
+            "rejected the response of the `{}` command: it is {} bytes, over the {} bytes limit",
+            cmd,
+            actual,
+            limit
+          );
+        }
+        InvokeResponse::Err(error.into())
+      }
+    };
+
     (responder.lock().unwrap().take().expect("resolver consumed"))(
       webview,
       cmd,
       response,
+      meta,
       success_callback,
       error_callback,
     );
@@ -433,11 +690,13 @@ pub struct InvokeMessage<R: Runtime> {
   /// Application managed state.
   pub(crate) state: Arc<StateManager>,
   /// The IPC command.
-  pub(crate) command: String,
+  pub(crate) command: Arc<str>,
   /// The JSON argument passed on the invoke message.
   pub(crate) payload: InvokeBody,
-  /// The request headers.
-  pub(crate) headers: HeaderMap,
+  /// The request headers, shared with the [`Headers`] command arguments.
+  pub(crate) headers: Arc<HeaderMap>,
+  /// Cancelled when the frontend no longer waits for the response.
+  pub(crate) cancellation: CancellationToken,
 }
 
 impl<R: Runtime> Clone for InvokeMessage<R> {
@@ -448,6 +707,7 @@ impl<R: Runtime> Clone for InvokeMessage<R> {
       command: self.command.clone(),
       payload: self.payload.clone(),
       headers: self.headers.clone(),
+      cancellation: self.cancellation.clone(),
     }
   }
 }
@@ -457,16 +717,18 @@ impl<R: Runtime> InvokeMessage<R> {
   pub(crate) fn new(
     webview: Webview<R>,
     state: Arc<StateManager>,
-    command: String,
+    command: Arc<str>,
     payload: InvokeBody,
     headers: HeaderMap,
+    cancellation: CancellationToken,
   ) -> Self {
     Self {
       webview,
       state,
       command,
       payload,
-      headers,
+      headers: Arc::new(headers),
+      cancellation,
     }
   }
 
@@ -511,6 +773,12 @@ impl<R: Runtime> InvokeMessage<R> {
   pub fn headers(&self) -> &HeaderMap {
     &self.headers
   }
+
+  /// The token cancelled when the frontend no longer waits for the response, see [`CancellationToken`].
+  #[inline(always)]
+  pub fn cancellation_token(&self) -> &CancellationToken {
+    &self.cancellation
+  }
 }
 
 /// The `Callback` type is the return value of the `transformCallback` JavaScript function.
@@ -0,0 +1,203 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Validation of the origin of IPC requests.
+
+use http::{header::ORIGIN, HeaderMap};
+use url::{Host, Url};
+
+use crate::{sealed::ManagerBase, webview::Webview, Runtime};
+
+/// The default IPC origin validator, enabled with [`crate::Builder::default_ipc_origin_validator`].
+///
+/// Accepts requests from the tauri protocol origin, from the app URL
+/// and from the origins registered with [`crate::Builder::add_local_origin`].
+/// In development, any `localhost` origin is also accepted.
+///
+/// Both the `Origin` header, when present, and the current webview URL must be allowed,
+/// since the header is set by the frontend when the IPC custom protocol is not used.
+pub fn default_origin_validator<R: Runtime>(
+  headers: &HeaderMap,
+  webview: &Webview<R>,
+) -> Result<(), String> {
+  let manager = webview.manager();
+  let mut allowed = vec![
+    manager.protocol_url().into_owned(),
+    manager.get_url().into_owned(),
+  ];
+  allowed.extend(manager.webview.local_origins.iter().cloned());
+  let allow_localhost = cfg!(dev);
+
+  if let Some(origin) = headers.get(ORIGIN) {
+    let origin = origin
+      .to_str()
+      .map_err(|_| "Origin header value must be a string".to_string())?;
+    check_origin(origin, &allowed, allow_localhost)?;
+  }
+
+  let url = webview.url().map_err(|e| e.to_string())?;
+  check_origin(url.as_str(), &allowed, allow_localhost)
+}
+
+fn check_origin(origin: &str, allowed: &[Url], allow_localhost: bool) -> Result<(), String> {
+  let url = Url::parse(origin).map_err(|_| format!("invalid origin `{origin}`"))?;
+
+  let is_allowed = allowed.iter().any(|allowed| is_same_origin(allowed, &url))
+    || (allow_localhost && matches!(url.scheme(), "http" | "https") && is_localhost(&url));
+
+  if is_allowed {
+    Ok(())
+  } else {
+    Err(format!("origin `{origin}` is not allowed"))
+  }
+}
+
+/// The origin of the pages served by the custom protocol with the given name.
+///
+/// On Windows and Android custom protocols are served from `http://<name>.localhost`,
+/// on the other platforms from `<name>://localhost`.
+pub(crate) fn protocol_origin(protocol: &str) -> Option<Url> {
+  if cfg!(windows) || cfg!(target_os = "android") {
+    Url::parse(&format!("http://{protocol}.localhost")).ok()
+  } else {
+    Url::parse(&format!("{protocol}://localhost")).ok()
+  }
+}
+
+/// Whether the URL has the same scheme, host and port as one of the local origins.
+pub(crate) fn is_local_url(url: &Url, local_origins: &[Url]) -> bool {
+  local_origins
+    .iter()
+    .any(|origin| is_same_origin(origin, url))
+}
+
+// `Url::origin` is opaque for custom schemes such as `tauri://` so we compare the tuple manually
+pub(crate) fn is_same_origin(a: &Url, b: &Url) -> bool {
+  a.scheme() == b.scheme()
+    && a.host() == b.host()
+    && a.port_or_known_default() == b.port_or_known_default()
+}
+
+fn is_localhost(url: &Url) -> bool {
+  match url.host() {
+    Some(Host::Domain(domain)) => domain == "localhost",
+    Some(Host::Ipv4(ip)) => ip.is_loopback(),
+    Some(Host::Ipv6(ip)) => ip.is_loopback(),
+    None => false,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{check_origin, is_local_url, protocol_origin};
+  use url::Url;
+
+  fn allowed() -> Vec<Url> {
+    vec![
+      "tauri://localhost".parse().unwrap(),
+      "http://tauri.localhost".parse().unwrap(),
+    ]
+  }
+
+  #[test]
+  fn accepts_app_origin() {
+    let allowed = allowed();
+    assert!(check_origin("tauri://localhost", &allowed, false).is_ok());
+    assert!(check_origin("http://tauri.localhost", &allowed, false).is_ok());
+    assert!(check_origin("tauri://localhost/index.html", &allowed, false).is_ok());
+  }
+
+  #[test]
+  fn rejects_spoofed_origin() {
+    let allowed = allowed();
+    for origin in [
+      "https://tauri.app",
+      "tauri://localhost.evil.com",
+      "http://tauri.localhost.evil.com",
+      "https://tauri.localhost",
+      "tauri://localhost:1430",
+      "tauri://evil",
+      "http://localhost:1420",
+      "null",
+      "",
+    ] {
+      assert!(
+        check_origin(origin, &allowed, false).is_err(),
+        "{origin} must be rejected"
+      );
+    }
+  }
+
+  #[test]
+  fn localhost_only_in_dev() {
+    let allowed = allowed();
+    for origin in [
+      "http://localhost:1420",
+      "http://127.0.0.1:8080",
+      "https://[::1]",
+    ] {
+      assert!(check_origin(origin, &allowed, true).is_ok());
+      assert!(check_origin(origin, &allowed, false).is_err());
+    }
+    assert!(check_origin("http://localhost.evil.com", &allowed, true).is_err());
+    assert!(check_origin("file://localhost/etc/passwd", &allowed, true).is_err());
+  }
+
+  fn local_origins() -> Vec<Url> {
+    let mut origins = allowed();
+    origins.push("http://localhost:1420/app/".parse().unwrap());
+    origins.push(protocol_origin("asset").unwrap());
+    origins
+  }
+
+  #[test]
+  fn local_urls() {
+    let origins = local_origins();
+    for url in [
+      "tauri://localhost/index.html",
+      "http://tauri.localhost/nested/page.html",
+      "http://localhost:1420/",
+      "http://localhost:1420/other/path?query",
+    ] {
+      assert!(
+        is_local_url(&url.parse().unwrap(), &origins),
+        "{url} must be local"
+      );
+    }
+    let asset = if cfg!(windows) || cfg!(target_os = "android") {
+      "http://asset.localhost/image.png"
+    } else {
+      "asset://localhost/image.png"
+    };
+    assert!(is_local_url(&asset.parse().unwrap(), &origins));
+  }
+
+  #[test]
+  fn rejects_spoofed_local_urls() {
+    let origins = local_origins();
+    for url in [
+      "https://tauri.localhost",
+      "http://tauri.localhost:8080",
+      "https://tauri.localhost:8080",
+      "http://evil.tauri.localhost",
+      "http://tauri.localhost.evil.com",
+      "tauri://localhost:8080",
+      "tauri://evil",
+      "tauri://evil.localhost",
+      "http://localhost:1421",
+      "https://localhost:1420",
+      "http://localhost",
+      "http://asset.evil.com",
+      "asset://evil.com",
+      "https://tauri.app",
+      "file:///etc/passwd",
+      "data:text/html,<script></script>",
+    ] {
+      assert!(
+        !is_local_url(&url.parse().unwrap(), &origins),
+        "{url} must not be local"
+      );
+    }
+  }
+}
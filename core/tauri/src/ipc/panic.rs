@@ -0,0 +1,150 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Panics of the command handlers, rejected with [`IpcError::CommandPanicked`], see [`crate::Builder::on_command_panic`].
+
+use std::{
+  any::Any,
+  cell::RefCell,
+  future::Future,
+  panic::{catch_unwind, AssertUnwindSafe},
+  sync::Once,
+};
+
+use futures_util::FutureExt;
+
+use super::{InvokeError, IpcError};
+use crate::{diagnostics::PanicReport, Manager, Runtime, Webview};
+
+/// The hook called with the command name, the panic report and the webview label when a command panics.
+pub(crate) type OnCommandPanic = dyn Fn(&str, &PanicReport, &str) + Send + Sync + 'static;
+
+/// The [`crate::Builder::on_command_panic`] hook, managed by the app.
+pub(crate) struct CommandPanicHook(pub(crate) Box<OnCommandPanic>);
+
+thread_local! {
+  /// The report of the last panic on the thread, see [`record_panics`].
+  static LAST_PANIC: RefCell<Option<PanicReport>> = const { RefCell::new(None) };
+}
+
+/// Records the report of each panic on its thread on top of the current panic hook,
+/// so the reports of the caught command panics carry their location.
+///
+/// The current hook still runs, printing the panic and its backtrace.
+pub(crate) fn record_panics() {
+  static RECORD_PANICS: Once = Once::new();
+  RECORD_PANICS.call_once(|| {
+    let previous_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+      // the thread local is not available while the thread is being destroyed
+      let _ = LAST_PANIC.try_with(|last_panic| {
+        last_panic.replace(Some(PanicReport::new(info.payload(), info.location())))
+      });
+      previous_hook(info);
+    }));
+  });
+}
+
+/// The report of a panic caught on the current thread.
+fn report(payload: Box<dyn Any + Send>) -> PanicReport {
+  LAST_PANIC
+    .try_with(|last_panic| last_panic.take())
+    .ok()
+    .flatten()
+    .unwrap_or_else(|| PanicReport::new(&*payload, None))
+}
+
+/// Runs a command handler, returning the report of its panic if it panicked.
+pub(crate) fn catch<T>(f: impl FnOnce() -> T) -> Result<T, PanicReport> {
+  catch_unwind(AssertUnwindSafe(f)).map_err(report)
+}
+
+/// Runs the task of an async command, returning the report of its panic if it panicked.
+pub(crate) async fn catch_async<T>(task: impl Future<Output = T>) -> Result<T, PanicReport> {
+  AssertUnwindSafe(task).catch_unwind().await.map_err(report)
+}
+
+/// Calls the [`crate::Builder::on_command_panic`] hook, returning the error rejecting the command.
+pub(crate) fn on_panic<R: Runtime>(
+  webview: &Webview<R>,
+  cmd: &str,
+  report: PanicReport,
+) -> InvokeError {
+  if let Some(hook) = webview.try_state::<CommandPanicHook>() {
+    (hook.0)(cmd, &report, webview.label());
+  }
+  IpcError::CommandPanicked {
+    message: report.message,
+  }
+  .into()
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, Mutex};
+
+  use serde_json::json;
+
+  use crate::{
+    test::{get_ipc_json_response, mock_builder, mock_context, noop_assets},
+    WebviewWindowBuilder,
+  };
+
+  #[crate::command(root = "crate")]
+  fn panics() {
+    panic!("sync command panicked");
+  }
+
+  #[crate::command(root = "crate")]
+  async fn panics_async() {
+    panic!("async command panicked");
+  }
+
+  #[test]
+  fn rejects_panicked_commands() {
+    let panics_ = Arc::new(Mutex::new(Vec::new()));
+    let panics__ = panics_.clone();
+    let app = mock_builder()
+      .invoke_handler(crate::generate_handler![panics, panics_async])
+      .on_command_panic(move |command, report, webview| {
+        panics__.lock().unwrap().push((
+          command.to_string(),
+          report.message.clone(),
+          report.location.is_some(),
+          webview.to_string(),
+        ));
+      })
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+
+    assert_eq!(
+      get_ipc_json_response(&webview, "panics", json!({}), Default::default()),
+      Err(json!({ "kind": "CommandPanicked", "message": "sync command panicked" }))
+    );
+    assert_eq!(
+      get_ipc_json_response(&webview, "panics_async", json!({}), Default::default()),
+      Err(json!({ "kind": "CommandPanicked", "message": "async command panicked" }))
+    );
+    assert_eq!(
+      *panics_.lock().unwrap(),
+      vec![
+        (
+          "panics".to_string(),
+          "sync command panicked".to_string(),
+          true,
+          "main".to_string()
+        ),
+        (
+          "panics_async".to_string(),
+          "async command panicked".to_string(),
+          true,
+          "main".to_string()
+        ),
+      ]
+    );
+  }
+}
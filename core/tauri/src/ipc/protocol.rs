@@ -10,14 +10,19 @@ use crate::{
   Runtime,
 };
 use http::{
-  header::{ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_ORIGIN, CONTENT_TYPE},
+  header::{
+    ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_EXPOSE_HEADERS,
+    CONTENT_TYPE,
+  },
   HeaderValue, Method, StatusCode,
 };
 
-use super::{CallbackFn, InvokeBody, InvokeResponse};
+use super::{sync::SYNC_INVOKE_HEADER, CallbackFn, InvokeBody, InvokeResponse, ResponseMeta};
 
 const TAURI_CALLBACK_HEADER_NAME: &str = "Tauri-Callback";
 const TAURI_ERROR_HEADER_NAME: &str = "Tauri-Error";
+/// The header with the JSON serialized [`ResponseMeta`] of a response.
+const TAURI_RESPONSE_META_HEADER_NAME: &str = "Tauri-Response-Meta";
 
 #[cfg(any(target_os = "macos", target_os = "ios", not(ipc_custom_protocol)))]
 pub fn message_handler<R: Runtime>(
@@ -47,10 +52,31 @@ pub fn get<R: Runtime>(manager: Arc<AppManager<R>>, label: String) -> UriSchemeP
     };
 
     match *request.method() {
+      Method::POST if request.headers().contains_key(SYNC_INVOKE_HEADER) => {
+        handle_sync_request(&manager, &label, request, respond)
+      }
+
       Method::POST => {
+        if let Err(error) = manager
+          .webview
+          .ipc_limits
+          .check_payload(request.body().len())
+        {
+          respond(
+            http::Response::builder()
+              .status(StatusCode::PAYLOAD_TOO_LARGE)
+              .header(CONTENT_TYPE, mime::APPLICATION_JSON.essence_str())
+              .body(serde_json::to_vec(&error).unwrap().into())
+              .unwrap(),
+          );
+          return;
+        }
+
         if let Some(webview) = manager.get_webview(&label) {
           match parse_invoke_request(&manager, request) {
             Ok(request) => {
+              let respond = CancellableResponder(Some(respond));
+
               #[cfg(feature = "tracing")]
               span.record(
                 "request",
@@ -64,7 +90,7 @@ pub fn get<R: Runtime>(manager: Arc<AppManager<R>>, label: String) -> UriSchemeP
 
               webview.on_message(
                 request,
-                Box::new(move |_webview, _cmd, response, _callback, _error| {
+                Box::new(move |_webview, _cmd, response, meta, _callback, _error| {
                   #[cfg(feature = "tracing")]
                   let _respond_span = tracing::trace_span!(
                     parent: &request_span,
@@ -104,8 +130,9 @@ pub fn get<R: Runtime>(manager: Arc<AppManager<R>>, label: String) -> UriSchemeP
                     CONTENT_TYPE,
                     HeaderValue::from_str(mime_type.essence_str()).unwrap(),
                   );
+                  insert_response_meta(response.headers_mut(), &meta);
 
-                  respond(response);
+                  respond.respond(response);
                 }),
               );
             }
@@ -139,7 +166,9 @@ pub fn get<R: Runtime>(manager: Arc<AppManager<R>>, label: String) -> UriSchemeP
         let mut r = http::Response::new(Vec::new().into());
         r.headers_mut().insert(
           ACCESS_CONTROL_ALLOW_HEADERS,
-          HeaderValue::from_static("Content-Type, Tauri-Callback, Tauri-Error, Tauri-Channel-Id"),
+          HeaderValue::from_static(
+            "Content-Type, Tauri-Callback, Tauri-Error, Tauri-Channel-Id, Tauri-Invoke-Key, Tauri-Sync",
+          ),
         );
         respond(r);
       }
@@ -162,6 +191,99 @@ pub fn get<R: Runtime>(manager: Arc<AppManager<R>>, label: String) -> UriSchemeP
   })
 }
 
+/// Sends the [`ResponseMeta`] in a header exposed to the frontend, since the IPC requests are cross-origin.
+fn insert_response_meta(headers: &mut http::HeaderMap, meta: &ResponseMeta) {
+  if meta.is_empty() {
+    return;
+  }
+  if let Ok(value) = serde_json::to_string(meta)
+    .map_err(|_| ())
+    .and_then(|meta| HeaderValue::from_str(&meta).map_err(|_| ()))
+  {
+    headers.insert(TAURI_RESPONSE_META_HEADER_NAME, value);
+    headers.insert(
+      ACCESS_CONTROL_EXPOSE_HEADERS,
+      HeaderValue::from_static(TAURI_RESPONSE_META_HEADER_NAME),
+    );
+  }
+}
+
+/// Responds to an invoke request, or with `204 No Content` if the response is never sent because the invoke was cancelled.
+///
+/// The frontend ignores the `204` response, see [`crate::ipc::CancellationToken`].
+struct CancellableResponder<F: FnOnce(http::Response<Cow<'static, [u8]>>)>(Option<F>);
+
+impl<F: FnOnce(http::Response<Cow<'static, [u8]>>)> CancellableResponder<F> {
+  fn respond(mut self, response: http::Response<Cow<'static, [u8]>>) {
+    if let Some(respond) = self.0.take() {
+      respond(response);
+    }
+  }
+}
+
+impl<F: FnOnce(http::Response<Cow<'static, [u8]>>)> Drop for CancellableResponder<F> {
+  fn drop(&mut self) {
+    if let Some(respond) = self.0.take() {
+      let mut response = http::Response::new(Vec::new().into());
+      *response.status_mut() = StatusCode::NO_CONTENT;
+      respond(response);
+    }
+  }
+}
+
+/// Handles a [`crate::Builder::sync_command`] call, with the JSON arguments in the `args` query parameter
+/// since the request body is not available on all platforms.
+fn handle_sync_request<R: Runtime>(
+  manager: &AppManager<R>,
+  label: &str,
+  request: http::Request<Vec<u8>>,
+  respond: impl FnOnce(http::Response<Cow<'static, [u8]>>) + Send + 'static,
+) {
+  let json_response = |status: StatusCode, body: &serde_json::Value| {
+    http::Response::builder()
+      .status(status)
+      .header(CONTENT_TYPE, mime::APPLICATION_JSON.essence_str())
+      .body(serde_json::to_vec(body).unwrap().into())
+      .unwrap()
+  };
+
+  let Some(webview) = manager.get_webview(label) else {
+    respond(json_response(
+      StatusCode::BAD_REQUEST,
+      &"failed to acquire webview reference".into(),
+    ));
+    return;
+  };
+
+  let cmd = percent_encoding::percent_decode(&request.uri().path().as_bytes()[1..])
+    .decode_utf8_lossy()
+    .to_string();
+  let args = url::form_urlencoded::parse(request.uri().query().unwrap_or_default().as_bytes())
+    .find(|(key, _)| key == "args")
+    .map(|(_, args)| serde_json::from_str(&args))
+    .unwrap_or(Ok(serde_json::Value::Null));
+  let args = match args {
+    Ok(args) => args,
+    Err(e) => {
+      respond(json_response(
+        StatusCode::BAD_REQUEST,
+        &e.to_string().into(),
+      ));
+      return;
+    }
+  };
+
+  manager
+    .webview
+    .sync_commands
+    .run(&webview, &cmd, args, request.headers(), move |result| {
+      respond(match result {
+        Ok(value) => json_response(StatusCode::OK, &value),
+        Err(e) => json_response(StatusCode::BAD_REQUEST, &e.0),
+      })
+    });
+}
+
 #[cfg(any(target_os = "macos", target_os = "ios", not(ipc_custom_protocol)))]
 fn handle_ipc_message<R: Runtime>(message: String, manager: &AppManager<R>, label: &str) {
   if let Some(webview) = manager.get_webview(label) {
@@ -169,6 +291,19 @@ fn handle_ipc_message<R: Runtime>(message: String, manager: &AppManager<R>, labe
     let _span =
       tracing::trace_span!("ipc::request", kind = "post-message", request = message).entered();
 
+    if let Err(e) = manager.webview.ipc_limits.check_payload(message.len()) {
+      let js = match message_error_callback(&message) {
+        Some(error) => crate::ipc::format_callback::format(error, &e),
+        None => serde_json::to_string(&e)
+          .map(|e| format!("console.error({e})"))
+          .map_err(Into::into),
+      };
+      if let Ok(js) = js {
+        let _ = webview.eval(&js);
+      }
+      return;
+    }
+
     use serde::{Deserialize, Deserializer};
 
     pub(crate) struct HeaderMap(http::HeaderMap);
@@ -263,10 +398,11 @@ fn handle_ipc_message<R: Runtime>(message: String, manager: &AppManager<R>, labe
 
         webview.on_message(
           request,
-          Box::new(move |webview, cmd, response, callback, error| {
+          Box::new(move |webview, cmd, response, meta, callback, error| {
             use crate::ipc::{
               format_callback::{
                 format as format_callback, format_result as format_callback_result,
+                format_with_meta as format_callback_with_meta,
               },
               Channel,
             };
@@ -282,7 +418,7 @@ fn handle_ipc_message<R: Runtime>(message: String, manager: &AppManager<R>, labe
 
             // the channel data command is the only command that uses a custom protocol on Linux
             if webview.manager().webview.invoke_responder.is_none()
-              && cmd != crate::ipc::channel::FETCH_CHANNEL_DATA_COMMAND
+              && &*cmd != crate::ipc::channel::FETCH_CHANNEL_DATA_COMMAND
             {
               fn responder_eval<R: Runtime>(
                 webview: &crate::Webview<R>,
@@ -311,6 +447,21 @@ fn handle_ipc_message<R: Runtime>(message: String, manager: &AppManager<R>, labe
               )
               .entered();
 
+              // channels can't carry the metadata, so the response is evaluated with it
+              if !meta.is_empty() {
+                let js = match &response {
+                  InvokeResponse::Ok(InvokeBody::Json(v)) => {
+                    format_callback_with_meta(callback, v, &meta)
+                  }
+                  InvokeResponse::Ok(InvokeBody::Raw(v)) => {
+                    format_callback_with_meta(callback, v, &meta)
+                  }
+                  InvokeResponse::Err(e) => format_callback_with_meta(error, &e.0, &meta),
+                };
+                responder_eval(&webview, js, error);
+                return;
+              }
+
               match &response {
                 InvokeResponse::Ok(InvokeBody::Json(v)) => {
                   if !(cfg!(target_os = "macos") || cfg!(target_os = "ios"))
@@ -360,6 +511,39 @@ fn handle_ipc_message<R: Runtime>(message: String, manager: &AppManager<R>, labe
   }
 }
 
+/// Reads the error callback of a message without parsing its payload,
+/// which is serialized after the callbacks by the `postMessage` function.
+#[cfg(any(target_os = "macos", target_os = "ios", not(ipc_custom_protocol)))]
+fn message_error_callback(message: &str) -> Option<CallbackFn> {
+  use serde::de::{Deserializer, IgnoredAny, MapAccess, Visitor};
+
+  struct ErrorCallback<'a>(&'a mut Option<CallbackFn>);
+
+  impl<'de> Visitor<'de> for ErrorCallback<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+      f.write_str("an IPC message")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+      while let Some(key) = map.next_key::<String>()? {
+        if key == "error" {
+          self.0.replace(map.next_value()?);
+          return Ok(());
+        }
+        map.next_value::<IgnoredAny>()?;
+      }
+      Ok(())
+    }
+  }
+
+  let mut error = None;
+  // returning before the end of the object fails the deserializer, but the callback is already read
+  let _ = serde_json::Deserializer::from_str(message).deserialize_map(ErrorCallback(&mut error));
+  error
+}
+
 fn parse_invoke_request<R: Runtime>(
   #[allow(unused_variables)] manager: &AppManager<R>,
   request: http::Request<Vec<u8>>,
@@ -416,6 +600,7 @@ fn parse_invoke_request<R: Runtime>(
   let span = tracing::trace_span!("ipc::request::deserialize").entered();
 
   let body = if content_type == mime::APPLICATION_OCTET_STREAM {
+    // moved without a copy, commands can borrow it with `RawBody`
     body.into()
   } else if content_type == mime::APPLICATION_JSON {
     if cfg!(ipc_custom_protocol) {
@@ -443,3 +628,136 @@ fn parse_invoke_request<R: Runtime>(
 
   Ok(payload)
 }
+
+#[cfg(test)]
+mod tests {
+  use std::{borrow::Cow, sync::mpsc::channel};
+
+  use http::StatusCode;
+
+  use crate::{
+    app::UriSchemeResponder,
+    sealed::ManagerBase,
+    test::{mock_builder, mock_context, noop_assets},
+  };
+
+  const LIMIT: usize = 16;
+
+  #[test]
+  fn oversized_payload_is_rejected_before_parsing() {
+    let app = mock_builder()
+      .max_ipc_payload_bytes(LIMIT)
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    let handler = super::get(app.manager_owned(), "main".into());
+
+    let post = |len: usize| -> http::Response<Cow<'static, [u8]>> {
+      let (tx, rx) = channel();
+      let request = http::Request::post("ipc://localhost/cmd")
+        .header("Content-Type", "application/json")
+        .body(vec![b' '; len])
+        .unwrap();
+      handler(
+        request,
+        UriSchemeResponder(Box::new(move |response| tx.send(response).unwrap())),
+      );
+      rx.recv().unwrap()
+    };
+
+    // the body is not JSON, so a parsed request is rejected with another error
+    assert_ne!(post(LIMIT - 1).status(), StatusCode::PAYLOAD_TOO_LARGE);
+    assert_ne!(post(LIMIT).status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+    let response = post(LIMIT + 1);
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(
+      serde_json::from_slice::<serde_json::Value>(response.body()).unwrap(),
+      serde_json::json!({ "kind": "PayloadTooLarge", "limit": LIMIT, "actual": LIMIT + 1 })
+    );
+  }
+
+  #[crate::command(root = "crate")]
+  fn greet() -> &'static str {
+    "hello"
+  }
+
+  #[test]
+  fn shared_session_webviews_use_their_own_allowlist() {
+    use crate::{
+      ipc::AllowList,
+      webview::{invoke_key::INVOKE_KEY_HEADER, SessionId},
+      Manager, WebviewWindowBuilder,
+    };
+
+    let app = mock_builder()
+      .invoke_handler(crate::generate_handler![greet])
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    for (label, allowed) in [("first", "greet"), ("second", "other")] {
+      WebviewWindowBuilder::new(&app, label, Default::default())
+        .shared_session(SessionId::new("shared"))
+        .allowed_commands(AllowList::new().allow(allowed))
+        .build()
+        .unwrap();
+    }
+
+    // the handlers webkit2gtk would register on the web context of the session
+    let invoke = |label: &str| {
+      let webview = app.get_webview_window(label).unwrap();
+      let key = webview
+        .as_ref()
+        .invoke_key
+        .lock()
+        .unwrap()
+        .current()
+        .to_string();
+      let handler = super::get(app.manager_owned(), label.into());
+      let (tx, rx) = channel();
+      let request = http::Request::post("ipc://localhost/greet")
+        .header("Content-Type", "application/json")
+        .header(super::TAURI_CALLBACK_HEADER_NAME, "0")
+        .header(super::TAURI_ERROR_HEADER_NAME, "1")
+        .header(INVOKE_KEY_HEADER, key)
+        .body(b"{}".to_vec())
+        .unwrap();
+      handler(
+        request,
+        UriSchemeResponder(Box::new(move |response| tx.send(response).unwrap())),
+      );
+      let response = rx.recv().unwrap();
+      (
+        response.status(),
+        serde_json::from_slice::<serde_json::Value>(response.body()).unwrap(),
+      )
+    };
+
+    assert_eq!(
+      invoke("first"),
+      (StatusCode::OK, serde_json::json!("hello"))
+    );
+    assert_eq!(
+      invoke("second"),
+      (
+        StatusCode::BAD_REQUEST,
+        serde_json::json!({ "kind": "CommandDenied", "command": "greet" })
+      )
+    );
+  }
+
+  #[cfg(any(target_os = "macos", target_os = "ios", not(ipc_custom_protocol)))]
+  #[test]
+  fn reads_error_callback_before_payload() {
+    use super::{message_error_callback, CallbackFn};
+
+    assert_eq!(
+      message_error_callback(r#"{"cmd":"ping","callback":1,"error":2,"payload":{"data":"#),
+      Some(CallbackFn(2))
+    );
+    assert_eq!(
+      message_error_callback(r#"{"payload":{"a":[1,"}"]},"error":3,"callback":4}"#),
+      Some(CallbackFn(3))
+    );
+    assert_eq!(message_error_callback(r#"{"cmd":"ping"}"#), None);
+    assert_eq!(message_error_callback("[not an object"), None);
+  }
+}
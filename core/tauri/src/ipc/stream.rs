@@ -0,0 +1,413 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Command responses that stream their items to the frontend.
+
+use std::{
+  collections::HashMap,
+  pin::Pin,
+  sync::{Mutex, OnceLock},
+  time::{Duration, Instant},
+};
+
+use futures_util::{
+  future::{AbortHandle, Abortable},
+  Stream, StreamExt,
+};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use super::{Channel, InvokeBody, InvokeError, IpcResponse};
+
+/// The key of the object the frontend receives in place of a [`StreamResponse`].
+pub(crate) const STREAM_DESCRIPTOR_KEY: &str = "__TAURI_STREAM__";
+/// Streams that are not consumed by the frontend in this time are dropped.
+const PENDING_STREAM_TIMEOUT: Duration = Duration::from_secs(30);
+
+type BoxStream = Pin<Box<dyn Stream<Item = Result<InvokeBody, InvokeError>> + Send>>;
+
+/// A stream returned by a command that the frontend did not start consuming yet.
+struct PendingStream {
+  created_at: Instant,
+  /// The label of the webview that invoked the command, set once the response is sent to it, see [`claim`].
+  /// Only this webview can consume or cancel the stream.
+  owner: Option<String>,
+  stream: BoxStream,
+}
+
+/// Streams returned by commands that the frontend did not start consuming yet.
+fn pending_streams() -> &'static Mutex<HashMap<u64, PendingStream>> {
+  static PENDING_STREAMS: OnceLock<Mutex<HashMap<u64, PendingStream>>> = OnceLock::new();
+  PENDING_STREAMS.get_or_init(Default::default)
+}
+
+/// A random stream id, kept under 2^53 so the frontend reads it as an exact number.
+fn stream_id() -> u64 {
+  let mut bytes = [0u8; 8];
+  getrandom::getrandom(&mut bytes).expect("failed to generate the stream id");
+  u64::from_le_bytes(bytes) & ((1 << 53) - 1)
+}
+
+/// Gives the stream of a command response to the webview the response is sent to.
+pub(crate) fn claim(body: &InvokeBody, webview_label: &str) {
+  let InvokeBody::Json(JsonValue::Object(object)) = body else {
+    return;
+  };
+  let Some(id) = object
+    .get(STREAM_DESCRIPTOR_KEY)
+    .and_then(JsonValue::as_u64)
+  else {
+    return;
+  };
+  if let Some(pending) = pending_streams().lock().unwrap().get_mut(&id) {
+    if pending.owner.is_none() {
+      pending.owner.replace(webview_label.to_string());
+    }
+  }
+}
+
+/// A command response that sends the items of a [`Stream`] to the frontend.
+///
+/// The `invoke` promise resolves to an async iterator of the stream items,
+/// which ends when the stream ends or throws the error of a [`Self::try_new`] stream.
+/// The stream is dropped when the webview navigates away or the iterator is closed early,
+/// and only the webview the response is returned to can consume or cancel it.
+///
+/// Commands can also return an `impl Stream<Item = T>` directly,
+/// which is converted with [`Self::new`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tauri::ipc::StreamResponse;
+/// use tokio::io::AsyncBufReadExt;
+///
+/// #[tauri::command]
+/// async fn read_lines(path: std::path::PathBuf) -> Result<StreamResponse, String> {
+///   let file = tokio::fs::File::open(path).await.map_err(|e| e.to_string())?;
+///   let lines = tokio::io::BufReader::new(file).lines();
+///   Ok(StreamResponse::try_new(futures_util::stream::unfold(
+///     lines,
+///     |mut lines| async move {
+///       lines
+///         .next_line()
+///         .await
+///         .map_err(|e| e.to_string())
+///         .transpose()
+///         .map(|line| (line, lines))
+///     },
+///   )))
+/// }
+/// ```
+///
+/// ```javascript
+/// for await (const line of await invoke('read_lines', { path: '/var/log/app.log' })) {
+///   console.log(line)
+/// }
+/// ```
+pub struct StreamResponse {
+  stream: BoxStream,
+}
+
+impl StreamResponse {
+  /// Creates a response from a stream of values.
+  pub fn new<T, S>(stream: S) -> Self
+  where
+    T: IpcResponse,
+    S: Stream<Item = T> + Send + 'static,
+  {
+    Self {
+      stream: Box::pin(stream.map(|item| item.body().map_err(InvokeError::from_error))),
+    }
+  }
+
+  /// Creates a response from a stream of results, ending the stream at the first error.
+  pub fn try_new<T, E, S>(stream: S) -> Self
+  where
+    T: IpcResponse,
+    E: Into<InvokeError>,
+    S: Stream<Item = Result<T, E>> + Send + 'static,
+  {
+    Self {
+      stream: Box::pin(stream.map(|item| {
+        item
+          .map_err(Into::into)
+          .and_then(|item| item.body().map_err(InvokeError::from_error))
+      })),
+    }
+  }
+}
+
+impl IpcResponse for StreamResponse {
+  fn body(self) -> crate::Result<InvokeBody> {
+    let mut pending = pending_streams().lock().unwrap();
+    pending.retain(|_, stream| stream.created_at.elapsed() < PENDING_STREAM_TIMEOUT);
+    let id = loop {
+      let id = stream_id();
+      if !pending.contains_key(&id) {
+        break id;
+      }
+    };
+    pending.insert(
+      id,
+      PendingStream {
+        created_at: Instant::now(),
+        owner: None,
+        stream: self.stream,
+      },
+    );
+    Ok(InvokeBody::Json(
+      serde_json::json!({ STREAM_DESCRIPTOR_KEY: id }),
+    ))
+  }
+}
+
+/// The streams currently sent to the frontend, see [`forward`].
+#[derive(Default)]
+pub(crate) struct ActiveStreams(Mutex<HashMap<u64, (String, AbortHandle)>>);
+
+impl ActiveStreams {
+  /// Stops sending the given stream, or drops it if the frontend did not consume it yet,
+  /// unless it belongs to another webview.
+  pub(crate) fn cancel(&self, id: u64, webview_label: &str) {
+    {
+      let mut pending = pending_streams().lock().unwrap();
+      if pending
+        .get(&id)
+        .is_some_and(|stream| stream.owner.as_deref() == Some(webview_label))
+      {
+        pending.remove(&id);
+      }
+    }
+    let mut active = self.0.lock().unwrap();
+    if active
+      .get(&id)
+      .is_some_and(|(webview, _)| webview == webview_label)
+    {
+      if let Some((_, handle)) = active.remove(&id) {
+        handle.abort();
+      }
+    }
+  }
+
+  /// Stops sending the streams consumed by the given webview.
+  pub(crate) fn cancel_webview(&self, label: &str) {
+    self.0.lock().unwrap().retain(|_, (webview, handle)| {
+      if webview == label {
+        handle.abort();
+        false
+      } else {
+        true
+      }
+    });
+  }
+}
+
+/// The value the frontend receives once a stream is fully sent.
+///
+/// Items are delivered asynchronously so the frontend waits for `count` items before ending the iterator.
+#[derive(Serialize)]
+pub(crate) struct StreamEnd {
+  count: u32,
+  error: Option<JsonValue>,
+}
+
+/// Sends the items of the pending stream with the given id through the channel,
+/// if the stream was returned to the given webview.
+pub(crate) async fn forward(
+  id: u64,
+  channel: Channel,
+  webview_label: String,
+  active: &ActiveStreams,
+) -> Result<StreamEnd, &'static str> {
+  let mut stream = {
+    let mut pending = pending_streams().lock().unwrap();
+    // streams of other webviews are reported as missing so their ids are not revealed
+    if !pending
+      .get(&id)
+      .is_some_and(|stream| stream.owner.as_deref() == Some(webview_label.as_str()))
+    {
+      return Err("stream not found");
+    }
+    pending.remove(&id).unwrap().stream
+  };
+
+  let (handle, registration) = AbortHandle::new_pair();
+  active.0.lock().unwrap().insert(id, (webview_label, handle));
+
+  let result = Abortable::new(
+    async move {
+      let mut count = 0;
+      while let Some(item) = stream.next().await {
+        match item {
+          Ok(body) => {
+            if channel.send(body).is_err() {
+              break;
+            }
+            count += 1;
+          }
+          Err(error) => {
+            return StreamEnd {
+              count,
+              error: Some(error.0),
+            }
+          }
+        }
+      }
+      StreamEnd { count, error: None }
+    },
+    registration,
+  )
+  .await;
+
+  active.0.lock().unwrap().remove(&id);
+  result.map_err(|_| "stream cancelled")
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, Mutex};
+
+  use futures_util::stream;
+
+  use super::{
+    claim, forward, pending_streams, ActiveStreams, StreamResponse, STREAM_DESCRIPTOR_KEY,
+  };
+  use crate::ipc::{Channel, InvokeBody, IpcResponse};
+
+  /// Registers the stream as returned to the `main` webview.
+  fn register(response: StreamResponse) -> u64 {
+    let body = response.body().unwrap();
+    claim(&body, "main");
+    match body {
+      InvokeBody::Json(value) => value[STREAM_DESCRIPTOR_KEY].as_u64().unwrap(),
+      InvokeBody::Raw(_) => unreachable!(),
+    }
+  }
+
+  fn recording_channel() -> (Channel, Arc<Mutex<Vec<serde_json::Value>>>) {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let received_ = received.clone();
+    let channel = Channel::new(move |body| {
+      if let InvokeBody::Json(value) = body {
+        received_.lock().unwrap().push(value);
+      }
+      Ok(())
+    });
+    (channel, received)
+  }
+
+  #[test]
+  fn forwards_items() {
+    let id = register(StreamResponse::new(stream::iter(["a", "b"])));
+    let (channel, received) = recording_channel();
+    let active = ActiveStreams::default();
+
+    let end = crate::async_runtime::block_on(forward(id, channel, "main".into(), &active)).unwrap();
+    assert_eq!(end.count, 2);
+    assert!(end.error.is_none());
+    assert_eq!(*received.lock().unwrap(), vec!["a", "b"]);
+    assert!(!pending_streams().lock().unwrap().contains_key(&id));
+    assert!(active.0.lock().unwrap().is_empty());
+  }
+
+  #[test]
+  fn ends_on_error() {
+    let id = register(StreamResponse::try_new(stream::iter([
+      Ok(1),
+      Err("failed"),
+      Ok(2),
+    ])));
+    let (channel, received) = recording_channel();
+
+    let end = crate::async_runtime::block_on(forward(
+      id,
+      channel,
+      "main".into(),
+      &ActiveStreams::default(),
+    ))
+    .unwrap();
+    assert_eq!(end.count, 1);
+    assert_eq!(end.error, Some("failed".into()));
+    assert_eq!(*received.lock().unwrap(), vec![1]);
+  }
+
+  #[test]
+  fn cancels_on_navigation() {
+    let id = register(StreamResponse::new(stream::pending::<u32>()));
+    let (channel, _) = recording_channel();
+    let active = Arc::new(ActiveStreams::default());
+
+    let active_ = active.clone();
+    let task =
+      crate::async_runtime::spawn(
+        async move { forward(id, channel, "main".into(), &active_).await },
+      );
+    while active.0.lock().unwrap().is_empty() {
+      std::thread::yield_now();
+    }
+    active.cancel_webview("other");
+    assert_eq!(active.0.lock().unwrap().len(), 1);
+    active.cancel_webview("main");
+
+    let result = crate::async_runtime::block_on(task).unwrap();
+    assert!(result.is_err());
+  }
+
+  #[crate::command(root = "crate")]
+  fn numbers() -> impl futures_util::Stream<Item = u32> + Send {
+    stream::iter([1, 2, 3])
+  }
+
+  #[test]
+  fn command_returns_stream() {
+    let _handler: Box<crate::ipc::InvokeHandler<crate::test::MockRuntime>> =
+      Box::new(crate::generate_handler![numbers]);
+  }
+
+  #[test]
+  fn streams_belong_to_their_webview() {
+    let id = register(StreamResponse::new(stream::iter([1])));
+    // claimed once
+    let body = InvokeBody::Json(serde_json::json!({ STREAM_DESCRIPTOR_KEY: id }));
+    claim(&body, "other");
+
+    let active = ActiveStreams::default();
+    active.cancel(id, "other");
+    let (channel, _) = recording_channel();
+    assert_eq!(
+      crate::async_runtime::block_on(forward(id, channel, "other".into(), &active)).err(),
+      Some("stream not found")
+    );
+
+    let (channel, received) = recording_channel();
+    crate::async_runtime::block_on(forward(id, channel, "main".into(), &active)).unwrap();
+    assert_eq!(*received.lock().unwrap(), vec![1]);
+
+    // unclaimed streams can't be consumed
+    let unclaimed = match StreamResponse::new(stream::iter([1])).body().unwrap() {
+      InvokeBody::Json(value) => value[STREAM_DESCRIPTOR_KEY].as_u64().unwrap(),
+      InvokeBody::Raw(_) => unreachable!(),
+    };
+    assert!(unclaimed < 1 << 53);
+    let (channel, _) = recording_channel();
+    assert!(
+      crate::async_runtime::block_on(forward(unclaimed, channel, "main".into(), &active)).is_err()
+    );
+  }
+
+  #[test]
+  fn cancels_pending_stream() {
+    let id = register(StreamResponse::new(stream::iter([1])));
+    ActiveStreams::default().cancel(id, "main");
+    let (channel, _) = recording_channel();
+    assert!(crate::async_runtime::block_on(forward(
+      id,
+      channel,
+      "main".into(),
+      &ActiveStreams::default()
+    ))
+    .is_err());
+  }
+}
@@ -0,0 +1,269 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Commands the frontend calls synchronously, see [`crate::Builder::sync_command`].
+
+use std::{
+  collections::HashMap,
+  sync::{
+    mpsc::{channel, Receiver, Sender},
+    Arc, Mutex, OnceLock,
+  },
+  time::Duration,
+};
+
+use http::HeaderMap;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value as JsonValue;
+
+use super::{InvokeError, IpcError};
+use crate::{webview::invoke_key, Runtime, Webview};
+
+/// The header marking an IPC request as a synchronous command call.
+pub(crate) const SYNC_INVOKE_HEADER: &str = "Tauri-Sync";
+/// Responses larger than this are rejected with [`IpcError::SyncResponseTooLarge`].
+pub(crate) const SYNC_COMMAND_MAX_RESPONSE_SIZE: usize = 64 * 1024;
+/// Commands running longer than this are rejected with [`IpcError::SyncCommandTimeout`].
+pub(crate) const SYNC_COMMAND_TIMEOUT: Duration = Duration::from_millis(100);
+/// The maximum number of threads running the synchronous commands.
+const MAX_POOL_SIZE: usize = 4;
+
+type SyncCommandHandler = dyn Fn(JsonValue) -> Result<JsonValue, InvokeError> + Send + Sync;
+type Job = Box<dyn FnOnce() + Send>;
+
+/// The commands registered with [`crate::Builder::sync_command`].
+#[derive(Default)]
+pub(crate) struct SyncCommands {
+  handlers: HashMap<String, Arc<SyncCommandHandler>>,
+}
+
+impl SyncCommands {
+  pub(crate) fn register<A, T, E, F>(&mut self, name: String, handler: F)
+  where
+    A: DeserializeOwned,
+    T: Serialize,
+    E: Into<InvokeError>,
+    F: Fn(A) -> Result<T, E> + Send + Sync + 'static,
+  {
+    assert!(
+      !self.handlers.contains_key(&name),
+      "sync command '{name}' is already registered",
+    );
+    self.handlers.insert(
+      name,
+      Arc::new(move |args| {
+        let args = serde_json::from_value(args).map_err(InvokeError::from_error)?;
+        let value = handler(args).map_err(Into::into)?;
+        serde_json::to_value(value).map_err(InvokeError::from_error)
+      }),
+    );
+  }
+
+  /// Runs the given command on the sync command pool,
+  /// calling `respond` with its result or an error if it exceeds the time budget or the response size cap.
+  pub(crate) fn run<R: Runtime>(
+    &self,
+    webview: &Webview<R>,
+    cmd: &str,
+    args: JsonValue,
+    headers: &HeaderMap,
+    respond: impl FnOnce(Result<JsonValue, InvokeError>) + Send + 'static,
+  ) {
+    if let Err(e) = authorize(webview, cmd, headers) {
+      respond(Err(e.into()));
+      return;
+    }
+    let Some(handler) = self.handlers.get(cmd).cloned() else {
+      respond(Err(format!("Sync command {cmd} not found").into()));
+      return;
+    };
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let webview = webview.clone();
+    let cmd = cmd.to_string();
+    let cmd_ = cmd.clone();
+    pool().execute(Box::new(move || {
+      let result = super::panic::catch(|| handler(args))
+        .unwrap_or_else(|report| Err(super::panic::on_panic(&webview, &cmd_, report)));
+      let _ = tx.send(result);
+    }));
+
+    crate::async_runtime::spawn(async move {
+      let result = match tokio::time::timeout(SYNC_COMMAND_TIMEOUT, rx).await {
+        Ok(Ok(result)) => result.and_then(|value| check_size(&cmd, value)),
+        Ok(Err(_)) => Err("sync command pool unavailable".into()),
+        Err(_) => Err(
+          IpcError::SyncCommandTimeout {
+            command: cmd,
+            millis: SYNC_COMMAND_TIMEOUT.as_millis() as u64,
+          }
+          .into(),
+        ),
+      };
+      respond(result);
+    });
+  }
+}
+
+/// Applies the same checks as the asynchronous IPC before a sync command runs.
+fn authorize<R: Runtime>(
+  webview: &Webview<R>,
+  cmd: &str,
+  headers: &HeaderMap,
+) -> Result<(), IpcError> {
+  use crate::sealed::ManagerBase;
+
+  if let Some(validator) = &webview.manager().webview.ipc_origin_validator {
+    validator(headers, webview).map_err(|message| IpcError::OriginDenied { message })?;
+  }
+
+  let key = headers
+    .get(invoke_key::INVOKE_KEY_HEADER)
+    .and_then(|key| key.to_str().ok());
+  if !webview.invoke_key.lock().unwrap().validate(key) {
+    return Err(IpcError::InvalidInvokeKey);
+  }
+
+  if !webview.is_command_allowed(cmd) {
+    return Err(IpcError::CommandDenied {
+      command: cmd.to_string(),
+    });
+  }
+
+  Ok(())
+}
+
+fn check_size(cmd: &str, value: JsonValue) -> Result<JsonValue, InvokeError> {
+  let size = serde_json::to_vec(&value)
+    .map_err(InvokeError::from_error)?
+    .len();
+  if size > SYNC_COMMAND_MAX_RESPONSE_SIZE {
+    return Err(
+      IpcError::SyncResponseTooLarge {
+        command: cmd.to_string(),
+        size,
+        limit: SYNC_COMMAND_MAX_RESPONSE_SIZE,
+      }
+      .into(),
+    );
+  }
+  Ok(value)
+}
+
+/// The threads running the sync commands, so they never block the main thread or the async runtime.
+struct ThreadPool {
+  sender: Mutex<Sender<Job>>,
+}
+
+impl ThreadPool {
+  fn new(size: usize) -> Self {
+    let (sender, receiver) = channel::<Job>();
+    let receiver = Arc::new(Mutex::new(receiver));
+    for i in 0..size {
+      let receiver = receiver.clone();
+      std::thread::Builder::new()
+        .name(format!("tauri-sync-ipc-{i}"))
+        .spawn(move || worker(&receiver))
+        .expect("failed to spawn the sync command thread");
+    }
+    Self {
+      sender: Mutex::new(sender),
+    }
+  }
+
+  fn execute(&self, job: Job) {
+    let _ = self.sender.lock().unwrap().send(job);
+  }
+}
+
+fn worker(receiver: &Mutex<Receiver<Job>>) {
+  loop {
+    let job = receiver.lock().unwrap().recv();
+    match job {
+      Ok(job) => job(),
+      Err(_) => break,
+    }
+  }
+}
+
+fn pool() -> &'static ThreadPool {
+  static POOL: OnceLock<ThreadPool> = OnceLock::new();
+  POOL.get_or_init(|| {
+    let size = std::thread::available_parallelism()
+      .map(|n| n.get())
+      .unwrap_or(1)
+      .min(MAX_POOL_SIZE);
+    ThreadPool::new(size)
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{sync::mpsc::channel, time::Duration};
+
+  use serde_json::json;
+
+  use super::{check_size, SyncCommands, SYNC_COMMAND_MAX_RESPONSE_SIZE};
+  use crate::{test::mock_app, webview::invoke_key::INVOKE_KEY_HEADER, WebviewWindowBuilder};
+
+  fn run(commands: &SyncCommands, cmd: &str, args: serde_json::Value) -> serde_json::Value {
+    let app = mock_app();
+    let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap()
+      .webview;
+    let key = webview.invoke_key.lock().unwrap().current().to_string();
+    let mut headers = http::HeaderMap::new();
+    headers.insert(INVOKE_KEY_HEADER, key.parse().unwrap());
+
+    let (tx, rx) = channel();
+    commands.run(&webview, cmd, args, &headers, move |result| {
+      let _ = tx.send(result);
+    });
+    match rx.recv_timeout(Duration::from_secs(5)).unwrap() {
+      Ok(value) => value,
+      Err(e) => json!({ "error": e.0 }),
+    }
+  }
+
+  #[test]
+  fn runs_command() {
+    let mut commands = SyncCommands::default();
+    commands.register("add".into(), |(a, b): (u32, u32)| Ok::<_, ()>(a + b));
+    assert_eq!(run(&commands, "add", json!([1, 2])), json!(3));
+    assert_eq!(
+      run(&commands, "missing", json!(null)),
+      json!({ "error": "Sync command missing not found" })
+    );
+  }
+
+  #[test]
+  fn rejects_slow_command() {
+    let mut commands = SyncCommands::default();
+    commands.register("slow".into(), |()| {
+      std::thread::sleep(Duration::from_secs(1));
+      Ok::<_, ()>(())
+    });
+    assert_eq!(
+      run(&commands, "slow", json!(null))["error"]["kind"],
+      "SyncCommandTimeout"
+    );
+  }
+
+  #[test]
+  fn rejects_large_response() {
+    let value = json!("a".repeat(SYNC_COMMAND_MAX_RESPONSE_SIZE));
+    let error = check_size("large", value).unwrap_err();
+    assert_eq!(error.0["kind"], "SyncResponseTooLarge");
+    assert!(check_size("small", json!("a")).is_ok());
+  }
+
+  #[test]
+  #[should_panic]
+  fn rejects_duplicate_command() {
+    let mut commands = SyncCommands::default();
+    commands.register("get".into(), |()| Ok::<_, ()>(()));
+    commands.register("get".into(), |()| Ok::<_, ()>(()));
+  }
+}
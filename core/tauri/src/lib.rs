@@ -17,6 +17,7 @@
 //! - **unstable**: Enables unstable features. Be careful, it might introduce breaking changes in future minor releases.
 //! - **tracing**: Enables [`tracing`](https://docs.rs/tracing/latest/tracing) for window startup, plugins, `Window::eval`, events, IPC, updater and custom protocol request handlers.
 //! - **test**: Enables the [`mod@test`] module exposing unit test helpers.
+//! - **command-manifest**: Records the commands registered with [`generate_handler!`] and their arguments, see [`AppHandle::command_manifest`].
 //! - **objc-exception**: Wrap each msg_send! in a @try/@catch and panics if an exception is caught, preventing Objective-C from unwinding into Rust.
 //! - **linux-ipc-protocol**: Use custom protocol for faster IPC on Linux. Requires webkit2gtk v2.40 or above.
 //! - **linux-libxdo**: Enables linking to libxdo which enables Cut, Copy, Paste and SelectAll menu items to work on Linux.
@@ -31,12 +32,28 @@
 //! - **tray-icon**: Enables application tray icon APIs. Enabled by default if the `trayIcon` config is defined on the `tauri.conf.json` file.
 //! - **macos-private-api**: Enables features only available in **macOS**'s private APIs, currently the `transparent` window functionality and the `fullScreenEnabled` preference setting to `true`. Enabled by default if the `tauri > macosPrivateApi` config flag is set to `true` on the `tauri.conf.json` file.
 //! - **webview-data-url**: Enables usage of data URLs on the webview.
+//! - **lenient-labels**: Also accepts the window and webview labels allowed by previous versions, which can include any alphanumeric character and `/`. Meant to migrate existing apps, see [`window::validate_label`].
 //! - **compression** *(enabled by default): Enables asset compression. You should only disable this if you want faster compile times in release builds - it produces larger binaries.
 //! - **config-json5**: Adds support to JSON5 format for `tauri.conf.json`.
 //! - **config-toml**: Adds support to TOML format for the configuration `Tauri.toml`.
 //! - **icon-ico**: Adds support to set `.ico` window icons. Enables [`Icon::File`] and [`Icon::Raw`] variants.
 //! - **icon-png**: Adds support to set `.png` window icons. Enables [`Icon::File`] and [`Icon::Raw`] variants.
 //! - **macos-proxy**: Adds support for [`WebviewBuilder::proxy_url`] on macOS. Requires macOS 14+.
+//! - **path-watch**: Enables [`path::PathResolver::watch`] to watch the known directories for changes.
+//! - **remote-automation**: Starts a WebSocket server on `127.0.0.1` that end-to-end test harnesses can use to drive the webviews, see the [`automation`] module.
+//!   Never enable it on production builds.
+//! - **devtools-protocol**: Enables [`Builder::remote_debugging_port`] so tools like Playwright or Puppeteer can attach to the webviews,
+//!   and [`webview::Webview::devtools_protocol`] to call the Chrome DevTools Protocol from Rust. Never enable the remote debugging server on production builds.
+//! - **ipc-inspector**: Enables [`AppHandle::open_ipc_inspector`] in debug builds, opening a window that lists the command invokes and emitted events live.
+//!   Has no effect in release builds.
+//! - **update-check**: Enables [`AppHandle::check_update`] to check for updates with the manifests and signing keys of the updater plugin, without downloading or installing them.
+//! - **dev-dynamic-assets**: In development, reads the app assets from the `frontendDist` directory instead of the embedded ones
+//!   and emits the `tauri://asset-changed` event with the asset `path` when one changes, so the frontend can reload.
+//!   Has no effect in production builds or when a `devUrl` is used.
+//! - **dev-config-reload**: In development, watches the Tauri config file and applies the changes of the `app > windows` title,
+//!   size, size constraints, decorations, always on top and theme to the open windows, emitting the `tauri://config-reloaded` event
+//!   with the `applied` and `skipped` fields of each window. Other fields and added or removed windows require a restart.
+//!   Has no effect in production builds.
 //!
 //! ## Cargo allowlist features
 //!
@@ -79,6 +96,10 @@ pub use tauri_macros::{command, generate_handler};
 
 pub(crate) mod app;
 pub mod async_runtime;
+#[cfg(feature = "remote-automation")]
+#[cfg_attr(docsrs, doc(cfg(feature = "remote-automation")))]
+pub mod automation;
+pub mod diagnostics;
 mod error;
 mod event;
 pub mod ipc;
@@ -98,6 +119,7 @@ pub mod menu;
 /// Path APIs.
 pub mod path;
 pub mod process;
+pub mod profile;
 /// The allowlist scopes.
 pub mod scope;
 mod state;
@@ -105,6 +127,9 @@ mod state;
 #[cfg(all(desktop, feature = "tray-icon"))]
 #[cfg_attr(docsrs, doc(cfg(all(desktop, feature = "tray-icon"))))]
 pub mod tray;
+#[cfg(feature = "update-check")]
+#[cfg_attr(docsrs, doc(cfg(feature = "update-check")))]
+pub mod update;
 pub use tauri_utils as utils;
 
 pub use http;
@@ -185,7 +210,7 @@ pub use tauri_runtime_wry::{tao, wry};
 /// A task to run on the main thread.
 pub type SyncTask = Box<dyn FnOnce() + Send>;
 
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::{
   collections::HashMap,
   fmt::{self, Debug},
@@ -193,9 +218,36 @@ use std::{
 };
 use utils::acl::resolved::Resolved;
 
+/// Gets the version of the webview installed on the system.
+///
+/// See [`AppHandle::runtime_info`] for a report of the environment the application runs in.
 #[cfg(feature = "wry")]
 #[cfg_attr(docsrs, doc(cfg(feature = "wry")))]
-pub use tauri_runtime_wry::webview_version;
+pub fn webview_version() -> Result<String> {
+  <Wry as runtime::Runtime<EventLoopMessage>>::webview_version().map_err(Into::into)
+}
+
+/// Detects whether the webview runtime is installed and at least [`webview::MINIMUM_WEBVIEW_VERSION`].
+///
+/// It can be called before the app is built, e.g. to run a bundled installer,
+/// see [`Builder::on_webview_runtime_missing`] to handle a missing runtime when building the app.
+///
+/// ## Platform-specific
+///
+/// - **Linux**: The app links the webkit2gtk library, so a missing library fails before `main` runs
+///   and the status can only report an outdated library.
+#[cfg(feature = "wry")]
+#[cfg_attr(docsrs, doc(cfg(feature = "wry")))]
+pub fn webview_runtime_status() -> webview::RuntimeStatus {
+  webview_runtime_status_with_minimum(webview::MINIMUM_WEBVIEW_VERSION)
+}
+
+/// Detects whether the webview runtime is installed and at least the given version, see [`webview_runtime_status`].
+#[cfg(feature = "wry")]
+#[cfg_attr(docsrs, doc(cfg(feature = "wry")))]
+pub fn webview_runtime_status_with_minimum(minimum: Option<&str>) -> webview::RuntimeStatus {
+  webview::RuntimeStatus::detect::<Wry>(minimum)
+}
 
 #[cfg(target_os = "macos")]
 #[cfg_attr(docsrs, doc(cfg(target_os = "macos")))]
@@ -204,15 +256,23 @@ pub use runtime::ActivationPolicy;
 #[cfg(target_os = "macos")]
 pub use self::utils::TitleBarStyle;
 
-pub use self::event::{Event, EventId, EventTarget};
+pub use self::event::{
+  Event, EventDecodeError, EventId, EventName, EventTarget, EventTransport, ListenerGuard,
+  RecordedEvent, RecorderConfig, DEFAULT_MAX_RECORDED_EVENTS, DEFAULT_MAX_RECORDED_PAYLOAD_BYTES,
+  MAX_STICKY_PAYLOAD_SIZE,
+};
 pub use {
-  self::app::{App, AppHandle, AssetResolver, Builder, CloseRequestApi, RunEvent, WindowEvent},
-  self::manager::Asset,
+  self::app::state_init::StateInitError,
+  self::app::{
+    App, AppHandle, AssetResolver, Builder, CloseRequestApi, ProtocolRequestContext, RunEvent,
+    RuntimeInfo, WebviewEvent, WindowEvent,
+  },
+  self::manager::{labels::LABEL_RESERVATION_TIMEOUT, Asset},
   self::runtime::{
     webview::WebviewAttributes,
     window::{
       dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize, Pixel, Position, Size},
-      CursorIcon, FileDropEvent,
+      CursorIcon, FileDropEvent, ProcessCrashReason,
     },
     DeviceEventFilter, UserAttentionType,
   },
@@ -429,6 +489,10 @@ pub struct Context<A: Assets> {
   pub(crate) _info_plist: (),
   pub(crate) pattern: Pattern,
   pub(crate) resolved_acl: Resolved,
+  #[cfg(all(dev, feature = "dev-dynamic-assets"))]
+  pub(crate) frontend_dist_dir: Option<std::path::PathBuf>,
+  #[cfg(all(dev, desktop, feature = "dev-config-reload"))]
+  pub(crate) config_dir: Option<std::path::PathBuf>,
 }
 
 impl<A: Assets> fmt::Debug for Context<A> {
@@ -553,6 +617,10 @@ impl<A: Assets> Context<A> {
       _info_plist: info_plist,
       pattern,
       resolved_acl,
+      #[cfg(all(dev, feature = "dev-dynamic-assets"))]
+      frontend_dist_dir: None,
+      #[cfg(all(dev, desktop, feature = "dev-config-reload"))]
+      config_dir: None,
     }
   }
 
@@ -564,6 +632,24 @@ impl<A: Assets> Context<A> {
     self.tray_icon.replace(icon);
   }
 
+  /// Sets the `frontendDist` directory the assets are read from when the `dev-dynamic-assets` feature is enabled.
+  #[doc(hidden)]
+  #[inline(always)]
+  #[allow(unused_variables)]
+  pub fn set_frontend_dist_dir(&mut self, dir: std::path::PathBuf) {
+    #[cfg(all(dev, feature = "dev-dynamic-assets"))]
+    self.frontend_dist_dir.replace(dir);
+  }
+
+  /// Sets the directory of the config file watched when the `dev-config-reload` feature is enabled.
+  #[doc(hidden)]
+  #[inline(always)]
+  #[allow(unused_variables)]
+  pub fn set_config_dir(&mut self, dir: std::path::PathBuf) {
+    #[cfg(all(dev, desktop, feature = "dev-config-reload"))]
+    self.config_dir.replace(dir);
+  }
+
   /// Sets the app shell scope.
   #[cfg(shell_scope)]
   #[inline(always)]
@@ -633,7 +719,7 @@ pub trait Manager<R: Runtime>: sealed::ManagerBase<R> {
   ///       println!("app is ready");
   ///
   ///       // we no longer need to listen to the event
-  ///       // we also could have used `app.once_global` instead
+  ///       // we also could have used `app.once_any` instead
   ///       handle.unlisten(event.id());
   ///     });
   ///
@@ -648,6 +734,39 @@ pub trait Manager<R: Runtime>: sealed::ManagerBase<R> {
     self.manager().unlisten(id)
   }
 
+  /// Listen to an emitted event to any [target](EventTarget) until the returned guard is dropped.
+  ///
+  /// Use it for the listeners registered by short-lived code such as commands,
+  /// which would otherwise stay registered for the lifetime of the app.
+  ///
+  /// # Examples
+  /// ```
+  /// use tauri::Manager;
+  ///
+  /// #[tauri::command]
+  /// async fn wait_for_upload(app: tauri::AppHandle) {
+  ///   let (tx, rx) = tokio::sync::oneshot::channel();
+  ///   let tx = std::sync::Mutex::new(Some(tx));
+  ///   let _listener = app.listen_scoped("upload-finished", move |_event| {
+  ///     if let Some(tx) = tx.lock().unwrap().take() {
+  ///       let _ = tx.send(());
+  ///     }
+  ///   });
+  ///   let _ = rx.await;
+  ///   // the listener is removed here
+  /// }
+  /// ```
+  fn listen_scoped<F>(&self, event: impl Into<String>, handler: F) -> ListenerGuard
+  where
+    F: Fn(Event) + Send + 'static,
+  {
+    let id = self
+      .manager()
+      .listen(event.into(), EventTarget::Any, handler);
+    let listeners = self.manager().listeners().clone();
+    ListenerGuard::new(id, move || listeners.unlisten(id))
+  }
+
   /// Listens once to an emitted event to any [target](EventTarget) .
   ///
   /// See [`Self::listen_any`] for more information.
@@ -658,8 +777,50 @@ pub trait Manager<R: Runtime>: sealed::ManagerBase<R> {
     self.manager().once(event.into(), EventTarget::Any, handler)
   }
 
+  /// Listen to a typed event emitted to any [target](EventTarget), see [`EventName`].
+  ///
+  /// The handler receives the deserialized payload, or the error describing why it does not match the type.
+  ///
+  /// # Examples
+  /// ```
+  /// use tauri::{EventName, Manager};
+  ///
+  /// #[derive(Clone, serde::Serialize, serde::Deserialize)]
+  /// struct DownloadProgress {
+  ///   downloaded: u64,
+  /// }
+  ///
+  /// impl EventName for DownloadProgress {
+  ///   const NAME: &'static str = "download-progress";
+  /// }
+  ///
+  /// tauri::Builder::default()
+  ///   .setup(|app| {
+  ///     app.listen_typed(|progress: Result<DownloadProgress, _>| match progress {
+  ///       Ok(progress) => println!("downloaded {} bytes", progress.downloaded),
+  ///       Err(e) => eprintln!("{e}"),
+  ///     });
+  ///     app.emit_typed(&DownloadProgress { downloaded: 0 })?;
+  ///     Ok(())
+  ///   });
+  /// ```
+  fn listen_typed<E, F>(&self, handler: F) -> EventId
+  where
+    E: EventName + DeserializeOwned,
+    F: Fn(std::result::Result<E, EventDecodeError>) + Send + 'static,
+  {
+    self
+      .manager()
+      .listen(E::NAME.into(), EventTarget::Any, move |event| {
+        handler(event.typed())
+      })
+  }
+
   /// Emits an event to all [targets](EventTarget).
   ///
+  /// The JS listeners observe the events with the same name in the order they were emitted,
+  /// even when they are emitted from different threads.
+  ///
   /// # Examples
   /// ```
   /// use tauri::Manager;
@@ -678,8 +839,76 @@ pub trait Manager<R: Runtime>: sealed::ManagerBase<R> {
     self.manager().emit(event, payload)
   }
 
+  /// Queues an event to all [targets](EventTarget), delivering the queued payloads together
+  /// at the interval set with [`Builder::event_batching`].
+  ///
+  /// Use it for high-frequency events such as progress reports:
+  /// each webview receives the payloads of a batch with a single script instead of one script per event,
+  /// and its listeners are called with each payload in emit order.
+  /// An event emitted with the other `emit` functions delivers the queued payloads of the same event first.
+  ///
+  /// # Examples
+  /// ```
+  /// use tauri::Manager;
+  ///
+  /// #[tauri::command]
+  /// fn download(app: tauri::AppHandle) {
+  ///   for chunk in 0..1000 {
+  ///     app.emit_batched("download-progress", chunk).unwrap();
+  ///   }
+  ///   // deliver the remaining progress events right away
+  ///   app.flush_events().unwrap();
+  /// }
+  /// ```
+  fn emit_batched<S: Serialize>(&self, event: &str, payload: S) -> Result<()> {
+    self
+      .manager()
+      .emit_batched(self.app_handle(), event, payload)
+  }
+
+  /// Delivers the queued payloads of the events emitted with [`Self::emit_batched`] without waiting for their interval.
+  ///
+  /// The payloads are also delivered when a webview is destroyed.
+  fn flush_events(&self) -> Result<()> {
+    self.manager().flush_events()
+  }
+
+  /// Emits an event to all [targets](EventTarget) and stores its payload,
+  /// delivering it to the JavaScript listeners registered later until [`Self::clear_sticky`] is called.
+  ///
+  /// Useful for events emitted before the webview is loaded, such as in the setup hook.
+  /// Each new listener receives the stored payload once, before any event emitted after it is registered.
+  /// Only the last payload of each event is stored and it must not exceed [`MAX_STICKY_PAYLOAD_SIZE`] once serialized.
+  ///
+  /// # Examples
+  /// ```
+  /// use tauri::Manager;
+  ///
+  /// tauri::Builder::default()
+  ///   .setup(|app| {
+  ///     // received by `listen('deep-link', ...)` even if the page is not loaded yet
+  ///     app.emit_sticky("deep-link", "myapp://open?id=1")?;
+  ///     Ok(())
+  ///   });
+  /// ```
+  fn emit_sticky<S: Serialize + Clone>(&self, event: &str, payload: S) -> Result<()> {
+    self.manager().emit_sticky(event, payload)
+  }
+
+  /// Removes the stored payload of a sticky event, see [`Self::emit_sticky`].
+  fn clear_sticky(&self, event: &str) {
+    self.manager().listeners().clear_sticky(event)
+  }
+
+  /// Emits a typed event to all [targets](EventTarget), see [`EventName`] and [`Self::listen_typed`].
+  fn emit_typed<E: EventName + Serialize + Clone>(&self, payload: &E) -> Result<()> {
+    self.manager().emit(E::NAME, payload)
+  }
+
   /// Emits an event to all [targets](EventTarget) matching the given target.
   ///
+  /// See [`EventTarget`] for the listeners reached by each target.
+  ///
   /// # Examples
   /// ```
   /// use tauri::{Manager, EventTarget};
@@ -695,7 +924,11 @@ pub trait Manager<R: Runtime>: sealed::ManagerBase<R> {
   ///     // emit an event to any webview/window/webviewWindow matching the given label
   ///     app.emit_to("updater", "download-progress", i); // similar to using EventTarget::labeled
   ///     app.emit_to(EventTarget::labeled("updater"), "download-progress", i);
-  ///     // emit an event to listeners that used WebviewWindow::listen
+  ///     // emit an event to listeners that used Window::listen, but not to its webviews
+  ///     app.emit_to(EventTarget::window("updater"), "download-progress", i);
+  ///     // emit an event to listeners that used Webview::listen
+  ///     app.emit_to(EventTarget::webview("updater"), "download-progress", i);
+  ///     // emit an event to the listeners of the window and all of its webviews
   ///     app.emit_to(EventTarget::webview_window("updater"), "download-progress", i);
   ///   }
   /// }
@@ -713,21 +946,7 @@ pub trait Manager<R: Runtime>: sealed::ManagerBase<R> {
     #[cfg(feature = "tracing")]
     tracing::Span::current().record("target", format!("{target:?}"));
 
-    self.manager().emit_filter(event, payload, |s| match s {
-      t @ EventTarget::Window { label }
-      | t @ EventTarget::Webview { label }
-      | t @ EventTarget::WebviewWindow { label } => {
-        if let EventTarget::AnyLabel {
-          label: target_label,
-        } = &target
-        {
-          label == target_label
-        } else {
-          t == &target
-        }
-      }
-      t => t == &target,
-    })
+    self.manager().emit_to(target, event, payload)
   }
 
   /// Emits an event to all [targets](EventTarget) based on the given filter.
@@ -795,6 +1014,34 @@ pub trait Manager<R: Runtime>: sealed::ManagerBase<R> {
     self.manager().webviews()
   }
 
+  /// Returns a `<prefix>-<n>` label that is not used by any window or webview, e.g. `dialog-1`,
+  /// with `n` incremented for each call with the same prefix.
+  ///
+  /// The label is reserved for the calling thread until a window or webview is built with it,
+  /// building it from another thread fails with [`Error::LabelReserved`].
+  /// The reservation expires after [`LABEL_RESERVATION_TIMEOUT`].
+  /// Async tasks can move to another thread before building,
+  /// use [`WebviewWindowBuilder::new_unique`] to reserve the label for the builder instead.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use tauri::Manager;
+  ///
+  /// #[tauri::command]
+  /// fn open_document(app: tauri::AppHandle) -> tauri::Result<String> {
+  ///   let label = app.unique_label("document");
+  ///   tauri::WebviewWindowBuilder::new(&app, &label, Default::default()).build()?;
+  ///   Ok(label)
+  /// }
+  /// ```
+  fn unique_label(&self, prefix: &str) -> String {
+    let manager = self.manager();
+    manager
+      .labels
+      .reserve(prefix, |label| manager.is_label_used(label))
+  }
+
   /// Fetch a single webview window from the manager.
   fn get_webview_window(&self, label: &str) -> Option<WebviewWindow<R>> {
     self.manager().get_webview(label).and_then(|webview| {
@@ -950,6 +1197,14 @@ pub trait Manager<R: Runtime>: sealed::ManagerBase<R> {
     self.state::<Env>().inner().clone()
   }
 
+  /// Gets the file system scope, changed at runtime to control the paths the commands can access.
+  ///
+  /// Its changes are emitted to all targets with the [`scope::FS_SCOPE_CHANGED_EVENT`] event
+  /// and persisted across launches when [`Builder::persist_fs_scope`] is set.
+  fn fs_scope(&self) -> scope::fs::Scope {
+    self.state::<Scopes>().inner().fs.clone()
+  }
+
   /// Gets the scope for the asset protocol.
   #[cfg(feature = "protocol-asset")]
   fn asset_protocol_scope(&self) -> scope::fs::Scope {
@@ -960,6 +1215,35 @@ pub trait Manager<R: Runtime>: sealed::ManagerBase<R> {
   fn path(&self) -> &crate::path::PathResolver<R> {
     self.state::<crate::path::PathResolver<R>>().inner()
   }
+
+  /// The origin of the pages served by the custom protocol with the given scheme on the current platform,
+  /// `http://<scheme>.localhost` on Windows and Android and `<scheme>://localhost` on the other platforms.
+  ///
+  /// Use it to build the CSP sources or the `postMessage` target origin of a custom protocol page.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `scheme` is not a valid URL scheme.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use tauri::Manager;
+  ///
+  /// tauri::Builder::default()
+  ///   .register_uri_scheme_protocol("doom", |_app, _request| {
+  ///     tauri::http::Response::new(b"".to_vec())
+  ///   })
+  ///   .setup(|app| {
+  ///     let origin = app.protocol_origin("doom");
+  ///     println!("connect-src {}", origin.origin().ascii_serialization());
+  ///     Ok(())
+  ///   });
+  /// ```
+  fn protocol_origin(&self, scheme: &str) -> url::Url {
+    crate::ipc::origin::protocol_origin(scheme)
+      .unwrap_or_else(|| panic!("`{scheme}` is not a valid URL scheme"))
+  }
 }
 
 /// Prevent implementation details from leaking out of the [`Manager`] trait.
@@ -0,0 +1,128 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! In-memory cache of the decompressed assets, see the `app > assetCache` configuration.
+
+use std::{
+  collections::{BTreeMap, HashMap},
+  sync::{Arc, Mutex},
+};
+
+struct CachedAsset {
+  bytes: Arc<[u8]>,
+  last_used: u64,
+}
+
+#[derive(Default)]
+struct CacheState {
+  assets: HashMap<String, CachedAsset>,
+  // maps the last use of each asset to its key, the first entry being the least recently used
+  recency: BTreeMap<u64, String>,
+  size: usize,
+  clock: u64,
+}
+
+/// A least recently used cache of asset contents, bounded by the total size of the cached assets.
+pub(crate) struct AssetCache {
+  max_size: usize,
+  state: Mutex<CacheState>,
+}
+
+impl AssetCache {
+  pub(crate) fn new(max_size: usize) -> Self {
+    Self {
+      max_size,
+      state: Default::default(),
+    }
+  }
+
+  /// Gets the cached asset, marking it as the most recently used.
+  pub(crate) fn get(&self, key: &str) -> Option<Arc<[u8]>> {
+    let mut state = self.state.lock().unwrap();
+    state.clock += 1;
+    let clock = state.clock;
+    let asset = state.assets.get_mut(key)?;
+    let last_used = std::mem::replace(&mut asset.last_used, clock);
+    let bytes = asset.bytes.clone();
+    state.recency.remove(&last_used);
+    state.recency.insert(clock, key.into());
+    Some(bytes)
+  }
+
+  /// Caches the asset, evicting the least recently used assets that no longer fit.
+  ///
+  /// Assets larger than the cache are not cached.
+  pub(crate) fn insert(&self, key: &str, bytes: Arc<[u8]>) {
+    if bytes.len() > self.max_size {
+      return;
+    }
+
+    let mut state = self.state.lock().unwrap();
+    state.clock += 1;
+    let clock = state.clock;
+    if let Some(previous) = state.assets.remove(key) {
+      state.recency.remove(&previous.last_used);
+      state.size -= previous.bytes.len();
+    }
+
+    while state.size + bytes.len() > self.max_size {
+      let Some((_, evicted)) = state.recency.pop_first() else {
+        break;
+      };
+      if let Some(evicted) = state.assets.remove(&evicted) {
+        state.size -= evicted.bytes.len();
+      }
+    }
+
+    state.size += bytes.len();
+    state.recency.insert(clock, key.into());
+    state.assets.insert(
+      key.into(),
+      CachedAsset {
+        bytes,
+        last_used: clock,
+      },
+    );
+  }
+
+  /// The total size of the cached assets.
+  #[cfg(test)]
+  fn size(&self) -> usize {
+    self.state.lock().unwrap().size
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::AssetCache;
+
+  #[test]
+  fn evicts_least_recently_used() {
+    let cache = AssetCache::new(10);
+    cache.insert("/a.js", vec![0; 4].into());
+    cache.insert("/b.js", vec![1; 4].into());
+    // `/a.js` is now more recent than `/b.js`
+    assert_eq!(cache.get("/a.js").as_deref(), Some(&[0; 4][..]));
+
+    cache.insert("/c.js", vec![2; 4].into());
+    assert!(cache.get("/b.js").is_none());
+    assert!(cache.get("/a.js").is_some());
+    assert!(cache.get("/c.js").is_some());
+    assert_eq!(cache.size(), 8);
+
+    // replacing an asset does not count its previous size
+    cache.insert("/c.js", vec![3; 6].into());
+    assert_eq!(cache.get("/c.js").as_deref(), Some(&[3; 6][..]));
+    assert_eq!(cache.size(), 10);
+
+    // too large to be cached
+    cache.insert("/d.js", vec![4; 11].into());
+    assert!(cache.get("/d.js").is_none());
+    assert_eq!(cache.size(), 10);
+
+    let disabled = AssetCache::new(0);
+    disabled.insert("/a.js", vec![0; 1].into());
+    assert!(disabled.get("/a.js").is_none());
+  }
+}
@@ -0,0 +1,292 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Applies the changes of the `app > windows` config to the open windows, enabled with the `dev-config-reload` feature.
+
+use std::{
+  collections::BTreeSet,
+  path::{Path, PathBuf},
+  sync::Mutex,
+};
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri_utils::{
+  config::{
+    parse::{is_configuration_file, read_from},
+    WindowConfig,
+  },
+  debug_eprintln,
+  platform::Target,
+};
+
+use crate::{
+  path::{canonicalize_lenient, PathEvent, WatchOptions, WatcherHandle},
+  sealed::ManagerBase,
+  AppHandle, LogicalSize, Manager, Runtime, Window,
+};
+
+/// The event emitted to all targets when the config file changes,
+/// with the window config fields that were applied and skipped.
+const CONFIG_RELOADED_EVENT: &str = "tauri://config-reloaded";
+
+/// The window config fields that are applied to the open windows, other changed fields are skipped.
+const RELOADABLE_FIELDS: &[&str] = &[
+  "title",
+  "width",
+  "height",
+  "minWidth",
+  "minHeight",
+  "maxWidth",
+  "maxHeight",
+  "decorations",
+  "alwaysOnTop",
+  "theme",
+];
+
+/// The changed fields of a window config.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct WindowConfigChanges {
+  label: String,
+  applied: Vec<String>,
+  skipped: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ConfigReloadedPayload {
+  windows: Vec<WindowConfigChanges>,
+  /// Labels of the windows added to or removed from the config, which are not created nor closed.
+  skipped_windows: Vec<String>,
+}
+
+/// The window configs the open windows were last updated with.
+struct ConfigReloadState {
+  windows: Mutex<Vec<WindowConfig>>,
+  _watcher: WatcherHandle,
+}
+
+/// Watches the directory of the config file and applies the window config changes to the open windows.
+pub(crate) fn watch<R: Runtime>(app: &AppHandle<R>, dir: &Path) -> crate::Result<()> {
+  let dir = canonicalize_lenient(dir)?;
+  let handle = app.clone();
+  let config_dir = dir.clone();
+  let watcher = app
+    .path()
+    .watch_with_options(&dir, WatchOptions::default(), move |event| {
+      let path = match event {
+        PathEvent::Created { path } | PathEvent::Modified { path } => path,
+        PathEvent::Renamed { to, .. } => to,
+        PathEvent::Removed { .. } => return,
+      };
+      if is_configuration_file(Target::current(), &path) {
+        if let Err(e) = reload(&handle, config_dir.clone()) {
+          debug_eprintln!("failed to reload the config: {}", e);
+        }
+      }
+    })?;
+
+  app.manage(ConfigReloadState {
+    windows: Mutex::new(app.config().app.windows.clone()),
+    _watcher: watcher,
+  });
+  Ok(())
+}
+
+fn reload<R: Runtime>(app: &AppHandle<R>, dir: PathBuf) -> crate::Result<()> {
+  let config = read_from(Target::current(), dir).map_err(anyhow::Error::from)?;
+  let windows: Vec<WindowConfig> = serde_json::from_value(
+    config
+      .get("app")
+      .and_then(|app| app.get("windows"))
+      .cloned()
+      .unwrap_or_else(|| Value::Array(Vec::new())),
+  )?;
+
+  let state = app.state::<ConfigReloadState>();
+  let payload = {
+    let mut current = state.windows.lock().unwrap();
+    let payload = diff(&current, &windows);
+    *current = windows.clone();
+    payload
+  };
+
+  for changes in &payload.windows {
+    if !changes.skipped.is_empty() {
+      debug_eprintln!(
+        "the `{}` window config fields of `{}` cannot be reloaded, restart the app to apply them",
+        changes.skipped.join("`, `"),
+        changes.label
+      );
+    }
+    if let (Some(window), Some(config)) = (
+      app.manager().get_window(&changes.label),
+      windows.iter().find(|config| config.label == changes.label),
+    ) {
+      apply(&window, config, &changes.applied)?;
+    }
+  }
+  for label in &payload.skipped_windows {
+    debug_eprintln!(
+      "the `{}` window was added to or removed from the config, restart the app to apply it",
+      label
+    );
+  }
+
+  app.emit(CONFIG_RELOADED_EVENT, payload)
+}
+
+/// Lists the serialized names of the fields that differ between two window configs.
+fn changed_fields(old: &WindowConfig, new: &WindowConfig) -> BTreeSet<String> {
+  let (Ok(Value::Object(old)), Ok(Value::Object(new))) =
+    (serde_json::to_value(old), serde_json::to_value(new))
+  else {
+    return BTreeSet::new();
+  };
+  old
+    .keys()
+    .chain(new.keys())
+    .filter(|field| old.get(*field) != new.get(*field))
+    .cloned()
+    .collect()
+}
+
+/// Compares the window configs by label.
+fn diff(old: &[WindowConfig], new: &[WindowConfig]) -> ConfigReloadedPayload {
+  let mut windows = Vec::new();
+  let mut skipped_windows = Vec::new();
+
+  for config in new {
+    let Some(previous) = old.iter().find(|previous| previous.label == config.label) else {
+      skipped_windows.push(config.label.clone());
+      continue;
+    };
+    let (applied, skipped): (Vec<_>, Vec<_>) = changed_fields(previous, config)
+      .into_iter()
+      .partition(|field| RELOADABLE_FIELDS.contains(&field.as_str()));
+    if !applied.is_empty() || !skipped.is_empty() {
+      windows.push(WindowConfigChanges {
+        label: config.label.clone(),
+        applied,
+        skipped,
+      });
+    }
+  }
+  skipped_windows.extend(
+    old
+      .iter()
+      .filter(|previous| !new.iter().any(|config| config.label == previous.label))
+      .map(|previous| previous.label.clone()),
+  );
+
+  ConfigReloadedPayload {
+    windows,
+    skipped_windows,
+  }
+}
+
+/// Applies the given fields of the config to the window with the existing setters.
+fn apply<R: Runtime>(
+  window: &Window<R>,
+  config: &WindowConfig,
+  fields: &[String],
+) -> crate::Result<()> {
+  let changed = |field: &str| fields.iter().any(|f| f == field);
+
+  if changed("title") {
+    window.set_title(&config.title)?;
+  }
+  if changed("width") || changed("height") {
+    window.set_size(LogicalSize::new(config.width, config.height))?;
+  }
+  if changed("minWidth") || changed("minHeight") {
+    window.set_min_size(
+      config
+        .min_width
+        .zip(config.min_height)
+        .map(|(width, height)| LogicalSize::new(width, height)),
+    )?;
+  }
+  if changed("maxWidth") || changed("maxHeight") {
+    window.set_max_size(
+      config
+        .max_width
+        .zip(config.max_height)
+        .map(|(width, height)| LogicalSize::new(width, height)),
+    )?;
+  }
+  if changed("decorations") {
+    window.set_decorations(config.decorations)?;
+  }
+  if changed("alwaysOnTop") {
+    window.set_always_on_top(config.always_on_top)?;
+  }
+  if changed("theme") {
+    for webview in window.webviews() {
+      webview.set_theme(config.theme)?;
+    }
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{apply, diff, ConfigReloadedPayload, WindowConfigChanges};
+  use crate::{test::mock_app, utils::config::WindowConfig, window::WindowBuilder, LogicalSize};
+
+  fn window_config(label: &str) -> WindowConfig {
+    WindowConfig {
+      label: label.into(),
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn diff_window_configs() {
+    let old = vec![window_config("main"), window_config("settings")];
+    let mut main = window_config("main");
+    main.title = "Reloaded".into();
+    main.min_width = Some(300.0);
+    main.url = crate::WebviewUrl::App("other.html".into());
+    let new = vec![main, window_config("about")];
+
+    assert_eq!(
+      diff(&old, &new),
+      ConfigReloadedPayload {
+        windows: vec![WindowConfigChanges {
+          label: "main".into(),
+          applied: vec!["minWidth".into(), "title".into()],
+          skipped: vec!["url".into()],
+        }],
+        skipped_windows: vec!["about".into(), "settings".into()],
+      }
+    );
+    assert!(diff(&new, &new).windows.is_empty());
+  }
+
+  #[test]
+  fn apply_window_config() {
+    let app = mock_app();
+    let window = WindowBuilder::new(&app, "main").build().unwrap();
+
+    let mut config = window_config("main");
+    config.title = "Reloaded".into();
+    config.width = 640.0;
+    config.height = 480.0;
+    config.always_on_top = true;
+    apply(
+      &window,
+      &config,
+      &["title".into(), "width".into(), "alwaysOnTop".into()],
+    )
+    .unwrap();
+
+    assert_eq!(window.title().unwrap(), "Reloaded");
+    assert_eq!(
+      window.inner_size().unwrap(),
+      LogicalSize::new(640, 480).to_physical(1.0)
+    );
+  }
+}
@@ -0,0 +1,135 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Assets read from the `frontendDist` directory at runtime, enabled with the `dev-dynamic-assets` feature.
+
+use std::{
+  borrow::Cow,
+  path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use crate::{
+  path::{canonicalize_lenient, safe_join, PathEvent, WatchOptions, WatcherHandle},
+  utils::assets::{AssetKey, Assets, CspHash},
+  AppHandle, Manager, Runtime,
+};
+
+/// The event emitted to all targets when an asset changes on disk.
+const ASSET_CHANGED_EVENT: &str = "tauri://asset-changed";
+
+/// Serves the assets from disk so they can be edited without rebuilding the app.
+///
+/// The embedded assets are still used to list the assets and for the CSP hashes,
+/// so HTML files are served without the compile-time CSP processing.
+pub(crate) struct DynamicAssets {
+  dir: PathBuf,
+  embedded: Box<dyn Assets>,
+}
+
+impl DynamicAssets {
+  pub(crate) fn new(dir: PathBuf, embedded: Box<dyn Assets>) -> Self {
+    Self {
+      dir: canonicalize_lenient(&dir).unwrap_or(dir),
+      embedded,
+    }
+  }
+
+  /// Resolves the asset path, returning `None` if it is outside the assets directory.
+  fn resolve(&self, key: &AssetKey) -> Option<PathBuf> {
+    let relative = key.as_ref().trim_start_matches('/');
+    safe_join(&self.dir, Path::new(relative))
+      .ok()
+      .filter(|path| path.is_file())
+  }
+}
+
+impl Assets for DynamicAssets {
+  fn get(&self, key: &AssetKey) -> Option<Cow<'_, [u8]>> {
+    self
+      .resolve(key)
+      .and_then(|path| std::fs::read(path).ok())
+      .map(Cow::Owned)
+  }
+
+  fn iter(&self) -> Box<dyn Iterator<Item = (&&str, &&[u8])> + '_> {
+    self.embedded.iter()
+  }
+
+  fn csp_hashes(&self, html_path: &AssetKey) -> Box<dyn Iterator<Item = CspHash<'_>> + '_> {
+    self.embedded.csp_hashes(html_path)
+  }
+
+  fn original_size(&self, key: &AssetKey) -> Option<usize> {
+    self
+      .resolve(key)
+      .and_then(|path| std::fs::metadata(path).ok())
+      .map(|metadata| metadata.len() as usize)
+  }
+}
+
+#[derive(Clone, Serialize)]
+struct AssetChangedPayload {
+  path: String,
+}
+
+/// Keeps the assets directory watcher alive for the application lifetime.
+pub(crate) struct DynamicAssetsWatcher(#[allow(dead_code)] WatcherHandle);
+
+/// Watches the assets directory, emitting `tauri://asset-changed` with the changed asset path.
+pub(crate) fn watch<R: Runtime>(app: &AppHandle<R>, dir: &Path) -> crate::Result<()> {
+  let dir = canonicalize_lenient(dir)?;
+  let handle = app.clone();
+  let watched_dir = dir.clone();
+  let watcher = app.path().watch_with_options(
+    &dir,
+    WatchOptions::default().recursive(true),
+    move |event| {
+      let path = match event {
+        PathEvent::Created { path }
+        | PathEvent::Modified { path }
+        | PathEvent::Removed { path } => path,
+        PathEvent::Renamed { to, .. } => to,
+      };
+      if let Ok(relative) = path.strip_prefix(&watched_dir) {
+        let _ = handle.emit(
+          ASSET_CHANGED_EVENT,
+          AssetChangedPayload {
+            path: AssetKey::from(relative).into(),
+          },
+        );
+      }
+    },
+  )?;
+  app.manage(DynamicAssetsWatcher(watcher));
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::DynamicAssets;
+  use crate::{test::noop_assets, utils::assets::Assets};
+
+  #[test]
+  fn reads_from_disk() {
+    let dir = std::env::temp_dir().join(format!("tauri-dynamic-assets-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("dist/js")).unwrap();
+    std::fs::write(dir.join("dist/js/main.js"), "console.log('hello')").unwrap();
+    std::fs::write(dir.join("secret.txt"), "secret").unwrap();
+
+    let assets = DynamicAssets::new(dir.join("dist"), Box::new(noop_assets()));
+
+    assert_eq!(
+      assets.get(&"js/main.js".into()).as_deref(),
+      Some(&b"console.log('hello')"[..])
+    );
+    assert_eq!(assets.original_size(&"/js/main.js".into()), Some(20));
+    assert!(assets.get(&"js".into()).is_none());
+    assert!(assets.get(&"../secret.txt".into()).is_none());
+    assert!(assets.get(&"js/../../secret.txt".into()).is_none());
+
+    std::fs::remove_dir_all(dir).unwrap();
+  }
+}
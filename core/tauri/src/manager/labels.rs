@@ -0,0 +1,233 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Unique window and webview labels, see [`crate::Manager::unique_label`].
+
+use std::{
+  collections::HashMap,
+  sync::{
+    atomic::{AtomicU64, Ordering},
+    Mutex,
+  },
+  thread::ThreadId,
+  time::{Duration, Instant},
+};
+
+/// How long a label returned by [`crate::Manager::unique_label`] stays reserved
+/// if no window or webview is built with it.
+pub const LABEL_RESERVATION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Identifies the builder a label was reserved for, see [`crate::webview::WebviewBuilder::new_unique`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ReservationToken(u64);
+
+/// Who can build a window or webview with a reserved label.
+#[derive(PartialEq, Eq)]
+enum Owner {
+  /// The thread that called [`crate::Manager::unique_label`].
+  Thread(ThreadId),
+  /// The builder carrying the token, on any thread.
+  Token(ReservationToken),
+}
+
+struct Reservation {
+  owner: Owner,
+  expires: Instant,
+}
+
+#[derive(Default)]
+struct Labels {
+  /// The last number used for each prefix.
+  counters: HashMap<String, u64>,
+  reserved: HashMap<String, Reservation>,
+}
+
+/// The labels reserved by [`crate::Manager::unique_label`] until a window or webview is built with them.
+pub(crate) struct LabelReservations {
+  timeout: Duration,
+  labels: Mutex<Labels>,
+  /// The last token given to a builder.
+  last_token: AtomicU64,
+}
+
+impl Default for LabelReservations {
+  fn default() -> Self {
+    Self::new(LABEL_RESERVATION_TIMEOUT)
+  }
+}
+
+impl LabelReservations {
+  pub(crate) fn new(timeout: Duration) -> Self {
+    Self {
+      timeout,
+      labels: Default::default(),
+      last_token: Default::default(),
+    }
+  }
+
+  /// Reserves the next `<prefix>-<n>` label for the current thread, skipping the labels `is_used` returns `true` for.
+  pub(crate) fn reserve(&self, prefix: &str, is_used: impl Fn(&str) -> bool) -> String {
+    self.reserve_for(prefix, is_used, Owner::Thread(std::thread::current().id()))
+  }
+
+  /// Reserves the next `<prefix>-<n>` label for the builder carrying the returned token,
+  /// which can be built on any thread, e.g. after an async task moved to another worker.
+  pub(crate) fn reserve_with_token(
+    &self,
+    prefix: &str,
+    is_used: impl Fn(&str) -> bool,
+  ) -> (String, ReservationToken) {
+    let token = ReservationToken(self.last_token.fetch_add(1, Ordering::Relaxed) + 1);
+    let label = self.reserve_for(prefix, is_used, Owner::Token(token));
+    (label, token)
+  }
+
+  fn reserve_for(&self, prefix: &str, is_used: impl Fn(&str) -> bool, owner: Owner) -> String {
+    let mut labels = self.labels.lock().unwrap();
+    let now = Instant::now();
+    labels
+      .reserved
+      .retain(|_, reservation| reservation.expires > now);
+
+    loop {
+      let counter = labels.counters.entry(prefix.to_string()).or_default();
+      *counter += 1;
+      let label = format!("{prefix}-{counter}");
+      if !labels.reserved.contains_key(&label) && !is_used(&label) {
+        labels.reserved.insert(
+          label.clone(),
+          Reservation {
+            owner,
+            expires: now + self.timeout,
+          },
+        );
+        return label;
+      }
+    }
+  }
+
+  /// Releases the reservation of a label a window or webview is built with,
+  /// failing if another thread or builder reserved it.
+  pub(crate) fn claim(&self, label: &str, token: Option<ReservationToken>) -> crate::Result<()> {
+    let mut labels = self.labels.lock().unwrap();
+    match labels.reserved.get(label) {
+      Some(reservation)
+        if reservation.expires > Instant::now()
+          && reservation.owner != Owner::Thread(std::thread::current().id())
+          && token.map(Owner::Token).as_ref() != Some(&reservation.owner) =>
+      {
+        Err(crate::Error::LabelReserved(label.into()))
+      }
+      Some(_) => {
+        labels.reserved.remove(label);
+        Ok(())
+      }
+      None => Ok(()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{collections::HashSet, time::Duration};
+
+  use super::LabelReservations;
+  use crate::{test::mock_app, Manager, WebviewWindowBuilder};
+
+  #[test]
+  fn reserves_labels() {
+    let reservations = LabelReservations::default();
+    assert_eq!(reservations.reserve("dialog", |_| false), "dialog-1");
+    // the labels in use are skipped
+    assert_eq!(
+      reservations.reserve("dialog", |label| label == "dialog-2"),
+      "dialog-3"
+    );
+    assert_eq!(reservations.reserve("panel", |_| false), "panel-1");
+
+    let label = reservations.reserve("dialog", |_| false);
+    std::thread::scope(|scope| {
+      scope.spawn(|| {
+        assert!(matches!(
+          reservations.claim(&label, None),
+          Err(crate::Error::LabelReserved(l)) if l == label
+        ));
+      });
+    });
+    assert!(reservations.claim(&label, None).is_ok());
+    // the reservation is released once claimed
+    std::thread::scope(|scope| {
+      scope.spawn(|| assert!(reservations.claim(&label, None).is_ok()));
+    });
+  }
+
+  #[test]
+  fn reservations_follow_tokens() {
+    let reservations = LabelReservations::default();
+    let (label, token) = reservations.reserve_with_token("dialog", |_| false);
+    assert_eq!(label, "dialog-1");
+    // the reserving thread does not own the label, the builder carrying the token does
+    assert!(matches!(
+      reservations.claim(&label, None),
+      Err(crate::Error::LabelReserved(l)) if l == label
+    ));
+    let (_, other_token) = reservations.reserve_with_token("dialog", |_| false);
+    assert!(reservations.claim(&label, Some(other_token)).is_err());
+    std::thread::scope(|scope| {
+      scope.spawn(|| assert!(reservations.claim(&label, Some(token)).is_ok()));
+    });
+  }
+
+  #[test]
+  fn reservations_expire() {
+    let reservations = LabelReservations::new(Duration::ZERO);
+    let label = reservations.reserve("dialog", |_| false);
+    std::thread::scope(|scope| {
+      scope.spawn(|| assert!(reservations.claim(&label, None).is_ok()));
+    });
+    // expired labels are not reused
+    assert_eq!(reservations.reserve("dialog", |_| false), "dialog-2");
+  }
+
+  #[test]
+  fn concurrent_creations() {
+    let app = mock_app();
+    WebviewWindowBuilder::new(&app, "document-1", Default::default())
+      .build()
+      .unwrap();
+
+    let threads = (0..100)
+      .map(|_| {
+        let app = app.handle().clone();
+        std::thread::spawn(move || {
+          let (builder, label) =
+            WebviewWindowBuilder::new_unique(&app, "document", Default::default());
+          builder.build().unwrap();
+          label
+        })
+      })
+      .collect::<Vec<_>>();
+    let labels = threads
+      .into_iter()
+      .map(|thread| thread.join().unwrap())
+      .collect::<HashSet<_>>();
+
+    assert_eq!(labels.len(), 100);
+    assert!(!labels.contains("document-1"));
+    assert_eq!(app.webview_windows().len(), 101);
+
+    // another thread cannot build a window with the reserved label
+    let label = app.unique_label("document");
+    let handle = app.handle().clone();
+    let label_ = label.clone();
+    let result = std::thread::spawn(move || {
+      WebviewWindowBuilder::new(&handle, label_, Default::default())
+        .build()
+        .map(|_| ())
+    })
+    .join()
+    .unwrap();
+    assert!(matches!(result, Err(crate::Error::LabelReserved(l)) if l == label));
+  }
+}
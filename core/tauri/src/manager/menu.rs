@@ -7,9 +7,11 @@ use std::{
   sync::{Arc, Mutex, MutexGuard},
 };
 
+use muda::accelerator::Accelerator;
+
 use crate::{
   menu::{Menu, MenuId},
-  AppHandle, Runtime, Window,
+  AppHandle, Runtime, Webview, Window,
 };
 
 pub struct MenuManager<R: Runtime> {
@@ -25,6 +27,11 @@ pub struct MenuManager<R: Runtime> {
   pub global_event_listeners: Mutex<Vec<crate::app::GlobalMenuEventListener<AppHandle<R>>>>,
   /// Menu event listeners to specific windows.
   pub event_listeners: Mutex<HashMap<String, crate::app::GlobalMenuEventListener<Window<R>>>>,
+  /// Menu event listeners to specific webviews, called while the webview is focused.
+  pub webview_event_listeners:
+    Mutex<HashMap<String, crate::app::GlobalMenuEventListener<Webview<R>>>>,
+  /// The accelerators of the menu items, used to detect conflicts with the window accelerators.
+  pub accelerators: Mutex<HashMap<MenuId, Accelerator>>,
 }
 
 impl<R: Runtime> MenuManager<R> {
@@ -37,6 +44,18 @@ impl<R: Runtime> MenuManager<R> {
     self.menus.lock().expect("poisoned menu mutex")
   }
 
+  pub fn accelerators_lock(&self) -> MutexGuard<'_, HashMap<MenuId, Accelerator>> {
+    self.accelerators.lock().expect("poisoned menu mutex")
+  }
+
+  pub fn set_item_accelerator(&self, id: &MenuId, accelerator: Option<Accelerator>) {
+    let mut accelerators = self.accelerators_lock();
+    match accelerator {
+      Some(accelerator) => accelerators.insert(id.clone(), accelerator),
+      None => accelerators.remove(id),
+    };
+  }
+
   pub fn is_menu_in_use<I: PartialEq<MenuId>>(&self, id: &I) -> bool {
     self
       .menu_lock()
@@ -4,9 +4,14 @@
 
 use std::{
   borrow::Cow,
-  collections::HashMap,
+  cell::RefCell,
+  collections::{HashMap, HashSet},
   fmt,
-  sync::{Arc, Mutex, MutexGuard},
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex, MutexGuard,
+  },
+  time::Duration,
 };
 
 use serde::Serialize;
@@ -22,14 +27,28 @@ use tauri_utils::{
 
 use crate::{
   app::{AppHandle, GlobalWindowEventListener, OnPageLoad},
-  event::{assert_event_name_is_valid, Event, EventId, EventTarget, Listeners},
-  ipc::{Invoke, InvokeHandler, InvokeResponder, RuntimeAuthority},
+  event::{
+    assert_event_name_is_valid, Event, EventBatches, EventId, EventRecorder, EventTarget,
+    EventTransport, Listeners, RecordedEvent, RecorderConfig,
+  },
+  ipc::{
+    dispatcher::{DefaultCommandRouter, IpcDispatcher},
+    limits::IpcLimits,
+    sync::SyncCommands,
+    Invoke, InvokeHandler, InvokeResponder, IpcOriginValidator, RuntimeAuthority, ScopePolicy,
+  },
   plugin::PluginStore,
   utils::{assets::Assets, config::Config, PackageInfo},
   Context, Pattern, Runtime, StateManager, Window,
 };
 use crate::{event::EmitArgs, resources::ResourceTable, Webview};
 
+mod asset_cache;
+#[cfg(all(dev, desktop, feature = "dev-config-reload"))]
+pub(crate) mod config_reload;
+#[cfg(all(dev, feature = "dev-dynamic-assets"))]
+pub(crate) mod dynamic_assets;
+pub(crate) mod labels;
 #[cfg(desktop)]
 mod menu;
 #[cfg(all(desktop, feature = "tray-icon"))]
@@ -111,6 +130,13 @@ fn set_csp<R: Runtime>(
 }
 
 // inspired by https://github.com/rust-lang/rust/blob/1be5c8f90912c446ecbdc405cbc4a89f9acd20fd/library/alloc/src/str.rs#L260-L297
+/// Adds the webview to the labels collected by [`AppManager::delivery_log`].
+fn log_delivery<R: Runtime>(delivered: &Option<RefCell<Vec<String>>>, webview: &Webview<R>) {
+  if let Some(delivered) = delivered {
+    delivered.borrow_mut().push(webview.label().into());
+  }
+}
+
 fn replace_with_callback<F: FnMut() -> String>(
   original: &str,
   pattern: &str,
@@ -171,6 +197,8 @@ pub struct Asset {
   pub mime_type: String,
   /// The `Content-Security-Policy` header value.
   pub csp_header: Option<String>,
+  /// The `ETag` header value, set when the asset content is known at build time.
+  pub etag: Option<String>,
 }
 
 #[default_runtime(crate::Wry, wry)]
@@ -184,10 +212,25 @@ pub struct AppManager<R: Runtime> {
   pub menu: menu::MenuManager<R>,
 
   pub(crate) plugins: Mutex<PluginStore<R>>,
+  /// The names of the initialized plugins, kept outside of the plugin store
+  /// since the plugins query it while they are initialized.
+  pub(crate) initialized_plugins: Mutex<HashSet<&'static str>>,
   pub listeners: Listeners,
+  /// The queued payloads of the batched events.
+  pub(crate) event_batches: EventBatches,
+  /// The recorded events, see [`crate::Builder::record_events`].
+  pub(crate) event_recorder: Option<EventRecorder>,
+  /// The labels reserved with [`crate::Manager::unique_label`].
+  pub(crate) labels: labels::LabelReservations,
+  /// Mirrors the IPC traffic to the window opened with [`crate::AppHandle::open_ipc_inspector`].
+  #[cfg(all(debug_assertions, feature = "ipc-inspector"))]
+  pub(crate) ipc_inspector: crate::ipc::inspector::IpcInspector,
+  /// Set when the app exits, before the runtime destroys the webviews, see [`crate::Webview::is_alive`].
+  shutting_down: AtomicBool,
   pub state: Arc<StateManager>,
   pub config: Config,
   pub assets: Box<dyn Assets>,
+  asset_cache: asset_cache::AssetCache,
 
   pub app_icon: Option<Vec<u8>>,
 
@@ -236,6 +279,16 @@ impl<R: Runtime> AppManager<R> {
       crate::app::GlobalMenuEventListener<Window<R>>,
     >,
     (invoke_responder, invoke_initialization_script): (Option<Arc<InvokeResponder<R>>>, String),
+    ipc_origin_validator: Option<Arc<IpcOriginValidator<R>>>,
+    scope_policy: Option<Box<dyn ScopePolicy<R>>>,
+    sync_commands: SyncCommands,
+    local_origins: Vec<Url>,
+    ipc_limits: IpcLimits,
+    event_batching: HashMap<String, Duration>,
+    event_recorder: Option<RecorderConfig>,
+    event_transport: EventTransport,
+    #[cfg(feature = "tracing")] trace_ipc: bool,
+    #[cfg(feature = "devtools-protocol")] remote_debugging_port: Option<u16>,
   ) -> Self {
     // generate a random isolation key at runtime
     #[cfg(feature = "isolation")]
@@ -243,10 +296,23 @@ impl<R: Runtime> AppManager<R> {
       *key = uuid::Uuid::new_v4().to_string();
     }
 
+    #[cfg(all(dev, feature = "dev-dynamic-assets"))]
+    let assets: Box<dyn Assets> = match &context.frontend_dist_dir {
+      Some(dir) => Box::new(dynamic_assets::DynamicAssets::new(
+        dir.clone(),
+        context.assets,
+      )),
+      None => context.assets,
+    };
+    #[cfg(not(all(dev, feature = "dev-dynamic-assets")))]
+    let assets = context.assets;
+
     Self {
       runtime_authority: RuntimeAuthority::new(context.resolved_acl),
       window: window::WindowManager {
         windows: Mutex::default(),
+        parents: Mutex::default(),
+        always_on_top: Mutex::default(),
         default_icon: context.default_window_icon,
         event_listeners: Arc::new(window_event_listeners),
       },
@@ -257,6 +323,27 @@ impl<R: Runtime> AppManager<R> {
         uri_scheme_protocols: Mutex::new(uri_scheme_protocols),
         invoke_responder,
         invoke_initialization_script,
+        ipc_origin_validator,
+        ipc_dispatcher: match scope_policy {
+          Some(policy) => IpcDispatcher::new(policy, Box::new(DefaultCommandRouter)),
+          None => IpcDispatcher::default(),
+        },
+        sync_commands,
+        local_origins,
+        ipc_limits,
+        #[cfg(feature = "tracing")]
+        trace_ipc,
+        #[cfg(feature = "devtools-protocol")]
+        remote_debugging_port,
+        allowed_certificates: Default::default(),
+        event_listeners: Default::default(),
+        event_transport,
+        profiles: Default::default(),
+        pending_invoke_keys: Default::default(),
+        focus: Default::default(),
+        file_access: Default::default(),
+        data_directories: Default::default(),
+        forced_themes: Default::default(),
       },
       #[cfg(all(desktop, feature = "tray-icon"))]
       tray: tray::TrayManager {
@@ -271,12 +358,22 @@ impl<R: Runtime> AppManager<R> {
         menu: Default::default(),
         global_event_listeners: Default::default(),
         event_listeners: Mutex::new(window_menu_event_listeners),
+        webview_event_listeners: Default::default(),
+        accelerators: Default::default(),
       },
       plugins: Mutex::new(plugins),
+      initialized_plugins: Default::default(),
       listeners: Listeners::default(),
+      event_batches: EventBatches::new(event_batching),
+      event_recorder: event_recorder.map(EventRecorder::new),
+      labels: Default::default(),
+      #[cfg(all(debug_assertions, feature = "ipc-inspector"))]
+      ipc_inspector: Default::default(),
+      shutting_down: AtomicBool::new(false),
       state: Arc::new(state),
+      asset_cache: asset_cache::AssetCache::new(context.config.app.asset_cache.memory_cache_size),
       config: context.config,
-      assets: context.assets,
+      assets,
       app_icon: context.app_icon,
       package_info: context.package_info,
       pattern: Arc::new(context.pattern),
@@ -284,6 +381,17 @@ impl<R: Runtime> AppManager<R> {
     }
   }
 
+  /// Marks the app as exiting, so the webviews stop accepting calls before the runtime tears them down
+  /// and the threads still holding them get errors instead of using closed runtime channels.
+  pub(crate) fn begin_shutdown(&self) {
+    self.shutting_down.store(true, Ordering::SeqCst);
+  }
+
+  /// Whether [`Self::begin_shutdown`] was called.
+  pub(crate) fn is_shutting_down(&self) -> bool {
+    self.shutting_down.load(Ordering::SeqCst)
+  }
+
   /// State managed by the application.
   pub(crate) fn state(&self) -> Arc<StateManager> {
     self.state.clone()
@@ -308,10 +416,25 @@ impl<R: Runtime> AppManager<R> {
   }
 
   pub(crate) fn protocol_url(&self) -> Cow<'_, Url> {
-    if cfg!(windows) || cfg!(target_os = "android") {
-      Cow::Owned(Url::parse("http://tauri.localhost").unwrap())
-    } else {
+    Cow::Owned(crate::ipc::origin::protocol_origin("tauri").unwrap())
+  }
+
+  /// Whether the `devUrl` is proxied through the `tauri` protocol, see the `devProxy` configuration value.
+  pub(crate) fn proxy_dev_server(&self) -> bool {
+    // we need to proxy the dev server on mobile because we can't use `localhost`, so we use the local IP address
+    // and we do not get a secure context without the custom protocol that proxies to the dev server
+    // additionally, we need the custom protocol to inject the initialization scripts on Android
+    cfg!(dev)
+      && self.config.build.dev_url.is_some()
+      && (cfg!(mobile) || self.config.build.dev_proxy)
+  }
+
+  /// The URL the webviews load the proxied dev server from.
+  pub(crate) fn proxy_url(&self) -> Cow<'_, Url> {
+    if cfg!(mobile) {
       Cow::Owned(Url::parse("tauri://localhost").unwrap())
+    } else {
+      self.protocol_url()
     }
   }
 
@@ -355,14 +478,29 @@ impl<R: Runtime> AppManager<R> {
       path.chars().skip(1).collect::<String>()
     };
 
+    // assets with a content hash never change, so their decompressed content can be kept in memory
+    let load = |key: &AssetKey| -> Option<Cow<'_, [u8]>> {
+      if assets.content_hash(key).is_none() {
+        return assets.get(key);
+      }
+      let bytes = match self.asset_cache.get(key.as_ref()) {
+        Some(bytes) => bytes,
+        None => {
+          let bytes: Arc<[u8]> = assets.get(key)?.into();
+          self.asset_cache.insert(key.as_ref(), bytes.clone());
+          bytes
+        }
+      };
+      Some(Cow::Owned(bytes.to_vec()))
+    };
+
     let mut asset_path = AssetKey::from(path.as_str());
 
-    let asset_response = assets
-      .get(&path.as_str().into())
+    let asset_response = load(&path.as_str().into())
       .or_else(|| {
         debug_eprintln!("Asset `{path}` not found; fallback to {path}.html");
         let fallback = format!("{}.html", path.as_str()).into();
-        let asset = assets.get(&fallback);
+        let asset = load(&fallback);
         asset_path = fallback;
         asset
       })
@@ -373,14 +511,14 @@ impl<R: Runtime> AppManager<R> {
           path
         );
         let fallback = format!("{}/index.html", path.as_str()).into();
-        let asset = assets.get(&fallback);
+        let asset = load(&fallback);
         asset_path = fallback;
         asset
       })
       .or_else(|| {
         debug_eprintln!("Asset `{}` not found; fallback to index.html", path);
         let fallback = AssetKey::from("index.html");
-        let asset = assets.get(&fallback);
+        let asset = load(&fallback);
         asset_path = fallback;
         asset
       })
@@ -403,10 +541,16 @@ impl<R: Runtime> AppManager<R> {
           asset
         };
         let mime_type = tauri_utils::mime_type::MimeType::parse(&final_data, &path);
+        // the injected CSP nonces change on every request
+        let etag = assets
+          .content_hash(&asset_path)
+          .filter(|_| csp_header.is_none())
+          .map(|hash| format!("\"{hash}\""));
         Ok(Asset {
           bytes: final_data.to_vec(),
           mime_type,
           csp_header,
+          etag,
         })
       }
       Err(e) => {
@@ -481,15 +625,49 @@ impl<R: Runtime> AppManager<R> {
 
     #[cfg(feature = "tracing")]
     let _span = tracing::debug_span!("emit::run").entered();
-    let emit_args = EmitArgs::new(event, payload)?;
+    let mut emit_args = EmitArgs::new(event, payload)?;
+    // the filter cannot be recorded
+    emit_args.target = None;
+    self.emit_args_filter(emit_args, filter)
+  }
+
+  /// Emits an event to the listeners matching `target`, see [`Self::event_target_filter`].
+  pub(crate) fn emit_to<S: Serialize>(
+    &self,
+    target: EventTarget,
+    event: &str,
+    payload: S,
+  ) -> crate::Result<()> {
+    assert_event_name_is_valid(event);
+
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("emit::run").entered();
+    let mut emit_args = EmitArgs::new(event, payload)?;
+    emit_args.target = Some(target.clone());
+    self.emit_args_filter(emit_args, self.event_target_filter(target))
+  }
+
+  /// Emits an event that is already serialized, see [`Self::emit_filter`].
+  pub(crate) fn emit_args_filter<F>(&self, emit_args: EmitArgs, filter: F) -> crate::Result<()>
+  where
+    F: Fn(&EventTarget) -> bool,
+  {
+    assert_event_name_is_valid(&emit_args.event_name);
+    self.flush_event_batch(&emit_args.event_name, None)?;
 
     let listeners = self.listeners();
+    let delivered = self.delivery_log();
 
     listeners.try_for_each_js(
-      event,
-      self.webview.webviews_lock().values(),
+      &emit_args.event_name,
+      self
+        .webview
+        .webviews_lock()
+        .values()
+        .filter(|webview| webview.is_alive()),
       |webview, target| {
-        if filter(target) {
+        if *target == EventTarget::Any || filter(target) {
+          log_delivery(&delivered, webview);
           webview.emit_js(&emit_args, target)
         } else {
           Ok(())
@@ -497,31 +675,193 @@ impl<R: Runtime> AppManager<R> {
       },
     )?;
 
+    self.record_event(&emit_args, delivered);
     listeners.emit_filter(emit_args, Some(filter))?;
 
     Ok(())
   }
 
+  /// A filter matching the listeners that receive the events emitted to `target`, see [`EventTarget`].
+  pub(crate) fn event_target_filter(&self, target: EventTarget) -> impl Fn(&EventTarget) -> bool {
+    let window_webviews = match &target {
+      EventTarget::WebviewWindow { label } => self
+        .webview
+        .webviews_lock()
+        .values()
+        .filter(|webview| webview.window().label() == label)
+        .map(|webview| webview.label().to_string())
+        .collect(),
+      _ => Vec::new(),
+    };
+    move |listener| target.matches(listener, &window_webviews)
+  }
+
   pub fn emit<S: Serialize + Clone>(&self, event: &str, payload: S) -> crate::Result<()> {
     assert_event_name_is_valid(event);
 
     #[cfg(feature = "tracing")]
     let _span = tracing::debug_span!("emit::run").entered();
     let emit_args = EmitArgs::new(event, payload)?;
+    self.flush_event_batch(event, None)?;
 
     let listeners = self.listeners();
+    let delivered = self.delivery_log();
 
     listeners.try_for_each_js(
       event,
-      self.webview.webviews_lock().values(),
-      |webview, target| webview.emit_js(&emit_args, target),
+      self
+        .webview
+        .webviews_lock()
+        .values()
+        .filter(|webview| webview.is_alive()),
+      |webview, target| {
+        log_delivery(&delivered, webview);
+        webview.emit_js(&emit_args, target)
+      },
+    )?;
+
+    self.record_event(&emit_args, delivered);
+    listeners.emit(emit_args)?;
+
+    Ok(())
+  }
+
+  pub fn emit_sticky<S: Serialize + Clone>(&self, event: &str, payload: S) -> crate::Result<()> {
+    assert_event_name_is_valid(event);
+
+    let emit_args = EmitArgs::new(event, payload)?;
+    if emit_args.payload.len() > crate::MAX_STICKY_PAYLOAD_SIZE {
+      return Err(crate::Error::StickyPayloadTooLarge(
+        event.into(),
+        emit_args.payload.len(),
+      ));
+    }
+    self.flush_event_batch(event, None)?;
+
+    let listeners = self.listeners();
+    let delivered = self.delivery_log();
+
+    listeners.try_for_each_js_sticky(
+      &emit_args,
+      self
+        .webview
+        .webviews_lock()
+        .values()
+        .filter(|webview| webview.is_alive()),
+      |webview, target| {
+        log_delivery(&delivered, webview);
+        webview.emit_js(&emit_args, target)
+      },
     )?;
 
+    self.record_event(&emit_args, delivered);
     listeners.emit(emit_args)?;
 
     Ok(())
   }
 
+  /// Queues the payload of a batched event, scheduling the flush of the batch if it was empty.
+  pub(crate) fn emit_batched<S: Serialize>(
+    &self,
+    app_handle: &AppHandle<R>,
+    event: &str,
+    payload: S,
+  ) -> crate::Result<()> {
+    assert_event_name_is_valid(event);
+
+    let emit_args = EmitArgs::new(event, payload)?;
+    if let Some(generation) = self.event_batches.push(emit_args) {
+      let app_handle = app_handle.clone();
+      let event = event.to_string();
+      let interval = self.event_batches.interval(&event);
+      crate::async_runtime::spawn(async move {
+        tokio::time::sleep(interval).await;
+        if let Err(e) = app_handle
+          .manager
+          .flush_event_batch(&event, Some(generation))
+        {
+          debug_eprintln!("failed to flush the `{}` event batch: {}", event, e);
+        }
+      });
+    }
+    Ok(())
+  }
+
+  /// Delivers the queued payloads of a batched event, with a single script per webview.
+  ///
+  /// Nothing is delivered if a `generation` is given and the batch was flushed since.
+  pub(crate) fn flush_event_batch(
+    &self,
+    event: &str,
+    generation: Option<u64>,
+  ) -> crate::Result<()> {
+    let batch = self.event_batches.take(event, generation);
+    if batch.is_empty() {
+      return Ok(());
+    }
+
+    let listeners = self.listeners();
+    let delivered = self.delivery_log();
+
+    listeners.try_for_each_js(
+      event,
+      self
+        .webview
+        .webviews_lock()
+        .values()
+        .filter(|webview| webview.is_alive()),
+      |webview, target| {
+        log_delivery(&delivered, webview);
+        webview.emit_js_batch(&batch, target)
+      },
+    )?;
+
+    for emit_args in batch {
+      self.record_event(&emit_args, delivered.clone());
+      listeners.emit(emit_args)?;
+    }
+
+    Ok(())
+  }
+
+  /// Emits a recorded event again, see [`AppHandle::replay_events`].
+  pub(crate) fn replay_event(&self, event: &RecordedEvent) -> crate::Result<()> {
+    let (Some(target), Some(payload)) = (&event.target, &event.payload) else {
+      return Ok(());
+    };
+    let emit_args = EmitArgs {
+      event_name: event.event.clone(),
+      event: serde_json::to_string(&event.event)?,
+      payload: payload.clone(),
+      profile: None,
+      source: event.source.clone(),
+      target: Some(target.clone()),
+      replayed: true,
+    };
+    self.emit_args_filter(emit_args, self.event_target_filter(target.clone()))
+  }
+
+  /// Collects the labels of the webviews an event is delivered to, only when the events are recorded.
+  fn delivery_log(&self) -> Option<RefCell<Vec<String>>> {
+    self.event_recorder.as_ref().map(|_| RefCell::default())
+  }
+
+  fn record_event(&self, emit_args: &EmitArgs, delivered: Option<RefCell<Vec<String>>>) {
+    #[cfg(all(debug_assertions, feature = "ipc-inspector"))]
+    self.ipc_inspector.record_event(emit_args);
+    if let (Some(recorder), Some(delivered)) = (&self.event_recorder, delivered) {
+      recorder.record(emit_args, delivered.into_inner());
+    }
+  }
+
+  /// Delivers the queued payloads of all batched events.
+  pub(crate) fn flush_events(&self) -> crate::Result<()> {
+    for event in self.event_batches.pending_events() {
+      self.flush_event_batch(&event, None)?;
+    }
+    Ok(())
+  }
+
   pub fn get_window(&self, label: &str) -> Option<Window<R>> {
     self.window.windows_lock().get(label).cloned()
   }
@@ -535,22 +875,72 @@ impl<R: Runtime> AppManager<R> {
       .map(|w| w.1.clone())
   }
 
+  /// The label of the webview receiving the menu events: the last focused webview of the focused window,
+  /// or the focused window itself when none of its webviews is known to be focused.
+  /// Without a focused window, the last focused webview of the app.
+  #[cfg(desktop)]
+  pub(crate) fn menu_event_target(&self) -> Option<String> {
+    let focused_window = self.get_focused_window();
+    let focused_window = focused_window.as_ref().map(|window| window.label());
+    let target = self
+      .webview
+      .focus
+      .lock()
+      .unwrap()
+      .target(focused_window)
+      .map(ToString::to_string);
+    target.or_else(|| focused_window.map(ToString::to_string))
+  }
+
   pub(crate) fn on_window_close(&self, label: &str) {
+    self.window.parents.lock().unwrap().remove(label);
+    self.window.always_on_top.lock().unwrap().remove(label);
     if let Some(window) = self.window.windows_lock().remove(label) {
       for webview in window.webviews() {
-        self.webview.webviews_lock().remove(webview.label());
+        self.on_webview_close(webview.label());
       }
     }
   }
 
   pub(crate) fn on_webview_close(&self, label: &str) {
-    self.webview.webviews_lock().remove(label);
+    // deliver the pending batches before the webview is gone
+    if let Err(e) = self.flush_events() {
+      debug_eprintln!("failed to flush the event batches: {}", e);
+    }
+    if let Some(webview) = self.webview.webviews_lock().remove(label) {
+      webview.alive.store(false, Ordering::SeqCst);
+    }
+    self.webview.event_listeners.destroy(label);
+    self.webview.profiles.lock().unwrap().remove(label);
+    self.webview.focus.lock().unwrap().remove(label);
+    self.webview.file_access.remove_webview(label);
+    self.webview.data_directories.lock().unwrap().remove(label);
+    self.webview.forced_themes.lock().unwrap().remove(label);
+    #[cfg(desktop)]
+    self
+      .menu
+      .webview_event_listeners
+      .lock()
+      .unwrap()
+      .remove(label);
+    if let Some(invokes) = self
+      .state
+      .try_get::<crate::ipc::cancellation::ActiveInvokes>()
+    {
+      invokes.cancel_webview(label);
+    }
   }
 
   pub fn windows(&self) -> HashMap<String, Window<R>> {
     self.window.windows_lock().clone()
   }
 
+  /// Whether a window or webview uses the label, see [`labels::LabelReservations::reserve`].
+  pub(crate) fn is_label_used(&self, label: &str) -> bool {
+    self.window.windows_lock().contains_key(label)
+      || self.webview.webviews_lock().contains_key(label)
+  }
+
   pub fn get_webview(&self, label: &str) -> Option<Webview<R>> {
     self.webview.webviews_lock().get(label).cloned()
   }
@@ -617,7 +1007,8 @@ mod test {
     event::EventTarget,
     generate_context,
     plugin::PluginStore,
-    test::{mock_app, MockRuntime},
+    sealed::ManagerBase,
+    test::{mock_app, mock_builder, mock_context, noop_assets, MockRuntime},
     webview::WebviewBuilder,
     window::WindowBuilder,
     App, Manager, StateManager, Webview, WebviewWindow, WebviewWindowBuilder, Window, Wry,
@@ -648,6 +1039,18 @@ mod test {
       Default::default(),
       Default::default(),
       (None, "".into()),
+      None,
+      None,
+      Default::default(),
+      Vec::new(),
+      Default::default(),
+      Default::default(),
+      None,
+      Default::default(),
+      #[cfg(feature = "tracing")]
+      false,
+      #[cfg(feature = "devtools-protocol")]
+      None,
     );
 
     #[cfg(custom_protocol)]
@@ -893,4 +1296,306 @@ mod test {
     }
     assert_events("emit_to", &received, &[other_webview_listen_id]);
   }
+
+  #[test]
+  fn emit_to_targets() {
+    let EventSetup {
+      app,
+      window,
+      webview,
+      webview_window,
+      tx: _,
+      rx,
+    } = setup_events(true);
+
+    let any_ids = [
+      APP_LISTEN_ANY_ID,
+      WINDOW_LISTEN_ANY_ID,
+      WEBVIEW_LISTEN_ANY_ID,
+      WEBVIEW_WINDOW_LISTEN_ANY_ID,
+    ];
+    let payload = "target-payload";
+    let test_target = |target: EventTarget, expected: &[&str]| {
+      app
+        .emit_to(target.clone(), TEST_EVENT_NAME, payload)
+        .unwrap();
+      let mut received = Vec::new();
+      while let Ok((source, p)) = rx.recv_timeout(Duration::from_secs(1)) {
+        assert_eq!(p, payload);
+        received.push(source);
+      }
+      let expected = [expected, &any_ids].concat();
+      assert_events(&format!("emit_to ({target:?})"), &received, &expected);
+    };
+
+    test_target(
+      EventTarget::Any,
+      &[
+        APP_LISTEN_ID,
+        WINDOW_LISTEN_ID,
+        WEBVIEW_LISTEN_ID,
+        WEBVIEW_WINDOW_LISTEN_ID,
+      ],
+    );
+    test_target(EventTarget::App, &[APP_LISTEN_ID]);
+    test_target(EventTarget::window(window.label()), &[WINDOW_LISTEN_ID]);
+    test_target(EventTarget::webview(webview.label()), &[WEBVIEW_LISTEN_ID]);
+    // the webview is hosted by the window
+    test_target(
+      EventTarget::webview_window(window.label()),
+      &[WINDOW_LISTEN_ID, WEBVIEW_LISTEN_ID],
+    );
+    test_target(
+      EventTarget::webview_window(webview_window.label()),
+      &[WEBVIEW_WINDOW_LISTEN_ID],
+    );
+    test_target(EventTarget::window(webview.label()), &[]);
+    test_target(EventTarget::webview("unknown"), &[]);
+  }
+
+  #[test]
+  fn emit_sticky() {
+    let EventSetup { app, rx, .. } = setup_events(false);
+
+    app.emit_sticky(TEST_EVENT_NAME, "ready").unwrap();
+    let mut received = Vec::new();
+    while let Ok((source, p)) = rx.recv_timeout(Duration::from_secs(1)) {
+      assert_eq!(p, "ready");
+      received.push(source);
+    }
+    assert_events(
+      "emit_sticky",
+      &received,
+      &[
+        APP_LISTEN_ID,
+        WINDOW_LISTEN_ID,
+        WEBVIEW_LISTEN_ID,
+        WEBVIEW_WINDOW_LISTEN_ID,
+      ],
+    );
+
+    let payload = "a".repeat(crate::MAX_STICKY_PAYLOAD_SIZE);
+    assert!(matches!(
+      app.emit_sticky(TEST_EVENT_NAME, payload),
+      Err(crate::Error::StickyPayloadTooLarge(..))
+    ));
+  }
+
+  #[test]
+  fn emit_batched() {
+    let app = mock_app();
+    let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    webview
+      .webview
+      .listen_js(TEST_EVENT_NAME, EventTarget::Any, crate::ipc::CallbackFn(1))
+      .unwrap();
+    let dispatcher = &webview.webview.webview.dispatcher;
+    let listen_script = dispatcher.last_evaluated_script();
+
+    let (tx, rx) = channel();
+    app.listen_any(TEST_EVENT_NAME, move |event| {
+      tx.send(serde_json::from_str::<u32>(event.payload()).unwrap())
+        .unwrap();
+    });
+
+    for i in 0..500 {
+      app.emit_batched(TEST_EVENT_NAME, i).unwrap();
+    }
+    // nothing is evaluated until the batch is flushed
+    assert_eq!(dispatcher.last_evaluated_script(), listen_script);
+    assert!(rx.try_recv().is_err());
+
+    app.flush_events().unwrap();
+    let payloads = (0..500).map(|i| i.to_string()).collect::<Vec<_>>();
+    assert!(dispatcher
+      .last_evaluated_script()
+      .unwrap()
+      .contains(&format!("batch: [{}]", payloads.join(","))));
+    assert_eq!(
+      rx.try_iter().collect::<Vec<_>>(),
+      (0..500).collect::<Vec<_>>()
+    );
+
+    // a regular emit delivers the queued payloads first
+    app.emit_batched(TEST_EVENT_NAME, 500).unwrap();
+    app.emit(TEST_EVENT_NAME, 501).unwrap();
+    assert_eq!(rx.try_iter().collect::<Vec<_>>(), [500, 501]);
+    assert!(dispatcher
+      .last_evaluated_script()
+      .unwrap()
+      .contains("payload: 501"));
+  }
+
+  #[test]
+  fn emit_batched_flushes_on_interval() {
+    let app = mock_builder()
+      .event_batching(TEST_EVENT_NAME, Duration::from_millis(10))
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    let (tx, rx) = channel();
+    app.listen_any(TEST_EVENT_NAME, move |event| {
+      tx.send(serde_json::from_str::<u32>(event.payload()).unwrap())
+        .unwrap();
+    });
+
+    app.emit_batched(TEST_EVENT_NAME, 1).unwrap();
+    app.emit_batched(TEST_EVENT_NAME, 2).unwrap();
+    assert_eq!(rx.recv_timeout(Duration::from_secs(1)).unwrap(), 1);
+    assert_eq!(rx.recv_timeout(Duration::from_secs(1)).unwrap(), 2);
+
+    // the batches are flushed when a webview is destroyed
+    app.emit_batched(TEST_EVENT_NAME, 3).unwrap();
+    app.manager().on_webview_close("main");
+    assert_eq!(rx.try_recv().unwrap(), 3);
+  }
+
+  #[test]
+  fn emit_order() {
+    let app = mock_app();
+    let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    webview
+      .webview
+      .listen_js(TEST_EVENT_NAME, EventTarget::Any, crate::ipc::CallbackFn(1))
+      .unwrap();
+    let dispatcher = &webview.webview.webview.dispatcher;
+
+    let (tx, rx) = channel();
+    app.listen_any(TEST_EVENT_NAME, move |event| {
+      tx.send(serde_json::from_str::<u32>(event.payload()).unwrap())
+        .unwrap();
+    });
+
+    // successive emits from a thread are numbered in order
+    let app_handle = app.handle().clone();
+    std::thread::spawn(move || {
+      for i in 0..10_000 {
+        app_handle.emit(TEST_EVENT_NAME, i).unwrap();
+      }
+    })
+    .join()
+    .unwrap();
+    assert_eq!(
+      rx.try_iter().collect::<Vec<_>>(),
+      (0..10_000).collect::<Vec<_>>()
+    );
+    let script = dispatcher.last_evaluated_script().unwrap();
+    assert!(script.contains("payload: 9999"));
+    assert!(script.contains(r#""epoch":0,"index":9999}"#));
+
+    // an emit from another thread continues the stream
+    app.emit(TEST_EVENT_NAME, 10_000).unwrap();
+    assert!(dispatcher
+      .last_evaluated_script()
+      .unwrap()
+      .contains(r#""epoch":0,"index":10000}"#));
+
+    // the batches take a single position in the stream
+    app.emit_batched(TEST_EVENT_NAME, 10_001).unwrap();
+    app.emit_batched(TEST_EVENT_NAME, 10_002).unwrap();
+    app.flush_events().unwrap();
+    assert!(dispatcher
+      .last_evaluated_script()
+      .unwrap()
+      .contains(r#""epoch":0,"index":10001}"#));
+
+    // the streams restart on navigation
+    webview
+      .webview
+      .event_sequences
+      .lock()
+      .unwrap()
+      .on_navigation();
+    app.emit(TEST_EVENT_NAME, 0).unwrap();
+    assert!(dispatcher
+      .last_evaluated_script()
+      .unwrap()
+      .contains(r#""epoch":1,"index":0}"#));
+  }
+
+  #[test]
+  fn emit_over_bridge() {
+    let app = mock_builder()
+      .event_transport(crate::EventTransport::Bridge)
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    let dispatcher = &webview.webview.webview.dispatcher;
+    let evaluated_script = dispatcher.last_evaluated_script();
+    let id = webview
+      .webview
+      .listen_js(TEST_EVENT_NAME, EventTarget::Any, crate::ipc::CallbackFn(1))
+      .unwrap();
+    app.emit(TEST_EVENT_NAME, "payload").unwrap();
+    app.emit_sticky("sticky", 1).unwrap();
+    webview
+      .webview
+      .listen_js("sticky", EventTarget::Any, crate::ipc::CallbackFn(2))
+      .unwrap();
+    webview.webview.unlisten_js(TEST_EVENT_NAME, id).unwrap();
+
+    // no script is evaluated, the operations are queued on the bridge
+    assert_eq!(dispatcher.last_evaluated_script(), evaluated_script);
+    let messages = crate::async_runtime::block_on(webview.webview.event_bridge.poll())
+      .iter()
+      .map(|message| serde_json::from_str::<serde_json::Value>(message.get()).unwrap())
+      .collect::<Vec<_>>();
+    let kinds = messages
+      .iter()
+      .map(|message| message["kind"].as_str().unwrap())
+      .collect::<Vec<_>>();
+    assert_eq!(kinds, ["listen", "emit", "listen", "call", "unlisten"]);
+    assert_eq!(messages[0]["handler"], 1);
+    assert_eq!(messages[1]["eventData"]["payload"], "payload");
+    assert_eq!(messages[1]["target"]["kind"], "Any");
+    assert_eq!(messages[1]["eventData"]["sequence"]["index"], 0);
+    assert_eq!(messages[3]["eventData"]["payload"], 1);
+    assert_eq!(messages[4]["id"], id);
+
+    let listeners = app.manager().listeners();
+    let init_script = crate::event::event_initialization_script(
+      listeners.function_name(),
+      listeners.listeners_object_name(),
+      crate::EventTransport::Bridge,
+    );
+    assert!(init_script.contains(crate::event::EVENT_BRIDGE_COMMAND));
+  }
+
+  #[derive(Clone, serde::Serialize, serde::Deserialize)]
+  struct Progress {
+    downloaded: u64,
+  }
+
+  impl crate::EventName for Progress {
+    const NAME: &'static str = "download-progress";
+  }
+
+  #[test]
+  fn emit_typed() {
+    let app = mock_app();
+    let (tx, rx) = channel();
+    app.listen_typed(move |progress: Result<Progress, crate::EventDecodeError>| {
+      tx.send(progress.map(|p| p.downloaded).map_err(|e| e.to_string()))
+        .unwrap();
+    });
+
+    app.emit_typed(&Progress { downloaded: 10 }).unwrap();
+    assert_eq!(rx.recv_timeout(Duration::from_secs(1)).unwrap(), Ok(10));
+
+    app.emit("download-progress", "done").unwrap();
+    let error = rx
+      .recv_timeout(Duration::from_secs(1))
+      .unwrap()
+      .unwrap_err();
+    assert!(
+      error.starts_with("failed to decode the payload of the `download-progress` event")
+        && error.ends_with("at line 1 column 6"),
+      "{error}"
+    );
+  }
 }
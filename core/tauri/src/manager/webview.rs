@@ -3,35 +3,53 @@
 // SPDX-License-Identifier: MIT
 
 use std::{
-  borrow::Cow,
   collections::{HashMap, HashSet},
   fmt,
   fs::create_dir_all,
-  sync::{Arc, Mutex, MutexGuard},
+  path::PathBuf,
+  sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc, Mutex, MutexGuard,
+  },
 };
 
 use serde::Serialize;
 use serialize_to_javascript::{default_template, DefaultTemplate, Template};
-use tauri_runtime::webview::{DetachedWebview, PendingWebview};
+use tauri_runtime::{
+  webview::{DetachedWebview, PendingWebview},
+  WebviewDispatch,
+};
 use tauri_utils::config::WebviewUrl;
 use url::Url;
 
 use crate::{
-  app::{OnPageLoad, UriSchemeResponder},
-  ipc::{InvokeHandler, InvokeResponder},
+  app::{OnPageLoad, ProtocolRequestContext, UriSchemeResponder},
+  ipc::{
+    dispatcher::IpcDispatcher, limits::IpcLimits, sync::SyncCommands, InvokeHandler,
+    InvokeResponder, IpcOriginValidator,
+  },
   pattern::PatternJavascript,
   sealed::ManagerBase,
-  webview::PageLoadPayload,
-  AppHandle, EventLoopMessage, Manager, Runtime, Webview, Window,
+  webview::{invoke_key::InvokeKey, PageLoadEvent, PageLoadPayload},
+  AppHandle, EventLoopMessage, EventTransport, Manager, Runtime, Webview, WebviewEvent, Window,
 };
 
 use super::AppManager;
 
-// we need to proxy the dev server on mobile because we can't use `localhost`, so we use the local IP address
-// and we do not get a secure context without the custom protocol that proxies to the dev server
-// additionally, we need the custom protocol to inject the initialization scripts on Android
-// must also keep in sync with the `let mut response` assignment in prepare_uri_scheme_protocol
-pub(crate) const PROXY_DEV_SERVER: bool = cfg!(all(dev, mobile));
+/// The data directory of a shared session without a data directory, see [`crate::webview::WebviewBuilder::shared_session`].
+///
+/// The identifier is percent-encoded so any identifier maps to a single, valid directory name.
+pub(crate) fn session_data_directory(
+  default_data_directory: PathBuf,
+  session: &crate::webview::SessionId,
+) -> PathBuf {
+  match session.id() {
+    Some(id) => default_data_directory.join("sessions").join(
+      percent_encoding::utf8_percent_encode(id, percent_encoding::NON_ALPHANUMERIC).to_string(),
+    ),
+    None => default_data_directory,
+  }
+}
 
 pub(crate) const PROCESS_IPC_MESSAGE_FN: &str =
   include_str!("../../scripts/process-ipc-message-fn.js");
@@ -54,8 +72,11 @@ pub(crate) struct IpcJavascript<'a> {
 pub struct UriSchemeProtocol<R: Runtime> {
   /// Handler for protocol
   #[allow(clippy::type_complexity)]
-  pub protocol:
-    Box<dyn Fn(&AppHandle<R>, http::Request<Vec<u8>>, UriSchemeResponder) + Send + Sync>,
+  pub protocol: Box<
+    dyn Fn(&AppHandle<R>, &ProtocolRequestContext, http::Request<Vec<u8>>, UriSchemeResponder)
+      + Send
+      + Sync,
+  >,
 }
 
 #[derive(Clone, Serialize)]
@@ -65,6 +86,70 @@ pub struct WebviewLabelDef {
   pub label: String,
 }
 
+pub(crate) type WebviewEventHandler = Arc<dyn Fn(&WebviewEvent) + Send + Sync>;
+
+/// The webview event listeners, by webview label.
+#[derive(Default)]
+pub(crate) struct WebviewEventListeners {
+  next_id: AtomicU32,
+  listeners: Mutex<HashMap<String, Vec<(u32, WebviewEventHandler)>>>,
+}
+
+impl WebviewEventListeners {
+  pub(crate) fn add(&self, label: &str, handler: WebviewEventHandler) -> u32 {
+    let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+    self
+      .listeners
+      .lock()
+      .unwrap()
+      .entry(label.into())
+      .or_default()
+      .push((id, handler));
+    id
+  }
+
+  pub(crate) fn remove(&self, label: &str, id: u32) {
+    if let Some(listeners) = self.listeners.lock().unwrap().get_mut(label) {
+      listeners.retain(|(listener_id, _)| *listener_id != id);
+    }
+  }
+
+  /// Calls every listener of the webview, outside of the lock so listeners can be added or removed by a handler.
+  pub(crate) fn emit(&self, label: &str, event: &WebviewEvent) {
+    let handlers = self
+      .listeners
+      .lock()
+      .unwrap()
+      .get(label)
+      .map(|listeners| {
+        listeners
+          .iter()
+          .map(|(_, handler)| handler.clone())
+          .collect::<Vec<_>>()
+      })
+      .unwrap_or_default();
+    for handler in handlers {
+      handler(event);
+    }
+  }
+
+  #[cfg(test)]
+  pub(crate) fn listener_count(&self, label: &str) -> usize {
+    self
+      .listeners
+      .lock()
+      .unwrap()
+      .get(label)
+      .map_or(0, Vec::len)
+  }
+
+  /// Emits [`WebviewEvent::Destroyed`] and removes every listener of the webview.
+  pub(crate) fn destroy(&self, label: &str) {
+    self.emit(label, &WebviewEvent::Destroyed);
+    self.listeners.lock().unwrap().remove(label);
+  }
+}
+
 pub struct WebviewManager<R: Runtime> {
   pub webviews: Mutex<HashMap<String, Webview<R>>>,
   /// The JS message handler.
@@ -78,6 +163,77 @@ pub struct WebviewManager<R: Runtime> {
   pub invoke_responder: Option<Arc<InvokeResponder<R>>>,
   /// The script that initializes the invoke system.
   pub invoke_initialization_script: String,
+  /// Validator for the IPC request headers.
+  pub ipc_origin_validator: Option<Arc<IpcOriginValidator<R>>>,
+  /// Checks the IPC requests and routes them to the command handlers.
+  pub(crate) ipc_dispatcher: IpcDispatcher<R>,
+  /// The commands the frontend can call synchronously.
+  pub(crate) sync_commands: SyncCommands,
+  /// Additional origins treated as the app origin, see [`crate::Builder::add_local_origin`].
+  pub(crate) local_origins: Vec<Url>,
+  /// The size limits of the IPC requests and responses.
+  pub(crate) ipc_limits: IpcLimits,
+  /// Whether the IPC requests are traced, see [`crate::Builder::trace_ipc`].
+  #[cfg(feature = "tracing")]
+  pub(crate) trace_ipc: bool,
+  /// The local port of the remote debugging server, see [`crate::Builder::remote_debugging_port`].
+  #[cfg(feature = "devtools-protocol")]
+  pub(crate) remote_debugging_port: Option<u16>,
+  /// The certificates allowed with [`crate::webview::CertDecision::AllowForHost`] for the session,
+  /// as pairs of host and DER encoded certificate.
+  pub(crate) allowed_certificates: Mutex<HashSet<(String, Vec<u8>)>>,
+  /// The listeners of the webview events.
+  pub(crate) event_listeners: WebviewEventListeners,
+  /// How the events reach the frontend, see [`crate::Builder::event_transport`].
+  pub(crate) event_transport: EventTransport,
+  /// The profiles the webviews are bound to, keyed by webview label, see [`crate::webview::WebviewBuilder::profile`].
+  pub(crate) profiles: Mutex<HashMap<String, String>>,
+  /// The webviews that last received the focus, used to route the menu events.
+  pub(crate) focus: Mutex<WebviewFocus>,
+  /// The scopes of [`crate::webview::WebviewBuilder::allow_file_access`].
+  pub(crate) file_access: crate::webview::file_access::FileAccessScopes,
+  /// The data directories of the webviews, keyed by webview label, when the runtime was given one.
+  pub(crate) data_directories: Mutex<HashMap<String, PathBuf>>,
+  /// The labels of the webviews with a theme forced by [`crate::webview::Webview::set_theme`].
+  pub(crate) forced_themes: Mutex<HashSet<String>>,
+  /// The invoke keys whose bootstrap secret was rendered into the initialization script of a webview being created,
+  /// keyed by webview label.
+  pub(crate) pending_invoke_keys: Mutex<HashMap<String, InvokeKey>>,
+}
+
+/// The webviews that last received the focus, see [`crate::menu::MenuEvent::webview`].
+///
+/// The focus is not cleared when a webview loses it, since opening a menu takes the focus on some platforms.
+#[derive(Default)]
+pub(crate) struct WebviewFocus {
+  /// The last focused webview of each window, keyed by window label.
+  by_window: HashMap<String, String>,
+  /// The last focused webview of the app.
+  last: Option<String>,
+}
+
+impl WebviewFocus {
+  pub(crate) fn focused(&mut self, window_label: &str, label: &str) {
+    self
+      .by_window
+      .insert(window_label.to_string(), label.to_string());
+    self.last.replace(label.to_string());
+  }
+
+  pub(crate) fn remove(&mut self, label: &str) {
+    self.by_window.retain(|_, webview| webview != label);
+    if self.last.as_deref() == Some(label) {
+      self.last = None;
+    }
+  }
+
+  /// The last focused webview of the window, or of the app without a focused window.
+  pub(crate) fn target(&self, focused_window: Option<&str>) -> Option<&str> {
+    match focused_window {
+      Some(window) => self.by_window.get(window).map(String::as_str),
+      None => self.last.as_deref(),
+    }
+  }
 }
 
 impl<R: Runtime> fmt::Debug for WebviewManager<R> {
@@ -134,6 +290,10 @@ impl<R: Runtime> WebviewManager<R> {
     .render_default(&Default::default())?;
 
     let mut webview_attributes = pending.webview_attributes;
+    #[cfg(feature = "devtools-protocol")]
+    if let Some(port) = self.remote_debugging_port {
+      webview_attributes = webview_attributes.remote_debugging_port(port);
+    }
 
     let ipc_init = IpcJavascript {
       isolation_origin: &match &*app_manager.pattern {
@@ -144,6 +304,8 @@ impl<R: Runtime> WebviewManager<R> {
     }
     .render_default(&Default::default())?;
 
+    let invoke_key = InvokeKey::default();
+
     let mut webview_labels = webview_labels.to_vec();
     if !webview_labels.iter().any(|w| w.label == label) {
       webview_labels.push(WebviewLabelDef {
@@ -171,7 +333,8 @@ impl<R: Runtime> WebviewManager<R> {
               windows: {window_labels_array}.map(function (label) {{ return {{ label: label }} }}),
               webviews: {webview_labels_array},
               currentWindow: {{ label: {current_window_label} }},
-              currentWebview: {{ label: {current_webview_label} }}
+              currentWebview: {{ label: {current_webview_label}, profile: {current_webview_profile} }},
+              runtimeInfo: {runtime_info}
             }}
           }})
         "#,
@@ -179,15 +342,24 @@ impl<R: Runtime> WebviewManager<R> {
         webview_labels_array = serde_json::to_string(&webview_labels)?,
         current_window_label = serde_json::to_string(window_label)?,
         current_webview_label = serde_json::to_string(&label)?,
+        current_webview_profile = serde_json::to_string(&self.profiles.lock().unwrap().get(label))?,
+        runtime_info = serde_json::to_string(&crate::RuntimeInfo::current(app_manager))?,
       ))
       .initialization_script(&self.initialization_script(
         app_manager,
         &ipc_init.into_string(),
         &pattern_init.into_string(),
         &plugin_init,
+        invoke_key.bootstrap(),
         is_init_global,
       )?);
 
+    self
+      .pending_invoke_keys
+      .lock()
+      .unwrap()
+      .insert(label.to_string(), invoke_key);
+
     #[cfg(feature = "isolation")]
     if let crate::Pattern::Isolation { schema, .. } = &*app_manager.pattern {
       webview_attributes = webview_attributes.initialization_script(
@@ -204,13 +376,22 @@ impl<R: Runtime> WebviewManager<R> {
 
     let mut registered_scheme_protocols = Vec::new();
 
+    // the handlers are registered for each webview so the context matches the requesting webview,
+    // except on Linux where the webviews sharing a web context keep the handlers of the first one
+    let context = ProtocolRequestContext {
+      webview_label: label.into(),
+      window_label: window_label.into(),
+    };
+
     for (uri_scheme, protocol) in &*self.uri_scheme_protocols.lock().unwrap() {
       registered_scheme_protocols.push(uri_scheme.clone());
       let protocol = protocol.clone();
       let app_handle = Mutex::new(manager.app_handle().clone());
+      let context = context.clone();
       pending.register_uri_scheme_protocol(uri_scheme.clone(), move |p, responder| {
         (protocol.protocol)(
           &app_handle.lock().unwrap(),
+          &context,
           p,
           UriSchemeResponder(responder),
         )
@@ -268,6 +449,12 @@ impl<R: Runtime> WebviewManager<R> {
         let payload = PageLoadPayload { url: &url, event };
 
         if let Some(w) = app_manager_.get_webview(&label) {
+          if event == PageLoadEvent::Started {
+            w.invoke_key.lock().unwrap().on_navigation();
+            w.event_sequences.lock().unwrap().on_navigation();
+            w.event_bridge.on_navigation();
+          }
+
           if let Some(on_page_load) = &app_manager_.webview.on_page_load {
             on_page_load(&w, &payload);
           }
@@ -279,6 +466,14 @@ impl<R: Runtime> WebviewManager<R> {
             .on_page_load(&w, &payload);
         }
 
+        app_manager_.webview.event_listeners.emit(
+          &label,
+          &WebviewEvent::PageLoad {
+            url: url.clone(),
+            event,
+          },
+        );
+
         if let Some(handler) = &on_page_load_handler {
           handler(url, event);
         }
@@ -291,7 +486,11 @@ impl<R: Runtime> WebviewManager<R> {
         .get::<crate::Scopes>()
         .asset_protocol
         .clone();
-      let protocol = crate::protocol::asset::get(asset_scope.clone(), window_origin.clone());
+      let protocol = crate::protocol::asset::get(
+        asset_scope.clone(),
+        window_origin.clone(),
+        app_manager.config.app.security.cross_origin_isolation,
+      );
       pending.register_uri_scheme_protocol("asset", move |request, responder| {
         protocol(request, UriSchemeResponder(responder))
       });
@@ -320,6 +519,7 @@ impl<R: Runtime> WebviewManager<R> {
     ipc_script: &str,
     pattern_script: &str,
     plugin_initialization_script: &str,
+    invoke_bootstrap: &str,
     with_global_tauri: bool,
   ) -> crate::Result<String> {
     #[derive(Template)]
@@ -345,6 +545,7 @@ impl<R: Runtime> WebviewManager<R> {
     #[default_template("../../scripts/core.js")]
     struct CoreJavascript<'a> {
       os_name: &'a str,
+      invoke_bootstrap: &'a str,
     }
 
     let bundle_script = if with_global_tauri {
@@ -365,12 +566,14 @@ impl<R: Runtime> WebviewManager<R> {
       bundle_script,
       core_script: &CoreJavascript {
         os_name: std::env::consts::OS,
+        invoke_bootstrap,
       }
       .render_default(&Default::default())?
       .into_string(),
       event_initialization_script: &crate::event::event_initialization_script(
         app_manager.listeners().function_name(),
         app_manager.listeners().listeners_object_name(),
+        self.event_transport,
       ),
       plugin_initialization_script,
       freeze_prototype,
@@ -380,10 +583,11 @@ impl<R: Runtime> WebviewManager<R> {
     .map_err(Into::into)
   }
 
-  pub fn prepare_webview<M: Manager<R>>(
+  pub(crate) fn prepare_webview<M: Manager<R>>(
     &self,
     manager: &M,
     mut pending: PendingWebview<EventLoopMessage, R>,
+    label_reservation: Option<super::labels::ReservationToken>,
     window_label: &str,
     window_labels: &[String],
     webview_labels: &[WebviewLabelDef],
@@ -393,12 +597,15 @@ impl<R: Runtime> WebviewManager<R> {
     }
 
     let app_manager = manager.manager();
+    app_manager
+      .labels
+      .claim(&pending.label, label_reservation)?;
 
     #[allow(unused_mut)] // mut url only for the data-url parsing
     let mut url = match &pending.webview_attributes.url {
       WebviewUrl::App(path) => {
-        let url = if PROXY_DEV_SERVER {
-          Cow::Owned(Url::parse("tauri://localhost").unwrap())
+        let url = if app_manager.proxy_dev_server() {
+          app_manager.proxy_url()
         } else {
           app_manager.get_url()
         };
@@ -416,15 +623,21 @@ impl<R: Runtime> WebviewManager<R> {
       WebviewUrl::External(url) => {
         let config_url = app_manager.get_url();
         let is_local = config_url.make_relative(url).is_some();
-        let mut url = url.clone();
-        if is_local && PROXY_DEV_SERVER {
-          url.set_scheme("tauri").unwrap();
-          url.set_host(Some("localhost")).unwrap();
+        if is_local && app_manager.proxy_dev_server() {
+          let mut proxied = app_manager.proxy_url().into_owned();
+          proxied.set_path(url.path());
+          proxied.set_query(url.query());
+          proxied.set_fragment(url.fragment());
+          proxied
+        } else {
+          url.clone()
         }
-        url
       }
 
-      WebviewUrl::CustomProtocol(url) => url.clone(),
+      WebviewUrl::CustomProtocol { scheme, path } => crate::ipc::origin::protocol_origin(scheme)
+        .ok_or_else(|| crate::Error::InvalidWebviewUrl("invalid custom protocol scheme"))?
+        .join(path)
+        .map_err(crate::Error::InvalidUrl)?,
       _ => unimplemented!(),
     };
 
@@ -504,7 +717,11 @@ impl<R: Runtime> WebviewManager<R> {
         crate::path::BaseDirectory::LocalData,
       );
       if let Ok(user_data_dir) = local_app_data {
-        pending.webview_attributes.data_directory = Some(user_data_dir);
+        pending.webview_attributes.data_directory =
+          Some(match &pending.webview_attributes.session {
+            Some(session) => session_data_directory(user_data_dir, session),
+            None => user_data_dir,
+          });
       }
     }
 
@@ -513,6 +730,11 @@ impl<R: Runtime> WebviewManager<R> {
       if !user_data_dir.exists() {
         create_dir_all(user_data_dir)?;
       }
+      self
+        .data_directories
+        .lock()
+        .unwrap()
+        .insert(pending.label.clone(), user_data_dir.clone());
     }
 
     #[cfg(feature = "isolation")]
@@ -536,7 +758,7 @@ impl<R: Runtime> WebviewManager<R> {
         }
       }
       let webview = app_manager.webview.webviews_lock().get(&label).cloned();
-      if let Some(w) = webview {
+      let allowed = if let Some(w) = webview {
         app_manager
           .plugins
           .lock()
@@ -544,7 +766,14 @@ impl<R: Runtime> WebviewManager<R> {
           .on_navigation(&w, url)
       } else {
         true
+      };
+      if allowed {
+        app_manager
+          .webview
+          .event_listeners
+          .emit(&label, &WebviewEvent::Navigation { url: url.clone() });
       }
+      allowed
     }));
 
     Ok(pending)
@@ -554,8 +783,35 @@ impl<R: Runtime> WebviewManager<R> {
     &self,
     window: Window<R>,
     webview: DetachedWebview<EventLoopMessage, R>,
+    allowed_commands: Option<crate::ipc::AllowList>,
   ) -> Webview<R> {
-    let webview = Webview::new(window, webview);
+    let webview = Webview::new(window, webview, allowed_commands);
+    if let Some(invoke_key) = self
+      .pending_invoke_keys
+      .lock()
+      .unwrap()
+      .remove(webview.label())
+    {
+      *webview.invoke_key.lock().unwrap() = invoke_key;
+    }
+
+    let manager = webview.manager_owned();
+    let label = webview.label().to_string();
+    webview.webview.dispatcher.on_webview_event(move |event| {
+      if let Some(event) = WebviewEvent::from_runtime(event) {
+        if let WebviewEvent::Focused(true) = event {
+          if let Some(webview) = manager.get_webview(&label) {
+            manager
+              .webview
+              .focus
+              .lock()
+              .unwrap()
+              .focused(webview.window().label(), &label);
+          }
+        }
+        manager.webview.event_listeners.emit(&label, &event);
+      }
+    });
 
     // insert the webview into our manager
     {
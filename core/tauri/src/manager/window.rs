@@ -32,13 +32,17 @@ const WINDOW_DESTROYED_EVENT: &str = "tauri://destroyed";
 const WINDOW_FOCUS_EVENT: &str = "tauri://focus";
 const WINDOW_BLUR_EVENT: &str = "tauri://blur";
 const WINDOW_SCALE_FACTOR_CHANGED_EVENT: &str = "tauri://scale-change";
-const WINDOW_THEME_CHANGED: &str = "tauri://theme-changed";
+pub(crate) const WINDOW_THEME_CHANGED: &str = "tauri://theme-changed";
 const WINDOW_FILE_DROP_EVENT: &str = "tauri://file-drop";
 const WINDOW_FILE_DROP_HOVER_EVENT: &str = "tauri://file-drop-hover";
 const WINDOW_FILE_DROP_CANCELLED_EVENT: &str = "tauri://file-drop-cancelled";
 
 pub struct WindowManager<R: Runtime> {
   pub windows: Mutex<HashMap<String, Window<R>>>,
+  /// The label of the parent window of each window built with [`crate::window::WindowBuilder::parent`].
+  pub(crate) parents: Mutex<HashMap<String, String>>,
+  /// The labels of the windows kept on top of other windows, tracked since the runtime cannot read it back.
+  pub(crate) always_on_top: Mutex<HashSet<String>>,
   pub default_icon: Option<Icon>,
   /// Window event listeners to all windows.
   pub event_listeners: Arc<Vec<GlobalWindowEventListener<R>>>,
@@ -146,13 +150,10 @@ fn on_window_event<R: Runtime>(
     WindowEvent::Moved(position) => window.emit(WINDOW_MOVED_EVENT, position)?,
     WindowEvent::CloseRequested { api } => {
       let listeners = window.manager().listeners();
-      let has_js_listener =
-        listeners.has_js_listener(WINDOW_CLOSE_REQUESTED_EVENT, |target| match target {
-          EventTarget::Window { label } | EventTarget::WebviewWindow { label } => {
-            label == window.label()
-          }
-          _ => false,
-        });
+      let has_js_listener = listeners.has_js_listener(
+        WINDOW_CLOSE_REQUESTED_EVENT,
+        manager.event_target_filter(EventTarget::webview_window(window.label())),
+      );
       if has_js_listener {
         api.prevent_close();
       }
@@ -160,12 +161,12 @@ fn on_window_event<R: Runtime>(
     }
     WindowEvent::Destroyed => {
       window.emit(WINDOW_DESTROYED_EVENT, ())?;
-      let label = window.label();
+      let label = serde_json::to_string(window.label())?;
       let webviews_map = manager.webview.webviews_lock();
       let webviews = webviews_map.values();
       for webview in webviews {
         webview.eval(&format!(
-          r#"(function () {{ const metadata = window.__TAURI_INTERNALS__.metadata; if (metadata != null) {{ metadata.windows = window.__TAURI_INTERNALS__.metadata.windows.filter(w => w.label !== "{label}"); }} }})()"#,
+          r#"(function () {{ const metadata = window.__TAURI_INTERNALS__.metadata; if (metadata != null) {{ metadata.windows = window.__TAURI_INTERNALS__.metadata.windows.filter(w => w.label !== {label}); }} }})()"#,
         ))?;
       }
     }
@@ -208,7 +209,23 @@ fn on_window_event<R: Runtime>(
       FileDropEvent::Cancelled => window.emit(WINDOW_FILE_DROP_CANCELLED_EVENT, ())?,
       _ => unimplemented!(),
     },
-    WindowEvent::ThemeChanged(theme) => window.emit(WINDOW_THEME_CHANGED, theme.to_string())?,
+    WindowEvent::ThemeChanged(theme) => {
+      // webviews with a forced theme keep it when the system theme changes
+      let forced_themes = window
+        .manager()
+        .webview
+        .forced_themes
+        .lock()
+        .unwrap()
+        .clone();
+      window.emit_filter(WINDOW_THEME_CHANGED, theme.to_string(), |target| {
+        !matches!(
+          target,
+          EventTarget::Webview { label } | EventTarget::WebviewWindow { label }
+            if forced_themes.contains(label)
+        )
+      })?
+    }
   }
   Ok(())
 }
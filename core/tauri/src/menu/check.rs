@@ -43,6 +43,11 @@ impl<R: Runtime> CheckMenuItem<R> {
       }
     })?;
 
+    handle
+      .manager
+      .menu
+      .set_item_accelerator(&item.id, accelerator);
+
     Ok(Self(Arc::new(item)))
   }
 
@@ -80,6 +85,11 @@ impl<R: Runtime> CheckMenuItem<R> {
       }
     })?;
 
+    handle
+      .manager
+      .menu
+      .set_item_accelerator(&item.id, accelerator);
+
     Ok(Self(Arc::new(item)))
   }
 
@@ -121,8 +131,14 @@ impl<R: Runtime> CheckMenuItem<R> {
     let accel = accelerator.and_then(|s| s.as_ref().parse().ok());
     run_item_main_thread!(self, |self_: Self| (*self_.0)
       .as_ref()
-      .set_accelerator(accel))?
-    .map_err(Into::into)
+      .set_accelerator(accel))??;
+    self
+      .0
+      .app_handle
+      .manager
+      .menu
+      .set_item_accelerator(self.id(), accel);
+    Ok(())
   }
 
   /// Get whether this check menu item is checked or not.
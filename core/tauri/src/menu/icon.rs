@@ -43,6 +43,11 @@ impl<R: Runtime> IconMenuItem<R> {
       }
     })?;
 
+    handle
+      .manager
+      .menu
+      .set_item_accelerator(&item.id, accelerator);
+
     Ok(Self(Arc::new(item)))
   }
 
@@ -81,6 +86,11 @@ impl<R: Runtime> IconMenuItem<R> {
       }
     })?;
 
+    handle
+      .manager
+      .menu
+      .set_item_accelerator(&item.id, accelerator);
+
     Ok(Self(Arc::new(item)))
   }
 
@@ -119,6 +129,11 @@ impl<R: Runtime> IconMenuItem<R> {
       }
     })?;
 
+    handle
+      .manager
+      .menu
+      .set_item_accelerator(&item.id, accelerator);
+
     Ok(Self(Arc::new(item)))
   }
 
@@ -161,6 +176,11 @@ impl<R: Runtime> IconMenuItem<R> {
       }
     })?;
 
+    handle
+      .manager
+      .menu
+      .set_item_accelerator(&item.id, accelerator);
+
     Ok(Self(Arc::new(item)))
   }
 
@@ -202,8 +222,14 @@ impl<R: Runtime> IconMenuItem<R> {
     let accel = accelerator.and_then(|s| s.as_ref().parse().ok());
     run_item_main_thread!(self, |self_: Self| (*self_.0)
       .as_ref()
-      .set_accelerator(accel))?
-    .map_err(Into::into)
+      .set_accelerator(accel))??;
+    self
+      .0
+      .app_handle
+      .manager
+      .menu
+      .set_item_accelerator(self.id(), accel);
+    Ok(())
   }
 
   /// Change this menu item icon or remove it.
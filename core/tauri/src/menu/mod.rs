@@ -47,6 +47,9 @@ pub(crate) use run_item_main_thread;
 pub struct MenuEvent {
   /// Id of the menu item which triggered this event
   pub id: MenuId,
+  /// Label of the webview focused when the item was activated,
+  /// or of the focused window when none of its webviews is known to be focused.
+  pub webview: Option<String>,
 }
 
 impl MenuEvent {
@@ -54,11 +57,23 @@ impl MenuEvent {
   pub fn id(&self) -> &MenuId {
     &self.id
   }
+
+  /// Returns the label of the webview focused when the item was activated,
+  /// or of the focused window when none of its webviews is known to be focused.
+  ///
+  /// Actions such as "Copy" should apply to this webview.
+  pub fn webview(&self) -> Option<&str> {
+    self.webview.as_deref()
+  }
 }
 
 impl From<muda::MenuEvent> for MenuEvent {
   fn from(value: muda::MenuEvent) -> Self {
-    Self { id: value.id }
+    // the focused webview is set when the event is dispatched on the main thread
+    Self {
+      id: value.id,
+      webview: None,
+    }
   }
 }
 
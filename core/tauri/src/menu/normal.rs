@@ -42,6 +42,11 @@ impl<R: Runtime> MenuItem<R> {
       }
     })?;
 
+    handle
+      .manager
+      .menu
+      .set_item_accelerator(&item.id, accelerator);
+
     Ok(Self(Arc::new(item)))
   }
 
@@ -78,6 +83,11 @@ impl<R: Runtime> MenuItem<R> {
       }
     })?;
 
+    handle
+      .manager
+      .menu
+      .set_item_accelerator(&item.id, accelerator);
+
     Ok(Self(Arc::new(item)))
   }
 
@@ -119,7 +129,13 @@ impl<R: Runtime> MenuItem<R> {
     let accel = accelerator.and_then(|s| s.as_ref().parse().ok());
     run_item_main_thread!(self, |self_: Self| (*self_.0)
       .as_ref()
-      .set_accelerator(accel))?
-    .map_err(Into::into)
+      .set_accelerator(accel))??;
+    self
+      .0
+      .app_handle
+      .manager
+      .menu
+      .set_item_accelerator(self.id(), accel);
+    Ok(())
   }
 }
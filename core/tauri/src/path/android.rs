@@ -4,7 +4,7 @@
 
 use super::Result;
 use crate::{plugin::PluginHandle, Runtime};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// A helper class to access the mobile path APIs.
 pub struct PathResolver<R: Runtime>(pub(crate) PluginHandle<R>);
@@ -14,6 +14,16 @@ struct PathResponse {
   path: PathBuf,
 }
 
+#[derive(serde::Serialize)]
+struct IsSharedStorageArgs<'a> {
+  path: &'a Path,
+}
+
+#[derive(serde::Deserialize)]
+struct IsSharedStorageResponse {
+  value: bool,
+}
+
 impl<R: Runtime> PathResolver<R> {
   fn call_resolve(&self, dir: &str) -> Result<PathBuf> {
     self
@@ -43,22 +53,36 @@ impl<R: Runtime> PathResolver<R> {
     self.call_resolve("getDataDir")
   }
 
+  /// Returns the data directories, which is only [`Self::data_dir`] on Android.
+  pub fn data_dirs(&self) -> Vec<PathBuf> {
+    self.data_dir().into_iter().collect()
+  }
+
   /// Returns the path to the user's local data directory.
   pub fn local_data_dir(&self) -> Result<PathBuf> {
     self.call_resolve("getDataDir")
   }
 
   /// Returns the path to the user's document directory.
+  ///
+  /// Resolves to the app-specific external files directory (`Context.getExternalFilesDir(DIRECTORY_DOCUMENTS)`),
+  /// or to a `Documents` folder in the internal files directory when the external storage is not available.
   pub fn document_dir(&self) -> Result<PathBuf> {
     self.call_resolve("getDocumentDir")
   }
 
   /// Returns the path to the user's download directory.
+  ///
+  /// Resolves to the app-specific external files directory (`Context.getExternalFilesDir(DIRECTORY_DOWNLOADS)`),
+  /// or to a `Download` folder in the internal files directory when the external storage is not available.
   pub fn download_dir(&self) -> Result<PathBuf> {
     self.call_resolve("getDownloadDir")
   }
 
   /// Returns the path to the user's picture directory.
+  ///
+  /// Resolves to the app-specific external files directory (`Context.getExternalFilesDir(DIRECTORY_PICTURES)`),
+  /// or to a `Pictures` folder in the internal files directory when the external storage is not available.
   pub fn picture_dir(&self) -> Result<PathBuf> {
     self.call_resolve("getPictureDir")
   }
@@ -117,4 +141,21 @@ impl<R: Runtime> PathResolver<R> {
   pub fn temp_dir(&self) -> Result<PathBuf> {
     Ok(std::env::temp_dir())
   }
+
+  /// Whether the path is in the device shared storage, outside of the app-specific directories,
+  /// so accessing it requires the Storage Access Framework or the storage permissions.
+  ///
+  /// The directories resolved by this type are never in the shared storage.
+  pub fn is_shared_storage<P: AsRef<Path>>(&self, path: P) -> Result<bool> {
+    self
+      .0
+      .run_mobile_plugin::<IsSharedStorageResponse>(
+        "isSharedStorage",
+        IsSharedStorageArgs {
+          path: path.as_ref(),
+        },
+      )
+      .map(|r| r.value)
+      .map_err(Into::into)
+  }
 }
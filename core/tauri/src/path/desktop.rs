@@ -4,7 +4,7 @@
 
 use super::{Error, Result};
 use crate::{AppHandle, Manager, Runtime};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// A helper class to access the mobile camera APIs.
 pub struct PathResolver<R: Runtime>(pub(crate) AppHandle<R>);
@@ -54,6 +54,18 @@ impl<R: Runtime> PathResolver<R> {
     dirs_next::data_dir().ok_or(Error::UnknownPath)
   }
 
+  /// Returns the user and system data directories that exist, starting with [`Self::data_dir`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux:** Resolves to [`Self::data_dir`] followed by the `$XDG_DATA_DIRS` entries,
+  ///   which default to `/usr/local/share` and `/usr/share`.
+  /// - **macOS:** Resolves to [`Self::data_dir`] and `/Library/Application Support`.
+  /// - **Windows:** Resolves to [`Self::data_dir`] and `{FOLDERID_ProgramData}`.
+  pub fn data_dirs(&self) -> Vec<PathBuf> {
+    existing_dirs(self.data_dir().ok().into_iter().chain(system_data_dirs()))
+  }
+
   /// Returns the path to the user's local data directory.
   ///
   /// ## Platform-specific
@@ -120,6 +132,23 @@ impl<R: Runtime> PathResolver<R> {
     dirs_next::font_dir().ok_or(Error::UnknownPath)
   }
 
+  /// Returns the user and system font directories that exist, starting with the user directories.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux:** Resolves to [`Self::font_dir`] followed by the `fonts` folder of each `$XDG_DATA_DIRS` entry,
+  ///   e.g. `/usr/local/share/fonts` and `/usr/share/fonts`.
+  /// - **macOS:** Resolves to [`Self::font_dir`], `/Library/Fonts` and `/System/Library/Fonts`.
+  /// - **Windows:** Resolves to `{FOLDERID_LocalAppData}\Microsoft\Windows\Fonts`, where the fonts installed for the current user are,
+  ///   and `%WINDIR%\Fonts`.
+  pub fn font_dirs(&self) -> Vec<PathBuf> {
+    #[cfg(windows)]
+    let user_dir = dirs_next::data_local_dir().map(|dir| dir.join("Microsoft\\Windows\\Fonts"));
+    #[cfg(not(windows))]
+    let user_dir = dirs_next::font_dir();
+    existing_dirs(user_dir.into_iter().chain(system_font_dirs()))
+  }
+
   /// Returns the path to the user's home directory.
   ///
   /// ## Platform-specific
@@ -253,4 +282,178 @@ impl<R: Runtime> PathResolver<R> {
   pub fn temp_dir(&self) -> Result<PathBuf> {
     Ok(std::env::temp_dir())
   }
+
+  /// Whether the path is in the device shared storage, so accessing it requires additional permissions.
+  ///
+  /// Always `false` on this platform, only Android has a shared storage.
+  pub fn is_shared_storage<P: AsRef<Path>>(&self, path: P) -> Result<bool> {
+    let _ = path;
+    Ok(false)
+  }
+}
+
+/// Keeps the directories that exist, removing the duplicates but keeping the order.
+fn existing_dirs(dirs: impl IntoIterator<Item = PathBuf>) -> Vec<PathBuf> {
+  let mut existing: Vec<PathBuf> = Vec::new();
+  for dir in dirs {
+    if dir.is_dir() && !existing.contains(&dir) {
+      existing.push(dir);
+    }
+  }
+  existing
+}
+
+/// The `$XDG_DATA_DIRS` entries, ignoring the relative paths as required by the specification.
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+))]
+fn xdg_data_dirs() -> Vec<PathBuf> {
+  let dirs = std::env::var_os("XDG_DATA_DIRS")
+    .map(|dirs| {
+      std::env::split_paths(&dirs)
+        .filter(|dir| dir.is_absolute())
+        .collect::<Vec<_>>()
+    })
+    .unwrap_or_default();
+  if dirs.is_empty() {
+    vec!["/usr/local/share".into(), "/usr/share".into()]
+  } else {
+    dirs
+  }
+}
+
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+))]
+fn system_data_dirs() -> Vec<PathBuf> {
+  xdg_data_dirs()
+}
+
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+))]
+fn system_font_dirs() -> Vec<PathBuf> {
+  xdg_data_dirs()
+    .into_iter()
+    .map(|dir| dir.join("fonts"))
+    .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn system_data_dirs() -> Vec<PathBuf> {
+  vec!["/Library/Application Support".into()]
+}
+
+#[cfg(target_os = "macos")]
+fn system_font_dirs() -> Vec<PathBuf> {
+  vec!["/Library/Fonts".into(), "/System/Library/Fonts".into()]
+}
+
+#[cfg(windows)]
+fn system_data_dirs() -> Vec<PathBuf> {
+  std::env::var_os("ProgramData")
+    .map(PathBuf::from)
+    .into_iter()
+    .collect()
+}
+
+#[cfg(windows)]
+fn system_font_dirs() -> Vec<PathBuf> {
+  let windows_dir = std::env::var_os("WINDIR")
+    .or_else(|| std::env::var_os("SystemRoot"))
+    .map(PathBuf::from)
+    .unwrap_or_else(|| "C:\\Windows".into());
+  vec![windows_dir.join("Fonts")]
+}
+
+#[cfg(target_os = "ios")]
+fn system_data_dirs() -> Vec<PathBuf> {
+  Vec::new()
+}
+
+#[cfg(target_os = "ios")]
+fn system_font_dirs() -> Vec<PathBuf> {
+  Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::PathBuf;
+
+  use super::existing_dirs;
+
+  #[test]
+  fn existing_dirs_keep_order() {
+    let temp = std::env::temp_dir();
+    let missing = temp.join("tauri-path-test-missing-dir");
+    assert_eq!(
+      existing_dirs([
+        missing.clone(),
+        temp.clone(),
+        PathBuf::from("/"),
+        temp.clone(),
+        missing
+      ]),
+      [temp, PathBuf::from("/")]
+    );
+  }
+
+  #[cfg(target_os = "linux")]
+  #[test]
+  fn xdg_dirs() {
+    use crate::Manager;
+
+    let base = std::env::temp_dir().join(format!("tauri-path-test-xdg-{}", std::process::id()));
+    let home = base.join("home");
+    let system = base.join("system");
+    let other = base.join("other");
+    for dir in [&home, &system, &other] {
+      std::fs::create_dir_all(dir.join("fonts")).unwrap();
+    }
+    std::env::set_var("XDG_DATA_HOME", &home);
+    std::env::set_var(
+      "XDG_DATA_DIRS",
+      std::env::join_paths([
+        system.clone(),
+        PathBuf::from("relative/share"),
+        base.join("missing"),
+        system.clone(),
+        other.clone(),
+      ])
+      .unwrap(),
+    );
+
+    let app = crate::test::mock_app();
+    assert_eq!(
+      app.path().data_dirs(),
+      [home.clone(), system.clone(), other.clone()]
+    );
+    assert_eq!(
+      app.path().font_dirs(),
+      [
+        home.join("fonts"),
+        system.join("fonts"),
+        other.join("fonts")
+      ]
+    );
+
+    std::env::set_var("XDG_DATA_DIRS", "");
+    assert_eq!(app.path().data_dirs()[0], home);
+
+    std::env::remove_var("XDG_DATA_HOME");
+    std::env::remove_var("XDG_DATA_DIRS");
+    std::fs::remove_dir_all(base).unwrap();
+  }
 }
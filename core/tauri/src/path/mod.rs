@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use std::path::{Component, Display, Path, PathBuf};
+use std::{
+  borrow::Cow,
+  path::{Component, Display, Path, PathBuf},
+};
 
 use crate::Runtime;
 
@@ -23,6 +26,12 @@ pub use android::PathResolver;
 #[cfg(not(target_os = "android"))]
 pub use desktop::PathResolver;
 
+#[cfg(feature = "path-watch")]
+mod watch;
+#[cfg(feature = "path-watch")]
+#[cfg_attr(docsrs, doc(cfg(feature = "path-watch")))]
+pub use watch::{PathEvent, WatchOptions, WatcherHandle, DEFAULT_DEBOUNCE, FS_CHANGE_EVENT};
+
 /// A wrapper for [`PathBuf`] that prevents path traversal.
 #[derive(Clone, Debug)]
 pub struct SafePathBuf(PathBuf);
@@ -210,6 +219,49 @@ impl BaseDirectory {
   }
 }
 
+/// A directory that is either one of the known [`BaseDirectory`] variants or an arbitrary path.
+#[derive(Debug, Clone)]
+pub enum Directory<'a> {
+  /// A known base directory.
+  Base(BaseDirectory),
+  /// An arbitrary path.
+  Path(Cow<'a, Path>),
+}
+
+impl Directory<'_> {
+  /// Resolves the directory to an absolute path using the given resolver.
+  pub fn resolve<R: Runtime>(&self, resolver: &PathResolver<R>) -> Result<PathBuf> {
+    match self {
+      Self::Base(base) => resolve_path::<R>(resolver, *base, None),
+      Self::Path(path) => Ok(path.to_path_buf()),
+    }
+  }
+}
+
+impl From<BaseDirectory> for Directory<'_> {
+  fn from(base: BaseDirectory) -> Self {
+    Self::Base(base)
+  }
+}
+
+impl<'a> From<&'a Path> for Directory<'a> {
+  fn from(path: &'a Path) -> Self {
+    Self::Path(Cow::Borrowed(path))
+  }
+}
+
+impl<'a> From<&'a PathBuf> for Directory<'a> {
+  fn from(path: &'a PathBuf) -> Self {
+    Self::Path(Cow::Borrowed(path.as_path()))
+  }
+}
+
+impl From<PathBuf> for Directory<'_> {
+  fn from(path: PathBuf) -> Self {
+    Self::Path(Cow::Owned(path))
+  }
+}
+
 impl<R: Runtime> PathResolver<R> {
   /// Resolves the path with the base directory.
   ///
@@ -228,7 +280,13 @@ impl<R: Runtime> PathResolver<R> {
     resolve_path::<R>(self, base_directory, Some(path.as_ref().to_path_buf()))
   }
 
-  /// Parse the given path, resolving a [`BaseDirectory`] variable if the path starts with one.
+  /// Parse the given path, resolving a [`BaseDirectory`] variable if the path starts with one,
+  /// such as `$APPDATA/config.json`. This is the format used by the JavaScript `path` APIs and the file system scopes.
+  ///
+  /// The path is normalized lexically, using the platform path separator.
+  /// Returns [`Error::UnknownPathVariable`] if the path starts with `$` but is not a [`BaseDirectory::variable`],
+  /// and [`Error::PathEscapesBase`] if a `..` component goes above the resolved variable, the root of an absolute path
+  /// or the start of a relative path.
   ///
   /// # Examples
   ///
@@ -238,33 +296,209 @@ impl<R: Runtime> PathResolver<R> {
   ///   .setup(|app| {
   ///     let path = app.path().parse("$HOME/.bashrc")?;
   ///     assert_eq!(path.to_str().unwrap(), "/home/${whoami}/.bashrc");
+  ///     assert!(app.path().parse("$HOME/../etc/passwd").is_err());
   ///     Ok(())
   ///   });
   /// ```
   pub fn parse<P: AsRef<Path>>(&self, path: P) -> Result<PathBuf> {
-    let mut p = PathBuf::new();
-    let mut components = path.as_ref().components();
+    let path = path.as_ref();
+    let mut components = path.components();
     match components.next() {
-      Some(Component::Normal(str)) => {
-        if let Some(base_directory) = BaseDirectory::from_variable(&str.to_string_lossy()) {
-          p.push(resolve_path::<R>(self, base_directory, None)?);
-        } else {
-          p.push(str);
+      Some(Component::Normal(first)) if first.to_string_lossy().starts_with('$') => {
+        let variable = first.to_string_lossy();
+        let base_directory = BaseDirectory::from_variable(&variable)
+          .ok_or_else(|| Error::UnknownPathVariable(variable.into_owned()))?;
+        let base = resolve_path::<R>(self, base_directory, None)?;
+        normalize_components(base, components, path)
+      }
+      _ => normalize_lexically(path),
+    }
+  }
+
+  /// Checks whether the `candidate` path is located inside the `base` directory.
+  ///
+  /// Both paths are canonicalized before the check so symbolic links pointing outside the base directory are detected.
+  /// If the candidate path does not exist yet, its deepest existing ancestor is canonicalized instead.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use tauri::{path::BaseDirectory, Manager};
+  /// tauri::Builder::default()
+  ///   .setup(|app| {
+  ///     let data_dir = app.path().app_data_dir()?;
+  ///     assert!(app.path().is_within(&data_dir.join("db.sqlite"), BaseDirectory::AppData)?);
+  ///     assert!(!app.path().is_within(&data_dir.join("../../etc"), BaseDirectory::AppData)?);
+  ///     Ok(())
+  ///   });
+  /// ```
+  pub fn is_within<'a, D: Into<Directory<'a>>>(&self, candidate: &Path, base: D) -> Result<bool> {
+    let base = canonicalize_lenient(&base.into().resolve(self)?)?;
+    let candidate = canonicalize_lenient(candidate)?;
+    Ok(candidate.starts_with(base))
+  }
+
+  /// Joins the `relative` path to the `base` directory, returning the canonicalized path.
+  ///
+  /// Returns [`Error::PathEscapesBase`] if the relative path is absolute, contains a prefix
+  /// (such as a Windows drive), references an NTFS alternate data stream or resolves outside the base directory,
+  /// including through symbolic links.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use tauri::{path::BaseDirectory, Manager};
+  /// tauri::Builder::default()
+  ///   .setup(|app| {
+  ///     let path = app.path().safe_join(BaseDirectory::AppData, "logs/app.log")?;
+  ///     assert!(app.path().safe_join(BaseDirectory::AppData, "../secret").is_err());
+  ///     Ok(())
+  ///   });
+  /// ```
+  pub fn safe_join<'a, D: Into<Directory<'a>>, P: AsRef<Path>>(
+    &self,
+    base: D,
+    relative: P,
+  ) -> Result<PathBuf> {
+    let base = canonicalize_lenient(&base.into().resolve(self)?)?;
+    safe_join(&base, relative.as_ref())
+  }
+}
+
+/// Joins `relative` to the canonical `base` path, erroring if the result escapes it.
+pub(crate) fn safe_join(base: &Path, relative: &Path) -> Result<PathBuf> {
+  let escapes = || Error::PathEscapesBase(relative.to_path_buf());
+
+  for component in relative.components() {
+    match component {
+      Component::Prefix(_) | Component::RootDir => return Err(escapes()),
+      // reject NTFS alternate data streams (`file.txt:stream`)
+      Component::Normal(c) if cfg!(windows) && c.to_string_lossy().contains(':') => {
+        return Err(escapes())
+      }
+      _ => {}
+    }
+  }
+
+  let joined = canonicalize_lenient(&base.join(relative))?;
+  if joined.starts_with(base) {
+    Ok(joined)
+  } else {
+    Err(escapes())
+  }
+}
+
+/// Normalizes the path without accessing the file system, removing `.` and resolving `..` components.
+///
+/// Errors if a `..` component goes above the root of an absolute path or the start of a relative path.
+pub(crate) fn normalize_lexically(path: &Path) -> Result<PathBuf> {
+  let mut components = path.components().peekable();
+  let mut base = PathBuf::new();
+  while let Some(component @ (Component::Prefix(_) | Component::RootDir)) = components.peek() {
+    base.push(component);
+    components.next();
+  }
+  normalize_components(base, components, path)
+}
+
+/// Pushes the normalized `components` of `path` to `base`, erroring if a `..` component escapes it.
+fn normalize_components<'a>(
+  mut base: PathBuf,
+  components: impl Iterator<Item = Component<'a>>,
+  path: &Path,
+) -> Result<PathBuf> {
+  let mut relative = Vec::new();
+  for component in components {
+    match component {
+      Component::CurDir => {}
+      Component::ParentDir => {
+        if relative.pop().is_none() {
+          return Err(Error::PathEscapesBase(path.to_path_buf()));
         }
       }
-      Some(component) => p.push(component),
-      None => (),
+      component => relative.push(component),
     }
+  }
+  base.extend(relative);
+  Ok(base)
+}
+
+/// The maximum number of dangling symbolic links [`canonicalize_lenient`] follows, like `ELOOP`.
+const MAX_DANGLING_LINKS: usize = 40;
+
+/// Canonicalizes the path, allowing its trailing components to not exist.
+///
+/// The deepest existing ancestor is canonicalized and the remaining components are normalized lexically.
+/// A dangling symbolic link is resolved to its target, so the path points to the file it would create.
+pub(crate) fn canonicalize_lenient(path: &Path) -> Result<PathBuf> {
+  canonicalize_lenient_inner(path, 0)
+}
+
+fn canonicalize_lenient_inner(path: &Path, followed_links: usize) -> Result<PathBuf> {
+  let path = if path.is_absolute() {
+    path.to_path_buf()
+  } else {
+    std::env::current_dir()
+      .map_err(Error::CurrentDir)?
+      .join(path)
+  };
 
-    for component in components {
-      if let Component::ParentDir = component {
-        continue;
+  let mut existing = path.as_path();
+  let mut remaining = Vec::new();
+  let mut canonical = loop {
+    match existing.canonicalize() {
+      Ok(p) => break p,
+      // the link exists but not its target
+      Err(e)
+        if existing
+          .symlink_metadata()
+          .is_ok_and(|metadata| metadata.file_type().is_symlink()) =>
+      {
+        if followed_links == MAX_DANGLING_LINKS {
+          return Err(Error::Io(e));
+        }
+        let target = std::fs::read_link(existing)?;
+        // relative targets are relative to the directory of the link
+        let mut resolved = match existing.parent() {
+          Some(parent) => parent.join(target),
+          None => target,
+        };
+        resolved.extend(remaining.iter().rev());
+        return canonicalize_lenient_inner(&resolved, followed_links + 1);
       }
-      p.push(component);
+      Err(e) => match (existing.parent(), existing.file_name()) {
+        (Some(parent), Some(name)) => {
+          remaining.push(name.to_os_string());
+          existing = parent;
+        }
+        // `..` and `.` components have no file name
+        (Some(parent), None) => {
+          remaining.push(
+            existing
+              .components()
+              .next_back()
+              .unwrap()
+              .as_os_str()
+              .into(),
+          );
+          existing = parent;
+        }
+        (None, _) => return Err(Error::Io(e)),
+      },
     }
+  };
 
-    Ok(p)
+  for component in remaining.iter().rev() {
+    match Path::new(component).components().next() {
+      Some(Component::ParentDir) => {
+        canonical.pop();
+      }
+      Some(Component::CurDir) | None => {}
+      Some(_) => canonical.push(component),
+    }
   }
+
+  Ok(canonical)
 }
 
 fn resolve_path<R: Runtime>(
@@ -329,10 +563,10 @@ fn resolve_path<R: Runtime>(
 
 #[cfg(test)]
 mod test {
-  use super::SafePathBuf;
+  use super::{canonicalize_lenient, safe_join, Error, SafePathBuf};
   use quickcheck::{Arbitrary, Gen};
 
-  use std::path::PathBuf;
+  use std::path::{Path, PathBuf};
 
   impl Arbitrary for SafePathBuf {
     fn arbitrary(g: &mut Gen) -> Self {
@@ -343,4 +577,134 @@ mod test {
       Box::new(self.0.shrink().map(SafePathBuf))
     }
   }
+
+  fn temp_base(name: &str) -> PathBuf {
+    let base = std::env::temp_dir().join(format!("tauri-path-test-{name}-{}", std::process::id()));
+    std::fs::create_dir_all(base.join("inner")).unwrap();
+    base.canonicalize().unwrap()
+  }
+
+  #[test]
+  fn canonicalize_missing_leaf() {
+    let base = temp_base("missing-leaf");
+    assert_eq!(
+      canonicalize_lenient(&base.join("inner/missing/../other.txt")).unwrap(),
+      base.join("inner/other.txt")
+    );
+    assert_eq!(
+      canonicalize_lenient(&base.join("missing/../../")).unwrap(),
+      base.parent().unwrap()
+    );
+  }
+
+  #[test]
+  fn safe_join_rejects_escapes() {
+    let base = temp_base("escapes");
+    assert_eq!(
+      safe_join(&base, Path::new("inner/file.txt")).unwrap(),
+      base.join("inner/file.txt")
+    );
+    assert_eq!(
+      safe_join(&base, Path::new("inner/../file.txt")).unwrap(),
+      base.join("file.txt")
+    );
+    assert!(safe_join(&base, Path::new("../file.txt")).is_err());
+    assert!(safe_join(&base, Path::new("inner/../../file.txt")).is_err());
+    assert!(safe_join(&base, &std::env::temp_dir()).is_err());
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn safe_join_rejects_symlink_escapes() {
+    let base = temp_base("symlink");
+    let link = base.join("link");
+    let _ = std::fs::remove_file(&link);
+    std::os::unix::fs::symlink("/etc", &link).unwrap();
+    assert!(safe_join(&base, Path::new("link/passwd")).is_err());
+    assert!(safe_join(&base, Path::new("link")).is_err());
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn dangling_symlinks_are_resolved() {
+    let base = temp_base("dangling");
+    let outside = temp_base("dangling-target");
+    for (name, target) in [
+      ("out", outside.join("missing")),
+      ("in", PathBuf::from("inner/missing")),
+      ("loop", PathBuf::from("loop")),
+    ] {
+      let _ = std::fs::remove_file(base.join(name));
+      std::os::unix::fs::symlink(target, base.join(name)).unwrap();
+    }
+
+    assert_eq!(
+      canonicalize_lenient(&base.join("out/file.txt")).unwrap(),
+      outside.join("missing/file.txt")
+    );
+    assert!(safe_join(&base, Path::new("out")).is_err());
+    assert!(safe_join(&base, Path::new("out/file.txt")).is_err());
+    assert_eq!(
+      safe_join(&base, Path::new("in/file.txt")).unwrap(),
+      base.join("inner/missing/file.txt")
+    );
+    assert!(canonicalize_lenient(&base.join("loop")).is_err());
+  }
+
+  #[cfg(windows)]
+  #[test]
+  fn safe_join_windows_edge_cases() {
+    let base = temp_base("windows");
+    // canonical paths use the verbatim `\\?\` prefix
+    assert!(base.to_string_lossy().starts_with(r"\\?\"));
+    assert!(safe_join(&base, Path::new(r"inner\file.txt")).is_ok());
+    // drive-relative path
+    assert!(safe_join(&base, Path::new(r"C:file.txt")).is_err());
+    // alternate data stream
+    assert!(safe_join(&base, Path::new(r"inner\file.txt:stream")).is_err());
+    assert!(safe_join(&base, Path::new(r"\\?\C:\Windows")).is_err());
+  }
+  #[test]
+  fn parse_variables() {
+    use crate::Manager;
+    let app = crate::test::mock_app();
+    let temp = std::env::temp_dir();
+
+    assert_eq!(
+      app.path().parse("$TEMP/a/./b").unwrap(),
+      temp.join("a").join("b")
+    );
+    assert_eq!(app.path().parse("$TEMP/a/../b").unwrap(), temp.join("b"));
+    assert_eq!(app.path().parse("a/../b").unwrap(), PathBuf::from("b"));
+
+    match app.path().parse("$UNKNOWN/file") {
+      Err(Error::UnknownPathVariable(variable)) => assert_eq!(variable, "$UNKNOWN"),
+      r => panic!("unexpected result {r:?}"),
+    }
+    assert!(matches!(
+      app.path().parse("$TEMP/a/../../b"),
+      Err(Error::PathEscapesBase(_))
+    ));
+    assert!(matches!(
+      app.path().parse("../b"),
+      Err(Error::PathEscapesBase(_))
+    ));
+  }
+
+  #[cfg(windows)]
+  #[test]
+  fn parse_normalizes_windows_separators() {
+    use crate::Manager;
+    let app = crate::test::mock_app();
+    let temp = std::env::temp_dir();
+
+    let parsed = app.path().parse(r"$TEMP\a/b\c").unwrap();
+    assert_eq!(parsed, temp.join(r"a\b\c"));
+    assert!(!parsed.to_string_lossy().contains('/'));
+    assert_eq!(
+      app.path().parse(r"C:/Users\..\Windows/./System32").unwrap(),
+      PathBuf::from(r"C:\Windows\System32")
+    );
+    assert!(app.path().parse(r"C:\..\Windows").is_err());
+  }
 }
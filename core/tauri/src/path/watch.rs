@@ -0,0 +1,281 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! File system watcher for the [`PathResolver`] known directories.
+
+use std::{
+  path::PathBuf,
+  sync::mpsc::{channel, RecvTimeoutError},
+  time::{Duration, Instant},
+};
+
+use notify::{
+  event::{ModifyKind, RenameMode},
+  EventKind, RecommendedWatcher, RecursiveMode, Watcher,
+};
+use serde::Serialize;
+
+use super::{Directory, PathResolver};
+use crate::{Manager, Runtime};
+
+/// The event emitted to the JavaScript listeners when [`WatchOptions::emit_event`] is enabled.
+pub const FS_CHANGE_EVENT: &str = "tauri://fs-change";
+
+/// The default debounce duration used by [`PathResolver::watch`].
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// A change detected on a watched directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum PathEvent {
+  /// A file or directory was created.
+  Created {
+    /// The created path.
+    path: PathBuf,
+  },
+  /// A file or directory was modified.
+  Modified {
+    /// The modified path.
+    path: PathBuf,
+  },
+  /// A file or directory was removed.
+  Removed {
+    /// The removed path.
+    path: PathBuf,
+  },
+  /// A file or directory was renamed.
+  Renamed {
+    /// The old path.
+    from: PathBuf,
+    /// The new path.
+    to: PathBuf,
+  },
+}
+
+/// Options for [`PathResolver::watch_with_options`].
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+  recursive: bool,
+  debounce: Duration,
+  emit_event: bool,
+}
+
+impl Default for WatchOptions {
+  fn default() -> Self {
+    Self {
+      recursive: false,
+      debounce: DEFAULT_DEBOUNCE,
+      emit_event: false,
+    }
+  }
+}
+
+impl WatchOptions {
+  /// Whether subdirectories should be watched too. Defaults to `false`.
+  #[must_use]
+  pub fn recursive(mut self, recursive: bool) -> Self {
+    self.recursive = recursive;
+    self
+  }
+
+  /// How long the watcher waits for the file system events to stop before calling the handler,
+  /// coalescing the events received in the meantime. Defaults to [`DEFAULT_DEBOUNCE`].
+  #[must_use]
+  pub fn debounce(mut self, debounce: Duration) -> Self {
+    self.debounce = debounce;
+    self
+  }
+
+  /// Whether the `tauri://fs-change` event should be emitted to all targets for each change.
+  /// Defaults to `false`.
+  #[must_use]
+  pub fn emit_event(mut self, emit: bool) -> Self {
+    self.emit_event = emit;
+    self
+  }
+}
+
+/// A handle to a directory watcher created with [`PathResolver::watch`].
+///
+/// The directory is no longer watched when this handle is dropped.
+#[derive(Debug)]
+pub struct WatcherHandle {
+  path: PathBuf,
+  _watcher: RecommendedWatcher,
+}
+
+impl WatcherHandle {
+  /// The watched path.
+  pub fn path(&self) -> &std::path::Path {
+    &self.path
+  }
+
+  /// Stops watching the directory. Equivalent to dropping the handle.
+  pub fn unwatch(self) {}
+}
+
+impl<R: Runtime> PathResolver<R> {
+  /// Watches the given directory for changes, calling the handler with debounced [`PathEvent`]s.
+  ///
+  /// The directory stops being watched when the returned [`WatcherHandle`] is dropped.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use tauri::{path::{BaseDirectory, PathEvent}, Manager};
+  /// tauri::Builder::default()
+  ///   .setup(|app| {
+  ///     let handle = app.path().watch(BaseDirectory::Download, false, |event| {
+  ///       if let PathEvent::Created { path } = event {
+  ///         println!("new download: {}", path.display());
+  ///       }
+  ///     })?;
+  ///     // keep the watcher alive for the application lifetime
+  ///     app.manage(handle);
+  ///     Ok(())
+  ///   });
+  /// ```
+  pub fn watch<'a, D, F>(&self, dir: D, recursive: bool, handler: F) -> crate::Result<WatcherHandle>
+  where
+    D: Into<Directory<'a>>,
+    F: Fn(PathEvent) + Send + 'static,
+  {
+    self.watch_with_options(dir, WatchOptions::default().recursive(recursive), handler)
+  }
+
+  /// Watches the given directory for changes with the given [`WatchOptions`].
+  ///
+  /// See [`Self::watch`] for more information.
+  pub fn watch_with_options<'a, D, F>(
+    &self,
+    dir: D,
+    options: WatchOptions,
+    handler: F,
+  ) -> crate::Result<WatcherHandle>
+  where
+    D: Into<Directory<'a>>,
+    F: Fn(PathEvent) + Send + 'static,
+  {
+    let path = dir.into().resolve(self)?;
+
+    let (tx, rx) = channel();
+    let mut watcher = notify::recommended_watcher(move |res| {
+      let _ = tx.send(res);
+    })?;
+    watcher.watch(
+      &path,
+      if options.recursive {
+        RecursiveMode::Recursive
+      } else {
+        RecursiveMode::NonRecursive
+      },
+    )?;
+
+    #[cfg(not(target_os = "android"))]
+    let app = options.emit_event.then(|| self.0.clone());
+    #[cfg(target_os = "android")]
+    let app = options.emit_event.then(|| self.0.app().clone());
+    let debounce = options.debounce;
+    std::thread::spawn(move || {
+      let mut pending: Vec<PathEvent> = Vec::new();
+      let mut deadline: Option<Instant> = None;
+      loop {
+        let timeout = deadline
+          .map(|d| d.saturating_duration_since(Instant::now()))
+          .unwrap_or(Duration::MAX);
+        match rx.recv_timeout(timeout) {
+          Ok(Ok(event)) => {
+            for event in map_event(event) {
+              coalesce(&mut pending, event);
+            }
+            // the events are delivered once no event was received for the debounce duration
+            if !pending.is_empty() {
+              deadline.replace(Instant::now() + debounce);
+            }
+          }
+          Ok(Err(e)) => {
+            tauri_utils::debug_eprintln!("file watcher error: {}", e);
+          }
+          Err(RecvTimeoutError::Timeout) => {
+            deadline = None;
+            for event in std::mem::take(&mut pending) {
+              if let Some(app) = &app {
+                let _ = app.emit(FS_CHANGE_EVENT, &event);
+              }
+              handler(event);
+            }
+          }
+          // the watcher was dropped
+          Err(RecvTimeoutError::Disconnected) => break,
+        }
+      }
+    });
+
+    Ok(WatcherHandle {
+      path,
+      _watcher: watcher,
+    })
+  }
+}
+
+fn map_event(event: notify::Event) -> Vec<PathEvent> {
+  let mut paths = event.paths.into_iter();
+  match event.kind {
+    EventKind::Create(_) => paths.map(|path| PathEvent::Created { path }).collect(),
+    EventKind::Remove(_) => paths.map(|path| PathEvent::Removed { path }).collect(),
+    EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => match (paths.next(), paths.next()) {
+      (Some(from), Some(to)) => vec![PathEvent::Renamed { from, to }],
+      _ => Vec::new(),
+    },
+    EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+      paths.map(|path| PathEvent::Removed { path }).collect()
+    }
+    EventKind::Modify(ModifyKind::Name(RenameMode::To)) => {
+      paths.map(|path| PathEvent::Created { path }).collect()
+    }
+    EventKind::Modify(_) => paths.map(|path| PathEvent::Modified { path }).collect(),
+    _ => Vec::new(),
+  }
+}
+
+/// Adds the event to the pending list, skipping duplicates
+/// and modifications of paths that were created before the events were delivered.
+fn coalesce(pending: &mut Vec<PathEvent>, event: PathEvent) {
+  if pending.contains(&event) {
+    return;
+  }
+  if let PathEvent::Modified { path } = &event {
+    if pending
+      .iter()
+      .any(|e| matches!(e, PathEvent::Created { path: p } if p == path))
+    {
+      return;
+    }
+  }
+  pending.push(event);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{coalesce, PathEvent};
+  use std::path::PathBuf;
+
+  #[test]
+  fn coalesces_events() {
+    let path = PathBuf::from("file.txt");
+    let mut pending = Vec::new();
+    coalesce(&mut pending, PathEvent::Created { path: path.clone() });
+    coalesce(&mut pending, PathEvent::Modified { path: path.clone() });
+    coalesce(&mut pending, PathEvent::Modified { path: path.clone() });
+    coalesce(&mut pending, PathEvent::Removed { path: path.clone() });
+    assert_eq!(
+      pending,
+      vec![
+        PathEvent::Created { path: path.clone() },
+        PathEvent::Removed { path }
+      ]
+    );
+  }
+}
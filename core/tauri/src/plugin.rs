@@ -5,12 +5,12 @@
 //! The Tauri plugin extension to expand Tauri functionality.
 
 use crate::{
-  app::UriSchemeResponder,
+  app::{ProtocolRequestContext, UriSchemeResponder},
   ipc::{Invoke, InvokeHandler, ScopeObject, ScopeValue},
   manager::webview::UriSchemeProtocol,
   utils::config::PluginConfig,
-  webview::PageLoadPayload,
-  AppHandle, Error, RunEvent, Runtime, Webview, Window,
+  webview::{PageLoadPayload, WebviewBuilderParts},
+  AppHandle, Error, Manager, RunEvent, Runtime, Webview, Window,
 };
 use serde::de::DeserializeOwned;
 use serde_json::Value as JsonValue;
@@ -28,11 +28,19 @@ use std::{
 #[cfg(mobile)]
 pub mod mobile;
 
+/// Emitted with the plugin name once a plugin is initialized.
+const PLUGIN_INITIALIZED_EVENT: &str = "tauri://plugin-initialized";
+
 /// The plugin interface.
 pub trait Plugin<R: Runtime>: Send {
   /// The plugin name. Used as key on the plugin config object.
   fn name(&self) -> &'static str;
 
+  /// The names of the plugins that must be initialized before this plugin.
+  fn dependencies(&self) -> &[&'static str] {
+    &[]
+  }
+
   /// Initializes the plugin.
   #[allow(unused_variables)]
   fn initialize(
@@ -59,6 +67,10 @@ pub trait Plugin<R: Runtime>: Send {
   #[allow(unused_variables)]
   fn window_created(&mut self, window: Window<R>) {}
 
+  /// Callback invoked when a webview is built, before it is created.
+  #[allow(unused_variables)]
+  fn webview_builder(&mut self, builder: &mut WebviewBuilderParts<'_>) {}
+
   /// Callback invoked when the webview is created.
   #[allow(unused_variables)]
   fn webview_created(&mut self, webview: Webview<R>) {}
@@ -87,6 +99,7 @@ pub trait Plugin<R: Runtime>: Send {
 type SetupHook<R, C> =
   dyn FnOnce(&AppHandle<R>, PluginApi<R, C>) -> Result<(), Box<dyn std::error::Error>> + Send;
 type OnWindowReady<R> = dyn FnMut(Window<R>) + Send;
+type OnWebviewBuilder = dyn FnMut(&mut WebviewBuilderParts<'_>) + Send;
 type OnWebviewReady<R> = dyn FnMut(Webview<R>) + Send;
 type OnEvent<R> = dyn FnMut(&AppHandle<R>, &RunEvent) + Send;
 type OnNavigation<R> = dyn Fn(&Webview<R>, &Url) -> bool + Send;
@@ -223,12 +236,14 @@ impl<R: Runtime, C: DeserializeOwned> PluginApi<R, C> {
 /// ```
 pub struct Builder<R: Runtime, C: DeserializeOwned = ()> {
   name: &'static str,
+  dependencies: Vec<&'static str>,
   invoke_handler: Box<InvokeHandler<R>>,
   setup: Option<Box<SetupHook<R, C>>>,
   js_init_script: Option<String>,
   on_navigation: Box<OnNavigation<R>>,
   on_page_load: Box<OnPageLoad<R>>,
   on_window_ready: Box<OnWindowReady<R>>,
+  on_webview_builder: Box<OnWebviewBuilder>,
   on_webview_ready: Box<OnWebviewReady<R>>,
   on_event: Box<OnEvent<R>>,
   on_drop: Option<Box<OnDrop<R>>>,
@@ -240,12 +255,14 @@ impl<R: Runtime, C: DeserializeOwned> Builder<R, C> {
   pub fn new(name: &'static str) -> Self {
     Self {
       name,
+      dependencies: Vec::new(),
       setup: None,
       js_init_script: None,
       invoke_handler: Box::new(|_| false),
       on_navigation: Box::new(|_, _| true),
       on_page_load: Box::new(|_, _| ()),
       on_window_ready: Box::new(|_| ()),
+      on_webview_builder: Box::new(|_| ()),
       on_webview_ready: Box::new(|_| ()),
       on_event: Box::new(|_, _| ()),
       on_drop: None,
@@ -253,6 +270,28 @@ impl<R: Runtime, C: DeserializeOwned> Builder<R, C> {
     }
   }
 
+  /// Declares that the plugin requires the plugin with the given name,
+  /// which is then initialized first regardless of the registration order.
+  ///
+  /// The app fails to start if the required plugin is not registered or if the dependencies form a cycle.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use tauri::{plugin::{Builder, TauriPlugin}, Runtime};
+  ///
+  /// fn init<R: Runtime>() -> TauriPlugin<R> {
+  ///   Builder::new("example")
+  ///     .requires("store")
+  ///     .build()
+  /// }
+  /// ```
+  #[must_use]
+  pub fn requires(mut self, plugin: &'static str) -> Self {
+    self.dependencies.push(plugin);
+    self
+  }
+
   /// Defines the JS message handler callback.
   /// It is recommended you use the [tauri::generate_handler] to generate the input to this method, as the input type is not considered stable yet.
   ///
@@ -281,6 +320,9 @@ impl<R: Runtime, C: DeserializeOwned> Builder<R, C> {
   where
     F: Fn(Invoke<R>) -> bool + Send + Sync + 'static,
   {
+    // plugin commands are not part of the app command manifest
+    #[cfg(feature = "command-manifest")]
+    let _ = crate::ipc::manifest::take_handler_manifest::<F>();
     self.invoke_handler = Box::new(invoke_handler);
     self
   }
@@ -428,6 +470,40 @@ impl<R: Runtime, C: DeserializeOwned> Builder<R, C> {
     self
   }
 
+  /// Callback invoked when a webview is built, before it is created, to change its options.
+  ///
+  /// Unlike [`Self::on_webview_ready`] and [`Self::js_init_script`], it can add initialization scripts and navigation checks
+  /// to a single webview. The plugins are called in the order they are registered, after the app configured the webview.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use tauri::{plugin::{Builder, TauriPlugin}, Runtime};
+  ///
+  /// fn init<R: Runtime>() -> TauriPlugin<R> {
+  ///   Builder::new("example")
+  ///     .on_webview_builder(|builder| {
+  ///       if builder.label() != "untrusted" {
+  ///         return;
+  ///       }
+  ///       builder
+  ///         .initialization_scripts()
+  ///         .push("window.__UNTRUSTED__ = true".into());
+  ///       builder.on_navigation(|url| url.scheme() == "https");
+  ///       builder.append_user_agent("ExamplePlugin/1.0");
+  ///     })
+  ///     .build()
+  /// }
+  /// ```
+  #[must_use]
+  pub fn on_webview_builder<F>(mut self, on_webview_builder: F) -> Self
+  where
+    F: FnMut(&mut WebviewBuilderParts<'_>) + Send + 'static,
+  {
+    self.on_webview_builder = Box::new(on_webview_builder);
+    self
+  }
+
   /// Callback invoked when the webview is created.
   ///
   /// # Examples
@@ -549,7 +625,7 @@ impl<R: Runtime, C: DeserializeOwned> Builder<R, C> {
     self.uri_scheme_protocols.insert(
       uri_scheme.into(),
       Arc::new(UriSchemeProtocol {
-        protocol: Box::new(move |app, request, responder| {
+        protocol: Box::new(move |app, _context, request, responder| {
           responder.respond(protocol(app, request))
         }),
       }),
@@ -557,6 +633,48 @@ impl<R: Runtime, C: DeserializeOwned> Builder<R, C> {
     self
   }
 
+  /// Similar to [`Self::register_uri_scheme_protocol`] but the handler also receives
+  /// the [`ProtocolRequestContext`] of the webview that made the request.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// use tauri::{plugin::{Builder, TauriPlugin}, Runtime};
+  ///
+  /// fn init<R: Runtime>() -> TauriPlugin<R> {
+  ///   Builder::new("myplugin")
+  ///     .register_uri_scheme_protocol_with_context("myscheme", |app, context, req| {
+  ///       http::Response::builder()
+  ///         .body(context.window_label.clone().into_bytes())
+  ///         .unwrap()
+  ///     })
+  ///     .build()
+  /// }
+  /// ```
+  #[must_use]
+  pub fn register_uri_scheme_protocol_with_context<
+    N: Into<String>,
+    T: Into<Cow<'static, [u8]>>,
+    H: Fn(&AppHandle<R>, &ProtocolRequestContext, http::Request<Vec<u8>>) -> http::Response<T>
+      + Send
+      + Sync
+      + 'static,
+  >(
+    mut self,
+    uri_scheme: N,
+    protocol: H,
+  ) -> Self {
+    self.uri_scheme_protocols.insert(
+      uri_scheme.into(),
+      Arc::new(UriSchemeProtocol {
+        protocol: Box::new(move |app, context, request, responder| {
+          responder.respond(protocol(app, context, request))
+        }),
+      }),
+    );
+    self
+  }
+
   /// Similar to [`Self::register_uri_scheme_protocol`] but with an asynchronous responder that allows you
   /// to process the request in a separate thread and respond asynchronously.
   ///
@@ -604,6 +722,31 @@ impl<R: Runtime, C: DeserializeOwned> Builder<R, C> {
     mut self,
     uri_scheme: N,
     protocol: H,
+  ) -> Self {
+    self.uri_scheme_protocols.insert(
+      uri_scheme.into(),
+      Arc::new(UriSchemeProtocol {
+        protocol: Box::new(move |app, _context, request, responder| {
+          protocol(app, request, responder)
+        }),
+      }),
+    );
+    self
+  }
+
+  /// Similar to [`Self::register_asynchronous_uri_scheme_protocol`] but the handler also receives
+  /// the [`ProtocolRequestContext`] of the webview that made the request.
+  #[must_use]
+  pub fn register_asynchronous_uri_scheme_protocol_with_context<
+    N: Into<String>,
+    H: Fn(&AppHandle<R>, &ProtocolRequestContext, http::Request<Vec<u8>>, UriSchemeResponder)
+      + Send
+      + Sync
+      + 'static,
+  >(
+    mut self,
+    uri_scheme: N,
+    protocol: H,
   ) -> Self {
     self.uri_scheme_protocols.insert(
       uri_scheme.into(),
@@ -618,6 +761,7 @@ impl<R: Runtime, C: DeserializeOwned> Builder<R, C> {
   pub fn build(self) -> TauriPlugin<R, C> {
     TauriPlugin {
       name: self.name,
+      dependencies: self.dependencies,
       app: None,
       invoke_handler: self.invoke_handler,
       setup: self.setup,
@@ -625,6 +769,7 @@ impl<R: Runtime, C: DeserializeOwned> Builder<R, C> {
       on_navigation: self.on_navigation,
       on_page_load: self.on_page_load,
       on_window_ready: self.on_window_ready,
+      on_webview_builder: self.on_webview_builder,
       on_webview_ready: self.on_webview_ready,
       on_event: self.on_event,
       on_drop: self.on_drop,
@@ -636,6 +781,7 @@ impl<R: Runtime, C: DeserializeOwned> Builder<R, C> {
 /// Plugin struct that is returned by the [`Builder`]. Should only be constructed through the builder.
 pub struct TauriPlugin<R: Runtime, C: DeserializeOwned = ()> {
   name: &'static str,
+  dependencies: Vec<&'static str>,
   app: Option<AppHandle<R>>,
   invoke_handler: Box<InvokeHandler<R>>,
   setup: Option<Box<SetupHook<R, C>>>,
@@ -643,6 +789,7 @@ pub struct TauriPlugin<R: Runtime, C: DeserializeOwned = ()> {
   on_navigation: Box<OnNavigation<R>>,
   on_page_load: Box<OnPageLoad<R>>,
   on_window_ready: Box<OnWindowReady<R>>,
+  on_webview_builder: Box<OnWebviewBuilder>,
   on_webview_ready: Box<OnWebviewReady<R>>,
   on_event: Box<OnEvent<R>>,
   on_drop: Option<Box<OnDrop<R>>>,
@@ -662,6 +809,10 @@ impl<R: Runtime, C: DeserializeOwned> Plugin<R> for TauriPlugin<R, C> {
     self.name
   }
 
+  fn dependencies(&self) -> &[&'static str] {
+    &self.dependencies
+  }
+
   fn initialize(
     &mut self,
     app: &AppHandle<R>,
@@ -697,6 +848,10 @@ impl<R: Runtime, C: DeserializeOwned> Plugin<R> for TauriPlugin<R, C> {
     (self.on_window_ready)(window)
   }
 
+  fn webview_builder(&mut self, builder: &mut WebviewBuilderParts<'_>) {
+    (self.on_webview_builder)(builder)
+  }
+
   fn webview_created(&mut self, webview: Webview<R>) {
     (self.on_webview_ready)(webview)
   }
@@ -758,22 +913,50 @@ impl<R: Runtime> PluginStore<R> {
     len != self.store.len()
   }
 
-  /// Initializes the given plugin.
+  /// The names of the registered plugins, in registration order.
+  pub(crate) fn names(&self) -> Vec<&'static str> {
+    self.store.iter().map(|plugin| plugin.name()).collect()
+  }
+
+  /// Initializes the given plugin, which requires its dependencies to be initialized already.
   pub(crate) fn initialize(
     &self,
     plugin: &mut Box<dyn Plugin<R>>,
     app: &AppHandle<R>,
     config: &PluginConfig,
   ) -> crate::Result<()> {
+    if let Some(dependency) = plugin
+      .dependencies()
+      .iter()
+      .find(|dependency| !app.plugin_initialized(dependency))
+    {
+      return Err(Error::PluginDependencyMissing(vec![
+        plugin.name().to_string(),
+        dependency.to_string(),
+      ]));
+    }
     initialize(plugin, app, config)
   }
 
-  /// Initializes all plugins in the store.
+  /// Initializes all plugins in the store, sorting them so each plugin is initialized after its dependencies.
   pub(crate) fn initialize_all(
     &mut self,
     app: &AppHandle<R>,
     config: &PluginConfig,
   ) -> crate::Result<()> {
+    let order = initialization_order(
+      &self
+        .store
+        .iter()
+        .map(|plugin| (plugin.name(), plugin.dependencies()))
+        .collect::<Vec<_>>(),
+    )?;
+    let mut plugins = self.store.drain(..).map(Some).collect::<Vec<_>>();
+    self.store = order
+      .into_iter()
+      .filter_map(|index| plugins[index].take())
+      .collect();
+
     self
       .store
       .iter_mut()
@@ -800,6 +983,16 @@ impl<R: Runtime> PluginStore<R> {
     })
   }
 
+  /// Runs the webview builder hook for all plugins in the store.
+  pub(crate) fn webview_builder(&mut self, builder: &mut WebviewBuilderParts<'_>) {
+    self.store.iter_mut().for_each(|plugin| {
+      #[cfg(feature = "tracing")]
+      let _span =
+        tracing::trace_span!("plugin::hooks::webview_builder", name = plugin.name()).entered();
+      plugin.webview_builder(builder)
+    })
+  }
+
   /// Runs the webview created hook for all plugins in the store.
   pub(crate) fn webview_created(&mut self, webview: Webview<R>) {
     self
@@ -865,5 +1058,169 @@ fn initialize<R: Runtime>(
       app,
       config.0.get(plugin.name()).cloned().unwrap_or_default(),
     )
-    .map_err(|e| Error::PluginInitialization(plugin.name().to_string(), e.to_string()))
+    .map_err(|e| Error::PluginInitialization(plugin.name().to_string(), e.to_string()))?;
+  app
+    .manager
+    .initialized_plugins
+    .lock()
+    .unwrap()
+    .insert(plugin.name());
+  app.emit(PLUGIN_INITIALIZED_EVENT, plugin.name())
+}
+
+/// The order in which the given plugins and their dependencies must be initialized,
+/// as indexes in the slice, keeping the registration order of the plugins that do not depend on each other.
+fn initialization_order(plugins: &[(&'static str, &[&'static str])]) -> crate::Result<Vec<usize>> {
+  fn visit(
+    index: usize,
+    plugins: &[(&'static str, &[&'static str])],
+    path: &mut Vec<&'static str>,
+    visited: &mut [bool],
+    order: &mut Vec<usize>,
+  ) -> crate::Result<()> {
+    if visited[index] {
+      return Ok(());
+    }
+    let (name, dependencies) = plugins[index];
+    let chain = |path: &[&str], last: &str| {
+      path
+        .iter()
+        .chain(std::iter::once(&last))
+        .map(|plugin| plugin.to_string())
+        .collect::<Vec<_>>()
+    };
+    if let Some(start) = path.iter().position(|plugin| *plugin == name) {
+      return Err(Error::PluginDependencyCycle(chain(&path[start..], name)));
+    }
+
+    path.push(name);
+    for dependency in dependencies {
+      match plugins.iter().position(|(plugin, _)| plugin == dependency) {
+        Some(dependency) => visit(dependency, plugins, path, visited, order)?,
+        None => return Err(Error::PluginDependencyMissing(chain(path, dependency))),
+      }
+    }
+    path.pop();
+
+    visited[index] = true;
+    order.push(index);
+    Ok(())
+  }
+
+  let mut visited = vec![false; plugins.len()];
+  let mut order = Vec::with_capacity(plugins.len());
+  for index in 0..plugins.len() {
+    visit(index, plugins, &mut Vec::new(), &mut visited, &mut order)?;
+  }
+  Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::initialization_order;
+  use crate::Error;
+
+  #[test]
+  fn initialization_order_follows_dependencies() {
+    let order = initialization_order(&[
+      ("window-state", &["store"]),
+      ("log", &[]),
+      ("store", &["fs"]),
+      ("fs", &[]),
+    ])
+    .unwrap();
+    assert_eq!(order, vec![3, 2, 0, 1]);
+
+    // independent plugins keep the registration order
+    assert_eq!(
+      initialization_order(&[("b", &[]), ("a", &[])]).unwrap(),
+      vec![0, 1]
+    );
+  }
+
+  #[test]
+  fn initialization_order_missing_dependency() {
+    let error =
+      initialization_order(&[("window-state", &["store"]), ("store", &["fs"])]).unwrap_err();
+    assert!(matches!(
+      &error,
+      Error::PluginDependencyMissing(chain) if chain == &["window-state", "store", "fs"]
+    ));
+    assert_eq!(
+      error.to_string(),
+      "plugin `fs` is not registered: window-state requires store requires fs"
+    );
+  }
+
+  #[test]
+  fn initialization_order_cycle() {
+    let error = initialization_order(&[("log", &[]), ("a", &["b"]), ("b", &["c"]), ("c", &["b"])])
+      .unwrap_err();
+    assert!(matches!(
+      &error,
+      Error::PluginDependencyCycle(chain) if chain == &["b", "c", "b"]
+    ));
+
+    assert!(matches!(
+      initialization_order(&[("a", &["a"])]),
+      Err(Error::PluginDependencyCycle(chain)) if chain == ["a", "a"]
+    ));
+  }
+
+  #[test]
+  fn webview_builder_hooks_compose() {
+    use crate::{
+      test::{mock_builder, mock_context, noop_assets, MockRuntime},
+      webview::WebviewBuilder,
+    };
+    use std::sync::{
+      atomic::{AtomicUsize, Ordering},
+      Arc,
+    };
+
+    let plugin = |name: &'static str, script: &'static str, denied_host: &'static str| {
+      super::Builder::<MockRuntime>::new(name)
+        .on_webview_builder(move |builder| {
+          builder.initialization_scripts().push(script.into());
+          builder.on_navigation(move |url| url.host_str() != Some(denied_host));
+          builder.append_user_agent(&format!("{name}/1.0"));
+        })
+        .build()
+    };
+    let app = mock_builder()
+      .plugin(plugin("first", "first()", "first.example"))
+      .plugin(plugin("second", "second()", "second.example"))
+      .build(mock_context(noop_assets()))
+      .unwrap();
+
+    let app_checks = Arc::new(AtomicUsize::new(0));
+    let app_checks_ = app_checks.clone();
+    let pending = WebviewBuilder::new("main", Default::default())
+      .initialization_script("app()")
+      .user_agent("App")
+      .on_navigation(move |url| {
+        app_checks_.fetch_add(1, Ordering::SeqCst);
+        url.scheme() == "https"
+      })
+      .into_pending_webview(&app, "main", &[], &[])
+      .unwrap();
+
+    assert_eq!(
+      pending.webview_attributes.initialization_scripts,
+      ["app()", "first()", "second()"]
+    );
+    assert_eq!(
+      pending.webview_attributes.user_agent_suffix.as_deref(),
+      Some("first/1.0 second/1.0")
+    );
+
+    let on_navigation = pending.navigation_handler.unwrap();
+    let allowed = |url: &str| on_navigation(&url.parse().unwrap());
+    assert!(allowed("https://tauri.app"));
+    assert!(!allowed("https://first.example"));
+    assert!(!allowed("https://second.example"));
+    // the app denies it before the plugins are called
+    assert!(!allowed("http://tauri.app"));
+    assert_eq!(app_checks.load(Ordering::SeqCst), 4);
+  }
 }
@@ -31,6 +31,28 @@ static PENDING_PLUGIN_CALLS_ID: AtomicI32 = AtomicI32::new(0);
 static PENDING_PLUGIN_CALLS: OnceLock<Mutex<HashMap<i32, PendingPluginCallHandler>>> =
   OnceLock::new();
 static CHANNELS: OnceLock<Mutex<HashMap<u32, Channel>>> = OnceLock::new();
+#[cfg(mobile)]
+static REGISTERED_PLUGINS: OnceLock<Mutex<std::collections::HashSet<&'static str>>> =
+  OnceLock::new();
+
+/// Whether a mobile plugin was registered with the given name.
+#[cfg(mobile)]
+pub(crate) fn is_registered(name: &str) -> bool {
+  REGISTERED_PLUGINS
+    .get_or_init(Default::default)
+    .lock()
+    .unwrap()
+    .contains(name)
+}
+
+#[cfg(mobile)]
+fn register(name: &'static str) {
+  REGISTERED_PLUGINS
+    .get_or_init(Default::default)
+    .lock()
+    .unwrap()
+    .insert(name);
+}
 
 /// Possible errors when invoking a plugin.
 #[derive(Debug, thiserror::Error)]
@@ -188,6 +210,8 @@ impl<R: Runtime, C: DeserializeOwned> PluginApi<R, C> {
         )
       };
     }
+    register(self.name);
+
     Ok(PluginHandle {
       name: self.name,
       handle: self.handle.clone(),
@@ -271,6 +295,8 @@ impl<R: Runtime, C: DeserializeOwned> PluginApi<R, C> {
 
     rx.recv().unwrap()?;
 
+    register(self.name);
+
     Ok(PluginHandle {
       name: self.name,
       handle: self.handle.clone(),
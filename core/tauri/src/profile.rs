@@ -0,0 +1,284 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Profiles running the same app with separate data, see [`AppHandle::create_profile`].
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::{
+  event::EmitArgs, sealed::ManagerBase, webview::SessionId, AppHandle, Manager, Runtime,
+};
+
+/// The directory of the profiles in the app directories.
+const PROFILES_DIR: &str = "profiles";
+
+/// A profile of the app, with its own directories, webview data and browsing session.
+///
+/// Bind a webview to a profile with [`WebviewBuilder::profile`](crate::webview::WebviewBuilder::profile).
+/// The default behavior of the app is unchanged for the webviews that are not bound to a profile.
+pub struct Profile<R: Runtime> {
+  app: AppHandle<R>,
+  name: String,
+  webview_data_directory: PathBuf,
+}
+
+impl<R: Runtime> Clone for Profile<R> {
+  fn clone(&self) -> Self {
+    Self {
+      app: self.app.clone(),
+      name: self.name.clone(),
+      webview_data_directory: self.webview_data_directory.clone(),
+    }
+  }
+}
+
+impl<R: Runtime> std::fmt::Debug for Profile<R> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("Profile").field("name", &self.name).finish()
+  }
+}
+
+impl<R: Runtime> AppHandle<R> {
+  /// Gets the profile with the given name, its directories are created on first use.
+  ///
+  /// The name must only include alphanumeric characters, `-` and `_`.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// tauri::Builder::default()
+  ///   .setup(|app| {
+  ///     let work = app.handle().create_profile("work")?;
+  ///     tauri::WebviewWindowBuilder::new(app, "work-main", Default::default())
+  ///       .profile(&work)
+  ///       .build()?;
+  ///     Ok(())
+  ///   });
+  /// ```
+  pub fn create_profile(&self, name: impl Into<String>) -> crate::Result<Profile<R>> {
+    let name = name.into();
+    if !is_valid_profile_name(&name) {
+      return Err(crate::Error::InvalidProfileName(name));
+    }
+    let webview_data_directory = self
+      .path()
+      .app_local_data_dir()?
+      .join(PROFILES_DIR)
+      .join(&name);
+    Ok(Profile {
+      app: self.clone(),
+      name,
+      webview_data_directory,
+    })
+  }
+}
+
+impl<R: Runtime> Profile<R> {
+  /// The name of the profile.
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  /// The [`PathResolver::app_config_dir`](crate::path::PathResolver::app_config_dir) of the profile,
+  /// `${app_config_dir}/profiles/${name}`.
+  pub fn app_config_dir(&self) -> crate::Result<PathBuf> {
+    self.namespace(self.app.path().app_config_dir())
+  }
+
+  /// The [`PathResolver::app_data_dir`](crate::path::PathResolver::app_data_dir) of the profile,
+  /// `${app_data_dir}/profiles/${name}`.
+  pub fn app_data_dir(&self) -> crate::Result<PathBuf> {
+    self.namespace(self.app.path().app_data_dir())
+  }
+
+  /// The [`PathResolver::app_local_data_dir`](crate::path::PathResolver::app_local_data_dir) of the profile,
+  /// `${app_local_data_dir}/profiles/${name}`. The webviews bound to the profile store their data in it.
+  pub fn app_local_data_dir(&self) -> crate::Result<PathBuf> {
+    self.namespace(self.app.path().app_local_data_dir())
+  }
+
+  /// The [`PathResolver::app_cache_dir`](crate::path::PathResolver::app_cache_dir) of the profile,
+  /// `${app_cache_dir}/profiles/${name}`.
+  pub fn app_cache_dir(&self) -> crate::Result<PathBuf> {
+    self.namespace(self.app.path().app_cache_dir())
+  }
+
+  /// The [`PathResolver::app_log_dir`](crate::path::PathResolver::app_log_dir) of the profile,
+  /// `${app_log_dir}/profiles/${name}`.
+  pub fn app_log_dir(&self) -> crate::Result<PathBuf> {
+    self.namespace(self.app.path().app_log_dir())
+  }
+
+  fn namespace(&self, dir: crate::Result<PathBuf>) -> crate::Result<PathBuf> {
+    dir.map(|dir| dir.join(PROFILES_DIR).join(&self.name))
+  }
+
+  /// The browsing session shared by the webviews bound to the profile.
+  pub fn session(&self) -> SessionId {
+    SessionId::new(format!("profile:{}", self.name))
+  }
+
+  pub(crate) fn webview_data_directory(&self) -> PathBuf {
+    self.webview_data_directory.clone()
+  }
+
+  /// The labels of the live webviews bound to the profile.
+  pub fn webviews(&self) -> Vec<String> {
+    let manager = self.app.manager();
+    let webviews = manager.webview.webviews_lock();
+    let mut labels: Vec<String> = manager
+      .webview
+      .profiles
+      .lock()
+      .unwrap()
+      .iter()
+      .filter(|(label, profile)| **profile == self.name && webviews.contains_key(*label))
+      .map(|(label, _)| label.clone())
+      .collect();
+    labels.sort();
+    labels
+  }
+
+  /// Emits an event to all [targets](crate::EventTarget), carrying the name of the profile
+  /// so the listeners can filter the events of their profile.
+  ///
+  /// The name is available with [`crate::Event::profile`] and the `profile` field of the JavaScript event,
+  /// the profile of a webview is exposed as `window.__TAURI_INTERNALS__.metadata.currentWebview.profile`.
+  pub fn emit<S: Serialize + Clone>(&self, event: &str, payload: S) -> crate::Result<()> {
+    let mut emit_args = EmitArgs::new(event, payload)?;
+    emit_args.profile = Some(self.name.clone());
+    self.app.manager().emit_args_filter(emit_args, |_| true)
+  }
+
+  /// Removes the directories of the profile.
+  ///
+  /// Fails with [`crate::Error::ProfileInUse`] while a webview bound to the profile is alive.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS:** The webview data such as cookies is stored by the system and is not removed.
+  pub fn delete(self) -> crate::Result<()> {
+    if !self.webviews().is_empty() {
+      return Err(crate::Error::ProfileInUse(self.name));
+    }
+
+    let mut dirs = vec![self.webview_data_directory.clone()];
+    for dir in [
+      self.app_config_dir(),
+      self.app_data_dir(),
+      self.app_local_data_dir(),
+      self.app_cache_dir(),
+      self.app_log_dir(),
+    ]
+    .into_iter()
+    .flatten()
+    {
+      // the directories are the same on some platforms
+      if !dirs.contains(&dir) {
+        dirs.push(dir);
+      }
+    }
+
+    for dir in dirs {
+      match std::fs::remove_dir_all(&dir) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+        _ => (),
+      }
+    }
+    Ok(())
+  }
+}
+
+fn is_valid_profile_name(name: &str) -> bool {
+  !name.is_empty()
+    && name
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, Mutex};
+
+  use super::is_valid_profile_name;
+  use crate::{sealed::ManagerBase, test::mock_app, Manager, WebviewWindowBuilder};
+
+  #[test]
+  fn validates_names() {
+    assert!(is_valid_profile_name("work"));
+    assert!(is_valid_profile_name("personal-2_b"));
+    assert!(!is_valid_profile_name(""));
+    assert!(!is_valid_profile_name("../work"));
+    assert!(!is_valid_profile_name("a/b"));
+    assert!(!is_valid_profile_name("a b"));
+  }
+
+  #[test]
+  fn namespaces_directories() {
+    let app = mock_app();
+    let profile = app.handle().create_profile("work").unwrap();
+    assert_eq!(
+      profile.app_data_dir().unwrap(),
+      app
+        .path()
+        .app_data_dir()
+        .unwrap()
+        .join("profiles")
+        .join("work")
+    );
+    assert_eq!(
+      profile.webview_data_directory(),
+      profile.app_local_data_dir().unwrap()
+    );
+    assert_eq!(profile.session().id(), Some("profile:work"));
+    assert!(matches!(
+      app.handle().create_profile("a/b"),
+      Err(crate::Error::InvalidProfileName(_))
+    ));
+  }
+
+  #[test]
+  fn binds_webviews() {
+    let app = mock_app();
+    let work = app.handle().create_profile("work").unwrap();
+    let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .profile(&work)
+      .build()
+      .unwrap();
+    WebviewWindowBuilder::new(&app, "other", Default::default())
+      .build()
+      .unwrap();
+
+    assert_eq!(webview.profile().unwrap().name(), "work");
+    assert!(app
+      .manager()
+      .get_webview("other")
+      .unwrap()
+      .profile()
+      .is_none());
+    assert_eq!(work.webviews(), ["main"]);
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let events_ = events.clone();
+    app.listen_any("sync", move |event| {
+      events_
+        .lock()
+        .unwrap()
+        .push(event.profile().map(ToString::to_string));
+    });
+    work.emit("sync", ()).unwrap();
+    app.emit("sync", ()).unwrap();
+    assert_eq!(*events.lock().unwrap(), [Some("work".into()), None]);
+
+    // the profile cannot be deleted while the webview is alive
+    assert!(matches!(
+      work.clone().delete(),
+      Err(crate::Error::ProfileInUse(_))
+    ));
+    app.manager().on_webview_close("main");
+    assert!(work.webviews().is_empty());
+  }
+}
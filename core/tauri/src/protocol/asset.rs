@@ -11,20 +11,30 @@ use tauri_utils::mime_type::MimeType;
 use tokio::fs::File;
 use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 
-pub fn get(scope: scope::fs::Scope, window_origin: String) -> UriSchemeProtocolHandler {
-  Box::new(
-    move |request, responder| match get_response(request, &scope, &window_origin) {
-      Ok(response) => responder.respond(response),
-      Err(e) => responder.respond(
-        http::Response::builder()
-          .status(http::StatusCode::BAD_REQUEST)
-          .header(CONTENT_TYPE, mime::TEXT_PLAIN.essence_str())
-          .header("Access-Control-Allow-Origin", &window_origin)
-          .body(e.to_string().as_bytes().to_vec())
-          .unwrap(),
-      ),
-    },
-  )
+pub fn get(
+  scope: scope::fs::Scope,
+  window_origin: String,
+  cross_origin_isolation: bool,
+) -> UriSchemeProtocolHandler {
+  Box::new(move |request, responder| {
+    let mut response = match get_response(request, &scope, &window_origin) {
+      Ok(response) => response,
+      Err(e) => http::Response::builder()
+        .status(http::StatusCode::BAD_REQUEST)
+        .header(CONTENT_TYPE, mime::TEXT_PLAIN.essence_str())
+        .header("Access-Control-Allow-Origin", &window_origin)
+        .body(e.to_string().into_bytes().into())
+        .unwrap(),
+    };
+    // cross-origin isolated documents can only embed resources of other origins that opt in
+    if cross_origin_isolation {
+      response.headers_mut().insert(
+        "Cross-Origin-Resource-Policy",
+        HeaderValue::from_static("cross-origin"),
+      );
+    }
+    responder.respond(response)
+  })
 }
 
 fn get_response(
@@ -49,6 +59,8 @@ fn get_response(
     return resp.status(403).body(Vec::new().into()).map_err(Into::into);
   }
 
+  let limits = scope.limits(&path);
+
   let (mut file, len, mime_type, read_bytes) = crate::async_runtime::safe_block_on(async move {
     let mut file = File::open(&path).await?;
 
@@ -80,12 +92,28 @@ fn get_response(
 
   resp = resp.header(CONTENT_TYPE, &mime_type);
 
-  // handle 206 (partial range) http requests
-  let response = if let Some(range_header) = request
+  let range_header = request
     .headers()
     .get("range")
-    .and_then(|r| r.to_str().map(|r| r.to_string()).ok())
-  {
+    .and_then(|r| r.to_str().map(|r| r.to_string()).ok());
+  // the whole file is served when range requests are not allowed
+  let range_header = if limits.allow_ranges {
+    range_header
+  } else {
+    resp = resp.header(ACCEPT_RANGES, "none");
+    None
+  };
+
+  if range_header.is_none() && limits.max_size.is_some_and(|max_size| len > max_size) {
+    debug_eprintln!("asset protocol file is larger than its size limit: {}", len);
+    return resp
+      .status(StatusCode::PAYLOAD_TOO_LARGE)
+      .body(Vec::new().into())
+      .map_err(Into::into);
+  }
+
+  // handle 206 (partial range) http requests
+  let response = if let Some(range_header) = range_header {
     resp = resp.header(ACCEPT_RANGES, "bytes");
 
     let not_satisfiable = || {
@@ -128,6 +156,17 @@ fn get_response(
       // calculate number of bytes needed to be read
       let nbytes = end + 1 - start;
 
+      if limits.max_size.is_some_and(|max_size| nbytes > max_size) {
+        debug_eprintln!(
+          "asset protocol range is larger than its size limit: {}",
+          nbytes
+        );
+        return resp
+          .status(StatusCode::PAYLOAD_TOO_LARGE)
+          .body(Vec::new().into())
+          .map_err(Into::into);
+      }
+
       let buf = crate::async_runtime::safe_block_on(async move {
         let mut buf = Vec::with_capacity(nbytes as usize);
         file.seek(SeekFrom::Start(start)).await?;
@@ -157,20 +196,45 @@ fn get_response(
         })
         .collect::<Vec<_>>();
 
+      if ranges.is_empty() {
+        return not_satisfiable();
+      }
+
+      // the ranges can overlap, so their total is bounded by the file length and by the size limit
+      let nbytes = ranges.iter().fold(0u64, |total, (start, end)| {
+        total.saturating_add(end + 1 - start)
+      });
+      if nbytes > len {
+        return not_satisfiable();
+      }
+      if limits.max_size.is_some_and(|max_size| nbytes > max_size) {
+        debug_eprintln!(
+          "asset protocol ranges are larger than their size limit: {}",
+          nbytes
+        );
+        return resp
+          .status(StatusCode::PAYLOAD_TOO_LARGE)
+          .body(Vec::new().into())
+          .map_err(Into::into);
+      }
+
       let boundary = random_boundary();
       let boundary_sep = format!("\r\n--{boundary}\r\n");
       let boundary_closer = format!("\r\n--{boundary}\r\n");
 
-      resp = resp.header(
-        CONTENT_TYPE,
-        format!("multipart/byteranges; boundary={boundary}"),
-      );
+      // replaces the type of the file, which is sent in each part
+      if let Some(headers) = resp.headers_mut() {
+        headers.insert(
+          CONTENT_TYPE,
+          format!("multipart/byteranges; boundary={boundary}").parse()?,
+        );
+      }
 
       let buf = crate::async_runtime::safe_block_on(async move {
         // multi-part range header
         let mut buf = Vec::new();
 
-        for (end, start) in ranges {
+        for (start, end) in ranges {
           // a new range is being written, write the range boundary
           buf.write_all(boundary_sep.as_bytes()).await?;
 
@@ -198,6 +262,7 @@ fn get_response(
 
         Ok::<Vec<u8>, anyhow::Error>(buf)
       })?;
+      resp = resp.status(StatusCode::PARTIAL_CONTENT);
       resp.body(buf.into())
     }
   } else {
@@ -230,3 +295,129 @@ fn random_boundary() -> String {
       a
     })
 }
+
+#[cfg(test)]
+mod tests {
+  use super::get_response;
+  use crate::scope::fs::{Limits, Scope};
+  use http::{header::*, Request, StatusCode};
+
+  fn request(path: &std::path::Path, range: Option<&str>) -> Request<Vec<u8>> {
+    let mut request = Request::builder().uri(format!(
+      "asset://localhost/{}",
+      percent_encoding::utf8_percent_encode(
+        &path.to_string_lossy(),
+        percent_encoding::NON_ALPHANUMERIC
+      )
+    ));
+    if let Some(range) = range {
+      request = request.header(RANGE, range);
+    }
+    request.body(Vec::new()).unwrap()
+  }
+
+  #[test]
+  fn limits_are_enforced() {
+    let app = crate::test::mock_app();
+    let dir = std::env::temp_dir().join(format!("tauri-asset-limits-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let dir = dir.canonicalize().unwrap();
+    let large = dir.join("large.bin");
+    let small = dir.join("small.bin");
+    std::fs::write(&large, vec![0; 100]).unwrap();
+    std::fs::write(&small, vec![0; 10]).unwrap();
+
+    let scope = Scope::new(&app, &Default::default()).unwrap();
+    scope
+      .allow_file_with_limits(
+        &large,
+        Limits {
+          max_size: Some(50),
+          allow_ranges: true,
+        },
+      )
+      .unwrap();
+    scope
+      .allow_file_with_limits(
+        &small,
+        Limits {
+          max_size: Some(50),
+          allow_ranges: false,
+        },
+      )
+      .unwrap();
+
+    let get = |path, range| get_response(request(path, range), &scope, "origin").unwrap();
+
+    assert_eq!(get(&large, None).status(), StatusCode::PAYLOAD_TOO_LARGE);
+    let response = get(&large, Some("bytes=0-9"));
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(response.body().len(), 10);
+    assert_eq!(
+      get(&large, Some("bytes=200-300")).status(),
+      StatusCode::RANGE_NOT_SATISFIABLE
+    );
+
+    let response = get(&small, Some("bytes=0-4"));
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get(ACCEPT_RANGES).unwrap(), "none");
+    assert_eq!(response.body().len(), 10);
+
+    let _ = std::fs::remove_dir_all(dir);
+  }
+
+  #[test]
+  fn multiple_ranges() {
+    let app = crate::test::mock_app();
+    let dir = std::env::temp_dir().join(format!("tauri-asset-ranges-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let dir = dir.canonicalize().unwrap();
+    let file = dir.join("file.bin");
+    std::fs::write(&file, (0..100).collect::<Vec<u8>>()).unwrap();
+
+    let scope = Scope::new(&app, &Default::default()).unwrap();
+    scope
+      .allow_file_with_limits(
+        &file,
+        Limits {
+          max_size: Some(50),
+          allow_ranges: true,
+        },
+      )
+      .unwrap();
+
+    let get = |range| get_response(request(&file, Some(range)), &scope, "origin").unwrap();
+
+    let response = get("bytes=0-9,20-29");
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert!(response
+      .headers()
+      .get(CONTENT_TYPE)
+      .unwrap()
+      .to_str()
+      .unwrap()
+      .starts_with("multipart/byteranges; boundary="));
+    let body = response.body();
+    let contains = |needle: &[u8]| body.windows(needle.len()).any(|w| w == needle);
+    assert!(contains(b"content-range: bytes 0-9/100\r\n\r\n"));
+    assert!(contains(b"content-range: bytes 20-29/100\r\n\r\n"));
+    assert!(contains(&(0..10).collect::<Vec<u8>>()));
+    assert!(contains(&(20..30).collect::<Vec<u8>>()));
+
+    // the total of the ranges is limited, not each range
+    assert_eq!(get("bytes=0-9").status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(get("bytes=0-59").status(), StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(
+      get("bytes=0-29,40-69").status(),
+      StatusCode::PAYLOAD_TOO_LARGE
+    );
+    // overlapping ranges can't request more than the file
+    let overlapping = vec!["0-9"; 11].join(",");
+    assert_eq!(
+      get(&format!("bytes={overlapping}")).status(),
+      StatusCode::RANGE_NOT_SATISFIABLE
+    );
+
+    let _ = std::fs::remove_dir_all(dir);
+  }
+}
@@ -4,18 +4,21 @@
 
 use std::{borrow::Cow, sync::Arc};
 
-use http::{header::CONTENT_TYPE, Request, Response as HttpResponse, StatusCode};
+use http::{
+  header::{CACHE_CONTROL, CONTENT_TYPE, ETAG, IF_NONE_MATCH},
+  HeaderValue, Request, Response as HttpResponse, StatusCode,
+};
 
 use crate::{
-  manager::{webview::PROXY_DEV_SERVER, AppManager},
+  manager::AppManager,
   webview::{UriSchemeProtocolHandler, WebResourceRequestHandler},
   Runtime,
 };
 
-#[cfg(all(dev, mobile))]
+#[cfg(dev)]
 use std::{collections::HashMap, sync::Mutex};
 
-#[cfg(all(dev, mobile))]
+#[cfg(dev)]
 #[derive(Clone)]
 struct CachedResponse {
   status: http::StatusCode,
@@ -23,155 +26,642 @@ struct CachedResponse {
   body: bytes::Bytes,
 }
 
+/// Forwards the `tauri` protocol requests to the dev server, see the `devProxy` configuration value.
+#[cfg(dev)]
+struct DevServerProxy {
+  url: String,
+  client: reqwest::Client,
+  response_cache: Mutex<HashMap<String, CachedResponse>>,
+}
+
+#[cfg(dev)]
+impl DevServerProxy {
+  fn new(url: &str) -> Self {
+    let url = url.strip_suffix('/').unwrap_or(url).to_string();
+    #[allow(unused_mut)]
+    let mut client_builder = reqwest::ClientBuilder::new();
+    #[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+    {
+      client_builder = client_builder.danger_accept_invalid_certs(true);
+    }
+    Self {
+      url,
+      client: client_builder.build().unwrap(),
+      response_cache: Default::default(),
+    }
+  }
+
+  async fn get(
+    &self,
+    request: &Request<Vec<u8>>,
+    window_origin: &str,
+  ) -> Result<HttpResponse<Cow<'static, [u8]>>, Box<dyn std::error::Error + Send + Sync>> {
+    use http::header::{
+      ACCEPT_ENCODING, ACCESS_CONTROL_ALLOW_ORIGIN, CONTENT_LENGTH, HOST, TRANSFER_ENCODING,
+    };
+
+    // use the entire URI as we are going to proxy the request
+    let path = request_path(request, true);
+    let decoded_path = percent_encoding::percent_decode(path.as_bytes())
+      .decode_utf8_lossy()
+      .to_string();
+    let url = format!("{}{decoded_path}", self.url);
+
+    let mut proxy_request = self
+      .client
+      .request(request.method().clone(), &url)
+      .body(request.body().clone());
+    for (name, value) in request.headers() {
+      // the response body is forwarded as is so it must not be compressed,
+      // and the host must match the dev server
+      if name != ACCEPT_ENCODING && name != HOST {
+        proxy_request = proxy_request.header(name, value);
+      }
+    }
+
+    let response = proxy_request.send().await.map_err(|e| {
+      tauri_utils::debug_eprintln!("Failed to request {}: {}", url, e);
+      format!("Failed to reach the development server at {url}: {e}")
+    })?;
+
+    let cached = if response.status() == StatusCode::NOT_MODIFIED {
+      self.response_cache.lock().unwrap().get(&url).cloned()
+    } else {
+      None
+    };
+    let response = match cached {
+      Some(response) => response,
+      None => {
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.bytes().await?;
+        let response = CachedResponse {
+          status,
+          headers,
+          body,
+        };
+        self
+          .response_cache
+          .lock()
+          .unwrap()
+          .insert(url, response.clone());
+        response
+      }
+    };
+
+    let mut builder = HttpResponse::builder()
+      .status(response.status)
+      .header(ACCESS_CONTROL_ALLOW_ORIGIN, window_origin);
+    for (name, value) in &response.headers {
+      // the body is sent at once and may be changed before responding
+      if name != CONTENT_LENGTH && name != TRANSFER_ENCODING && name != ACCESS_CONTROL_ALLOW_ORIGIN
+      {
+        builder = builder.header(name, value);
+      }
+    }
+    Ok(builder.body(response.body.to_vec().into())?)
+  }
+}
+
 pub fn get<R: Runtime>(
-  #[allow(unused_variables)] manager: Arc<AppManager<R>>,
+  manager: Arc<AppManager<R>>,
   window_origin: &str,
   web_resource_request_handler: Option<Box<WebResourceRequestHandler>>,
 ) -> UriSchemeProtocolHandler {
-  #[cfg(all(dev, mobile))]
-  let url = {
-    let mut url = manager.get_url().as_str().to_string();
-    if url.ends_with('/') {
-      url.pop();
-    }
-    url
-  };
+  #[cfg(dev)]
+  let proxy = manager
+    .proxy_dev_server()
+    .then(|| Arc::new(DevServerProxy::new(manager.get_url().as_str())));
 
-  let manager = manager.clone();
   let window_origin = window_origin.to_string();
-
-  #[cfg(all(dev, mobile))]
-  let response_cache = Arc::new(Mutex::new(HashMap::new()));
+  let cross_origin_isolation = manager.config.app.security.cross_origin_isolation;
+  let web_resource_request_handler: Option<Arc<WebResourceRequestHandler>> =
+    web_resource_request_handler.map(Into::into);
 
   Box::new(move |request, responder| {
-    match get_response(
+    #[cfg(dev)]
+    if let Some(proxy) = proxy.clone() {
+      let window_origin = window_origin.clone();
+      let web_resource_request_handler = web_resource_request_handler.clone();
+      // the dev server is requested asynchronously so the event loop is not blocked
+      crate::async_runtime::spawn(async move {
+        let response = match proxy.get(&request, &window_origin).await {
+          Ok(response) => response,
+          Err(e) => error_response(StatusCode::BAD_GATEWAY, &window_origin, &*e),
+        };
+        responder.respond(finish_response(
+          request,
+          response,
+          cross_origin_isolation,
+          web_resource_request_handler.as_deref(),
+        ));
+      });
+      return;
+    }
+
+    let response = match get_response(&request, &manager, &window_origin) {
+      Ok(response) => response,
+      Err(e) => error_response(StatusCode::BAD_REQUEST, &window_origin, &*e),
+    };
+    responder.respond(finish_response(
       request,
-      &manager,
-      &window_origin,
+      response,
+      cross_origin_isolation,
       web_resource_request_handler.as_deref(),
-      #[cfg(all(dev, mobile))]
-      (&url, &response_cache),
-    ) {
-      Ok(response) => responder.respond(response),
-      Err(e) => responder.respond(
-        HttpResponse::builder()
-          .status(StatusCode::BAD_REQUEST)
-          .header(CONTENT_TYPE, mime::TEXT_PLAIN.essence_str())
-          .header("Access-Control-Allow-Origin", &window_origin)
-          .body(e.to_string().as_bytes().to_vec())
-          .unwrap(),
-      ),
-    }
+    ));
   })
 }
 
-fn get_response<R: Runtime>(
-  request: Request<Vec<u8>>,
-  #[allow(unused_variables)] manager: &AppManager<R>,
-  window_origin: &str,
-  web_resource_request_handler: Option<&WebResourceRequestHandler>,
-  #[cfg(all(dev, mobile))] (url, response_cache): (
-    &str,
-    &Arc<Mutex<HashMap<String, CachedResponse>>>,
-  ),
-) -> Result<HttpResponse<Cow<'static, [u8]>>, Box<dyn std::error::Error>> {
-  // use the entire URI as we are going to proxy the request
-  let path = if PROXY_DEV_SERVER {
-    request.uri().to_string()
+/// The path of the request URI, optionally keeping the query string and fragment.
+fn request_path(request: &Request<Vec<u8>>, keep_query: bool) -> String {
+  let uri = request.uri().to_string();
+  let path = if keep_query {
+    uri.as_str()
   } else {
     // ignore query string and fragment
-    request
-      .uri()
-      .to_string()
-      .split(&['?', '#'][..])
-      .next()
-      .unwrap()
-      .into()
+    uri.split(&['?', '#'][..]).next().unwrap()
   };
 
-  let path = path
+  path
     .strip_prefix("tauri://localhost")
     .map(|p| p.to_string())
     // the `strip_prefix` only returns None when a request is made to `https://tauri.$P` on Windows
     // where `$P` is not `localhost/*`
-    .unwrap_or_else(|| "".to_string());
+    .unwrap_or_default()
+}
+
+fn error_response(
+  status: StatusCode,
+  window_origin: &str,
+  error: &dyn std::error::Error,
+) -> HttpResponse<Cow<'static, [u8]>> {
+  HttpResponse::builder()
+    .status(status)
+    .header(CONTENT_TYPE, mime::TEXT_PLAIN.essence_str())
+    .header("Access-Control-Allow-Origin", window_origin)
+    .body(error.to_string().into_bytes().into())
+    .unwrap()
+}
+
+fn get_response<R: Runtime>(
+  request: &Request<Vec<u8>>,
+  manager: &AppManager<R>,
+  window_origin: &str,
+) -> Result<HttpResponse<Cow<'static, [u8]>>, Box<dyn std::error::Error>> {
+  let path = request_path(request, false);
 
   let mut builder = HttpResponse::builder().header("Access-Control-Allow-Origin", window_origin);
 
-  #[cfg(all(dev, mobile))]
-  let mut response = {
-    let decoded_path = percent_encoding::percent_decode(path.as_bytes())
-      .decode_utf8_lossy()
-      .to_string();
-    let url = format!("{url}{decoded_path}");
-    #[allow(unused_mut)]
-    let mut client_builder = reqwest::ClientBuilder::new();
-    #[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
-    {
-      client_builder = client_builder.danger_accept_invalid_certs(true);
-    }
-    let mut proxy_builder = client_builder
-      .build()
-      .unwrap()
-      .request(request.method().clone(), &url);
-    for (name, value) in request.headers() {
-      proxy_builder = proxy_builder.header(name, value);
-    }
-    match crate::async_runtime::block_on(proxy_builder.send()) {
-      Ok(r) => {
-        let mut response_cache_ = response_cache.lock().unwrap();
-        let mut response = None;
-        if r.status() == http::StatusCode::NOT_MODIFIED {
-          response = response_cache_.get(&url);
-        }
-        let response = if let Some(r) = response {
-          r
-        } else {
-          let status = r.status();
-          let headers = r.headers().clone();
-          let body = crate::async_runtime::block_on(r.bytes())?;
-          let response = CachedResponse {
-            status,
-            headers,
-            body,
-          };
-          response_cache_.insert(url.clone(), response);
-          response_cache_.get(&url).unwrap()
-        };
-        for (name, value) in &response.headers {
-          builder = builder.header(name, value);
-        }
+  let asset = manager.get_asset(path)?;
+  builder = builder.header(CONTENT_TYPE, &asset.mime_type);
+  if let Some(csp) = &asset.csp_header {
+    builder = builder.header("Content-Security-Policy", csp);
+  }
+  if let Some(etag) = &asset.etag {
+    builder = builder
+      .header(ETAG, etag)
+      .header(CACHE_CONTROL, &manager.config.app.asset_cache.cache_control);
+    if is_not_modified(request, etag) {
+      return Ok(
         builder
-          .status(response.status)
-          .body(response.body.to_vec().into())?
-      }
-      Err(e) => {
-        tauri_utils::debug_eprintln!("Failed to request {}: {}", url.as_str(), e);
-        return Err(Box::new(e));
-      }
+          .status(StatusCode::NOT_MODIFIED)
+          .body(Vec::new().into())?,
+      );
     }
-  };
+  }
+  Ok(builder.body(asset.bytes.into())?)
+}
 
-  #[cfg(not(all(dev, mobile)))]
-  let mut response = {
-    let asset = manager.get_asset(path)?;
-    builder = builder.header(CONTENT_TYPE, &asset.mime_type);
-    if let Some(csp) = &asset.csp_header {
-      builder = builder.header("Content-Security-Policy", csp);
-    }
-    builder.body(asset.bytes.into())?
-  };
-  if let Some(handler) = &web_resource_request_handler {
+/// Whether the `If-None-Match` header of the request matches the `ETag` of the asset.
+fn is_not_modified(request: &Request<Vec<u8>>, etag: &str) -> bool {
+  request
+    .headers()
+    .get_all(IF_NONE_MATCH)
+    .iter()
+    .filter_map(|value| value.to_str().ok())
+    .flat_map(|value| value.split(','))
+    .map(|tag| tag.trim())
+    .any(|tag| tag == "*" || tag.strip_prefix("W/").unwrap_or(tag) == etag)
+}
+
+/// Runs the web resource request handler on the response, which is then served exactly as the handler left it,
+/// including the error responses, apart from the platform adjustments below.
+///
+/// The cross-origin isolation headers are set before the handler runs so it can still override them.
+fn finish_response(
+  request: Request<Vec<u8>>,
+  mut response: HttpResponse<Cow<'static, [u8]>>,
+  cross_origin_isolation: bool,
+  web_resource_request_handler: Option<&WebResourceRequestHandler>,
+) -> HttpResponse<Cow<'static, [u8]>> {
+  #[cfg(any(target_os = "linux", target_os = "macos", target_os = "ios"))]
+  let is_document = request
+    .headers()
+    .get(http::header::ACCEPT)
+    .and_then(|accept| accept.to_str().ok())
+    .is_some_and(|accept| accept.contains("text/html"));
+
+  if cross_origin_isolation {
+    set_cross_origin_isolation(&mut response);
+  }
+
+  if let Some(handler) = web_resource_request_handler {
     handler(request, &mut response);
   }
+
   // if it's an HTML file, we need to set the CSP meta tag on Linux
   #[cfg(target_os = "linux")]
-  if let Some(response_csp) = response.headers().get("Content-Security-Policy") {
-    let response_csp = String::from_utf8_lossy(response_csp.as_bytes());
-    let html = String::from_utf8_lossy(response.body());
-    let body = html.replacen(tauri_utils::html::CSP_TOKEN, &response_csp, 1);
-    *response.body_mut() = body.as_bytes().to_vec().into();
+  set_csp_meta(&mut response);
+
+  #[cfg(any(target_os = "linux", target_os = "macos", target_os = "ios"))]
+  if is_document {
+    redirect_document(&mut response);
+  }
+
+  response
+}
+
+/// Sets `Cross-Origin-Opener-Policy: same-origin` and `Cross-Origin-Embedder-Policy: require-corp` on HTML responses,
+/// keeping the values of the asset if it already has them.
+fn set_cross_origin_isolation(response: &mut HttpResponse<Cow<'static, [u8]>>) {
+  let is_html = response
+    .headers()
+    .get(CONTENT_TYPE)
+    .and_then(|content_type| content_type.to_str().ok())
+    .is_some_and(|content_type| content_type.starts_with(mime::TEXT_HTML.essence_str()));
+  if !is_html {
+    return;
+  }
+  let headers = response.headers_mut();
+  headers
+    .entry("Cross-Origin-Opener-Policy")
+    .or_insert(HeaderValue::from_static("same-origin"));
+  headers
+    .entry("Cross-Origin-Embedder-Policy")
+    .or_insert(HeaderValue::from_static("require-corp"));
+}
+
+/// Replaces the CSP token of the HTML document with the response CSP header.
+///
+/// Bodies that are not UTF-8 or do not have the token, such as a body replaced by the web resource request handler, are not changed.
+#[cfg(any(test, target_os = "linux"))]
+fn set_csp_meta(response: &mut HttpResponse<Cow<'static, [u8]>>) {
+  let Some(csp) = response
+    .headers()
+    .get("Content-Security-Policy")
+    .and_then(|csp| csp.to_str().ok())
+  else {
+    return;
+  };
+  let body = match std::str::from_utf8(response.body()) {
+    Ok(html) if html.contains(tauri_utils::html::CSP_TOKEN) => {
+      html.replacen(tauri_utils::html::CSP_TOKEN, csp, 1)
+    }
+    _ => return,
+  };
+  *response.body_mut() = body.into_bytes().into();
+}
+
+/// WebKit does not follow the redirects of custom protocol responses,
+/// so a redirect without a body gets a document navigating to its `Location`.
+#[cfg(any(test, target_os = "linux", target_os = "macos", target_os = "ios"))]
+fn redirect_document(response: &mut HttpResponse<Cow<'static, [u8]>>) {
+  if !response.status().is_redirection() || !response.body().is_empty() {
+    return;
+  }
+  let Some(location) = response
+    .headers()
+    .get(http::header::LOCATION)
+    .and_then(|location| location.to_str().ok())
+  else {
+    return;
+  };
+  let location = location
+    .replace('&', "&amp;")
+    .replace('"', "&quot;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;");
+  let body = format!(
+    r#"<!DOCTYPE html><html><head><meta http-equiv="refresh" content="0;url={location}"></head></html>"#
+  );
+  response.headers_mut().insert(
+    CONTENT_TYPE,
+    http::HeaderValue::from_static("text/html; charset=utf-8"),
+  );
+  *response.body_mut() = body.into_bytes().into();
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    borrow::Cow,
+    sync::{
+      atomic::{AtomicUsize, Ordering},
+      Arc,
+    },
+  };
+  #[cfg(dev)]
+  use std::{
+    io::{Read, Write},
+    net::TcpListener,
+  };
+
+  use http::{header::LOCATION, Request, Response, StatusCode};
+
+  #[cfg(dev)]
+  use super::DevServerProxy;
+  use super::{finish_response, redirect_document, set_csp_meta};
+  use crate::{
+    app::UriSchemeResponder,
+    test::{mock_app, mock_builder, mock_context},
+    utils::assets::{AssetKey, Assets, CspHash},
+    webview::WebResourceRequestHandler,
+  };
+
+  fn asset_response() -> Response<Cow<'static, [u8]>> {
+    Response::builder()
+      .header("Content-Type", "text/html")
+      .header("Content-Security-Policy", "default-src 'self'")
+      .body(Cow::Borrowed(
+        &b"<meta http-equiv=\"Content-Security-Policy\" content=\"__TAURI_CSP__\">"[..],
+      ))
+      .unwrap()
+  }
+
+  fn finish(handler: &WebResourceRequestHandler) -> Response<Cow<'static, [u8]>> {
+    let request = Request::builder()
+      .uri("tauri://localhost/")
+      .header("Accept", "text/html")
+      .body(Vec::new())
+      .unwrap();
+    finish_response(request, asset_response(), false, Some(handler))
+  }
+
+  #[test]
+  fn status_override() {
+    let response = finish(&|_request, response| {
+      *response.status_mut() = StatusCode::NOT_FOUND;
+      *response.body_mut() = Cow::Borrowed(&[]);
+    });
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(response.body().is_empty());
+
+    let response = finish(&|_request, response| {
+      *response.status_mut() = StatusCode::PARTIAL_CONTENT;
+      response
+        .headers_mut()
+        .insert("Content-Range", "bytes 0-1/10".parse().unwrap());
+      *response.body_mut() = Cow::Borrowed(b"<m");
+    });
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(&**response.body(), b"<m");
+  }
+
+  #[test]
+  fn body_replacement() {
+    let response = finish(&|_request, response| {
+      *response.body_mut() = Cow::Owned(vec![0xff, 0xfe, 0x00]);
+    });
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(&**response.body(), &[0xff, 0xfe, 0x00]);
+
+    // the CSP token is still replaced when the handler keeps the document
+    let mut response = asset_response();
+    set_csp_meta(&mut response);
+    assert_eq!(
+      &**response.body(),
+      &b"<meta http-equiv=\"Content-Security-Policy\" content=\"default-src 'self'\">"[..]
+    );
   }
 
-  Ok(response)
+  #[test]
+  fn cross_origin_isolation() {
+    let request = || {
+      Request::builder()
+        .uri("tauri://localhost/")
+        .body(Vec::new())
+        .unwrap()
+    };
+    let handler: &WebResourceRequestHandler = &|_request, response| {
+      response
+        .headers_mut()
+        .insert("X-Custom", "value".parse().unwrap());
+    };
+
+    let response = finish_response(request(), asset_response(), true, Some(handler));
+    let headers = response.headers();
+    assert_eq!(
+      headers.get("Cross-Origin-Opener-Policy").unwrap(),
+      "same-origin"
+    );
+    assert_eq!(
+      headers.get("Cross-Origin-Embedder-Policy").unwrap(),
+      "require-corp"
+    );
+    assert_eq!(headers.get("X-Custom").unwrap(), "value");
+
+    // the headers are left to the handler when isolation is disabled
+    let response = finish_response(request(), asset_response(), false, Some(handler));
+    assert!(response
+      .headers()
+      .get("Cross-Origin-Opener-Policy")
+      .is_none());
+    assert_eq!(response.headers().get("X-Custom").unwrap(), "value");
+
+    // only HTML responses are changed, and existing values are kept
+    let script = Response::builder()
+      .header("Content-Type", "text/javascript")
+      .body(Cow::Borrowed(&b""[..]))
+      .unwrap();
+    let response = finish_response(request(), script, true, None);
+    assert!(response
+      .headers()
+      .get("Cross-Origin-Embedder-Policy")
+      .is_none());
+
+    let mut html = asset_response();
+    html.headers_mut().insert(
+      "Cross-Origin-Embedder-Policy",
+      "credentialless".parse().unwrap(),
+    );
+    let response = finish_response(request(), html, true, None);
+    assert_eq!(
+      response
+        .headers()
+        .get("Cross-Origin-Embedder-Policy")
+        .unwrap(),
+      "credentialless"
+    );
+  }
+
+  #[test]
+  fn redirect() {
+    let response = finish(&|_request, response| {
+      *response.status_mut() = StatusCode::MOVED_PERMANENTLY;
+      response
+        .headers_mut()
+        .insert(LOCATION, "tauri://localhost/new?a=1&b=2".parse().unwrap());
+      *response.body_mut() = Cow::Borrowed(&[]);
+    });
+    assert_eq!(response.status(), StatusCode::MOVED_PERMANENTLY);
+    assert_eq!(
+      response.headers().get(LOCATION).unwrap(),
+      "tauri://localhost/new?a=1&b=2"
+    );
+
+    let mut redirected = Response::builder()
+      .status(StatusCode::FOUND)
+      .header(LOCATION, "tauri://localhost/new?a=1&b=2")
+      .body(Cow::Borrowed(&[][..]))
+      .unwrap();
+    redirect_document(&mut redirected);
+    assert_eq!(redirected.status(), StatusCode::FOUND);
+    assert!(String::from_utf8_lossy(redirected.body())
+      .contains(r#"content="0;url=tauri://localhost/new?a=1&amp;b=2""#));
+
+    // a body set by the handler is kept
+    let mut redirected = Response::builder()
+      .status(StatusCode::FOUND)
+      .header(LOCATION, "tauri://localhost/new")
+      .body(Cow::Borrowed(&b"moved"[..]))
+      .unwrap();
+    redirect_document(&mut redirected);
+    assert_eq!(&**redirected.body(), b"moved");
+  }
+
+  #[test]
+  fn handles_missing_assets() {
+    let app = mock_app();
+    let protocol = super::get(
+      app.handle().manager.clone(),
+      "tauri://localhost",
+      Some(Box::new(|_request, response| {
+        if response.status() == StatusCode::BAD_REQUEST {
+          *response.status_mut() = StatusCode::NOT_FOUND;
+          *response.body_mut() = Cow::Borrowed(b"not found");
+        }
+      })),
+    );
+    let (tx, rx) = std::sync::mpsc::channel();
+    protocol(
+      Request::builder()
+        .uri("tauri://localhost/missing.js")
+        .body(Vec::new())
+        .unwrap(),
+      UriSchemeResponder(Box::new(move |response| tx.send(response).unwrap())),
+    );
+    let response = rx.recv().unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(&**response.body(), b"not found");
+  }
+
+  struct HashedAssets(Arc<AtomicUsize>);
+
+  impl Assets for HashedAssets {
+    fn get(&self, key: &AssetKey) -> Option<Cow<'_, [u8]>> {
+      self.0.fetch_add(1, Ordering::Relaxed);
+      (key.as_ref() == "/main.js").then_some(Cow::Borrowed(b"console.log('hello')"))
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = (&&str, &&[u8])> + '_> {
+      Box::new(std::iter::empty())
+    }
+
+    fn csp_hashes(&self, _html_path: &AssetKey) -> Box<dyn Iterator<Item = CspHash<'_>> + '_> {
+      Box::new(std::iter::empty())
+    }
+
+    fn content_hash(&self, key: &AssetKey) -> Option<&str> {
+      (key.as_ref() == "/main.js").then_some("af1349b9")
+    }
+  }
+
+  #[test]
+  fn etag() {
+    let reads = Arc::new(AtomicUsize::new(0));
+    let app = mock_builder()
+      .build(mock_context(HashedAssets(reads.clone())))
+      .unwrap();
+    let protocol = super::get(app.handle().manager.clone(), "tauri://localhost", None);
+    let request = |if_none_match: Option<&str>| {
+      let mut request = Request::builder().uri("tauri://localhost/main.js");
+      if let Some(etag) = if_none_match {
+        request = request.header("If-None-Match", etag);
+      }
+      let (tx, rx) = std::sync::mpsc::channel();
+      protocol(
+        request.body(Vec::new()).unwrap(),
+        UriSchemeResponder(Box::new(move |response| tx.send(response).unwrap())),
+      );
+      rx.recv().unwrap()
+    };
+
+    let response = request(None);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get("ETag").unwrap(), "\"af1349b9\"");
+    assert_eq!(response.headers().get("Cache-Control").unwrap(), "no-cache");
+    assert_eq!(&**response.body(), b"console.log('hello')");
+
+    let response = request(Some("\"0000\", W/\"af1349b9\""));
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(response.headers().get("ETag").unwrap(), "\"af1349b9\"");
+    assert!(response.body().is_empty());
+
+    let response = request(Some("\"0000\""));
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(&**response.body(), b"console.log('hello')");
+
+    // the asset is only read once, then served from the memory cache
+    assert_eq!(reads.load(Ordering::Relaxed), 1);
+  }
+
+  #[cfg(dev)]
+  #[test]
+  fn forwards_dev_server_errors() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    let server = std::thread::spawn(move || {
+      let (mut stream, _) = listener.accept().unwrap();
+      let mut request = [0; 4096];
+      let len = stream.read(&mut request).unwrap();
+      stream
+        .write_all(b"HTTP/1.1 500 Internal Server Error\r\nContent-Length: 13\r\n\r\nfailed to run")
+        .unwrap();
+      String::from_utf8_lossy(&request[..len]).to_lowercase()
+    });
+
+    let proxy = DevServerProxy::new(&url);
+    let request = Request::builder()
+      .uri("tauri://localhost/src/main.ts?t=1")
+      .header("Accept-Encoding", "gzip")
+      .body(Vec::new())
+      .unwrap();
+    let response =
+      crate::async_runtime::block_on(proxy.get(&request, "tauri://localhost")).unwrap();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(&**response.body(), b"failed to run");
+    assert!(!response.headers().contains_key("content-length"));
+
+    let request = server.join().unwrap();
+    assert!(request.starts_with("get /src/main.ts?t=1 "));
+    assert!(!request.contains("accept-encoding"));
+  }
+
+  #[cfg(dev)]
+  #[test]
+  fn unreachable_dev_server() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    drop(listener);
+
+    let proxy = DevServerProxy::new(&url);
+    let request = Request::builder()
+      .uri("tauri://localhost/")
+      .body(Vec::new())
+      .unwrap();
+    let error = crate::async_runtime::block_on(proxy.get(&request, "tauri://localhost"))
+      .unwrap_err()
+      .to_string();
+    assert!(error.starts_with(&format!("Failed to reach the development server at {url}/")));
+  }
 }
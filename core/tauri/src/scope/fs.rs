@@ -12,14 +12,16 @@ use std::{
   },
 };
 
-use tauri_utils::config::FsScope;
+use serde::{Deserialize, Serialize};
+use tauri_utils::config::{AssetScopeLimits, FsScope};
 
 use crate::ScopeEventId;
 
 pub use glob::Pattern;
 
 /// Scope change event.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", content = "path", rename_all = "camelCase")]
 pub enum Event {
   /// A path has been allowed.
   PathAllowed(PathBuf),
@@ -29,11 +31,80 @@ pub enum Event {
 
 type EventListener = Box<dyn Fn(&Event) + Send>;
 
+/// Constraints on the files served from an entry of the asset protocol scope,
+/// see [`Scope::allow_directory_with_limits`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Limits {
+  /// The maximum size of the files, in bytes.
+  ///
+  /// Larger files are rejected unless only ranges of the file totalling at most this size are requested.
+  pub max_size: Option<u64>,
+  /// Whether range requests are served.
+  pub allow_ranges: bool,
+}
+
+impl Default for Limits {
+  fn default() -> Self {
+    Self {
+      max_size: None,
+      allow_ranges: true,
+    }
+  }
+}
+
+impl From<&AssetScopeLimits> for Limits {
+  fn from(limits: &AssetScopeLimits) -> Self {
+    Self {
+      max_size: limits.max_size,
+      allow_ranges: limits.allow_ranges,
+    }
+  }
+}
+
+/// A change made to a scope after its creation, see [`Scope::entries`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum Entry {
+  /// See [`Scope::allow_directory`].
+  AllowDirectory {
+    /// The directory path.
+    path: PathBuf,
+    /// Whether the subdirectories are allowed.
+    recursive: bool,
+    /// See [`Scope::allow_directory_with_limits`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    limits: Option<Limits>,
+  },
+  /// See [`Scope::allow_file`].
+  AllowFile {
+    /// The file path.
+    path: PathBuf,
+    /// See [`Scope::allow_file_with_limits`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    limits: Option<Limits>,
+  },
+  /// See [`Scope::forbid_directory`].
+  ForbidDirectory {
+    /// The directory path.
+    path: PathBuf,
+    /// Whether the subdirectories are forbidden.
+    recursive: bool,
+  },
+  /// See [`Scope::forbid_file`].
+  ForbidFile {
+    /// The file path.
+    path: PathBuf,
+  },
+}
+
 /// Scope for filesystem access.
 #[derive(Clone)]
 pub struct Scope {
   allowed_patterns: Arc<Mutex<HashSet<Pattern>>>,
   forbidden_patterns: Arc<Mutex<HashSet<Pattern>>>,
+  limits: Arc<Mutex<Vec<(Pattern, Limits)>>>,
+  entries: Arc<Mutex<Vec<Entry>>>,
   event_listeners: Arc<Mutex<HashMap<ScopeEventId, EventListener>>>,
   match_options: glob::MatchOptions,
   next_event_id: Arc<AtomicU32>,
@@ -132,13 +203,17 @@ fn push_pattern<P: AsRef<Path>, F: Fn(&str) -> Result<Pattern, glob::PatternErro
 
 impl Scope {
   /// Creates a new scope from a [`FsScope`] configuration.
+  ///
+  /// The paths are parsed with [`crate::path::PathResolver::parse`].
+  /// Paths whose base directory is not available on the current platform are ignored,
+  /// and paths with an unknown variable or a `..` escaping their base directory are rejected.
   pub fn new<R: crate::Runtime, M: crate::Manager<R>>(
     manager: &M,
     scope: &FsScope,
   ) -> crate::Result<Self> {
     let mut allowed_patterns = HashSet::new();
     for path in scope.allowed_paths() {
-      if let Ok(path) = manager.path().parse(path) {
+      if let Some(path) = parse_scope_path(manager, path)? {
         push_pattern(&mut allowed_patterns, path, Pattern::new)?;
       }
     }
@@ -146,7 +221,7 @@ impl Scope {
     let mut forbidden_patterns = HashSet::new();
     if let Some(forbidden_paths) = scope.forbidden_paths() {
       for path in forbidden_paths {
-        if let Ok(path) = manager.path().parse(path) {
+        if let Some(path) = parse_scope_path(manager, path)? {
           push_pattern(&mut forbidden_patterns, path, Pattern::new)?;
         }
       }
@@ -167,6 +242,8 @@ impl Scope {
     Ok(Self {
       allowed_patterns: Arc::new(Mutex::new(allowed_patterns)),
       forbidden_patterns: Arc::new(Mutex::new(forbidden_patterns)),
+      limits: Default::default(),
+      entries: Default::default(),
       event_listeners: Default::default(),
       next_event_id: Default::default(),
       match_options: glob::MatchOptions {
@@ -189,6 +266,30 @@ impl Scope {
     self.forbidden_patterns.lock().unwrap().clone()
   }
 
+  /// The changes made to this scope after its creation, in order.
+  ///
+  /// Use [`Self::restore`] to apply them to another scope, for instance on the next app launch.
+  pub fn entries(&self) -> Vec<Entry> {
+    self.entries.lock().unwrap().clone()
+  }
+
+  /// Applies the given changes to this scope, see [`Self::entries`].
+  pub fn restore(&self, entries: &[Entry]) -> crate::Result<()> {
+    for entry in entries {
+      match entry {
+        Entry::AllowDirectory {
+          path,
+          recursive,
+          limits,
+        } => self.allow_directory_inner(path, *recursive, *limits)?,
+        Entry::AllowFile { path, limits } => self.allow_file_inner(path, *limits)?,
+        Entry::ForbidDirectory { path, recursive } => self.forbid_directory(path, *recursive)?,
+        Entry::ForbidFile { path } => self.forbid_file(path)?,
+      }
+    }
+    Ok(())
+  }
+
   /// Listen to an event on this scope.
   pub fn listen<F: Fn(&Event) + Send + 'static>(&self, f: F) -> ScopeEventId {
     let id = self.next_event_id();
@@ -232,17 +333,42 @@ impl Scope {
   /// After this function has been called, the frontend will be able to use the Tauri API to read
   /// the directory and all of its files. If `recursive` is `true`, subdirectories will be accessible too.
   pub fn allow_directory<P: AsRef<Path>>(&self, path: P, recursive: bool) -> crate::Result<()> {
-    let path = path.as_ref();
-    {
-      let mut list = self.allowed_patterns.lock().unwrap();
+    self.allow_directory_inner(path.as_ref(), recursive, None)
+  }
 
-      // allow the directory to be read
-      push_pattern(&mut list, path, escaped_pattern)?;
-      // allow its files and subdirectories to be read
-      push_pattern(&mut list, path, |p| {
-        escaped_pattern_with(p, if recursive { "**" } else { "*" })
-      })?;
-    }
+  /// Extend the allowed patterns with the given directory,
+  /// constraining how the asset protocol serves its files.
+  ///
+  /// See [`Self::allow_directory`].
+  pub fn allow_directory_with_limits<P: AsRef<Path>>(
+    &self,
+    path: P,
+    recursive: bool,
+    limits: Limits,
+  ) -> crate::Result<()> {
+    self.allow_directory_inner(path.as_ref(), recursive, Some(limits))
+  }
+
+  fn allow_directory_inner(
+    &self,
+    path: &Path,
+    recursive: bool,
+    limits: Option<Limits>,
+  ) -> crate::Result<()> {
+    let mut patterns = HashSet::new();
+    // allow the directory to be read
+    push_pattern(&mut patterns, path, escaped_pattern)?;
+    // allow its files and subdirectories to be read
+    push_pattern(&mut patterns, path, |p| {
+      escaped_pattern_with(p, if recursive { "**" } else { "*" })
+    })?;
+    self.allow_patterns(patterns, limits);
+
+    self.entries.lock().unwrap().push(Entry::AllowDirectory {
+      path: path.to_path_buf(),
+      recursive,
+      limits,
+    });
     self.emit(Event::PathAllowed(path.to_path_buf()));
     Ok(())
   }
@@ -251,16 +377,78 @@ impl Scope {
   ///
   /// After this function has been called, the frontend will be able to use the Tauri API to read the contents of this file.
   pub fn allow_file<P: AsRef<Path>>(&self, path: P) -> crate::Result<()> {
-    let path = path.as_ref();
-    push_pattern(
-      &mut self.allowed_patterns.lock().unwrap(),
-      path,
-      escaped_pattern,
-    )?;
+    self.allow_file_inner(path.as_ref(), None)
+  }
+
+  /// Extend the allowed patterns with the given file path,
+  /// constraining how the asset protocol serves it.
+  ///
+  /// See [`Self::allow_file`].
+  pub fn allow_file_with_limits<P: AsRef<Path>>(
+    &self,
+    path: P,
+    limits: Limits,
+  ) -> crate::Result<()> {
+    self.allow_file_inner(path.as_ref(), Some(limits))
+  }
+
+  fn allow_file_inner(&self, path: &Path, limits: Option<Limits>) -> crate::Result<()> {
+    let mut patterns = HashSet::new();
+    push_pattern(&mut patterns, path, escaped_pattern)?;
+    self.allow_patterns(patterns, limits);
+
+    self.entries.lock().unwrap().push(Entry::AllowFile {
+      path: path.to_path_buf(),
+      limits,
+    });
     self.emit(Event::PathAllowed(path.to_path_buf()));
     Ok(())
   }
 
+  fn allow_patterns(&self, patterns: HashSet<Pattern>, limits: Option<Limits>) {
+    if let Some(limits) = limits {
+      self
+        .limits
+        .lock()
+        .unwrap()
+        .extend(patterns.iter().map(|p| (p.clone(), limits)));
+    }
+    self.allowed_patterns.lock().unwrap().extend(patterns);
+  }
+
+  /// Adds the limits of the asset protocol configuration.
+  #[cfg(feature = "protocol-asset")]
+  pub(crate) fn add_config_limits<R: crate::Runtime, M: crate::Manager<R>>(
+    &self,
+    manager: &M,
+    config: &[AssetScopeLimits],
+  ) -> crate::Result<()> {
+    let mut list = self.limits.lock().unwrap();
+    for limits in config {
+      if let Some(path) = parse_scope_path(manager, &limits.path)? {
+        let mut patterns = HashSet::new();
+        push_pattern(&mut patterns, path, Pattern::new)?;
+        list.extend(patterns.into_iter().map(|p| (p, limits.into())));
+      }
+    }
+    Ok(())
+  }
+
+  /// The constraints on the given file, from the first matching entry with limits.
+  pub fn limits<P: AsRef<Path>>(&self, path: P) -> Limits {
+    resolve_path(path.as_ref())
+      .and_then(|path| {
+        self
+          .limits
+          .lock()
+          .unwrap()
+          .iter()
+          .find(|(p, _)| p.matches_path_with(&path, self.match_options))
+          .map(|(_, limits)| *limits)
+      })
+      .unwrap_or_default()
+  }
+
   /// Set the given directory path to be forbidden by this scope.
   ///
   /// **Note:** this takes precedence over allowed paths, so its access gets denied **always**.
@@ -276,6 +464,10 @@ impl Scope {
         escaped_pattern_with(p, if recursive { "**" } else { "*" })
       })?;
     }
+    self.entries.lock().unwrap().push(Entry::ForbidDirectory {
+      path: path.to_path_buf(),
+      recursive,
+    });
     self.emit(Event::PathForbidden(path.to_path_buf()));
     Ok(())
   }
@@ -290,21 +482,26 @@ impl Scope {
       path,
       escaped_pattern,
     )?;
+    self.entries.lock().unwrap().push(Entry::ForbidFile {
+      path: path.to_path_buf(),
+    });
     self.emit(Event::PathForbidden(path.to_path_buf()));
     Ok(())
   }
 
+  /// Set the given path to be forbidden by this scope,
+  /// along with all of its files and subdirectories if it is a directory.
+  ///
+  /// **Note:** this takes precedence over allowed paths, so its access gets denied **always**.
+  pub fn forbid<P: AsRef<Path>>(&self, path: P) -> crate::Result<()> {
+    self.forbid_directory(path, true)
+  }
+
   /// Determines if the given path is allowed on this scope.
+  ///
+  /// Symbolic links are resolved before matching, so a link is only allowed if its target is.
   pub fn is_allowed<P: AsRef<Path>>(&self, path: P) -> bool {
-    let path = path.as_ref();
-    let path = if !path.exists() {
-      crate::Result::Ok(path.to_path_buf())
-    } else {
-      std::fs::canonicalize(path).map_err(Into::into)
-    };
-
-    if let Ok(path) = path {
-      let path: PathBuf = path.components().collect();
+    if let Some(path) = resolve_path(path.as_ref()) {
       let forbidden = self
         .forbidden_patterns
         .lock()
@@ -329,6 +526,45 @@ impl Scope {
   }
 }
 
+/// Resolves the symbolic links of a path, or normalizes it if it does not exist.
+fn resolve_path(path: &Path) -> Option<PathBuf> {
+  let path = if !path.exists() {
+    crate::path::normalize_lexically(path)
+  } else {
+    std::fs::canonicalize(path).map_err(Into::into)
+  };
+  path.ok().map(|path| path.components().collect())
+}
+
+/// Reads the entries persisted with [`save_entries`], empty if the file does not exist.
+pub(crate) fn load_entries(path: &Path) -> crate::Result<Vec<Entry>> {
+  match std::fs::read(path) {
+    Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+    Err(e) => Err(e.into()),
+  }
+}
+
+/// Writes the entries of a scope to the given file.
+pub(crate) fn save_entries(path: &Path, entries: &[Entry]) -> crate::Result<()> {
+  if let Some(parent) = path.parent() {
+    std::fs::create_dir_all(parent)?;
+  }
+  std::fs::write(path, serde_json::to_vec(entries)?)?;
+  Ok(())
+}
+
+fn parse_scope_path<R: crate::Runtime, M: crate::Manager<R>>(
+  manager: &M,
+  path: &Path,
+) -> crate::Result<Option<PathBuf>> {
+  match manager.path().parse(path) {
+    Ok(path) => Ok(Some(path)),
+    Err(e @ (crate::Error::UnknownPathVariable(_) | crate::Error::PathEscapesBase(_))) => Err(e),
+    Err(_) => Ok(None),
+  }
+}
+
 fn escaped_pattern(p: &str) -> Result<Pattern, glob::PatternError> {
   Pattern::new(&glob::Pattern::escape(p))
 }
@@ -343,12 +579,14 @@ fn escaped_pattern_with(p: &str, append: &str) -> Result<Pattern, glob::PatternE
 
 #[cfg(test)]
 mod tests {
-  use super::Scope;
+  use super::{load_entries, save_entries, Entry, Scope};
 
   fn new_scope() -> Scope {
     Scope {
       allowed_patterns: Default::default(),
       forbidden_patterns: Default::default(),
+      limits: Default::default(),
+      entries: Default::default(),
       event_listeners: Default::default(),
       next_event_id: Default::default(),
       match_options: glob::MatchOptions {
@@ -461,4 +699,116 @@ mod tests {
       assert!(scope.is_allowed("C:\\home\\tauri\\anyfile"));
     }
   }
+
+  #[test]
+  fn parent_components_are_normalized() {
+    let scope = new_scope();
+    #[cfg(unix)]
+    {
+      scope.allow_directory("/home/tauri", true).unwrap();
+      assert!(scope.is_allowed("/home/tauri/missing/../file"));
+      assert!(!scope.is_allowed("/home/tauri/../secret"));
+      assert!(!scope.is_allowed("/home/tauri/missing/../../secret"));
+    }
+    #[cfg(windows)]
+    {
+      scope.allow_directory("C:\\home\\tauri", true).unwrap();
+      assert!(scope.is_allowed("C:/home/tauri/missing\\..\\file"));
+      assert!(scope.is_allowed("C:\\home/tauri/./file"));
+      assert!(!scope.is_allowed("C:\\home\\tauri\\..\\secret"));
+      assert!(!scope.is_allowed("C:/home/tauri/missing/../../secret"));
+    }
+  }
+
+  fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("tauri-fs-scope-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.canonicalize().unwrap()
+  }
+
+  #[test]
+  fn forbidden_takes_precedence() {
+    let dir = temp_dir("precedence");
+    let secret = dir.join("secret");
+    std::fs::create_dir_all(&secret).unwrap();
+
+    let scope = new_scope();
+    scope.forbid(&secret).unwrap();
+    scope.allow_directory(&dir, true).unwrap();
+    scope.allow_file(secret.join("key")).unwrap();
+
+    assert!(scope.is_allowed(dir.join("file")));
+    assert!(!scope.is_allowed(&secret));
+    assert!(!scope.is_allowed(secret.join("key")));
+    assert!(!scope.is_allowed(secret.join("inner/key")));
+
+    let _ = std::fs::remove_dir_all(dir);
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn symlinks_are_resolved() {
+    let dir = temp_dir("symlinks");
+    let allowed = dir.join("allowed");
+    let outside = dir.join("outside");
+    std::fs::create_dir_all(&allowed).unwrap();
+    std::fs::create_dir_all(&outside).unwrap();
+    std::fs::write(outside.join("file"), "").unwrap();
+    std::os::unix::fs::symlink(&outside, allowed.join("link")).unwrap();
+
+    let scope = new_scope();
+    scope.allow_directory(&allowed, true).unwrap();
+    assert!(!scope.is_allowed(allowed.join("link/file")));
+
+    scope.allow_directory(allowed.join("link"), true).unwrap();
+    assert!(scope.is_allowed(outside.join("file")));
+    scope.forbid(&outside).unwrap();
+    assert!(!scope.is_allowed(allowed.join("link/file")));
+
+    let _ = std::fs::remove_dir_all(dir);
+  }
+
+  #[test]
+  fn entries_are_persisted() {
+    let dir = temp_dir("persistence");
+    let path = dir.join("data/scope.json");
+    assert!(load_entries(&path).unwrap().is_empty());
+
+    let scope = new_scope();
+    scope.allow_directory(dir.join("project"), true).unwrap();
+    scope.allow_file(dir.join("notes.txt")).unwrap();
+    scope.forbid(dir.join("project/secret")).unwrap();
+    save_entries(&path, &scope.entries()).unwrap();
+
+    let entries = load_entries(&path).unwrap();
+    assert_eq!(entries, scope.entries());
+    assert_eq!(
+      entries[0],
+      Entry::AllowDirectory {
+        path: dir.join("project"),
+        recursive: true,
+        limits: None,
+      }
+    );
+
+    let restored = new_scope();
+    restored.restore(&entries).unwrap();
+    assert_eq!(restored.entries(), entries);
+    assert_eq!(restored.allowed_patterns(), scope.allowed_patterns());
+    assert_eq!(restored.forbidden_patterns(), scope.forbidden_patterns());
+    assert!(restored.is_allowed(dir.join("project/src/main.rs")));
+    assert!(restored.is_allowed(dir.join("notes.txt")));
+    assert!(!restored.is_allowed(dir.join("project/secret/key")));
+
+    let _ = std::fs::remove_dir_all(dir);
+  }
+
+  #[test]
+  fn events_are_serialized() {
+    assert_eq!(
+      serde_json::to_value(super::Event::PathAllowed("/home/tauri".into())).unwrap(),
+      serde_json::json!({ "kind": "pathAllowed", "path": "/home/tauri" })
+    );
+  }
 }
@@ -7,11 +7,22 @@ pub mod fs;
 
 use std::path::Path;
 
+use crate::{AppHandle, Manager, Runtime};
+
 /// Unique id of a scope event.
 pub type ScopeEventId = u32;
 
+/// The event emitted to all targets when the [`Manager::fs_scope`] changes,
+/// with a [`fs::Event`] payload.
+pub const FS_SCOPE_CHANGED_EVENT: &str = "tauri://fs-scope-changed";
+
+/// The file in the app data directory where the changes of the [`Manager::fs_scope`] are persisted,
+/// see [`crate::Builder::persist_fs_scope`].
+const PERSISTED_FS_SCOPE_FILE: &str = "scope.json";
+
 /// Managed state for all the core scopes in a tauri application.
 pub struct Scopes {
+  pub(crate) fs: fs::Scope,
   #[cfg(feature = "protocol-asset")]
   pub(crate) asset_protocol: fs::Scope,
 }
@@ -39,3 +50,39 @@ impl Scopes {
     Ok(())
   }
 }
+
+/// Creates the scope returned by [`Manager::fs_scope`],
+/// restoring the changes persisted by the previous launches when `persist` is set.
+pub(crate) fn app_fs_scope<R: Runtime>(
+  app: &AppHandle<R>,
+  persist: bool,
+) -> crate::Result<fs::Scope> {
+  let scope = fs::Scope::new(app, &Default::default())?;
+
+  if persist {
+    match app.path().app_data_dir() {
+      Ok(dir) => {
+        let path = dir.join(PERSISTED_FS_SCOPE_FILE);
+        if let Err(e) = fs::load_entries(&path).and_then(|entries| scope.restore(&entries)) {
+          tauri_utils::debug_eprintln!("failed to restore the persisted fs scope: {}", e);
+        }
+        let persisted = scope.clone();
+        scope.listen(move |_| {
+          if let Err(e) = fs::save_entries(&path, &persisted.entries()) {
+            tauri_utils::debug_eprintln!("failed to persist the fs scope: {}", e);
+          }
+        });
+      }
+      Err(e) => {
+        tauri_utils::debug_eprintln!("failed to resolve the fs scope persistence file: {}", e);
+      }
+    }
+  }
+
+  let app = app.clone();
+  scope.listen(move |event| {
+    let _ = app.emit(FS_SCOPE_CHANGED_EVENT, event);
+  });
+
+  Ok(scope)
+}
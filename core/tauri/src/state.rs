@@ -7,6 +7,7 @@ use crate::{
   Runtime,
 };
 use state::TypeMap;
+use std::sync::Mutex;
 
 /// A guard for a state value.
 ///
@@ -58,22 +59,41 @@ impl<'r, 'de: 'r, T: Send + Sync + 'static, R: Runtime> CommandArg<'de, R> for S
 
 /// The Tauri state manager.
 #[derive(Debug)]
-pub struct StateManager(pub(crate) TypeMap![Send + Sync]);
+pub struct StateManager {
+  map: TypeMap![Send + Sync],
+  type_names: Mutex<Vec<&'static str>>,
+}
 
 impl StateManager {
   pub(crate) fn new() -> Self {
-    Self(<TypeMap![Send + Sync]>::new())
+    Self {
+      map: <TypeMap![Send + Sync]>::new(),
+      type_names: Default::default(),
+    }
   }
 
   pub(crate) fn set<T: Send + Sync + 'static>(&self, state: T) -> bool {
-    self.0.set(state)
+    let set = self.map.set(state);
+    if set {
+      self
+        .type_names
+        .lock()
+        .unwrap()
+        .push(std::any::type_name::<T>());
+    }
+    set
+  }
+
+  /// The type names of the managed states, in the order they were set.
+  pub(crate) fn type_names(&self) -> Vec<&'static str> {
+    self.type_names.lock().unwrap().clone()
   }
 
   /// Gets the state associated with the specified type.
   pub fn get<T: Send + Sync + 'static>(&self) -> State<'_, T> {
     State(
       self
-        .0
+        .map
         .try_get()
         .expect("state: get() called before set() for given type"),
     )
@@ -81,6 +101,6 @@ impl StateManager {
 
   /// Gets the state associated with the specified type.
   pub fn try_get<T: Send + Sync + 'static>(&self) -> Option<State<'_, T>> {
-    self.0.try_get().map(State)
+    self.map.try_get().map(State)
   }
 }
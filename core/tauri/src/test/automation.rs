@@ -0,0 +1,79 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+use crate::automation::{
+  AutomationCommand, AutomationEndpoint, AutomationError, AutomationRequest, AutomationResponse,
+  CommandResponse,
+};
+
+/// A blocking client for the [`crate::automation`] server.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tauri::{
+///   automation::{AutomationCommand, AutomationEndpoint, CommandResponse},
+///   test::{mock_app, AutomationClient},
+///   Manager,
+/// };
+///
+/// let app = mock_app();
+/// let mut client = AutomationClient::connect(&app.state::<AutomationEndpoint>()).unwrap();
+/// let response = client.send(AutomationCommand::ListWebviews).unwrap();
+/// assert!(matches!(response, CommandResponse::ListWebviews { .. }));
+/// ```
+pub struct AutomationClient {
+  socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+  next_id: u64,
+}
+
+impl AutomationClient {
+  /// Connects to the automation server of an app.
+  pub fn connect(endpoint: &AutomationEndpoint) -> crate::Result<Self> {
+    let (socket, _) =
+      crate::async_runtime::block_on(tokio_tungstenite::connect_async(endpoint.url()))
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::ConnectionRefused, e))?;
+    Ok(Self { socket, next_id: 0 })
+  }
+
+  /// Sends a command and waits for its response.
+  ///
+  /// Connection errors are returned as [`AutomationError::Failed`].
+  pub fn send(&mut self, command: AutomationCommand) -> Result<CommandResponse, AutomationError> {
+    self.next_id += 1;
+    let id = self.next_id;
+    let request = serde_json::to_string(&AutomationRequest { id, command }).map_err(failed)?;
+
+    crate::async_runtime::block_on(async {
+      self
+        .socket
+        .send(Message::Text(request))
+        .await
+        .map_err(failed)?;
+      loop {
+        match self.socket.next().await {
+          Some(Ok(Message::Text(text))) => {
+            let response = serde_json::from_str::<AutomationResponse>(&text).map_err(failed)?;
+            if response.id == id {
+              return response.result.into();
+            }
+          }
+          Some(Ok(_)) => continue,
+          Some(Err(e)) => return Err(failed(e)),
+          None => return Err(failed("the connection was closed")),
+        }
+      }
+    })
+  }
+}
+
+fn failed(error: impl ToString) -> AutomationError {
+  AutomationError::Failed {
+    message: error.to_string(),
+  }
+}
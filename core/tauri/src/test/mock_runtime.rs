@@ -10,17 +10,20 @@ use tauri_runtime::{
   webview::{DetachedWebview, PendingWebview},
   window::{
     dpi::{PhysicalPosition, PhysicalSize, Position, Size},
-    CursorIcon, DetachedWindow, PendingWindow, RawWindow, WindowEvent, WindowId,
+    CursorIcon, DetachedWindow, PendingWindow, RawWindow, WebviewEvent, WindowEvent, WindowId,
   },
   window::{WindowBuilder, WindowBuilderBase},
-  DeviceEventFilter, Error, EventLoopProxy, ExitRequestedEventAction, Icon, Result, RunEvent,
-  Runtime, RuntimeHandle, RuntimeInitArgs, UserAttentionType, UserEvent, WebviewDispatch,
-  WindowDispatch, WindowEventId,
+  DeviceEventFilter, Error, EventLoopProxy, ExitRequestedEventAction, Icon, NativeViewId, Result,
+  RunEvent, Runtime, RuntimeHandle, RuntimeInitArgs, UserAttentionType, UserEvent, WebviewDispatch,
+  WebviewEventId, WindowDispatch, WindowEventId,
 };
 
 #[cfg(target_os = "macos")]
 use tauri_utils::TitleBarStyle;
-use tauri_utils::{config::WindowConfig, ProgressBarState, Theme};
+use tauri_utils::{
+  config::{BackgroundThrottlingPolicy, WindowConfig},
+  ProgressBarState, Theme,
+};
 use url::Url;
 
 #[cfg(windows)]
@@ -43,6 +46,7 @@ enum Message {
   Task(Box<dyn FnOnce() + Send>),
   CloseWindow(WindowId),
   DestroyWindow(WindowId),
+  RequestExit(i32),
 }
 
 struct Webview;
@@ -84,6 +88,7 @@ impl RuntimeContext {
         Message::CloseWindow(id) | Message::DestroyWindow(id) => {
           self.windows.borrow_mut().remove(&id);
         }
+        Message::RequestExit(_) => {}
       }
       Ok(())
     }
@@ -130,7 +135,7 @@ impl<T: UserEvent> RuntimeHandle<T> for MockRuntimeHandle {
   }
 
   fn request_exit(&self, code: i32) -> Result<()> {
-    unimplemented!()
+    self.context.send_message(Message::RequestExit(code))
   }
 
   /// Create a new webview window.
@@ -139,6 +144,10 @@ impl<T: UserEvent> RuntimeHandle<T> for MockRuntimeHandle {
     pending: PendingWindow<T, Self::Runtime>,
     _after_window_creation: Option<F>,
   ) -> Result<DetachedWindow<T, Self::Runtime>> {
+    if let Some(webview) = &pending.webview {
+      check_data_directory(webview)?;
+    }
+
     let id = self.context.next_window_id();
 
     let (webview_id, webviews) = if let Some(w) = &pending.webview {
@@ -155,15 +164,25 @@ impl<T: UserEvent> RuntimeHandle<T> for MockRuntimeHandle {
       },
     );
 
-    let webview = webview_id.map(|id| DetachedWebview {
-      label: pending.label.clone(),
-      dispatcher: MockWebviewDispatcher {
-        id,
-        context: self.context.clone(),
-        url: Arc::new(Mutex::new(pending.webview.unwrap().url)),
-        last_evaluated_script: Default::default(),
-      },
-    });
+    let webview = webview_id
+      .zip(pending.webview)
+      .map(|(id, webview)| DetachedWebview {
+        label: pending.label.clone(),
+        dispatcher: MockWebviewDispatcher {
+          id,
+          context: self.context.clone(),
+          url: Arc::new(Mutex::new(webview.url)),
+          navigation_handler: MockNavigationHandler(Arc::new(Mutex::new(
+            webview.navigation_handler,
+          ))),
+          last_evaluated_script: Default::default(),
+          muted: Default::default(),
+          focused: Arc::new(AtomicBool::new(webview.webview_attributes.focus)),
+          bounds: initial_bounds(webview.webview_attributes.bounds),
+          webview_event_listeners: Default::default(),
+          eval_result: Default::default(),
+        },
+      });
 
     Ok(DetachedWindow {
       id,
@@ -171,6 +190,9 @@ impl<T: UserEvent> RuntimeHandle<T> for MockRuntimeHandle {
       dispatcher: MockWindowDispatcher {
         id,
         context: self.context.clone(),
+        size: Default::default(),
+        window_event_listeners: Default::default(),
+        native_views: Default::default(),
       },
       webview,
     })
@@ -194,6 +216,12 @@ impl<T: UserEvent> RuntimeHandle<T> for MockRuntimeHandle {
         context: self.context.clone(),
         last_evaluated_script: Default::default(),
         url: Arc::new(Mutex::new(pending.url)),
+        navigation_handler: MockNavigationHandler(Arc::new(Mutex::new(pending.navigation_handler))),
+        muted: Default::default(),
+        focused: Arc::new(AtomicBool::new(pending.webview_attributes.focus)),
+        bounds: initial_bounds(pending.webview_attributes.bounds),
+        webview_event_listeners: Default::default(),
+        eval_result: Default::default(),
       },
     })
   }
@@ -236,6 +264,10 @@ impl<T: UserEvent> RuntimeHandle<T> for MockRuntimeHandle {
     unimplemented!()
   }
 
+  fn cursor_position(&self) -> Result<PhysicalPosition<f64>> {
+    Ok(PhysicalPosition::new(0.0, 0.0))
+  }
+
   /// Shows the application, but does not automatically focus it.
   #[cfg(target_os = "macos")]
   fn show(&self) -> Result<()> {
@@ -272,19 +304,163 @@ pub struct MockWebviewDispatcher {
   id: u32,
   context: RuntimeContext,
   url: Arc<Mutex<String>>,
+  navigation_handler: MockNavigationHandler,
   last_evaluated_script: Arc<Mutex<Option<String>>>,
+  muted: Arc<AtomicBool>,
+  focused: Arc<AtomicBool>,
+  bounds: Arc<Mutex<(PhysicalPosition<i32>, PhysicalSize<u32>)>>,
+  webview_event_listeners: MockEventListeners<WebviewEvent>,
+  eval_result: Arc<Mutex<Option<String>>>,
+}
+
+type NavigationHandler = Box<dyn Fn(&Url) -> bool + Send>;
+
+#[derive(Clone)]
+struct MockNavigationHandler(Arc<Mutex<Option<NavigationHandler>>>);
+
+impl fmt::Debug for MockNavigationHandler {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("MockNavigationHandler").finish()
+  }
+}
+
+type EventListener<E> = Box<dyn Fn(&E) + Send>;
+
+struct MockEventListeners<E>(Arc<Mutex<HashMap<u32, EventListener<E>>>>);
+
+impl<E> MockEventListeners<E> {
+  fn add(&self, id: u32, listener: EventListener<E>) {
+    self.0.lock().unwrap().insert(id, listener);
+  }
+
+  fn emit(&self, event: &E) {
+    for listener in self.0.lock().unwrap().values() {
+      listener(event);
+    }
+  }
+}
+
+impl<E> Default for MockEventListeners<E> {
+  fn default() -> Self {
+    Self(Default::default())
+  }
+}
+
+impl<E> Clone for MockEventListeners<E> {
+  fn clone(&self) -> Self {
+    Self(self.0.clone())
+  }
+}
+
+impl<E> fmt::Debug for MockEventListeners<E> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("MockEventListeners").finish()
+  }
+}
+
+/// The mock runtime uses a scale factor of 1.
+fn initial_bounds(
+  bounds: Option<(Position, Size)>,
+) -> Arc<Mutex<(PhysicalPosition<i32>, PhysicalSize<u32>)>> {
+  let bounds = bounds
+    .map(|(position, size)| (position.to_physical(1.0), size.to_physical(1.0)))
+    .unwrap_or_default();
+  Arc::new(Mutex::new(bounds))
+}
+
+/// Like the real runtimes, the webview cannot be created when its data directory is a file.
+fn check_data_directory<T: UserEvent>(webview: &PendingWebview<T, MockRuntime>) -> Result<()> {
+  match &webview.webview_attributes.data_directory {
+    Some(data_directory) if data_directory.is_file() => Err(Error::CreateWebview(
+      format!("`{}` is not a directory", data_directory.display()).into(),
+    )),
+    _ => Ok(()),
+  }
 }
 
 impl MockWebviewDispatcher {
+  /// Updates the webview bounds, emitting [`WebviewEvent::Resized`] if its size changed.
+  fn update_bounds(&self, position: Option<Position>, size: Option<Size>) {
+    let resized = {
+      let mut bounds = self.bounds.lock().unwrap();
+      if let Some(position) = position {
+        bounds.0 = position.to_physical(1.0);
+      }
+      match size.map(|size| size.to_physical(1.0)) {
+        Some(size) if size != bounds.1 => {
+          bounds.1 = size;
+          Some(size)
+        }
+        _ => None,
+      }
+    };
+    if let Some(size) = resized {
+      self
+        .webview_event_listeners
+        .emit(&WebviewEvent::Resized(size));
+    }
+  }
+
+  /// Emits a synthetic webview event to the webview listeners.
+  pub fn emit_webview_event(&self, event: WebviewEvent) {
+    self.webview_event_listeners.emit(&event);
+  }
+
+  /// Sets the JSON result passed to the callback of the scripts evaluated with a callback, `null` by default.
+  ///
+  /// The scripts evaluated in an isolated world receive it as is, an empty string by default.
+  pub fn set_eval_result(&self, result: impl Into<String>) {
+    self.eval_result.lock().unwrap().replace(result.into());
+  }
+
   pub fn last_evaluated_script(&self) -> Option<String> {
     self.last_evaluated_script.lock().unwrap().clone()
   }
+
+  pub fn is_focused(&self) -> bool {
+    self.focused.load(Ordering::Relaxed)
+  }
 }
 
 #[derive(Debug, Clone)]
 pub struct MockWindowDispatcher {
   id: WindowId,
   context: RuntimeContext,
+  size: Arc<Mutex<PhysicalSize<u32>>>,
+  window_event_listeners: MockEventListeners<WindowEvent>,
+  native_views: Arc<Mutex<Vec<MockNativeView>>>,
+}
+
+/// A native view of a [`MockWindowDispatcher`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockNativeView {
+  /// The native view id.
+  pub id: NativeViewId,
+  /// The position of the view.
+  pub position: PhysicalPosition<i32>,
+  /// The size of the view.
+  pub size: PhysicalSize<u32>,
+  /// Whether the view is visible.
+  pub visible: bool,
+}
+
+impl MockWindowDispatcher {
+  /// The native views of the window, from the bottom-most.
+  pub fn native_views(&self) -> Vec<MockNativeView> {
+    self.native_views.lock().unwrap().clone()
+  }
+
+  fn update_native_view<F: FnOnce(&mut MockNativeView)>(&self, id: NativeViewId, f: F) {
+    if let Some(view) = self
+      .native_views
+      .lock()
+      .unwrap()
+      .iter_mut()
+      .find(|view| view.id == id)
+    {
+      f(view);
+    }
+  }
 }
 
 #[derive(Debug, Clone)]
@@ -464,6 +640,12 @@ impl<T: UserEvent> WebviewDispatch<T> for MockWebviewDispatcher {
     Ok(())
   }
 
+  fn on_webview_event<F: Fn(&WebviewEvent) + Send + 'static>(&self, f: F) -> WebviewEventId {
+    let id = self.context.next_window_event_id();
+    self.webview_event_listeners.add(id, Box::new(f));
+    id
+  }
+
   #[cfg(any(debug_assertions, feature = "devtools"))]
   fn open_devtools(&self) {}
 
@@ -484,6 +666,33 @@ impl<T: UserEvent> WebviewDispatch<T> for MockWebviewDispatcher {
     Ok(())
   }
 
+  fn eval_script_with_callback<S: Into<String>, F: FnOnce(String) + Send + 'static>(
+    &self,
+    script: S,
+    callback: F,
+  ) -> Result<()> {
+    WebviewDispatch::<T>::eval_script(self, script)?;
+    let result = self.eval_result.lock().unwrap().clone();
+    callback(result.unwrap_or_else(|| "null".into()));
+    Ok(())
+  }
+
+  fn add_initialization_script_in_world(&self, _world: String, _script: String) -> Result<()> {
+    Ok(())
+  }
+
+  fn eval_script_in_world<S: Into<String>, F: FnOnce(String) + Send + 'static>(
+    &self,
+    _world: String,
+    script: S,
+    callback: F,
+  ) -> Result<()> {
+    WebviewDispatch::<T>::eval_script(self, script)?;
+    let result = self.eval_result.lock().unwrap().clone();
+    callback(result.unwrap_or_default());
+    Ok(())
+  }
+
   fn url(&self) -> Result<url::Url> {
     self
       .url
@@ -494,21 +703,43 @@ impl<T: UserEvent> WebviewDispatch<T> for MockWebviewDispatcher {
   }
 
   fn position(&self) -> Result<PhysicalPosition<i32>> {
-    Ok(PhysicalPosition { x: 0, y: 0 })
+    Ok(self.bounds.lock().unwrap().0)
   }
 
   fn size(&self) -> Result<PhysicalSize<u32>> {
-    Ok(PhysicalSize {
-      width: 0,
-      height: 0,
-    })
+    Ok(self.bounds.lock().unwrap().1)
+  }
+
+  fn scale_factor(&self) -> Result<f64> {
+    Ok(1.0)
+  }
+
+  fn theme(&self) -> Result<Theme> {
+    Ok(Theme::Light)
+  }
+
+  fn process_id(&self) -> Result<u32> {
+    Ok(std::process::id())
+  }
+
+  fn is_muted(&self) -> Result<bool> {
+    Ok(self.muted.load(Ordering::Relaxed))
   }
 
   fn navigate(&self, url: Url) -> Result<()> {
+    if let Some(handler) = &*self.navigation_handler.0.lock().unwrap() {
+      if !handler(&url) {
+        return Ok(());
+      }
+    }
     *self.url.lock().unwrap() = url.to_string();
     Ok(())
   }
 
+  fn navigate_with_headers(&self, url: Url, _headers: http::HeaderMap) -> Result<()> {
+    WebviewDispatch::<T>::navigate(self, url)
+  }
+
   fn print(&self) -> Result<()> {
     Ok(())
   }
@@ -517,15 +748,109 @@ impl<T: UserEvent> WebviewDispatch<T> for MockWebviewDispatcher {
     Ok(())
   }
 
-  fn set_size(&self, _size: Size) -> Result<()> {
+  fn set_size(&self, size: Size) -> Result<()> {
+    self.update_bounds(None, Some(size));
+    Ok(())
+  }
+
+  fn set_position(&self, position: Position) -> Result<()> {
+    self.update_bounds(Some(position), None);
+    Ok(())
+  }
+
+  fn set_bounds(&self, position: Position, size: Size) -> Result<()> {
+    self.update_bounds(Some(position), Some(size));
     Ok(())
   }
 
-  fn set_position(&self, _position: Position) -> Result<()> {
+  fn set_z_order(&self, index: usize) -> Result<()> {
     Ok(())
   }
 
   fn set_focus(&self) -> Result<()> {
+    if !self.focused.swap(true, Ordering::Relaxed) {
+      self
+        .webview_event_listeners
+        .emit(&WebviewEvent::Focused(true));
+    }
+    Ok(())
+  }
+
+  fn set_ignore_cursor_events(&self, ignore: bool) -> Result<()> {
+    Ok(())
+  }
+
+  fn set_content_insets(&self, insets: tauri_runtime::webview::Insets) -> Result<()> {
+    Ok(())
+  }
+
+  fn set_theme(&self, theme: Option<Theme>) -> Result<()> {
+    Ok(())
+  }
+
+  fn set_suspended(&self, suspended: bool) -> Result<()> {
+    Ok(())
+  }
+
+  fn set_muted(&self, muted: bool) -> Result<()> {
+    self.muted.store(muted, Ordering::Relaxed);
+    Ok(())
+  }
+
+  fn pause_all_media(&self) -> Result<()> {
+    Ok(())
+  }
+
+  fn screenshot<F: FnOnce(Result<Vec<u8>>) + Send + 'static>(&self, f: F) -> Result<()> {
+    f(Err(Error::Unsupported));
+    Ok(())
+  }
+
+  #[cfg(feature = "devtools-protocol")]
+  fn call_devtools_protocol_method<F: FnOnce(Result<serde_json::Value>) + Send + 'static>(
+    &self,
+    _method: String,
+    _params: serde_json::Value,
+    f: F,
+  ) -> Result<()> {
+    f(Err(Error::Unsupported));
+    Ok(())
+  }
+
+  #[cfg(feature = "devtools-protocol")]
+  fn subscribe_devtools_protocol_event<F: Fn(serde_json::Value) + Send + 'static>(
+    &self,
+    _event: String,
+    _f: F,
+  ) -> Result<()> {
+    Ok(())
+  }
+
+  fn reparent(&self, window_id: WindowId) -> Result<()> {
+    if self.context.windows.borrow().contains_key(&window_id) {
+      Ok(())
+    } else {
+      Err(Error::WindowNotFound)
+    }
+  }
+
+  fn recover(&self) -> Result<()> {
+    Ok(())
+  }
+
+  fn add_initialization_script(&self, _script: String) -> Result<()> {
+    Ok(())
+  }
+
+  fn set_background_throttling(&self, policy: BackgroundThrottlingPolicy) -> Result<()> {
+    Ok(())
+  }
+
+  fn set_spellcheck(&self, enabled: bool) -> Result<()> {
+    Ok(())
+  }
+
+  fn set_spellcheck_languages(&self, languages: Vec<String>) -> Result<()> {
     Ok(())
   }
 }
@@ -540,7 +865,9 @@ impl<T: UserEvent> WindowDispatch<T> for MockWindowDispatcher {
   }
 
   fn on_window_event<F: Fn(&WindowEvent) + Send + 'static>(&self, f: F) -> WindowEventId {
-    self.context.next_window_event_id()
+    let id = self.context.next_window_event_id();
+    self.window_event_listeners.add(id, Box::new(f));
+    id
   }
 
   fn scale_factor(&self) -> Result<f64> {
@@ -556,10 +883,7 @@ impl<T: UserEvent> WindowDispatch<T> for MockWindowDispatcher {
   }
 
   fn inner_size(&self) -> Result<PhysicalSize<u32>> {
-    Ok(PhysicalSize {
-      width: 0,
-      height: 0,
-    })
+    Ok(*self.size.lock().unwrap())
   }
 
   fn outer_size(&self) -> Result<PhysicalSize<u32>> {
@@ -693,6 +1017,10 @@ impl<T: UserEvent> WindowDispatch<T> for MockWindowDispatcher {
     pending: PendingWindow<T, Self::Runtime>,
     _after_window_creation: Option<F>,
   ) -> Result<DetachedWindow<T, Self::Runtime>> {
+    if let Some(webview) = &pending.webview {
+      check_data_directory(webview)?;
+    }
+
     let id = self.context.next_window_id();
 
     let (webview_id, webviews) = if let Some(w) = &pending.webview {
@@ -709,15 +1037,25 @@ impl<T: UserEvent> WindowDispatch<T> for MockWindowDispatcher {
       },
     );
 
-    let webview = webview_id.map(|id| DetachedWebview {
-      label: pending.label.clone(),
-      dispatcher: MockWebviewDispatcher {
-        id,
-        context: self.context.clone(),
-        url: Arc::new(Mutex::new(pending.webview.unwrap().url)),
-        last_evaluated_script: Default::default(),
-      },
-    });
+    let webview = webview_id
+      .zip(pending.webview)
+      .map(|(id, webview)| DetachedWebview {
+        label: pending.label.clone(),
+        dispatcher: MockWebviewDispatcher {
+          id,
+          context: self.context.clone(),
+          url: Arc::new(Mutex::new(webview.url)),
+          navigation_handler: MockNavigationHandler(Arc::new(Mutex::new(
+            webview.navigation_handler,
+          ))),
+          last_evaluated_script: Default::default(),
+          muted: Default::default(),
+          focused: Arc::new(AtomicBool::new(webview.webview_attributes.focus)),
+          bounds: initial_bounds(webview.webview_attributes.bounds),
+          webview_event_listeners: Default::default(),
+          eval_result: Default::default(),
+        },
+      });
 
     Ok(DetachedWindow {
       id,
@@ -725,6 +1063,9 @@ impl<T: UserEvent> WindowDispatch<T> for MockWindowDispatcher {
       dispatcher: MockWindowDispatcher {
         id,
         context: self.context.clone(),
+        size: Default::default(),
+        window_event_listeners: Default::default(),
+        native_views: Default::default(),
       },
       webview,
     })
@@ -747,6 +1088,12 @@ impl<T: UserEvent> WindowDispatch<T> for MockWindowDispatcher {
         context: self.context.clone(),
         last_evaluated_script: Default::default(),
         url: Arc::new(Mutex::new(pending.url)),
+        navigation_handler: MockNavigationHandler(Arc::new(Mutex::new(pending.navigation_handler))),
+        muted: Default::default(),
+        focused: Arc::new(AtomicBool::new(pending.webview_attributes.focus)),
+        bounds: initial_bounds(pending.webview_attributes.bounds),
+        webview_event_listeners: Default::default(),
+        eval_result: Default::default(),
       },
     })
   }
@@ -830,6 +1177,11 @@ impl<T: UserEvent> WindowDispatch<T> for MockWindowDispatcher {
   }
 
   fn set_size(&self, size: Size) -> Result<()> {
+    let size = size.to_physical(1.0);
+    *self.size.lock().unwrap() = size;
+    self
+      .window_event_listeners
+      .emit(&WindowEvent::Resized(size));
     Ok(())
   }
 
@@ -892,6 +1244,65 @@ impl<T: UserEvent> WindowDispatch<T> for MockWindowDispatcher {
   fn set_progress_bar(&self, progress_state: ProgressBarState) -> Result<()> {
     Ok(())
   }
+
+  fn create_native_view(&self, position: Position, size: Size) -> Result<NativeViewId> {
+    let mut native_views = self.native_views.lock().unwrap();
+    let id = native_views
+      .iter()
+      .map(|view| view.id + 1)
+      .max()
+      .unwrap_or(0);
+    native_views.push(MockNativeView {
+      id,
+      position: position.to_physical(1.0),
+      size: size.to_physical(1.0),
+      visible: true,
+    });
+    Ok(id)
+  }
+
+  fn native_view_handle(
+    &self,
+    id: NativeViewId,
+  ) -> std::result::Result<raw_window_handle::WindowHandle<'_>, raw_window_handle::HandleError> {
+    if self.native_views().iter().any(|view| view.id == id) {
+      WindowDispatch::<T>::window_handle(self)
+    } else {
+      Err(raw_window_handle::HandleError::Unavailable)
+    }
+  }
+
+  fn set_native_view_bounds(&self, id: NativeViewId, position: Position, size: Size) -> Result<()> {
+    self.update_native_view(id, |view| {
+      view.position = position.to_physical(1.0);
+      view.size = size.to_physical(1.0);
+    });
+    Ok(())
+  }
+
+  fn set_native_view_visible(&self, id: NativeViewId, visible: bool) -> Result<()> {
+    self.update_native_view(id, |view| view.visible = visible);
+    Ok(())
+  }
+
+  fn set_native_view_z_order(&self, id: NativeViewId, index: usize) -> Result<()> {
+    let mut native_views = self.native_views.lock().unwrap();
+    if let Some(current) = native_views.iter().position(|view| view.id == id) {
+      let view = native_views.remove(current);
+      let index = index.min(native_views.len());
+      native_views.insert(index, view);
+    }
+    Ok(())
+  }
+
+  fn remove_native_view(&self, id: NativeViewId) -> Result<()> {
+    self
+      .native_views
+      .lock()
+      .unwrap()
+      .retain(|view| view.id != id);
+    Ok(())
+  }
 }
 
 #[derive(Debug, Clone)]
@@ -937,6 +1348,12 @@ impl<T: UserEvent> Runtime<T> for MockRuntime {
   type Handle = MockRuntimeHandle;
   type EventLoopProxy = EventProxy;
 
+  const NAME: &'static str = "mock";
+
+  fn webview_version() -> Result<String> {
+    Err(Error::Unsupported)
+  }
+
   fn new(_args: RuntimeInitArgs) -> Result<Self> {
     Ok(Self::init())
   }
@@ -961,6 +1378,10 @@ impl<T: UserEvent> Runtime<T> for MockRuntime {
     pending: PendingWindow<T, Self>,
     _after_window_creation: Option<F>,
   ) -> Result<DetachedWindow<T, Self>> {
+    if let Some(webview) = &pending.webview {
+      check_data_directory(webview)?;
+    }
+
     let id = self.context.next_window_id();
 
     let (webview_id, webviews) = if let Some(w) = &pending.webview {
@@ -977,15 +1398,25 @@ impl<T: UserEvent> Runtime<T> for MockRuntime {
       },
     );
 
-    let webview = webview_id.map(|id| DetachedWebview {
-      label: pending.label.clone(),
-      dispatcher: MockWebviewDispatcher {
-        id,
-        context: self.context.clone(),
-        url: Arc::new(Mutex::new(pending.webview.unwrap().url)),
-        last_evaluated_script: Default::default(),
-      },
-    });
+    let webview = webview_id
+      .zip(pending.webview)
+      .map(|(id, webview)| DetachedWebview {
+        label: pending.label.clone(),
+        dispatcher: MockWebviewDispatcher {
+          id,
+          context: self.context.clone(),
+          url: Arc::new(Mutex::new(webview.url)),
+          navigation_handler: MockNavigationHandler(Arc::new(Mutex::new(
+            webview.navigation_handler,
+          ))),
+          last_evaluated_script: Default::default(),
+          muted: Default::default(),
+          focused: Arc::new(AtomicBool::new(webview.webview_attributes.focus)),
+          bounds: initial_bounds(webview.webview_attributes.bounds),
+          webview_event_listeners: Default::default(),
+          eval_result: Default::default(),
+        },
+      });
 
     Ok(DetachedWindow {
       id,
@@ -993,6 +1424,9 @@ impl<T: UserEvent> Runtime<T> for MockRuntime {
       dispatcher: MockWindowDispatcher {
         id,
         context: self.context.clone(),
+        size: Default::default(),
+        window_event_listeners: Default::default(),
+        native_views: Default::default(),
       },
       webview,
     })
@@ -1016,6 +1450,12 @@ impl<T: UserEvent> Runtime<T> for MockRuntime {
         context: self.context.clone(),
         last_evaluated_script: Default::default(),
         url: Arc::new(Mutex::new(pending.url)),
+        navigation_handler: MockNavigationHandler(Arc::new(Mutex::new(pending.navigation_handler))),
+        muted: Default::default(),
+        focused: Arc::new(AtomicBool::new(pending.webview_attributes.focus)),
+        bounds: initial_bounds(pending.webview_attributes.bounds),
+        webview_event_listeners: Default::default(),
+        eval_result: Default::default(),
       },
     })
   }
@@ -1094,6 +1534,20 @@ impl<T: UserEvent> Runtime<T> for MockRuntime {
               }
             }
           }
+          Message::RequestExit(code) => {
+            let (tx, rx) = channel();
+            callback(RunEvent::ExitRequested {
+              code: Some(code),
+              tx,
+            });
+
+            let recv = rx.try_recv();
+            let should_prevent = matches!(recv, Ok(ExitRequestedEventAction::Prevent));
+
+            if !should_prevent {
+              break;
+            }
+          }
           Message::DestroyWindow(id) => {
             let removed = self.context.windows.borrow_mut().remove(&id).is_some();
             if removed {
@@ -48,15 +48,29 @@
 
 #![allow(unused_variables)]
 
+#[cfg(feature = "remote-automation")]
+mod automation;
 mod mock_runtime;
+#[cfg(feature = "test")]
+mod snapshot;
+#[cfg(feature = "remote-automation")]
+#[cfg_attr(docsrs, doc(cfg(feature = "remote-automation")))]
+pub use automation::AutomationClient;
 pub use mock_runtime::*;
 use serde::Serialize;
+#[cfg(all(desktop, feature = "test"))]
+pub use snapshot::set_snapshot_size;
+#[cfg(feature = "test")]
+pub use snapshot::{
+  assert_snapshot, assert_snapshot_with, force_scale_factor, SnapshotOptions, SnapshotStatus,
+  SNAPSHOT_DIR_ENV, UPDATE_SNAPSHOTS_ENV,
+};
 
 use std::{borrow::Cow, collections::HashMap, fmt::Debug};
 
 use crate::{
   ipc::{InvokeBody, InvokeError, InvokeResponse},
-  webview::InvokeRequest,
+  webview::{invoke_key::INVOKE_KEY_HEADER, InvokeRequest},
   App, Builder, Context, Pattern, Webview,
 };
 use tauri_utils::{
@@ -107,6 +121,7 @@ pub fn mock_context<A: Assets>(assets: A) -> crate::Context<A> {
         security: Default::default(),
         tray_icon: None,
         macos_private_api: false,
+        asset_cache: Default::default(),
       },
       bundle: Default::default(),
       build: Default::default(),
@@ -134,6 +149,10 @@ pub fn mock_context<A: Assets>(assets: A) -> crate::Context<A> {
       command_scope: Default::default(),
       global_scope: Default::default(),
     },
+    #[cfg(all(dev, feature = "dev-dynamic-assets"))]
+    frontend_dist_dir: None,
+    #[cfg(all(dev, desktop, feature = "dev-config-reload"))]
+    config_dir: None,
   }
 }
 
@@ -261,9 +280,17 @@ pub fn get_ipc_response<W: AsRef<Webview<MockRuntime>>>(
   request: InvokeRequest,
 ) -> Result<InvokeBody, serde_json::Value> {
   let (tx, rx) = std::sync::mpsc::sync_channel(1);
-  webview.as_ref().clone().on_message(
+  let webview = webview.as_ref().clone();
+  let mut request = request;
+  if !request.headers.contains_key(INVOKE_KEY_HEADER) {
+    let key = webview.invoke_key.lock().unwrap().current().to_string();
+    request
+      .headers
+      .insert(INVOKE_KEY_HEADER, key.parse().unwrap());
+  }
+  webview.on_message(
     request,
-    Box::new(move |_window, _cmd, response, _callback, _error| {
+    Box::new(move |_window, _cmd, response, _meta, _callback, _error| {
       tx.send(response).unwrap();
     }),
   );
@@ -275,6 +302,27 @@ pub fn get_ipc_response<W: AsRef<Webview<MockRuntime>>>(
   }
 }
 
+/// Executes the given command with the given body and headers and get its JSON response.
+#[cfg(test)]
+pub(crate) fn get_ipc_json_response<W: AsRef<Webview<MockRuntime>>>(
+  webview: &W,
+  cmd: &str,
+  body: impl Into<InvokeBody>,
+  headers: http::HeaderMap,
+) -> Result<serde_json::Value, serde_json::Value> {
+  get_ipc_response(
+    webview,
+    InvokeRequest {
+      cmd: cmd.into(),
+      callback: crate::ipc::CallbackFn(0),
+      error: crate::ipc::CallbackFn(1),
+      body: body.into(),
+      headers,
+    },
+  )
+  .map(InvokeBody::into_json)
+}
+
 #[cfg(test)]
 mod tests {
   use std::time::Duration;
@@ -0,0 +1,454 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Comparing webview screenshots against stored PNG snapshots, see [`assert_snapshot`].
+
+use std::{
+  fs::File,
+  io::BufWriter,
+  path::{Path, PathBuf},
+  sync::mpsc::channel,
+  time::Duration,
+};
+
+#[cfg(desktop)]
+use crate::{LogicalSize, Size, Window};
+use crate::{Runtime, Webview};
+
+/// The environment variable that creates or updates the snapshots when set to `1`.
+pub const UPDATE_SNAPSHOTS_ENV: &str = "UPDATE_SNAPSHOTS";
+/// The environment variable that overrides the default snapshot directory.
+pub const SNAPSHOT_DIR_ENV: &str = "TAURI_SNAPSHOT_DIR";
+
+/// The result of [`assert_snapshot`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotStatus {
+  /// The capture matches the stored snapshot.
+  Matched,
+  /// The snapshot did not exist and was created from the capture.
+  Created,
+  /// The snapshot did not match and was replaced by the capture.
+  Updated,
+  /// The webview could not be captured, for instance on a headless CI machine.
+  Skipped(String),
+}
+
+/// Options of [`assert_snapshot_with`].
+#[derive(Debug, Clone)]
+pub struct SnapshotOptions {
+  /// The directory of the snapshots.
+  ///
+  /// Defaults to the `TAURI_SNAPSHOT_DIR` environment variable, or `tests/snapshots` in the crate directory.
+  pub dir: PathBuf,
+  /// How different two pixels can be before they are considered different, between `0.0` and `1.0`.
+  ///
+  /// The difference is perceptual, so small anti-aliasing changes are ignored with the default of `0.1`.
+  pub threshold: f64,
+  /// The portion of the pixels that can differ before the capture is considered a mismatch,
+  /// between `0.0` and `1.0`. Defaults to `0.0`.
+  pub max_diff_ratio: f64,
+  /// How long to wait for the capture. Defaults to 10 seconds.
+  pub timeout: Duration,
+}
+
+impl Default for SnapshotOptions {
+  fn default() -> Self {
+    let dir = std::env::var_os(SNAPSHOT_DIR_ENV)
+      .map(PathBuf::from)
+      .or_else(|| {
+        std::env::var_os("CARGO_MANIFEST_DIR").map(|dir| Path::new(&dir).join("tests/snapshots"))
+      })
+      .unwrap_or_else(|| PathBuf::from("snapshots"));
+    Self {
+      dir,
+      threshold: 0.1,
+      max_diff_ratio: 0.0,
+      timeout: Duration::from_secs(10),
+    }
+  }
+}
+
+/// Forces the scale factor of the webviews so their captures have the same size on every machine.
+///
+/// Must be called before the app is built.
+///
+/// ## Platform-specific
+///
+/// - **Linux:** Only integer scale factors are supported.
+/// - **macOS / iOS / Android:** Unsupported, the captures use the scale factor of the display.
+pub fn force_scale_factor(scale_factor: f64) {
+  #[cfg(windows)]
+  std::env::set_var(
+    "WEBVIEW2_ADDITIONAL_BROWSER_ARGUMENTS",
+    format!("--force-device-scale-factor={scale_factor}"),
+  );
+  #[cfg(target_os = "linux")]
+  std::env::set_var(
+    "GDK_SCALE",
+    (scale_factor.round() as u32).max(1).to_string(),
+  );
+}
+
+/// Resizes the window to a fixed logical size before a capture,
+/// unmaximizing it and preventing the user from resizing it.
+#[cfg(desktop)]
+pub fn set_snapshot_size<R: Runtime>(
+  window: &Window<R>,
+  size: LogicalSize<f64>,
+) -> crate::Result<()> {
+  window.unmaximize()?;
+  window.set_resizable(false)?;
+  window.set_size(Size::Logical(size))
+}
+
+/// Captures the webview and compares it with the `<name>.png` snapshot,
+/// using the default [`SnapshotOptions`].
+///
+/// See [`assert_snapshot_with`].
+pub fn assert_snapshot<R: Runtime, W: AsRef<Webview<R>>>(
+  webview: &W,
+  name: &str,
+) -> SnapshotStatus {
+  assert_snapshot_with(webview, name, &SnapshotOptions::default())
+}
+
+/// Captures the webview and compares it with the `<name>.png` snapshot.
+///
+/// When the snapshot is missing, it is created if the `UPDATE_SNAPSHOTS` environment variable is set to `1`.
+/// When the capture does not match, the snapshot is replaced if `UPDATE_SNAPSHOTS=1`,
+/// otherwise the capture and an image highlighting the differences are written next to it
+/// as `<name>.actual.png` and `<name>.diff.png`.
+///
+/// The capture runs on the main thread, so this function must be called from another thread.
+/// It returns [`SnapshotStatus::Skipped`] when the webview cannot be captured.
+///
+/// # Panics
+///
+/// Panics if the capture does not match, if the snapshot is missing without `UPDATE_SNAPSHOTS=1`
+/// or if the snapshot files cannot be read or written.
+pub fn assert_snapshot_with<R: Runtime, W: AsRef<Webview<R>>>(
+  webview: &W,
+  name: &str,
+  options: &SnapshotOptions,
+) -> SnapshotStatus {
+  let status = match capture(webview.as_ref(), options.timeout) {
+    Ok(png) => compare_snapshot(&png, name, options),
+    Err(reason) => SnapshotStatus::Skipped(reason),
+  };
+  if let SnapshotStatus::Skipped(reason) = &status {
+    eprintln!("snapshot `{name}` skipped: {reason}");
+  }
+  status
+}
+
+fn capture<R: Runtime>(webview: &Webview<R>, timeout: Duration) -> Result<Vec<u8>, String> {
+  #[cfg(target_os = "linux")]
+  if std::env::var_os("DISPLAY").is_none() && std::env::var_os("WAYLAND_DISPLAY").is_none() {
+    return Err("no display available".into());
+  }
+
+  let (tx, rx) = channel();
+  webview
+    .screenshot(move |result| {
+      let _ = tx.send(result);
+    })
+    .map_err(|e| e.to_string())?;
+  match rx.recv_timeout(timeout) {
+    Ok(result) => result.map_err(|e| e.to_string()),
+    Err(_) => Err("timed out waiting for the capture".into()),
+  }
+}
+
+fn compare_snapshot(png: &[u8], name: &str, options: &SnapshotOptions) -> SnapshotStatus {
+  let update = std::env::var(UPDATE_SNAPSHOTS_ENV).is_ok_and(|v| v == "1");
+  let path = options.dir.join(format!("{name}.png"));
+  let actual = Image::decode(png).expect("failed to decode the capture");
+
+  let write = |path: &Path, image: &Image| {
+    std::fs::create_dir_all(&options.dir).expect("failed to create the snapshot directory");
+    image
+      .write(path)
+      .unwrap_or_else(|e| panic!("failed to write {}: {e}", path.display()));
+  };
+
+  if !path.exists() {
+    assert!(
+      update,
+      "snapshot {} does not exist, run the tests with {UPDATE_SNAPSHOTS_ENV}=1 to create it",
+      path.display()
+    );
+    write(&path, &actual);
+    return SnapshotStatus::Created;
+  }
+
+  let expected = std::fs::read(&path)
+    .map_err(|e| e.to_string())
+    .and_then(|bytes| Image::decode(&bytes))
+    .unwrap_or_else(|e| panic!("failed to read {}: {e}", path.display()));
+
+  let mismatch = match diff(&expected, &actual, options.threshold) {
+    Some(diff) => {
+      let ratio = diff.count as f64 / (actual.width as f64 * actual.height as f64).max(1.0);
+      (ratio > options.max_diff_ratio).then(|| {
+        (
+          format!(
+            "{} pixels differ ({:.3}% of the image)",
+            diff.count,
+            ratio * 100.0
+          ),
+          Some(diff.image),
+        )
+      })
+    }
+    None => Some((
+      format!(
+        "the capture is {}x{} but the snapshot is {}x{}",
+        actual.width, actual.height, expected.width, expected.height
+      ),
+      None,
+    )),
+  };
+
+  match mismatch {
+    None => SnapshotStatus::Matched,
+    Some(_) if update => {
+      write(&path, &actual);
+      SnapshotStatus::Updated
+    }
+    Some((reason, diff_image)) => {
+      write(&options.dir.join(format!("{name}.actual.png")), &actual);
+      if let Some(diff_image) = diff_image {
+        write(&options.dir.join(format!("{name}.diff.png")), &diff_image);
+      }
+      panic!(
+        "snapshot {} does not match: {reason}, run the tests with {UPDATE_SNAPSHOTS_ENV}=1 to update it",
+        path.display()
+      );
+    }
+  }
+}
+
+/// An RGBA image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Image {
+  width: u32,
+  height: u32,
+  rgba: Vec<u8>,
+}
+
+impl Image {
+  fn decode(png: &[u8]) -> Result<Self, String> {
+    let mut decoder = png::Decoder::new(png);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(|e| e.to_string())?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer).map_err(|e| e.to_string())?;
+    buffer.truncate(info.buffer_size());
+
+    let rgba = match info.color_type {
+      png::ColorType::Rgba => buffer,
+      png::ColorType::Rgb => buffer
+        .chunks_exact(3)
+        .flat_map(|p| [p[0], p[1], p[2], 255])
+        .collect(),
+      png::ColorType::GrayscaleAlpha => buffer
+        .chunks_exact(2)
+        .flat_map(|p| [p[0], p[0], p[0], p[1]])
+        .collect(),
+      png::ColorType::Grayscale => buffer.iter().flat_map(|&p| [p, p, p, 255]).collect(),
+      png::ColorType::Indexed => return Err("unexpected indexed PNG".into()),
+    };
+    Ok(Self {
+      width: info.width,
+      height: info.height,
+      rgba,
+    })
+  }
+
+  fn encode(&self) -> Result<Vec<u8>, png::EncodingError> {
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, self.width, self.height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(&self.rgba)?;
+    Ok(png)
+  }
+
+  fn write(&self, path: &Path) -> std::io::Result<()> {
+    let png = self
+      .encode()
+      .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+    let mut file = BufWriter::new(File::create(path)?);
+    std::io::Write::write_all(&mut file, &png)
+  }
+}
+
+struct Diff {
+  /// The number of different pixels.
+  count: usize,
+  /// The expected image faded out, with the different pixels in red.
+  image: Image,
+}
+
+/// The maximum value of [`color_delta`], between black and white.
+const MAX_DELTA: f64 = 35215.0;
+
+/// The perceptual difference between two pixels, measured in the YIQ color space
+/// after blending them with a white background.
+fn color_delta(a: &[u8], b: &[u8]) -> f64 {
+  fn blend(pixel: &[u8]) -> (f64, f64, f64) {
+    let alpha = pixel[3] as f64 / 255.0;
+    let blend = |c: u8| 255.0 + (c as f64 - 255.0) * alpha;
+    (blend(pixel[0]), blend(pixel[1]), blend(pixel[2]))
+  }
+  fn yiq((r, g, b): (f64, f64, f64)) -> (f64, f64, f64) {
+    (
+      r * 0.29889531 + g * 0.58662247 + b * 0.11448223,
+      r * 0.59597799 - g * 0.2741761 - b * 0.32180189,
+      r * 0.21147017 - g * 0.52261711 + b * 0.31114694,
+    )
+  }
+
+  let (ya, ia, qa) = yiq(blend(a));
+  let (yb, ib, qb) = yiq(blend(b));
+  let (y, i, q) = (ya - yb, ia - ib, qa - qb);
+  0.5053 * y * y + 0.299 * i * i + 0.1957 * q * q
+}
+
+/// Compares two images, `None` if their sizes differ.
+fn diff(expected: &Image, actual: &Image, threshold: f64) -> Option<Diff> {
+  if (expected.width, expected.height) != (actual.width, actual.height) {
+    return None;
+  }
+
+  let max_delta = MAX_DELTA * threshold * threshold;
+  let mut count = 0;
+  let mut rgba = Vec::with_capacity(expected.rgba.len());
+  for (a, b) in expected
+    .rgba
+    .chunks_exact(4)
+    .zip(actual.rgba.chunks_exact(4))
+  {
+    if color_delta(a, b) > max_delta {
+      count += 1;
+      rgba.extend_from_slice(&[255, 0, 0, 255]);
+    } else {
+      let (r, g, b) = (a[0] as u32, a[1] as u32, a[2] as u32);
+      let gray = ((r * 299 + g * 587 + b * 114) / 1000) as u8;
+      // fade the unchanged pixels so the differences stand out
+      let faded = 255 - (255 - gray) / 10;
+      rgba.extend_from_slice(&[faded, faded, faded, 255]);
+    }
+  }
+
+  Some(Diff {
+    count,
+    image: Image {
+      width: expected.width,
+      height: expected.height,
+      rgba,
+    },
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn image(pixels: &[[u8; 4]]) -> Image {
+    Image {
+      width: pixels.len() as u32,
+      height: 1,
+      rgba: pixels.concat(),
+    }
+  }
+
+  fn options(name: &str) -> SnapshotOptions {
+    let dir = std::env::temp_dir().join(format!("tauri-snapshots-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    SnapshotOptions {
+      dir,
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn png_round_trip() {
+    let image = image(&[[255, 0, 0, 255], [0, 0, 0, 0], [10, 20, 30, 128]]);
+    assert_eq!(Image::decode(&image.encode().unwrap()).unwrap(), image);
+  }
+
+  #[test]
+  fn perceptual_threshold() {
+    let white = [255, 255, 255, 255];
+    let expected = image(&[white, white, [0, 0, 0, 255]]);
+    // a slightly different white and a transparent pixel blended on white are not different
+    let actual = image(&[[250, 250, 250, 255], [0, 0, 0, 0], [255, 0, 0, 255]]);
+
+    let result = diff(&expected, &actual, 0.1).unwrap();
+    assert_eq!(result.count, 1);
+    assert_eq!(&result.image.rgba[8..], &[255, 0, 0, 255]);
+    assert_eq!(diff(&expected, &actual, 0.0).unwrap().count, 2);
+    assert_eq!(diff(&expected, &actual, 1.0).unwrap().count, 0);
+
+    assert!(diff(&expected, &image(&[white]), 0.1).is_none());
+  }
+
+  #[test]
+  fn snapshot_lifecycle() {
+    let options = options("lifecycle");
+    let black = image(&[[0, 0, 0, 255]; 4]).encode().unwrap();
+    let red = image(&[
+      [0, 0, 0, 255],
+      [255, 0, 0, 255],
+      [0, 0, 0, 255],
+      [0, 0, 0, 255],
+    ])
+    .encode()
+    .unwrap();
+
+    std::env::set_var(UPDATE_SNAPSHOTS_ENV, "1");
+    assert_eq!(
+      compare_snapshot(&black, "page", &options),
+      SnapshotStatus::Created
+    );
+    assert_eq!(
+      compare_snapshot(&black, "page", &options),
+      SnapshotStatus::Matched
+    );
+    std::env::remove_var(UPDATE_SNAPSHOTS_ENV);
+
+    let mismatch = std::panic::catch_unwind(|| compare_snapshot(&red, "page", &options));
+    assert!(mismatch.is_err());
+    assert!(options.dir.join("page.actual.png").exists());
+    let diff = std::fs::read(options.dir.join("page.diff.png")).unwrap();
+    assert_eq!(&Image::decode(&diff).unwrap().rgba[4..8], &[255, 0, 0, 255]);
+
+    let tolerant = SnapshotOptions {
+      max_diff_ratio: 0.25,
+      ..options.clone()
+    };
+    assert_eq!(
+      compare_snapshot(&red, "page", &tolerant),
+      SnapshotStatus::Matched
+    );
+
+    let missing = std::panic::catch_unwind(|| compare_snapshot(&black, "missing", &options));
+    assert!(missing.is_err());
+
+    let _ = std::fs::remove_dir_all(&options.dir);
+  }
+
+  #[test]
+  fn unsupported_capture_is_skipped() {
+    let app = crate::test::mock_app();
+    let webview = crate::WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    assert!(matches!(
+      assert_snapshot_with(&webview, "mock", &options("mock")),
+      SnapshotStatus::Skipped(_)
+    ));
+  }
+}
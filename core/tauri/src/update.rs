@@ -0,0 +1,436 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Checks for app updates without the updater plugin, see [`AppHandle::check_update`].
+//!
+//! The update manifests use the static JSON format of the updater plugin:
+//!
+//! ```json
+//! {
+//!   "version": "v1.1.0",
+//!   "notes": "Bug fixes",
+//!   "pub_date": "2023-11-14T22:13:20Z",
+//!   "platforms": {
+//!     "linux-x86_64": { "signature": "<base64 minisign signature>", "url": "https://releases.myapp.com/1.1.0/myapp.AppImage.tar.gz" },
+//!     "windows-x86_64": { "signature": "<base64 minisign signature>", "url": "https://releases.myapp.com/1.1.0/myapp.msi.zip" }
+//!   }
+//! }
+//! ```
+//!
+//! The signatures are checked against the public key of the `plugins > updater > pubkey` config,
+//! so the keys generated with `tauri signer generate` work as is.
+//! Downloading and installing the update is left to the app, see [`UpdateInfo::verify`].
+
+use std::{collections::HashMap, fmt, future::Future};
+
+use base64::Engine;
+use futures_util::future::BoxFuture;
+use minisign_verify::{PublicKey, Signature};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{AppHandle, Manager, Runtime};
+
+/// The event emitted by [`AppHandle::check_update`] when an update is available, with the [`UpdateInfo`] as payload.
+pub const UPDATE_AVAILABLE_EVENT: &str = "tauri://update-available";
+
+/// An update manifest, in the static JSON format of the updater plugin.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateManifest {
+  /// The version of the update, with an optional `v` prefix.
+  pub version: String,
+  /// The release notes.
+  #[serde(default)]
+  pub notes: Option<String>,
+  /// The publication date, in RFC 3339 format.
+  #[serde(default)]
+  pub pub_date: Option<String>,
+  /// The update of each `<os>-<arch>` target, e.g. `linux-x86_64`, `darwin-aarch64` or `windows-i686`.
+  pub platforms: HashMap<String, PlatformUpdate>,
+}
+
+/// The update of a target in an [`UpdateManifest`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlatformUpdate {
+  /// The URL of the update artifact.
+  pub url: Url,
+  /// The base64 encoded minisign signature of the update artifact.
+  pub signature: String,
+}
+
+/// Where [`AppHandle::check_update`] gets the update manifest from.
+pub enum UpdateSource {
+  /// Fetches the manifest with a `GET` request.
+  ///
+  /// A `204 No Content` response means there is no update.
+  Url(Url),
+  /// Gets the manifest from a handler, returning `None` when there is no update.
+  Custom(Box<dyn Fn() -> BoxFuture<'static, crate::Result<Option<UpdateManifest>>> + Send + Sync>),
+}
+
+impl fmt::Debug for UpdateSource {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Url(url) => f.debug_tuple("Url").field(url).finish(),
+      Self::Custom(_) => f.debug_tuple("Custom").finish(),
+    }
+  }
+}
+
+impl UpdateSource {
+  /// Creates an [`UpdateSource::Custom`] from an async handler.
+  pub fn custom<F, Fut>(handler: F) -> Self
+  where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = crate::Result<Option<UpdateManifest>>> + Send + 'static,
+  {
+    Self::Custom(Box::new(move || Box::pin(handler())))
+  }
+
+  async fn manifest(&self) -> crate::Result<Option<UpdateManifest>> {
+    match self {
+      Self::Url(url) => {
+        let response = reqwest::Client::new()
+          .get(url.clone())
+          .header(reqwest::header::ACCEPT, "application/json")
+          .send()
+          .await
+          .and_then(|response| response.error_for_status())
+          .map_err(|e| crate::Error::UpdateCheck(e.to_string()))?;
+        if response.status() == reqwest::StatusCode::NO_CONTENT {
+          return Ok(None);
+        }
+        response
+          .json()
+          .await
+          .map(Some)
+          .map_err(|e| crate::Error::UpdateCheck(format!("invalid update manifest: {e}")))
+      }
+      Self::Custom(handler) => handler().await,
+    }
+  }
+}
+
+/// An available update, see [`AppHandle::check_update`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInfo {
+  /// The version of the update.
+  pub version: String,
+  /// The version of the running app.
+  pub current_version: String,
+  /// The release notes.
+  pub notes: Option<String>,
+  /// The publication date, in RFC 3339 format.
+  pub pub_date: Option<String>,
+  /// The `<os>-<arch>` target of the update.
+  pub target: String,
+  /// The URL of the update artifact.
+  pub url: Url,
+  /// The base64 encoded minisign signature of the update artifact.
+  pub signature: String,
+  #[serde(skip)]
+  pubkey: String,
+}
+
+impl UpdateInfo {
+  /// Verifies the downloaded update artifact against its signature.
+  ///
+  /// Always verify the artifact before installing it.
+  pub fn verify(&self, data: &[u8]) -> crate::Result<()> {
+    let public_key = decode_public_key(&self.pubkey)?;
+    let signature = decode_signature(&self.signature)?;
+    public_key
+      .verify(data, &signature, true)
+      .map_err(|e| crate::Error::UpdateCheck(format!("invalid update signature: {e}")))
+  }
+}
+
+fn decode_base64(value: &str, name: &str) -> crate::Result<String> {
+  base64::engine::general_purpose::STANDARD
+    .decode(value.trim())
+    .ok()
+    .and_then(|decoded| String::from_utf8(decoded).ok())
+    .ok_or_else(|| crate::Error::UpdateCheck(format!("the {name} is not valid base64")))
+}
+
+fn decode_public_key(pubkey: &str) -> crate::Result<PublicKey> {
+  PublicKey::decode(&decode_base64(pubkey, "update public key")?)
+    .map_err(|e| crate::Error::UpdateCheck(format!("invalid update public key: {e}")))
+}
+
+fn decode_signature(signature: &str) -> crate::Result<Signature> {
+  Signature::decode(&decode_base64(signature, "update signature")?)
+    .map_err(|e| crate::Error::UpdateCheck(format!("invalid update signature: {e}")))
+}
+
+/// The `<os>-<arch>` target of the running app in the update manifests.
+fn current_target() -> Option<String> {
+  let os = if cfg!(target_os = "macos") {
+    "darwin"
+  } else if cfg!(windows) {
+    "windows"
+  } else if cfg!(target_os = "linux") {
+    "linux"
+  } else {
+    return None;
+  };
+  let arch = match std::env::consts::ARCH {
+    "x86" => "i686",
+    "arm" => "armv7",
+    arch => arch,
+  };
+  Some(format!("{os}-{arch}"))
+}
+
+/// Returns the update of `target` in the manifest if it is newer than `current_version`,
+/// checking that its signature was made with `pubkey`.
+fn check(
+  manifest: UpdateManifest,
+  current_version: &semver::Version,
+  pubkey: &str,
+  target: &str,
+) -> crate::Result<Option<UpdateInfo>> {
+  let version = semver::Version::parse(manifest.version.trim_start_matches('v')).map_err(|e| {
+    crate::Error::UpdateCheck(format!(
+      "invalid update version `{}`: {e}",
+      manifest.version
+    ))
+  })?;
+  if &version <= current_version {
+    return Ok(None);
+  }
+
+  let Some(update) = manifest.platforms.get(target) else {
+    return Err(crate::Error::UpdateCheck(format!(
+      "the update manifest has no `{target}` platform"
+    )));
+  };
+
+  // the artifact is not downloaded yet, but the signature must come from the configured key
+  let public_key = decode_public_key(pubkey)?;
+  let signature = decode_signature(&update.signature)?;
+  public_key
+    .verify_stream(&signature)
+    .map_err(|e| crate::Error::UpdateCheck(format!("invalid update signature: {e}")))?;
+
+  Ok(Some(UpdateInfo {
+    version: version.to_string(),
+    current_version: current_version.to_string(),
+    notes: manifest.notes,
+    pub_date: manifest.pub_date,
+    target: target.into(),
+    url: update.url.clone(),
+    signature: update.signature.clone(),
+    pubkey: pubkey.into(),
+  }))
+}
+
+impl<R: Runtime> AppHandle<R> {
+  /// Checks for an update of the app, emitting the [`UPDATE_AVAILABLE_EVENT`] when one is available.
+  ///
+  /// The manifest update is returned if its version is greater than the app version
+  /// and it has an update for the running target, signed with the key of the `plugins > updater > pubkey` config.
+  /// Since the artifact is not downloaded, only the signing key is checked here,
+  /// the artifact must be checked with [`UpdateInfo::verify`] once downloaded.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **iOS / Android:** Unsupported.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use tauri::update::UpdateSource;
+  ///
+  /// tauri::Builder::default()
+  ///   .setup(|app| {
+  ///     let handle = app.handle().clone();
+  ///     tauri::async_runtime::spawn(async move {
+  ///       let source = UpdateSource::Url("https://releases.myapp.com/latest.json".parse().unwrap());
+  ///       if let Ok(Some(update)) = handle.check_update(source).await {
+  ///         println!("version {} is available", update.version);
+  ///       }
+  ///     });
+  ///     Ok(())
+  ///   });
+  /// ```
+  pub async fn check_update(&self, source: UpdateSource) -> crate::Result<Option<UpdateInfo>> {
+    let pubkey = self
+      .config()
+      .plugins
+      .0
+      .get("updater")
+      .and_then(|updater| updater.get("pubkey"))
+      .and_then(|pubkey| pubkey.as_str())
+      .ok_or_else(|| {
+        crate::Error::UpdateCheck("the `plugins > updater > pubkey` config is not set".into())
+      })?
+      .to_string();
+    let target =
+      current_target().ok_or_else(|| crate::Error::UpdateCheck("unsupported platform".into()))?;
+
+    let Some(manifest) = source.manifest().await? else {
+      return Ok(None);
+    };
+    let update = check(manifest, &self.package_info().version, &pubkey, &target)?;
+    if let Some(update) = &update {
+      self.emit(UPDATE_AVAILABLE_EVENT, update)?;
+    }
+    Ok(update)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+  };
+
+  use serde_json::json;
+
+  use super::{check, current_target, UpdateManifest, UpdateSource, UPDATE_AVAILABLE_EVENT};
+  use crate::{
+    test::{mock_builder, mock_context, noop_assets},
+    Manager,
+  };
+
+  fn fixture(name: &str) -> String {
+    std::fs::read_to_string(
+      PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("test/fixture/update")
+        .join(name),
+    )
+    .unwrap()
+  }
+
+  fn manifest(name: &str) -> UpdateManifest {
+    serde_json::from_str(&fixture(name)).unwrap()
+  }
+
+  #[test]
+  fn checks_manifest() {
+    let pubkey = fixture("key.pub");
+    let artifact = std::fs::read(
+      PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test/fixture/update/artifact.bin"),
+    )
+    .unwrap();
+
+    let update = check(
+      manifest("latest.json"),
+      &"1.0.0".parse().unwrap(),
+      &pubkey,
+      "linux-x86_64",
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(update.version, "1.1.0");
+    assert_eq!(update.current_version, "1.0.0");
+    assert_eq!(update.notes.as_deref(), Some("Bug fixes"));
+    assert_eq!(
+      update.url.as_str(),
+      "https://releases.tauri.app/1.1.0/linux-x86_64/artifact.bin"
+    );
+    assert!(update.verify(&artifact).is_ok());
+    assert!(update.verify(b"tampered artifact").is_err());
+
+    // same or newer versions are not updates
+    for current in ["1.1.0", "1.2.0-beta.1"] {
+      assert!(check(
+        manifest("latest.json"),
+        &current.parse().unwrap(),
+        &pubkey,
+        "linux-x86_64"
+      )
+      .unwrap()
+      .is_none());
+    }
+    // prereleases are older than their release
+    assert!(check(
+      manifest("latest.json"),
+      &"1.1.0-rc.1".parse().unwrap(),
+      &pubkey,
+      "linux-x86_64"
+    )
+    .unwrap()
+    .is_some());
+
+    assert!(check(
+      manifest("latest.json"),
+      &"1.0.0".parse().unwrap(),
+      &pubkey,
+      "linux-riscv64"
+    )
+    .is_err());
+  }
+
+  #[test]
+  fn rejects_bad_signatures() {
+    let pubkey = fixture("key.pub");
+
+    // signed with another key
+    assert!(matches!(
+      check(
+        manifest("bad-signature.json"),
+        &"1.0.0".parse().unwrap(),
+        &pubkey,
+        "darwin-aarch64"
+      ),
+      Err(crate::Error::UpdateCheck(e)) if e.contains("signature")
+    ));
+
+    let mut malformed = manifest("latest.json");
+    for update in malformed.platforms.values_mut() {
+      update.signature = "bm90IGEgc2lnbmF0dXJl".into();
+    }
+    assert!(matches!(
+      check(malformed, &"1.0.0".parse().unwrap(), &pubkey, "darwin-aarch64"),
+      Err(crate::Error::UpdateCheck(e)) if e.contains("signature")
+    ));
+  }
+
+  #[test]
+  fn emits_update_available() {
+    let mut context = mock_context(noop_assets());
+    context.package_info_mut().version = "1.0.0".parse().unwrap();
+    context
+      .config_mut()
+      .plugins
+      .0
+      .insert("updater".into(), json!({ "pubkey": fixture("key.pub") }));
+    let app = mock_builder().build(context).unwrap();
+
+    let payloads = Arc::new(Mutex::new(Vec::new()));
+    let payloads_ = payloads.clone();
+    app.listen_any(UPDATE_AVAILABLE_EVENT, move |event| {
+      payloads_
+        .lock()
+        .unwrap()
+        .push(serde_json::from_str::<serde_json::Value>(event.payload()).unwrap());
+    });
+
+    let update =
+      crate::async_runtime::block_on(app.handle().check_update(UpdateSource::custom(|| async {
+        Ok(Some(manifest("latest.json")))
+      })))
+      .unwrap()
+      .unwrap();
+    assert_eq!(Some(update.target.clone()), current_target());
+    let payloads = payloads.lock().unwrap();
+    assert_eq!(payloads.len(), 1);
+    assert_eq!(payloads[0]["version"], "1.1.0");
+    assert_eq!(payloads[0]["currentVersion"], "1.0.0");
+    assert_eq!(payloads[0]["url"], update.url.as_str());
+    assert!(payloads[0].get("pubkey").is_none());
+
+    let update = crate::async_runtime::block_on(
+      app
+        .handle()
+        .check_update(UpdateSource::custom(|| async { Ok(None) })),
+    )
+    .unwrap();
+    assert!(update.is_none());
+  }
+}
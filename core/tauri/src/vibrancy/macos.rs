@@ -10,71 +10,117 @@ use crate::window::{Effect, EffectState};
 use raw_window_handle::HasWindowHandle;
 use window_vibrancy::{NSVisualEffectMaterial, NSVisualEffectState};
 
-pub fn apply_effects(window: impl HasWindowHandle, effects: WindowEffectsConfig) {
+/// Identifies the effect view inserted behind the webview content, so it is updated in place.
+#[cfg(feature = "wry")]
+const WEBVIEW_EFFECT_VIEW_IDENTIFIER: &str = "TauriWebviewEffectView";
+
+/// The material of the first macOS effect of the list, with the effect state and radius.
+fn visual_effect(
+  effects: WindowEffectsConfig,
+) -> Option<(
+  NSVisualEffectMaterial,
+  Option<NSVisualEffectState>,
+  Option<f64>,
+)> {
   let WindowEffectsConfig {
     effects,
     radius,
     state,
     ..
   } = effects;
-  let effect = if let Some(effect) = effects.into_iter().find(|e| {
-    matches!(
-      e,
-      Effect::AppearanceBased
-        | Effect::Light
-        | Effect::Dark
-        | Effect::MediumLight
-        | Effect::UltraDark
-        | Effect::Titlebar
-        | Effect::Selection
-        | Effect::Menu
-        | Effect::Popover
-        | Effect::Sidebar
-        | Effect::HeaderView
-        | Effect::Sheet
-        | Effect::WindowBackground
-        | Effect::HudWindow
-        | Effect::FullScreenUI
-        | Effect::Tooltip
-        | Effect::ContentBackground
-        | Effect::UnderWindowBackground
-        | Effect::UnderPageBackground
-    )
-  }) {
-    effect
-  } else {
-    return;
+  let material = effects.into_iter().find_map(|e| match e {
+    Effect::AppearanceBased => Some(NSVisualEffectMaterial::AppearanceBased),
+    Effect::Light => Some(NSVisualEffectMaterial::Light),
+    Effect::Dark => Some(NSVisualEffectMaterial::Dark),
+    Effect::MediumLight => Some(NSVisualEffectMaterial::MediumLight),
+    Effect::UltraDark => Some(NSVisualEffectMaterial::UltraDark),
+    Effect::Titlebar => Some(NSVisualEffectMaterial::Titlebar),
+    Effect::Selection => Some(NSVisualEffectMaterial::Selection),
+    Effect::Menu => Some(NSVisualEffectMaterial::Menu),
+    Effect::Popover => Some(NSVisualEffectMaterial::Popover),
+    Effect::Sidebar => Some(NSVisualEffectMaterial::Sidebar),
+    Effect::HeaderView => Some(NSVisualEffectMaterial::HeaderView),
+    Effect::Sheet => Some(NSVisualEffectMaterial::Sheet),
+    Effect::WindowBackground => Some(NSVisualEffectMaterial::WindowBackground),
+    Effect::HudWindow => Some(NSVisualEffectMaterial::HudWindow),
+    Effect::FullScreenUI => Some(NSVisualEffectMaterial::FullScreenUI),
+    Effect::Tooltip => Some(NSVisualEffectMaterial::Tooltip),
+    Effect::ContentBackground => Some(NSVisualEffectMaterial::ContentBackground),
+    Effect::UnderWindowBackground => Some(NSVisualEffectMaterial::UnderWindowBackground),
+    Effect::UnderPageBackground => Some(NSVisualEffectMaterial::UnderPageBackground),
+    _ => None,
+  })?;
+  let state = state.map(|s| match s {
+    EffectState::FollowsWindowActiveState => NSVisualEffectState::FollowsWindowActiveState,
+    EffectState::Active => NSVisualEffectState::Active,
+    EffectState::Inactive => NSVisualEffectState::Inactive,
+  });
+  Some((material, state, radius))
+}
+
+pub fn apply_effects(window: impl HasWindowHandle, effects: WindowEffectsConfig) {
+  if let Some((material, state, radius)) = visual_effect(effects) {
+    window_vibrancy::apply_vibrancy(window, material, state, radius);
+  }
+}
+
+/// Adds, updates or removes the `NSVisualEffectView` behind the content of the WKWebView.
+///
+/// The effect view is the bottom subview of the WKWebView so it follows the webview bounds,
+/// and it is reused when the effects change so the webview never renders without a background.
+#[cfg(feature = "wry")]
+pub fn apply_webview_effects(webview: cocoa::base::id, effects: Option<WindowEffectsConfig>) {
+  use cocoa::{
+    appkit::{NSView, NSViewHeightSizable, NSViewWidthSizable},
+    base::{id, nil, BOOL, NO},
+    foundation::NSString,
   };
+  use objc::{class, msg_send, sel, sel_impl};
 
-  window_vibrancy::apply_vibrancy(
-    window,
-    match effect {
-      Effect::AppearanceBased => NSVisualEffectMaterial::AppearanceBased,
-      Effect::Light => NSVisualEffectMaterial::Light,
-      Effect::Dark => NSVisualEffectMaterial::Dark,
-      Effect::MediumLight => NSVisualEffectMaterial::MediumLight,
-      Effect::UltraDark => NSVisualEffectMaterial::UltraDark,
-      Effect::Titlebar => NSVisualEffectMaterial::Titlebar,
-      Effect::Selection => NSVisualEffectMaterial::Selection,
-      Effect::Menu => NSVisualEffectMaterial::Menu,
-      Effect::Popover => NSVisualEffectMaterial::Popover,
-      Effect::Sidebar => NSVisualEffectMaterial::Sidebar,
-      Effect::HeaderView => NSVisualEffectMaterial::HeaderView,
-      Effect::Sheet => NSVisualEffectMaterial::Sheet,
-      Effect::WindowBackground => NSVisualEffectMaterial::WindowBackground,
-      Effect::HudWindow => NSVisualEffectMaterial::HudWindow,
-      Effect::FullScreenUI => NSVisualEffectMaterial::FullScreenUI,
-      Effect::Tooltip => NSVisualEffectMaterial::Tooltip,
-      Effect::ContentBackground => NSVisualEffectMaterial::ContentBackground,
-      Effect::UnderWindowBackground => NSVisualEffectMaterial::UnderWindowBackground,
-      Effect::UnderPageBackground => NSVisualEffectMaterial::UnderPageBackground,
-      _ => unreachable!(),
-    },
-    state.map(|s| match s {
-      EffectState::FollowsWindowActiveState => NSVisualEffectState::FollowsWindowActiveState,
-      EffectState::Active => NSVisualEffectState::Active,
-      EffectState::Inactive => NSVisualEffectState::Inactive,
-    }),
-    radius,
-  );
+  // NSVisualEffectBlendingModeBehindWindow
+  const BLENDING_MODE_BEHIND_WINDOW: i64 = 0;
+  // NSWindowBelow
+  const ORDERING_BELOW: i64 = -1;
+
+  unsafe {
+    let identifier = NSString::alloc(nil).init_str(WEBVIEW_EFFECT_VIEW_IDENTIFIER);
+    let _: id = msg_send![identifier, autorelease];
+    let subviews: id = msg_send![webview, subviews];
+    let count: usize = msg_send![subviews, count];
+    let mut effect_view: id = nil;
+    for i in 0..count {
+      let view: id = msg_send![subviews, objectAtIndex: i];
+      let view_identifier: id = msg_send![view, identifier];
+      let is_effect_view: BOOL = msg_send![view_identifier, isEqualToString: identifier];
+      if view_identifier != nil && is_effect_view != NO {
+        effect_view = view;
+        break;
+      }
+    }
+
+    let Some((material, state, radius)) = effects.and_then(visual_effect) else {
+      if effect_view != nil {
+        let _: () = msg_send![effect_view, removeFromSuperview];
+      }
+      return;
+    };
+
+    if effect_view == nil {
+      effect_view = msg_send![class!(NSVisualEffectView), alloc];
+      effect_view = msg_send![effect_view, initWithFrame: NSView::bounds(webview)];
+      let _: () = msg_send![effect_view, setIdentifier: identifier];
+      let _: () = msg_send![effect_view, setBlendingMode: BLENDING_MODE_BEHIND_WINDOW];
+      effect_view.setAutoresizingMask_(NSViewWidthSizable | NSViewHeightSizable);
+      let _: () =
+        msg_send![webview, addSubview: effect_view positioned: ORDERING_BELOW relativeTo: nil];
+      let _: () = msg_send![effect_view, release];
+    }
+
+    let _: () = msg_send![effect_view, setMaterial: material as i64];
+    let _: () = msg_send![
+      effect_view,
+      setState: state.unwrap_or(NSVisualEffectState::FollowsWindowActiveState) as i64
+    ];
+    let _: () = msg_send![effect_view, setCornerRadius: radius.unwrap_or(0.0)];
+  }
 }
@@ -6,7 +6,7 @@
 
 use tauri_utils::config::WindowEffectsConfig;
 
-use crate::{Runtime, Window};
+use crate::{Runtime, Webview, Window};
 
 #[cfg(target_os = "macos")]
 mod macos;
@@ -28,3 +28,27 @@ pub fn set_window_effects<R: Runtime>(
   }
   Ok(())
 }
+
+/// Applies the effects behind the webview region, see [`Webview::set_effects`].
+pub fn set_webview_effects<R: Runtime>(
+  webview: &Webview<R>,
+  effects: Option<WindowEffectsConfig>,
+) -> crate::Result<()> {
+  #[cfg(windows)]
+  {
+    // Mica and Acrylic are window-level DWM attributes, there's no per-region API
+    webview.window().set_effects(effects)
+  }
+  #[cfg(all(target_os = "macos", feature = "wry"))]
+  {
+    webview.with_webview(move |webview| macos::apply_webview_effects(webview.inner(), effects))
+  }
+  #[cfg(not(any(windows, all(target_os = "macos", feature = "wry"))))]
+  {
+    if effects.is_some() {
+      Err(crate::Error::Runtime(tauri_runtime::Error::Unsupported))
+    } else {
+      Ok(())
+    }
+  }
+}
@@ -0,0 +1,186 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Forwarding the webview console messages, see [`super::WebviewBuilder::on_console_message`].
+
+use serde::Deserialize;
+use serialize_to_javascript::{default_template, DefaultTemplate, Template};
+
+use crate::{Runtime, Webview};
+
+/// The internal command the console script sends the messages with.
+pub(crate) const CONSOLE_MESSAGE_COMMAND: &str = "__TAURI_CONSOLE_MESSAGE__";
+/// Messages longer than this number of characters are truncated.
+pub(crate) const MAX_CONSOLE_MESSAGE_LENGTH: usize = 16 * 1024;
+
+pub(crate) type ConsoleMessageHandler<R> = dyn Fn(Webview<R>, ConsoleMessage) + Send + Sync;
+
+/// The level of a [`ConsoleMessage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum ConsoleMessageLevel {
+  /// `console.debug` and `console.trace`.
+  Debug,
+  /// `console.log`.
+  Log,
+  /// `console.info`.
+  Info,
+  /// `console.warn`.
+  Warn,
+  /// `console.error`.
+  Error,
+}
+
+/// A message logged to the webview console.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ConsoleMessage {
+  /// The console method level.
+  pub level: ConsoleMessageLevel,
+  /// The logged values, separated by spaces.
+  pub message: String,
+  /// The URL of the script that logged the message, if known.
+  pub source_id: Option<String>,
+  /// The line of the script that logged the message, if known.
+  pub line: Option<u32>,
+  /// Whether the message was truncated because it exceeded the size limit.
+  #[serde(default)]
+  pub truncated: bool,
+}
+
+impl ConsoleMessage {
+  /// Truncates the message to [`MAX_CONSOLE_MESSAGE_LENGTH`] characters.
+  pub(crate) fn truncate(mut self) -> Self {
+    if let Some((index, _)) = self.message.char_indices().nth(MAX_CONSOLE_MESSAGE_LENGTH) {
+      self.message.truncate(index);
+      self.truncated = true;
+    }
+    self
+  }
+}
+
+#[derive(Template)]
+#[default_template("./scripts/console-message.js")]
+struct ConsoleMessageScript<'a> {
+  command: &'a str,
+  max_length: usize,
+}
+
+/// The script forwarding the console messages to [`CONSOLE_MESSAGE_COMMAND`].
+///
+/// None of the webviews expose the console messages natively in this version
+/// (the webkit2gtk console signal is only available to web extensions), so the console methods are wrapped on all platforms.
+pub(crate) fn script() -> String {
+  ConsoleMessageScript {
+    command: CONSOLE_MESSAGE_COMMAND,
+    max_length: MAX_CONSOLE_MESSAGE_LENGTH,
+  }
+  .render_default(&Default::default())
+  .unwrap()
+  .into_string()
+}
+
+/// Handles a [`CONSOLE_MESSAGE_COMMAND`] request.
+pub(crate) fn handle<R: Runtime>(
+  webview: &Webview<R>,
+  body: crate::ipc::InvokeBody,
+) -> Result<(), String> {
+  let handler = webview
+    .console_message_handler
+    .lock()
+    .unwrap()
+    .clone()
+    .ok_or("the console message hook is not installed")?;
+  let message =
+    serde_json::from_value::<ConsoleMessage>(body.into_json()).map_err(|e| e.to_string())?;
+  handler(webview.clone(), message.truncate());
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, Mutex};
+
+  use serde_json::json;
+
+  use super::{ConsoleMessageLevel, CONSOLE_MESSAGE_COMMAND, MAX_CONSOLE_MESSAGE_LENGTH};
+  use crate::{
+    ipc::{CallbackFn, InvokeBody},
+    test::{get_ipc_response, mock_app},
+    webview::InvokeRequest,
+    WebviewWindowBuilder,
+  };
+
+  fn send(
+    webview: &crate::WebviewWindow<crate::test::MockRuntime>,
+    body: serde_json::Value,
+  ) -> bool {
+    get_ipc_response(
+      webview,
+      InvokeRequest {
+        cmd: CONSOLE_MESSAGE_COMMAND.into(),
+        callback: CallbackFn(0),
+        error: CallbackFn(1),
+        body: InvokeBody::Json(body),
+        headers: Default::default(),
+      },
+    )
+    .is_ok()
+  }
+
+  #[test]
+  fn forwards_messages() {
+    let app = mock_app();
+    let messages = Arc::new(Mutex::new(Vec::new()));
+    let messages_ = messages.clone();
+    let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .on_console_message(move |webview, message| {
+        messages_
+          .lock()
+          .unwrap()
+          .push((webview.label().to_string(), message));
+      })
+      .build()
+      .unwrap();
+
+    assert!(send(
+      &webview,
+      json!({ "level": "error", "message": "failed", "sourceId": "tauri://localhost/main.js", "line": 10 })
+    ));
+    assert!(send(
+      &webview,
+      json!({ "level": "log", "message": "a".repeat(MAX_CONSOLE_MESSAGE_LENGTH + 1) })
+    ));
+
+    let messages = messages.lock().unwrap();
+    let (label, error) = &messages[0];
+    assert_eq!(label, "main");
+    assert_eq!(error.level, ConsoleMessageLevel::Error);
+    assert_eq!(error.message, "failed");
+    assert_eq!(
+      error.source_id.as_deref(),
+      Some("tauri://localhost/main.js")
+    );
+    assert_eq!(error.line, Some(10));
+    assert!(!error.truncated);
+
+    let (_, log) = &messages[1];
+    assert_eq!(log.message.len(), MAX_CONSOLE_MESSAGE_LENGTH);
+    assert!(log.truncated);
+  }
+
+  #[test]
+  fn rejects_without_hook() {
+    let app = mock_app();
+    let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    assert!(!send(
+      &webview,
+      json!({ "level": "log", "message": "ignored" })
+    ));
+  }
+}
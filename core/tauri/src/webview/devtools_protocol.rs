@@ -0,0 +1,121 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Access to the Chrome DevTools Protocol of a webview, see [`Webview::devtools_protocol`].
+
+use serde_json::Value as JsonValue;
+use tauri_runtime::WebviewDispatch;
+
+use super::Webview;
+use crate::Runtime;
+
+/// Calls the Chrome DevTools Protocol methods of a webview and listens to its events,
+/// created with [`Webview::devtools_protocol`].
+///
+/// ## Platform-specific
+///
+/// - **Linux / macOS / iOS / Android:** Unsupported, the methods fail with [`tauri_runtime::Error::Unsupported`].
+pub struct DevtoolsProtocol<R: Runtime> {
+  webview: Webview<R>,
+}
+
+impl<R: Runtime> Clone for DevtoolsProtocol<R> {
+  fn clone(&self) -> Self {
+    Self {
+      webview: self.webview.clone(),
+    }
+  }
+}
+
+impl<R: Runtime> Webview<R> {
+  /// Accesses the Chrome DevTools Protocol of the webview.
+  ///
+  /// To attach external tools like Playwright or Puppeteer instead, see [`crate::Builder::remote_debugging_port`].
+  pub fn devtools_protocol(&self) -> DevtoolsProtocol<R> {
+    DevtoolsProtocol {
+      webview: self.clone(),
+    }
+  }
+}
+
+impl<R: Runtime> DevtoolsProtocol<R> {
+  /// The webview the protocol belongs to.
+  pub fn webview(&self) -> &Webview<R> {
+    &self.webview
+  }
+
+  /// Calls a method, e.g. `Page.captureScreenshot`, and returns its JSON result.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// tauri::Builder::default()
+  ///   .setup(|app| {
+  ///     # use tauri::Manager;
+  ///     let webview = app.get_webview_window("main").unwrap();
+  ///     tauri::async_runtime::spawn(async move {
+  ///       let protocol = webview.devtools_protocol();
+  ///       let result = protocol
+  ///         .call_method("Runtime.evaluate", serde_json::json!({ "expression": "1 + 1" }))
+  ///         .await;
+  ///       println!("{result:?}");
+  ///     });
+  ///     Ok(())
+  ///   });
+  /// ```
+  pub async fn call_method(&self, method: &str, params: JsonValue) -> crate::Result<JsonValue> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    self
+      .webview
+      .webview
+      .dispatcher
+      .call_devtools_protocol_method(method.into(), params, move |result| {
+        let _ = tx.send(result);
+      })?;
+    rx.await
+      .map_err(|_| crate::Error::Runtime(tauri_runtime::Error::FailedToReceiveMessage))?
+      .map_err(Into::into)
+  }
+
+  /// Calls `handler` with the parameters of every event with the given name, e.g. `Network.requestWillBeSent`,
+  /// for as long as the webview lives.
+  ///
+  /// The events of a domain are only sent once it is enabled, e.g. with the `Network.enable` method.
+  pub fn subscribe<F: Fn(JsonValue) + Send + 'static>(
+    &self,
+    event: &str,
+    handler: F,
+  ) -> crate::Result<()> {
+    self
+      .webview
+      .webview
+      .dispatcher
+      .subscribe_devtools_protocol_event(event.into(), handler)
+      .map_err(Into::into)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use crate::{test::mock_app, WebviewWindowBuilder};
+
+  #[test]
+  fn unsupported_by_mock_runtime() {
+    let app = mock_app();
+    let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+
+    let protocol = webview.devtools_protocol();
+    assert!(matches!(
+      crate::async_runtime::block_on(protocol.call_method("Browser.getVersion", json!({}))),
+      Err(crate::Error::Runtime(tauri_runtime::Error::Unsupported))
+    ));
+    assert!(protocol
+      .subscribe("Network.requestWillBeSent", |_| {})
+      .is_ok());
+  }
+}
@@ -0,0 +1,90 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+/// The position of an event in its stream, read by the event system script to deliver the events in order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct EventSequence {
+  /// The stream of the event: its source, name and target.
+  pub(crate) key: String,
+  /// Incremented on each main frame navigation.
+  pub(crate) epoch: u64,
+  /// The index of the event in the stream for the current epoch.
+  pub(crate) index: u64,
+}
+
+/// Numbers the events delivered to a webview so the frontend observes them in the order they were emitted.
+///
+/// The event scripts are evaluated in the order the runtime receives them, which is not the emit order
+/// when the events come from several threads: the main thread evaluates its scripts right away while
+/// the other threads queue them on the event loop. The events are numbered per source, event name and target,
+/// and the event system script holds back an event until the previous events of its stream are delivered.
+///
+/// The numbering restarts on each main frame navigation, with a new epoch.
+/// A page starts each stream at the first event it receives, since the events emitted while it loads
+/// can be evaluated before it is created, and delivers the events of an older epoch as they arrive.
+#[derive(Default)]
+pub(crate) struct EventSequences {
+  epoch: u64,
+  next: HashMap<(Option<String>, String, String), u64>,
+}
+
+impl EventSequences {
+  /// Called when the main frame starts a navigation, as the new page starts with empty streams.
+  pub(crate) fn on_navigation(&mut self) {
+    self.epoch += 1;
+    self.next.clear();
+  }
+
+  /// Takes the next position in the stream of the event.
+  pub(crate) fn next(
+    &mut self,
+    source: Option<&str>,
+    event: &str,
+    serialized_target: &str,
+  ) -> crate::Result<EventSequence> {
+    let key = (
+      source.map(Into::into),
+      event.to_string(),
+      serialized_target.to_string(),
+    );
+    let sequence_key = serde_json::to_string(&key)?;
+    let next = self.next.entry(key).or_default();
+    let index = *next;
+    *next += 1;
+    Ok(EventSequence {
+      key: sequence_key,
+      epoch: self.epoch,
+      index,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::EventSequences;
+
+  #[test]
+  fn streams_are_numbered_separately() {
+    let mut sequences = EventSequences::default();
+    let target = r#"{"kind":"Any"}"#;
+
+    let first = sequences.next(None, "progress", target).unwrap();
+    assert_eq!((first.epoch, first.index), (0, 0));
+    assert_eq!(sequences.next(None, "progress", target).unwrap().index, 1);
+    assert_eq!(sequences.next(None, "done", target).unwrap().index, 0);
+
+    let from_webview = sequences.next(Some("main"), "progress", target).unwrap();
+    assert_eq!(from_webview.index, 0);
+    assert_ne!(from_webview.key, first.key);
+
+    sequences.on_navigation();
+    let after_navigation = sequences.next(None, "progress", target).unwrap();
+    assert_eq!((after_navigation.epoch, after_navigation.index), (1, 0));
+    assert_eq!(after_navigation.key, first.key);
+  }
+}
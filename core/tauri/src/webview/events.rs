@@ -0,0 +1,222 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Subscriptions to the webview events, see [`super::Webview::subscribe_events`].
+
+use std::{
+  collections::VecDeque,
+  pin::Pin,
+  sync::{Arc, Mutex},
+  task::{Context, Poll, Waker},
+};
+
+use futures_util::Stream;
+
+use super::Webview;
+use crate::{manager::AppManager, sealed::ManagerBase, Runtime, WebviewEvent};
+
+/// The number of events buffered by a [`Webview::subscribe_events`] stream.
+///
+/// When the stream is not polled fast enough, the oldest events are dropped to make room for the new ones.
+pub const WEBVIEW_EVENT_STREAM_CAPACITY: usize = 64;
+
+#[derive(Default)]
+struct Buffer {
+  events: VecDeque<WebviewEvent>,
+  waker: Option<Waker>,
+  /// Whether the webview was destroyed, ending the stream once the buffer is drained.
+  closed: bool,
+}
+
+/// A stream of the events of a webview, which stops listening to the events when dropped.
+struct WebviewEventStream<R: Runtime> {
+  manager: Arc<AppManager<R>>,
+  label: String,
+  listener: u32,
+  buffer: Arc<Mutex<Buffer>>,
+}
+
+impl<R: Runtime> Stream for WebviewEventStream<R> {
+  type Item = WebviewEvent;
+
+  fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+    let mut buffer = self.buffer.lock().unwrap();
+    if let Some(event) = buffer.events.pop_front() {
+      Poll::Ready(Some(event))
+    } else if buffer.closed {
+      Poll::Ready(None)
+    } else {
+      buffer.waker.replace(cx.waker().clone());
+      Poll::Pending
+    }
+  }
+}
+
+impl<R: Runtime> Drop for WebviewEventStream<R> {
+  fn drop(&mut self) {
+    self
+      .manager
+      .webview
+      .event_listeners
+      .remove(&self.label, self.listener);
+  }
+}
+
+impl<R: Runtime> Webview<R> {
+  /// Subscribes to the events of this webview.
+  ///
+  /// Every subscriber receives every event. The stream buffers up to [`WEBVIEW_EVENT_STREAM_CAPACITY`] events,
+  /// dropping the oldest ones when it is full, and ends after [`WebviewEvent::Destroyed`].
+  /// Dropping the stream stops the subscription.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use futures_util::StreamExt;
+  /// use tauri::{Manager, WebviewEvent};
+  ///
+  /// tauri::Builder::default()
+  ///   .setup(|app| {
+  ///     let webview = app.get_webview("main").unwrap();
+  ///     let mut events = webview.subscribe_events();
+  ///     tauri::async_runtime::spawn(async move {
+  ///       while let Some(event) = events.next().await {
+  ///         if let WebviewEvent::PageLoad { url, .. } = event {
+  ///           println!("loading {url}");
+  ///         }
+  ///       }
+  ///     });
+  ///     Ok(())
+  ///   });
+  /// ```
+  pub fn subscribe_events(&self) -> impl Stream<Item = WebviewEvent> + Send + Unpin + 'static {
+    let buffer = Arc::new(Mutex::new(Buffer::default()));
+    let buffer_ = buffer.clone();
+    let manager = self.manager_owned();
+    let listener = manager.webview.event_listeners.add(
+      self.label(),
+      Arc::new(move |event| {
+        let mut buffer = buffer_.lock().unwrap();
+        if buffer.events.len() == WEBVIEW_EVENT_STREAM_CAPACITY {
+          buffer.events.pop_front();
+        }
+        buffer.events.push_back(event.clone());
+        if let WebviewEvent::Destroyed = event {
+          buffer.closed = true;
+        }
+        if let Some(waker) = buffer.waker.take() {
+          waker.wake();
+        }
+      }),
+    );
+
+    WebviewEventStream {
+      manager,
+      label: self.label().into(),
+      listener,
+      buffer,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use futures_util::StreamExt;
+
+  use super::WEBVIEW_EVENT_STREAM_CAPACITY;
+  use crate::{
+    sealed::ManagerBase, test::mock_app, webview::WebviewBuilder, window::WindowBuilder,
+    LogicalPosition, LogicalSize, PhysicalSize, WebviewEvent, WebviewWindowBuilder,
+  };
+
+  #[test]
+  fn subscribers_receive_every_event() {
+    let app = mock_app();
+    let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+
+    let mut first = webview.webview.subscribe_events();
+    let mut second = webview.webview.subscribe_events();
+    webview.webview.webview.dispatcher.emit_webview_event(
+      crate::runtime::window::WebviewEvent::MediaPlaybackChanged(true),
+    );
+    webview.webview.set_focus().unwrap();
+
+    for stream in [&mut first, &mut second] {
+      let events = crate::async_runtime::block_on(stream.take(2).collect::<Vec<_>>());
+      assert!(matches!(
+        events[0],
+        WebviewEvent::MediaPlaybackChanged(true)
+      ));
+      assert!(matches!(events[1], WebviewEvent::Focused(true)));
+    }
+
+    // dropped subscriptions stop listening
+    drop(first);
+    assert_eq!(
+      webview
+        .manager()
+        .webview
+        .event_listeners
+        .listener_count(webview.label()),
+      1
+    );
+
+    webview.webview.webview.dispatcher.emit_webview_event(
+      crate::runtime::window::WebviewEvent::MediaPlaybackChanged(false),
+    );
+    let event = crate::async_runtime::block_on(second.next());
+    assert!(matches!(
+      event,
+      Some(WebviewEvent::MediaPlaybackChanged(false))
+    ));
+  }
+
+  #[test]
+  fn full_stream_drops_oldest_events() {
+    let app = mock_app();
+    let window = WindowBuilder::new(&app, "main").build().unwrap();
+    let webview = window
+      .add_child(
+        WebviewBuilder::new("child", Default::default()),
+        LogicalPosition::new(0, 0),
+        LogicalSize::new(100, 100),
+      )
+      .unwrap();
+    let events = webview.subscribe_events();
+
+    for width in 0..WEBVIEW_EVENT_STREAM_CAPACITY as u32 + 10 {
+      webview.set_size(PhysicalSize::new(width + 1, 100)).unwrap();
+    }
+    webview.close().unwrap();
+
+    let events = crate::async_runtime::block_on(events.collect::<Vec<_>>());
+    assert_eq!(events.len(), WEBVIEW_EVENT_STREAM_CAPACITY);
+    assert!(matches!(
+      events[0],
+      WebviewEvent::Resized(size) if size.width == 12
+    ));
+    assert!(matches!(events.last(), Some(WebviewEvent::Destroyed)));
+  }
+
+  #[test]
+  fn builder_event_handler() {
+    let app = mock_app();
+    let (tx, rx) = std::sync::mpsc::channel();
+    let mut webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .on_webview_event(move |event| {
+        if let WebviewEvent::Navigation { url } = event {
+          tx.send(url.to_string()).unwrap();
+        }
+      })
+      .build()
+      .unwrap();
+
+    webview
+      .navigate("https://tauri.app".parse().unwrap())
+      .unwrap();
+    assert_eq!(rx.try_recv().unwrap(), "https://tauri.app/");
+  }
+}
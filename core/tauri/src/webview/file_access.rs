@@ -0,0 +1,435 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Loading local files in a webview, see [`super::WebviewBuilder::allow_file_access`].
+//!
+//! The webview does not get access to the file system. The `file://` URLs of the scope are served by the
+//! [`FILE_ACCESS_PROTOCOL`] at `<protocol origin>/<token>/<file URL path>`, where the token identifies the scope
+//! of the webview, so the relative URLs of the pages keep working and each of their requests is checked against the scope.
+
+use std::{
+  borrow::Cow,
+  collections::HashMap,
+  path::{Path, PathBuf},
+  sync::{Arc, Mutex},
+};
+
+use http::{header::CONTENT_TYPE, Request, Response, StatusCode};
+use serde::Serialize;
+use tauri_utils::mime_type::MimeType;
+use url::Url;
+
+use super::NavigationHandler;
+use crate::{
+  app::UriSchemeResponderFn, event::EventTarget, ipc::origin::protocol_origin, manager::AppManager,
+  Runtime,
+};
+
+/// The event emitted to a webview when it is denied a navigation to a `file://` URL outside of its [`FileAccessScope`],
+/// with a [`BlockedNavigation`] payload.
+pub const BLOCKED_NAVIGATION_EVENT: &str = "tauri://blocked-navigation";
+
+/// The custom protocol serving the files of the [`FileAccessScope`]s.
+pub(crate) const FILE_ACCESS_PROTOCOL: &str = "tauri-file";
+
+/// The payload of the [`BLOCKED_NAVIGATION_EVENT`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockedNavigation {
+  /// The URL the webview was denied.
+  pub url: Url,
+}
+
+/// The local directories a webview can load with `file://` URLs.
+///
+/// The directories are canonicalized when the webview is created, so they must exist,
+/// and the requested paths are canonicalized before they are checked, resolving symbolic links.
+///
+/// # Examples
+///
+/// ```
+/// use tauri::webview::FileAccessScope;
+///
+/// let scope = FileAccessScope::new()
+///   // the files of the directory and its subdirectories
+///   .allow_directory("/opt/manuals", true)
+///   // only the files directly in the directory
+///   .allow_directory("/opt/legacy/pages", false);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FileAccessScope {
+  directories: Vec<(PathBuf, bool)>,
+}
+
+impl FileAccessScope {
+  /// Creates a scope without directories, denying all `file://` URLs.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Allows the files of a directory, and the files of its subdirectories if `recursive` is `true`.
+  #[must_use]
+  pub fn allow_directory<P: AsRef<Path>>(mut self, path: P, recursive: bool) -> Self {
+    self
+      .directories
+      .push((path.as_ref().to_path_buf(), recursive));
+    self
+  }
+
+  /// The allowed directories, and whether their subdirectories are allowed.
+  pub fn directories(&self) -> &[(PathBuf, bool)] {
+    &self.directories
+  }
+
+  /// Canonicalizes the allowed directories.
+  pub(crate) fn canonicalize(self) -> crate::Result<Self> {
+    let directories = self
+      .directories
+      .into_iter()
+      .map(|(path, recursive)| Ok((path.canonicalize()?, recursive)))
+      .collect::<crate::Result<_>>()?;
+    Ok(Self { directories })
+  }
+
+  /// Whether the scope allows the file.
+  pub(crate) fn allows(&self, path: &Path) -> bool {
+    let Ok(path) = crate::path::canonicalize_lenient(path) else {
+      return false;
+    };
+    self.directories.iter().any(|(directory, recursive)| {
+      if *recursive {
+        path.starts_with(directory) && path != *directory
+      } else {
+        path.parent() == Some(directory.as_path())
+      }
+    })
+  }
+}
+
+/// The scopes of the webviews, keyed by the random token of their [`FILE_ACCESS_PROTOCOL`] URLs.
+#[derive(Default)]
+pub(crate) struct FileAccessScopes(Mutex<HashMap<String, (String, Arc<FileAccessScope>)>>);
+
+impl FileAccessScopes {
+  /// Registers the scope of a webview, returning its token.
+  pub(crate) fn insert(&self, webview_label: &str, scope: FileAccessScope) -> String {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes).expect("failed to generate the file access token");
+    let token = bytes.iter().map(|b| format!("{b:02x}")).collect::<String>();
+    self
+      .0
+      .lock()
+      .unwrap()
+      .insert(token.clone(), (webview_label.into(), Arc::new(scope)));
+    token
+  }
+
+  fn get(&self, token: &str) -> Option<Arc<FileAccessScope>> {
+    self
+      .0
+      .lock()
+      .unwrap()
+      .get(token)
+      .map(|(_, scope)| scope.clone())
+  }
+
+  /// Removes the scope of a destroyed webview.
+  pub(crate) fn remove_webview(&self, webview_label: &str) {
+    self
+      .0
+      .lock()
+      .unwrap()
+      .retain(|_, (label, _)| label != webview_label);
+  }
+}
+
+/// The [`FILE_ACCESS_PROTOCOL`] URL serving the file of the `file://` URL.
+pub(crate) fn protocol_url(token: &str, file_url: &Url) -> Url {
+  let mut url = protocol_origin(FILE_ACCESS_PROTOCOL).expect("invalid file access protocol origin");
+  url.set_path(&format!("/{token}{}", file_url.path()));
+  url.set_query(file_url.query());
+  url.set_fragment(file_url.fragment());
+  url
+}
+
+/// The token and the file of a [`FILE_ACCESS_PROTOCOL`] URL path.
+fn protocol_path(path: &str) -> Option<(&str, PathBuf)> {
+  let (token, path) = path.strip_prefix('/')?.split_once('/')?;
+  let path = Url::parse(&format!("file:///{path}"))
+    .ok()?
+    .to_file_path()
+    .ok()?;
+  Some((token, path))
+}
+
+/// Whether the URL is served by the [`FILE_ACCESS_PROTOCOL`].
+fn is_protocol_url(url: &Url) -> bool {
+  protocol_origin(FILE_ACCESS_PROTOCOL).is_some_and(|origin| {
+    url.scheme() == origin.scheme()
+      && url.host() == origin.host()
+      && url.port_or_known_default() == origin.port_or_known_default()
+  })
+}
+
+/// Wraps the navigation handler so the `file://` URLs of the scope are loaded from the [`FILE_ACCESS_PROTOCOL`],
+/// and the `file://` URLs outside of the scope are denied and reported with the [`BLOCKED_NAVIGATION_EVENT`].
+pub(crate) fn wrap<R: Runtime>(
+  token: String,
+  navigation_handler: Option<Box<NavigationHandler>>,
+  manager: Arc<AppManager<R>>,
+  label: String,
+) -> Box<NavigationHandler> {
+  Box::new(move |url| {
+    let blocked = || {
+      let _ = manager.emit_to(
+        EventTarget::webview(&label),
+        BLOCKED_NAVIGATION_EVENT,
+        BlockedNavigation { url: url.clone() },
+      );
+      false
+    };
+    let scope = manager.webview.file_access.get(&token);
+    let allows = |path: &Path| scope.as_ref().is_some_and(|scope| scope.allows(path));
+
+    if url.scheme() == "file" {
+      // e.g. a `file://` URL with a host on Unix
+      let Ok(path) = url.to_file_path() else {
+        return blocked();
+      };
+      if !allows(&path) {
+        return blocked();
+      }
+      // loaded again from the protocol, which checks the requests of the page
+      if let Some(mut webview) = manager.get_webview(&label) {
+        let url = protocol_url(&token, url);
+        crate::async_runtime::spawn(async move {
+          let _ = webview.navigate(url);
+        });
+      }
+      return false;
+    }
+
+    if is_protocol_url(url) {
+      match protocol_path(url.path()) {
+        Some((url_token, path)) if url_token == token && allows(&path) => (),
+        _ => return blocked(),
+      }
+    }
+
+    navigation_handler
+      .as_ref()
+      .map_or(true, |navigation_handler| navigation_handler(url))
+  })
+}
+
+/// The [`FILE_ACCESS_PROTOCOL`] handler, serving the files of the scope of the token of the URL.
+///
+/// It only depends on the URL, so it can be shared by the webviews of a web context.
+pub(crate) fn protocol<R: Runtime>(
+  manager: Arc<AppManager<R>>,
+) -> impl Fn(Request<Vec<u8>>, UriSchemeResponderFn) + Send + Sync {
+  move |request, responder| {
+    let response = |status: StatusCode| Response::builder().status(status);
+    let file = protocol_path(request.uri().path()).and_then(|(token, path)| {
+      manager
+        .webview
+        .file_access
+        .get(token)
+        .filter(|scope| scope.allows(&path))
+        .map(|_| path)
+    });
+    let response = match file {
+      None => response(StatusCode::FORBIDDEN).body(Cow::Borrowed(&[][..])),
+      Some(path) => match std::fs::read(&path) {
+        Ok(content) => response(StatusCode::OK)
+          .header(
+            CONTENT_TYPE,
+            MimeType::parse(&content, &path.to_string_lossy()),
+          )
+          .body(content.into()),
+        Err(_) => response(StatusCode::NOT_FOUND).body(Cow::Borrowed(&[][..])),
+      },
+    };
+    responder(response.unwrap());
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+  };
+
+  use url::Url;
+
+  use super::{protocol, protocol_path, protocol_url, FileAccessScope, BLOCKED_NAVIGATION_EVENT};
+  use crate::{
+    sealed::ManagerBase, test::mock_app, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder,
+  };
+
+  fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("tauri-file-access-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("pages").join("nested")).unwrap();
+    std::fs::create_dir_all(dir.join("private")).unwrap();
+    dir.canonicalize().unwrap()
+  }
+
+  fn file_url(path: PathBuf) -> Url {
+    Url::from_file_path(path).unwrap()
+  }
+
+  #[test]
+  fn recursive_directories() {
+    let dir = temp_dir("recursive");
+    let scope = FileAccessScope::new()
+      .allow_directory(dir.join("pages"), true)
+      .canonicalize()
+      .unwrap();
+
+    assert!(scope.allows(&dir.join("pages").join("index.html")));
+    assert!(scope.allows(&dir.join("pages").join("nested").join("index.html")));
+    assert!(!scope.allows(&dir.join("private").join("key")));
+    assert!(!scope.allows(&dir.join("pages")));
+    assert!(!scope.allows(&dir.join("pages").join("..").join("private").join("key")));
+    assert!(!FileAccessScope::new().allows(&dir.join("pages").join("index.html")));
+  }
+
+  #[test]
+  fn non_recursive_directories() {
+    let dir = temp_dir("non-recursive");
+    let scope = FileAccessScope::new()
+      .allow_directory(dir.join("pages"), false)
+      .canonicalize()
+      .unwrap();
+
+    assert!(scope.allows(&dir.join("pages").join("index.html")));
+    assert!(!scope.allows(&dir.join("pages").join("nested").join("index.html")));
+  }
+
+  #[test]
+  fn missing_directories_are_rejected() {
+    let dir = temp_dir("missing");
+    assert!(FileAccessScope::new()
+      .allow_directory(dir.join("missing"), true)
+      .canonicalize()
+      .is_err());
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn symbolic_links_are_resolved() {
+    let dir = temp_dir("symlinks");
+    std::os::unix::fs::symlink(dir.join("private"), dir.join("pages").join("link")).unwrap();
+    let scope = FileAccessScope::new()
+      .allow_directory(dir.join("pages"), true)
+      .canonicalize()
+      .unwrap();
+
+    assert!(!scope.allows(&dir.join("pages").join("link").join("key")));
+  }
+
+  #[test]
+  fn protocol_urls_map_file_urls() {
+    let dir = temp_dir("urls");
+    let path = dir.join("pages").join("a page.html");
+    let mut file = file_url(path.clone());
+    file.set_query(Some("q=1"));
+    file.set_fragment(Some("section"));
+
+    let url = protocol_url("token", &file);
+    assert_eq!(url.query(), Some("q=1"));
+    assert_eq!(url.fragment(), Some("section"));
+    assert_eq!(protocol_path(url.path()), Some(("token", path)));
+    assert_eq!(protocol_path("/token"), None);
+  }
+
+  #[cfg(windows)]
+  #[test]
+  fn windows_paths() {
+    let dir = temp_dir("windows");
+    let path = dir.join("pages").join("index.html");
+    let url = protocol_url("token", &file_url(path.clone()));
+    assert_eq!(protocol_path(url.path()), Some(("token", path)));
+  }
+
+  fn wait_for_url(webview: &WebviewWindow<crate::test::MockRuntime>, url: &Url) -> bool {
+    let start = Instant::now();
+    while start.elapsed() < Duration::from_secs(2) {
+      if webview.url().unwrap() == *url {
+        return true;
+      }
+      std::thread::sleep(Duration::from_millis(5));
+    }
+    false
+  }
+
+  #[test]
+  fn serves_the_scope_through_the_protocol() {
+    let dir = temp_dir("navigation");
+    std::fs::write(dir.join("pages").join("index.html"), "<p>manual</p>").unwrap();
+    std::fs::write(dir.join("private").join("key"), "secret").unwrap();
+    let app = mock_app();
+    let blocked = Arc::new(Mutex::new(Vec::new()));
+    let blocked_ = blocked.clone();
+    app.listen_any(BLOCKED_NAVIGATION_EVENT, move |event| {
+      blocked_.lock().unwrap().push(event.payload().to_string());
+    });
+
+    let index = file_url(dir.join("pages").join("index.html"));
+    let mut webview = WebviewWindowBuilder::new(&app, "main", WebviewUrl::External(index))
+      .allow_file_access(FileAccessScope::new().allow_directory(dir.join("pages"), true))
+      .build()
+      .unwrap();
+    // the initial URL is loaded from the protocol
+    let initial = webview.url().unwrap();
+    assert_ne!(initial.scheme(), "file");
+
+    let request = |url: &Url| {
+      let (tx, rx) = std::sync::mpsc::channel();
+      protocol(app.manager_owned())(
+        http::Request::get(url.as_str()).body(Vec::new()).unwrap(),
+        Box::new(move |response| tx.send(response).unwrap()),
+      );
+      rx.recv().unwrap()
+    };
+    let response = request(&initial);
+    assert_eq!(response.status(), 200);
+    assert_eq!(&**response.body(), b"<p>manual</p>");
+    // the requests of the page outside of the scope are denied
+    let private = initial.join("../private/key").unwrap();
+    assert_eq!(request(&private).status(), 403);
+    assert_eq!(
+      request(&initial.join("missing.html").unwrap()).status(),
+      404
+    );
+
+    let nested = file_url(dir.join("pages").join("nested").join("index.html"));
+    webview.navigate(nested.clone()).unwrap();
+    let expected = initial.join("nested/index.html").unwrap();
+    assert!(wait_for_url(&webview, &expected));
+
+    let private_file = file_url(dir.join("private").join("key"));
+    webview.navigate(private_file.clone()).unwrap();
+    webview.navigate(private.clone()).unwrap();
+    assert_eq!(webview.url().unwrap(), expected);
+    assert_eq!(
+      *blocked.lock().unwrap(),
+      vec![
+        serde_json::json!({ "url": private_file }).to_string(),
+        serde_json::json!({ "url": private }).to_string()
+      ]
+    );
+
+    // the initial URL is checked too
+    assert!(
+      WebviewWindowBuilder::new(&app, "other", WebviewUrl::External(private_file))
+        .allow_file_access(FileAccessScope::new().allow_directory(dir.join("pages"), true))
+        .build()
+        .is_err()
+    );
+  }
+}
@@ -0,0 +1,134 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+/// The header the frontend sends the invoke key in.
+pub(crate) const INVOKE_KEY_HEADER: &str = "Tauri-Invoke-Key";
+/// The command the initialization script calls to get the invoke key of the current page.
+pub(crate) const INVOKE_HANDSHAKE_COMMAND: &str = "__TAURI_INVOKE_HANDSHAKE__";
+
+/// The key authenticating the IPC requests of a webview.
+///
+/// Every document running the initialization script requests the key through the [`INVOKE_HANDSHAKE_COMMAND`],
+/// authenticated by the bootstrap secret rendered into the script and never exposed to the page scripts.
+pub(crate) struct InvokeKey {
+  rotate: bool,
+  bootstrap: String,
+  current: String,
+  generation: u64,
+}
+
+impl Default for InvokeKey {
+  fn default() -> Self {
+    Self {
+      rotate: true,
+      bootstrap: generate_key(),
+      current: generate_key(),
+      generation: 0,
+    }
+  }
+}
+
+impl InvokeKey {
+  pub(crate) fn set_rotate(&mut self, rotate: bool) {
+    self.rotate = rotate;
+  }
+
+  /// The secret the initialization script authenticates the handshake with.
+  pub(crate) fn bootstrap(&self) -> &str {
+    &self.bootstrap
+  }
+
+  /// Called when the main frame starts a navigation, replacing the key unless rotation is disabled.
+  pub(crate) fn on_navigation(&mut self) {
+    if self.rotate {
+      self.current = generate_key();
+      self.generation += 1;
+    }
+  }
+
+  /// Hands out the key of the current page to a document holding the bootstrap secret.
+  pub(crate) fn handshake(&self, bootstrap: Option<&str>) -> Option<&str> {
+    (bootstrap == Some(self.bootstrap.as_str())).then_some(self.current.as_str())
+  }
+
+  /// Whether the given key is the key of the current page.
+  pub(crate) fn validate(&self, key: Option<&str>) -> bool {
+    key == Some(self.current.as_str())
+  }
+
+  /// The key of the current page.
+  #[cfg(any(test, feature = "test", feature = "remote-automation"))]
+  pub(crate) fn current(&self) -> &str {
+    &self.current
+  }
+
+  /// Incremented every time the key is replaced.
+  pub(crate) fn generation(&self) -> u64 {
+    self.generation
+  }
+}
+
+fn generate_key() -> String {
+  let mut bytes = [0u8; 16];
+  getrandom::getrandom(&mut bytes).expect("failed to generate the invoke key");
+  bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::InvokeKey;
+
+  #[test]
+  fn handshake_per_document() {
+    let mut key = InvokeKey::default();
+    let bootstrap = key.bootstrap().to_string();
+    assert_eq!(bootstrap.len(), 32);
+    assert!(key.handshake(None).is_none());
+    assert!(key.handshake(Some("guessed")).is_none());
+
+    // the main frame and its frames all get the key of the page
+    let first = key.handshake(Some(&bootstrap)).unwrap().to_string();
+    assert_eq!(key.handshake(Some(&bootstrap)), Some(first.as_str()));
+    assert!(key.validate(Some(&first)));
+    assert!(!key.validate(Some(&bootstrap)));
+    assert!(!key.validate(Some("replayed")));
+    assert!(!key.validate(None));
+
+    key.on_navigation();
+    assert!(!key.validate(Some(&first)));
+    let second = key.handshake(Some(&bootstrap)).unwrap().to_string();
+    assert_ne!(first, second);
+    assert!(key.validate(Some(&second)));
+    assert_eq!(key.generation(), 1);
+  }
+
+  #[test]
+  fn outgoing_page_does_not_take_the_next_handshake() {
+    let mut key = InvokeKey::default();
+    let bootstrap = key.bootstrap().to_string();
+    let outgoing = key.handshake(Some(&bootstrap)).unwrap().to_string();
+
+    key.on_navigation();
+    // a frame of the outgoing page retries after its key was rejected
+    assert!(!key.validate(Some(&outgoing)));
+    let retried = key.handshake(Some(&bootstrap)).unwrap().to_string();
+    // the next page still gets its key before any of its scripts run
+    assert_eq!(key.handshake(Some(&bootstrap)), Some(retried.as_str()));
+    assert!(key.validate(Some(&retried)));
+  }
+
+  #[test]
+  fn keeps_key_without_rotation() {
+    let mut key = InvokeKey::default();
+    key.set_rotate(false);
+    let bootstrap = key.bootstrap().to_string();
+    let first = key.handshake(Some(&bootstrap)).unwrap().to_string();
+
+    key.on_navigation();
+    assert!(key.validate(Some(&first)));
+    assert_eq!(key.handshake(Some(&bootstrap)), Some(first.as_str()));
+    assert_eq!(key.handshake(Some(&bootstrap)), Some(first.as_str()));
+    assert_eq!(key.generation(), 0);
+  }
+}
@@ -0,0 +1,154 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Scripts evaluated in isolated worlds, see [`Webview::eval_isolated`].
+
+use serde::{de::DeserializeOwned, Deserialize};
+use tauri_runtime::WebviewDispatch;
+
+use super::Webview;
+use crate::Runtime;
+
+/// Wraps a script so its declarations stay in a function scope where isolated worlds are not supported,
+/// and so it evaluates to the JSON serialized `{ value }` it returns or the `{ error }` it throws.
+fn isolated_script(js: &str) -> String {
+  format!(
+    r#"(function () {{
+  let result;
+  try {{
+    result = {{ value: (function () {{
+{js}
+    }})() }};
+  }} catch (e) {{
+    result = {{ error: String(e) }};
+  }}
+  try {{
+    return JSON.stringify(result);
+  }} catch (e) {{
+    return JSON.stringify({{ error: String(e) }});
+  }}
+}})()"#
+  )
+}
+
+#[derive(Deserialize)]
+struct IsolatedResult {
+  #[serde(default)]
+  value: serde_json::Value,
+  error: Option<String>,
+}
+
+fn parse_result<T: DeserializeOwned>(result: &str) -> crate::Result<T> {
+  let result: IsolatedResult = serde_json::from_str(result)
+    .map_err(|_| crate::Error::IsolatedScript("the script could not be evaluated".into()))?;
+  match result.error {
+    Some(error) => Err(crate::Error::IsolatedScript(error)),
+    None => serde_json::from_value(result.value).map_err(Into::into),
+  }
+}
+
+impl<R: Runtime> Webview<R> {
+  /// Evaluates JavaScript in an isolated world of this webview.
+  ///
+  /// An isolated world shares the DOM with the page but not its globals,
+  /// so the page can neither observe nor tamper with the script.
+  /// Worlds are identified by name and keep their globals until the next page load,
+  /// see [`WebviewBuilder::isolated_initialization_script`](super::WebviewBuilder::isolated_initialization_script)
+  /// to run a script in a world on every page load.
+  ///
+  /// The script is the body of a function, use `return` to pass a value to [`Self::eval_isolated_with_result`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS:** Uses a `WKContentWorld`, requires macOS 11+.
+  /// - **Linux:** Uses a webkit2gtk script world.
+  /// - **Windows / iOS / Older macOS versions:** Isolated worlds are not supported,
+  ///   the script runs in the page world, wrapped in a function so its declarations are not visible to the page.
+  ///   It is not protected from a page modifying the built-in objects it uses.
+  /// - **Android:** Unsupported.
+  pub fn eval_isolated(&self, world: &str, js: &str) -> crate::Result<()> {
+    self
+      .webview
+      .dispatcher
+      .eval_script_in_world(world.into(), isolated_script(js), |_| ())
+      .map_err(Into::into)
+  }
+
+  /// Evaluates JavaScript in an isolated world of this webview and returns the value returned by the script,
+  /// see [`Self::eval_isolated`].
+  ///
+  /// The value crosses the world boundary serialized to JSON, so it must be JSON compatible:
+  /// functions and `undefined` properties are dropped and DOM nodes are not supported.
+  /// An exception thrown by the script is returned as [`crate::Error::IsolatedScript`].
+  pub async fn eval_isolated_with_result<T: DeserializeOwned>(
+    &self,
+    world: &str,
+    js: &str,
+  ) -> crate::Result<T> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    self
+      .dispatcher()?
+      .eval_script_in_world(world.into(), isolated_script(js), move |result| {
+        let _ = tx.send(result);
+      })?;
+    let result = rx
+      .await
+      .map_err(|_| crate::Error::Runtime(tauri_runtime::Error::FailedToReceiveMessage))?;
+    parse_result(&result)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{isolated_script, parse_result};
+  use crate::{test::mock_app, WebviewWindowBuilder};
+
+  #[test]
+  fn wraps_script_in_function() {
+    let script = isolated_script("const a = 1;\nreturn a // no semicolon");
+    assert!(script.contains("const a = 1;\nreturn a // no semicolon\n"));
+    assert!(script.starts_with("(function () {"));
+    assert!(script.ends_with("})()"));
+  }
+
+  #[test]
+  fn parses_results() {
+    assert_eq!(parse_result::<u32>(r#"{"value":1}"#).unwrap(), 1);
+    assert_eq!(parse_result::<Option<u32>>("{}").unwrap(), None);
+    assert!(matches!(
+      parse_result::<u32>(r#"{"error":"ReferenceError: a is not defined"}"#),
+      Err(crate::Error::IsolatedScript(e)) if e == "ReferenceError: a is not defined"
+    ));
+    assert!(matches!(
+      parse_result::<u32>(r#"{"value":"a"}"#),
+      Err(crate::Error::Json(_))
+    ));
+    // the evaluation failed
+    assert!(matches!(
+      parse_result::<u32>(""),
+      Err(crate::Error::IsolatedScript(_))
+    ));
+  }
+
+  #[test]
+  fn evaluates_in_world() {
+    let app = mock_app();
+    let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .isolated_initialization_script("extension", "const secret = 1;")
+      .build()
+      .unwrap();
+    let dispatcher = &webview.webview.webview.dispatcher;
+
+    dispatcher.set_eval_result(r#"{"value":{"title":"Tauri"}}"#);
+    let value: serde_json::Value = crate::async_runtime::block_on(
+      webview.eval_isolated_with_result("extension", "return { title: document.title };"),
+    )
+    .unwrap();
+    assert_eq!(value["title"], "Tauri");
+    assert!(dispatcher
+      .last_evaluated_script()
+      .unwrap()
+      .contains("return { title: document.title };"));
+  }
+}
@@ -0,0 +1,161 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Direct messages between webviews, see [`super::Webview::post_message_to`].
+
+use std::collections::VecDeque;
+
+use serde::Serialize;
+
+use super::Webview;
+use crate::{event::EventTarget, sealed::ManagerBase, Manager, Runtime};
+
+/// The maximum size in bytes of the messages waiting to be received by a webview.
+///
+/// [`Webview::post_message_to`] fails with [`crate::Error::WebviewMessageQueueFull`] when a message would exceed it.
+pub const MAX_WEBVIEW_MESSAGE_QUEUE_SIZE: usize = 64 * 1024 * 1024;
+
+/// The event notifying the target webview that a message is ready to be received.
+pub(crate) const WEBVIEW_MESSAGE_EVENT: &str = "tauri://webview-message";
+/// The header the frontend sends the label of the target webview in.
+#[cfg_attr(mobile, allow(dead_code))]
+pub(crate) const WEBVIEW_MESSAGE_TARGET_HEADER: &str = "Tauri-Webview-Target";
+/// The header the frontend sends the identifier of the message to receive in.
+#[cfg_attr(mobile, allow(dead_code))]
+pub(crate) const WEBVIEW_MESSAGE_ID_HEADER: &str = "Tauri-Webview-Message-Id";
+
+#[derive(Clone, Serialize)]
+struct MessageReady<'a> {
+  id: u32,
+  source: &'a str,
+}
+
+/// The messages waiting to be received by a webview.
+#[derive(Default)]
+pub(crate) struct MessageQueue {
+  messages: VecDeque<(u32, Vec<u8>)>,
+  size: usize,
+  next_id: u32,
+}
+
+impl MessageQueue {
+  /// Queues the message, returning its identifier, or `None` if the queue is full.
+  fn push(&mut self, message: Vec<u8>) -> Option<u32> {
+    if self.size + message.len() > MAX_WEBVIEW_MESSAGE_QUEUE_SIZE {
+      return None;
+    }
+    let id = self.next_id;
+    self.next_id = self.next_id.wrapping_add(1);
+    self.size += message.len();
+    self.messages.push_back((id, message));
+    Some(id)
+  }
+
+  /// Removes the message with the given identifier from the queue.
+  #[cfg_attr(mobile, allow(dead_code))]
+  pub(crate) fn take(&mut self, id: u32) -> Option<Vec<u8>> {
+    let index = self.messages.iter().position(|(i, _)| *i == id)?;
+    let (_, message) = self.messages.remove(index)?;
+    self.size -= message.len();
+    Some(message)
+  }
+}
+
+impl<R: Runtime> Webview<R> {
+  /// Sends binary data to the webview with the given label,
+  /// delivered as an `ArrayBuffer` to its `onMessage` handlers without being serialized to JSON.
+  ///
+  /// Fails with [`crate::Error::WebviewMessageQueueFull`] if the messages waiting to be received by the target
+  /// would exceed [`MAX_WEBVIEW_MESSAGE_QUEUE_SIZE`], for instance when it does not listen to messages.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// tauri::Builder::default()
+  ///   .on_page_load(|webview, _payload| {
+  ///     let _ = webview.post_message_to("preview", b"hello".to_vec());
+  ///   });
+  /// ```
+  pub fn post_message_to(&self, label: &str, message: Vec<u8>) -> crate::Result<()> {
+    let target = self
+      .manager()
+      .get_webview(label)
+      .ok_or(crate::Error::WebviewNotFound)?;
+
+    let id = target
+      .messages
+      .lock()
+      .unwrap()
+      .push(message)
+      .ok_or_else(|| crate::Error::WebviewMessageQueueFull(label.into()))?;
+
+    target.emit_to(
+      EventTarget::labeled(label),
+      WEBVIEW_MESSAGE_EVENT,
+      MessageReady {
+        id,
+        source: self.label(),
+      },
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{MessageQueue, MAX_WEBVIEW_MESSAGE_QUEUE_SIZE};
+  use crate::{test::mock_app, WebviewWindowBuilder};
+
+  #[test]
+  fn queue_cap() {
+    let mut queue = MessageQueue::default();
+    let first = queue
+      .push(vec![0; MAX_WEBVIEW_MESSAGE_QUEUE_SIZE - 1])
+      .unwrap();
+    assert!(queue.push(vec![0; 2]).is_none());
+    let second = queue.push(vec![1]).unwrap();
+    assert!(queue.push(vec![1]).is_none());
+
+    assert_eq!(queue.take(second), Some(vec![1]));
+    assert!(queue.take(second).is_none());
+    assert_eq!(
+      queue.take(first).unwrap().len(),
+      MAX_WEBVIEW_MESSAGE_QUEUE_SIZE - 1
+    );
+    assert!(queue
+      .push(vec![0; MAX_WEBVIEW_MESSAGE_QUEUE_SIZE])
+      .is_some());
+  }
+
+  #[test]
+  fn post_large_message() {
+    let app = mock_app();
+    let source = WebviewWindowBuilder::new(&app, "source", Default::default())
+      .build()
+      .unwrap();
+    let target = WebviewWindowBuilder::new(&app, "target", Default::default())
+      .build()
+      .unwrap();
+
+    let message = (0..8 * 1024 * 1024)
+      .map(|i: u32| (i % 251) as u8)
+      .collect::<Vec<_>>();
+    source.post_message_to("target", message.clone()).unwrap();
+    assert_eq!(
+      target.webview.messages.lock().unwrap().take(0),
+      Some(message)
+    );
+
+    assert!(matches!(
+      source.post_message_to("missing", Vec::new()),
+      Err(crate::Error::WebviewNotFound)
+    ));
+    source
+      .post_message_to("target", vec![0; MAX_WEBVIEW_MESSAGE_QUEUE_SIZE])
+      .unwrap();
+    assert!(matches!(
+      source.post_message_to("target", vec![0]),
+      Err(crate::Error::WebviewMessageQueueFull(label)) if label == "target"
+    ));
+  }
+}
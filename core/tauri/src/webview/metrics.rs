@@ -0,0 +1,204 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Memory and performance metrics of webviews.
+
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri_runtime::WebviewDispatch;
+
+use super::Webview;
+use crate::{sealed::ManagerBase, Manager, Runtime};
+
+/// The event emitted periodically when [`super::WebviewBuilder::metrics_interval`] is set.
+pub const WEBVIEW_METRICS_EVENT: &str = "tauri://webview-metrics";
+
+/// Memory usage of the process rendering a webview, see [`Webview::memory_usage`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebviewMemoryStats {
+  /// Memory exclusively used by the process, in bytes.
+  pub private_bytes: u64,
+  /// Memory shared with other processes, in bytes.
+  pub shared_bytes: u64,
+  /// Whether the numbers refer to the embedding process instead of a dedicated webview process,
+  /// in which case they include the memory used by the application and every other webview.
+  pub approximate: bool,
+}
+
+/// Payload of the `tauri://webview-metrics` event.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebviewMetrics {
+  /// The webview label.
+  pub label: String,
+  /// The identifier of the process rendering the webview.
+  pub process_id: u32,
+  /// The memory usage of the process.
+  pub memory: WebviewMemoryStats,
+}
+
+impl<R: Runtime> Webview<R> {
+  /// Returns the identifier of the process rendering the webview content.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Returns the WebView2 browser process identifier.
+  /// - **Linux / macOS / iOS / Android**: Returns the current process identifier.
+  pub fn process_id(&self) -> crate::Result<u32> {
+    self.dispatcher()?.process_id().map_err(Into::into)
+  }
+
+  /// Returns the memory usage of the process rendering the webview content.
+  ///
+  /// On platforms without a dedicated webview process the numbers refer to the current process
+  /// and [`WebviewMemoryStats::approximate`] is set.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **iOS / Android:** Unsupported.
+  pub fn memory_usage(&self) -> crate::Result<WebviewMemoryStats> {
+    let process_id = self.process_id()?;
+    let (private_bytes, shared_bytes) = process_memory(process_id)?;
+    Ok(WebviewMemoryStats {
+      private_bytes,
+      shared_bytes,
+      approximate: process_id == std::process::id(),
+    })
+  }
+
+  /// Collects the metrics emitted with the `tauri://webview-metrics` event.
+  pub fn metrics(&self) -> crate::Result<WebviewMetrics> {
+    Ok(WebviewMetrics {
+      label: self.label().into(),
+      process_id: self.process_id()?,
+      memory: self.memory_usage()?,
+    })
+  }
+}
+
+/// Emits the `tauri://webview-metrics` event for the webview at the given interval until it is closed.
+pub(crate) fn spawn_metrics_emitter<R: Runtime>(webview: &Webview<R>, interval: Duration) {
+  let manager = webview.manager_owned();
+  let label = webview.label().to_string();
+  std::thread::spawn(move || loop {
+    std::thread::sleep(interval);
+    let Some(webview) = manager.get_webview(&label) else {
+      break;
+    };
+    if let Ok(metrics) = webview.metrics() {
+      let _ = webview.emit(WEBVIEW_METRICS_EVENT, metrics);
+    }
+  });
+}
+
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+))]
+fn process_memory(process_id: u32) -> crate::Result<(u64, u64)> {
+  let smaps = std::fs::read_to_string(format!("/proc/{process_id}/smaps_rollup"))?;
+  Ok(parse_smaps_rollup(&smaps))
+}
+
+/// Sums the private and shared memory of a `/proc/<pid>/smaps_rollup` file, in bytes.
+#[cfg(any(
+  test,
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+))]
+fn parse_smaps_rollup(smaps: &str) -> (u64, u64) {
+  let mut private = 0;
+  let mut shared = 0;
+  for line in smaps.lines() {
+    let mut parts = line.split_whitespace();
+    let (Some(key), Some(value)) = (parts.next(), parts.next()) else {
+      continue;
+    };
+    let Ok(kb) = value.parse::<u64>() else {
+      continue;
+    };
+    match key {
+      "Private_Clean:" | "Private_Dirty:" => private += kb * 1024,
+      "Shared_Clean:" | "Shared_Dirty:" => shared += kb * 1024,
+      _ => {}
+    }
+  }
+  (private, shared)
+}
+
+#[cfg(windows)]
+fn process_memory(process_id: u32) -> crate::Result<(u64, u64)> {
+  use windows::Win32::{
+    Foundation::CloseHandle,
+    System::{
+      ProcessStatus::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS, PROCESS_MEMORY_COUNTERS_EX},
+      Threading::{OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION},
+    },
+  };
+
+  unsafe {
+    let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id)
+      .map_err(std::io::Error::from)?;
+    let mut counters = PROCESS_MEMORY_COUNTERS_EX::default();
+    let res = GetProcessMemoryInfo(
+      process,
+      &mut counters as *mut PROCESS_MEMORY_COUNTERS_EX as *mut PROCESS_MEMORY_COUNTERS,
+      std::mem::size_of::<PROCESS_MEMORY_COUNTERS_EX>() as u32,
+    );
+    let _ = CloseHandle(process);
+    res.map_err(std::io::Error::from)?;
+
+    let private = counters.PrivateUsage as u64;
+    let shared = (counters.WorkingSetSize as u64).saturating_sub(private);
+    Ok((private, shared))
+  }
+}
+
+#[cfg(target_os = "macos")]
+fn process_memory(process_id: u32) -> crate::Result<(u64, u64)> {
+  let mut info: libc::proc_taskinfo = unsafe { std::mem::zeroed() };
+  let size = std::mem::size_of::<libc::proc_taskinfo>() as libc::c_int;
+  let res = unsafe {
+    libc::proc_pidinfo(
+      process_id as libc::c_int,
+      libc::PROC_PIDTASKINFO,
+      0,
+      &mut info as *mut libc::proc_taskinfo as *mut libc::c_void,
+      size,
+    )
+  };
+  if res != size {
+    return Err(std::io::Error::last_os_error().into());
+  }
+  Ok((info.pti_resident_size, 0))
+}
+
+#[cfg(mobile)]
+fn process_memory(_process_id: u32) -> crate::Result<(u64, u64)> {
+  Err(std::io::Error::from(std::io::ErrorKind::Unsupported).into())
+}
+
+#[cfg(test)]
+mod tests {
+  #[test]
+  fn parse_smaps_rollup() {
+    let smaps = "55d4c5a1e000-7ffd6a5f1000 ---p 00000000 00:00 0                          [rollup]
+Rss:               10240 kB
+Pss:                6144 kB
+Shared_Clean:       2048 kB
+Shared_Dirty:       1024 kB
+Private_Clean:       512 kB
+Private_Dirty:      6656 kB
+Referenced:        10240 kB";
+    assert_eq!(super::parse_smaps_rollup(smaps), (7168 * 1024, 3072 * 1024));
+  }
+}
@@ -4,15 +4,58 @@
 
 //! The Tauri webview types and functions.
 
+mod console;
+#[cfg(feature = "devtools-protocol")]
+mod devtools_protocol;
+pub(crate) mod event_sequence;
+mod events;
+pub(crate) mod file_access;
+pub(crate) mod invoke_key;
+mod isolated;
+pub(crate) mod messaging;
+mod metrics;
+mod navigation;
+mod parts;
 pub(crate) mod plugin;
+#[cfg(any(test, all(desktop, feature = "unstable")))]
+mod prepared;
+pub(crate) mod runtime_status;
+mod scroll;
+#[cfg(feature = "webview-storage")]
+mod storage;
+mod suspend;
 mod webview_window;
 
+pub use console::{ConsoleMessage, ConsoleMessageLevel};
+#[cfg(feature = "devtools-protocol")]
+#[cfg_attr(docsrs, doc(cfg(feature = "devtools-protocol")))]
+pub use devtools_protocol::DevtoolsProtocol;
+pub use events::WEBVIEW_EVENT_STREAM_CAPACITY;
+pub use file_access::{BlockedNavigation, FileAccessScope, BLOCKED_NAVIGATION_EVENT};
+pub use messaging::MAX_WEBVIEW_MESSAGE_QUEUE_SIZE;
+pub use metrics::{WebviewMemoryStats, WebviewMetrics, WEBVIEW_METRICS_EVENT};
+pub use navigation::DEFAULT_ASYNC_NAVIGATION_TIMEOUT;
+pub use parts::WebviewBuilderParts;
+#[cfg(any(test, all(desktop, feature = "unstable")))]
+#[cfg_attr(docsrs, doc(cfg(all(desktop, feature = "unstable"))))]
+pub use prepared::PreparedWebview;
+pub use runtime_status::{RuntimeStatus, WebviewRuntimeAction, MINIMUM_WEBVIEW_VERSION};
+pub use scroll::{ScrollPosition, WebviewScroll, WEBVIEW_SCROLL_EVENT};
+#[cfg(feature = "webview-storage")]
+#[cfg_attr(docsrs, doc(cfg(feature = "webview-storage")))]
+pub use storage::{StorageArea, StorageHandle};
+pub use suspend::DEFAULT_SUSPENDED_EVENT_QUEUE_LIMIT;
+pub(crate) use webview_window::build_config_webviews;
 pub use webview_window::{WebviewWindow, WebviewWindowBuilder};
 
+use futures_util::future::BoxFuture;
 use http::HeaderMap;
 use serde::Serialize;
 use tauri_macros::default_runtime;
-pub use tauri_runtime::webview::PageLoadEvent;
+pub use tauri_runtime::webview::{
+  AuthChallenge, AuthResponse, CertDecision, CertError, CertErrorKind, ContextMenuRequest, Insets,
+  PageLoadEvent, RequestSummary, ResourceType, SessionId,
+};
 use tauri_runtime::{
   webview::{DetachedWebview, PendingWebview, WebviewAttributes},
   WebviewDispatch,
@@ -22,28 +65,42 @@ use tauri_runtime::{
   window::dpi::{PhysicalPosition, PhysicalSize, Position, Size},
   WindowDispatch,
 };
-use tauri_utils::config::{WebviewUrl, WindowConfig};
+use tauri_utils::config::{
+  BackgroundThrottlingPolicy, WebviewConfig, WebviewUrl, WindowConfig, WindowEffectsConfig,
+};
 pub use url::Url;
 
 use crate::{
   app::UriSchemeResponder,
   event::{EmitArgs, EventTarget},
   ipc::{
-    CallbackFn, CommandArg, CommandItem, Invoke, InvokeBody, InvokeError, InvokeMessage,
-    InvokeResolver, Origin, OwnedInvokeResponder,
+    AllowList, CallbackFn, CommandArg, CommandItem, InvokeBody, InvokeError, OwnedInvokeResponder,
+  },
+  manager::{
+    webview::{WebviewEventHandler, WebviewLabelDef},
+    AppManager,
   },
-  manager::{webview::WebviewLabelDef, AppManager},
   sealed::{ManagerBase, RuntimeOrDispatch},
-  AppHandle, Event, EventId, EventLoopMessage, Manager, Runtime, Window,
+  AppHandle, Event, EventId, EventLoopMessage, ListenerGuard, Manager, Runtime, Theme,
+  WebviewEvent, Window,
 };
 
 use std::{
   borrow::Cow,
+  future::Future,
   hash::{Hash, Hasher},
   path::PathBuf,
-  sync::{Arc, Mutex},
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+  },
+  time::Duration,
 };
 
+/// The number of rejected credentials after which an [`AuthChallenge`] is cancelled
+/// without calling the [`WebviewBuilder::on_auth_challenge`] handler.
+pub const MAX_AUTH_CHALLENGE_FAILURES: u32 = 2;
+
 pub(crate) type WebResourceRequestHandler =
   dyn Fn(http::Request<Vec<u8>>, &mut http::Response<Cow<'static, [u8]>>) + Send + Sync;
 pub(crate) type NavigationHandler = dyn Fn(&Url) -> bool + Send;
@@ -52,6 +109,16 @@ pub(crate) type UriSchemeProtocolHandler =
 pub(crate) type OnPageLoad<R> = dyn Fn(Webview<R>, PageLoadPayload<'_>) + Send + Sync + 'static;
 
 pub(crate) type DownloadHandler<R> = dyn Fn(Webview<R>, DownloadEvent<'_>) -> bool + Send + Sync;
+pub(crate) type AuthChallengeHandler<R> = dyn Fn(Webview<R>, AuthChallenge) -> AuthResponse + Send;
+pub(crate) type CertErrorHandler<R> = dyn Fn(Webview<R>, CertError) -> CertDecision + Send;
+pub(crate) type RequestObserver<R> = dyn Fn(Webview<R>, RequestSummary) + Send;
+#[cfg(desktop)]
+pub(crate) type ContextMenuHandler<R> =
+  dyn Fn(Webview<R>, ContextMenuRequest) -> ContextMenuAction<R> + Send;
+pub(crate) type InternalCommandHandler<R> = fn(&Webview<R>, InvokeBody) -> Result<(), String>;
+
+/// Emitted to a webview recreated with [`Webview::recover`].
+pub(crate) const WEBVIEW_RECOVERED_EVENT: &str = "tauri://webview-recovered";
 
 #[derive(Clone, Serialize)]
 struct CreatedEvent {
@@ -90,6 +157,17 @@ pub enum DownloadEvent<'a> {
   },
 }
 
+/// What to do when the webview requests a context menu, see [`WebviewBuilder::on_context_menu`].
+#[cfg(desktop)]
+pub enum ContextMenuAction<R: Runtime> {
+  /// Shows the default context menu of the webview.
+  ShowDefault,
+  /// Hides the context menu.
+  Suppress,
+  /// Hides the default context menu and shows the given menu at the requested position.
+  ShowCustom(crate::menu::Menu<R>),
+}
+
 /// The payload for the [`WebviewBuilder::on_page_load`] hook.
 #[derive(Debug, Clone)]
 pub struct PageLoadPayload<'a> {
@@ -109,6 +187,21 @@ impl<'a> PageLoadPayload<'a> {
   }
 }
 
+/// Shows the menu at the given position relative to the webview.
+#[cfg(desktop)]
+fn popup_context_menu<R: Runtime>(
+  webview: &Webview<R>,
+  menu: &crate::menu::Menu<R>,
+  position: Position,
+) -> crate::Result<()> {
+  let position = position.to_physical::<i32>(webview.window().scale_factor()?);
+  let offset = webview.position()?;
+  webview.window().popup_menu_at(
+    menu,
+    PhysicalPosition::new(position.x + offset.x, position.y + offset.y),
+  )
+}
+
 /// The IPC invoke request.
 #[derive(Debug)]
 pub struct InvokeRequest {
@@ -124,6 +217,14 @@ pub struct InvokeRequest {
   pub headers: HeaderMap,
 }
 
+/// Splits a `plugin:<name>|<command>` invoke command into the plugin name and command name.
+///
+/// Only the first `|` separates the plugin name, so the command name is preserved as is.
+pub(crate) fn parse_plugin_command(cmd: &str) -> Option<(&str, &str)> {
+  let raw_command = cmd.strip_prefix("plugin:")?;
+  Some(raw_command.split_once('|').unwrap_or((raw_command, "")))
+}
+
 /// The platform webview handle. Accessed with [`Webview#method.with_webview`];
 #[cfg(feature = "wry")]
 #[cfg_attr(docsrs, doc(cfg(feature = "wry")))]
@@ -224,11 +325,103 @@ unstable_struct!(
     pub(crate) webview_attributes: WebviewAttributes,
     pub(crate) web_resource_request_handler: Option<Box<WebResourceRequestHandler>>,
     pub(crate) navigation_handler: Option<Box<NavigationHandler>>,
+    pub(crate) async_navigation: navigation::AsyncNavigation,
+    pub(crate) file_access: Option<FileAccessScope>,
     pub(crate) on_page_load_handler: Option<Box<OnPageLoad<R>>>,
     pub(crate) download_handler: Option<Arc<DownloadHandler<R>>>,
+    #[cfg(desktop)]
+    pub(crate) context_menu_handler: Option<Box<ContextMenuHandler<R>>>,
+    pub(crate) auth_challenge_handler: Option<Box<AuthChallengeHandler<R>>>,
+    pub(crate) cert_error_handler: Option<Box<CertErrorHandler<R>>>,
+    pub(crate) request_observer: Option<Box<RequestObserver<R>>>,
+    pub(crate) profile: Option<String>,
+    pub(crate) label_reservation: Option<crate::manager::labels::ReservationToken>,
+    pub(crate) created_options: CreatedOptions<R>,
   }
 );
 
+/// Webview options applied after the webview is created by the runtime.
+pub(crate) struct CreatedOptions<R: Runtime> {
+  /// Given to the webview when it is attached, before it can receive IPC requests.
+  pub(crate) allowed_commands: Option<AllowList>,
+  metrics_interval: Option<Duration>,
+  scroll_events: Option<Duration>,
+  auto_suspend_on_hidden: bool,
+  suspended_event_queue_limit: usize,
+  rotate_invoke_key: bool,
+  console_message_handler: Option<Arc<console::ConsoleMessageHandler<R>>>,
+  transparent: bool,
+  effects: Option<WindowEffectsConfig>,
+  allow_accelerator_override: bool,
+  event_handlers: Vec<WebviewEventHandler>,
+}
+
+impl<R: Runtime> Default for CreatedOptions<R> {
+  fn default() -> Self {
+    Self {
+      allowed_commands: None,
+      metrics_interval: None,
+      scroll_events: None,
+      auto_suspend_on_hidden: false,
+      suspended_event_queue_limit: DEFAULT_SUSPENDED_EVENT_QUEUE_LIMIT,
+      rotate_invoke_key: true,
+      console_message_handler: None,
+      transparent: false,
+      effects: None,
+      allow_accelerator_override: false,
+      event_handlers: Vec::new(),
+    }
+  }
+}
+
+impl<R: Runtime> CreatedOptions<R> {
+  pub(crate) fn apply(self, webview: &Webview<R>) {
+    for handler in self.event_handlers {
+      webview
+        .manager()
+        .webview
+        .event_listeners
+        .add(webview.label(), handler);
+    }
+    *webview.console_message_handler.lock().unwrap() = self.console_message_handler;
+    webview
+      .scroll_events
+      .store(self.scroll_events.is_some(), Ordering::Relaxed);
+    webview
+      .invoke_key
+      .lock()
+      .unwrap()
+      .set_rotate(self.rotate_invoke_key);
+
+    webview.suspension.lock().unwrap().configure(
+      self.auto_suspend_on_hidden,
+      self.suspended_event_queue_limit,
+    );
+
+    if let Some(interval) = self.metrics_interval {
+      metrics::spawn_metrics_emitter(webview, interval);
+    }
+
+    #[cfg(desktop)]
+    webview
+      .window
+      .attach_accelerators(webview, self.allow_accelerator_override);
+
+    webview
+      .transparent
+      .store(self.transparent, Ordering::Relaxed);
+    if let Some(effects) = self.effects {
+      if let Err(e) = webview.set_effects(effects) {
+        tauri_utils::debug_eprintln!(
+          "failed to apply the `{}` webview effects: {}",
+          webview.label(),
+          e
+        );
+      }
+    }
+  }
+}
+
 #[cfg_attr(not(feature = "unstable"), allow(dead_code))]
 impl<R: Runtime> WebviewBuilder<R> {
   /// Initializes a webview builder with the given webview label and URL to load.
@@ -300,11 +493,36 @@ async fn create_window(app: tauri::AppHandle) {
       webview_attributes: WebviewAttributes::new(url),
       web_resource_request_handler: None,
       navigation_handler: None,
+      async_navigation: Default::default(),
+      file_access: None,
       on_page_load_handler: None,
       download_handler: None,
+      #[cfg(desktop)]
+      context_menu_handler: None,
+      auth_challenge_handler: None,
+      cert_error_handler: None,
+      request_observer: None,
+      profile: None,
+      label_reservation: None,
+      created_options: Default::default(),
     }
   }
 
+  /// Initializes a webview builder with a unique `<prefix>-<n>` label like [`Manager::unique_label`],
+  /// returning the builder and the label.
+  ///
+  /// The label is reserved for this builder rather than the calling thread,
+  /// so the webview can be built on any thread until the reservation expires.
+  pub fn new_unique<M: Manager<R>>(manager: &M, prefix: &str, url: WebviewUrl) -> (Self, String) {
+    let manager = manager.manager();
+    let (label, token) = manager
+      .labels
+      .reserve_with_token(prefix, |label| manager.is_label_used(label));
+    let mut builder = Self::new(&label, url);
+    builder.label_reservation = Some(token);
+    (builder, label)
+  }
+
   /// Initializes a webview builder from a [`WindowConfig`] from tauri.conf.json.
   /// Keep in mind that you can't create 2 webviews with the same `label` so make sure
   /// that the initial webview was closed or change the label of the new [`WebviewBuilder`].
@@ -340,8 +558,50 @@ async fn reopen_window(app: tauri::AppHandle) {
       webview_attributes: WebviewAttributes::from(config),
       web_resource_request_handler: None,
       navigation_handler: None,
+      async_navigation: Default::default(),
+      file_access: None,
+      on_page_load_handler: None,
+      download_handler: None,
+      #[cfg(desktop)]
+      context_menu_handler: None,
+      auth_challenge_handler: None,
+      cert_error_handler: None,
+      request_observer: None,
+      profile: None,
+      label_reservation: None,
+      created_options: CreatedOptions {
+        allowed_commands: config.allowlist.as_ref().map(|list| list.iter().collect()),
+        ..Default::default()
+      },
+    }
+  }
+
+  /// Initializes a webview builder from a [`WebviewConfig`] of a [`WindowConfig::webviews`] entry.
+  ///
+  /// The [`WebviewConfig::bounds`] are not applied, they must be resolved against the window size
+  /// when adding the webview to its window.
+  pub fn from_webview_config(config: &WebviewConfig) -> Self {
+    Self {
+      label: config.label.clone(),
+      webview_attributes: WebviewAttributes::from(config),
+      web_resource_request_handler: None,
+      navigation_handler: None,
+      async_navigation: Default::default(),
+      file_access: None,
       on_page_load_handler: None,
       download_handler: None,
+      #[cfg(desktop)]
+      context_menu_handler: None,
+      auth_challenge_handler: None,
+      cert_error_handler: None,
+      request_observer: None,
+      profile: None,
+      label_reservation: None,
+      created_options: CreatedOptions {
+        allowed_commands: config.allowlist.as_ref().map(|list| list.iter().collect()),
+        effects: config.effects.clone(),
+        ..Default::default()
+      },
     }
   }
 
@@ -352,6 +612,15 @@ async fn reopen_window(app: tauri::AppHandle) {
   /// **NOTE:** Currently this is **not** executed when using external URLs such as a development server,
   /// but it might be implemented in the future. **Always** check the request URL.
   ///
+  /// The closure receives the response Tauri would serve, including the error responses for missing assets.
+  /// The response is then served exactly as the closure leaves it, so it can change the status code
+  /// (e.g. a `404`, a `206` partial response or a `301` redirect), the headers and the body.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux / macOS / iOS**: WebKit does not follow the redirects of custom protocol responses,
+  ///   so a document request redirected with an empty body gets a body navigating to the `Location` header.
+  ///
   /// # Examples
   ///
   #[cfg_attr(
@@ -438,6 +707,56 @@ tauri::Builder::default()
     self
   }
 
+  /// Defines an async closure deciding whether the webview can navigate to the given URL,
+  /// for checks that cannot run on the main thread such as a database query.
+  ///
+  /// The navigations allowed by [`Self::on_navigation`] are cancelled and the returned future is driven
+  /// on the [`crate::async_runtime`], the webview navigates to the URL again once it resolves to `true`.
+  /// The navigation is denied if the future does not resolve before the [`Self::async_navigation_timeout`],
+  /// or if the webview starts another navigation in the meantime.
+  /// The URL the webview is created with is not checked.
+  ///
+  /// Note that the navigation is resumed as a plain `GET` request, so form submissions are not replayed.
+  ///
+  /// # Examples
+  ///
+  #[cfg_attr(
+    feature = "unstable",
+    doc = r####"
+```rust,no_run
+use tauri::{utils::config::WebviewUrl, webview::WebviewBuilder};
+
+async fn is_allowed(host: Option<String>) -> bool {
+  host.as_deref() == Some("tauri.app")
+}
+
+tauri::Builder::default()
+  .setup(|app| {
+    let window = tauri::window::WindowBuilder::new(app, "label").build()?;
+    let webview_builder = WebviewBuilder::new("core", WebviewUrl::App("index.html".into()))
+      .on_navigation_async(|url| Box::pin(is_allowed(url.host_str().map(Into::into))));
+    let webview = window.add_child(webview_builder, tauri::LogicalPosition::new(0, 0), window.inner_size().unwrap())?;
+    Ok(())
+  });
+```
+  "####
+  )]
+  pub fn on_navigation_async<F: Fn(&Url) -> BoxFuture<'static, bool> + Send + 'static>(
+    mut self,
+    f: F,
+  ) -> Self {
+    self.async_navigation.handler.replace(Box::new(f));
+    self
+  }
+
+  /// Sets how long the [`Self::on_navigation_async`] handler has to allow a navigation before it is denied.
+  ///
+  /// Defaults to [`DEFAULT_ASYNC_NAVIGATION_TIMEOUT`].
+  pub fn async_navigation_timeout(mut self, timeout: Duration) -> Self {
+    self.async_navigation.timeout = timeout;
+    self
+  }
+
   /// Set a download event handler to be notified when a download is requested or finished.
   ///
   /// Returning `false` prevents the download from happening on a [`DownloadEvent::Requested`] event.
@@ -532,261 +851,1037 @@ tauri::Builder::default()
     self
   }
 
-  pub(crate) fn into_pending_webview<M: Manager<R>>(
-    mut self,
-    manager: &M,
-    window_label: &str,
-    window_labels: &[String],
-    webview_labels: &[WebviewLabelDef],
-  ) -> crate::Result<PendingWebview<EventLoopMessage, R>> {
-    let mut pending = PendingWebview::new(self.webview_attributes, self.label.clone())?;
-    pending.navigation_handler = self.navigation_handler.take();
-    pending.web_resource_request_handler = self.web_resource_request_handler.take();
-
-    if let Some(download_handler) = self.download_handler.take() {
-      let label = pending.label.clone();
-      let manager = manager.manager_owned();
-      pending.download_handler.replace(Arc::new(move |event| {
-        if let Some(w) = manager.get_webview(&label) {
-          download_handler(
-            w,
-            match event {
-              tauri_runtime::webview::DownloadEvent::Requested { url, destination } => {
-                DownloadEvent::Requested { url, destination }
-              }
-              tauri_runtime::webview::DownloadEvent::Finished { url, path, success } => {
-                DownloadEvent::Finished { url, path, success }
-              }
-            },
-          )
-        } else {
-          false
-        }
-      }));
-    }
-
-    if let Some(on_page_load_handler) = self.on_page_load_handler.take() {
-      let label = pending.label.clone();
-      let manager = manager.manager_owned();
-      pending
-        .on_page_load_handler
-        .replace(Box::new(move |url, event| {
-          if let Some(w) = manager.get_webview(&label) {
-            on_page_load_handler(w, PageLoadPayload { url: &url, event });
-          }
-        }));
-    }
-
-    manager.manager().webview.prepare_webview(
-      manager,
-      pending,
-      window_label,
-      window_labels,
-      webview_labels,
-    )
-  }
-
-  /// Creates a new webview on the given window.
-  #[cfg(desktop)]
-  pub(crate) fn build(
+  /// Defines an async closure to be executed when a page load event is triggered,
+  /// see [`Self::on_page_load`] which this replaces.
+  ///
+  /// The closure is called on the main thread and the returned future is spawned on the [`crate::async_runtime`],
+  /// so it must own the data it needs from the payload.
+  pub fn on_page_load_async<
+    F: Fn(Webview<R>, PageLoadPayload<'_>) -> BoxFuture<'static, ()> + Send + Sync + 'static,
+  >(
     self,
-    window: Window<R>,
-    position: Position,
-    size: Size,
-  ) -> crate::Result<Webview<R>> {
-    let window_labels = window
-      .manager()
-      .window
-      .labels()
-      .into_iter()
-      .collect::<Vec<_>>();
-    let webview_labels = window
-      .manager()
-      .webview
-      .webviews_lock()
-      .values()
-      .map(|w| WebviewLabelDef {
-        window_label: w.window.label().to_string(),
-        label: w.label().to_string(),
-      })
-      .collect::<Vec<_>>();
-
-    let app_manager = window.manager();
-
-    let mut pending =
-      self.into_pending_webview(&window, window.label(), &window_labels, &webview_labels)?;
-
-    pending.webview_attributes.bounds = Some((position, size));
-
-    let webview = match &mut window.runtime() {
-      RuntimeOrDispatch::Dispatch(dispatcher) => dispatcher.create_webview(pending),
-      _ => unimplemented!(),
-    }
-    .map(|webview| app_manager.webview.attach_webview(window.clone(), webview))?;
-
-    app_manager.webview.eval_script_all(format!(
-      "window.__TAURI_INTERNALS__.metadata.windows = {window_labels_array}.map(function (label) {{ return {{ label: label }} }})",
-      window_labels_array = serde_json::to_string(&app_manager.webview.labels())?,
-    ))?;
-
-    app_manager.emit_filter(
-      "tauri://webview-created",
-      Some(CreatedEvent {
-        label: webview.label().into(),
-      }),
-      |s| match s {
-        EventTarget::Webview { label } => label == webview.label(),
-        _ => false,
-      },
-    )?;
-
-    Ok(webview)
-  }
-}
-
-/// Webview attributes.
-impl<R: Runtime> WebviewBuilder<R> {
-  /// Sets whether clicking an inactive window also clicks through to the webview.
-  #[must_use]
-  pub fn accept_first_mouse(mut self, accept: bool) -> Self {
-    self.webview_attributes.accept_first_mouse = accept;
-    self
+    f: F,
+  ) -> Self {
+    self.on_page_load(move |webview, payload| {
+      crate::async_runtime::spawn(f(webview, payload));
+    })
   }
 
-  /// Adds the provided JavaScript to a list of scripts that should be run after the global object has been created,
-  /// but before the HTML document has been parsed and before any other script included by the HTML document is run.
+  /// Defines a closure to be executed when the page logs a message to the console,
+  /// e.g. to forward the page errors to a Rust logger.
   ///
-  /// Since it runs on all top-level document and child frame page navigations,
-  /// it's recommended to check the `window.location` to guard your script from running on unexpected origins.
+  /// The console methods are wrapped by an initialization script that still calls the original methods,
+  /// so the messages are also shown in the devtools.
+  /// Messages longer than 16384 characters are truncated, see [`ConsoleMessage::truncated`].
+  /// Messages logged by the initialization scripts that run before the Tauri scripts are not forwarded.
   ///
   /// # Examples
   ///
   #[cfg_attr(
     feature = "unstable",
     doc = r####"
-```rust
-use tauri::{WindowBuilder, Runtime};
-
-const INIT_SCRIPT: &str = r#"
-  if (window.location.origin === 'https://tauri.app') {
-    console.log("hello world from js init script");
-
-    window.__MY_CUSTOM_PROPERTY__ = { foo: 'bar' };
-  }
-"#;
-
-fn main() {
-  tauri::Builder::default()
-    .setup(|app| {
-      let window = tauri::window::WindowBuilder::new(app, "label").build()?;
-      let webview_builder = tauri::webview::WebviewBuilder::new("label", tauri::WebviewUrl::App("index.html".into()))
-        .initialization_script(INIT_SCRIPT);
-      let webview = window.add_child(webview_builder, tauri::LogicalPosition::new(0, 0), window.inner_size().unwrap())?;
-      Ok(())
-    });
-}
+```rust,no_run
+use tauri::{
+  utils::config::WebviewUrl,
+  webview::{ConsoleMessageLevel, WebviewBuilder},
+};
+tauri::Builder::default()
+  .setup(|app| {
+    let window = tauri::window::WindowBuilder::new(app, "label").build()?;
+    let webview_builder = WebviewBuilder::new("core", WebviewUrl::App("index.html".into()))
+      .on_console_message(|webview, message| {
+        if message.level == ConsoleMessageLevel::Error {
+          eprintln!(
+            "[{}] {} ({}:{})",
+            webview.label(),
+            message.message,
+            message.source_id.unwrap_or_default(),
+            message.line.unwrap_or_default(),
+          );
+        }
+      });
+    let webview = window.add_child(webview_builder, tauri::LogicalPosition::new(0, 0), window.inner_size().unwrap())?;
+    Ok(())
+  });
 ```
   "####
   )]
-  #[must_use]
-  pub fn initialization_script(mut self, script: &str) -> Self {
+  pub fn on_console_message<F: Fn(Webview<R>, ConsoleMessage) + Send + Sync + 'static>(
+    mut self,
+    f: F,
+  ) -> Self {
+    if self.created_options.console_message_handler.is_none() {
+      self
+        .webview_attributes
+        .initialization_scripts
+        .push(console::script());
+    }
     self
-      .webview_attributes
-      .initialization_scripts
-      .push(script.to_string());
+      .created_options
+      .console_message_handler
+      .replace(Arc::new(f));
     self
   }
 
-  /// Set the user agent for the webview
-  #[must_use]
-  pub fn user_agent(mut self, user_agent: &str) -> Self {
+  /// Defines a closure to be executed when the webview requests a context menu, usually on right click,
+  /// to show the default menu, hide it or show a custom [`crate::menu::Menu`] instead.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS**: The [`ContextMenuRequest`] hit-test data is not available, only its position.
+  /// - **Linux**: [`ContextMenuRequest::selected_text`] is not available.
+  /// - **iOS / Android:** Unsupported.
+  ///
+  /// # Examples
+  ///
+  #[cfg_attr(
+    feature = "unstable",
+    doc = r####"
+```rust,no_run
+use tauri::{
+  menu::{Menu, MenuItem},
+  utils::config::WebviewUrl,
+  webview::{ContextMenuAction, WebviewBuilder},
+};
+tauri::Builder::default()
+  .setup(|app| {
+    let window = tauri::window::WindowBuilder::new(app, "label").build()?;
+    let handle = app.handle().clone();
+    let webview_builder = WebviewBuilder::new("core", WebviewUrl::App("index.html".into()))
+      .on_context_menu(move |_webview, request| match request.link_url {
+        Some(_) => Menu::with_items(&handle, &[&MenuItem::new(&handle, "Open link", true, None::<&str>).unwrap()])
+          .map(ContextMenuAction::ShowCustom)
+          .unwrap_or(ContextMenuAction::Suppress),
+        None if request.is_editable => ContextMenuAction::ShowDefault,
+        None => ContextMenuAction::Suppress,
+      });
+    let webview = window.add_child(webview_builder, tauri::LogicalPosition::new(0, 0), window.inner_size().unwrap())?;
+    Ok(())
+  });
+```
+  "####
+  )]
+  #[cfg(desktop)]
+  pub fn on_context_menu<
+    F: Fn(Webview<R>, ContextMenuRequest) -> ContextMenuAction<R> + Send + 'static,
+  >(
+    mut self,
+    f: F,
+  ) -> Self {
+    self.context_menu_handler.replace(Box::new(f));
+    self
+  }
+
+  /// Defines a closure to be executed when a page requests HTTP authentication, e.g. with basic auth,
+  /// returning the credentials to use.
+  ///
+  /// After more than [`MAX_AUTH_CHALLENGE_FAILURES`] rejected credentials for a host,
+  /// the challenge is cancelled without calling the closure to avoid prompting forever.
+  /// The credentials are never logged.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** [`AuthChallenge::is_proxy`] is always `false`, proxy authentication is not reported.
+  /// - **iOS / Android:** Unsupported.
+  ///
+  /// # Examples
+  ///
+  #[cfg_attr(
+    feature = "unstable",
+    doc = r####"
+```rust,no_run
+use tauri::{
+  utils::config::WebviewUrl,
+  webview::{AuthResponse, WebviewBuilder},
+};
+tauri::Builder::default()
+  .setup(|app| {
+    let window = tauri::window::WindowBuilder::new(app, "label").build()?;
+    let webview_builder = WebviewBuilder::new("core", WebviewUrl::External("https://intranet.local".parse().unwrap()))
+      .on_auth_challenge(|_webview, challenge| {
+        if challenge.host == "intranet.local" && challenge.previous_failures == 0 {
+          AuthResponse::Credentials {
+            username: "user".into(),
+            password: std::env::var("INTRANET_PASSWORD").unwrap_or_default(),
+          }
+        } else {
+          AuthResponse::Cancel
+        }
+      });
+    let webview = window.add_child(webview_builder, tauri::LogicalPosition::new(0, 0), window.inner_size().unwrap())?;
+    Ok(())
+  });
+```
+  "####
+  )]
+  pub fn on_auth_challenge<F: Fn(Webview<R>, AuthChallenge) -> AuthResponse + Send + 'static>(
+    mut self,
+    f: F,
+  ) -> Self {
+    self.auth_challenge_handler.replace(Box::new(f));
+    self
+  }
+
+  /// Defines a closure deciding whether a server certificate rejected by the webview is accepted,
+  /// e.g. to load a device on the local network with a self-signed certificate.
+  ///
+  /// Certificates are denied by default. The certificates allowed with [`CertDecision::AllowForHost`]
+  /// are remembered for their host until the app exits, without calling the closure again.
+  /// Compare [`CertError::certificate_der`] with a pinned certificate instead of allowing any certificate.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** [`CertDecision::AllowOnce`] allows the certificate for the host until the webview is closed.
+  ///   Untrusted issuers are reported as [`CertErrorKind::Invalid`].
+  /// - **Linux:** Only the errors of the main frame are reported, and [`CertDecision::AllowOnce`]
+  ///   allows the certificate for the host until the app exits.
+  /// - **iOS / Android:** Unsupported.
+  ///
+  /// # Examples
+  ///
+  #[cfg_attr(
+    feature = "unstable",
+    doc = r####"
+```rust,no_run
+use tauri::{
+  utils::config::WebviewUrl,
+  webview::{CertDecision, WebviewBuilder},
+};
+const PINNED_CERTIFICATE: &[u8] = &[];
+tauri::Builder::default()
+  .setup(|app| {
+    let window = tauri::window::WindowBuilder::new(app, "label").build()?;
+    let webview_builder = WebviewBuilder::new("core", WebviewUrl::External("https://192.168.1.10".parse().unwrap()))
+      .on_certificate_error(|_webview, error| {
+        if error.certificate_der == PINNED_CERTIFICATE {
+          CertDecision::AllowForHost
+        } else {
+          CertDecision::Deny
+        }
+      });
+    let webview = window.add_child(webview_builder, tauri::LogicalPosition::new(0, 0), window.inner_size().unwrap())?;
+    Ok(())
+  });
+```
+  "####
+  )]
+  pub fn on_certificate_error<F: Fn(Webview<R>, CertError) -> CertDecision + Send + 'static>(
+    mut self,
+    f: F,
+  ) -> Self {
+    self.cert_error_handler.replace(Box::new(f));
+    self
+  }
+
+  /// Defines a closure called with the summary of each network request made by the page,
+  /// e.g. to show an in-app network inspector.
+  ///
+  /// The requests to the custom protocols, which can be seen with [`Self::on_web_resource_request`]
+  /// and [`crate::Builder::register_uri_scheme_protocol`], are not reported.
+  /// The summary never includes the request and response bodies.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** All the requests are routed through the `WebResourceRequested` event,
+  ///   which slightly slows down the page loads.
+  /// - **macOS:** WebKit only exposes the custom protocol requests to a `WKURLSchemeHandler`,
+  ///   so only the main frame navigations are reported, as [`ResourceType::Document`],
+  ///   and the duration is the time until the response headers are received.
+  /// - **Linux:** Only the main frame navigations are reported as [`ResourceType::Document`],
+  ///   the other requests are [`ResourceType::Other`].
+  /// - **iOS / Android:** Unsupported.
+  ///
+  /// # Examples
+  ///
+  #[cfg_attr(
+    feature = "unstable",
+    doc = r####"
+```rust,no_run
+use tauri::{utils::config::WebviewUrl, webview::WebviewBuilder};
+tauri::Builder::default()
+  .setup(|app| {
+    let window = tauri::window::WindowBuilder::new(app, "label").build()?;
+    let webview_builder = WebviewBuilder::new("core", WebviewUrl::App("index.html".into()))
+      .on_request_observed(|_webview, request| {
+        println!(
+          "{} {} {:?} in {:?}",
+          request.method, request.url, request.status, request.duration
+        );
+      });
+    let webview = window.add_child(webview_builder, tauri::LogicalPosition::new(0, 0), window.inner_size().unwrap())?;
+    Ok(())
+  });
+```
+  "####
+  )]
+  pub fn on_request_observed<F: Fn(Webview<R>, RequestSummary) + Send + 'static>(
+    mut self,
+    f: F,
+  ) -> Self {
+    self.request_observer.replace(Box::new(f));
+    self
+  }
+
+  /// Takes the options applied once the webview is created, rejecting effects on an opaque webview.
+  pub(crate) fn take_created_options(&mut self) -> crate::Result<CreatedOptions<R>> {
+    let mut options = std::mem::take(&mut self.created_options);
+    options.transparent = self.webview_attributes.transparent;
+    if options.effects.is_some() && !options.transparent {
+      return Err(crate::Error::WebviewNotTransparent(self.label.clone()));
+    }
+    Ok(options)
+  }
+
+  pub(crate) fn into_pending_webview<M: Manager<R>>(
+    mut self,
+    manager: &M,
+    window_label: &str,
+    window_labels: &[String],
+    webview_labels: &[WebviewLabelDef],
+  ) -> crate::Result<PendingWebview<EventLoopMessage, R>> {
+    // the plugins change the webview before its attributes are moved to the runtime
+    manager
+      .manager()
+      .plugins
+      .lock()
+      .expect("poisoned plugin store")
+      .webview_builder(&mut WebviewBuilderParts {
+        label: &self.label,
+        window_label,
+        attributes: &mut self.webview_attributes,
+        navigation_handler: &mut self.navigation_handler,
+      });
+
+    let mut pending = PendingWebview::new(self.webview_attributes, self.label.clone())?;
+    if let Some(profile) = self.profile.take() {
+      manager
+        .manager()
+        .webview
+        .profiles
+        .lock()
+        .unwrap()
+        .insert(pending.label.clone(), profile);
+    }
+    pending.navigation_handler = std::mem::take(&mut self.async_navigation).wrap(
+      self.navigation_handler.take(),
+      manager.manager_owned(),
+      pending.label.clone(),
+      &pending.url,
+    );
+    if let Some(scope) = self.file_access.take() {
+      let scope = scope.canonicalize()?;
+      let initial_file = match &pending.webview_attributes.url {
+        WebviewUrl::External(url) if url.scheme() == "file" => match url.to_file_path() {
+          Ok(path) if scope.allows(&path) => Some(url.clone()),
+          _ => return Err(crate::Error::FileAccessDenied(pending.label, url.clone())),
+        },
+        _ => None,
+      };
+      let manager = manager.manager_owned();
+      let token = manager.webview.file_access.insert(&pending.label, scope);
+      if let Some(url) = initial_file {
+        pending.webview_attributes.url =
+          WebviewUrl::External(file_access::protocol_url(&token, &url));
+      }
+      pending.register_uri_scheme_protocol(
+        file_access::FILE_ACCESS_PROTOCOL,
+        file_access::protocol(manager.clone()),
+      );
+      pending.navigation_handler = Some(file_access::wrap(
+        token,
+        pending.navigation_handler.take(),
+        manager,
+        pending.label.clone(),
+      ));
+    }
+    pending.web_resource_request_handler = self.web_resource_request_handler.take();
+
+    if let Some(download_handler) = self.download_handler.take() {
+      let label = pending.label.clone();
+      let manager = manager.manager_owned();
+      pending.download_handler.replace(Arc::new(move |event| {
+        if let Some(w) = manager.get_webview(&label) {
+          download_handler(
+            w,
+            match event {
+              tauri_runtime::webview::DownloadEvent::Requested { url, destination } => {
+                DownloadEvent::Requested { url, destination }
+              }
+              tauri_runtime::webview::DownloadEvent::Finished { url, path, success } => {
+                DownloadEvent::Finished { url, path, success }
+              }
+            },
+          )
+        } else {
+          false
+        }
+      }));
+    }
+
+    #[cfg(desktop)]
+    if let Some(context_menu_handler) = self.context_menu_handler.take() {
+      let label = pending.label.clone();
+      let manager = manager.manager_owned();
+      pending
+        .context_menu_handler
+        .replace(Box::new(move |request| {
+          let Some(webview) = manager.get_webview(&label) else {
+            return true;
+          };
+          let position = request.position;
+          match context_menu_handler(webview.clone(), request) {
+            ContextMenuAction::ShowDefault => true,
+            ContextMenuAction::Suppress => false,
+            ContextMenuAction::ShowCustom(menu) => {
+              // shown once the native context menu request is handled
+              let webview_ = webview.clone();
+              let _ = webview.run_on_main_thread(move || {
+                if let Err(e) = popup_context_menu(&webview_, &menu, position) {
+                  tauri_utils::debug_eprintln!("failed to show the webview context menu: {e}");
+                }
+              });
+              false
+            }
+          }
+        }));
+    }
+
+    if let Some(auth_challenge_handler) = self.auth_challenge_handler.take() {
+      let label = pending.label.clone();
+      let manager = manager.manager_owned();
+      pending
+        .auth_challenge_handler
+        .replace(Box::new(move |challenge| {
+          if challenge.previous_failures > MAX_AUTH_CHALLENGE_FAILURES {
+            return AuthResponse::Cancel;
+          }
+          match manager.get_webview(&label) {
+            Some(webview) => auth_challenge_handler(webview, challenge),
+            None => AuthResponse::Default,
+          }
+        }));
+    }
+
+    if let Some(cert_error_handler) = self.cert_error_handler.take() {
+      let label = pending.label.clone();
+      let manager = manager.manager_owned();
+      pending.cert_error_handler.replace(Box::new(move |error| {
+        let key = (
+          error.url.host_str().unwrap_or_default().to_string(),
+          error.certificate_der.clone(),
+        );
+        if manager
+          .webview
+          .allowed_certificates
+          .lock()
+          .unwrap()
+          .contains(&key)
+        {
+          return CertDecision::AllowForHost;
+        }
+        let Some(webview) = manager.get_webview(&label) else {
+          return CertDecision::Deny;
+        };
+        let decision = cert_error_handler(webview, error);
+        if decision == CertDecision::AllowForHost {
+          manager
+            .webview
+            .allowed_certificates
+            .lock()
+            .unwrap()
+            .insert(key);
+        }
+        decision
+      }));
+    }
+
+    if let Some(request_observer) = self.request_observer.take() {
+      let label = pending.label.clone();
+      let manager = manager.manager_owned();
+      pending.request_observer.replace(Box::new(move |request| {
+        if let Some(webview) = manager.get_webview(&label) {
+          request_observer(webview, request);
+        }
+      }));
+    }
+
+    if let Some(on_page_load_handler) = self.on_page_load_handler.take() {
+      let label = pending.label.clone();
+      let manager = manager.manager_owned();
+      pending
+        .on_page_load_handler
+        .replace(Box::new(move |url, event| {
+          if let Some(w) = manager.get_webview(&label) {
+            on_page_load_handler(w, PageLoadPayload { url: &url, event });
+          }
+        }));
+    }
+
+    manager.manager().webview.prepare_webview(
+      manager,
+      pending,
+      self.label_reservation,
+      window_label,
+      window_labels,
+      webview_labels,
+    )
+  }
+
+  /// Creates a new webview on the given window.
+  #[cfg(desktop)]
+  pub(crate) fn build(
+    self,
+    window: Window<R>,
+    position: Position,
+    size: Size,
+  ) -> crate::Result<Webview<R>> {
+    self.build_with(window, position, size, |_| ())
+  }
+
+  /// Creates the native webview ahead of time, without a window, so attaching it later is fast.
+  ///
+  /// The webview loads `about:blank` until [`PreparedWebview::attach`] moves it to its window
+  /// and navigates to its URL. Dropping the [`PreparedWebview`] before it is attached closes the webview.
+  ///
+  /// None of the platforms can create a webview without a parent window,
+  /// so prepared webviews are hosted by a hidden window until they are attached.
+  /// This window is destroyed when it has no prepared webview left.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// use tauri::{webview::WebviewBuilder, LogicalPosition, LogicalSize, WebviewUrl};
+  ///
+  /// tauri::Builder::default()
+  ///   .setup(|app| {
+  ///     let tab = WebviewBuilder::new("tab-1", WebviewUrl::App("tab.html".into())).prepare(app)?;
+  ///     let window = tauri::window::WindowBuilder::new(app, "main").build()?;
+  ///     // later, e.g. when the tab is opened
+  ///     let size = window.inner_size()?;
+  ///     tab.attach(&window, LogicalPosition::new(0, 0), size)?;
+  ///     Ok(())
+  ///   });
+  /// ```
+  #[cfg(any(test, all(desktop, feature = "unstable")))]
+  #[cfg_attr(docsrs, doc(cfg(all(desktop, feature = "unstable"))))]
+  pub fn prepare<M: Manager<R>>(self, manager: &M) -> crate::Result<PreparedWebview<R>> {
+    prepared::prepare(self, manager)
+  }
+
+  /// Creates a new webview on the given window, calling `on_pending` before the runtime creates it.
+  #[cfg(desktop)]
+  pub(crate) fn build_with(
+    mut self,
+    window: Window<R>,
+    position: Position,
+    size: Size,
+    on_pending: impl FnOnce(&mut PendingWebview<EventLoopMessage, R>),
+  ) -> crate::Result<Webview<R>> {
+    let window_labels = window
+      .manager()
+      .window
+      .labels()
+      .into_iter()
+      .collect::<Vec<_>>();
+    let webview_labels = window
+      .manager()
+      .webview
+      .webviews_lock()
+      .values()
+      .map(|w| WebviewLabelDef {
+        window_label: w.window.label().to_string(),
+        label: w.label().to_string(),
+      })
+      .collect::<Vec<_>>();
+
+    let app_manager = window.manager();
+    let mut created_options = self.take_created_options()?;
+    let allowed_commands = created_options.allowed_commands.take();
+
+    let mut pending =
+      self.into_pending_webview(&window, window.label(), &window_labels, &webview_labels)?;
+
+    pending.webview_attributes.bounds = Some((position, size));
+    on_pending(&mut pending);
+
+    let webview = match &mut window.runtime() {
+      RuntimeOrDispatch::Dispatch(dispatcher) => dispatcher.create_webview(pending),
+      _ => unimplemented!(),
+    }
+    .map(|webview| {
+      app_manager
+        .webview
+        .attach_webview(window.clone(), webview, allowed_commands)
+    })?;
+
+    created_options.apply(&webview);
+
+    app_manager.webview.eval_script_all(format!(
+      "window.__TAURI_INTERNALS__.metadata.windows = {window_labels_array}.map(function (label) {{ return {{ label: label }} }})",
+      window_labels_array = serde_json::to_string(&app_manager.webview.labels())?,
+    ))?;
+
+    app_manager.emit_filter(
+      "tauri://webview-created",
+      Some(CreatedEvent {
+        label: webview.label().into(),
+      }),
+      |s| match s {
+        EventTarget::Webview { label } => label == webview.label(),
+        _ => false,
+      },
+    )?;
+
+    Ok(webview)
+  }
+}
+
+/// Webview attributes.
+impl<R: Runtime> WebviewBuilder<R> {
+  /// Sets whether clicking an inactive window also clicks through to the webview.
+  #[must_use]
+  pub fn accept_first_mouse(mut self, accept: bool) -> Self {
+    self.webview_attributes.accept_first_mouse = accept;
+    self
+  }
+
+  /// Whether the webview is focused when created, `true` by default.
+  ///
+  /// Use `false` to create a webview without taking the focus from the webview that has it,
+  /// e.g. when adding several webviews to a window at startup.
+  /// The webview is never focused when its window is not focused or is minimized,
+  /// so creating a webview does not restore a minimized window.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS:** The webview becomes the window first responder when focused,
+  ///   so keyboard events keep going to the previously focused webview until this webview is clicked or [`Webview::set_focus`] is called.
+  #[must_use]
+  pub fn focused(mut self, focused: bool) -> Self {
+    self.webview_attributes.focus = focused;
+    self
+  }
+
+  /// Whether mouse and touch input goes through the webview to the content beneath it, `false` by default.
+  ///
+  /// See [`Webview::set_ignore_cursor_events`] for more information.
+  #[must_use]
+  pub fn ignore_cursor_events(mut self, ignore: bool) -> Self {
+    self.webview_attributes.ignore_cursor_events = ignore;
+    self
+  }
+
+  /// Insets the webview content from the webview bounds, no insets by default.
+  ///
+  /// See [`Webview::set_content_insets`] for more information.
+  #[must_use]
+  pub fn content_insets(mut self, insets: Insets) -> Self {
+    self.webview_attributes.content_insets = insets;
+    self
+  }
+
+  /// Adds the provided JavaScript to a list of scripts that should be run after the global object has been created,
+  /// but before the HTML document has been parsed and before any other script included by the HTML document is run.
+  ///
+  /// Since it runs on all top-level document and child frame page navigations,
+  /// it's recommended to check the `window.location` to guard your script from running on unexpected origins.
+  ///
+  /// # Examples
+  ///
+  #[cfg_attr(
+    feature = "unstable",
+    doc = r####"
+```rust
+use tauri::{WindowBuilder, Runtime};
+
+const INIT_SCRIPT: &str = r#"
+  if (window.location.origin === 'https://tauri.app') {
+    console.log("hello world from js init script");
+
+    window.__MY_CUSTOM_PROPERTY__ = { foo: 'bar' };
+  }
+"#;
+
+fn main() {
+  tauri::Builder::default()
+    .setup(|app| {
+      let window = tauri::window::WindowBuilder::new(app, "label").build()?;
+      let webview_builder = tauri::webview::WebviewBuilder::new("label", tauri::WebviewUrl::App("index.html".into()))
+        .initialization_script(INIT_SCRIPT);
+      let webview = window.add_child(webview_builder, tauri::LogicalPosition::new(0, 0), window.inner_size().unwrap())?;
+      Ok(())
+    });
+}
+```
+  "####
+  )]
+  #[must_use]
+  pub fn initialization_script(mut self, script: &str) -> Self {
+    self
+      .webview_attributes
+      .initialization_scripts
+      .push(script.to_string());
+    self
+  }
+
+  /// Adds a script injected before the page scripts are executed in the given isolated world.
+  ///
+  /// The script shares the DOM with the page but not its globals,
+  /// and the globals it sets are visible to the scripts later evaluated in the same world
+  /// with [`Webview::eval_isolated`]. See [`Webview::eval_isolated`] for the platform support.
+  #[must_use]
+  pub fn isolated_initialization_script(mut self, world: &str, script: &str) -> Self {
+    self
+      .webview_attributes
+      .isolated_initialization_scripts
+      .push((world.to_string(), script.to_string()));
+    self
+  }
+
+  /// Set the user agent for the webview
+  #[must_use]
+  pub fn user_agent(mut self, user_agent: &str) -> Self {
     self.webview_attributes.user_agent = Some(user_agent.to_string());
     self
   }
 
-  /// Set additional arguments for the webview.
+  /// Sets the headers of the request loading the webview URL, e.g. an `Authorization` header for a page requiring authentication.
+  ///
+  /// Only the main frame request of the first load receives the headers, not the requests of the page subresources,
+  /// the later navigations or the reloads. See [`Webview::navigate_with_headers`] to navigate with headers.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use tauri::{http::{header::AUTHORIZATION, HeaderMap}, WebviewUrl, WebviewWindowBuilder};
+  ///
+  /// tauri::Builder::default()
+  ///   .setup(|app| {
+  ///     let mut headers = HeaderMap::new();
+  ///     headers.insert(AUTHORIZATION, "Bearer token".parse()?);
+  ///     WebviewWindowBuilder::new(app, "dashboard", WebviewUrl::External("https://dashboard.example.com".parse()?))
+  ///       .initial_headers(headers)
+  ///       .build()?;
+  ///     Ok(())
+  ///   });
+  /// ```
+  #[must_use]
+  pub fn initial_headers(mut self, headers: http::HeaderMap) -> Self {
+    self.webview_attributes.initial_headers.replace(headers);
+    self
+  }
+
+  /// Set additional arguments for the webview.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / Linux / Android / iOS**: Unsupported.
+  ///
+  /// ## Warning
+  ///
+  /// By default wry passes `--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection`
+  /// so if you use this method, you also need to disable these components by yourself if you want.
+  #[must_use]
+  pub fn additional_browser_args(mut self, additional_args: &str) -> Self {
+    self.webview_attributes.additional_browser_args = Some(additional_args.to_string());
+    self
+  }
+
+  /// Data directory for the webview.
+  #[must_use]
+  pub fn data_directory(mut self, data_directory: PathBuf) -> Self {
+    self
+      .webview_attributes
+      .data_directory
+      .replace(data_directory);
+    self
+  }
+
+  /// Disables the file drop handler. This is required to use drag and drop APIs on the front end on Windows.
+  #[must_use]
+  pub fn disable_file_drop_handler(mut self) -> Self {
+    self.webview_attributes.file_drop_handler_enabled = false;
+    self
+  }
+
+  /// Enables clipboard access for the page rendered on **Linux** and **Windows**.
+  ///
+  /// **macOS** doesn't provide such method and is always enabled by default,
+  /// but you still need to add menu item accelerators to use shortcuts.
+  #[must_use]
+  pub fn enable_clipboard_access(mut self) -> Self {
+    self.webview_attributes.clipboard = true;
+    self
+  }
+
+  /// Enable or disable incognito mode for the WebView..
+  ///
+  ///  ## Platform-specific:
+  ///
+  ///  **Android**: Unsupported.
+  #[must_use]
+  pub fn incognito(mut self, incognito: bool) -> Self {
+    self.webview_attributes.incognito = incognito;
+    self
+  }
+
+  /// Set a proxy URL for the WebView for all network requests.
+  ///
+  /// Must be either a `http://` or a `socks5://` URL.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS**: Requires the `macos-proxy` feature flag and only compiles for macOS 14+.
+  #[must_use]
+  pub fn proxy_url(mut self, url: Url) -> Self {
+    self.webview_attributes.proxy_url = Some(url);
+    self
+  }
+
+  /// Sets how timers and rendering behave while the webview is hidden or in the background.
+  /// The platform default is used if not set.
+  ///
+  /// Policies a platform can't honor are ignored with a log, see [`Webview::set_background_throttling`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Only [`BackgroundThrottlingPolicy::Disabled`] has an effect, which adds browser arguments
+  ///   disabling the background timer throttling and renderer backgrounding.
+  /// - **macOS**: Requires macOS 14+.
+  /// - **Linux / iOS / Android**: Unsupported.
+  #[must_use]
+  pub fn background_throttling(mut self, policy: BackgroundThrottlingPolicy) -> Self {
+    self.webview_attributes.background_throttling = Some(policy);
+    self
+  }
+
+  /// Enables or disables the spell checker, which marks the misspelled words of editable content.
+  /// The platform default is used if not set.
+  ///
+  /// The flag is accepted on all platforms, even those that can't change it later with [`Webview::set_spellcheck`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: Applies to all webviews sharing the data directory of the webview.
+  /// - **Windows / macOS / iOS / Android**: Sets the `spellcheck` attribute of the document element,
+  ///   unless the page sets it.
+  #[must_use]
+  pub fn spellcheck(mut self, enabled: bool) -> Self {
+    self.webview_attributes.spellcheck = Some(enabled);
+    self
+  }
+
+  /// Allows the webview to load the `file://` URLs of the directories of the scope.
+  ///
+  /// The webview is not given access to the file system: the files of the scope are served by the `tauri-file`
+  /// custom protocol, which the `file://` URLs of the scope are redirected to, so the relative URLs of the pages
+  /// keep working and every navigation and subresource request is checked against the scope.
+  /// The pages can't request `file://` URLs directly.
+  ///
+  /// The navigations outside of the scope are denied and reported to the webview
+  /// with the [`BLOCKED_NAVIGATION_EVENT`], before the [`Self::on_navigation`] handler is called.
+  /// Creating the webview fails if a directory of the scope does not exist or if its initial URL is outside of the scope.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use tauri::webview::{FileAccessScope, WebviewUrl, WebviewWindowBuilder};
+  ///
+  /// tauri::Builder::default()
+  ///   .setup(|app| {
+  ///     let manuals = std::path::Path::new("/opt/manuals");
+  ///     let url = tauri::Url::from_file_path(manuals.join("index.html")).unwrap();
+  ///     WebviewWindowBuilder::new(app, "manuals", WebviewUrl::External(url))
+  ///       .allow_file_access(FileAccessScope::new().allow_directory(manuals, true))
+  ///       .build()?;
+  ///     Ok(())
+  ///   });
+  /// ```
+  #[must_use]
+  pub fn allow_file_access(mut self, scope: FileAccessScope) -> Self {
+    self.file_access.replace(scope);
+    self
+  }
+
+  /// Applies platform background effects behind the webview, see [`Webview::set_effects`].
+  ///
+  /// Requires the webview to be [transparent](Self::transparent),
+  /// building an opaque webview with effects fails with [`crate::Error::WebviewNotTransparent`].
+  #[must_use]
+  pub fn effects(mut self, effects: WindowEffectsConfig) -> Self {
+    self.created_options.effects.replace(effects);
+    self
+  }
+
+  /// Lets this webview receive the key events of the [window accelerators](crate::window::Window::register_accelerator)
+  /// instead of the window handling them first. Only use it for trusted content. Defaults to `false`.
+  #[cfg(desktop)]
+  #[must_use]
+  pub fn allow_accelerator_override(mut self, allow: bool) -> Self {
+    self.created_options.allow_accelerator_override = allow;
+    self
+  }
+
+  /// Registers a webview event listener, called for the events of the webview from its creation.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use tauri::{webview::WebviewBuilder, WebviewEvent};
+  ///
+  /// let builder = WebviewBuilder::new("main", Default::default()).on_webview_event(|event| {
+  ///   if let WebviewEvent::Navigation { url } = event {
+  ///     println!("navigated to {url}");
+  ///   }
+  /// });
+  /// ```
+  #[must_use]
+  pub fn on_webview_event<F: Fn(&WebviewEvent) + Send + Sync + 'static>(mut self, f: F) -> Self {
+    self.created_options.event_handlers.push(Arc::new(f));
+    self
+  }
+
+  /// Enable or disable transparency for the WebView.
+  #[cfg(any(not(target_os = "macos"), feature = "macos-private-api"))]
+  #[cfg_attr(
+    docsrs,
+    doc(cfg(any(not(target_os = "macos"), feature = "macos-private-api")))
+  )]
+  #[must_use]
+  pub fn transparent(mut self, transparent: bool) -> Self {
+    self.webview_attributes.transparent = transparent;
+    self
+  }
+
+  /// Sets the webview to automatically grow and shrink its size and position when the parent window resizes.
+  #[must_use]
+  pub fn auto_resize(mut self) -> Self {
+    self.webview_attributes.auto_resize = true;
+    self
+  }
+
+  /// Sets the browsing session of the webview.
   ///
-  /// ## Platform-specific
+  /// Webviews created with the same [`SessionId`] share cookies, cache and local storage,
+  /// while [`SessionId::ephemeral`] creates a session that is not persisted, like [`Self::incognito`].
+  /// When not set, the webview uses the default session of the process.
   ///
-  /// - **macOS / Linux / Android / iOS**: Unsupported.
+  /// Without a [`Self::data_directory`], the data of a shared session is stored in
+  /// the `sessions/<id>` subdirectory of the default data directory, with the identifier percent-encoded.
   ///
-  /// ## Warning
+  /// Building the webview fails if an ephemeral session is used with a [`Self::data_directory`],
+  /// if a shared session is used with [`Self::incognito`],
+  /// or if webviews sharing a session use different data directories.
   ///
-  /// By default wry passes `--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection`
-  /// so if you use this method, you also need to disable these components by yourself if you want.
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Webviews sharing a session use the same user data folder.
+  /// - **macOS / iOS**: All persistent webviews already share the default website data store and process pool.
+  /// - **Linux**: Webviews sharing a session use the same `WebContext`, which keeps a single handler
+  ///   for the IPC and app protocols, so building a webview fails while another webview uses the session.
+  /// - **Android**: Unsupported.
   #[must_use]
-  pub fn additional_browser_args(mut self, additional_args: &str) -> Self {
-    self.webview_attributes.additional_browser_args = Some(additional_args.to_string());
+  pub fn shared_session(mut self, session: SessionId) -> Self {
+    self.webview_attributes.session.replace(session);
     self
   }
 
-  /// Data directory for the webview.
+  /// Binds the webview to a profile, storing its data in the profile directory
+  /// and using the [session](crate::profile::Profile::session) of the profile.
+  ///
+  /// A profile cannot be deleted while a webview bound to it is alive.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS**: The webview data is not separated, see [`Self::shared_session`].
   #[must_use]
-  pub fn data_directory(mut self, data_directory: PathBuf) -> Self {
-    self
-      .webview_attributes
-      .data_directory
-      .replace(data_directory);
+  pub fn profile(mut self, profile: &crate::profile::Profile<R>) -> Self {
+    self.webview_attributes.data_directory = Some(profile.webview_data_directory());
+    self.webview_attributes.session = Some(profile.session());
+    self.profile = Some(profile.name().into());
     self
   }
 
-  /// Disables the file drop handler. This is required to use drag and drop APIs on the front end on Windows.
+  /// Gives mutable access to the [`WebviewAttributes`] used to create the webview,
+  /// allowing the use of attributes that do not have a builder method yet.
   #[must_use]
-  pub fn disable_file_drop_handler(mut self) -> Self {
-    self.webview_attributes.file_drop_handler_enabled = false;
+  pub fn with_attributes<F: FnOnce(&mut WebviewAttributes)>(mut self, f: F) -> Self {
+    f(&mut self.webview_attributes);
     self
   }
 
-  /// Enables clipboard access for the page rendered on **Linux** and **Windows**.
+  /// Overrides the webview attributes with the given JSON object,
+  /// allowing config-driven webviews to use attributes that are not part of [`WindowConfig`].
   ///
-  /// **macOS** doesn't provide such method and is always enabled by default,
-  /// but you still need to add menu item accelerators to use shortcuts.
+  /// The keys are the camelCase attribute names, see [`WebviewAttributes::apply_overrides`].
+  /// Returns an error if the object contains unknown keys or invalid values.
+  pub fn try_build_config_overrides(mut self, overrides: serde_json::Value) -> crate::Result<Self> {
+    self.webview_attributes.apply_overrides(overrides)?;
+    Ok(self)
+  }
+
+  /// Restricts the commands the webview can invoke, on top of the capabilities.
+  ///
+  /// Commands not in the list are rejected with an [`crate::ipc::IpcError::CommandDenied`] error.
+  /// All commands are allowed by default.
   #[must_use]
-  pub fn enable_clipboard_access(mut self) -> Self {
-    self.webview_attributes.clipboard = true;
+  pub fn allowed_commands(mut self, allowlist: AllowList) -> Self {
+    self.created_options.allowed_commands.replace(allowlist);
     self
   }
 
-  /// Enable or disable incognito mode for the WebView..
-  ///
-  ///  ## Platform-specific:
-  ///
-  ///  **Android**: Unsupported.
+  /// Emits the `tauri://webview-metrics` event with the [`WebviewMetrics`] of the webview at the given interval.
   #[must_use]
-  pub fn incognito(mut self, incognito: bool) -> Self {
-    self.webview_attributes.incognito = incognito;
+  pub fn metrics_interval(mut self, interval: Duration) -> Self {
+    self.created_options.metrics_interval.replace(interval);
     self
   }
 
-  /// Set a proxy URL for the WebView for all network requests.
-  ///
-  /// Must be either a `http://` or a `socks5://` URL.
+  /// Emits the `tauri://scroll` event with the [`WebviewScroll`] of the document when it scrolls,
+  /// at most once per `throttle`. The last position of a scroll is always emitted.
   ///
-  /// ## Platform-specific
+  /// Only the document scroll is reported, nested scroll containers are not.
+  #[must_use]
+  pub fn emit_scroll_events(mut self, throttle: Duration) -> Self {
+    let scripts = &mut self.webview_attributes.initialization_scripts;
+    if let Some(previous) = self.created_options.scroll_events.replace(throttle) {
+      let previous = scroll::script(previous);
+      scripts.retain(|script| *script != previous);
+    }
+    scripts.push(scroll::script(throttle));
+    self
+  }
+
+  /// Suspends the webview when its window is hidden and resumes it when the window is shown.
   ///
-  /// - **macOS**: Requires the `macos-proxy` feature flag and only compiles for macOS 14+.
+  /// See [`Webview::set_suspended`] for more information.
   #[must_use]
-  pub fn proxy_url(mut self, url: Url) -> Self {
-    self.webview_attributes.proxy_url = Some(url);
+  pub fn auto_suspend_on_hidden(mut self, auto_suspend: bool) -> Self {
+    self.created_options.auto_suspend_on_hidden = auto_suspend;
     self
   }
 
-  /// Enable or disable transparency for the WebView.
-  #[cfg(any(not(target_os = "macos"), feature = "macos-private-api"))]
-  #[cfg_attr(
-    docsrs,
-    doc(cfg(any(not(target_os = "macos"), feature = "macos-private-api")))
-  )]
+  /// Maximum number of events queued while the webview is suspended.
+  /// Defaults to [`DEFAULT_SUSPENDED_EVENT_QUEUE_LIMIT`].
   #[must_use]
-  pub fn transparent(mut self, transparent: bool) -> Self {
-    self.webview_attributes.transparent = transparent;
+  pub fn suspended_event_queue_limit(mut self, limit: usize) -> Self {
+    self.created_options.suspended_event_queue_limit = limit;
     self
   }
 
-  /// Sets the webview to automatically grow and shrink its size and position when the parent window resizes.
+  /// Whether the key authenticating the IPC requests is regenerated on every main frame navigation. Defaults to `true`.
+  ///
+  /// Every document running the initialization script, including the frames on Windows and macOS,
+  /// requests the key of the current page before any page script runs,
+  /// so a key captured from a previous page can't be replayed after the webview navigates.
+  /// Requests without the current key are rejected with an [`crate::ipc::IpcError::InvalidInvokeKey`] error,
+  /// which the `invoke` function recovers from by requesting the new key,
+  /// and channels created by a previous page stop receiving messages.
+  ///
+  /// Disable it if the app embeds frames whose channels must outlive a navigation of the main frame.
   #[must_use]
-  pub fn auto_resize(mut self) -> Self {
-    self.webview_attributes.auto_resize = true;
+  pub fn rotate_invoke_key(mut self, rotate: bool) -> Self {
+    self.created_options.rotate_invoke_key = rotate;
     self
   }
 }
@@ -797,6 +1892,17 @@ pub struct Webview<R: Runtime> {
   pub(crate) window: Window<R>,
   /// The webview created by the runtime.
   pub(crate) webview: DetachedWebview<EventLoopMessage, R>,
+  pub(crate) suspension: Arc<Mutex<suspend::Suspension>>,
+  pub(crate) allowed_commands: Arc<Mutex<Option<AllowList>>>,
+  pub(crate) invoke_key: Arc<Mutex<invoke_key::InvokeKey>>,
+  pub(crate) event_sequences: Arc<Mutex<event_sequence::EventSequences>>,
+  pub(crate) event_bridge: Arc<crate::event::EventBridge>,
+  pub(crate) console_message_handler: Arc<Mutex<Option<Arc<console::ConsoleMessageHandler<R>>>>>,
+  pub(crate) messages: Arc<Mutex<messaging::MessageQueue>>,
+  pub(crate) transparent: Arc<AtomicBool>,
+  pub(crate) scroll_events: Arc<AtomicBool>,
+  /// Cleared by the manager before the webview is destroyed, see [`Webview::is_alive`].
+  pub(crate) alive: Arc<AtomicBool>,
 }
 
 impl<R: Runtime> std::fmt::Debug for Webview<R> {
@@ -813,6 +1919,16 @@ impl<R: Runtime> Clone for Webview<R> {
     Self {
       window: self.window.clone(),
       webview: self.webview.clone(),
+      suspension: self.suspension.clone(),
+      allowed_commands: self.allowed_commands.clone(),
+      invoke_key: self.invoke_key.clone(),
+      event_sequences: self.event_sequences.clone(),
+      event_bridge: self.event_bridge.clone(),
+      console_message_handler: self.console_message_handler.clone(),
+      messages: self.messages.clone(),
+      transparent: self.transparent.clone(),
+      scroll_events: self.scroll_events.clone(),
+      alive: self.alive.clone(),
     }
   }
 }
@@ -835,8 +1951,25 @@ impl<R: Runtime> PartialEq for Webview<R> {
 /// Base webview functions.
 impl<R: Runtime> Webview<R> {
   /// Create a new webview that is attached to the window.
-  pub(crate) fn new(window: Window<R>, webview: DetachedWebview<EventLoopMessage, R>) -> Self {
-    Self { window, webview }
+  pub(crate) fn new(
+    window: Window<R>,
+    webview: DetachedWebview<EventLoopMessage, R>,
+    allowed_commands: Option<AllowList>,
+  ) -> Self {
+    Self {
+      window,
+      webview,
+      suspension: Default::default(),
+      allowed_commands: Arc::new(Mutex::new(allowed_commands)),
+      invoke_key: Default::default(),
+      event_sequences: Default::default(),
+      event_bridge: Default::default(),
+      console_message_handler: Default::default(),
+      messages: Default::default(),
+      transparent: Default::default(),
+      scroll_events: Default::default(),
+      alive: Arc::new(AtomicBool::new(true)),
+    }
   }
 
   /// Initializes a webview builder with the given window label and URL to load on the webview.
@@ -850,27 +1983,68 @@ impl<R: Runtime> Webview<R> {
 
   /// Runs the given closure on the main thread.
   pub fn run_on_main_thread<F: FnOnce() + Send + 'static>(&self, f: F) -> crate::Result<()> {
-    self
-      .webview
-      .dispatcher
-      .run_on_main_thread(f)
-      .map_err(Into::into)
+    self.dispatcher()?.run_on_main_thread(f).map_err(Into::into)
   }
 
   /// The webview label.
   pub fn label(&self) -> &str {
     &self.webview.label
   }
+
+  /// Whether the webview can still be used.
+  ///
+  /// Returns `false` once the webview is closed or its window destroyed, and when the app exits.
+  /// The methods using the webview then fail with [`crate::Error::WebviewDestroyed`].
+  pub fn is_alive(&self) -> bool {
+    self.alive.load(Ordering::SeqCst) && !self.manager().is_shutting_down()
+  }
+
+  /// The runtime webview, or [`crate::Error::WebviewDestroyed`] if it is no longer alive.
+  pub(crate) fn dispatcher(&self) -> crate::Result<&R::WebviewDispatcher> {
+    if self.is_alive() {
+      Ok(&self.webview.dispatcher)
+    } else {
+      Err(crate::Error::WebviewDestroyed(self.label().into()))
+    }
+  }
+
+  /// Registers a webview event listener.
+  ///
+  /// See [`Self::subscribe_events`] to receive the events as a stream.
+  pub fn on_webview_event<F: Fn(&WebviewEvent) + Send + Sync + 'static>(&self, f: F) {
+    self
+      .manager()
+      .webview
+      .event_listeners
+      .add(self.label(), Arc::new(f));
+  }
 }
 
 /// Desktop webview setters and actions.
 #[cfg(desktop)]
 impl<R: Runtime> Webview<R> {
+  /// Registers a menu event listener called when a menu item is activated while this webview is focused,
+  /// see [`crate::menu::MenuEvent::webview`].
+  ///
+  /// Replaces the previous listener of the webview, and is removed when the webview is destroyed.
+  pub fn on_menu_event<F: Fn(&Webview<R>, crate::menu::MenuEvent) + Send + Sync + 'static>(
+    &self,
+    f: F,
+  ) {
+    self
+      .manager()
+      .menu
+      .webview_event_listeners
+      .lock()
+      .unwrap()
+      .insert(self.label().to_string(), Box::new(f));
+  }
+
   /// Opens the dialog to prints the contents of the webview.
   /// Currently only supported on macOS on `wry`.
   /// `window.print()` works on all platforms.
   pub fn print(&self) -> crate::Result<()> {
-    self.webview.dispatcher.print().map_err(Into::into)
+    self.dispatcher()?.print().map_err(Into::into)
   }
 
   /// Closes this webview.
@@ -878,41 +2052,107 @@ impl<R: Runtime> Webview<R> {
     if self.window.webview_window {
       self.window.close()
     } else {
-      self.webview.dispatcher.close()?;
+      self.dispatcher()?.close()?;
       self.manager().on_webview_close(self.label());
       Ok(())
     }
   }
 
   /// Resizes this webview.
+  ///
+  /// The webview is excluded from the [`Window::set_webview_layout`] layout.
   pub fn set_size<S: Into<Size>>(&self, size: S) -> crate::Result<()> {
     if self.window.webview_window {
       self.window.set_size(size.into())
     } else {
-      self
-        .webview
-        .dispatcher
-        .set_size(size.into())
-        .map_err(Into::into)
+      #[cfg(desktop)]
+      self.window.exclude_from_webview_layout(self.label());
+      self.dispatcher()?.set_size(size.into()).map_err(Into::into)
     }
   }
 
   /// Sets this webviews's position.
+  ///
+  /// The webview is excluded from the [`Window::set_webview_layout`] layout.
   pub fn set_position<Pos: Into<Position>>(&self, position: Pos) -> crate::Result<()> {
     if self.window.webview_window {
       self.window.set_position(position.into())
     } else {
+      #[cfg(desktop)]
+      self.window.exclude_from_webview_layout(self.label());
       self
-        .webview
-        .dispatcher
+        .dispatcher()?
         .set_position(position.into())
         .map_err(Into::into)
     }
   }
 
+  /// Sets this webviews's position and size at once.
+  ///
+  /// The webview is excluded from the [`Window::set_webview_layout`] layout.
+  pub fn set_bounds<Pos: Into<Position>, S: Into<Size>>(
+    &self,
+    position: Pos,
+    size: S,
+  ) -> crate::Result<()> {
+    if self.window.webview_window {
+      self.window.set_position(position.into())?;
+      self.window.set_size(size.into())
+    } else {
+      #[cfg(desktop)]
+      self.window.exclude_from_webview_layout(self.label());
+      self
+        .dispatcher()?
+        .set_bounds(position.into(), size.into())
+        .map_err(Into::into)
+    }
+  }
+
+  /// Moves this webview in the stacking order of the child webviews and native views of its window,
+  /// `0` being the bottom-most one. See [`crate::window::NativeViewHandle::set_z_order`].
+  ///
+  /// This has no effect on the webview of a [`crate::WebviewWindow`].
+  pub fn set_z_order(&self, index: usize) -> crate::Result<()> {
+    self.dispatcher()?.set_z_order(index).map_err(Into::into)
+  }
+
   /// Focus the webview.
   pub fn set_focus(&self) -> crate::Result<()> {
-    self.webview.dispatcher.set_focus().map_err(Into::into)
+    self.dispatcher()?.set_focus().map_err(Into::into)
+  }
+
+  /// Sets whether mouse and touch input goes through the webview to the content beneath it,
+  /// such as another webview of the same window.
+  ///
+  /// The webview keeps its content and can still be focused with [`Self::set_focus`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Only supported by child webviews, use [`Window::set_ignore_cursor_events`] for the webview of a [`crate::WebviewWindow`].
+  /// - **iOS / Android**: Unsupported.
+  pub fn set_ignore_cursor_events(&self, ignore: bool) -> crate::Result<()> {
+    self
+      .dispatcher()?
+      .set_ignore_cursor_events(ignore)
+      .map_err(Into::into)
+  }
+
+  /// Insets the webview content from the webview bounds, in logical pixels, without reloading the page.
+  ///
+  /// This is useful to keep the content of a webview clear of the traffic lights
+  /// with [`crate::TitleBarStyle::Overlay`] on macOS.
+  /// The bounds of the webview, including the ones computed by [`Window::set_webview_layout`], include the insets.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS:** The webview of a [`crate::WebviewWindow`] is inset from the window content view.
+  /// - **Windows / Linux:** Only supported by child webviews, the webview of a [`crate::WebviewWindow`] is not affected.
+  /// - **iOS / Android:** Unsupported.
+  pub fn set_content_insets(&self, insets: Insets) -> crate::Result<()> {
+    self
+      .dispatcher()?
+      .set_content_insets(insets)
+      .map_err(Into::into)
   }
 
   /// Returns the webview position.
@@ -923,7 +2163,7 @@ impl<R: Runtime> Webview<R> {
     if self.window.webview_window {
       self.window.inner_position()
     } else {
-      self.webview.dispatcher.position().map_err(Into::into)
+      self.dispatcher()?.position().map_err(Into::into)
     }
   }
 
@@ -932,9 +2172,23 @@ impl<R: Runtime> Webview<R> {
     if self.window.webview_window {
       self.window.inner_size()
     } else {
-      self.webview.dispatcher.size().map_err(Into::into)
+      self.dispatcher()?.size().map_err(Into::into)
     }
   }
+
+  /// Returns the physical size of the webview content, independently of its window.
+  ///
+  /// Listen to [`WebviewEvent::Resized`] with [`Self::on_webview_event`] to know when it changes,
+  /// including when the webview is laid out again after its window is resized.
+  pub fn inner_size(&self) -> crate::Result<PhysicalSize<u32>> {
+    self.dispatcher()?.size().map_err(Into::into)
+  }
+
+  /// Returns the scale factor of the webview content, also known as its device pixel ratio,
+  /// mapping its logical pixels to physical pixels.
+  pub fn scale_factor(&self) -> crate::Result<f64> {
+    self.dispatcher()?.scale_factor().map_err(Into::into)
+  }
 }
 
 /// Webview APIs.
@@ -986,232 +2240,349 @@ fn main() {
           let () = msg_send![webview.ns_window(), setBackgroundColor: bg_color];
         }
 
-        #[cfg(target_os = "android")]
-        {
-          use jni::objects::JValue;
-          webview.jni_handle().exec(|env, _, webview| {
-            env.call_method(webview, "zoomBy", "(F)V", &[JValue::Float(4.)]).unwrap();
-          })
-        }
-      });
-      Ok(())
-  });
-}
-```
-  "####
-  )]
-  #[cfg(feature = "wry")]
-  #[cfg_attr(docsrs, doc(feature = "wry"))]
-  pub fn with_webview<F: FnOnce(PlatformWebview) + Send + 'static>(
+        #[cfg(target_os = "android")]
+        {
+          use jni::objects::JValue;
+          webview.jni_handle().exec(|env, _, webview| {
+            env.call_method(webview, "zoomBy", "(F)V", &[JValue::Float(4.)]).unwrap();
+          })
+        }
+      });
+      Ok(())
+  });
+}
+```
+  "####
+  )]
+  #[cfg(feature = "wry")]
+  #[cfg_attr(docsrs, doc(feature = "wry"))]
+  pub fn with_webview<F: FnOnce(PlatformWebview) + Send + 'static>(
+    &self,
+    f: F,
+  ) -> crate::Result<()> {
+    self
+      .dispatcher()?
+      .with_webview(|w| f(PlatformWebview(*w.downcast().unwrap())))
+      .map_err(Into::into)
+  }
+
+  /// Like [`Self::with_webview`], but the closure returns a future that is awaited on the main thread,
+  /// so it can await the asynchronous APIs of the platform webview.
+  ///
+  /// The future is not required to be [`Send`], see [`crate::async_runtime::spawn_local_on_main`]
+  /// for how it is driven by the event loop. A panic of the future is returned as a
+  /// [`crate::Error::LocalTaskPanicked`] error.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// #[tauri::command]
+  /// async fn webview_ready(webview: tauri::Webview) -> tauri::Result<bool> {
+  ///   webview
+  ///     .with_webview_async(|platform| async move {
+  ///       // await the completion handlers of the platform webview here
+  ///       let _ = platform;
+  ///       true
+  ///     })
+  ///     .await
+  /// }
+  /// ```
+  #[cfg(feature = "wry")]
+  #[cfg_attr(docsrs, doc(feature = "wry"))]
+  pub fn with_webview_async<F, Fut, T>(
+    &self,
+    f: F,
+  ) -> impl Future<Output = crate::Result<T>> + Send + 'static
+  where
+    F: FnOnce(PlatformWebview) -> Fut + Send + 'static,
+    Fut: Future<Output = T> + 'static,
+    T: Send + 'static,
+  {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let app = self.app_handle().clone();
+    let dispatched = self.with_webview(move |webview| {
+      let _ = tx.send(crate::async_runtime::spawn_local(&app, f(webview)));
+    });
+
+    async move {
+      dispatched?;
+      rx.await
+        .map_err(|_| crate::Error::Runtime(tauri_runtime::Error::FailedToReceiveMessage))?
+        .join()
+        .await
+    }
+  }
+
+  /// Returns the current url of the webview.
+  pub fn url(&self) -> crate::Result<Url> {
+    self.dispatcher()?.url().map_err(Into::into)
+  }
+
+  /// Navigates the webview to the defined url.
+  pub fn navigate(&mut self, url: Url) -> crate::Result<()> {
+    self.dispatcher()?.navigate(url).map_err(Into::into)
+  }
+
+  /// Navigates the webview to the defined url, sending the headers with the request loading it,
+  /// e.g. an `Authorization` header for a page requiring authentication.
+  ///
+  /// Only the main frame request receives the headers, not the requests of the page subresources.
+  /// See [`WebviewBuilder::initial_headers`] to send headers with the first load of the webview.
+  pub fn navigate_with_headers(&mut self, url: Url, headers: http::HeaderMap) -> crate::Result<()> {
+    self
+      .dispatcher()?
+      .navigate_with_headers(url, headers)
+      .map_err(Into::into)
+  }
+
+  /// Returns the theme used by the webview content,
+  /// either the one forced with [`Self::set_theme`] or the system theme.
+  pub fn theme(&self) -> crate::Result<Theme> {
+    self.dispatcher()?.theme().map_err(Into::into)
+  }
+
+  /// Forces the `prefers-color-scheme` of the webview content or follows the system theme if `None` is provided.
+  ///
+  /// Emits the `tauri://theme-changed` event to this webview with the new theme.
+  /// The event is also emitted to the webviews following the system theme when it changes,
+  /// while webviews with a forced theme do not receive it.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: Sets the GTK dark theme preference, which affects the whole application.
+  ///   The theme forced last applies, and the system preference is restored once no webview forces a theme.
+  /// - **iOS / Android:** Unsupported.
+  pub fn set_theme(&self, theme: Option<Theme>) -> crate::Result<()> {
+    self.dispatcher()?.set_theme(theme)?;
+    {
+      let mut forced_themes = self.manager().webview.forced_themes.lock().unwrap();
+      if theme.is_some() {
+        forced_themes.insert(self.label().to_string());
+      } else {
+        forced_themes.remove(self.label());
+      }
+    }
+    self.emit_to(
+      EventTarget::webview(self.label()),
+      crate::manager::window::WINDOW_THEME_CHANGED,
+      self.theme()?.to_string(),
+    )
+  }
+
+  /// Changes how timers and rendering behave while the webview is hidden or in the background.
+  ///
+  /// Returns a [`tauri_runtime::Error::Unsupported`] runtime error if the platform can't change the policy of a running webview.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS**: Requires macOS 14+.
+  /// - **Windows / Linux / iOS / Android**: Unsupported, the Windows policy can only be set with [`WebviewBuilder::background_throttling`].
+  pub fn set_background_throttling(&self, policy: BackgroundThrottlingPolicy) -> crate::Result<()> {
+    self
+      .dispatcher()?
+      .set_background_throttling(policy)
+      .map_err(Into::into)
+  }
+
+  /// Enables or disables the spell checker, see [`WebviewBuilder::spellcheck`].
+  ///
+  /// Returns a [`tauri_runtime::Error::Unsupported`] runtime error if the platform can't change the spell checker of a running webview.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: Applies to all webviews sharing the data directory of the webview.
+  /// - **Windows / macOS / iOS / Android**: Unsupported, use [`WebviewBuilder::spellcheck`].
+  pub fn set_spellcheck(&self, enabled: bool) -> crate::Result<()> {
+    self
+      .dispatcher()?
+      .set_spellcheck(enabled)
+      .map_err(Into::into)
+  }
+
+  /// Sets the languages of the spell checker, as language codes such as `en_US`.
+  ///
+  /// Returns a [`tauri_runtime::Error::Unsupported`] runtime error if the platform can't choose the languages.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: Applies to all webviews sharing the data directory of the webview.
+  /// - **Windows / macOS / iOS / Android**: Unsupported, the system languages are used.
+  pub fn set_spellcheck_languages(&self, languages: Vec<String>) -> crate::Result<()> {
+    self
+      .dispatcher()?
+      .set_spellcheck_languages(languages)
+      .map_err(Into::into)
+  }
+
+  /// Applies platform background effects behind the webview region, pass [`None`] to clear them.
+  ///
+  /// The webview must be transparent for the effects to show,
+  /// so this fails with [`crate::Error::WebviewNotTransparent`] on an opaque webview.
+  /// Changing the effects of a webview updates them in place instead of recreating them.
+  ///
+  /// See [`crate::window::EffectsBuilder`] for a convenient builder for [`WindowEffectsConfig`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS**: The effect is drawn by a `NSVisualEffectView` behind the webview content,
+  ///   following the webview bounds. Only the first macOS effect of the list is used.
+  /// - **Windows**: Mica, Acrylic and Blur can only be applied to the whole window,
+  ///   so this is the same as calling [`Window::set_effects`] on the webview window.
+  /// - **Linux / iOS / Android**: Unsupported, returns a [`tauri_runtime::Error::Unsupported`] runtime error.
+  pub fn set_effects<E: Into<Option<WindowEffectsConfig>>>(&self, effects: E) -> crate::Result<()> {
+    let effects = effects.into();
+    if effects.is_some() && !self.transparent.load(Ordering::Relaxed) {
+      return Err(crate::Error::WebviewNotTransparent(self.label().into()));
+    }
+    crate::vibrancy::set_webview_effects(self, effects)
+  }
+
+  /// Whether the webview audio is muted, see [`Self::set_muted`].
+  pub fn is_muted(&self) -> crate::Result<bool> {
+    self.dispatcher()?.is_muted().map_err(Into::into)
+  }
+
+  /// Mutes or unmutes the webview audio. The state is kept when the webview navigates.
+  ///
+  /// Returns a [`tauri_runtime::Error::Unsupported`] runtime error if the platform can't mute the webview.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS**: Requires the `macos-private-api` feature flag.
+  /// - **iOS / Android**: Unsupported.
+  pub fn set_muted(&self, muted: bool) -> crate::Result<()> {
+    self.dispatcher()?.set_muted(muted).map_err(Into::into)
+  }
+
+  /// Pauses all audio and video playing in the webview.
+  ///
+  /// Listen to [`WebviewEvent::MediaPlaybackChanged`] with [`Self::on_webview_event`] to know when the webview plays audio.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / Linux / macOS < 11.3**: Pauses the `audio` and `video` elements of the main frame document.
+  pub fn pause_all_media(&self) -> crate::Result<()> {
+    self.dispatcher()?.pause_all_media().map_err(Into::into)
+  }
+
+  /// Captures the visible content of the webview as a PNG image.
+  ///
+  /// The capture happens on the main thread, `f` is called with the encoded image once it completes.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS**: Requires macOS 10.13+.
+  /// - **iOS / Android**: Unsupported.
+  pub fn screenshot<F: FnOnce(crate::Result<Vec<u8>>) + Send + 'static>(
     &self,
     f: F,
   ) -> crate::Result<()> {
     self
-      .webview
-      .dispatcher
-      .with_webview(|w| f(PlatformWebview(*w.downcast().unwrap())))
+      .dispatcher()?
+      .screenshot(move |result| f(result.map_err(Into::into)))
       .map_err(Into::into)
   }
 
-  /// Returns the current url of the webview.
-  // TODO: in v2, change this type to Result
-  pub fn url(&self) -> Url {
-    self.webview.dispatcher.url().unwrap()
+  /// Recreates the native webview after its content process terminated,
+  /// which is reported with [`WebviewEvent::ProcessCrashed`].
+  ///
+  /// The new webview has the same attributes and bounds, runs the initialization scripts again
+  /// (including the ones added after the webview was created) and loads the current URL.
+  /// This handle, its listeners and its state keep working with the recreated webview.
+  ///
+  /// Emits the `tauri://webview-recovered` event to the webview once it is recreated.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **iOS / Android**: Unsupported.
+  pub fn recover(&self) -> crate::Result<()> {
+    self.dispatcher()?.recover()?;
+    self.manager().emit_filter(
+      WEBVIEW_RECOVERED_EVENT,
+      Some(CreatedEvent {
+        label: self.label().into(),
+      }),
+      |target| match target {
+        EventTarget::Webview { label } => label == self.label(),
+        _ => false,
+      },
+    )
   }
 
-  /// Navigates the webview to the defined url.
-  pub fn navigate(&mut self, url: Url) {
-    self.webview.dispatcher.navigate(url).unwrap();
-  }
+  /// Whether the URL is served by the app: the tauri protocol, the `devUrl` or `frontendDist` URL,
+  /// a custom protocol registered by the user or an origin added with [`crate::Builder::add_local_origin`].
+  ///
+  /// The scheme, host and port must match exactly, so subdomains and other ports are remote.
+  pub(crate) fn is_local_url(&self, current_url: &Url) -> bool {
+    use crate::ipc::origin::{is_local_url, protocol_origin};
 
-  fn is_local_url(&self, current_url: &Url) -> bool {
-    // if from `tauri://` custom protocol
-    ({
-      let protocol_url = self.manager().protocol_url();
-      current_url.scheme() == protocol_url.scheme()
-      && current_url.domain() == protocol_url.domain()
-    }) ||
+    let manager = self.manager();
+    let mut local_origins = vec![
+      manager.protocol_url().into_owned(),
+      manager.get_url().into_owned(),
+    ];
+    local_origins.extend(
+      manager
+        .webview
+        .uri_scheme_protocols
+        .lock()
+        .unwrap()
+        .keys()
+        .filter_map(|protocol| protocol_origin(protocol)),
+    );
+    local_origins.extend(manager.webview.local_origins.iter().cloned());
 
-    // or if relative to `devUrl` or `frontendDist`
-      self
-          .manager()
-          .get_url()
-          .make_relative(current_url)
-          .is_some()
-
-      // or from a custom protocol registered by the user
-      || ({
-        let scheme = current_url.scheme();
-        let protocols = self.manager().webview.uri_scheme_protocols.lock().unwrap();
-
-        #[cfg(all(not(windows), not(target_os = "android")))]
-        let local = protocols.contains_key(scheme);
-
-        // on window and android, custom protocols are `http://<protocol-name>.path/to/route`
-        // so we check using the first part of the domain
-        #[cfg(any(windows, target_os = "android"))]
-        let local = {
-          let protocol_url = self.manager().protocol_url();
-          let maybe_protocol = current_url
-            .domain()
-            .and_then(|d| d .split_once('.'))
-            .unwrap_or_default()
-            .0;
-
-          protocols.contains_key(maybe_protocol) && scheme == protocol_url.scheme()
-        };
+    is_local_url(current_url, &local_origins)
+  }
 
-        local
-      })
+  /// Whether the command is in the [`WebviewBuilder::allowed_commands`] list.
+  pub(crate) fn is_command_allowed(&self, command: &str) -> bool {
+    self
+      .allowed_commands
+      .lock()
+      .unwrap()
+      .as_ref()
+      .map_or(true, |list| list.is_allowed(command))
+  }
+
+  /// The handler of an internal command that never reaches the user command handlers.
+  pub(crate) fn internal_command(cmd: &str) -> Option<InternalCommandHandler<R>> {
+    match cmd {
+      console::CONSOLE_MESSAGE_COMMAND => Some(console::handle),
+      scroll::SCROLL_COMMAND => Some(scroll::handle),
+      #[cfg(feature = "remote-automation")]
+      crate::automation::EVAL_RESULT_COMMAND => Some(crate::automation::handle_eval_result),
+      #[cfg(all(debug_assertions, feature = "ipc-inspector"))]
+      crate::ipc::inspector::INSPECTOR_COMMAND => Some(crate::ipc::inspector::handle),
+      _ => None,
+    }
   }
 
   /// Handles this window receiving an [`InvokeRequest`].
   pub fn on_message(self, request: InvokeRequest, responder: Box<OwnedInvokeResponder<R>>) {
     let manager = self.manager_owned();
-    let current_url = self.url();
-    let is_local = self.is_local_url(&current_url);
-
-    let custom_responder = self.manager().webview.invoke_responder.clone();
-
-    let resolver = InvokeResolver::new(
-      self.clone(),
-      Arc::new(Mutex::new(Some(Box::new(
-        #[allow(unused_variables)]
-        move |webview: Webview<R>, cmd, response, callback, error| {
-          if let Some(responder) = &custom_responder {
-            (responder)(&webview, &cmd, &response, callback, error);
-          }
-
-          responder(webview, cmd, response, callback, error);
-        },
-      )))),
-      request.cmd.clone(),
-      request.callback,
-      request.error,
-    );
-
-    #[cfg(mobile)]
-    let app_handle = self.window.app_handle.clone();
-
-    let message = InvokeMessage::new(
-      self,
-      manager.state(),
-      request.cmd.to_string(),
-      request.body,
-      request.headers,
-    );
-
-    let acl_origin = if is_local {
-      Origin::Local
-    } else {
-      Origin::Remote {
-        domain: current_url
-          .domain()
-          .map(|d| d.to_string())
-          .unwrap_or_default(),
-      }
-    };
-    let resolved_acl = manager
-      .runtime_authority
-      .resolve_access(&request.cmd, &message.webview.webview.label, &acl_origin)
-      .cloned();
-
-    let mut invoke = Invoke {
-      message,
-      resolver: resolver.clone(),
-      acl: resolved_acl,
-    };
-
-    if let Some((plugin, command_name)) = request.cmd.strip_prefix("plugin:").map(|raw_command| {
-      let mut tokens = raw_command.split('|');
-      // safe to unwrap: split always has a least one item
-      let plugin = tokens.next().unwrap();
-      let command = tokens.next().map(|c| c.to_string()).unwrap_or_default();
-      (plugin, command)
-    }) {
-      if request.cmd != crate::ipc::channel::FETCH_CHANNEL_DATA_COMMAND && invoke.acl.is_none() {
-        #[cfg(debug_assertions)]
-        {
-          invoke
-            .resolver
-            .reject(manager.runtime_authority.resolve_access_message(
-              plugin,
-              &command_name,
-              &invoke.message.webview.webview.label,
-              &acl_origin,
-            ));
-        }
-        #[cfg(not(debug_assertions))]
-        invoke
-          .resolver
-          .reject(format!("Command {} not allowed by ACL", request.cmd));
-        return;
-      }
-
-      invoke.message.command = command_name;
-
-      let command = invoke.message.command.clone();
-
-      #[cfg(mobile)]
-      let message = invoke.message.clone();
-
-      #[allow(unused_mut)]
-      let mut handled = manager.extend_api(plugin, invoke);
-
-      #[cfg(mobile)]
-      {
-        if !handled {
-          handled = true;
-
-          fn load_channels<R: Runtime>(payload: &serde_json::Value, webview: &Webview<R>) {
-            use std::str::FromStr;
-
-            if let serde_json::Value::Object(map) = payload {
-              for v in map.values() {
-                if let serde_json::Value::String(s) = v {
-                  let _ = crate::ipc::JavaScriptChannelId::from_str(s)
-                    .map(|id| id.channel_on(webview.clone()));
-                }
-              }
-            }
-          }
-
-          let payload = message.payload.into_json();
-          // initialize channels
-          load_channels(&payload, &message.webview);
-
-          let resolver_ = resolver.clone();
-          if let Err(e) = crate::plugin::mobile::run_command(
-            plugin,
-            &app_handle,
-            message.command,
-            payload,
-            move |response| match response {
-              Ok(r) => resolver_.resolve(r),
-              Err(e) => resolver_.reject(e),
-            },
-          ) {
-            resolver.reject(e.to_string());
-            return;
-          }
-        }
-      }
+    manager
+      .webview
+      .ipc_dispatcher
+      .dispatch(self, request, responder);
+  }
 
-      if !handled {
-        resolver.reject(format!("Command {command} not found"));
-      }
-    } else {
-      let command = invoke.message.command.clone();
-      let handled = manager.run_invoke_handler(invoke);
-      if !handled {
-        resolver.reject(format!("Command {command} not found"));
-      }
-    }
+  /// The profile the webview is bound to, see [`WebviewBuilder::profile`].
+  pub fn profile(&self) -> Option<crate::profile::Profile<R>> {
+    let profile = self
+      .manager()
+      .webview
+      .profiles
+      .lock()
+      .unwrap()
+      .get(self.label())
+      .cloned()?;
+    self.app_handle().create_profile(profile).ok()
   }
 
   /// Evaluates JavaScript on this window.
   pub fn eval(&self, js: &str) -> crate::Result<()> {
-    self.webview.dispatcher.eval_script(js).map_err(Into::into)
+    self.dispatcher()?.eval_script(js).map_err(Into::into)
   }
 
   /// Register a JS event listener and return its identifier.
@@ -1224,16 +2595,29 @@ fn main() {
     let listeners = self.manager().listeners();
 
     let id = listeners.next_event_id();
+    let serialized_target = serde_json::to_string(&target)?;
+
+    self.send_event_js(
+      || {
+        Ok(crate::event::listen_js_script(
+          listeners.listeners_object_name(),
+          &serialized_target,
+          event,
+          id,
+          &format!("window['_{}']", handler.0),
+        ))
+      },
+      || crate::event::listen_message(&serialized_target, event, id, handler),
+    )?;
 
-    self.eval(&crate::event::listen_js_script(
-      listeners.listeners_object_name(),
-      &serde_json::to_string(&target)?,
-      event,
-      id,
-      &format!("window['_{}']", handler.0),
-    ))?;
-
-    listeners.listen_js(event, self.label(), target, id);
+    listeners.listen_js(event, self.label(), target, id, |emit_args| {
+      self.send_event_js(
+        || {
+          crate::event::emit_js_listener_script(emit_args, id, &format!("window['_{}']", handler.0))
+        },
+        || crate::event::listener_message(emit_args, id, handler),
+      )
+    })?;
 
     Ok(id)
   }
@@ -1242,11 +2626,16 @@ fn main() {
   pub(crate) fn unlisten_js(&self, event: &str, id: EventId) -> crate::Result<()> {
     let listeners = self.manager().listeners();
 
-    self.eval(&crate::event::unlisten_js_script(
-      listeners.listeners_object_name(),
-      event,
-      id,
-    ))?;
+    self.send_event_js(
+      || {
+        Ok(crate::event::unlisten_js_script(
+          listeners.listeners_object_name(),
+          event,
+          id,
+        ))
+      },
+      || crate::event::unlisten_message(event, id),
+    )?;
 
     listeners.unlisten_js(id);
 
@@ -1254,12 +2643,75 @@ fn main() {
   }
 
   pub(crate) fn emit_js(&self, emit_args: &EmitArgs, target: &EventTarget) -> crate::Result<()> {
-    self.eval(&crate::event::emit_js_script(
-      self.manager().listeners().function_name(),
-      emit_args,
-      &serde_json::to_string(target)?,
-    )?)?;
-    Ok(())
+    if self.queue_event_if_suspended(emit_args, target) {
+      return Ok(());
+    }
+    let target = serde_json::to_string(target)?;
+    let sequence = self.event_sequences.lock().unwrap().next(
+      emit_args.source.as_deref(),
+      &emit_args.event_name,
+      &target,
+    )?;
+    self.send_event_js(
+      || {
+        crate::event::emit_js_script(
+          self.manager().listeners().function_name(),
+          emit_args,
+          &target,
+          &sequence,
+        )
+      },
+      || crate::event::emit_message(emit_args, &target, &sequence),
+    )
+  }
+
+  /// Delivers the payloads of a batched event with a single script, see [`Manager::emit_batched`].
+  pub(crate) fn emit_js_batch(
+    &self,
+    batch: &[EmitArgs],
+    target: &EventTarget,
+  ) -> crate::Result<()> {
+    let batch = batch
+      .iter()
+      .filter(|emit_args| !self.queue_event_if_suspended(emit_args, target))
+      .collect::<Vec<_>>();
+    if batch.is_empty() {
+      return Ok(());
+    }
+    let target = serde_json::to_string(target)?;
+    // the batch is a single entry in the stream of the event
+    let sequence = self.event_sequences.lock().unwrap().next(
+      batch[0].source.as_deref(),
+      &batch[0].event_name,
+      &target,
+    )?;
+    self.send_event_js(
+      || {
+        crate::event::emit_js_batch_script(
+          self.manager().listeners().function_name(),
+          &batch,
+          &target,
+          &sequence,
+        )
+      },
+      || crate::event::emit_batch_message(&batch, &target, &sequence),
+    )
+  }
+
+  /// Runs an event system operation in the frontend, evaluating its script
+  /// or sending its message over the event bridge, see [`crate::EventTransport`].
+  fn send_event_js(
+    &self,
+    script: impl FnOnce() -> crate::Result<String>,
+    message: impl FnOnce() -> crate::Result<String>,
+  ) -> crate::Result<()> {
+    match self.manager().webview.event_transport {
+      crate::EventTransport::Eval => self.eval(&script()?),
+      crate::EventTransport::Bridge => {
+        self.dispatcher()?;
+        self.event_bridge.push(message()?)
+      }
+    }
   }
 
   /// Opens the developer tools window (Web Inspector).
@@ -1440,6 +2892,58 @@ tauri::Builder::default()
     self.window.manager.unlisten(id)
   }
 
+  /// Listen to an event on this webview until the returned guard is dropped or the webview is destroyed.
+  ///
+  /// See [`Self::listen`] and [`Manager::listen_scoped`] for more information.
+  pub fn listen_scoped<F>(&self, event: impl Into<String>, handler: F) -> ListenerGuard
+  where
+    F: Fn(Event) + Send + 'static,
+  {
+    self.listen_scoped_to(
+      event.into(),
+      EventTarget::Webview {
+        label: self.label().to_string(),
+      },
+      handler,
+    )
+  }
+
+  /// Adds a listener that is removed when the guard is dropped or when this webview is destroyed.
+  pub(crate) fn listen_scoped_to<F>(
+    &self,
+    event: String,
+    target: EventTarget,
+    handler: F,
+  ) -> ListenerGuard
+  where
+    F: Fn(Event) + Send + 'static,
+  {
+    let manager = &self.window.manager;
+    let id = manager.listen(event, target, handler);
+    let listeners = manager.listeners().clone();
+    let destroyed_hook = manager.webview.event_listeners.add(
+      self.label(),
+      Arc::new(move |event| {
+        if let WebviewEvent::Destroyed = event {
+          listeners.unlisten(id);
+        }
+      }),
+    );
+
+    // a weak reference so a guard kept in the app state does not keep the manager alive
+    let manager = Arc::downgrade(manager);
+    let label = self.label().to_string();
+    ListenerGuard::new(id, move || {
+      if let Some(manager) = manager.upgrade() {
+        manager.unlisten(id);
+        manager
+          .webview
+          .event_listeners
+          .remove(&label, destroyed_hook);
+      }
+    })
+  }
+
   /// Listen to an event on this webview only once.
   ///
   /// See [`Self::listen`] for more information.
@@ -1491,4 +2995,639 @@ mod tests {
     crate::test_utils::assert_send::<super::Webview>();
     crate::test_utils::assert_sync::<super::Webview>();
   }
+
+  #[test]
+  fn config_overrides() {
+    let builder =
+      super::WebviewBuilder::<crate::test::MockRuntime>::new("main", Default::default())
+        .try_build_config_overrides(serde_json::json!({
+          "userAgent": "custom",
+          "fileDropEnabled": false,
+          "ignoreCursorEvents": true
+        }))
+        .unwrap();
+    assert_eq!(
+      builder.webview_attributes.user_agent.as_deref(),
+      Some("custom")
+    );
+    assert!(!builder.webview_attributes.file_drop_handler_enabled);
+    assert!(builder.webview_attributes.ignore_cursor_events);
+
+    assert!(
+      super::WebviewBuilder::<crate::test::MockRuntime>::new("main", Default::default())
+        .try_build_config_overrides(serde_json::json!({ "unknownAttribute": true }))
+        .is_err()
+    );
+  }
+
+  #[test]
+  fn scoped_listener_removed_on_destroy() {
+    use crate::sealed::ManagerBase;
+
+    let app = crate::test::mock_app();
+    let webview = crate::WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    let manager = app.manager();
+    let count = manager.listeners().listener_count();
+    let hooks = manager.webview.event_listeners.listener_count("main");
+
+    let guard = webview.as_ref().listen_scoped("scoped", |_| {});
+    assert_eq!(manager.listeners().listener_count(), count + 1);
+    assert_eq!(
+      manager.webview.event_listeners.listener_count("main"),
+      hooks + 1
+    );
+
+    // dropping the guard also removes the destruction hook
+    drop(guard);
+    assert_eq!(manager.listeners().listener_count(), count);
+    assert_eq!(
+      manager.webview.event_listeners.listener_count("main"),
+      hooks
+    );
+
+    let _guard = webview.listen_scoped("scoped", |_| {});
+    assert_eq!(manager.listeners().listener_count(), count + 1);
+    manager.on_webview_close("main");
+    assert_eq!(manager.listeners().listener_count(), count);
+  }
+
+  #[test]
+  fn destroyed_webview_errors() {
+    use crate::{
+      event::{EmitArgs, EventTarget},
+      sealed::ManagerBase,
+      Manager,
+    };
+
+    fn destroyed<T>(result: crate::Result<T>) -> bool {
+      matches!(result, Err(crate::Error::WebviewDestroyed(label)) if label == "main")
+    }
+
+    let app = crate::test::mock_app();
+    let webview = crate::WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    let mut webview = webview.as_ref().clone();
+    assert!(webview.is_alive());
+    assert!(webview.url().is_ok());
+
+    app.manager().on_webview_close("main");
+    assert!(!webview.is_alive());
+    assert!(destroyed(webview.url()));
+    assert!(destroyed(
+      webview.navigate("https://tauri.app".parse().unwrap())
+    ));
+    assert!(destroyed(webview.eval("1 + 1")));
+    assert!(destroyed(
+      webview.emit_js(&EmitArgs::new("event", ()).unwrap(), &EventTarget::Any)
+    ));
+    assert!(destroyed(webview.set_focus()));
+    assert!(destroyed(webview.position()));
+    // emitting to all webviews skips the destroyed one
+    app.emit("event", ()).unwrap();
+
+    let other = crate::WebviewWindowBuilder::new(&app, "other", Default::default())
+      .build()
+      .unwrap();
+    assert!(other.as_ref().is_alive());
+    app.manager().begin_shutdown();
+    assert!(!other.as_ref().is_alive());
+    assert!(matches!(
+      other.eval("1 + 1"),
+      Err(crate::Error::WebviewDestroyed(label)) if label == "other"
+    ));
+  }
+
+  #[cfg(desktop)]
+  #[test]
+  fn muted() {
+    let app = crate::test::mock_app();
+    let webview = crate::WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    let webview = webview.as_ref();
+    assert!(!webview.is_muted().unwrap());
+    webview.set_muted(true).unwrap();
+    assert!(webview.is_muted().unwrap());
+    webview.pause_all_media().unwrap();
+  }
+
+  #[test]
+  fn recover_emits_event() {
+    use std::sync::{
+      atomic::{AtomicBool, Ordering},
+      Arc,
+    };
+
+    let app = crate::test::mock_app();
+    let webview = crate::WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    let recovered = Arc::new(AtomicBool::new(false));
+    let recovered_ = recovered.clone();
+    webview
+      .as_ref()
+      .listen(super::WEBVIEW_RECOVERED_EVENT, move |_| {
+        recovered_.store(true, Ordering::SeqCst);
+      });
+
+    webview.as_ref().recover().unwrap();
+    assert!(recovered.load(Ordering::SeqCst));
+  }
+
+  #[cfg(desktop)]
+  #[test]
+  fn focused() {
+    let app = crate::test::mock_app();
+    let window = crate::window::WindowBuilder::new(&app, "main")
+      .build()
+      .unwrap();
+    let size = crate::LogicalSize::new(100, 100);
+
+    let focused = window
+      .add_child(
+        super::WebviewBuilder::new("focused", Default::default()),
+        crate::LogicalPosition::new(0, 0),
+        size,
+      )
+      .unwrap();
+    assert!(focused.webview.dispatcher.is_focused());
+
+    let unfocused = window
+      .add_child(
+        super::WebviewBuilder::new("unfocused", Default::default()).focused(false),
+        crate::LogicalPosition::new(0, 0),
+        size,
+      )
+      .unwrap();
+    assert!(!unfocused.webview.dispatcher.is_focused());
+    unfocused.set_focus().unwrap();
+    assert!(unfocused.webview.dispatcher.is_focused());
+
+    let config = crate::utils::config::WindowConfig {
+      label: "config".into(),
+      focus: false,
+      ..Default::default()
+    };
+    let webview_window = crate::WebviewWindowBuilder::from_config(&app, &config)
+      .unwrap()
+      .build()
+      .unwrap();
+    assert!(!webview_window.webview.webview.dispatcher.is_focused());
+  }
+
+  #[cfg(target_os = "linux")]
+  #[test]
+  fn effects_require_transparency() {
+    use crate::window::{Effect, EffectsBuilder};
+
+    let app = crate::test::mock_app();
+    let window = crate::window::WindowBuilder::new(&app, "main")
+      .build()
+      .unwrap();
+    let effects = EffectsBuilder::new().effect(Effect::Sidebar).build();
+    let position = crate::LogicalPosition::new(0, 0);
+    let size = crate::LogicalSize::new(100, 100);
+
+    assert!(matches!(
+      window.add_child(
+        super::WebviewBuilder::new("opaque", Default::default()).effects(effects.clone()),
+        position,
+        size,
+      ),
+      Err(crate::Error::WebviewNotTransparent(label)) if label == "opaque"
+    ));
+
+    let content = window
+      .add_child(
+        super::WebviewBuilder::new("content", Default::default()),
+        position,
+        size,
+      )
+      .unwrap();
+    assert!(matches!(
+      content.set_effects(effects.clone()),
+      Err(crate::Error::WebviewNotTransparent(_))
+    ));
+    content.set_effects(None).unwrap();
+
+    let sidebar = window
+      .add_child(
+        super::WebviewBuilder::new("sidebar", Default::default())
+          .transparent(true)
+          .effects(effects.clone()),
+        position,
+        size,
+      )
+      .unwrap();
+    assert!(matches!(
+      sidebar.set_effects(effects),
+      Err(crate::Error::Runtime(tauri_runtime::Error::Unsupported))
+    ));
+  }
+
+  #[test]
+  fn auth_challenge_failures() {
+    use super::{AuthChallenge, AuthResponse, WebviewBuilder, MAX_AUTH_CHALLENGE_FAILURES};
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    let app = crate::test::mock_app();
+    let calls = std::sync::Arc::new(AtomicU32::new(0));
+    let calls_ = calls.clone();
+    let mut pending = WebviewBuilder::new("main", Default::default())
+      .on_auth_challenge(move |_webview, _challenge| {
+        calls_.fetch_add(1, Ordering::Relaxed);
+        AuthResponse::Cancel
+      })
+      .into_pending_webview(&app, "main", &[], &[])
+      .unwrap();
+    let handler = pending.auth_challenge_handler.take().unwrap();
+
+    let mut challenge = AuthChallenge::new("tauri.app");
+    challenge.previous_failures = MAX_AUTH_CHALLENGE_FAILURES + 1;
+    assert!(matches!(handler(challenge), AuthResponse::Cancel));
+    assert_eq!(calls.load(Ordering::Relaxed), 0);
+
+    let credentials = AuthResponse::Credentials {
+      username: "user".into(),
+      password: "secret".into(),
+    };
+    assert!(!format!("{credentials:?}").contains("secret"));
+  }
+
+  #[test]
+  fn certificate_allowed_for_host() {
+    use super::{CertDecision, CertError, CertErrorKind, WebviewBuilder};
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    let app = crate::test::mock_app();
+    let calls = std::sync::Arc::new(AtomicU32::new(0));
+    let calls_ = calls.clone();
+    let mut pending = WebviewBuilder::new("main", Default::default())
+      .on_certificate_error(move |_webview, error| {
+        calls_.fetch_add(1, Ordering::Relaxed);
+        match error.error {
+          CertErrorKind::UnknownIssuer => CertDecision::AllowForHost,
+          _ => CertDecision::Deny,
+        }
+      })
+      .into_pending_webview(&app, "main", &[], &[])
+      .unwrap();
+    let handler = pending.cert_error_handler.take().unwrap();
+    let _webview = crate::WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+
+    let url: url::Url = "https://192.168.1.10/".parse().unwrap();
+    let error = CertError::new(url.clone(), vec![1, 2, 3], CertErrorKind::UnknownIssuer);
+    assert_eq!(handler(error.clone()), CertDecision::AllowForHost);
+    assert_eq!(handler(error), CertDecision::AllowForHost);
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+    // another certificate for the same host is checked again
+    let error = CertError::new(url, vec![4, 5, 6], CertErrorKind::HostMismatch);
+    assert_eq!(handler(error), CertDecision::Deny);
+    assert_eq!(calls.load(Ordering::Relaxed), 2);
+  }
+
+  #[test]
+  fn request_observer() {
+    use super::{RequestSummary, ResourceType, WebviewBuilder};
+    use std::{sync::Mutex, time::Duration};
+
+    let app = crate::test::mock_app();
+    let observed = std::sync::Arc::new(Mutex::new(Vec::new()));
+    let observed_ = observed.clone();
+    let mut pending = WebviewBuilder::new("main", Default::default())
+      .on_request_observed(move |webview, request| {
+        observed_
+          .lock()
+          .unwrap()
+          .push((webview.label().to_string(), request));
+      })
+      .into_pending_webview(&app, "main", &[], &[])
+      .unwrap();
+    let observer = pending.request_observer.take().unwrap();
+
+    let url: url::Url = "https://tauri.app/logo.png".parse().unwrap();
+    let mut request = RequestSummary::new(url.clone(), "GET", Duration::from_millis(20));
+    request.resource_type = ResourceType::Image;
+    request.status = Some(200);
+
+    // requests made before the webview is registered are not reported
+    observer(request.clone());
+    assert!(observed.lock().unwrap().is_empty());
+
+    let _webview = crate::WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    observer(request);
+    let observed = observed.lock().unwrap();
+    assert_eq!(observed.len(), 1);
+    let (label, request) = &observed[0];
+    assert_eq!(label, "main");
+    assert_eq!(request.url, url);
+    assert_eq!(request.resource_type, ResourceType::Image);
+    assert_eq!(request.status, Some(200));
+  }
+
+  #[test]
+  fn session_conflicts() {
+    use super::{SessionId, WebviewAttributes};
+
+    let attributes = WebviewAttributes::new(Default::default());
+    assert!(attributes
+      .clone()
+      .session(SessionId::new("shared"))
+      .data_directory("data".into())
+      .validate_session()
+      .is_ok());
+    assert!(attributes
+      .clone()
+      .session(SessionId::ephemeral())
+      .incognito(true)
+      .validate_session()
+      .is_ok());
+    assert!(attributes
+      .clone()
+      .session(SessionId::ephemeral())
+      .data_directory("data".into())
+      .validate_session()
+      .is_err());
+    assert!(attributes
+      .session(SessionId::new("shared"))
+      .incognito(true)
+      .validate_session()
+      .is_err());
+  }
+
+  #[test]
+  fn shared_sessions_use_separate_data_directories() {
+    use super::SessionId;
+    use crate::manager::webview::session_data_directory;
+    use std::path::PathBuf;
+
+    let default = PathBuf::from("data");
+    assert_eq!(
+      session_data_directory(default.clone(), &SessionId::new("work")),
+      default.join("sessions").join("work")
+    );
+    assert_eq!(
+      session_data_directory(default.clone(), &SessionId::new("a/b:c")),
+      default.join("sessions").join("a%2Fb%3Ac")
+    );
+    assert_eq!(
+      session_data_directory(default.clone(), &SessionId::ephemeral()),
+      default
+    );
+  }
+
+  #[test]
+  fn parse_plugin_command() {
+    use super::parse_plugin_command;
+
+    assert_eq!(
+      parse_plugin_command("plugin:event|listen"),
+      Some(("event", "listen"))
+    );
+    assert_eq!(
+      parse_plugin_command("plugin:foo|do_plugin:things"),
+      Some(("foo", "do_plugin:things"))
+    );
+    assert_eq!(
+      parse_plugin_command("plugin:foo|do|things"),
+      Some(("foo", "do|things"))
+    );
+    assert_eq!(parse_plugin_command("plugin:foo"), Some(("foo", "")));
+    assert_eq!(parse_plugin_command("greet"), None);
+    assert_eq!(parse_plugin_command("do_plugin:things"), None);
+  }
+
+  #[crate::command(root = "crate")]
+  fn ping() -> &'static str {
+    "pong"
+  }
+
+  #[test]
+  fn invoke_key() {
+    use crate::{
+      ipc::{CallbackFn, Channel},
+      test::{get_ipc_response, mock_builder, mock_context, noop_assets},
+      webview::{
+        invoke_key::{self, INVOKE_KEY_HEADER},
+        InvokeRequest,
+      },
+      WebviewWindowBuilder,
+    };
+
+    let app = mock_builder()
+      .invoke_handler(crate::generate_handler![ping])
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    let request = |key: Option<&str>| {
+      let mut headers = http::HeaderMap::new();
+      if let Some(key) = key {
+        headers.insert(INVOKE_KEY_HEADER, key.parse().unwrap());
+      }
+      InvokeRequest {
+        cmd: "ping".into(),
+        callback: CallbackFn(0),
+        error: CallbackFn(1),
+        body: Default::default(),
+        headers,
+      }
+    };
+
+    // the test helper sends the current key
+    assert!(get_ipc_response(&webview, request(None)).is_ok());
+    assert_eq!(
+      get_ipc_response(&webview, request(Some("replayed"))).unwrap_err(),
+      serde_json::json!({ "kind": "InvalidInvokeKey" })
+    );
+
+    // the main frame and its frames hand the bootstrap secret of the initialization script
+    let bootstrap = webview
+      .webview
+      .invoke_key
+      .lock()
+      .unwrap()
+      .bootstrap()
+      .to_string();
+    let handshake = |key: &str| {
+      let mut request = request(Some(key));
+      request.cmd = invoke_key::INVOKE_HANDSHAKE_COMMAND.into();
+      get_ipc_response(&webview, request).map(|body| body.deserialize::<String>().unwrap())
+    };
+    let main_frame = handshake(&bootstrap).unwrap();
+    let iframe = handshake(&bootstrap).unwrap();
+    assert_eq!(main_frame, iframe);
+    assert!(get_ipc_response(&webview, request(Some(&iframe))).is_ok());
+    assert_eq!(
+      handshake("guessed").unwrap_err(),
+      serde_json::json!({ "kind": "InvalidInvokeKey" })
+    );
+
+    let channel = Channel::from_callback_fn(webview.webview.clone(), CallbackFn(2));
+    assert!(channel.send("before navigation").is_ok());
+    webview.webview.invoke_key.lock().unwrap().on_navigation();
+    assert!(matches!(
+      channel.send("after navigation"),
+      Err(crate::Error::ChannelExpired)
+    ));
+
+    // a frame of the outgoing page retrying its request does not lock the next page out
+    assert!(get_ipc_response(&webview, request(Some(&main_frame))).is_err());
+    let retried = handshake(&bootstrap).unwrap();
+    assert_ne!(retried, main_frame);
+    assert_eq!(handshake(&bootstrap).unwrap(), retried);
+  }
+
+  #[test]
+  fn response_meta() {
+    use crate::{
+      ipc::{CallbackFn, ResponseMeta},
+      test::{mock_builder, mock_context, noop_assets},
+      webview::{invoke_key::INVOKE_KEY_HEADER, InvokeRequest},
+      WebviewWindowBuilder,
+    };
+    use std::sync::{mpsc::channel, Mutex};
+
+    let (custom_tx, custom_rx) = channel();
+    let custom_tx = Mutex::new(custom_tx);
+    let app = mock_builder()
+      .invoke_handler(|invoke| {
+        let mut headers = http::HeaderMap::new();
+        headers.insert("x-trace-id", "abc".parse().unwrap());
+        invoke.resolver.respond_with_headers(Ok("pong"), headers);
+        true
+      })
+      .invoke_system(
+        String::new(),
+        move |_webview, _cmd, _response, meta, _callback, _error| {
+          custom_tx.lock().unwrap().send(meta.clone()).unwrap();
+        },
+      )
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+
+    let key = webview
+      .webview
+      .invoke_key
+      .lock()
+      .unwrap()
+      .current()
+      .to_string();
+    let mut headers = http::HeaderMap::new();
+    headers.insert(INVOKE_KEY_HEADER, key.parse().unwrap());
+    let (tx, rx) = channel::<ResponseMeta>();
+    webview.webview.clone().on_message(
+      InvokeRequest {
+        cmd: "ping".into(),
+        callback: CallbackFn(0),
+        error: CallbackFn(1),
+        body: Default::default(),
+        headers,
+      },
+      Box::new(move |_webview, _cmd, _response, meta, _callback, _error| {
+        tx.send(meta).unwrap();
+      }),
+    );
+
+    let meta = rx.recv().unwrap();
+    assert_eq!(meta.headers.get("x-trace-id").unwrap(), "abc");
+    assert_eq!(meta.status, None);
+    assert_eq!(
+      custom_rx.recv().unwrap().headers.get("x-trace-id").unwrap(),
+      "abc"
+    );
+  }
+
+  #[test]
+  fn navigate_with_headers() {
+    use http::{header::AUTHORIZATION, HeaderMap};
+
+    let app = crate::test::mock_app();
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, "Bearer token".parse().unwrap());
+
+    let builder =
+      super::WebviewBuilder::<crate::test::MockRuntime>::new("main", Default::default())
+        .initial_headers(headers.clone());
+    assert_eq!(
+      builder.webview_attributes.initial_headers.as_ref(),
+      Some(&headers)
+    );
+
+    let mut webview = crate::WebviewWindowBuilder::new(&app, "main", Default::default())
+      .initial_headers(headers.clone())
+      .build()
+      .unwrap();
+    let url: url::Url = "https://tauri.app/dashboard".parse().unwrap();
+    webview.navigate_with_headers(url.clone(), headers).unwrap();
+    assert_eq!(webview.url().unwrap(), url);
+  }
+
+  #[cfg(feature = "tracing")]
+  #[test]
+  fn traced_requests_send_request_id() {
+    use crate::{
+      ipc::{CallbackFn, InvokeResponse, ResponseMeta, IPC_REQUEST_ID_HEADER},
+      test::{mock_builder, mock_context, noop_assets},
+      webview::InvokeRequest,
+      WebviewWindowBuilder,
+    };
+    use std::sync::mpsc::channel;
+
+    let invoke = |trace_ipc: bool, cmd: &str| {
+      let app = mock_builder()
+        .trace_ipc(trace_ipc)
+        .build(mock_context(noop_assets()))
+        .unwrap();
+      let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+        .build()
+        .unwrap();
+      let (tx, rx) = channel::<(InvokeResponse, ResponseMeta)>();
+      // no invoke key, so the request is rejected before reaching a command
+      webview.webview.clone().on_message(
+        InvokeRequest {
+          cmd: cmd.into(),
+          callback: CallbackFn(0),
+          error: CallbackFn(1),
+          body: Default::default(),
+          headers: Default::default(),
+        },
+        Box::new(move |_webview, _cmd, response, meta, _callback, _error| {
+          tx.send((response, meta)).unwrap();
+        }),
+      );
+      rx.recv().unwrap()
+    };
+
+    let request_id = |meta: &ResponseMeta| -> u64 {
+      meta
+        .headers
+        .get(IPC_REQUEST_ID_HEADER)
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap()
+    };
+
+    let (response, first) = invoke(true, "missing");
+    assert!(matches!(response, InvokeResponse::Err(_)));
+    let (_, second) = invoke(true, "missing");
+    assert_ne!(request_id(&first), request_id(&second));
+
+    let (_, untraced) = invoke(false, "missing");
+    assert!(untraced.is_empty());
+  }
 }
@@ -0,0 +1,211 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Deferring navigations to async handlers, see [`super::WebviewBuilder::on_navigation_async`].
+
+use std::{
+  sync::{Arc, Mutex},
+  time::Duration,
+};
+
+use futures_util::future::BoxFuture;
+use url::Url;
+
+use super::NavigationHandler;
+use crate::{manager::AppManager, Runtime};
+
+/// The default time an async navigation handler has to allow a navigation before it is denied.
+pub const DEFAULT_ASYNC_NAVIGATION_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub(crate) type AsyncNavigationHandler = dyn Fn(&Url) -> BoxFuture<'static, bool> + Send;
+
+pub(crate) struct AsyncNavigation {
+  pub(crate) handler: Option<Box<AsyncNavigationHandler>>,
+  pub(crate) timeout: Duration,
+}
+
+impl Default for AsyncNavigation {
+  fn default() -> Self {
+    Self {
+      handler: None,
+      timeout: DEFAULT_ASYNC_NAVIGATION_TIMEOUT,
+    }
+  }
+}
+
+#[derive(Default)]
+struct Deferred {
+  /// The URL the webview was created with, which is not checked.
+  initial: Option<Url>,
+  /// The URL an async handler allowed, let through when the webview navigates to it again.
+  approved: Option<Url>,
+  /// Incremented on every deferred navigation so a late answer does not override a newer navigation.
+  generation: u64,
+}
+
+impl AsyncNavigation {
+  /// Wraps the navigation handler so the navigations it allows are cancelled
+  /// and only resumed once the async handler allows them.
+  pub(crate) fn wrap<R: Runtime>(
+    self,
+    navigation_handler: Option<Box<NavigationHandler>>,
+    manager: Arc<AppManager<R>>,
+    label: String,
+    initial_url: &str,
+  ) -> Option<Box<NavigationHandler>> {
+    let Some(handler) = self.handler else {
+      return navigation_handler;
+    };
+    let timeout = self.timeout;
+    let state = Arc::new(Mutex::new(Deferred {
+      initial: Url::parse(initial_url).ok(),
+      ..Default::default()
+    }));
+
+    Some(Box::new(move |url| {
+      {
+        let mut state = state.lock().unwrap();
+        if state.approved.as_ref() == Some(url) {
+          state.approved = None;
+          return true;
+        }
+      }
+
+      if let Some(navigation_handler) = &navigation_handler {
+        if !navigation_handler(url) {
+          return false;
+        }
+      }
+
+      let generation = {
+        let mut state = state.lock().unwrap();
+        if state.initial.take().as_ref() == Some(url) {
+          return true;
+        }
+        state.approved = None;
+        state.generation += 1;
+        state.generation
+      };
+
+      let future = handler(url);
+      let url = url.clone();
+      let state = state.clone();
+      let manager = manager.clone();
+      let label = label.clone();
+      crate::async_runtime::spawn(async move {
+        // the future never runs on the UI thread, so it can take its time up to the timeout
+        let allowed = tokio::time::timeout(timeout, future).await.unwrap_or(false);
+        if !allowed {
+          return;
+        }
+
+        {
+          let mut state = state.lock().unwrap();
+          if state.generation != generation {
+            return;
+          }
+          state.approved.replace(url.clone());
+        }
+        if let Some(mut webview) = manager.get_webview(&label) {
+          let _ = webview.navigate(url);
+        }
+      });
+
+      false
+    }))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::{Duration, Instant};
+
+  use futures_util::FutureExt;
+
+  use crate::{test::mock_app, webview::Url, WebviewWindowBuilder};
+
+  fn wait_for_url(
+    webview: &crate::WebviewWindow<crate::test::MockRuntime>,
+    url: &str,
+    timeout: Duration,
+  ) -> bool {
+    let start = Instant::now();
+    while start.elapsed() < timeout {
+      if webview.url().unwrap().as_str() == url {
+        return true;
+      }
+      std::thread::sleep(Duration::from_millis(10));
+    }
+    false
+  }
+
+  #[test]
+  fn async_allow_resumes_navigation() {
+    let app = mock_app();
+    let mut webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .on_navigation_async(|url| {
+        let allowed = url.host_str() == Some("tauri.app");
+        async move {
+          tokio::time::sleep(Duration::from_millis(20)).await;
+          allowed
+        }
+        .boxed()
+      })
+      .build()
+      .unwrap();
+    let initial = webview.url().unwrap().to_string();
+
+    webview
+      .navigate(Url::parse("https://tauri.app/").unwrap())
+      .unwrap();
+    // cancelled until the handler answers
+    assert_eq!(webview.url().unwrap().as_str(), initial);
+    assert!(wait_for_url(
+      &webview,
+      "https://tauri.app/",
+      Duration::from_secs(2)
+    ));
+  }
+
+  #[test]
+  fn async_deny_cancels_navigation() {
+    let app = mock_app();
+    let mut webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .on_navigation_async(|_url| async { false }.boxed())
+      .build()
+      .unwrap();
+    let initial = webview.url().unwrap().to_string();
+
+    webview
+      .navigate(Url::parse("https://tauri.app/").unwrap())
+      .unwrap();
+    assert!(!wait_for_url(
+      &webview,
+      "https://tauri.app/",
+      Duration::from_millis(200)
+    ));
+    assert_eq!(webview.url().unwrap().as_str(), initial);
+  }
+
+  #[test]
+  fn async_timeout_denies_navigation() {
+    let app = mock_app();
+    let mut webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .on_navigation_async(|_url| futures_util::future::pending().boxed())
+      .async_navigation_timeout(Duration::from_millis(20))
+      .build()
+      .unwrap();
+    let initial = webview.url().unwrap().to_string();
+
+    webview
+      .navigate(Url::parse("https://tauri.app/").unwrap())
+      .unwrap();
+    assert!(!wait_for_url(
+      &webview,
+      "https://tauri.app/",
+      Duration::from_millis(200)
+    ));
+    assert_eq!(webview.url().unwrap().as_str(), initial);
+  }
+}
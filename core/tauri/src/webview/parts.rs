@@ -0,0 +1,64 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The webview builder options plugins can change, see [`crate::plugin::Builder::on_webview_builder`].
+
+use tauri_runtime::webview::WebviewAttributes;
+use tauri_utils::config::WebviewUrl;
+use url::Url;
+
+use super::NavigationHandler;
+
+/// The options of a webview being built that plugins can change before it is created,
+/// see [`crate::plugin::Builder::on_webview_builder`].
+pub struct WebviewBuilderParts<'a> {
+  pub(crate) label: &'a str,
+  pub(crate) window_label: &'a str,
+  pub(crate) attributes: &'a mut WebviewAttributes,
+  pub(crate) navigation_handler: &'a mut Option<Box<NavigationHandler>>,
+}
+
+impl<'a> WebviewBuilderParts<'a> {
+  /// The label of the webview.
+  pub fn label(&self) -> &str {
+    self.label
+  }
+
+  /// The label of the window hosting the webview.
+  pub fn window_label(&self) -> &str {
+    self.window_label
+  }
+
+  /// The URL the webview loads.
+  pub fn url(&self) -> &WebviewUrl {
+    &self.attributes.url
+  }
+
+  /// The scripts injected before the page scripts are executed, see [`crate::webview::WebviewBuilder::initialization_script`].
+  pub fn initialization_scripts(&mut self) -> &mut Vec<String> {
+    &mut self.attributes.initialization_scripts
+  }
+
+  /// Adds a navigation check. The navigation is only allowed if the app and every plugin allow it,
+  /// the checks run in the order they were added and stop at the first denial.
+  pub fn on_navigation<F: Fn(&Url) -> bool + Send + 'static>(&mut self, on_navigation: F) {
+    let previous = self.navigation_handler.take();
+    self.navigation_handler.replace(Box::new(move |url| {
+      previous.as_ref().map_or(true, |previous| previous(url)) && on_navigation(url)
+    }));
+  }
+
+  /// Appends to the user agent, separated by a space.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS / iOS / Android**: Ignored unless the user agent is set with [`crate::webview::WebviewBuilder::user_agent`].
+  pub fn append_user_agent(&mut self, suffix: &str) {
+    let user_agent_suffix = &mut self.attributes.user_agent_suffix;
+    *user_agent_suffix = Some(match user_agent_suffix.take() {
+      Some(user_agent_suffix) => format!("{user_agent_suffix} {suffix}"),
+      None => suffix.into(),
+    });
+  }
+}
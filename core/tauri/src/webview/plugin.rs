@@ -14,12 +14,16 @@ mod desktop_commands {
 
   use serde::Deserialize;
   use tauri_runtime::window::dpi::{Position, Size};
-  use tauri_utils::config::{WebviewUrl, WindowConfig};
+  use tauri_utils::config::{BackgroundThrottlingPolicy, WebviewUrl, WindowConfig};
 
   use super::*;
   use crate::{
-    command, sealed::ManagerBase, utils::config::WindowEffectsConfig, AppHandle, Webview,
-    WebviewWindowBuilder,
+    command,
+    ipc::{InvokeBody, Response},
+    sealed::ManagerBase,
+    utils::config::WindowEffectsConfig,
+    webview::messaging::{WEBVIEW_MESSAGE_ID_HEADER, WEBVIEW_MESSAGE_TARGET_HEADER},
+    AppHandle, Webview, WebviewWindowBuilder,
   };
 
   #[derive(Debug, PartialEq, Clone, Deserialize)]
@@ -40,6 +44,8 @@ mod desktop_commands {
     window_effects: Option<WindowEffectsConfig>,
     #[serde(default)]
     incognito: bool,
+    background_throttling: Option<BackgroundThrottlingPolicy>,
+    focus: Option<bool>,
   }
 
   #[command(root = "crate")]
@@ -77,6 +83,8 @@ mod desktop_commands {
     builder.webview_attributes.accept_first_mouse = options.accept_first_mouse;
     builder.webview_attributes.window_effects = options.window_effects;
     builder.webview_attributes.incognito = options.incognito;
+    builder.webview_attributes.background_throttling = options.background_throttling;
+    builder.webview_attributes.focus = options.focus.unwrap_or(true);
 
     window.add_child(
       builder,
@@ -151,6 +159,7 @@ mod desktop_commands {
     size,
     tauri_runtime::window::dpi::PhysicalSize<u32>
   );
+  getter!(webview_theme, theme, crate::Theme);
   //getter!(is_focused, bool);
 
   setter!(print);
@@ -158,6 +167,50 @@ mod desktop_commands {
   setter!(set_webview_size, set_size, Size);
   setter!(set_webview_position, set_position, Position);
   setter!(set_webview_focus, set_focus);
+  setter!(set_webview_theme, set_theme, Option<crate::Theme>);
+  setter!(
+    set_webview_background_throttling,
+    set_background_throttling,
+    BackgroundThrottlingPolicy
+  );
+
+  #[command(root = "crate")]
+  pub fn post_webview_message<R: Runtime>(
+    webview: Webview<R>,
+    request: crate::ipc::Request<'_>,
+  ) -> crate::Result<()> {
+    let target = request
+      .headers()
+      .get(WEBVIEW_MESSAGE_TARGET_HEADER)
+      .and_then(|v| v.to_str().ok())
+      .ok_or(crate::Error::WebviewNotFound)?;
+    let message = match request.body() {
+      InvokeBody::Raw(message) => message.clone(),
+      // the postMessage IPC sends the bytes as a JSON array
+      InvokeBody::Json(message) => serde_json::from_value(message.clone())?,
+    };
+    webview.post_message_to(target, message)
+  }
+
+  #[command(root = "crate")]
+  pub fn take_webview_message<R: Runtime>(
+    webview: Webview<R>,
+    request: crate::ipc::Request<'_>,
+  ) -> Result<Response, &'static str> {
+    let id = request
+      .headers()
+      .get(WEBVIEW_MESSAGE_ID_HEADER)
+      .and_then(|v| v.to_str().ok())
+      .and_then(|id| id.parse().ok())
+      .ok_or("missing message id header")?;
+    webview
+      .messages
+      .lock()
+      .unwrap()
+      .take(id)
+      .map(Response::new)
+      .ok_or("message not found")
+  }
 
   #[cfg(any(debug_assertions, feature = "devtools"))]
   #[command(root = "crate")]
@@ -221,12 +274,18 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
             // getters
             desktop_commands::webview_position,
             desktop_commands::webview_size,
+            desktop_commands::webview_theme,
             // setters
             desktop_commands::webview_close,
             desktop_commands::set_webview_size,
             desktop_commands::set_webview_position,
             desktop_commands::set_webview_focus,
+            desktop_commands::set_webview_theme,
+            desktop_commands::set_webview_background_throttling,
             desktop_commands::print,
+            // messaging
+            desktop_commands::post_webview_message,
+            desktop_commands::take_webview_message,
             #[cfg(any(debug_assertions, feature = "devtools"))]
             desktop_commands::internal_toggle_devtools,
           ]);
@@ -0,0 +1,196 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Webviews created ahead of time, see [`super::WebviewBuilder::prepare`].
+
+use tauri_runtime::{
+  window::dpi::{LogicalPosition, LogicalSize, Position, Size},
+  WebviewDispatch,
+};
+use url::Url;
+
+use super::{Webview, WebviewBuilder};
+use crate::{
+  manager::AppManager, sealed::ManagerBase, window::WindowBuilder, Manager, Runtime, Window,
+};
+
+/// The label of the hidden window hosting the prepared webviews until they are attached.
+pub(crate) const PREPARED_WEBVIEWS_WINDOW_LABEL: &str = "__tauri_prepared_webviews__";
+
+/// A webview created with [`WebviewBuilder::prepare`] that is not attached to a window yet.
+///
+/// The native webview lives in a hidden window until [`Self::attach`] moves it to its window.
+/// Dropping an unattached prepared webview closes it.
+pub struct PreparedWebview<R: Runtime> {
+  webview: Option<Webview<R>>,
+  url: Url,
+}
+
+impl<R: Runtime> std::fmt::Debug for PreparedWebview<R> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("PreparedWebview")
+      .field("webview", &self.webview)
+      .field("url", &self.url)
+      .finish()
+  }
+}
+
+impl<R: Runtime> PreparedWebview<R> {
+  /// The label of the webview.
+  pub fn label(&self) -> &str {
+    self.webview().label()
+  }
+
+  /// Attaches the webview to the given window with the given bounds and starts loading its URL.
+  pub fn attach<P: Into<Position>, S: Into<Size>>(
+    mut self,
+    window: &Window<R>,
+    position: P,
+    size: S,
+  ) -> crate::Result<Webview<R>> {
+    let mut webview = self
+      .webview
+      .take()
+      .expect("prepared webview already attached");
+    if let Err(e) = webview.webview.dispatcher.reparent(window.window.id) {
+      // closed on drop
+      self.webview.replace(webview);
+      return Err(e.into());
+    }
+
+    webview.window = window.clone();
+    let manager = webview.manager_owned();
+    manager
+      .webview
+      .webviews_lock()
+      .insert(webview.label().to_string(), webview.clone());
+    release_host_window(&manager);
+
+    webview
+      .webview
+      .dispatcher
+      .set_bounds(position.into(), size.into())?;
+    // the initialization scripts were generated for the hidden window
+    webview
+      .webview
+      .dispatcher
+      .add_initialization_script(format!(
+        "window.__TAURI_INTERNALS__.metadata.currentWindow = {{ label: {} }}",
+        serde_json::to_string(window.label())?
+      ))?;
+    webview.webview.dispatcher.navigate(self.url.clone())?;
+
+    Ok(webview)
+  }
+
+  fn webview(&self) -> &Webview<R> {
+    self
+      .webview
+      .as_ref()
+      .expect("prepared webview already attached")
+  }
+}
+
+impl<R: Runtime> Drop for PreparedWebview<R> {
+  fn drop(&mut self) {
+    if let Some(webview) = self.webview.take() {
+      let _ = webview.close();
+      release_host_window(webview.manager());
+    }
+  }
+}
+
+/// Creates the webview on the hidden host window, loading `about:blank` until it is attached.
+pub(crate) fn prepare<R: Runtime, M: Manager<R>>(
+  builder: WebviewBuilder<R>,
+  manager: &M,
+) -> crate::Result<PreparedWebview<R>> {
+  let window = match manager.manager().get_window(PREPARED_WEBVIEWS_WINDOW_LABEL) {
+    Some(window) => window,
+    None => WindowBuilder::new(manager, PREPARED_WEBVIEWS_WINDOW_LABEL)
+      .visible(false)
+      .focused(false)
+      .skip_taskbar(true)
+      .inner_size(0., 0.)
+      .build()?,
+  };
+
+  let mut url = None;
+  let webview = builder.build_with(
+    window,
+    LogicalPosition::new(0, 0).into(),
+    LogicalSize::new(0, 0).into(),
+    |pending| {
+      url.replace(std::mem::replace(&mut pending.url, "about:blank".into()));
+    },
+  )?;
+
+  let url = match Url::parse(&url.unwrap_or_default()) {
+    Ok(url) => url,
+    Err(e) => {
+      let _ = webview.close();
+      release_host_window(webview.manager());
+      return Err(crate::Error::InvalidUrl(e));
+    }
+  };
+
+  Ok(PreparedWebview {
+    webview: Some(webview),
+    url,
+  })
+}
+
+/// Destroys the host window once it has no prepared webview left, so it never keeps the app alive.
+fn release_host_window<R: Runtime>(manager: &AppManager<R>) {
+  if let Some(window) = manager.get_window(PREPARED_WEBVIEWS_WINDOW_LABEL) {
+    if window.webviews().is_empty() {
+      let _ = window.destroy();
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::PREPARED_WEBVIEWS_WINDOW_LABEL;
+  use crate::{
+    test::mock_app, webview::WebviewBuilder, window::WindowBuilder, LogicalPosition, LogicalSize,
+  };
+
+  #[test]
+  fn attach_prepared_webview() {
+    let app = mock_app();
+    let prepared = WebviewBuilder::new("tab", Default::default())
+      .prepare(&app)
+      .unwrap();
+    assert_eq!(prepared.label(), "tab");
+    let host = app
+      .handle()
+      .manager
+      .get_window(PREPARED_WEBVIEWS_WINDOW_LABEL)
+      .unwrap();
+    assert_eq!(host.webviews().len(), 1);
+
+    let window = WindowBuilder::new(&app, "main").build().unwrap();
+    let webview = prepared
+      .attach(
+        &window,
+        LogicalPosition::new(0, 0),
+        LogicalSize::new(100, 100),
+      )
+      .unwrap();
+    assert_eq!(webview.window().label(), "main");
+    assert_eq!(window.webviews().len(), 1);
+    assert!(host.webviews().is_empty());
+  }
+
+  #[test]
+  fn drop_prepared_webview() {
+    let app = mock_app();
+    let prepared = WebviewBuilder::new("tab", Default::default())
+      .prepare(&app)
+      .unwrap();
+    drop(prepared);
+    assert!(app.handle().manager.get_webview("tab").is_none());
+  }
+}
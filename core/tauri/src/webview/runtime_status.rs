@@ -0,0 +1,202 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Detection of the webview runtime, see [`crate::webview_runtime_status`].
+
+use std::{cmp::Ordering, fmt};
+
+use crate::Runtime;
+
+/// The minimum version of the webview runtime on the current platform, see [`crate::webview_runtime_status`].
+///
+/// Apps requiring a newer version can set their own with [`crate::Builder::minimum_webview_version`].
+///
+/// ## Platform-specific
+///
+/// - **Windows**: The first stable release of the WebView2 runtime.
+/// - **Linux**: The webkit2gtk version Tauri is built against.
+/// - **macOS / iOS / Android**: `None`, the webview ships with the system.
+pub const MINIMUM_WEBVIEW_VERSION: Option<&str> = if cfg!(windows) {
+  Some("86.0.616.0")
+} else if cfg!(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd"
+)) {
+  Some("2.38.0")
+} else {
+  None
+};
+
+/// The availability of the webview runtime, see [`crate::webview_runtime_status`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuntimeStatus {
+  /// The webview runtime is installed and supported.
+  Installed {
+    /// The installed version.
+    version: String,
+  },
+  /// The webview runtime is not installed.
+  Missing,
+  /// The installed webview runtime is older than the minimum version.
+  Outdated {
+    /// The installed version.
+    version: String,
+    /// The minimum version.
+    minimum: String,
+  },
+}
+
+impl RuntimeStatus {
+  /// Whether the webview runtime is installed and supported.
+  pub fn is_installed(&self) -> bool {
+    matches!(self, Self::Installed { .. })
+  }
+
+  /// Detects the webview runtime of `R`.
+  pub(crate) fn detect<R: Runtime>(minimum: Option<&str>) -> Self {
+    match R::webview_version() {
+      Ok(version) => Self::from_version(version, minimum),
+      Err(_) => Self::Missing,
+    }
+  }
+
+  fn from_version(version: String, minimum: Option<&str>) -> Self {
+    match minimum {
+      Some(minimum) if compare_versions(&version, minimum) == Ordering::Less => Self::Outdated {
+        version,
+        minimum: minimum.into(),
+      },
+      _ => Self::Installed { version },
+    }
+  }
+}
+
+impl fmt::Display for RuntimeStatus {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Installed { version } => write!(f, "version {version} is installed"),
+      Self::Missing => write!(f, "it is not installed"),
+      Self::Outdated { version, minimum } => {
+        write!(
+          f,
+          "version {version} is older than the minimum version {minimum}"
+        )
+      }
+    }
+  }
+}
+
+/// Compares dot separated numeric versions, ignoring the text after the first whitespace.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+  let parts = |version: &str| {
+    version
+      .split_whitespace()
+      .next()
+      .unwrap_or_default()
+      .split('.')
+      .map(|part| part.parse::<u64>().unwrap_or(0))
+      .collect::<Vec<_>>()
+  };
+  let (a, b) = (parts(a), parts(b));
+  for i in 0..a.len().max(b.len()) {
+    match a.get(i).unwrap_or(&0).cmp(b.get(i).unwrap_or(&0)) {
+      Ordering::Equal => (),
+      ordering => return ordering,
+    }
+  }
+  Ordering::Equal
+}
+
+/// What to do when the webview runtime is missing or outdated, see [`crate::Builder::on_webview_runtime_missing`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebviewRuntimeAction {
+  /// Builds the app anyway.
+  Continue,
+  /// Fails to build the app with [`crate::Error::WebviewRuntimeUnavailable`].
+  Abort,
+  /// Detects the webview runtime again, e.g. after running an installer.
+  Retry,
+}
+
+/// A handler called when the webview runtime is missing or outdated.
+pub(crate) type WebviewRuntimeMissingHandler =
+  dyn FnMut(&RuntimeStatus) -> WebviewRuntimeAction + Send;
+
+/// Calls the handler until the webview runtime is available or the handler does not retry.
+pub(crate) fn check(
+  handler: &mut WebviewRuntimeMissingHandler,
+  detect: impl Fn() -> RuntimeStatus,
+) -> crate::Result<()> {
+  loop {
+    let status = detect();
+    if status.is_installed() {
+      return Ok(());
+    }
+    match handler(&status) {
+      WebviewRuntimeAction::Continue => return Ok(()),
+      WebviewRuntimeAction::Abort => return Err(crate::Error::WebviewRuntimeUnavailable(status)),
+      WebviewRuntimeAction::Retry => (),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::cmp::Ordering;
+
+  use super::{compare_versions, RuntimeStatus, WebviewRuntimeAction};
+  use crate::test::{mock_builder, mock_context, noop_assets};
+
+  #[test]
+  fn compares_versions() {
+    assert_eq!(
+      compare_versions("120.0.2210.91", "86.0.616.0"),
+      Ordering::Greater
+    );
+    assert_eq!(compare_versions("2.38", "2.38.0"), Ordering::Equal);
+    assert_eq!(
+      compare_versions("2.36.8 (libwebkit2gtk-4.1.so.0)", "2.38.0"),
+      Ordering::Less
+    );
+    assert_eq!(
+      RuntimeStatus::from_version("2.36.8".into(), Some("2.38.0")),
+      RuntimeStatus::Outdated {
+        version: "2.36.8".into(),
+        minimum: "2.38.0".into()
+      }
+    );
+    assert!(RuntimeStatus::from_version("2.36.8".into(), None).is_installed());
+  }
+
+  #[test]
+  fn handler_decides_whether_to_build() {
+    // the mock runtime has no webview version
+    let mut attempts = 0;
+    let result = mock_builder()
+      .on_webview_runtime_missing(move |status| {
+        assert_eq!(*status, RuntimeStatus::Missing);
+        attempts += 1;
+        if attempts < 3 {
+          WebviewRuntimeAction::Retry
+        } else {
+          WebviewRuntimeAction::Abort
+        }
+      })
+      .build(mock_context(noop_assets()));
+    assert!(matches!(
+      result,
+      Err(crate::Error::WebviewRuntimeUnavailable(
+        RuntimeStatus::Missing
+      ))
+    ));
+
+    assert!(mock_builder()
+      .on_webview_runtime_missing(|_| WebviewRuntimeAction::Continue)
+      .build(mock_context(noop_assets()))
+      .is_ok());
+  }
+}
@@ -0,0 +1,65 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+;(function () {
+  const command = __TEMPLATE_command__
+  const maxLength = __TEMPLATE_max_length__
+  const levels = {
+    debug: 'debug',
+    trace: 'debug',
+    log: 'log',
+    info: 'info',
+    warn: 'warn',
+    error: 'error'
+  }
+
+  function format(value) {
+    if (typeof value === 'string') {
+      return value
+    }
+    if (value instanceof Error) {
+      return value.stack || String(value)
+    }
+    try {
+      return JSON.stringify(value) ?? String(value)
+    } catch {
+      return String(value)
+    }
+  }
+
+  // the first stack frame that is not this script
+  function caller() {
+    const stack = new Error().stack || ''
+    const frames = stack.split('\n')
+    const hook = frames.findIndex((frame) =>
+      frame.includes('__TAURI_CONSOLE_HOOK__')
+    )
+    for (const frame of frames.slice(hook + 1)) {
+      const match = frame.match(/([a-z][\w+.-]*:\/\/[^\s()]*?):(\d+):\d+\)?$/i)
+      if (match) {
+        return { sourceId: match[1], line: Number(match[2]) }
+      }
+    }
+    return { sourceId: null, line: null }
+  }
+
+  for (const [method, level] of Object.entries(levels)) {
+    const original = console[method]
+    console[method] = function __TAURI_CONSOLE_HOOK__(...args) {
+      original.apply(console, args)
+      try {
+        let message = args.map(format).join(' ')
+        const truncated = message.length > maxLength
+        if (truncated) {
+          message = message.slice(0, maxLength)
+        }
+        window.__TAURI_INTERNALS__
+          .invoke(command, { level, message, truncated, ...caller() })
+          .catch(() => {})
+      } catch {
+        // never break the page logging
+      }
+    }
+  }
+})()
@@ -0,0 +1,40 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+;(function () {
+  const command = __TEMPLATE_command__
+  const throttle = __TEMPLATE_throttle__
+  let last = 0
+  let timeout = null
+
+  function send() {
+    timeout = null
+    last = Date.now()
+    window.__TAURI_INTERNALS__
+      .invoke(command, {
+        x: window.scrollX,
+        y: window.scrollY,
+        scaleFactor: window.devicePixelRatio
+      })
+      .catch(() => {})
+  }
+
+  // only the document scroll reaches the window, nested scroll containers do not bubble the event
+  window.addEventListener(
+    'scroll',
+    function () {
+      if (timeout !== null) {
+        return
+      }
+      // the last position of a burst is always sent once the throttle window ends
+      const wait = last + throttle - Date.now()
+      if (wait <= 0) {
+        send()
+      } else {
+        timeout = setTimeout(send, wait)
+      }
+    },
+    { passive: true }
+  )
+})()
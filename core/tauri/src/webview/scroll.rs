@@ -0,0 +1,237 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The document scroll position of webviews, see [`Webview::scroll_to`] and [`super::WebviewBuilder::emit_scroll_events`].
+//!
+//! None of the webviews expose the document scroll natively in this version,
+//! so the position is read and set with scripts on all platforms.
+
+use std::{sync::atomic::Ordering, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use serialize_to_javascript::{default_template, DefaultTemplate, Template};
+use tauri_runtime::WebviewDispatch;
+
+use super::Webview;
+use crate::{Manager, Runtime};
+
+/// The event emitted when the document of a webview scrolls, see [`super::WebviewBuilder::emit_scroll_events`].
+pub const WEBVIEW_SCROLL_EVENT: &str = "tauri://scroll";
+
+/// The internal command the scroll script sends the positions with.
+pub(crate) const SCROLL_COMMAND: &str = "__TAURI_SCROLL__";
+
+/// The scroll position of a document, in CSS pixels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScrollPosition {
+  /// The horizontal scroll offset.
+  pub x: f64,
+  /// The vertical scroll offset.
+  pub y: f64,
+}
+
+impl ScrollPosition {
+  /// Creates a scroll position from its offsets in CSS pixels.
+  pub fn new(x: f64, y: f64) -> Self {
+    Self { x, y }
+  }
+}
+
+/// Payload of the `tauri://scroll` event.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebviewScroll {
+  /// The webview label.
+  pub label: String,
+  /// The document scroll position.
+  pub position: ScrollPosition,
+  /// The number of physical pixels per CSS pixel, including the page zoom.
+  pub scale_factor: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ScrollMessage {
+  x: f64,
+  y: f64,
+  scale_factor: f64,
+}
+
+#[derive(Template)]
+#[default_template("./scripts/scroll.js")]
+struct ScrollScript<'a> {
+  command: &'a str,
+  throttle: u128,
+}
+
+/// The script sending the document scroll position to [`SCROLL_COMMAND`] at most once per `throttle`.
+pub(crate) fn script(throttle: Duration) -> String {
+  ScrollScript {
+    command: SCROLL_COMMAND,
+    throttle: throttle.as_millis(),
+  }
+  .render_default(&Default::default())
+  .unwrap()
+  .into_string()
+}
+
+/// Handles a [`SCROLL_COMMAND`] request.
+pub(crate) fn handle<R: Runtime>(
+  webview: &Webview<R>,
+  body: crate::ipc::InvokeBody,
+) -> Result<(), String> {
+  if !webview.scroll_events.load(Ordering::Relaxed) {
+    return Err("the scroll events are not enabled".into());
+  }
+  let message =
+    serde_json::from_value::<ScrollMessage>(body.into_json()).map_err(|e| e.to_string())?;
+  webview
+    .emit(
+      WEBVIEW_SCROLL_EVENT,
+      WebviewScroll {
+        label: webview.label().into(),
+        position: ScrollPosition::new(message.x, message.y),
+        scale_factor: message.scale_factor,
+      },
+    )
+    .map_err(|e| e.to_string())
+}
+
+impl<R: Runtime> Webview<R> {
+  /// Scrolls the document to the given position, in CSS pixels.
+  ///
+  /// Only the document scrolls, nested scroll containers are left as is.
+  pub fn scroll_to(&self, position: ScrollPosition) -> crate::Result<()> {
+    self.eval(&format!(
+      "window.scrollTo({}, {})",
+      serde_json::to_string(&position.x)?,
+      serde_json::to_string(&position.y)?
+    ))
+  }
+
+  /// Returns the scroll position of the document, in CSS pixels.
+  ///
+  /// The scroll position of nested scroll containers is not reported.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Android:** Unsupported.
+  pub async fn scroll_position(&self) -> crate::Result<ScrollPosition> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    self.dispatcher()?.eval_script_with_callback(
+      "({ x: window.scrollX, y: window.scrollY })",
+      move |result| {
+        let _ = tx.send(result);
+      },
+    )?;
+    let result = rx
+      .await
+      .map_err(|_| crate::Error::Runtime(tauri_runtime::Error::FailedToReceiveMessage))?;
+    serde_json::from_str(&result).map_err(Into::into)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+  };
+
+  use serde_json::json;
+
+  use super::{ScrollPosition, SCROLL_COMMAND, WEBVIEW_SCROLL_EVENT};
+  use crate::{
+    ipc::{CallbackFn, InvokeBody},
+    test::{get_ipc_response, mock_app, MockRuntime},
+    webview::{InvokeRequest, WebviewBuilder},
+    Manager, WebviewWindow, WebviewWindowBuilder,
+  };
+
+  fn send(webview: &WebviewWindow<MockRuntime>, body: serde_json::Value) -> bool {
+    get_ipc_response(
+      webview,
+      InvokeRequest {
+        cmd: SCROLL_COMMAND.into(),
+        callback: CallbackFn(0),
+        error: CallbackFn(1),
+        body: InvokeBody::Json(body),
+        headers: Default::default(),
+      },
+    )
+    .is_ok()
+  }
+
+  #[test]
+  fn scrolls_document() {
+    let app = mock_app();
+    let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    let dispatcher = &webview.webview.webview.dispatcher;
+
+    webview.scroll_to(ScrollPosition::new(0.0, 120.5)).unwrap();
+    assert_eq!(
+      dispatcher.last_evaluated_script().unwrap(),
+      "window.scrollTo(0.0, 120.5)"
+    );
+
+    dispatcher.set_eval_result(r#"{"x":0,"y":120.5}"#);
+    assert_eq!(
+      crate::async_runtime::block_on(webview.scroll_position()).unwrap(),
+      ScrollPosition::new(0.0, 120.5)
+    );
+  }
+
+  #[test]
+  fn emits_scroll_events() {
+    let builder = WebviewBuilder::<MockRuntime>::new("main", Default::default())
+      .emit_scroll_events(Duration::from_millis(100))
+      .emit_scroll_events(Duration::from_millis(50));
+    assert_eq!(
+      builder
+        .webview_attributes
+        .initialization_scripts
+        .iter()
+        .filter(|script| script.contains(SCROLL_COMMAND))
+        .count(),
+      1
+    );
+
+    let app = mock_app();
+    let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .emit_scroll_events(Duration::from_millis(50))
+      .build()
+      .unwrap();
+    let payloads = Arc::new(Mutex::new(Vec::new()));
+    let payloads_ = payloads.clone();
+    app.listen_any(WEBVIEW_SCROLL_EVENT, move |event| {
+      payloads_
+        .lock()
+        .unwrap()
+        .push(serde_json::from_str::<serde_json::Value>(event.payload()).unwrap());
+    });
+
+    assert!(send(
+      &webview,
+      json!({ "x": 0, "y": 300, "scaleFactor": 2 })
+    ));
+    assert_eq!(
+      *payloads.lock().unwrap(),
+      [json!({ "label": "main", "position": { "x": 0.0, "y": 300.0 }, "scaleFactor": 2.0 })]
+    );
+  }
+
+  #[test]
+  fn rejects_without_scroll_events() {
+    let app = mock_app();
+    let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    assert!(!send(
+      &webview,
+      json!({ "x": 0, "y": 300, "scaleFactor": 1 })
+    ));
+  }
+}
@@ -0,0 +1,850 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The Chromium `Local Storage` LevelDB database used by WebView2.
+//!
+//! The database is a directory with a `CURRENT` file naming the `MANIFEST-*` file,
+//! which lists the live `*.ldb` tables, and the `*.log` files holding the recent writes.
+//! Both the manifest and the logs are made of 32KiB blocks of checksummed records.
+//!
+//! Chromium stores the items of an origin as `_<origin>\0<key>` keys, where the key and the value
+//! start with a byte telling their encoding: `0` for UTF-16LE and `1` for Latin-1.
+//! `META:<origin>` keys hold the last modification time and the size of the origin items.
+//!
+//! Reading skips the records with invalid checksums, the truncated blocks and the unknown entries instead of failing.
+//! Writing adds a log file with a single batch, which is replayed by Chromium when the webview starts.
+
+use std::{
+  collections::{BTreeMap, HashSet},
+  fs::{self, OpenOptions},
+  io::Write,
+  path::PathBuf,
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use url::Url;
+
+use super::{storage_origin, Backend};
+
+const BLOCK_SIZE: usize = 32768;
+const HEADER_SIZE: usize = 7;
+
+const FULL: u8 = 1;
+const FIRST: u8 = 2;
+const MIDDLE: u8 = 3;
+const LAST: u8 = 4;
+
+const TABLE_MAGIC: u64 = 0xdb4775248b80fb57;
+const FOOTER_SIZE: usize = 48;
+
+const UTF16_FORMAT: u8 = 0;
+const LATIN1_FORMAT: u8 = 1;
+
+/// Microseconds between the Windows epoch (1601-01-01) used by Chromium and the Unix epoch.
+const WINDOWS_EPOCH_OFFSET_MICROS: u64 = 11_644_473_600_000_000;
+
+/// A write of a key, `None` values being deletions.
+type Operation = (Vec<u8>, Option<Vec<u8>>);
+
+/// The Local Storage database of a WebView2 data directory.
+pub(super) struct LevelDbStorage {
+  dir: PathBuf,
+}
+
+/// The merged view of the tables and logs of the database.
+#[derive(Default)]
+struct Database {
+  /// The latest sequence number and value of each key, `None` for deleted keys.
+  entries: BTreeMap<Vec<u8>, (u64, Option<Vec<u8>>)>,
+  last_sequence: u64,
+  next_file_number: u64,
+}
+
+impl Database {
+  fn insert(&mut self, key: &[u8], sequence: u64, value: Option<Vec<u8>>) {
+    self.last_sequence = self.last_sequence.max(sequence);
+    match self.entries.get(key) {
+      Some((current, _)) if *current > sequence => {}
+      _ => {
+        self.entries.insert(key.to_vec(), (sequence, value));
+      }
+    }
+  }
+
+  /// The items of an origin as (raw key, decoded key, raw value).
+  fn items(&self, origin: &str) -> impl Iterator<Item = (&[u8], String, &[u8])> {
+    let prefix = item_prefix(origin);
+    let prefix_len = prefix.len();
+    self
+      .entries
+      .range(prefix.clone()..)
+      .take_while(move |(key, _)| key.starts_with(&prefix))
+      .filter_map(move |(key, (_, value))| {
+        let value = value.as_deref()?;
+        let name = decode_string(&key[prefix_len..])?;
+        Some((key.as_slice(), name, value))
+      })
+  }
+}
+
+impl LevelDbStorage {
+  pub(super) fn new(dir: PathBuf) -> Self {
+    Self { dir }
+  }
+
+  fn load(&self) -> std::io::Result<Database> {
+    let mut database = Database::default();
+    if !self.dir.exists() {
+      return Ok(database);
+    }
+
+    let manifest = fs::read_to_string(self.dir.join("CURRENT"))
+      .ok()
+      .and_then(|current| fs::read(self.dir.join(current.trim())).ok())
+      .map(|data| Manifest::parse(&data));
+
+    let mut tables = Vec::new();
+    let mut logs = Vec::new();
+    for entry in fs::read_dir(&self.dir)? {
+      let path = entry?.path();
+      let (Some(stem), Some(extension)) = (
+        path.file_stem().and_then(|s| s.to_str()),
+        path.extension().and_then(|s| s.to_str()),
+      ) else {
+        continue;
+      };
+      let Ok(number) = stem.parse::<u64>() else {
+        continue;
+      };
+      match extension {
+        "ldb" | "sst" => tables.push((number, path)),
+        "log" => logs.push((number, path)),
+        _ => {}
+      }
+    }
+    tables.sort();
+    logs.sort();
+
+    if let Some(manifest) = &manifest {
+      // the obsolete files waiting to be deleted could resurrect deleted keys
+      tables.retain(|(number, _)| manifest.tables.contains(number));
+      logs.retain(|(number, _)| *number >= manifest.min_log_number());
+      database.last_sequence = manifest.last_sequence;
+      database.next_file_number = manifest.next_file_number;
+    }
+    for (number, _) in tables.iter().chain(logs.iter()) {
+      database.next_file_number = database.next_file_number.max(number + 1);
+    }
+
+    for (_, path) in &tables {
+      for (key, value) in read_table(&fs::read(path)?) {
+        if let Some((key, sequence, live)) = parse_internal_key(&key) {
+          database.insert(key, sequence, live.then_some(value));
+        }
+      }
+    }
+
+    for (_, path) in &logs {
+      for record in read_log_records(&fs::read(path)?) {
+        if let Some((sequence, operations)) = parse_batch(&record) {
+          for (i, (key, value)) in operations.into_iter().enumerate() {
+            database.insert(&key, sequence + i as u64, value);
+          }
+        }
+      }
+    }
+
+    Ok(database)
+  }
+
+  /// Writes a batch to a new log file, so a torn write at the end of the current log cannot hide it.
+  fn write(&self, database: &Database, operations: &[Operation]) -> crate::Result<()> {
+    if !self.dir.join("CURRENT").exists() {
+      return Err(crate::Error::WebviewStorage(format!(
+        "no Local Storage database found in {}",
+        self.dir.display()
+      )));
+    }
+
+    let path = self
+      .dir
+      .join(format!("{:06}.log", database.next_file_number));
+    let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+    let batch = encode_batch(database.last_sequence + 1, operations);
+    file.write_all(&encode_log_records(0, &batch))?;
+    file.sync_all()?;
+    Ok(())
+  }
+
+  /// The operations updating the `META:` entry of the origin after the given changes.
+  fn meta_operation(database: &Database, origin: &str, changes: &[Operation]) -> Operation {
+    let mut size = 0;
+    for (key, _, value) in database.items(origin) {
+      if !changes.iter().any(|(changed, _)| changed == key) {
+        size += key.len() + value.len();
+      }
+    }
+    for (key, value) in changes {
+      if let Some(value) = value {
+        size += key.len() + value.len();
+      }
+    }
+
+    let now = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|d| d.as_micros() as u64)
+      .unwrap_or_default()
+      + WINDOWS_EPOCH_OFFSET_MICROS;
+    // the `LocalStorageOriginMetaData` protobuf message
+    let mut meta = vec![0x08];
+    write_varint(&mut meta, now);
+    meta.push(0x10);
+    write_varint(&mut meta, size as u64);
+
+    (format!("META:{origin}").into_bytes(), Some(meta))
+  }
+
+  fn update(&self, origin: &Url, key: &str, value: Option<&str>) -> crate::Result<()> {
+    let database = self.load()?;
+    let origin = storage_origin(origin);
+
+    // the key may be stored with another encoding
+    let mut changes: Vec<Operation> = database
+      .items(&origin)
+      .filter(|(_, name, _)| name == key)
+      .map(|(raw, _, _)| (raw.to_vec(), None))
+      .collect();
+    if let Some(value) = value {
+      let mut raw = item_prefix(&origin);
+      raw.extend(encode_string(key));
+      changes.retain(|(changed, _)| *changed != raw);
+      changes.push((raw, Some(encode_string(value))));
+    }
+    if changes.is_empty() {
+      return Ok(());
+    }
+
+    let meta = Self::meta_operation(&database, &origin, &changes);
+    changes.push(meta);
+    self.write(&database, &changes)
+  }
+}
+
+impl Backend for LevelDbStorage {
+  fn get(&self, origin: &Url, key: &str) -> crate::Result<Option<String>> {
+    let database = self.load()?;
+    let origin = storage_origin(origin);
+    let value = database
+      .items(&origin)
+      .find(|(_, name, _)| name == key)
+      .and_then(|(_, _, value)| decode_string(value));
+    Ok(value)
+  }
+
+  fn set(&self, origin: &Url, key: &str, value: &str) -> crate::Result<()> {
+    self.update(origin, key, Some(value))
+  }
+
+  fn remove(&self, origin: &Url, key: &str) -> crate::Result<()> {
+    self.update(origin, key, None)
+  }
+
+  fn keys(&self, origin: &Url) -> crate::Result<Vec<String>> {
+    let database = self.load()?;
+    let origin = storage_origin(origin);
+    let keys = database.items(&origin).map(|(_, name, _)| name).collect();
+    Ok(keys)
+  }
+}
+
+/// The live files and the last sequence number recorded by a `MANIFEST-*` file.
+#[derive(Default)]
+struct Manifest {
+  tables: HashSet<u64>,
+  log_number: u64,
+  prev_log_number: u64,
+  next_file_number: u64,
+  last_sequence: u64,
+}
+
+impl Manifest {
+  fn parse(data: &[u8]) -> Self {
+    let mut manifest = Self::default();
+    for record in read_log_records(data) {
+      manifest.apply_edit(&record);
+    }
+    manifest
+  }
+
+  /// Applies a `VersionEdit` record, stopping at the first unknown or malformed field.
+  fn apply_edit(&mut self, edit: &[u8]) {
+    let mut pos = 0;
+    while pos < edit.len() {
+      let Some(tag) = read_varint(edit, &mut pos) else {
+        return;
+      };
+      let parsed = match tag {
+        // comparator
+        1 => read_slice(edit, &mut pos).map(|_| ()),
+        2 => read_varint(edit, &mut pos).map(|n| self.log_number = n),
+        3 => read_varint(edit, &mut pos).map(|n| self.next_file_number = n),
+        4 => read_varint(edit, &mut pos).map(|n| self.last_sequence = n),
+        // compact pointer
+        5 => read_varint(edit, &mut pos).and_then(|_| read_slice(edit, &mut pos).map(|_| ())),
+        // deleted file
+        6 => read_varint(edit, &mut pos)
+          .and_then(|_| read_varint(edit, &mut pos))
+          .map(|number| {
+            self.tables.remove(&number);
+          }),
+        // new file: level, number, size, smallest and largest keys
+        7 => read_varint(edit, &mut pos)
+          .and_then(|_| read_varint(edit, &mut pos))
+          .and_then(|number| {
+            read_varint(edit, &mut pos)?;
+            read_slice(edit, &mut pos)?;
+            read_slice(edit, &mut pos)?;
+            self.tables.insert(number);
+            Some(())
+          }),
+        9 => read_varint(edit, &mut pos).map(|n| self.prev_log_number = n),
+        _ => None,
+      };
+      if parsed.is_none() {
+        return;
+      }
+    }
+  }
+
+  fn min_log_number(&self) -> u64 {
+    if self.prev_log_number == 0 {
+      self.log_number
+    } else {
+      self.log_number.min(self.prev_log_number)
+    }
+  }
+}
+
+fn item_prefix(origin: &str) -> Vec<u8> {
+  let mut prefix = Vec::with_capacity(origin.len() + 2);
+  prefix.push(b'_');
+  prefix.extend_from_slice(origin.as_bytes());
+  prefix.push(0);
+  prefix
+}
+
+/// Encodes a string with the Chromium format prefix, using Latin-1 when possible.
+fn encode_string(s: &str) -> Vec<u8> {
+  if s.chars().all(|c| (c as u32) <= 0xff) {
+    let mut encoded = vec![LATIN1_FORMAT];
+    encoded.extend(s.chars().map(|c| c as u8));
+    encoded
+  } else {
+    let mut encoded = vec![UTF16_FORMAT];
+    encoded.extend(s.encode_utf16().flat_map(u16::to_le_bytes));
+    encoded
+  }
+}
+
+fn decode_string(bytes: &[u8]) -> Option<String> {
+  let (format, data) = bytes.split_first()?;
+  match *format {
+    UTF16_FORMAT if data.len() % 2 == 0 => {
+      let units = data
+        .chunks_exact(2)
+        .map(|unit| u16::from_le_bytes([unit[0], unit[1]]));
+      Some(
+        char::decode_utf16(units)
+          .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+          .collect(),
+      )
+    }
+    LATIN1_FORMAT => Some(data.iter().map(|b| *b as char).collect()),
+    _ => None,
+  }
+}
+
+fn read_varint(data: &[u8], pos: &mut usize) -> Option<u64> {
+  let mut value = 0u64;
+  for shift in (0..64).step_by(7) {
+    let byte = *data.get(*pos)?;
+    *pos += 1;
+    value |= u64::from(byte & 0x7f) << shift;
+    if byte & 0x80 == 0 {
+      return Some(value);
+    }
+  }
+  None
+}
+
+fn read_slice<'a>(data: &'a [u8], pos: &mut usize) -> Option<&'a [u8]> {
+  let len = read_varint(data, pos)? as usize;
+  let slice = data.get(*pos..pos.checked_add(len)?)?;
+  *pos += len;
+  Some(slice)
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+  while value >= 0x80 {
+    out.push(value as u8 | 0x80);
+    value >>= 7;
+  }
+  out.push(value as u8);
+}
+
+fn write_slice(out: &mut Vec<u8>, slice: &[u8]) {
+  write_varint(out, slice.len() as u64);
+  out.extend_from_slice(slice);
+}
+
+/// Splits a table key into the user key, its sequence number and whether it holds a value.
+fn parse_internal_key(key: &[u8]) -> Option<(&[u8], u64, bool)> {
+  let split = key.len().checked_sub(8)?;
+  let (user_key, trailer) = key.split_at(split);
+  let trailer = u64::from_le_bytes(trailer.try_into().ok()?);
+  match trailer & 0xff {
+    0 => Some((user_key, trailer >> 8, false)),
+    1 => Some((user_key, trailer >> 8, true)),
+    _ => None,
+  }
+}
+
+/// Reads the records of a log or manifest file, skipping the corrupted ones.
+fn read_log_records(data: &[u8]) -> Vec<Vec<u8>> {
+  let mut records = Vec::new();
+  let mut fragments: Option<Vec<u8>> = None;
+  let mut offset = 0;
+
+  while offset + HEADER_SIZE <= data.len() {
+    let block_remaining = BLOCK_SIZE - offset % BLOCK_SIZE;
+    if block_remaining < HEADER_SIZE {
+      // block trailer
+      offset += block_remaining;
+      continue;
+    }
+
+    let header = &data[offset..offset + HEADER_SIZE];
+    let checksum = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+    let length = u16::from_le_bytes([header[4], header[5]]) as usize;
+    let kind = header[6];
+    let start = offset + HEADER_SIZE;
+    let end = start + length;
+
+    if (kind == 0 && length == 0) || end > data.len() || length > block_remaining - HEADER_SIZE {
+      // preallocated zeroes or a corrupted header: the rest of the block is unusable
+      fragments = None;
+      offset += block_remaining;
+      continue;
+    }
+    offset = end;
+
+    let payload = &data[start..end];
+    if unmask_crc(checksum) != crc32c(crc32c(0, &[kind]), payload) {
+      fragments = None;
+      continue;
+    }
+
+    match kind {
+      FULL => {
+        fragments = None;
+        records.push(payload.to_vec());
+      }
+      FIRST => fragments = Some(payload.to_vec()),
+      MIDDLE => {
+        if let Some(fragments) = &mut fragments {
+          fragments.extend_from_slice(payload);
+        }
+      }
+      LAST => {
+        if let Some(mut record) = fragments.take() {
+          record.extend_from_slice(payload);
+          records.push(record);
+        }
+      }
+      _ => fragments = None,
+    }
+  }
+
+  records
+}
+
+/// Encodes a record as log fragments, starting at the given offset of the current block.
+fn encode_log_records(mut block_offset: usize, record: &[u8]) -> Vec<u8> {
+  let mut out = Vec::with_capacity(record.len() + HEADER_SIZE);
+  let mut remaining = record;
+  let mut begin = true;
+  loop {
+    let leftover = BLOCK_SIZE - block_offset;
+    if leftover < HEADER_SIZE {
+      out.resize(out.len() + leftover, 0);
+      block_offset = 0;
+    }
+
+    let available = BLOCK_SIZE - block_offset - HEADER_SIZE;
+    let (fragment, rest) = remaining.split_at(remaining.len().min(available));
+    let end = rest.is_empty();
+    let kind = match (begin, end) {
+      (true, true) => FULL,
+      (true, false) => FIRST,
+      (false, true) => LAST,
+      (false, false) => MIDDLE,
+    };
+
+    out.extend_from_slice(&mask_crc(crc32c(crc32c(0, &[kind]), fragment)).to_le_bytes());
+    out.extend_from_slice(&(fragment.len() as u16).to_le_bytes());
+    out.push(kind);
+    out.extend_from_slice(fragment);
+
+    block_offset += HEADER_SIZE + fragment.len();
+    remaining = rest;
+    begin = false;
+    if end {
+      return out;
+    }
+  }
+}
+
+/// Parses a write batch into its sequence number and operations.
+fn parse_batch(record: &[u8]) -> Option<(u64, Vec<Operation>)> {
+  let sequence = u64::from_le_bytes(record.get(..8)?.try_into().ok()?);
+  let count = u32::from_le_bytes(record.get(8..12)?.try_into().ok()?);
+  let mut pos = 12;
+  let mut operations = Vec::new();
+  for _ in 0..count {
+    let tag = *record.get(pos)?;
+    pos += 1;
+    let key = read_slice(record, &mut pos)?.to_vec();
+    match tag {
+      0 => operations.push((key, None)),
+      1 => {
+        let value = read_slice(record, &mut pos)?.to_vec();
+        operations.push((key, Some(value)));
+      }
+      _ => return None,
+    }
+  }
+  Some((sequence, operations))
+}
+
+fn encode_batch(sequence: u64, operations: &[Operation]) -> Vec<u8> {
+  let mut batch = Vec::new();
+  batch.extend_from_slice(&sequence.to_le_bytes());
+  batch.extend_from_slice(&(operations.len() as u32).to_le_bytes());
+  for (key, value) in operations {
+    match value {
+      Some(value) => {
+        batch.push(1);
+        write_slice(&mut batch, key);
+        write_slice(&mut batch, value);
+      }
+      None => {
+        batch.push(0);
+        write_slice(&mut batch, key);
+      }
+    }
+  }
+  batch
+}
+
+/// Reads the entries of a `.ldb` table, skipping the corrupted blocks.
+fn read_table(data: &[u8]) -> Vec<(Vec<u8>, Vec<u8>)> {
+  let mut entries = Vec::new();
+  let Some(footer) = data
+    .len()
+    .checked_sub(FOOTER_SIZE)
+    .map(|start| &data[start..])
+  else {
+    return entries;
+  };
+  if u64::from_le_bytes(footer[40..].try_into().unwrap()) != TABLE_MAGIC {
+    return entries;
+  }
+
+  let mut pos = 0;
+  // skip the metaindex handle
+  let index = read_varint(footer, &mut pos)
+    .and_then(|_| read_varint(footer, &mut pos))
+    .and_then(|_| read_block_handle(footer, &mut pos))
+    .and_then(|handle| read_block(data, handle));
+  let Some(index) = index else {
+    return entries;
+  };
+
+  for (_, handle) in block_entries(&index) {
+    let block = read_block_handle(&handle, &mut 0).and_then(|handle| read_block(data, handle));
+    if let Some(block) = block {
+      entries.extend(block_entries(&block));
+    }
+  }
+  entries
+}
+
+fn read_block_handle(data: &[u8], pos: &mut usize) -> Option<(usize, usize)> {
+  let offset = read_varint(data, pos)? as usize;
+  let size = read_varint(data, pos)? as usize;
+  Some((offset, size))
+}
+
+/// Reads a table block, uncompressing it when needed. The block checksum is not verified.
+fn read_block(data: &[u8], (offset, size): (usize, usize)) -> Option<Vec<u8>> {
+  let end = offset.checked_add(size)?;
+  let block = data.get(offset..end)?;
+  match data.get(end)? {
+    0 => Some(block.to_vec()),
+    1 => snappy_decompress(block),
+    _ => None,
+  }
+}
+
+/// Reads the prefix compressed entries of a block, stopping at the first malformed one.
+fn block_entries(block: &[u8]) -> Vec<(Vec<u8>, Vec<u8>)> {
+  let mut entries = Vec::new();
+  let Some(restarts) = block
+    .len()
+    .checked_sub(4)
+    .map(|start| u32::from_le_bytes(block[start..].try_into().unwrap()) as usize)
+  else {
+    return entries;
+  };
+  let Some(end) = restarts
+    .checked_mul(4)
+    .and_then(|size| block.len().checked_sub(4 + size))
+  else {
+    return entries;
+  };
+
+  let data = &block[..end];
+  let mut pos = 0;
+  let mut key = Vec::new();
+  while pos < data.len() {
+    let entry = (|| {
+      let shared = read_varint(data, &mut pos)? as usize;
+      let non_shared = read_varint(data, &mut pos)? as usize;
+      let value_len = read_varint(data, &mut pos)? as usize;
+      if shared > key.len() {
+        return None;
+      }
+      let key_delta = data.get(pos..pos.checked_add(non_shared)?)?;
+      pos += non_shared;
+      let value = data.get(pos..pos.checked_add(value_len)?)?;
+      pos += value_len;
+      key.truncate(shared);
+      key.extend_from_slice(key_delta);
+      Some(value.to_vec())
+    })();
+    match entry {
+      Some(value) => entries.push((key.clone(), value)),
+      None => break,
+    }
+  }
+  entries
+}
+
+/// Uncompresses a raw snappy block.
+fn snappy_decompress(input: &[u8]) -> Option<Vec<u8>> {
+  let mut pos = 0;
+  let len = read_varint(input, &mut pos)? as usize;
+  let mut out = Vec::with_capacity(len.min(BLOCK_SIZE * 4));
+
+  while pos < input.len() {
+    let tag = input[pos];
+    pos += 1;
+    match tag & 0b11 {
+      0 => {
+        let mut literal_len = (tag >> 2) as usize;
+        if literal_len >= 60 {
+          let bytes = literal_len - 59;
+          let len_bytes = input.get(pos..pos + bytes)?;
+          pos += bytes;
+          literal_len = len_bytes
+            .iter()
+            .rev()
+            .fold(0, |len, b| (len << 8) | *b as usize);
+        }
+        let literal = input.get(pos..pos.checked_add(literal_len + 1)?)?;
+        pos += literal_len + 1;
+        out.extend_from_slice(literal);
+      }
+      kind => {
+        let (copy_len, offset) = match kind {
+          1 => {
+            let next = *input.get(pos)? as usize;
+            pos += 1;
+            (
+              ((tag >> 2) & 0b111) as usize + 4,
+              (((tag >> 5) as usize) << 8) | next,
+            )
+          }
+          2 => {
+            let bytes = input.get(pos..pos + 2)?;
+            pos += 2;
+            (
+              (tag >> 2) as usize + 1,
+              u16::from_le_bytes([bytes[0], bytes[1]]) as usize,
+            )
+          }
+          _ => {
+            let bytes = input.get(pos..pos + 4)?;
+            pos += 4;
+            (
+              (tag >> 2) as usize + 1,
+              u32::from_le_bytes(bytes.try_into().ok()?) as usize,
+            )
+          }
+        };
+        if offset == 0 || offset > out.len() {
+          return None;
+        }
+        let start = out.len() - offset;
+        // the copy may overlap the bytes it produces
+        for i in 0..copy_len {
+          out.push(out[start + i]);
+        }
+      }
+    }
+  }
+
+  (out.len() == len).then_some(out)
+}
+
+fn crc32c(crc: u32, data: &[u8]) -> u32 {
+  let mut crc = !crc;
+  for byte in data {
+    crc ^= u32::from(*byte);
+    for _ in 0..8 {
+      crc = (crc >> 1) ^ (0x82f6_3b78 & (!(crc & 1)).wrapping_add(1));
+    }
+  }
+  !crc
+}
+
+fn mask_crc(crc: u32) -> u32 {
+  crc.rotate_right(15).wrapping_add(0xa282_ead8)
+}
+
+fn unmask_crc(masked: u32) -> u32 {
+  masked.wrapping_sub(0xa282_ead8).rotate_left(15)
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::PathBuf;
+
+  use url::Url;
+
+  use super::{
+    crc32c, encode_log_records, read_log_records, snappy_decompress, Backend, LevelDbStorage,
+    BLOCK_SIZE, HEADER_SIZE,
+  };
+
+  fn fixture() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test/fixture/webview-storage/leveldb")
+  }
+
+  fn origin() -> Url {
+    "https://tauri.localhost".parse().unwrap()
+  }
+
+  #[test]
+  fn crc32c_values() {
+    assert_eq!(crc32c(0, b"123456789"), 0xe306_9283);
+    assert_eq!(crc32c(0, &[0; 32]), 0x8a91_36aa);
+  }
+
+  #[test]
+  fn snappy() {
+    // literal "abcd" then a 6 bytes copy at offset 4
+    let compressed = [10, 0b0000_1100, b'a', b'b', b'c', b'd', 0b0000_1001, 4];
+    assert_eq!(snappy_decompress(&compressed).unwrap(), b"abcdabcdab");
+    // out of bounds copy
+    assert!(snappy_decompress(&[4, 0b0000_0001, 4]).is_none());
+  }
+
+  #[test]
+  fn log_records_span_blocks() {
+    let large = vec![7u8; BLOCK_SIZE * 2];
+    let mut log = encode_log_records(0, b"first");
+    log.extend(encode_log_records(log.len() % BLOCK_SIZE, &large));
+    log.extend(encode_log_records(log.len() % BLOCK_SIZE, b"last"));
+    assert_eq!(
+      read_log_records(&log),
+      vec![b"first".to_vec(), large, b"last".to_vec()]
+    );
+
+    // a corrupted record is skipped
+    log[HEADER_SIZE] ^= 0xff;
+    assert_eq!(read_log_records(&log).len(), 2);
+  }
+
+  #[test]
+  fn reads_fixture() {
+    let storage = LevelDbStorage::new(fixture());
+    // from the snappy compressed table
+    assert_eq!(
+      storage.get(&origin(), "theme").unwrap().as_deref(),
+      Some("dark")
+    );
+    // overwritten by the log
+    assert_eq!(
+      storage.get(&origin(), "width").unwrap().as_deref(),
+      Some("1280")
+    );
+    // UTF-16 encoded
+    assert_eq!(
+      storage.get(&origin(), "greeting").unwrap().as_deref(),
+      Some("héllo wörld ✓")
+    );
+    // deleted by the log
+    assert_eq!(storage.get(&origin(), "token").unwrap(), None);
+    // written by a record with an invalid checksum
+    assert_eq!(storage.get(&origin(), "corrupted").unwrap(), None);
+    // stored in an obsolete table
+    assert_eq!(storage.get(&origin(), "stale").unwrap(), None);
+
+    let mut keys = storage.keys(&origin()).unwrap();
+    keys.sort();
+    assert_eq!(keys, ["greeting", "height", "theme", "width"]);
+    assert!(storage
+      .keys(&"https://other.localhost".parse().unwrap())
+      .unwrap()
+      .is_empty());
+  }
+
+  #[test]
+  fn writes_to_the_log() {
+    let dir = std::env::temp_dir().join(format!("tauri-leveldb-storage-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    for entry in std::fs::read_dir(fixture()).unwrap() {
+      let entry = entry.unwrap();
+      std::fs::copy(entry.path(), dir.join(entry.file_name())).unwrap();
+    }
+
+    let storage = LevelDbStorage::new(dir.clone());
+    storage.set(&origin(), "width", "1920").unwrap();
+    storage.set(&origin(), "title", "日本語").unwrap();
+    storage.remove(&origin(), "theme").unwrap();
+
+    assert_eq!(
+      storage.get(&origin(), "width").unwrap().as_deref(),
+      Some("1920")
+    );
+    assert_eq!(
+      storage.get(&origin(), "title").unwrap().as_deref(),
+      Some("日本語")
+    );
+    assert_eq!(storage.get(&origin(), "theme").unwrap(), None);
+    let database = storage.load().unwrap();
+    assert!(database
+      .entries
+      .contains_key(b"META:https://tauri.localhost".as_slice()));
+
+    let _ = std::fs::remove_dir_all(&dir);
+
+    // no database to write to
+    let missing = LevelDbStorage::new(dir);
+    assert_eq!(missing.get(&origin(), "width").unwrap(), None);
+    assert!(missing.set(&origin(), "width", "1920").is_err());
+  }
+}
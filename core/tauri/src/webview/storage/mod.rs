@@ -0,0 +1,492 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Access to the web storage of the webviews from Rust, see [`AppHandle::webview_storage`].
+
+#[cfg(any(windows, test))]
+mod leveldb;
+#[cfg(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "openbsd",
+  target_os = "netbsd",
+  target_os = "macos"
+))]
+mod webkit;
+
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use tauri_runtime::WebviewDispatch;
+use url::Url;
+
+use super::Webview;
+use crate::{sealed::ManagerBase, AppHandle, Manager, Runtime};
+
+/// The web storage area accessed by a [`StorageHandle`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StorageArea {
+  /// `window.localStorage`, persisted to the webview data directory.
+  #[default]
+  Local,
+  /// `window.sessionStorage`, only available while the webview is running.
+  Session,
+}
+
+impl StorageArea {
+  fn js_object(self) -> &'static str {
+    match self {
+      Self::Local => "localStorage",
+      Self::Session => "sessionStorage",
+    }
+  }
+}
+
+/// The files backing the local storage of a platform.
+trait Backend: Send {
+  fn get(&self, origin: &Url, key: &str) -> crate::Result<Option<String>>;
+  fn set(&self, origin: &Url, key: &str, value: &str) -> crate::Result<()>;
+  fn remove(&self, origin: &Url, key: &str) -> crate::Result<()>;
+  fn keys(&self, origin: &Url) -> crate::Result<Vec<String>>;
+}
+
+/// Reads and writes the web storage of a webview, see [`AppHandle::webview_storage`].
+pub struct StorageHandle<R: Runtime> {
+  app: AppHandle<R>,
+  label: String,
+  #[cfg_attr(any(target_os = "macos", mobile), allow(dead_code))]
+  data_directory: Option<PathBuf>,
+  area: StorageArea,
+}
+
+impl<R: Runtime> AppHandle<R> {
+  /// Accesses the web storage of the webview with the given label.
+  ///
+  /// While the webview is running, the storage is accessed through the webview itself,
+  /// so the origin must be the origin of the page it shows.
+  /// Otherwise the files backing the local storage in the webview data directory are read and written directly,
+  /// which allows reading a value stored during a previous run before any webview is created.
+  /// The webviews sharing a data directory share the same local storage,
+  /// use [`StorageHandle::data_directory`] for the webviews built with [`super::WebviewBuilder::data_directory`].
+  /// While another webview using the data directory is running, the storage is accessed through it instead of the files,
+  /// and the access fails if it is not showing a page of the origin.
+  ///
+  /// The following formats are handled:
+  /// - **Windows:** the Chromium LevelDB database in the `EBWebView/Default/Local Storage/leveldb` directory.
+  ///   Writes are appended to its log and applied by WebView2 when it starts.
+  /// - **Linux:** the WebKitGTK SQLite databases in the data directory.
+  /// - **macOS:** the WKWebView SQLite databases in `~/Library/WebKit/<identifier>/WebsiteData`.
+  ///   New databases can only be created by the legacy layout of older macOS versions.
+  ///
+  /// Corrupted records are skipped instead of failing the read.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS:** The data directory is not configurable.
+  /// - **iOS / Android:** Only supported while the webview is running.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use tauri::{WebviewUrl, WebviewWindowBuilder};
+  ///
+  /// tauri::Builder::default()
+  ///   .setup(|app| {
+  ///     let origin = "https://tauri.localhost".parse().unwrap();
+  ///     let storage = app.handle().webview_storage("main");
+  ///     let width = tauri::async_runtime::block_on(storage.get(&origin, "width"))?
+  ///       .and_then(|width| width.parse().ok())
+  ///       .unwrap_or(800.);
+  ///     WebviewWindowBuilder::new(app, "main", WebviewUrl::default())
+  ///       .inner_size(width, 600.)
+  ///       .build()?;
+  ///     Ok(())
+  ///   });
+  /// ```
+  pub fn webview_storage(&self, label: impl Into<String>) -> StorageHandle<R> {
+    StorageHandle {
+      app: self.clone(),
+      label: label.into(),
+      data_directory: None,
+      area: StorageArea::Local,
+    }
+  }
+}
+
+impl<R: Runtime> StorageHandle<R> {
+  /// Sets the data directory of the webview, see [`super::WebviewBuilder::data_directory`].
+  #[must_use]
+  pub fn data_directory(mut self, data_directory: PathBuf) -> Self {
+    self.data_directory.replace(data_directory);
+    self
+  }
+
+  /// Sets the storage area to access. Defaults to [`StorageArea::Local`].
+  #[must_use]
+  pub fn area(mut self, area: StorageArea) -> Self {
+    self.area = area;
+    self
+  }
+
+  /// Gets the value of a key.
+  pub async fn get(&self, origin: &Url, key: &str) -> crate::Result<Option<String>> {
+    match self.target(origin)? {
+      Target::Webview(webview) => {
+        let expression = format!(
+          "{}.getItem({})",
+          self.area.js_object(),
+          serde_json::to_string(key)?
+        );
+        let value = self.eval(&webview, origin, &expression).await?;
+        Ok(serde_json::from_value(value)?)
+      }
+      Target::Files(backend) => backend.get(origin, key),
+    }
+  }
+
+  /// Sets the value of a key.
+  pub async fn set(&self, origin: &Url, key: &str, value: &str) -> crate::Result<()> {
+    match self.target(origin)? {
+      Target::Webview(webview) => {
+        let expression = format!(
+          "({}.setItem({}, {}), null)",
+          self.area.js_object(),
+          serde_json::to_string(key)?,
+          serde_json::to_string(value)?
+        );
+        self.eval(&webview, origin, &expression).await.map(|_| ())
+      }
+      Target::Files(backend) => backend.set(origin, key, value),
+    }
+  }
+
+  /// Removes a key.
+  pub async fn remove(&self, origin: &Url, key: &str) -> crate::Result<()> {
+    match self.target(origin)? {
+      Target::Webview(webview) => {
+        let expression = format!(
+          "({}.removeItem({}), null)",
+          self.area.js_object(),
+          serde_json::to_string(key)?
+        );
+        self.eval(&webview, origin, &expression).await.map(|_| ())
+      }
+      Target::Files(backend) => backend.remove(origin, key),
+    }
+  }
+
+  /// Lists the keys of an origin.
+  pub async fn keys(&self, origin: &Url) -> crate::Result<Vec<String>> {
+    match self.target(origin)? {
+      Target::Webview(webview) => {
+        let expression = format!("Object.keys({})", self.area.js_object());
+        let value = self.eval(&webview, origin, &expression).await?;
+        Ok(serde_json::from_value(value)?)
+      }
+      Target::Files(backend) => backend.keys(origin),
+    }
+  }
+
+  fn target(&self, origin: &Url) -> crate::Result<Target<R>> {
+    if let Some(webview) = self.app.manager().get_webview(&self.label) {
+      return Ok(Target::Webview(webview));
+    }
+    if self.area == StorageArea::Session {
+      return Err(crate::Error::WebviewStorage(format!(
+        "the sessionStorage of the `{}` webview is only available while it is running",
+        self.label
+      )));
+    }
+
+    // the running webviews keep the storage in memory and overwrite the files
+    let mut sharing = Vec::new();
+    for webview in self.app.manager().webviews().into_values() {
+      if self.shares_data_directory(&webview)? {
+        sharing.push(webview);
+      }
+    }
+    if sharing.is_empty() {
+      return self.backend().map(Target::Files);
+    }
+    let origin = storage_origin(origin);
+    match sharing
+      .iter()
+      .find(|webview| webview.url().is_ok_and(|url| storage_origin(&url) == origin))
+    {
+      Some(webview) => Ok(Target::Webview(webview.clone())),
+      None => Err(crate::Error::WebviewStorage(format!(
+        "the local storage of the `{}` webview is used by the running `{}` webview, which is not showing a page of the {origin} origin",
+        self.label,
+        sharing[0].label()
+      ))),
+    }
+  }
+
+  /// Whether the running webview uses the data directory of the storage.
+  #[cfg(any(
+    windows,
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd"
+  ))]
+  fn shares_data_directory(&self, webview: &Webview<R>) -> crate::Result<bool> {
+    let recorded = self
+      .app
+      .manager()
+      .webview
+      .data_directories
+      .lock()
+      .unwrap()
+      .get(webview.label())
+      .cloned();
+    let webview_directory = match recorded {
+      Some(data_directory) => data_directory,
+      None => self.default_data_directory()?,
+    };
+    Ok(webview_directory == self.resolved_data_directory()?)
+  }
+
+  /// All the webviews share the data store of the app.
+  #[cfg(any(target_os = "macos", mobile))]
+  fn shares_data_directory(&self, _webview: &Webview<R>) -> crate::Result<bool> {
+    Ok(true)
+  }
+
+  /// The default data directory set by the webview manager.
+  #[cfg(any(
+    windows,
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd"
+  ))]
+  fn default_data_directory(&self) -> crate::Result<PathBuf> {
+    self.app.path().resolve(
+      &self.app.config().identifier,
+      crate::path::BaseDirectory::LocalData,
+    )
+  }
+
+  #[cfg(any(
+    windows,
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd"
+  ))]
+  fn resolved_data_directory(&self) -> crate::Result<PathBuf> {
+    match &self.data_directory {
+      Some(data_directory) => Ok(data_directory.clone()),
+      None => self.default_data_directory(),
+    }
+  }
+
+  #[cfg(any(
+    windows,
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "netbsd"
+  ))]
+  fn backend(&self) -> crate::Result<Box<dyn Backend>> {
+    let data_directory = self.resolved_data_directory()?;
+
+    #[cfg(windows)]
+    return Ok(Box::new(leveldb::LevelDbStorage::new(
+      data_directory.join("EBWebView/Default/Local Storage/leveldb"),
+    )));
+    #[cfg(not(windows))]
+    Ok(Box::new(webkit::WebKitStorage::new(data_directory)))
+  }
+
+  #[cfg(target_os = "macos")]
+  fn backend(&self) -> crate::Result<Box<dyn Backend>> {
+    let root = self
+      .app
+      .path()
+      .home_dir()?
+      .join("Library/WebKit")
+      .join(&self.app.config().identifier)
+      .join("WebsiteData");
+    Ok(Box::new(webkit::WebKitStorage::new(root)))
+  }
+
+  #[cfg(mobile)]
+  fn backend(&self) -> crate::Result<Box<dyn Backend>> {
+    Err(std::io::Error::from(std::io::ErrorKind::Unsupported).into())
+  }
+
+  /// Evaluates a storage expression on the webview and returns its result.
+  ///
+  /// The origin is checked by the script, so a navigation can't make it access the storage of another origin.
+  async fn eval(
+    &self,
+    webview: &Webview<R>,
+    origin: &Url,
+    expression: &str,
+  ) -> crate::Result<serde_json::Value> {
+    let origin = storage_origin(origin);
+
+    #[derive(Deserialize)]
+    struct EvalResult {
+      #[serde(default)]
+      value: serde_json::Value,
+      error: Option<String>,
+    }
+
+    // `location.host` omits the default ports, like `storage_origin`
+    let script = format!(
+      "(function () {{ if (location.protocol + '//' + location.host !== {origin_json}) {{ return {{ error: {origin_error} }}; }} try {{ return {{ value: {expression} }}; }} catch (e) {{ return {{ error: String(e) }}; }} }})()",
+      origin_json = serde_json::to_string(&origin)?,
+      origin_error = serde_json::to_string(&format!(
+        "the `{}` webview is not showing a page of the {origin} origin",
+        webview.label()
+      ))?,
+    );
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    webview
+      .webview
+      .dispatcher
+      .eval_script_with_callback(script, move |result| {
+        let _ = tx.send(result);
+      })?;
+    let result = rx
+      .await
+      .map_err(|_| crate::Error::Runtime(tauri_runtime::Error::FailedToReceiveMessage))?;
+
+    let result: EvalResult = serde_json::from_str(&result).map_err(|_| {
+      crate::Error::WebviewStorage(format!(
+        "failed to evaluate the storage script on the `{}` webview",
+        webview.label()
+      ))
+    })?;
+    match result.error {
+      Some(error) => Err(crate::Error::WebviewStorage(error)),
+      None => Ok(result.value),
+    }
+  }
+}
+
+enum Target<R: Runtime> {
+  Webview(Webview<R>),
+  Files(Box<dyn Backend>),
+}
+
+/// Serializes the origin of a URL the way the webviews key their storage, e.g. `https://tauri.localhost`.
+///
+/// Unlike [`Url::origin`], the origins of custom schemes such as `tauri://localhost` are not opaque.
+fn storage_origin(url: &Url) -> String {
+  let mut origin = format!("{}://{}", url.scheme(), url.host_str().unwrap_or_default());
+  if let Some(port) = url.port() {
+    origin.push_str(&format!(":{port}"));
+  }
+  origin
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{storage_origin, StorageArea};
+  use crate::{test::mock_app, WebviewWindowBuilder};
+
+  #[test]
+  fn serializes_origins() {
+    for (url, origin) in [
+      (
+        "https://tauri.localhost/index.html",
+        "https://tauri.localhost",
+      ),
+      ("tauri://localhost", "tauri://localhost"),
+      ("http://localhost:1420/?a=b", "http://localhost:1420"),
+      ("https://tauri.app:443", "https://tauri.app"),
+    ] {
+      assert_eq!(storage_origin(&url.parse().unwrap()), origin);
+    }
+  }
+
+  #[test]
+  fn live_webview_uses_eval() {
+    let app = mock_app();
+    let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    let origin = webview.url().unwrap();
+    let dispatcher = &webview.webview.webview.dispatcher;
+    let storage = app.handle().webview_storage("main");
+
+    dispatcher.set_eval_result(r#"{"value":"1280"}"#);
+    let value = crate::async_runtime::block_on(storage.get(&origin, "width")).unwrap();
+    assert_eq!(value.as_deref(), Some("1280"));
+    assert!(dispatcher
+      .last_evaluated_script()
+      .unwrap()
+      .contains(r#"localStorage.getItem("width")"#));
+
+    dispatcher.set_eval_result(r#"{"value":["a","b"]}"#);
+    let keys = crate::async_runtime::block_on(
+      app
+        .handle()
+        .webview_storage("main")
+        .area(StorageArea::Session)
+        .keys(&origin),
+    )
+    .unwrap();
+    assert_eq!(keys, ["a", "b"]);
+    assert!(dispatcher
+      .last_evaluated_script()
+      .unwrap()
+      .contains("Object.keys(sessionStorage)"));
+
+    dispatcher.set_eval_result(r#"{"error":"SecurityError"}"#);
+    assert!(crate::async_runtime::block_on(storage.set(&origin, "width", "1")).is_err());
+
+    // the script checks the origin of the page when it is evaluated
+    dispatcher.set_eval_result(r#"{"value":null}"#);
+    let other = "https://other.localhost".parse().unwrap();
+    crate::async_runtime::block_on(storage.get(&other, "width")).unwrap();
+    assert!(dispatcher
+      .last_evaluated_script()
+      .unwrap()
+      .contains(r#"location.host !== "https://other.localhost""#));
+  }
+
+  #[test]
+  fn running_webviews_sharing_the_data_directory_use_eval() {
+    let app = mock_app();
+    let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+      .build()
+      .unwrap();
+    let origin = webview.url().unwrap();
+    let dispatcher = &webview.webview.webview.dispatcher;
+    let storage = app.handle().webview_storage("closed");
+
+    dispatcher.set_eval_result(r#"{"value":"1280"}"#);
+    let value = crate::async_runtime::block_on(storage.get(&origin, "width")).unwrap();
+    assert_eq!(value.as_deref(), Some("1280"));
+    assert!(dispatcher
+      .last_evaluated_script()
+      .unwrap()
+      .contains(r#"localStorage.getItem("width")"#));
+
+    // the files would be overwritten by the running webview
+    let other = "https://other.localhost".parse().unwrap();
+    assert!(crate::async_runtime::block_on(storage.set(&other, "width", "1")).is_err());
+  }
+
+  #[test]
+  fn session_storage_requires_a_webview() {
+    let app = mock_app();
+    let storage = app
+      .handle()
+      .webview_storage("missing")
+      .area(StorageArea::Session);
+    let origin = "https://tauri.localhost".parse().unwrap();
+    assert!(crate::async_runtime::block_on(storage.get(&origin, "width")).is_err());
+  }
+}
@@ -0,0 +1,280 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The SQLite local storage databases used by WKWebView and WebKitGTK.
+//!
+//! Two layouts are handled:
+//! - the legacy one, with a `<scheme>_<host>_<port>.localstorage` database per origin
+//!   in a `LocalStorage` (macOS) or `localstorage` (Linux) directory, the port being `0` when it is the default one;
+//! - the one of recent WebKit versions, with a `LocalStorage/localstorage.sqlite3` database in a directory per origin
+//!   named after a salted hash, next to an `origin` file holding the serialized origin.
+//!
+//! The items are stored in the `ItemTable` table, with a text `key` and a UTF-16LE `value` blob.
+//! Reading skips the rows that cannot be decoded and stops at the first corrupted page, keeping the rows read so far.
+
+use std::{
+  fs,
+  path::{Path, PathBuf},
+};
+
+use rusqlite::{types::ValueRef, Connection, OpenFlags, Row};
+use url::Url;
+
+use super::Backend;
+
+const LEGACY_DIRECTORIES: &[&str] = &["LocalStorage", "localstorage"];
+const DATABASE: &str = "LocalStorage/localstorage.sqlite3";
+/// How deep the origin directories of the recent layout are searched for.
+const MAX_DEPTH: usize = 4;
+
+/// The local storage databases of a WebKit data directory.
+pub(super) struct WebKitStorage {
+  root: PathBuf,
+}
+
+impl WebKitStorage {
+  pub(super) fn new(root: PathBuf) -> Self {
+    Self { root }
+  }
+
+  /// Finds the database of an origin.
+  fn database(&self, origin: &Url) -> Option<PathBuf> {
+    let name = legacy_name(origin);
+    LEGACY_DIRECTORIES
+      .iter()
+      .map(|dir| self.root.join(dir).join(&name))
+      .find(|path| path.is_file())
+      .or_else(|| find_origin_database(&self.root, origin, 0))
+  }
+
+  /// Opens the database of an origin for writing, creating it in the legacy directory if needed.
+  fn open_writable(&self, origin: &Url) -> crate::Result<Connection> {
+    if let Some(path) = self.database(origin) {
+      return Connection::open(path).map_err(storage_error);
+    }
+
+    let dir = LEGACY_DIRECTORIES
+      .iter()
+      .map(|dir| self.root.join(dir))
+      .find(|dir| dir.is_dir())
+      .ok_or_else(|| {
+        crate::Error::WebviewStorage(format!(
+          "no local storage database found for {origin} in {}",
+          self.root.display()
+        ))
+      })?;
+    let connection = Connection::open(dir.join(legacy_name(origin))).map_err(storage_error)?;
+    connection
+      .execute(
+        "CREATE TABLE IF NOT EXISTS ItemTable (key TEXT UNIQUE ON CONFLICT REPLACE PRIMARY KEY NOT NULL, value BLOB NOT NULL ON CONFLICT FAIL)",
+        [],
+      )
+      .map_err(storage_error)?;
+    Ok(connection)
+  }
+
+  fn items(&self, origin: &Url) -> crate::Result<Vec<(String, String)>> {
+    let Some(path) = self.database(origin) else {
+      return Ok(Vec::new());
+    };
+
+    let connection =
+      Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY).map_err(storage_error)?;
+    let mut statement = connection
+      .prepare("SELECT key, value FROM ItemTable")
+      .map_err(storage_error)?;
+    let mut rows = statement.query([]).map_err(storage_error)?;
+
+    let mut items = Vec::new();
+    // an error means the rest of the table is corrupted
+    while let Ok(Some(row)) = rows.next() {
+      if let Some(item) = decode_row(row) {
+        items.push(item);
+      }
+    }
+    Ok(items)
+  }
+}
+
+impl Backend for WebKitStorage {
+  fn get(&self, origin: &Url, key: &str) -> crate::Result<Option<String>> {
+    let value = self
+      .items(origin)?
+      .into_iter()
+      .find(|(name, _)| name == key)
+      .map(|(_, value)| value);
+    Ok(value)
+  }
+
+  fn set(&self, origin: &Url, key: &str, value: &str) -> crate::Result<()> {
+    let value = value
+      .encode_utf16()
+      .flat_map(u16::to_le_bytes)
+      .collect::<Vec<u8>>();
+    self
+      .open_writable(origin)?
+      .execute(
+        "INSERT OR REPLACE INTO ItemTable (key, value) VALUES (?1, ?2)",
+        rusqlite::params![key, value],
+      )
+      .map_err(storage_error)?;
+    Ok(())
+  }
+
+  fn remove(&self, origin: &Url, key: &str) -> crate::Result<()> {
+    let Some(path) = self.database(origin) else {
+      return Ok(());
+    };
+    Connection::open(path)
+      .and_then(|connection| connection.execute("DELETE FROM ItemTable WHERE key = ?1", [key]))
+      .map_err(storage_error)?;
+    Ok(())
+  }
+
+  fn keys(&self, origin: &Url) -> crate::Result<Vec<String>> {
+    let keys = self
+      .items(origin)?
+      .into_iter()
+      .map(|(name, _)| name)
+      .collect();
+    Ok(keys)
+  }
+}
+
+fn storage_error(error: rusqlite::Error) -> crate::Error {
+  crate::Error::WebviewStorage(error.to_string())
+}
+
+/// The `<scheme>_<host>_<port>.localstorage` name of the legacy database of an origin.
+fn legacy_name(origin: &Url) -> String {
+  format!(
+    "{}_{}_{}.localstorage",
+    origin.scheme(),
+    origin.host_str().unwrap_or_default(),
+    origin.port().unwrap_or(0)
+  )
+}
+
+fn find_origin_database(dir: &Path, origin: &Url, depth: usize) -> Option<PathBuf> {
+  let database = dir.join(DATABASE);
+  if database.is_file() && origin_file_matches(&dir.join("origin"), origin) {
+    return Some(database);
+  }
+  if depth == MAX_DEPTH {
+    return None;
+  }
+  fs::read_dir(dir)
+    .ok()?
+    .flatten()
+    .filter(|entry| entry.file_type().map(|t| t.is_dir()).unwrap_or(false))
+    .find_map(|entry| find_origin_database(&entry.path(), origin, depth + 1))
+}
+
+/// Whether the serialized origin file names the origin.
+///
+/// The file holds the binary encoding of the top level and frame origins,
+/// so it is only checked to contain the scheme and the host of the origin.
+fn origin_file_matches(path: &Path, origin: &Url) -> bool {
+  let Ok(data) = fs::read(path) else {
+    return false;
+  };
+  let contains =
+    |needle: &[u8]| !needle.is_empty() && data.windows(needle.len()).any(|window| window == needle);
+  contains(origin.scheme().as_bytes()) && contains(origin.host_str().unwrap_or_default().as_bytes())
+}
+
+fn decode_row(row: &Row<'_>) -> Option<(String, String)> {
+  let key = match row.get_ref(0).ok()? {
+    ValueRef::Text(text) => std::str::from_utf8(text).ok()?.to_string(),
+    _ => return None,
+  };
+  let value = match row.get_ref(1).ok()? {
+    ValueRef::Blob(blob) if blob.len() % 2 == 0 => String::from_utf16(
+      &blob
+        .chunks_exact(2)
+        .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+        .collect::<Vec<_>>(),
+    )
+    .ok()?,
+    ValueRef::Text(text) => std::str::from_utf8(text).ok()?.to_string(),
+    _ => return None,
+  };
+  Some((key, value))
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::PathBuf;
+
+  use url::Url;
+
+  use super::{Backend, WebKitStorage};
+
+  fn fixture() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test/fixture/webview-storage/webkit")
+  }
+
+  #[test]
+  fn reads_legacy_database() {
+    let storage = WebKitStorage::new(fixture());
+    let origin: Url = "https://tauri.localhost".parse().unwrap();
+
+    assert_eq!(
+      storage.get(&origin, "width").unwrap().as_deref(),
+      Some("1280")
+    );
+    assert_eq!(
+      storage.get(&origin, "greeting").unwrap().as_deref(),
+      Some("héllo wörld ✓")
+    );
+    // odd length value
+    assert_eq!(storage.get(&origin, "broken").unwrap(), None);
+
+    let mut keys = storage.keys(&origin).unwrap();
+    keys.sort();
+    assert_eq!(keys, ["greeting", "theme", "width"]);
+
+    // not a database
+    let corrupted: Url = "https://corrupted.localhost".parse().unwrap();
+    assert!(storage.get(&corrupted, "width").is_err());
+    // no database
+    let missing: Url = "https://missing.localhost".parse().unwrap();
+    assert!(storage.keys(&missing).unwrap().is_empty());
+  }
+
+  #[test]
+  fn reads_origin_directories() {
+    let storage = WebKitStorage::new(fixture());
+    let origin: Url = "tauri://localhost".parse().unwrap();
+    assert_eq!(
+      storage.get(&origin, "theme").unwrap().as_deref(),
+      Some("light")
+    );
+  }
+
+  #[test]
+  fn writes_database() {
+    let dir = std::env::temp_dir().join(format!("tauri-webkit-storage-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("localstorage")).unwrap();
+
+    let storage = WebKitStorage::new(dir.clone());
+    let origin: Url = "http://localhost:1420".parse().unwrap();
+    storage.set(&origin, "width", "1920").unwrap();
+    storage.set(&origin, "title", "日本語").unwrap();
+    storage.set(&origin, "width", "2560").unwrap();
+    storage.remove(&origin, "title").unwrap();
+
+    assert!(dir
+      .join("localstorage/http_localhost_1420.localstorage")
+      .is_file());
+    assert_eq!(
+      storage.get(&origin, "width").unwrap().as_deref(),
+      Some("2560")
+    );
+    assert_eq!(storage.keys(&origin).unwrap(), ["width"]);
+
+    let _ = std::fs::remove_dir_all(&dir);
+  }
+}
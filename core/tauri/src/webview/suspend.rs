@@ -0,0 +1,212 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::collections::VecDeque;
+
+use tauri_runtime::WebviewDispatch;
+
+use super::Webview;
+use crate::{
+  event::{EmitArgs, EventTarget},
+  ipc::channel::ChannelDataIpcQueue,
+  Manager, Runtime,
+};
+
+/// The default maximum number of events queued while a webview is suspended.
+pub const DEFAULT_SUSPENDED_EVENT_QUEUE_LIMIT: usize = 100;
+
+/// A message to the frontend, delayed while the webview is suspended.
+pub(crate) enum Delivery {
+  /// An event, see [`Webview::emit_js`].
+  Event(EmitArgs, EventTarget),
+  /// The script notifying a channel of its message stored with the id in the [`ChannelDataIpcQueue`].
+  ChannelMessage { data_id: u32, script: String },
+}
+
+/// Suspension state of a webview.
+pub(crate) struct Suspension {
+  suspended: bool,
+  auto_suspend_on_hidden: bool,
+  queue_limit: usize,
+  queue: VecDeque<Delivery>,
+}
+
+impl Default for Suspension {
+  fn default() -> Self {
+    Self {
+      suspended: false,
+      auto_suspend_on_hidden: false,
+      queue_limit: DEFAULT_SUSPENDED_EVENT_QUEUE_LIMIT,
+      queue: Default::default(),
+    }
+  }
+}
+
+impl Suspension {
+  pub(crate) fn configure(&mut self, auto_suspend_on_hidden: bool, queue_limit: usize) {
+    self.auto_suspend_on_hidden = auto_suspend_on_hidden;
+    self.queue_limit = queue_limit;
+  }
+
+  /// Queues the delivery, dropping the oldest one when the queue is full.
+  ///
+  /// Returns the dropped delivery.
+  fn push(&mut self, delivery: Delivery) -> Option<Delivery> {
+    if self.queue_limit == 0 {
+      return Some(delivery);
+    }
+    let dropped = if self.queue.len() == self.queue_limit {
+      self.queue.pop_front()
+    } else {
+      None
+    };
+    self.queue.push_back(delivery);
+    dropped
+  }
+}
+
+impl<R: Runtime> Webview<R> {
+  /// Suspends or resumes the webview content to save CPU when it is not visible.
+  ///
+  /// Events and channel messages sent to a suspended webview are queued and delivered when it is resumed.
+  /// The queue is capped by [`super::WebviewBuilder::suspended_event_queue_limit`], dropping the oldest messages first.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Uses `TrySuspend` and `Resume` which only succeed if the webview is hidden.
+  /// - **macOS**: Suspends media playback. Requires macOS 12+.
+  /// - **Linux**: Unsupported, returns an error.
+  /// - **iOS / Android:** Only queues the messages.
+  pub fn set_suspended(&self, suspended: bool) -> crate::Result<()> {
+    self.dispatcher()?.set_suspended(suspended)?;
+
+    let queued = {
+      let mut suspension = self.suspension.lock().unwrap();
+      suspension.suspended = suspended;
+      if suspended {
+        VecDeque::new()
+      } else {
+        std::mem::take(&mut suspension.queue)
+      }
+    };
+
+    for delivery in queued {
+      match delivery {
+        Delivery::Event(emit_args, target) => self.emit_js(&emit_args, &target)?,
+        Delivery::ChannelMessage { script, .. } => self.eval(&script)?,
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Whether the webview is suspended.
+  pub fn is_suspended(&self) -> bool {
+    self.suspension.lock().unwrap().suspended
+  }
+
+  /// Suspends or resumes the webview if it was created with [`super::WebviewBuilder::auto_suspend_on_hidden`].
+  ///
+  /// Failures are logged, so they don't fail showing or hiding the window.
+  pub(crate) fn on_visibility_changed(&self, visible: bool) {
+    let auto_suspend = self.suspension.lock().unwrap().auto_suspend_on_hidden;
+    if auto_suspend && visible == self.is_suspended() {
+      if let Err(e) = self.set_suspended(!visible) {
+        tauri_utils::debug_eprintln!(
+          "failed to {} the `{}` webview: {}",
+          if visible { "resume" } else { "suspend" },
+          self.label(),
+          e
+        );
+      }
+    }
+  }
+
+  /// Queues the event if the webview is suspended, returning `true` in that case.
+  pub(crate) fn queue_event_if_suspended(
+    &self,
+    emit_args: &EmitArgs,
+    target: &EventTarget,
+  ) -> bool {
+    self.queue_if_suspended(|| Delivery::Event(emit_args.clone(), target.clone()))
+  }
+
+  /// Evaluates the script notifying a channel of a message, or queues it if the webview is suspended.
+  pub(crate) fn eval_channel_message(&self, data_id: u32, script: String) -> crate::Result<()> {
+    let mut script = Some(script);
+    self.queue_if_suspended(|| Delivery::ChannelMessage {
+      data_id,
+      script: script.take().unwrap(),
+    });
+    // the script is taken when it is queued
+    match script {
+      Some(script) => self.eval(&script),
+      None => Ok(()),
+    }
+  }
+
+  fn queue_if_suspended(&self, delivery: impl FnOnce() -> Delivery) -> bool {
+    let dropped = {
+      let mut suspension = self.suspension.lock().unwrap();
+      if !suspension.suspended {
+        return false;
+      }
+      suspension.push(delivery())
+    };
+    // the data of a dropped channel message would never be fetched
+    if let Some(Delivery::ChannelMessage { data_id, .. }) = dropped {
+      self
+        .state::<ChannelDataIpcQueue>()
+        .0
+        .lock()
+        .unwrap()
+        .remove(&data_id);
+    }
+    true
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{Delivery, Suspension};
+  use crate::event::{EmitArgs, EventTarget};
+
+  #[test]
+  fn queue_is_capped() {
+    let mut suspension = Suspension::default();
+    suspension.configure(false, 2);
+
+    let target = EventTarget::webview("main");
+    let event =
+      |payload: u32| Delivery::Event(EmitArgs::new("event", payload).unwrap(), target.clone());
+
+    assert!(suspension.push(event(1)).is_none());
+    assert!(suspension
+      .push(Delivery::ChannelMessage {
+        data_id: 2,
+        script: "2".into(),
+      })
+      .is_none());
+    assert!(
+      matches!(suspension.push(event(3)), Some(Delivery::Event(args, _)) if args.payload == "1")
+    );
+
+    let payloads = suspension
+      .queue
+      .iter()
+      .map(|delivery| match delivery {
+        Delivery::Event(args, _) => args.payload.as_str(),
+        Delivery::ChannelMessage { script, .. } => script.as_str(),
+      })
+      .collect::<Vec<_>>();
+    assert_eq!(payloads, vec!["2", "3"]);
+
+    // nothing is queued without a limit
+    suspension.configure(false, 0);
+    assert!(matches!(
+      suspension.push(event(4)),
+      Some(Delivery::Event(..))
+    ));
+  }
+}
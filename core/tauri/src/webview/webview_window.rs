@@ -16,13 +16,15 @@ use crate::{
   menu::{ContextMenu, Menu},
   runtime::{
     window::{
-      dpi::{Position, Size},
+      dpi::{LogicalPosition, LogicalSize, Position, Size},
       CursorIcon,
     },
     UserAttentionType,
   },
+  window::{MonitorSelector, RelativePosition},
   Icon,
 };
+use serde::de::DeserializeOwned;
 use tauri_utils::config::{WebviewUrl, WindowConfig};
 use url::Url;
 
@@ -31,9 +33,9 @@ use crate::{
   manager::{webview::WebviewLabelDef, AppManager},
   sealed::{ManagerBase, RuntimeOrDispatch},
   webview::PageLoadPayload,
-  webview::WebviewBuilder,
+  webview::{ContextMenuRequest, Insets, SessionId, WebviewBuilder},
   window::WindowBuilder,
-  AppHandle, Event, EventId, Manager, Runtime, Webview, WindowEvent,
+  AppHandle, Event, EventId, ListenerGuard, Manager, Runtime, Webview, WebviewEvent, WindowEvent,
 };
 
 use tauri_macros::default_runtime;
@@ -103,6 +105,33 @@ impl<'a, R: Runtime, M: Manager<R>> WebviewWindowBuilder<'a, R, M> {
     }
   }
 
+  /// Initializes a webview window builder with a unique `<prefix>-<n>` label like [`Manager::unique_label`],
+  /// returning the builder and the label.
+  ///
+  /// The label is reserved for this builder rather than the calling thread,
+  /// so the webview window can be built on any thread until the reservation expires.
+  ///
+  /// # Examples
+  ///
+  /// ```
+  /// #[tauri::command]
+  /// async fn open_document(app: tauri::AppHandle) -> tauri::Result<String> {
+  ///   let (builder, label) = tauri::WebviewWindowBuilder::new_unique(&app, "document", Default::default());
+  ///   builder.build()?;
+  ///   Ok(label)
+  /// }
+  /// ```
+  pub fn new_unique(manager: &'a M, prefix: &str, url: WebviewUrl) -> (Self, String) {
+    let (webview_builder, label) = WebviewBuilder::new_unique(manager, prefix, url);
+    (
+      Self {
+        window_builder: WindowBuilder::new(manager, &label),
+        webview_builder,
+      },
+      label,
+    )
+  }
+
   /// Initializes a window builder from a [`WindowConfig`] from tauri.conf.json.
   /// Keep in mind that you can't create 2 windows with the same `label` so make sure
   /// that the initial window was closed or change the label of the new [`WindowBuilder`].
@@ -188,6 +217,15 @@ async fn reopen_window(app: tauri::AppHandle) {
   /// **NOTE:** Currently this is **not** executed when using external URLs such as a development server,
   /// but it might be implemented in the future. **Always** check the request URL.
   ///
+  /// The closure receives the response Tauri would serve, including the error responses for missing assets.
+  /// The response is then served exactly as the closure leaves it, so it can change the status code
+  /// (e.g. a `404`, a `206` partial response or a `301` redirect), the headers and the body.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux / macOS / iOS**: WebKit does not follow the redirects of custom protocol responses,
+  ///   so a document request redirected with an empty body gets a body navigating to the `Location` header.
+  ///
   /// # Examples
   #[cfg_attr(
     feature = "unstable",
@@ -265,6 +303,27 @@ tauri::Builder::default()
     self
   }
 
+  /// Defines an async closure deciding whether the webview can navigate to the given URL.
+  ///
+  /// See [`crate::webview::WebviewBuilder::on_navigation_async`] for more information.
+  pub fn on_navigation_async<
+    F: Fn(&Url) -> futures_util::future::BoxFuture<'static, bool> + Send + 'static,
+  >(
+    mut self,
+    f: F,
+  ) -> Self {
+    self.webview_builder = self.webview_builder.on_navigation_async(f);
+    self
+  }
+
+  /// Sets how long the [`Self::on_navigation_async`] handler has to allow a navigation before it is denied.
+  ///
+  /// Defaults to [`crate::webview::DEFAULT_ASYNC_NAVIGATION_TIMEOUT`].
+  pub fn async_navigation_timeout(mut self, timeout: std::time::Duration) -> Self {
+    self.webview_builder = self.webview_builder.async_navigation_timeout(timeout);
+    self
+  }
+
   /// Defines a closure to be executed when a page load event is triggered.
   /// The event can be either [`tauri_runtime::webview::PageLoadEvent::Started`] if the page has started loading
   /// or [`tauri_runtime::webview::PageLoadEvent::Finished`] when the page finishes loading.
@@ -310,10 +369,111 @@ tauri::Builder::default()
     self
   }
 
+  /// Defines an async closure to be executed when a page load event is triggered.
+  ///
+  /// See [`crate::webview::WebviewBuilder::on_page_load_async`] for more information.
+  pub fn on_page_load_async<
+    F: Fn(WebviewWindow<R>, PageLoadPayload<'_>) -> futures_util::future::BoxFuture<'static, ()>
+      + Send
+      + Sync
+      + 'static,
+  >(
+    mut self,
+    f: F,
+  ) -> Self {
+    self.webview_builder = self
+      .webview_builder
+      .on_page_load_async(move |webview, payload| f(WebviewWindow { webview }, payload));
+    self
+  }
+
+  /// Defines a closure to be executed when the page logs a message to the console.
+  ///
+  /// See [`crate::webview::WebviewBuilder::on_console_message`] for more information.
+  pub fn on_console_message<
+    F: Fn(WebviewWindow<R>, crate::webview::ConsoleMessage) + Send + Sync + 'static,
+  >(
+    mut self,
+    f: F,
+  ) -> Self {
+    self.webview_builder = self
+      .webview_builder
+      .on_console_message(move |webview, message| f(WebviewWindow { webview }, message));
+    self
+  }
+
+  /// Defines a closure to be executed when the webview requests a context menu, usually on right click.
+  ///
+  /// See [`crate::webview::WebviewBuilder::on_context_menu`] for more information.
+  #[cfg(desktop)]
+  pub fn on_context_menu<
+    F: Fn(WebviewWindow<R>, ContextMenuRequest) -> crate::webview::ContextMenuAction<R>
+      + Send
+      + 'static,
+  >(
+    mut self,
+    f: F,
+  ) -> Self {
+    self.webview_builder = self
+      .webview_builder
+      .on_context_menu(move |webview, request| f(WebviewWindow { webview }, request));
+    self
+  }
+
+  /// Defines a closure to be executed when a page requests HTTP authentication.
+  ///
+  /// See [`crate::webview::WebviewBuilder::on_auth_challenge`] for more information.
+  pub fn on_auth_challenge<
+    F: Fn(WebviewWindow<R>, crate::webview::AuthChallenge) -> crate::webview::AuthResponse
+      + Send
+      + 'static,
+  >(
+    mut self,
+    f: F,
+  ) -> Self {
+    self.webview_builder = self
+      .webview_builder
+      .on_auth_challenge(move |webview, challenge| f(WebviewWindow { webview }, challenge));
+    self
+  }
+
+  /// Defines a closure deciding whether a server certificate rejected by the webview is accepted.
+  ///
+  /// See [`crate::webview::WebviewBuilder::on_certificate_error`] for more information.
+  pub fn on_certificate_error<
+    F: Fn(WebviewWindow<R>, crate::webview::CertError) -> crate::webview::CertDecision
+      + Send
+      + 'static,
+  >(
+    mut self,
+    f: F,
+  ) -> Self {
+    self.webview_builder = self
+      .webview_builder
+      .on_certificate_error(move |webview, error| f(WebviewWindow { webview }, error));
+    self
+  }
+
+  /// Defines a closure called with the summary of each network request made by the page.
+  ///
+  /// See [`crate::webview::WebviewBuilder::on_request_observed`] for more information.
+  pub fn on_request_observed<
+    F: Fn(WebviewWindow<R>, crate::webview::RequestSummary) + Send + 'static,
+  >(
+    mut self,
+    f: F,
+  ) -> Self {
+    self.webview_builder = self
+      .webview_builder
+      .on_request_observed(move |webview, request| f(WebviewWindow { webview }, request));
+    self
+  }
+
   /// Creates a new window.
   pub fn build(self) -> crate::Result<WebviewWindow<R>> {
-    let (_window, webview) = self.window_builder.with_webview(self.webview_builder)?;
-    Ok(WebviewWindow { webview })
+    self
+      .window_builder
+      .build_webview_window(self.webview_builder)
   }
 
   pub(crate) fn build_internal(
@@ -330,6 +490,54 @@ tauri::Builder::default()
   }
 }
 
+/// Creates a window from a [`WindowConfig`] with [`WindowConfig::webviews`] entries.
+///
+/// On desktop every webview is added to the window with its bounds resolved against the window inner size,
+/// and webviews with [relative bounds](tauri_utils::config::WebviewBounds::is_relative) follow the window resizes.
+/// On mobile a window can only host one webview, so only the first entry is created.
+pub(crate) fn build_config_webviews<R: Runtime, M: Manager<R>>(
+  manager: &M,
+  config: &WindowConfig,
+  window_labels: &[String],
+  webview_labels: &[WebviewLabelDef],
+) -> crate::Result<()> {
+  #[cfg(desktop)]
+  {
+    let _ = (window_labels, webview_labels);
+    let window = WindowBuilder::from_config(manager, config)?.build()?;
+    let size = window
+      .inner_size()?
+      .to_logical::<f64>(window.scale_factor()?);
+    for webview_config in &config.webviews {
+      let bounds = &webview_config.bounds;
+      let mut builder = WebviewBuilder::from_webview_config(webview_config);
+      if bounds.is_relative() {
+        builder = builder.auto_resize();
+      }
+      builder.build(
+        window.clone(),
+        LogicalPosition::new(bounds.x.resolve(size.width), bounds.y.resolve(size.height)).into(),
+        LogicalSize::new(
+          bounds.width.resolve(size.width),
+          bounds.height.resolve(size.height),
+        )
+        .into(),
+      )?;
+    }
+  }
+
+  #[cfg(mobile)]
+  if let Some(webview_config) = config.webviews.first() {
+    WebviewWindowBuilder {
+      window_builder: WindowBuilder::from_config(manager, config)?,
+      webview_builder: WebviewBuilder::from_webview_config(webview_config),
+    }
+    .build_internal(window_labels, webview_labels)?;
+  }
+
+  Ok(())
+}
+
 /// Desktop APIs.
 #[cfg(desktop)]
 impl<'a, R: Runtime, M: Manager<R>> WebviewWindowBuilder<'a, R, M> {
@@ -347,6 +555,25 @@ impl<'a, R: Runtime, M: Manager<R>> WebviewWindowBuilder<'a, R, M> {
     self
   }
 
+  /// Centers the window in the work area of the selected monitor,
+  /// keeping its logical size even if the monitor has a different scale factor.
+  ///
+  /// The window is shrunk if it does not fit the work area of the monitor.
+  /// When the selected monitor does not exist, the primary monitor is used instead.
+  #[must_use]
+  pub fn on_monitor(mut self, selector: MonitorSelector) -> Self {
+    self.window_builder = self.window_builder.on_monitor(selector);
+    self
+  }
+
+  /// Maximizes the window on the selected monitor,
+  /// see [`Self::on_monitor`].
+  #[must_use]
+  pub fn maximized_on_monitor(mut self, selector: MonitorSelector) -> Self {
+    self.window_builder = self.window_builder.maximized_on_monitor(selector);
+    self
+  }
+
   /// The initial position of the window's.
   #[must_use]
   pub fn position(mut self, x: f64, y: f64) -> Self {
@@ -445,10 +672,11 @@ impl<'a, R: Runtime, M: Manager<R>> WebviewWindowBuilder<'a, R, M> {
     self
   }
 
-  /// Whether the window will be initially focused or not.
+  /// Whether the window and its webview will be initially focused or not.
   #[must_use]
   pub fn focused(mut self, focused: bool) -> Self {
     self.window_builder = self.window_builder.focused(focused);
+    self.webview_builder = self.webview_builder.focused(focused);
     self
   }
 
@@ -576,6 +804,33 @@ impl<'a, R: Runtime, M: Manager<R>> WebviewWindowBuilder<'a, R, M> {
     Ok(self)
   }
 
+  /// Places the window relative to its [parent](Self::parent) when it is created.
+  ///
+  /// See [`crate::window::WindowBuilder::position_relative`] for more information.
+  #[must_use]
+  pub fn position_relative(mut self, position: RelativePosition) -> Self {
+    self.window_builder = self.window_builder.position_relative(position);
+    self
+  }
+
+  /// Whether the window is closed when its [parent](Self::parent) is closed.
+  ///
+  /// See [`crate::window::WindowBuilder::close_with_parent`] for more information.
+  #[must_use]
+  pub fn close_with_parent(mut self, close: bool) -> Self {
+    self.window_builder = self.window_builder.close_with_parent(close);
+    self
+  }
+
+  /// Makes the window to be created a modal of the given parent window,
+  /// blocking the input of the parent window until it is closed.
+  ///
+  /// See [`crate::window::WindowBuilder::modal`] for more information.
+  pub fn modal(mut self, parent: &WebviewWindow<R>) -> crate::Result<Self> {
+    self.window_builder = self.window_builder.modal(&parent.webview.window)?;
+    Ok(self)
+  }
+
   /// Set an owner to the window to be created.
   ///
   /// From MSDN:
@@ -718,6 +973,27 @@ impl<'a, R: Runtime, M: Manager<R>> WebviewWindowBuilder<'a, R, M> {
     self
   }
 
+  /// Whether mouse and touch input goes through the webview to the content beneath it, `false` by default.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Unsupported, use [`WebviewWindow::set_ignore_cursor_events`] to make the whole window ignore cursor events.
+  /// - **iOS / Android**: Unsupported.
+  #[must_use]
+  pub fn ignore_cursor_events(mut self, ignore: bool) -> Self {
+    self.webview_builder = self.webview_builder.ignore_cursor_events(ignore);
+    self
+  }
+
+  /// Insets the webview content from the window content area, no insets by default.
+  ///
+  /// See [`WebviewWindow::set_content_insets`] for more information.
+  #[must_use]
+  pub fn content_insets(mut self, insets: Insets) -> Self {
+    self.webview_builder = self.webview_builder.content_insets(insets);
+    self
+  }
+
   /// Adds the provided JavaScript to a list of scripts that should be run after the global object has been created,
   /// but before the HTML document has been parsed and before any other script included by the HTML document is run.
   ///
@@ -758,6 +1034,16 @@ fn main() {
     self
   }
 
+  /// Adds a script injected before the page scripts are executed in the given isolated world,
+  /// see [`WebviewBuilder::isolated_initialization_script`].
+  #[must_use]
+  pub fn isolated_initialization_script(mut self, world: &str, script: &str) -> Self {
+    self.webview_builder = self
+      .webview_builder
+      .isolated_initialization_script(world, script);
+    self
+  }
+
   /// Set the user agent for the webview
   #[must_use]
   pub fn user_agent(mut self, user_agent: &str) -> Self {
@@ -765,6 +1051,13 @@ fn main() {
     self
   }
 
+  /// Sets the headers of the request loading the webview URL, see [`WebviewBuilder::initial_headers`].
+  #[must_use]
+  pub fn initial_headers(mut self, headers: http::HeaderMap) -> Self {
+    self.webview_builder = self.webview_builder.initial_headers(headers);
+    self
+  }
+
   /// Set additional arguments for the webview.
   ///
   /// ## Platform-specific
@@ -825,6 +1118,135 @@ fn main() {
     self
   }
 
+  /// Emits the `tauri://webview-metrics` event with the [`crate::webview::WebviewMetrics`] of the webview at the given interval.
+  #[must_use]
+  pub fn metrics_interval(mut self, interval: std::time::Duration) -> Self {
+    self.webview_builder = self.webview_builder.metrics_interval(interval);
+    self
+  }
+
+  /// Emits the `tauri://scroll` event with the [`crate::webview::WebviewScroll`] of the document when it scrolls,
+  /// at most once per `throttle`.
+  ///
+  /// See [`crate::webview::WebviewBuilder::emit_scroll_events`] for more information.
+  #[must_use]
+  pub fn emit_scroll_events(mut self, throttle: std::time::Duration) -> Self {
+    self.webview_builder = self.webview_builder.emit_scroll_events(throttle);
+    self
+  }
+
+  /// Suspends the webview when the window is hidden and resumes it when the window is shown.
+  ///
+  /// See [`crate::Webview::set_suspended`] for more information.
+  #[must_use]
+  pub fn auto_suspend_on_hidden(mut self, auto_suspend: bool) -> Self {
+    self.webview_builder = self.webview_builder.auto_suspend_on_hidden(auto_suspend);
+    self
+  }
+
+  /// Sets the browsing session of the webview.
+  ///
+  /// Webviews created with the same [`SessionId`] share cookies, cache and local storage,
+  /// while [`SessionId::ephemeral`] creates a session that is not persisted, like [`Self::incognito`].
+  /// When not set, the webview uses the default session of the process.
+  ///
+  /// Building the webview fails if an ephemeral session is used with a [`Self::data_directory`],
+  /// if a shared session is used with [`Self::incognito`],
+  /// or if webviews sharing a session use different data directories.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: Webviews sharing a session use the same user data folder.
+  /// - **macOS / iOS**: All persistent webviews already share the default website data store and process pool.
+  /// - **Linux**: Webviews sharing a session use the same `WebContext`, which keeps a single handler
+  ///   for the IPC and app protocols, so building a webview fails while another webview uses the session.
+  /// - **Android**: Unsupported.
+  #[must_use]
+  pub fn shared_session(mut self, session: SessionId) -> Self {
+    self.webview_builder = self.webview_builder.shared_session(session);
+    self
+  }
+
+  /// Binds the webview to a profile, see [`WebviewBuilder::profile`].
+  #[must_use]
+  pub fn profile(mut self, profile: &crate::profile::Profile<R>) -> Self {
+    self.webview_builder = self.webview_builder.profile(profile);
+    self
+  }
+
+  /// Gives mutable access to the [`crate::WebviewAttributes`] used to create the webview,
+  /// allowing the use of attributes that do not have a builder method yet.
+  #[must_use]
+  pub fn with_attributes<F: FnOnce(&mut crate::WebviewAttributes)>(mut self, f: F) -> Self {
+    self.webview_builder = self.webview_builder.with_attributes(f);
+    self
+  }
+
+  /// Overrides the webview attributes with the given JSON object,
+  /// allowing config-driven webviews to use attributes that are not part of [`WindowConfig`].
+  ///
+  /// The keys are the camelCase attribute names, see [`crate::WebviewAttributes::apply_overrides`].
+  /// Returns an error if the object contains unknown keys or invalid values.
+  pub fn try_build_config_overrides(mut self, overrides: serde_json::Value) -> crate::Result<Self> {
+    self.webview_builder = self.webview_builder.try_build_config_overrides(overrides)?;
+    Ok(self)
+  }
+
+  /// Restricts the commands the webview can invoke, on top of the capabilities.
+  ///
+  /// Commands not in the list are rejected with an [`crate::ipc::IpcError::CommandDenied`] error.
+  /// All commands are allowed by default.
+  #[must_use]
+  pub fn allowed_commands(mut self, allowlist: crate::ipc::AllowList) -> Self {
+    self.webview_builder = self.webview_builder.allowed_commands(allowlist);
+    self
+  }
+
+  /// Applies platform background effects behind the webview instead of the whole window.
+  ///
+  /// See [`crate::webview::WebviewBuilder::effects`] for more information.
+  #[must_use]
+  pub fn webview_effects(mut self, effects: crate::utils::config::WindowEffectsConfig) -> Self {
+    self.webview_builder = self.webview_builder.effects(effects);
+    self
+  }
+
+  /// Lets the webview receive the key events of the window accelerators instead of the window handling them first.
+  ///
+  /// See [`crate::webview::WebviewBuilder::allow_accelerator_override`] for more information.
+  #[cfg(desktop)]
+  #[must_use]
+  pub fn allow_accelerator_override(mut self, allow: bool) -> Self {
+    self.webview_builder = self.webview_builder.allow_accelerator_override(allow);
+    self
+  }
+
+  /// Registers a webview event listener, called for the events of the webview from its creation.
+  ///
+  /// See [`crate::webview::WebviewBuilder::on_webview_event`] for more information.
+  #[must_use]
+  pub fn on_webview_event<F: Fn(&WebviewEvent) + Send + Sync + 'static>(mut self, f: F) -> Self {
+    self.webview_builder = self.webview_builder.on_webview_event(f);
+    self
+  }
+
+  /// Whether the key authenticating the IPC requests is regenerated on every main frame navigation. Defaults to `true`.
+  ///
+  /// See [`crate::webview::WebviewBuilder::rotate_invoke_key`] for more information.
+  #[must_use]
+  pub fn rotate_invoke_key(mut self, rotate: bool) -> Self {
+    self.webview_builder = self.webview_builder.rotate_invoke_key(rotate);
+    self
+  }
+
+  /// Maximum number of events queued while the webview is suspended.
+  /// Defaults to [`crate::webview::DEFAULT_SUSPENDED_EVENT_QUEUE_LIMIT`].
+  #[must_use]
+  pub fn suspended_event_queue_limit(mut self, limit: usize) -> Self {
+    self.webview_builder = self.webview_builder.suspended_event_queue_limit(limit);
+    self
+  }
+
   /// Set a proxy URL for the WebView for all network requests.
   ///
   /// Must be either a `http://` or a `socks5://` URL.
@@ -833,6 +1255,36 @@ fn main() {
     self.webview_builder = self.webview_builder.proxy_url(url);
     self
   }
+
+  /// Sets how timers and rendering behave while the webview is hidden or in the background.
+  ///
+  /// See [`crate::webview::WebviewBuilder::background_throttling`] for more information.
+  #[must_use]
+  pub fn background_throttling(
+    mut self,
+    policy: crate::utils::config::BackgroundThrottlingPolicy,
+  ) -> Self {
+    self.webview_builder = self.webview_builder.background_throttling(policy);
+    self
+  }
+
+  /// Enables or disables the spell checker.
+  ///
+  /// See [`crate::webview::WebviewBuilder::spellcheck`] for more information.
+  #[must_use]
+  pub fn spellcheck(mut self, enabled: bool) -> Self {
+    self.webview_builder = self.webview_builder.spellcheck(enabled);
+    self
+  }
+
+  /// Allows the webview to load the `file://` URLs of the directories of the scope.
+  ///
+  /// See [`crate::webview::WebviewBuilder::allow_file_access`] for more information.
+  #[must_use]
+  pub fn allow_file_access(mut self, scope: crate::webview::FileAccessScope) -> Self {
+    self.webview_builder = self.webview_builder.allow_file_access(scope);
+    self
+  }
 }
 
 /// A type that wraps a [`Window`] together with a [`Webview`].
@@ -913,6 +1365,11 @@ impl<R: Runtime> WebviewWindow<R> {
   pub fn on_window_event<F: Fn(&WindowEvent) + Send + 'static>(&self, f: F) {
     self.webview.window().on_window_event(f);
   }
+
+  /// Registers a webview event listener.
+  pub fn on_webview_event<F: Fn(&WebviewEvent) + Send + Sync + 'static>(&self, f: F) {
+    self.webview.on_webview_event(f);
+  }
 }
 
 /// Menu APIs
@@ -1121,6 +1578,13 @@ impl<R: Runtime> WebviewWindow<R> {
     self.webview.window().title()
   }
 
+  /// Captures the live state of the window as a [`WindowConfig`].
+  ///
+  /// See [`crate::window::Window::to_config`] for more information.
+  pub fn to_config(&self) -> WindowConfig {
+    self.webview.window().to_config()
+  }
+
   /// Returns the monitor on which the window currently resides.
   ///
   /// Returns None if current monitor can't be detected.
@@ -1204,6 +1668,34 @@ impl<R: Runtime> WebviewWindow<R> {
     self.webview.window().center()
   }
 
+  /// Moves the window to the center of the work area of a monitor,
+  /// keeping its logical size and its maximized state.
+  ///
+  /// The window is shrunk if it does not fit the work area of the monitor.
+  /// When the selected monitor does not exist, the primary monitor is used instead.
+  pub fn move_to_monitor(&self, selector: MonitorSelector) -> crate::Result<()> {
+    self.webview.window().move_to_monitor(selector)
+  }
+
+  /// The window this window was created with as [`WebviewWindowBuilder::parent`], if it is still open.
+  pub fn parent_window(&self) -> Option<crate::window::Window<R>> {
+    self.webview.window().parent_window()
+  }
+
+  /// Centers the window on its parent window.
+  ///
+  /// See [`crate::window::Window::center_on_parent`] for more information.
+  pub fn center_on_parent(&self) -> crate::Result<()> {
+    self.webview.window().center_on_parent()
+  }
+
+  /// Moves the window relative to its parent window.
+  ///
+  /// See [`crate::window::Window::reposition_relative`] for more information.
+  pub fn reposition_relative(&self, position: RelativePosition) -> crate::Result<()> {
+    self.webview.window().reposition_relative(position)
+  }
+
   /// Requests user attention to the window, this has no effect if the application
   /// is already focused. How requesting for user attention manifests is platform dependent,
   /// see `UserAttentionType` for details.
@@ -1563,15 +2055,37 @@ impl<R: Runtime> WebviewWindow<R> {
     self.webview.with_webview(f)
   }
 
+  /// Like [`Self::with_webview`], but the closure returns a future that is awaited on the main thread.
+  ///
+  /// See [`Webview::with_webview_async`] for more information.
+  #[cfg(feature = "wry")]
+  #[cfg_attr(docsrs, doc(feature = "wry"))]
+  pub fn with_webview_async<F, Fut, T>(
+    &self,
+    f: F,
+  ) -> impl std::future::Future<Output = crate::Result<T>> + Send + 'static
+  where
+    F: FnOnce(crate::webview::PlatformWebview) -> Fut + Send + 'static,
+    Fut: std::future::Future<Output = T> + 'static,
+    T: Send + 'static,
+  {
+    self.webview.with_webview_async(f)
+  }
+
   /// Returns the current url of the webview.
-  // TODO: in v2, change this type to Result
-  pub fn url(&self) -> Url {
+  pub fn url(&self) -> crate::Result<Url> {
     self.webview.url()
   }
 
   /// Navigates the webview to the defined url.
-  pub fn navigate(&mut self, url: Url) {
-    self.webview.navigate(url);
+  pub fn navigate(&mut self, url: Url) -> crate::Result<()> {
+    self.webview.navigate(url)
+  }
+
+  /// Navigates the webview to the defined url, sending the headers with the request loading it,
+  /// see [`Webview::navigate_with_headers`].
+  pub fn navigate_with_headers(&mut self, url: Url, headers: http::HeaderMap) -> crate::Result<()> {
+    self.webview.navigate_with_headers(url, headers)
   }
 
   /// Handles this window receiving an [`crate::webview::InvokeRequest`].
@@ -1583,11 +2097,67 @@ impl<R: Runtime> WebviewWindow<R> {
     self.webview.on_message(request, responder)
   }
 
+  /// The profile the webview is bound to, see [`WebviewBuilder::profile`].
+  pub fn profile(&self) -> Option<crate::profile::Profile<R>> {
+    self.webview.profile()
+  }
+
   /// Evaluates JavaScript on this window.
   pub fn eval(&self, js: &str) -> crate::Result<()> {
     self.webview.eval(js)
   }
 
+  /// Scrolls the document to the given position, in CSS pixels, see [`Webview::scroll_to`].
+  pub fn scroll_to(&self, position: crate::webview::ScrollPosition) -> crate::Result<()> {
+    self.webview.scroll_to(position)
+  }
+
+  /// Returns the scroll position of the document, in CSS pixels, see [`Webview::scroll_position`].
+  pub async fn scroll_position(&self) -> crate::Result<crate::webview::ScrollPosition> {
+    self.webview.scroll_position().await
+  }
+
+  /// Evaluates JavaScript in an isolated world of this window, see [`Webview::eval_isolated`].
+  pub fn eval_isolated(&self, world: &str, js: &str) -> crate::Result<()> {
+    self.webview.eval_isolated(world, js)
+  }
+
+  /// Evaluates JavaScript in an isolated world of this window and returns its result,
+  /// see [`Webview::eval_isolated_with_result`].
+  pub async fn eval_isolated_with_result<T: DeserializeOwned>(
+    &self,
+    world: &str,
+    js: &str,
+  ) -> crate::Result<T> {
+    self.webview.eval_isolated_with_result(world, js).await
+  }
+
+  /// Enables or disables the spell checker of this window, see [`Webview::set_spellcheck`].
+  pub fn set_spellcheck(&self, enabled: bool) -> crate::Result<()> {
+    self.webview.set_spellcheck(enabled)
+  }
+
+  /// Sets the spell checker languages of this window, see [`Webview::set_spellcheck_languages`].
+  pub fn set_spellcheck_languages(&self, languages: Vec<String>) -> crate::Result<()> {
+    self.webview.set_spellcheck_languages(languages)
+  }
+
+  /// Insets the webview content from the window content area, see [`Webview::set_content_insets`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / Linux / iOS / Android:** Unsupported.
+  pub fn set_content_insets(&self, insets: Insets) -> crate::Result<()> {
+    self.webview.set_content_insets(insets)
+  }
+
+  /// Accesses the Chrome DevTools Protocol of the webview, see [`Webview::devtools_protocol`].
+  #[cfg(feature = "devtools-protocol")]
+  #[cfg_attr(docsrs, doc(cfg(feature = "devtools-protocol")))]
+  pub fn devtools_protocol(&self) -> crate::webview::DevtoolsProtocol<R> {
+    self.webview.devtools_protocol()
+  }
+
   /// Opens the developer tools window (Web Inspector).
   /// The devtools is only enabled on debug builds or with the `devtools` feature flag.
   ///
@@ -1691,6 +2261,11 @@ tauri::Builder::default()
   pub fn is_devtools_open(&self) -> bool {
     self.webview.is_devtools_open()
   }
+
+  /// Sends binary data to the webview with the given label, see [`Webview::post_message_to`].
+  pub fn post_message_to(&self, label: &str, message: Vec<u8>) -> crate::Result<()> {
+    self.webview.post_message_to(label, message)
+  }
 }
 
 /// Event system APIs.
@@ -1763,6 +2338,22 @@ tauri::Builder::default()
     self.manager().unlisten(id)
   }
 
+  /// Listen to an event on this webview window until the returned guard is dropped or the webview is destroyed.
+  ///
+  /// See [`Self::listen`] and [`Manager::listen_scoped`] for more information.
+  pub fn listen_scoped<F>(&self, event: impl Into<String>, handler: F) -> ListenerGuard
+  where
+    F: Fn(Event) + Send + 'static,
+  {
+    self.webview.listen_scoped_to(
+      event.into(),
+      EventTarget::WebviewWindow {
+        label: self.label().to_string(),
+      },
+      handler,
+    )
+  }
+
   /// Listen to an event on this window webview only once.
   ///
   /// See [`Self::listen`] for more information.
@@ -0,0 +1,723 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Keyboard accelerators handled by the window before its webviews receive the key events.
+
+use std::{
+  collections::{HashMap, HashSet},
+  sync::Arc,
+};
+
+use muda::accelerator::{Accelerator, Code, Modifiers};
+
+use super::Window;
+use crate::{
+  menu::{Menu, MenuId, MenuItemKind},
+  webview::Webview,
+  Runtime,
+};
+
+type AcceleratorHandler<R> = Arc<dyn Fn(&Window<R>, Option<Webview<R>>) + Send + Sync>;
+
+pub(crate) struct AcceleratorState<R: Runtime> {
+  handlers: HashMap<Accelerator, AcceleratorHandler<R>>,
+  /// Webviews that receive the accelerators instead of the window.
+  overrides: HashSet<String>,
+  /// Webviews with the key event hook installed.
+  hooked: HashSet<String>,
+  /// The key event monitor of the window.
+  #[cfg(all(target_os = "macos", feature = "wry"))]
+  monitor: Option<macos::Monitor>,
+}
+
+impl<R: Runtime> Default for AcceleratorState<R> {
+  fn default() -> Self {
+    Self {
+      handlers: Default::default(),
+      overrides: Default::default(),
+      hooked: Default::default(),
+      #[cfg(all(target_os = "macos", feature = "wry"))]
+      monitor: None,
+    }
+  }
+}
+
+impl<R: Runtime> Window<R> {
+  /// Registers a keyboard accelerator, e.g. `CmdOrCtrl+1`, handled before the focused webview receives the key event.
+  ///
+  /// The page cannot prevent the handler from running, since the key event never reaches the webview.
+  /// The handler receives the webview that had focus when the accelerator was pressed.
+  ///
+  /// Fails with [`crate::Error::AcceleratorConflict`] if the accelerator is already registered on this window
+  /// or used by a menu item of the app or window menu.
+  /// Webviews built with [`crate::webview::WebviewBuilder::allow_accelerator_override`] receive the key events instead.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS**: the focused webview is the one containing the first responder of the window.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use tauri::Manager;
+  ///
+  /// tauri::Builder::default()
+  ///   .setup(|app| {
+  ///     let window = app.get_window("main").unwrap();
+  ///     for tab in 1..=9 {
+  ///       window.register_accelerator(&format!("CmdOrCtrl+{tab}"), move |_window, webview| {
+  ///         println!("switch to tab {tab} from {:?}", webview.map(|w| w.label().to_string()));
+  ///       })?;
+  ///     }
+  ///     Ok(())
+  ///   });
+  /// ```
+  pub fn register_accelerator<F>(&self, accelerator: &str, handler: F) -> crate::Result<()>
+  where
+    F: Fn(&Window<R>, Option<Webview<R>>) + Send + Sync + 'static,
+  {
+    let parsed = accelerator.parse::<Accelerator>()?;
+
+    if self
+      .accelerators
+      .lock()
+      .unwrap()
+      .handlers
+      .contains_key(&parsed)
+      || self.menu_uses_accelerator(&parsed)?
+    {
+      return Err(crate::Error::AcceleratorConflict(accelerator.into()));
+    }
+
+    self
+      .accelerators
+      .lock()
+      .unwrap()
+      .handlers
+      .insert(parsed, Arc::new(handler));
+
+    for webview in self.webviews() {
+      self.hook_accelerators(&webview);
+    }
+
+    Ok(())
+  }
+
+  /// Unregisters a keyboard accelerator registered with [`Self::register_accelerator`].
+  pub fn unregister_accelerator(&self, accelerator: &str) -> crate::Result<()> {
+    let parsed = accelerator.parse::<Accelerator>()?;
+    self.accelerators.lock().unwrap().handlers.remove(&parsed);
+    Ok(())
+  }
+
+  /// Whether a menu item of the app or window menu uses the accelerator.
+  fn menu_uses_accelerator(&self, accelerator: &Accelerator) -> crate::Result<bool> {
+    let ids = self
+      .manager
+      .menu
+      .accelerators_lock()
+      .iter()
+      .filter(|(_, a)| *a == accelerator)
+      .map(|(id, _)| id.clone())
+      .collect::<HashSet<_>>();
+    if ids.is_empty() {
+      return Ok(false);
+    }
+
+    let menus = self
+      .manager
+      .menu
+      .menu_lock()
+      .iter()
+      .cloned()
+      .chain(self.menu.lock().unwrap().iter().map(|m| m.menu.clone()))
+      .collect::<Vec<_>>();
+    for menu in menus {
+      if menu_item_ids(&menu)?.iter().any(|id| ids.contains(id)) {
+        return Ok(true);
+      }
+    }
+    Ok(false)
+  }
+
+  /// Prepares a new webview of this window, installing the key event hook if accelerators are registered.
+  pub(crate) fn attach_accelerators(&self, webview: &Webview<R>, allow_override: bool) {
+    let register = {
+      let mut state = self.accelerators.lock().unwrap();
+      // the label may belong to a closed webview
+      state.hooked.remove(webview.label());
+      if allow_override {
+        state.overrides.insert(webview.label().into());
+      } else {
+        state.overrides.remove(webview.label());
+      }
+      !state.handlers.is_empty()
+    };
+
+    if register {
+      self.hook_accelerators(webview);
+    }
+  }
+
+  /// Runs the handler of the accelerator matching the key event, returning whether the event was handled.
+  pub(crate) fn handle_accelerator(
+    &self,
+    modifiers: Modifiers,
+    code: Code,
+    webview: Option<&str>,
+  ) -> bool {
+    let handler = {
+      let state = self.accelerators.lock().unwrap();
+      if webview.is_some_and(|label| state.overrides.contains(label)) {
+        return false;
+      }
+      state
+        .handlers
+        .iter()
+        .find(|(accelerator, _)| accelerator.matches(modifiers, code))
+        .map(|(_, handler)| handler.clone())
+    };
+
+    match handler {
+      Some(handler) => {
+        handler(self, webview.and_then(|label| self.get_webview(label)));
+        true
+      }
+      None => false,
+    }
+  }
+
+  /// Installs the platform key event hook on the webview, once.
+  #[cfg_attr(not(feature = "wry"), allow(unused_variables))]
+  fn hook_accelerators(&self, webview: &Webview<R>) {
+    if !self
+      .accelerators
+      .lock()
+      .unwrap()
+      .hooked
+      .insert(webview.label().into())
+    {
+      return;
+    }
+
+    #[cfg(feature = "wry")]
+    {
+      let window = self.clone();
+      let label = webview.label().to_string();
+      let result = webview.with_webview(move |platform| {
+        #[cfg(any(
+          target_os = "linux",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "netbsd",
+          target_os = "openbsd"
+        ))]
+        linux::hook(window, label, platform.inner());
+        #[cfg(windows)]
+        windows::hook(window, label, platform.controller());
+        #[cfg(target_os = "macos")]
+        macos::hook(window, label, platform.inner(), platform.ns_window());
+      });
+      if let Err(e) = result {
+        tauri_utils::debug_eprintln!(
+          "failed to hook the accelerators of the `{}` webview: {}",
+          webview.label(),
+          e
+        );
+      }
+    }
+  }
+}
+
+/// The identifiers of the items of the menu and its submenus.
+fn menu_item_ids<R: Runtime>(menu: &Menu<R>) -> crate::Result<Vec<MenuId>> {
+  fn collect<R: Runtime>(items: Vec<MenuItemKind<R>>, ids: &mut Vec<MenuId>) -> crate::Result<()> {
+    for item in items {
+      if let MenuItemKind::Submenu(submenu) = &item {
+        collect(submenu.items()?, ids)?;
+      }
+      ids.push(item.id().clone());
+    }
+    Ok(())
+  }
+
+  let mut ids = Vec::new();
+  collect(menu.items()?, &mut ids)?;
+  Ok(ids)
+}
+
+#[cfg(all(
+  feature = "wry",
+  any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  )
+))]
+mod linux {
+  use gtk::{gdk, glib::Propagation, prelude::*};
+  use muda::accelerator::{Code, Modifiers};
+
+  use crate::{Runtime, Window};
+
+  /// Handles the accelerators in the key press handler of the webview widget,
+  /// which runs before the default handler forwarding the event to the page.
+  pub fn hook<R: Runtime>(window: Window<R>, label: String, webview: webkit2gtk::WebView) {
+    webview.connect_key_press_event(move |_, event| match key_event(event) {
+      Some((modifiers, code)) if window.handle_accelerator(modifiers, code, Some(&label)) => {
+        Propagation::Stop
+      }
+      _ => Propagation::Proceed,
+    });
+  }
+
+  fn key_event(event: &gdk::EventKey) -> Option<(Modifiers, Code)> {
+    // the key without the modifiers applied, so `Shift+1` is not read as `!`
+    let keymap = gdk::Keymap::for_display(&event.window()?.display())?;
+    let (key, ..) = keymap.translate_keyboard_state(
+      event.hardware_keycode() as u32,
+      gdk::ModifierType::empty(),
+      event.group() as i32,
+    )?;
+    let code = key_code(&gdk::keys::Key::from(key).name()?)?;
+
+    let state = event.state();
+    let mut modifiers = Modifiers::empty();
+    modifiers.set(
+      Modifiers::SHIFT,
+      state.contains(gdk::ModifierType::SHIFT_MASK),
+    );
+    modifiers.set(
+      Modifiers::CONTROL,
+      state.contains(gdk::ModifierType::CONTROL_MASK),
+    );
+    modifiers.set(Modifiers::ALT, state.contains(gdk::ModifierType::MOD1_MASK));
+    modifiers.set(
+      Modifiers::SUPER,
+      state.intersects(gdk::ModifierType::SUPER_MASK | gdk::ModifierType::META_MASK),
+    );
+    Some((modifiers, code))
+  }
+
+  fn key_code(name: &str) -> Option<Code> {
+    let code = match name {
+      "Return" => Code::Enter,
+      "Escape" => Code::Escape,
+      "Tab" => Code::Tab,
+      "space" => Code::Space,
+      "BackSpace" => Code::Backspace,
+      "Delete" => Code::Delete,
+      "Insert" => Code::Insert,
+      "Home" => Code::Home,
+      "End" => Code::End,
+      "Page_Up" => Code::PageUp,
+      "Page_Down" => Code::PageDown,
+      "Left" => Code::ArrowLeft,
+      "Right" => Code::ArrowRight,
+      "Up" => Code::ArrowUp,
+      "Down" => Code::ArrowDown,
+      "minus" => Code::Minus,
+      "equal" => Code::Equal,
+      "comma" => Code::Comma,
+      "period" => Code::Period,
+      "slash" => Code::Slash,
+      "semicolon" => Code::Semicolon,
+      "apostrophe" => Code::Quote,
+      "bracketleft" => Code::BracketLeft,
+      "bracketright" => Code::BracketRight,
+      "backslash" => Code::Backslash,
+      "grave" => Code::Backquote,
+      name => return super::character_code(name).or_else(|| super::function_key_code(name)),
+    };
+    Some(code)
+  }
+}
+
+#[cfg(all(feature = "wry", windows))]
+mod windows {
+  use muda::accelerator::{Code, Modifiers};
+  use webview2_com::{
+    AcceleratorKeyPressedEventHandler,
+    Microsoft::Web::WebView2::Win32::{
+      ICoreWebView2Controller, COREWEBVIEW2_KEY_EVENT_KIND, COREWEBVIEW2_KEY_EVENT_KIND_KEY_DOWN,
+      COREWEBVIEW2_KEY_EVENT_KIND_SYSTEM_KEY_DOWN,
+    },
+  };
+  use windows::Win32::{
+    System::WinRT::EventRegistrationToken,
+    UI::Input::KeyboardAndMouse::{
+      GetKeyState, VIRTUAL_KEY, VK_CONTROL, VK_LWIN, VK_MENU, VK_RWIN, VK_SHIFT,
+    },
+  };
+
+  use crate::{Runtime, Window};
+
+  /// Handles the accelerators in the `AcceleratorKeyPressed` event of the controller,
+  /// raised before the key event is sent to the page.
+  pub fn hook<R: Runtime>(window: Window<R>, label: String, controller: ICoreWebView2Controller) {
+    let handler = AcceleratorKeyPressedEventHandler::create(Box::new(move |_, args| {
+      let Some(args) = args else {
+        return Ok(());
+      };
+      unsafe {
+        let mut kind = COREWEBVIEW2_KEY_EVENT_KIND::default();
+        args.KeyEventKind(&mut kind)?;
+        if kind != COREWEBVIEW2_KEY_EVENT_KIND_KEY_DOWN
+          && kind != COREWEBVIEW2_KEY_EVENT_KIND_SYSTEM_KEY_DOWN
+        {
+          return Ok(());
+        }
+        let mut virtual_key = 0;
+        args.VirtualKey(&mut virtual_key)?;
+        if let Some(code) = key_code(virtual_key) {
+          if window.handle_accelerator(modifiers(), code, Some(&label)) {
+            args.SetHandled(true)?;
+          }
+        }
+      }
+      Ok(())
+    }));
+
+    let mut token = EventRegistrationToken::default();
+    if let Err(e) = unsafe { controller.add_AcceleratorKeyPressed(&handler, &mut token) } {
+      tauri_utils::debug_eprintln!("failed to listen to the accelerator keys: {}", e);
+    }
+  }
+
+  fn modifiers() -> Modifiers {
+    let pressed = |key: VIRTUAL_KEY| unsafe { GetKeyState(key.0 as i32) } < 0;
+    let mut modifiers = Modifiers::empty();
+    modifiers.set(Modifiers::SHIFT, pressed(VK_SHIFT));
+    modifiers.set(Modifiers::CONTROL, pressed(VK_CONTROL));
+    modifiers.set(Modifiers::ALT, pressed(VK_MENU));
+    modifiers.set(Modifiers::SUPER, pressed(VK_LWIN) || pressed(VK_RWIN));
+    modifiers
+  }
+
+  fn key_code(virtual_key: u32) -> Option<Code> {
+    let code = match virtual_key {
+      0x0D => Code::Enter,
+      0x1B => Code::Escape,
+      0x09 => Code::Tab,
+      0x20 => Code::Space,
+      0x08 => Code::Backspace,
+      0x2E => Code::Delete,
+      0x2D => Code::Insert,
+      0x24 => Code::Home,
+      0x23 => Code::End,
+      0x21 => Code::PageUp,
+      0x22 => Code::PageDown,
+      0x25 => Code::ArrowLeft,
+      0x27 => Code::ArrowRight,
+      0x26 => Code::ArrowUp,
+      0x28 => Code::ArrowDown,
+      0xBD => Code::Minus,
+      0xBB => Code::Equal,
+      0xBC => Code::Comma,
+      0xBE => Code::Period,
+      0xBF => Code::Slash,
+      0xBA => Code::Semicolon,
+      0xDE => Code::Quote,
+      0xDB => Code::BracketLeft,
+      0xDD => Code::BracketRight,
+      0xDC => Code::Backslash,
+      0xC0 => Code::Backquote,
+      0x30..=0x39 | 0x41..=0x5A => {
+        return super::character_code(&char::from(virtual_key as u8).to_string())
+      }
+      0x70..=0x87 => return super::function_key_code(&format!("F{}", virtual_key - 0x6F)),
+      _ => return None,
+    };
+    Some(code)
+  }
+}
+
+#[cfg(all(feature = "wry", target_os = "macos"))]
+mod macos {
+  use std::collections::HashMap;
+
+  use block::ConcreteBlock;
+  use cocoa::base::{id, nil, BOOL, NO};
+  use muda::accelerator::{Code, Modifiers};
+  use objc::{class, msg_send, sel, sel_impl};
+
+  use crate::{Runtime, Window, WindowEvent};
+
+  // NSEventMaskKeyDown
+  const KEY_DOWN_MASK: u64 = 1 << 10;
+
+  /// The local key event monitor of a window and the WKWebView of each hooked webview.
+  pub struct Monitor {
+    monitor: usize,
+    webviews: HashMap<String, usize>,
+  }
+
+  /// Handles the accelerators in a local event monitor of the app,
+  /// which runs before the event is dispatched to the window and its first responder.
+  pub fn hook<R: Runtime>(window: Window<R>, label: String, webview: id, ns_window: id) {
+    let mut state = window.accelerators.lock().unwrap();
+    if let Some(monitor) = &mut state.monitor {
+      monitor.webviews.insert(label, webview as usize);
+      return;
+    }
+
+    let window_ = window.clone();
+    let ns_window = ns_window as usize;
+    let block = ConcreteBlock::new(move |event: id| -> id {
+      unsafe {
+        let event_window: id = msg_send![event, window];
+        if event_window as usize != ns_window {
+          return event;
+        }
+        let Some(code) = key_code(msg_send![event, keyCode]) else {
+          return event;
+        };
+        let flags: u64 = msg_send![event, modifierFlags];
+        let mut modifiers = Modifiers::empty();
+        modifiers.set(Modifiers::SHIFT, flags & (1 << 17) != 0);
+        modifiers.set(Modifiers::CONTROL, flags & (1 << 18) != 0);
+        modifiers.set(Modifiers::ALT, flags & (1 << 19) != 0);
+        modifiers.set(Modifiers::SUPER, flags & (1 << 20) != 0);
+
+        let label = focused_webview(&window_, ns_window as id);
+        if window_.handle_accelerator(modifiers, code, label.as_deref()) {
+          nil
+        } else {
+          event
+        }
+      }
+    })
+    .copy();
+    let monitor: id = unsafe {
+      msg_send![class!(NSEvent), addLocalMonitorForEventsMatchingMask: KEY_DOWN_MASK handler: &*block]
+    };
+
+    state.monitor.replace(Monitor {
+      monitor: monitor as usize,
+      webviews: HashMap::from([(label, webview as usize)]),
+    });
+    drop(state);
+
+    // the monitor keeps the window alive, remove it with the window
+    let window_ = window.clone();
+    window.on_window_event(move |event| {
+      if let WindowEvent::Destroyed = event {
+        if let Some(monitor) = window_.accelerators.lock().unwrap().monitor.take() {
+          let _: () = unsafe { msg_send![class!(NSEvent), removeMonitor: monitor.monitor as id] };
+        }
+      }
+    });
+  }
+
+  /// The label of the webview containing the first responder of the window.
+  unsafe fn focused_webview<R: Runtime>(window: &Window<R>, ns_window: id) -> Option<String> {
+    let responder: id = msg_send![ns_window, firstResponder];
+    let is_view: BOOL = msg_send![responder, isKindOfClass: class!(NSView)];
+    if responder == nil || is_view == NO {
+      return None;
+    }
+    let state = window.accelerators.lock().unwrap();
+    state
+      .monitor
+      .as_ref()?
+      .webviews
+      .iter()
+      .find_map(|(label, webview)| {
+        let is_descendant: BOOL = msg_send![responder, isDescendantOf: *webview as id];
+        (is_descendant != NO).then(|| label.clone())
+      })
+  }
+
+  fn key_code(key_code: u16) -> Option<Code> {
+    let code = match key_code {
+      0x00 => Code::KeyA,
+      0x01 => Code::KeyS,
+      0x02 => Code::KeyD,
+      0x03 => Code::KeyF,
+      0x04 => Code::KeyH,
+      0x05 => Code::KeyG,
+      0x06 => Code::KeyZ,
+      0x07 => Code::KeyX,
+      0x08 => Code::KeyC,
+      0x09 => Code::KeyV,
+      0x0B => Code::KeyB,
+      0x0C => Code::KeyQ,
+      0x0D => Code::KeyW,
+      0x0E => Code::KeyE,
+      0x0F => Code::KeyR,
+      0x10 => Code::KeyY,
+      0x11 => Code::KeyT,
+      0x12 => Code::Digit1,
+      0x13 => Code::Digit2,
+      0x14 => Code::Digit3,
+      0x15 => Code::Digit4,
+      0x16 => Code::Digit6,
+      0x17 => Code::Digit5,
+      0x18 => Code::Equal,
+      0x19 => Code::Digit9,
+      0x1A => Code::Digit7,
+      0x1B => Code::Minus,
+      0x1C => Code::Digit8,
+      0x1D => Code::Digit0,
+      0x1E => Code::BracketRight,
+      0x1F => Code::KeyO,
+      0x20 => Code::KeyU,
+      0x21 => Code::BracketLeft,
+      0x22 => Code::KeyI,
+      0x23 => Code::KeyP,
+      0x24 => Code::Enter,
+      0x25 => Code::KeyL,
+      0x26 => Code::KeyJ,
+      0x27 => Code::Quote,
+      0x28 => Code::KeyK,
+      0x29 => Code::Semicolon,
+      0x2A => Code::Backslash,
+      0x2B => Code::Comma,
+      0x2C => Code::Slash,
+      0x2D => Code::KeyN,
+      0x2E => Code::KeyM,
+      0x2F => Code::Period,
+      0x30 => Code::Tab,
+      0x31 => Code::Space,
+      0x32 => Code::Backquote,
+      0x33 => Code::Backspace,
+      0x35 => Code::Escape,
+      0x60 => Code::F5,
+      0x61 => Code::F6,
+      0x62 => Code::F7,
+      0x63 => Code::F3,
+      0x64 => Code::F8,
+      0x65 => Code::F9,
+      0x67 => Code::F11,
+      0x6D => Code::F10,
+      0x6F => Code::F12,
+      0x73 => Code::Home,
+      0x74 => Code::PageUp,
+      0x75 => Code::Delete,
+      0x76 => Code::F4,
+      0x77 => Code::End,
+      0x78 => Code::F2,
+      0x79 => Code::PageDown,
+      0x7A => Code::F1,
+      0x7B => Code::ArrowLeft,
+      0x7C => Code::ArrowRight,
+      0x7D => Code::ArrowDown,
+      0x7E => Code::ArrowUp,
+      _ => return None,
+    };
+    Some(code)
+  }
+}
+
+/// The code of a letter or digit key.
+#[cfg(all(feature = "wry", not(target_os = "macos")))]
+fn character_code(name: &str) -> Option<Code> {
+  let mut chars = name.chars();
+  match (chars.next()?.to_ascii_uppercase(), chars.next()) {
+    (c @ 'A'..='Z', None) => format!("Key{c}").parse().ok(),
+    (c @ '0'..='9', None) => format!("Digit{c}").parse().ok(),
+    _ => None,
+  }
+}
+
+/// The code of a `F1` to `F24` function key.
+#[cfg(all(feature = "wry", not(target_os = "macos")))]
+fn function_key_code(name: &str) -> Option<Code> {
+  match name.strip_prefix('F')?.parse::<u8>() {
+    Ok(1..=24) => name.parse().ok(),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+  };
+
+  use muda::accelerator::{Code, Modifiers};
+
+  use crate::{
+    menu::{MenuBuilder, MenuItemBuilder},
+    test::mock_app,
+    webview::WebviewBuilder,
+    window::WindowBuilder,
+    LogicalPosition, LogicalSize,
+  };
+
+  #[test]
+  fn accelerators() {
+    let app = mock_app();
+    let window = WindowBuilder::new(&app, "main").build().unwrap();
+    let add_child = |label: &str, allow_override: bool| {
+      window
+        .add_child(
+          WebviewBuilder::new(label, Default::default()).allow_accelerator_override(allow_override),
+          LogicalPosition::new(0, 0),
+          LogicalSize::new(100, 100),
+        )
+        .unwrap()
+    };
+    add_child("tab", false);
+    add_child("trusted", true);
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let calls_ = calls.clone();
+    window
+      .register_accelerator("Ctrl+Shift+1", move |_, webview| {
+        assert_eq!(webview.unwrap().label(), "tab");
+        calls_.fetch_add(1, Ordering::Relaxed);
+      })
+      .unwrap();
+
+    let modifiers = Modifiers::CONTROL | Modifiers::SHIFT;
+    assert!(window.handle_accelerator(modifiers, Code::Digit1, Some("tab")));
+    assert!(!window.handle_accelerator(Modifiers::CONTROL, Code::Digit1, Some("tab")));
+    // the trusted webview receives the key events
+    assert!(!window.handle_accelerator(modifiers, Code::Digit1, Some("trusted")));
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+    assert!(matches!(
+      window.register_accelerator("Ctrl+Shift+1", |_, _| {}),
+      Err(crate::Error::AcceleratorConflict(_))
+    ));
+
+    window.unregister_accelerator("Ctrl+Shift+1").unwrap();
+    assert!(!window.handle_accelerator(modifiers, Code::Digit1, Some("tab")));
+    assert!(window
+      .register_accelerator("Ctrl+Shift+1", |_, _| {})
+      .is_ok());
+  }
+
+  #[test]
+  fn menu_accelerator_conflict() {
+    let app = mock_app();
+    let window = WindowBuilder::new(&app, "main").build().unwrap();
+
+    let item = MenuItemBuilder::new("Reload")
+      .accelerator("CmdOrCtrl+R")
+      .build(&app)
+      .unwrap();
+    let menu = MenuBuilder::new(&app).item(&item).build().unwrap();
+    // not part of the app menu yet
+    assert!(window
+      .register_accelerator("CmdOrCtrl+R", |_, _| {})
+      .is_ok());
+    window.unregister_accelerator("CmdOrCtrl+R").unwrap();
+
+    app.set_menu(menu).unwrap();
+    assert!(matches!(
+      window.register_accelerator("CmdOrCtrl+R", |_, _| {}),
+      Err(crate::Error::AcceleratorConflict(_))
+    ));
+
+    item.set_accelerator(None::<&str>).unwrap();
+    assert!(window
+      .register_accelerator("CmdOrCtrl+R", |_, _| {})
+      .is_ok());
+  }
+}
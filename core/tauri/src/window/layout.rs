@@ -0,0 +1,688 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Automatic layout of the webviews of a window.
+
+use std::collections::{HashMap, HashSet};
+
+use tauri_runtime::{NativeViewId, WebviewDispatch, WindowDispatch};
+
+use super::{NativeViewHandle, Window};
+#[cfg(any(test, feature = "unstable"))]
+use crate::webview::{Webview, WebviewBuilder};
+use crate::{
+  runtime::window::dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize},
+  Runtime, WindowEvent,
+};
+
+/// The direction of a [`Layout::Split`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitDirection {
+  /// The webviews are laid out from left to right.
+  Horizontal,
+  /// The webviews are laid out from top to bottom.
+  Vertical,
+}
+
+/// A layout of the webviews of a window, see [`Window::set_webview_layout`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Layout {
+  /// Lays out the webviews on a grid, filling each row from left to right.
+  ///
+  /// Webviews that do not fit in the grid are not moved.
+  Grid {
+    /// The number of rows.
+    rows: u32,
+    /// The number of columns.
+    cols: u32,
+  },
+  /// Gives the first webview `ratio` of the window in the given direction
+  /// and splits the remaining space equally between the other webviews.
+  Split {
+    /// The split direction.
+    direction: SplitDirection,
+    /// The portion of the window used by the first webview, between `0.0` and `1.0`.
+    ratio: f64,
+  },
+}
+
+/// A rectangle in logical pixels, relative to the top-left corner of the window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LogicalRect {
+  /// The position of the top-left corner.
+  pub position: LogicalPosition<f64>,
+  /// The size of the rectangle.
+  pub size: LogicalSize<f64>,
+}
+
+/// The area of a window used by a webview or a native view, see [`Window::add_webview`] and [`Window::add_native_view`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LayoutSlot {
+  /// The whole window.
+  Fill,
+  /// The left part of the window, the value is its percentage of the window width.
+  Left(f64),
+  /// The right part of the window, the value is its percentage of the window width.
+  Right(f64),
+  /// The top part of the window, the value is its height in logical pixels.
+  Top(f64),
+  /// The bottom part of the window, the value is its height in logical pixels.
+  Bottom(f64),
+  /// A fixed rectangle, which does not follow the window size.
+  Rect(LogicalRect),
+}
+
+impl LayoutSlot {
+  /// Whether the bounds of the slot depend on the window size.
+  fn follows_window_size(&self) -> bool {
+    !matches!(self, Self::Rect(_))
+  }
+}
+
+#[derive(Default)]
+pub(crate) struct LayoutState {
+  layout: Option<Layout>,
+  /// Webviews that had their bounds set manually since the layout was set.
+  excluded: HashSet<String>,
+  /// Webviews added in a slot that follows the window size, by label.
+  slots: HashMap<String, LayoutSlot>,
+  /// Native views added in a slot that follows the window size, by id.
+  native_slots: HashMap<NativeViewId, LayoutSlot>,
+  /// Whether the resize listener was registered.
+  listening: bool,
+}
+
+impl<R: Runtime> Window<R> {
+  /// Lays out the webviews of this window and keeps them laid out when the window is resized.
+  ///
+  /// Webviews are ordered by label. A webview is no longer laid out
+  /// after its bounds are set manually, until a layout is set again.
+  ///
+  /// This has no effect on webview windows, where the webview always fills the window.
+  pub fn set_webview_layout(&self, layout: Layout) -> crate::Result<()> {
+    {
+      let mut state = self.layout.lock().unwrap();
+      state.layout.replace(layout);
+      state.excluded.clear();
+    }
+    self.listen_to_resizes();
+
+    self.apply_webview_layout(self.inner_size()?)
+  }
+
+  /// Adds a new webview as a child of this window, in the given slot of the window.
+  ///
+  /// The slot is converted to physical bounds with the scale factor and the current inner size of the window.
+  /// Unless the slot is a [`LayoutSlot::Rect`], the webview is kept in its slot when the window is resized,
+  /// until its bounds are set manually.
+  ///
+  /// Webviews added in a slot are not moved by the [`Layout`] of the window.
+  #[cfg(any(test, feature = "unstable"))]
+  #[cfg_attr(docsrs, doc(cfg(all(desktop, feature = "unstable"))))]
+  pub fn add_webview(
+    &self,
+    webview_builder: WebviewBuilder<R>,
+    slot: LayoutSlot,
+  ) -> crate::Result<Webview<R>> {
+    let (position, size) = slot_bounds(slot, self.inner_size()?, self.scale_factor()?);
+    let label = webview_builder.label.clone();
+    let webview = webview_builder.build(self.clone(), position.into(), size.into())?;
+
+    if slot.follows_window_size() {
+      self.layout.lock().unwrap().slots.insert(label, slot);
+      self.listen_to_resizes();
+    }
+
+    Ok(webview)
+  }
+
+  /// Adds a native child view to this window, in the given slot of the window.
+  ///
+  /// The view is a raw surface (a `HWND` on Windows, a `NSView` on macOS and a `GtkDrawingArea` on Linux)
+  /// that can be rendered into with the handle returned by [`NativeViewHandle`]'s [`HasWindowHandle`] implementation,
+  /// for instance with `wgpu` or `skia`. It is kept in its slot like the webviews added with [`Window::add_webview`]
+  /// and is stacked with the child webviews, see [`NativeViewHandle::set_z_order`].
+  ///
+  /// [`HasWindowHandle`]: raw_window_handle::HasWindowHandle
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux**: The view is packed below the child webviews of the window, its position is ignored.
+  pub fn add_native_view(&self, slot: LayoutSlot) -> crate::Result<NativeViewHandle<R>> {
+    let (position, size) = slot_bounds(slot, self.inner_size()?, self.scale_factor()?);
+    let id = self
+      .window
+      .dispatcher
+      .create_native_view(position.into(), size.into())?;
+
+    if slot.follows_window_size() {
+      self.layout.lock().unwrap().native_slots.insert(id, slot);
+      self.listen_to_resizes();
+    }
+
+    Ok(NativeViewHandle::new(self.clone(), id))
+  }
+
+  /// Stops laying out the webviews of this window. Their current bounds are kept.
+  pub fn clear_webview_layout(&self) {
+    let mut state = self.layout.lock().unwrap();
+    state.layout = None;
+    state.excluded.clear();
+  }
+
+  /// Excludes the webview from the layout, called when its bounds are set manually.
+  pub(crate) fn exclude_from_webview_layout(&self, label: &str) {
+    let mut state = self.layout.lock().unwrap();
+    state.slots.remove(label);
+    if state.layout.is_some() {
+      state.excluded.insert(label.into());
+    }
+  }
+
+  /// Stops keeping the native view in its slot, called when its bounds are set manually or it is removed.
+  pub(crate) fn exclude_native_view_from_layout(&self, id: NativeViewId) {
+    self.layout.lock().unwrap().native_slots.remove(&id);
+  }
+
+  /// Registers the listener that lays out the webviews when the window is resized, once.
+  fn listen_to_resizes(&self) {
+    if std::mem::replace(&mut self.layout.lock().unwrap().listening, true) {
+      return;
+    }
+
+    // the listener is owned by the window, so it must not keep the window nor the manager alive
+    let manager = std::sync::Arc::downgrade(&self.manager);
+    let label = self.label().to_string();
+    self.on_window_event(move |event| {
+      if let WindowEvent::Resized(size) = event {
+        let Some(window) = manager
+          .upgrade()
+          .and_then(|manager| manager.get_window(&label))
+        else {
+          return;
+        };
+        let _ = window.apply_webview_layout(*size);
+        let _ = window.apply_webview_slots(*size);
+        let _ = window.apply_native_view_slots(*size);
+      }
+    });
+  }
+
+  fn apply_webview_slots(&self, size: PhysicalSize<u32>) -> crate::Result<()> {
+    let slots = self.layout.lock().unwrap().slots.clone();
+    if slots.is_empty() {
+      return Ok(());
+    }
+
+    let scale_factor = self.scale_factor()?;
+    for webview in self.webviews() {
+      if let Some(slot) = slots.get(webview.label()) {
+        let (position, size) = slot_bounds(*slot, size, scale_factor);
+        webview
+          .webview
+          .dispatcher
+          .set_bounds(position.into(), size.into())?;
+      }
+    }
+
+    Ok(())
+  }
+
+  fn apply_native_view_slots(&self, size: PhysicalSize<u32>) -> crate::Result<()> {
+    let slots = self.layout.lock().unwrap().native_slots.clone();
+    if slots.is_empty() {
+      return Ok(());
+    }
+
+    let scale_factor = self.scale_factor()?;
+    for (id, slot) in slots {
+      let (position, size) = slot_bounds(slot, size, scale_factor);
+      self
+        .window
+        .dispatcher
+        .set_native_view_bounds(id, position.into(), size.into())?;
+    }
+
+    Ok(())
+  }
+
+  fn apply_webview_layout(&self, size: PhysicalSize<u32>) -> crate::Result<()> {
+    if self.webview_window {
+      return Ok(());
+    }
+
+    let (layout, excluded) = {
+      let state = self.layout.lock().unwrap();
+      match state.layout {
+        Some(layout) => (
+          layout,
+          state
+            .excluded
+            .iter()
+            .chain(state.slots.keys())
+            .cloned()
+            .collect::<HashSet<_>>(),
+        ),
+        None => return Ok(()),
+      }
+    };
+
+    let mut webviews = self
+      .webviews()
+      .into_iter()
+      .filter(|webview| !excluded.contains(webview.label()))
+      .collect::<Vec<_>>();
+    webviews.sort_by(|a, b| a.label().cmp(b.label()));
+
+    for (webview, (position, size)) in
+      webviews
+        .iter()
+        .zip(compute_bounds(layout, size, webviews.len()))
+    {
+      webview
+        .webview
+        .dispatcher
+        .set_bounds(position.into(), size.into())?;
+    }
+
+    Ok(())
+  }
+}
+
+/// Splits `total` in `count` contiguous segments, returning the offset and length of the segment at `index`.
+fn segment(total: u32, count: u32, index: u32) -> (u32, u32) {
+  let start = (total as u64 * index as u64 / count as u64) as u32;
+  let end = (total as u64 * (index as u64 + 1) / count as u64) as u32;
+  (start, end - start)
+}
+
+/// Computes the bounds of `count` webviews in a window of the given size.
+fn compute_bounds(
+  layout: Layout,
+  size: PhysicalSize<u32>,
+  count: usize,
+) -> Vec<(PhysicalPosition<i32>, PhysicalSize<u32>)> {
+  let bounds = |x: u32, y: u32, width: u32, height: u32| {
+    (
+      PhysicalPosition::new(x as i32, y as i32),
+      PhysicalSize::new(width, height),
+    )
+  };
+
+  match layout {
+    Layout::Grid { rows, cols } => {
+      if rows == 0 || cols == 0 {
+        return Vec::new();
+      }
+      (0..rows.saturating_mul(cols).min(count as u32))
+        .map(|i| {
+          let (x, width) = segment(size.width, cols, i % cols);
+          let (y, height) = segment(size.height, rows, i / cols);
+          bounds(x, y, width, height)
+        })
+        .collect()
+    }
+    Layout::Split { direction, ratio } => {
+      if count == 0 {
+        return Vec::new();
+      }
+      let total = match direction {
+        SplitDirection::Horizontal => size.width,
+        SplitDirection::Vertical => size.height,
+      };
+      let first = if count == 1 {
+        total
+      } else {
+        (total as f64 * ratio.clamp(0.0, 1.0)).round() as u32
+      };
+      let rest = count as u32 - 1;
+
+      (0..count as u32)
+        .map(|i| {
+          let (offset, length) = if i == 0 {
+            (0, first)
+          } else {
+            let (offset, length) = segment(total - first, rest, i - 1);
+            (first + offset, length)
+          };
+          match direction {
+            SplitDirection::Horizontal => bounds(offset, 0, length, size.height),
+            SplitDirection::Vertical => bounds(0, offset, size.width, length),
+          }
+        })
+        .collect()
+    }
+  }
+}
+
+/// Computes the physical bounds of a slot in a window of the given inner size.
+fn slot_bounds(
+  slot: LayoutSlot,
+  size: PhysicalSize<u32>,
+  scale_factor: f64,
+) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+  let percent =
+    |percent: f64, total: u32| (total as f64 * percent.clamp(0.0, 100.0) / 100.0).round() as u32;
+  let pixels = |logical: f64, total: u32| {
+    LogicalSize::new(logical.max(0.0), 0.0)
+      .to_physical::<u32>(scale_factor)
+      .width
+      .min(total)
+  };
+
+  match slot {
+    LayoutSlot::Fill => (PhysicalPosition::new(0, 0), size),
+    LayoutSlot::Left(width) => (
+      PhysicalPosition::new(0, 0),
+      PhysicalSize::new(percent(width, size.width), size.height),
+    ),
+    LayoutSlot::Right(width) => {
+      let width = percent(width, size.width);
+      (
+        PhysicalPosition::new((size.width - width) as i32, 0),
+        PhysicalSize::new(width, size.height),
+      )
+    }
+    LayoutSlot::Top(height) => (
+      PhysicalPosition::new(0, 0),
+      PhysicalSize::new(size.width, pixels(height, size.height)),
+    ),
+    LayoutSlot::Bottom(height) => {
+      let height = pixels(height, size.height);
+      (
+        PhysicalPosition::new(0, (size.height - height) as i32),
+        PhysicalSize::new(size.width, height),
+      )
+    }
+    LayoutSlot::Rect(rect) => (
+      rect.position.to_physical(scale_factor),
+      rect.size.to_physical(scale_factor),
+    ),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, Mutex};
+
+  use super::{compute_bounds, slot_bounds, Layout, LayoutSlot, LogicalRect, SplitDirection};
+  use crate::{
+    runtime::window::dpi::{PhysicalPosition, PhysicalSize},
+    test::mock_app,
+    webview::WebviewBuilder,
+    window::WindowBuilder,
+    LogicalPosition, LogicalSize, WebviewEvent,
+  };
+
+  fn bounds(x: i32, y: i32, width: u32, height: u32) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+    (
+      PhysicalPosition::new(x, y),
+      PhysicalSize::new(width, height),
+    )
+  }
+
+  #[test]
+  fn grid_layout() {
+    let size = PhysicalSize::new(801, 600);
+    let layout = Layout::Grid { rows: 2, cols: 2 };
+
+    assert_eq!(
+      compute_bounds(layout, size, 3),
+      vec![
+        bounds(0, 0, 400, 300),
+        bounds(400, 0, 401, 300),
+        bounds(0, 300, 400, 300),
+      ]
+    );
+    // webviews that do not fit are not laid out
+    assert_eq!(compute_bounds(layout, size, 5).len(), 4);
+    assert!(compute_bounds(Layout::Grid { rows: 0, cols: 2 }, size, 2).is_empty());
+    // large grids do not overflow
+    let layout = Layout::Grid {
+      rows: u32::MAX,
+      cols: 2,
+    };
+    assert_eq!(compute_bounds(layout, size, 3).len(), 3);
+  }
+
+  #[test]
+  fn split_layout() {
+    let size = PhysicalSize::new(1000, 600);
+
+    assert_eq!(
+      compute_bounds(
+        Layout::Split {
+          direction: SplitDirection::Horizontal,
+          ratio: 0.25
+        },
+        size,
+        3
+      ),
+      vec![
+        bounds(0, 0, 250, 600),
+        bounds(250, 0, 375, 600),
+        bounds(625, 0, 375, 600),
+      ]
+    );
+    assert_eq!(
+      compute_bounds(
+        Layout::Split {
+          direction: SplitDirection::Vertical,
+          ratio: 2.0
+        },
+        size,
+        2
+      ),
+      vec![bounds(0, 0, 1000, 600), bounds(0, 600, 1000, 0)]
+    );
+    assert_eq!(
+      compute_bounds(
+        Layout::Split {
+          direction: SplitDirection::Vertical,
+          ratio: 0.5
+        },
+        size,
+        1
+      ),
+      vec![bounds(0, 0, 1000, 600)]
+    );
+  }
+
+  #[test]
+  fn layout_resizes_webviews() {
+    let app = mock_app();
+    let window = WindowBuilder::new(&app, "main").build().unwrap();
+    window.set_size(PhysicalSize::new(1000, 600)).unwrap();
+    let add_child = |label: &str| {
+      window
+        .add_child(
+          WebviewBuilder::new(label, Default::default()),
+          LogicalPosition::new(0, 0),
+          LogicalSize::new(100, 100),
+        )
+        .unwrap()
+    };
+    let left = add_child("left");
+    let right = add_child("right");
+    assert_eq!(left.inner_size().unwrap(), PhysicalSize::new(100, 100));
+    assert_eq!(left.scale_factor().unwrap(), 1.0);
+
+    let resized = Arc::new(Mutex::new(Vec::new()));
+    let resized_ = resized.clone();
+    right.on_webview_event(move |event| {
+      if let WebviewEvent::Resized(size) = event {
+        resized_.lock().unwrap().push(*size);
+      }
+    });
+
+    window
+      .set_webview_layout(Layout::Split {
+        direction: SplitDirection::Horizontal,
+        ratio: 0.25,
+      })
+      .unwrap();
+    assert_eq!(left.inner_size().unwrap(), PhysicalSize::new(250, 600));
+    assert_eq!(right.inner_size().unwrap(), PhysicalSize::new(750, 600));
+
+    // laid out again when the window is resized
+    window.set_size(PhysicalSize::new(2000, 600)).unwrap();
+    assert_eq!(left.inner_size().unwrap(), PhysicalSize::new(500, 600));
+    assert_eq!(right.position().unwrap(), PhysicalPosition::new(500, 0));
+    assert_eq!(
+      *resized.lock().unwrap(),
+      vec![PhysicalSize::new(750, 600), PhysicalSize::new(1500, 600)]
+    );
+  }
+
+  #[test]
+  fn slot_bounds_scale_factor() {
+    let size = PhysicalSize::new(1000, 600);
+    let rect = LayoutSlot::Rect(LogicalRect {
+      position: LogicalPosition::new(10.0, 20.0),
+      size: LogicalSize::new(300.0, 200.0),
+    });
+
+    // scale factor 1.0
+    assert_eq!(
+      slot_bounds(LayoutSlot::Fill, size, 1.0),
+      bounds(0, 0, 1000, 600)
+    );
+    assert_eq!(
+      slot_bounds(LayoutSlot::Left(25.0), size, 1.0),
+      bounds(0, 0, 250, 600)
+    );
+    assert_eq!(
+      slot_bounds(LayoutSlot::Right(25.0), size, 1.0),
+      bounds(750, 0, 250, 600)
+    );
+    assert_eq!(
+      slot_bounds(LayoutSlot::Top(40.0), size, 1.0),
+      bounds(0, 0, 1000, 40)
+    );
+    assert_eq!(
+      slot_bounds(LayoutSlot::Bottom(40.0), size, 1.0),
+      bounds(0, 560, 1000, 40)
+    );
+    assert_eq!(slot_bounds(rect, size, 1.0), bounds(10, 20, 300, 200));
+
+    // scale factor 2.0, percentages are relative to the physical size
+    assert_eq!(
+      slot_bounds(LayoutSlot::Fill, size, 2.0),
+      bounds(0, 0, 1000, 600)
+    );
+    assert_eq!(
+      slot_bounds(LayoutSlot::Left(25.0), size, 2.0),
+      bounds(0, 0, 250, 600)
+    );
+    assert_eq!(
+      slot_bounds(LayoutSlot::Right(25.0), size, 2.0),
+      bounds(750, 0, 250, 600)
+    );
+    assert_eq!(
+      slot_bounds(LayoutSlot::Top(40.0), size, 2.0),
+      bounds(0, 0, 1000, 80)
+    );
+    assert_eq!(
+      slot_bounds(LayoutSlot::Bottom(40.0), size, 2.0),
+      bounds(0, 520, 1000, 80)
+    );
+    assert_eq!(slot_bounds(rect, size, 2.0), bounds(20, 40, 600, 400));
+
+    // out of range values are clamped to the window
+    assert_eq!(
+      slot_bounds(LayoutSlot::Right(150.0), size, 1.0),
+      bounds(0, 0, 1000, 600)
+    );
+    assert_eq!(
+      slot_bounds(LayoutSlot::Bottom(400.0), size, 2.0),
+      bounds(0, 0, 1000, 600)
+    );
+  }
+
+  #[test]
+  fn slots_follow_window_size() {
+    let app = mock_app();
+    let window = WindowBuilder::new(&app, "main").build().unwrap();
+    window.set_size(PhysicalSize::new(1000, 600)).unwrap();
+
+    let sidebar = window
+      .add_webview(
+        WebviewBuilder::new("sidebar", Default::default()),
+        LayoutSlot::Left(20.0),
+      )
+      .unwrap();
+    let footer = window
+      .add_webview(
+        WebviewBuilder::new("footer", Default::default()),
+        LayoutSlot::Bottom(50.0),
+      )
+      .unwrap();
+    let fixed = window
+      .add_webview(
+        WebviewBuilder::new("fixed", Default::default()),
+        LayoutSlot::Rect(LogicalRect {
+          position: LogicalPosition::new(10.0, 10.0),
+          size: LogicalSize::new(100.0, 100.0),
+        }),
+      )
+      .unwrap();
+    assert_eq!(sidebar.inner_size().unwrap(), PhysicalSize::new(200, 600));
+    assert_eq!(footer.position().unwrap(), PhysicalPosition::new(0, 550));
+
+    window.set_size(PhysicalSize::new(2000, 800)).unwrap();
+    assert_eq!(sidebar.inner_size().unwrap(), PhysicalSize::new(400, 800));
+    assert_eq!(footer.position().unwrap(), PhysicalPosition::new(0, 750));
+    assert_eq!(footer.inner_size().unwrap(), PhysicalSize::new(2000, 50));
+    assert_eq!(fixed.inner_size().unwrap(), PhysicalSize::new(100, 100));
+
+    // slots are not tracked after the bounds are set manually
+    sidebar.set_size(LogicalSize::new(300, 300)).unwrap();
+    window.set_size(PhysicalSize::new(1000, 600)).unwrap();
+    assert_eq!(sidebar.inner_size().unwrap(), PhysicalSize::new(300, 300));
+    assert_eq!(footer.position().unwrap(), PhysicalPosition::new(0, 550));
+  }
+
+  #[test]
+  fn native_views_follow_window_size() {
+    use raw_window_handle::HasWindowHandle;
+
+    let app = mock_app();
+    let window = WindowBuilder::new(&app, "main").build().unwrap();
+    window.set_size(PhysicalSize::new(1000, 600)).unwrap();
+    let views = || window.window.dispatcher.native_views();
+
+    let canvas = window.add_native_view(LayoutSlot::Right(50.0)).unwrap();
+    let overlay = window
+      .add_native_view(LayoutSlot::Rect(LogicalRect {
+        position: LogicalPosition::new(10.0, 10.0),
+        size: LogicalSize::new(100.0, 100.0),
+      }))
+      .unwrap();
+    assert!(canvas.window_handle().is_ok());
+    assert_eq!(views()[0].position, PhysicalPosition::new(500, 0));
+    assert_eq!(views()[0].size, PhysicalSize::new(500, 600));
+
+    window.set_size(PhysicalSize::new(2000, 800)).unwrap();
+    assert_eq!(views()[0].position, PhysicalPosition::new(1000, 0));
+    assert_eq!(views()[0].size, PhysicalSize::new(1000, 800));
+    assert_eq!(views()[1].size, PhysicalSize::new(100, 100));
+
+    overlay.hide().unwrap();
+    assert!(!views()[1].visible);
+    overlay.set_z_order(0).unwrap();
+    assert_eq!(views()[0].id, overlay.id());
+
+    // the view is not kept in its slot after its bounds are set manually
+    canvas
+      .set_bounds(PhysicalPosition::new(0, 0), PhysicalSize::new(300, 300))
+      .unwrap();
+    window.set_size(PhysicalSize::new(1000, 600)).unwrap();
+    assert_eq!(views()[1].size, PhysicalSize::new(300, 300));
+
+    let removed = canvas.clone();
+    canvas.remove().unwrap();
+    assert_eq!(views().len(), 1);
+    assert!(removed.window_handle().is_err());
+  }
+}
@@ -4,8 +4,35 @@
 
 //! The Tauri window types and functions.
 
+#[cfg(desktop)]
+mod accelerator;
+#[cfg(desktop)]
+mod layout;
+#[cfg(desktop)]
+mod modal;
+#[cfg(desktop)]
+mod native_view;
+#[cfg(desktop)]
+mod placement;
 pub(crate) mod plugin;
+#[cfg(desktop)]
+mod relative;
+mod snapshot;
 
+#[cfg(desktop)]
+pub use layout::{Layout, LayoutSlot, LogicalRect, SplitDirection};
+#[cfg(desktop)]
+pub use modal::MODAL_CLOSED_EVENT;
+#[cfg(desktop)]
+pub use native_view::NativeViewHandle;
+#[cfg(desktop)]
+pub use placement::MonitorSelector;
+#[cfg(desktop)]
+pub use relative::{Edge, RelativePosition};
+pub use tauri_runtime::{
+  monitor::PhysicalRect,
+  window::{validate_label, RESERVED_LABEL_PREFIX},
+};
 use tauri_runtime::{
   webview::PendingWebview,
   window::dpi::{PhysicalPosition, PhysicalSize},
@@ -26,7 +53,7 @@ use crate::{
   sealed::RuntimeOrDispatch,
   utils::config::{WindowConfig, WindowEffectsConfig},
   webview::WebviewBuilder,
-  EventLoopMessage, Manager, Runtime, Theme, Webview, WindowEvent,
+  EventLoopMessage, Manager, Runtime, Theme, Webview, WebviewWindow, WindowEvent,
 };
 #[cfg(desktop)]
 use crate::{
@@ -58,6 +85,7 @@ pub struct Monitor {
   pub(crate) size: PhysicalSize<u32>,
   pub(crate) position: PhysicalPosition<i32>,
   pub(crate) scale_factor: f64,
+  pub(crate) work_area: PhysicalRect,
 }
 
 impl From<RuntimeMonitor> for Monitor {
@@ -67,6 +95,7 @@ impl From<RuntimeMonitor> for Monitor {
       size: monitor.size,
       position: monitor.position,
       scale_factor: monitor.scale_factor,
+      work_area: monitor.work_area,
     }
   }
 }
@@ -92,6 +121,13 @@ impl Monitor {
   pub fn scale_factor(&self) -> f64 {
     self.scale_factor
   }
+
+  /// Returns the area of the monitor not covered by the taskbar, the dock or the menu bar.
+  ///
+  /// Returns the whole monitor if the platform does not report it.
+  pub fn work_area(&self) -> &PhysicalRect {
+    &self.work_area
+  }
 }
 
 macro_rules! unstable_struct {
@@ -117,6 +153,19 @@ unstable_struct!(
     pub(crate) menu: Option<Menu<R>>,
     #[cfg(desktop)]
     on_menu_event: Option<crate::app::GlobalMenuEventListener<Window<R>>>,
+    #[cfg(desktop)]
+    modal_parent: Option<Window<R>>,
+    #[cfg(desktop)]
+    monitor_placement: Option<(MonitorSelector, bool)>,
+    #[cfg(desktop)]
+    parent: Option<Window<R>>,
+    #[cfg(desktop)]
+    relative_position: Option<RelativePosition>,
+    #[cfg(desktop)]
+    close_with_parent: bool,
+    #[cfg(desktop)]
+    visible: bool,
+    always_on_top: bool,
     window_effects: Option<WindowEffectsConfig>,
   }
 );
@@ -203,6 +252,19 @@ async fn create_window(app: tauri::AppHandle) {
       menu: None,
       #[cfg(desktop)]
       on_menu_event: None,
+      #[cfg(desktop)]
+      modal_parent: None,
+      #[cfg(desktop)]
+      monitor_placement: None,
+      #[cfg(desktop)]
+      parent: None,
+      #[cfg(desktop)]
+      relative_position: None,
+      #[cfg(desktop)]
+      close_with_parent: false,
+      #[cfg(desktop)]
+      visible: true,
+      always_on_top: false,
       window_effects: None,
     }
   }
@@ -250,6 +312,19 @@ async fn reopen_window(app: tauri::AppHandle) {
       menu: None,
       #[cfg(desktop)]
       on_menu_event: None,
+      #[cfg(desktop)]
+      modal_parent: None,
+      #[cfg(desktop)]
+      monitor_placement: None,
+      #[cfg(desktop)]
+      parent: None,
+      #[cfg(desktop)]
+      relative_position: None,
+      #[cfg(desktop)]
+      close_with_parent: false,
+      #[cfg(desktop)]
+      visible: config.visible,
+      always_on_top: config.always_on_top,
     };
 
     #[cfg(desktop)]
@@ -311,14 +386,16 @@ tauri::Builder::default()
   }
 
   /// Creates this window with a webview with it.
+  ///
+  /// The window and its webview are created together: if the webview cannot be created,
+  /// the window is destroyed and the error names both labels, see [`crate::Error::WebviewWindowCreation`].
+  ///
+  /// Use [`Self::build_webview_window`] to manage both through a single [`WebviewWindow`].
   #[cfg_attr(
     feature = "tracing",
     tracing::instrument(name = "webview::create", skip_all)
   )]
-  pub(crate) fn with_webview(
-    self,
-    webview: WebviewBuilder<R>,
-  ) -> crate::Result<(Window<R>, Webview<R>)> {
+  pub fn with_webview(self, webview: WebviewBuilder<R>) -> crate::Result<(Window<R>, Webview<R>)> {
     let window_labels = self
       .manager
       .manager()
@@ -341,18 +418,28 @@ tauri::Builder::default()
     self.with_webview_internal(webview, &window_labels, &webview_labels)
   }
 
+  /// Creates this window with a webview with it, see [`Self::with_webview`].
+  pub fn build_webview_window(self, webview: WebviewBuilder<R>) -> crate::Result<WebviewWindow<R>> {
+    let (_window, webview) = self.with_webview(webview)?;
+    Ok(WebviewWindow { webview })
+  }
+
   pub(crate) fn with_webview_internal(
     self,
-    webview: WebviewBuilder<R>,
+    mut webview: WebviewBuilder<R>,
     window_labels: &[String],
     webview_labels: &[WebviewLabelDef],
   ) -> crate::Result<(Window<R>, Webview<R>)> {
+    let mut created_options = webview.take_created_options()?;
+    let allowed_commands = created_options.allowed_commands.take();
     let pending_webview =
       webview.into_pending_webview(self.manager, &self.label, window_labels, webview_labels)?;
-    let window = self.build_internal(Some(pending_webview))?;
+    let window = self.build_internal(Some((pending_webview, allowed_commands)))?;
 
     let webview = window.webviews().first().unwrap().clone();
 
+    created_options.apply(&webview);
+
     Ok((window, webview))
   }
 
@@ -361,19 +448,45 @@ tauri::Builder::default()
     self.build_internal(None)
   }
 
-  /// Creates a new window with an optional webview.
+  /// Creates a new window with an optional webview and its allowed commands.
+  ///
+  /// Once the window is created, any failure destroys it before the error is returned.
   fn build_internal(
-    self,
-    webview: Option<PendingWebview<EventLoopMessage, R>>,
+    #[cfg_attr(not(desktop), allow(unused_mut))] mut self,
+    webview: Option<(
+      PendingWebview<EventLoopMessage, R>,
+      Option<crate::ipc::AllowList>,
+    )>,
   ) -> crate::Result<Window<R>> {
+    // the window is shown once it is placed on the monitor or next to its parent
+    #[cfg(desktop)]
+    if self.monitor_placement.is_some()
+      || (self.parent.is_some() && self.relative_position.is_some())
+    {
+      self.window_builder = self.window_builder.visible(false);
+    }
+
+    let webview_label = webview.as_ref().map(|(webview, _)| webview.label.clone());
+    let window_label = self.label.clone();
+    let with_context = move |error: crate::Error| match &webview_label {
+      Some(webview_label) => crate::Error::WebviewWindowCreation(
+        window_label.clone(),
+        webview_label.clone(),
+        Box::new(error),
+      ),
+      None => error,
+    };
+
     let mut pending = PendingWindow::new(self.window_builder.clone(), self.label.clone())?;
-    if let Some(webview) = webview {
+    let allowed_commands = webview.and_then(|(webview, allowed_commands)| {
       pending.set_webview(webview);
-    }
+      allowed_commands
+    });
 
     let app_manager = self.manager.manager();
 
     let pending = app_manager.window.prepare_window(pending)?;
+    app_manager.labels.claim(&pending.label, None)?;
 
     #[cfg(desktop)]
     let window_menu = {
@@ -392,6 +505,7 @@ tauri::Builder::default()
     #[allow(clippy::type_complexity)]
     let handler: Option<Box<dyn Fn(tauri_runtime::window::RawWindow<'_>) + Send>> = None;
 
+    // the runtime does not keep the window when its webview fails to be created
     let window = match &mut self.manager.runtime() {
       RuntimeOrDispatch::Runtime(runtime) => runtime.create_window(pending, handler),
       RuntimeOrDispatch::RuntimeHandle(handle) => handle.create_window(pending, handler),
@@ -407,19 +521,74 @@ tauri::Builder::default()
       );
 
       if let Some(webview) = detached_window.webview {
-        app_manager.webview.attach_webview(window.clone(), webview);
+        app_manager
+          .webview
+          .attach_webview(window.clone(), webview, allowed_commands);
       }
 
       window
-    })?;
+    })
+    .map_err(|e| with_context(e.into()))?;
+
+    if self.always_on_top {
+      app_manager
+        .window
+        .always_on_top
+        .lock()
+        .unwrap()
+        .insert(window.label().into());
+    }
 
     #[cfg(desktop)]
     if let Some(handler) = self.on_menu_event {
       window.on_menu_event(handler);
     }
 
-    if let Some(effects) = self.window_effects {
-      crate::vibrancy::set_window_effects(&window, Some(effects))?;
+    let configure = || -> crate::Result<()> {
+      #[cfg(desktop)]
+      if let Some(parent) = self.modal_parent {
+        modal::attach(&window, &parent)?;
+      }
+
+      #[cfg(desktop)]
+      if let Some((selector, maximized)) = self.monitor_placement {
+        window.move_to_monitor(selector)?;
+        if maximized {
+          window.maximize()?;
+        }
+        if self.visible {
+          window.show()?;
+        }
+      }
+
+      #[cfg(desktop)]
+      if let Some(parent) = self.parent {
+        app_manager
+          .window
+          .parents
+          .lock()
+          .unwrap()
+          .insert(window.label().into(), parent.label().into());
+        relative::attach(&window, &parent, self.close_with_parent);
+        if let Some(position) = self.relative_position {
+          window.position_relative_to(&parent, position)?;
+          if self.visible {
+            window.show()?;
+          }
+        }
+      }
+
+      if let Some(effects) = self.window_effects {
+        crate::vibrancy::set_window_effects(&window, Some(effects))?;
+      }
+
+      Ok(())
+    };
+
+    if let Err(e) = configure() {
+      let _ = window.destroy();
+      app_manager.on_window_close(window.label());
+      return Err(with_context(e));
     }
 
     Ok(window)
@@ -444,6 +613,25 @@ impl<'a, R: Runtime, M: Manager<R>> WindowBuilder<'a, R, M> {
     self
   }
 
+  /// Centers the window in the work area of the selected monitor,
+  /// keeping its logical size even if the monitor has a different scale factor.
+  ///
+  /// The window is shrunk if it does not fit the work area of the monitor.
+  /// When the selected monitor does not exist, the primary monitor is used instead.
+  #[must_use]
+  pub fn on_monitor(mut self, selector: MonitorSelector) -> Self {
+    self.monitor_placement.replace((selector, false));
+    self
+  }
+
+  /// Maximizes the window on the selected monitor,
+  /// see [`Self::on_monitor`].
+  #[must_use]
+  pub fn maximized_on_monitor(mut self, selector: MonitorSelector) -> Self {
+    self.monitor_placement.replace((selector, true));
+    self
+  }
+
   /// The initial position of the window's.
   #[must_use]
   pub fn position(mut self, x: f64, y: f64) -> Self {
@@ -560,6 +748,7 @@ impl<'a, R: Runtime, M: Manager<R>> WindowBuilder<'a, R, M> {
   #[must_use]
   pub fn visible(mut self, visible: bool) -> Self {
     self.window_builder = self.window_builder.visible(visible);
+    self.visible = visible;
     self
   }
 
@@ -605,6 +794,7 @@ impl<'a, R: Runtime, M: Manager<R>> WindowBuilder<'a, R, M> {
   #[must_use]
   pub fn always_on_top(mut self, always_on_top: bool) -> Self {
     self.window_builder = self.window_builder.always_on_top(always_on_top);
+    self.always_on_top = always_on_top;
     self
   }
 
@@ -693,6 +883,63 @@ impl<'a, R: Runtime, M: Manager<R>> WindowBuilder<'a, R, M> {
       self.window_builder = self.window_builder.parent(parent.ns_window()?);
     }
 
+    self.parent.replace(parent.clone());
+    Ok(self)
+  }
+
+  /// Places the window relative to its [parent](Self::parent) when it is created,
+  /// see [`Window::reposition_relative`] to move it later.
+  ///
+  /// The position is computed from the outer position, size and scale factor of the parent window
+  /// and kept in the work area of the parent's monitor. Has no effect without a parent.
+  #[must_use]
+  pub fn position_relative(mut self, position: RelativePosition) -> Self {
+    self.relative_position.replace(position);
+    self
+  }
+
+  /// Whether the window is closed when its [parent](Self::parent) is closed.
+  /// Otherwise the window becomes independent. Defaults to `false`.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows**: The system always destroys owned windows with their owner, so `false` has no effect.
+  #[must_use]
+  pub fn close_with_parent(mut self, close: bool) -> Self {
+    self.close_with_parent = close;
+    self
+  }
+
+  /// Makes the window to be created a modal of the given parent window,
+  /// blocking the input of the parent window until it is closed.
+  ///
+  /// The modal window is closed with its parent. When it is closed, the focus returns to the parent window
+  /// and the parent window receives the [`MODAL_CLOSED_EVENT`] with the label of the modal window.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **macOS**: The window is presented as a sheet of the parent window, without a title bar,
+  ///   so the page must close it with its own controls.
+  /// - **Windows**: The window is owned by the parent window, which is disabled until the window is closed.
+  /// - **Linux**: The window is transient for the parent window and modal.
+  pub fn modal(mut self, parent: &Window<R>) -> crate::Result<Self> {
+    #[cfg(windows)]
+    {
+      self.window_builder = self.window_builder.owner(parent.hwnd()?);
+    }
+
+    #[cfg(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd"
+    ))]
+    {
+      self.window_builder = self.window_builder.transient_for(&parent.gtk_window()?);
+    }
+
+    self.modal_parent.replace(parent.clone());
     Ok(self)
   }
 
@@ -864,6 +1111,10 @@ pub struct Window<R: Runtime> {
   pub(crate) menu: Arc<std::sync::Mutex<Option<WindowMenu<R>>>>,
   /// Whether this window is a Webview window (hosts only a single webview) or a container for multiple webviews
   pub(crate) webview_window: bool,
+  #[cfg(desktop)]
+  pub(crate) layout: Arc<std::sync::Mutex<layout::LayoutState>>,
+  #[cfg(desktop)]
+  pub(crate) accelerators: Arc<std::sync::Mutex<accelerator::AcceleratorState<R>>>,
 }
 
 impl<R: Runtime> std::fmt::Debug for Window<R> {
@@ -894,6 +1145,10 @@ impl<R: Runtime> Clone for Window<R> {
       #[cfg(desktop)]
       menu: self.menu.clone(),
       webview_window: self.webview_window,
+      #[cfg(desktop)]
+      layout: self.layout.clone(),
+      #[cfg(desktop)]
+      accelerators: self.accelerators.clone(),
     }
   }
 }
@@ -957,6 +1212,10 @@ impl<R: Runtime> Window<R> {
       #[cfg(desktop)]
       menu: Arc::new(std::sync::Mutex::new(menu)),
       webview_window,
+      #[cfg(desktop)]
+      layout: Default::default(),
+      #[cfg(desktop)]
+      accelerators: Default::default(),
     }
   }
 
@@ -993,6 +1252,17 @@ impl<R: Runtime> Window<R> {
       .collect()
   }
 
+  /// Fetch a webview associated with this window by its label.
+  pub fn get_webview(&self, label: &str) -> Option<Webview<R>> {
+    self
+      .manager
+      .webview
+      .webviews_lock()
+      .get(label)
+      .filter(|w| w.window() == self)
+      .cloned()
+  }
+
   /// Runs the given closure on the main thread.
   pub fn run_on_main_thread<F: FnOnce() + Send + 'static>(&self, f: F) -> crate::Result<()> {
     self
@@ -1622,12 +1892,20 @@ impl<R: Runtime> Window<R> {
 
   /// Show this window.
   pub fn show(&self) -> crate::Result<()> {
-    self.window.dispatcher.show().map_err(Into::into)
+    self.window.dispatcher.show()?;
+    for webview in self.webviews() {
+      webview.on_visibility_changed(true);
+    }
+    Ok(())
   }
 
   /// Hide this window.
   pub fn hide(&self) -> crate::Result<()> {
-    self.window.dispatcher.hide().map_err(Into::into)
+    self.window.dispatcher.hide()?;
+    for webview in self.webviews() {
+      webview.on_visibility_changed(false);
+    }
+    Ok(())
   }
 
   /// Closes this window. It emits [`crate::RunEvent::CloseRequested`] first like a user-initiated close request so you can intercept it.
@@ -1719,11 +1997,14 @@ tauri::Builder::default()
 
   /// Determines if this window should always be on top of other windows.
   pub fn set_always_on_top(&self, always_on_top: bool) -> crate::Result<()> {
-    self
-      .window
-      .dispatcher
-      .set_always_on_top(always_on_top)
-      .map_err(Into::into)
+    self.window.dispatcher.set_always_on_top(always_on_top)?;
+    let mut windows = self.manager.window.always_on_top.lock().unwrap();
+    if always_on_top {
+      windows.insert(self.label().into());
+    } else {
+      windows.remove(self.label());
+    }
+    Ok(())
   }
 
   /// Sets whether the window should be visible on all workspaces or virtual desktops.
@@ -2068,4 +2349,78 @@ mod tests {
     crate::test_utils::assert_send::<super::Window>();
     crate::test_utils::assert_sync::<super::Window>();
   }
+
+  #[test]
+  fn with_webview_rollback() {
+    use crate::{sealed::ManagerBase, webview::WebviewBuilder, Manager};
+
+    let app = crate::test::mock_app();
+    // a file cannot be used as the data directory
+    let data_directory = std::env::current_exe().unwrap();
+
+    let error = super::WindowBuilder::new(&app, "main")
+      .with_webview(
+        WebviewBuilder::new("content", Default::default()).data_directory(data_directory.clone()),
+      )
+      .unwrap_err();
+    assert!(matches!(
+      &error,
+      crate::Error::WebviewWindowCreation(window, webview, source)
+        if window == "main"
+          && webview == "content"
+          && matches!(**source, crate::Error::Runtime(tauri_runtime::Error::CreateWebview(_)))
+    ));
+    assert!(app.manager().windows().is_empty());
+    assert!(app.manager().webviews().is_empty());
+
+    let error = crate::WebviewWindowBuilder::new(&app, "main", Default::default())
+      .data_directory(data_directory)
+      .build()
+      .unwrap_err();
+    assert!(matches!(error, crate::Error::WebviewWindowCreation(..)));
+    assert!(app.webview_windows().is_empty());
+
+    let (window, webview) = super::WindowBuilder::new(&app, "main")
+      .with_webview(WebviewBuilder::new("content", Default::default()))
+      .unwrap();
+    assert_eq!(webview.window().label(), window.label());
+    assert_eq!(app.manager().windows().len(), 1);
+  }
+
+  #[test]
+  fn label_validation() {
+    use super::validate_label;
+    use tauri_runtime::Error;
+
+    assert!(validate_label("main").is_ok());
+    assert!(validate_label("settings.general-2:tab_1").is_ok());
+    assert!(matches!(
+      validate_label("tauri:devtools"),
+      Err(Error::ReservedLabel(label)) if label == "tauri:devtools"
+    ));
+    #[cfg(not(feature = "lenient-labels"))]
+    {
+      assert!(matches!(
+        validate_label("my window"),
+        Err(Error::InvalidLabel(_, ' '))
+      ));
+      assert!(matches!(
+        validate_label("settings/general"),
+        Err(Error::InvalidLabel(_, '/'))
+      ));
+      assert!(matches!(
+        validate_label("fenêtre"),
+        Err(Error::InvalidLabel(_, 'ê'))
+      ));
+
+      let app = crate::test::mock_app();
+      let error = crate::WebviewWindowBuilder::new(&app, "a\"b", Default::default())
+        .build()
+        .unwrap_err();
+      assert_eq!(
+        error.to_string(),
+        r#"runtime error: invalid label "a\"b": the character '"' is not allowed, labels must only include ASCII alphanumeric characters, `-`, `_`, `:` and `.`"#
+      );
+    }
+  }
 }
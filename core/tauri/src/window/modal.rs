@@ -0,0 +1,126 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Modal windows, see [`super::WindowBuilder::modal`].
+
+use super::Window;
+use crate::{event::EventTarget, sealed::ManagerBase, Manager, Runtime, WindowEvent};
+
+/// The event emitted to the parent window when one of its modal windows is closed,
+/// with the label of the modal window as payload.
+pub const MODAL_CLOSED_EVENT: &str = "tauri://modal-closed";
+
+/// Blocks the input of the parent window until the modal window is closed,
+/// and closes the modal window with its parent.
+pub(crate) fn attach<R: Runtime>(modal: &Window<R>, parent: &Window<R>) -> crate::Result<()> {
+  {
+    let modal = modal.clone();
+    let parent = parent.clone();
+    modal.clone().run_on_main_thread(move || {
+      if let Err(e) = block_parent(&modal, &parent) {
+        tauri_utils::debug_eprintln!("failed to make the window modal: {e}");
+      }
+    })?;
+  }
+
+  let modal_ = modal.clone();
+  let parent_ = parent.clone();
+  modal.on_window_event(move |event| {
+    if let WindowEvent::Destroyed = event {
+      let parent = parent_.clone();
+      let label = modal_.label().to_string();
+      let _ = parent_.run_on_main_thread(move || {
+        release_parent(&parent);
+        let _ = parent.set_focus();
+        let _ = parent.emit_to(
+          EventTarget::labeled(parent.label()),
+          MODAL_CLOSED_EVENT,
+          label,
+        );
+      });
+    }
+  });
+
+  let modal_label = modal.label().to_string();
+  let manager = modal.manager_owned();
+  parent.on_window_event(move |event| {
+    if let WindowEvent::Destroyed = event {
+      if let Some(modal) = manager.get_window(&modal_label) {
+        let _ = modal.destroy();
+      }
+    }
+  });
+
+  Ok(())
+}
+
+/// Presents the modal window as a sheet on macOS, disables the owner window on Windows
+/// and makes the transient window modal on Linux.
+fn block_parent<R: Runtime>(modal: &Window<R>, parent: &Window<R>) -> crate::Result<()> {
+  #[cfg(target_os = "macos")]
+  unsafe {
+    use cocoa::base::id;
+    use objc::{msg_send, sel, sel_impl};
+
+    let parent = parent.ns_window()? as id;
+    let modal = modal.ns_window()? as id;
+    // kept alive until the sheet is ended, see `release_parent`
+    let _: id = msg_send![modal, retain];
+    let _: () = msg_send![parent, beginSheet: modal completionHandler: cocoa::base::nil];
+  }
+
+  #[cfg(windows)]
+  unsafe {
+    use windows::Win32::UI::Input::KeyboardAndMouse::EnableWindow;
+
+    let _ = modal;
+    EnableWindow(parent.hwnd()?, false);
+  }
+
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+  ))]
+  {
+    use gtk::prelude::GtkWindowExt;
+
+    let _ = parent;
+    modal.gtk_window()?.set_modal(true);
+  }
+
+  Ok(())
+}
+
+/// Restores the input of the parent window once the modal window is destroyed.
+fn release_parent<R: Runtime>(parent: &Window<R>) {
+  #[cfg(target_os = "macos")]
+  unsafe {
+    use cocoa::base::{id, nil};
+    use objc::{msg_send, sel, sel_impl};
+
+    if let Ok(parent) = parent.ns_window() {
+      let parent = parent as id;
+      let sheet: id = msg_send![parent, attachedSheet];
+      if sheet != nil {
+        let _: () = msg_send![parent, endSheet: sheet];
+        let _: () = msg_send![sheet, release];
+      }
+    }
+  }
+
+  #[cfg(windows)]
+  unsafe {
+    use windows::Win32::UI::Input::KeyboardAndMouse::EnableWindow;
+
+    if let Ok(hwnd) = parent.hwnd() {
+      EnableWindow(hwnd, true);
+    }
+  }
+
+  #[cfg(not(any(target_os = "macos", windows)))]
+  let _ = parent;
+}
@@ -0,0 +1,130 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Native child views of a window, see [`Window::add_native_view`].
+
+use std::fmt;
+
+use raw_window_handle::{
+  DisplayHandle, HandleError, HasDisplayHandle, HasWindowHandle, WindowHandle,
+};
+use tauri_runtime::{NativeViewId, WindowDispatch};
+
+use super::Window;
+use crate::{Position, Runtime, Size};
+
+/// A native child view of a window, created with [`Window::add_native_view`].
+///
+/// Implements [`HasWindowHandle`] and [`HasDisplayHandle`] so it can be used as a render target,
+/// for instance with `wgpu::Instance::create_surface`.
+///
+/// Dropping the handle does not remove the view, see [`Self::remove`].
+pub struct NativeViewHandle<R: Runtime> {
+  window: Window<R>,
+  id: NativeViewId,
+}
+
+impl<R: Runtime> fmt::Debug for NativeViewHandle<R> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("NativeViewHandle")
+      .field("window", &self.window.label())
+      .field("id", &self.id)
+      .finish()
+  }
+}
+
+impl<R: Runtime> Clone for NativeViewHandle<R> {
+  fn clone(&self) -> Self {
+    Self {
+      window: self.window.clone(),
+      id: self.id,
+    }
+  }
+}
+
+impl<R: Runtime> NativeViewHandle<R> {
+  pub(crate) fn new(window: Window<R>, id: NativeViewId) -> Self {
+    Self { window, id }
+  }
+
+  /// The identifier of the view, unique in the app.
+  pub fn id(&self) -> NativeViewId {
+    self.id
+  }
+
+  /// The window containing the view.
+  pub fn window(&self) -> &Window<R> {
+    &self.window
+  }
+
+  /// Sets the position and size of the view, relative to the window.
+  ///
+  /// The view is no longer kept in the slot it was added in.
+  pub fn set_bounds<Pos: Into<Position>, S: Into<Size>>(
+    &self,
+    position: Pos,
+    size: S,
+  ) -> crate::Result<()> {
+    self.window.exclude_native_view_from_layout(self.id);
+    self
+      .window
+      .window
+      .dispatcher
+      .set_native_view_bounds(self.id, position.into(), size.into())
+      .map_err(Into::into)
+  }
+
+  /// Shows the view.
+  pub fn show(&self) -> crate::Result<()> {
+    self.set_visible(true)
+  }
+
+  /// Hides the view.
+  pub fn hide(&self) -> crate::Result<()> {
+    self.set_visible(false)
+  }
+
+  fn set_visible(&self, visible: bool) -> crate::Result<()> {
+    self
+      .window
+      .window
+      .dispatcher
+      .set_native_view_visible(self.id, visible)
+      .map_err(Into::into)
+  }
+
+  /// Moves the view in the stacking order of the native views and child webviews of the window,
+  /// `0` being the bottom-most one. See [`crate::webview::Webview::set_z_order`].
+  pub fn set_z_order(&self, index: usize) -> crate::Result<()> {
+    self
+      .window
+      .window
+      .dispatcher
+      .set_native_view_z_order(self.id, index)
+      .map_err(Into::into)
+  }
+
+  /// Removes the view from the window and destroys it.
+  pub fn remove(self) -> crate::Result<()> {
+    self.window.exclude_native_view_from_layout(self.id);
+    self
+      .window
+      .window
+      .dispatcher
+      .remove_native_view(self.id)
+      .map_err(Into::into)
+  }
+}
+
+impl<R: Runtime> HasWindowHandle for NativeViewHandle<R> {
+  fn window_handle(&self) -> std::result::Result<WindowHandle<'_>, HandleError> {
+    self.window.window.dispatcher.native_view_handle(self.id)
+  }
+}
+
+impl<R: Runtime> HasDisplayHandle for NativeViewHandle<R> {
+  fn display_handle(&self) -> std::result::Result<DisplayHandle<'_>, HandleError> {
+    self.window.app_handle.display_handle()
+  }
+}
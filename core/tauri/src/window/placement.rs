@@ -0,0 +1,234 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Placement of windows on a given monitor, see [`Window::move_to_monitor`].
+
+use super::{Monitor, PhysicalRect, Window};
+use crate::{
+  runtime::{
+    window::dpi::{LogicalSize, PhysicalPosition, PhysicalSize, Position, Size},
+    RuntimeHandle,
+  },
+  Runtime,
+};
+
+/// Selects the monitor a window is placed on,
+/// see [`WindowBuilder::on_monitor`](super::WindowBuilder::on_monitor) and [`Window::move_to_monitor`].
+///
+/// When the selected monitor does not exist, the primary monitor is used instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MonitorSelector {
+  /// The primary monitor of the system.
+  Primary,
+  /// The monitor at the given index of [`Window::available_monitors`].
+  Index(usize),
+  /// The monitor the cursor is currently on.
+  ContainingCursor,
+  /// The monitor with the given name, see [`Monitor::name`].
+  Name(String),
+}
+
+/// Finds the monitor matching the selector, falling back to the primary monitor,
+/// or the first available one if the primary monitor is unknown.
+fn select_monitor(
+  selector: &MonitorSelector,
+  monitors: Vec<Monitor>,
+  primary: Option<Monitor>,
+  cursor: Option<PhysicalPosition<f64>>,
+) -> Option<Monitor> {
+  let found = match selector {
+    MonitorSelector::Primary => return primary.or_else(|| monitors.into_iter().next()),
+    MonitorSelector::Index(index) => monitors.get(*index),
+    MonitorSelector::ContainingCursor => cursor.and_then(|cursor| {
+      monitors.iter().find(|monitor| {
+        let (x, y) = (monitor.position.x as f64, monitor.position.y as f64);
+        (x..x + monitor.size.width as f64).contains(&cursor.x)
+          && (y..y + monitor.size.height as f64).contains(&cursor.y)
+      })
+    }),
+    MonitorSelector::Name(name) => monitors
+      .iter()
+      .find(|monitor| monitor.name.as_ref() == Some(name)),
+  };
+
+  match found {
+    Some(monitor) => Some(monitor.clone()),
+    None => {
+      tauri_utils::debug_eprintln!(
+        "monitor {:?} not found, falling back to the primary monitor",
+        selector
+      );
+      primary.or_else(|| monitors.into_iter().next())
+    }
+  }
+}
+
+/// Centers a window of the given logical size in the work area of the monitor,
+/// shrinking it to fit the work area.
+fn place(size: LogicalSize<f64>, monitor: &Monitor) -> PhysicalRect {
+  let work_area = monitor.work_area;
+  let size: PhysicalSize<u32> = size.to_physical(monitor.scale_factor);
+  let size = PhysicalSize::new(
+    size.width.min(work_area.size.width),
+    size.height.min(work_area.size.height),
+  );
+  PhysicalRect {
+    position: PhysicalPosition::new(
+      work_area.position.x + ((work_area.size.width - size.width) / 2) as i32,
+      work_area.position.y + ((work_area.size.height - size.height) / 2) as i32,
+    ),
+    size,
+  }
+}
+
+impl<R: Runtime> Window<R> {
+  /// Moves the window to the center of the work area of a monitor,
+  /// keeping its logical size and its maximized state.
+  ///
+  /// The window is shrunk if it does not fit the work area of the monitor.
+  /// When the selected monitor does not exist, the primary monitor is used instead.
+  pub fn move_to_monitor(&self, selector: MonitorSelector) -> crate::Result<()> {
+    let cursor = match selector {
+      MonitorSelector::ContainingCursor => self.app_handle.runtime_handle.cursor_position().ok(),
+      _ => None,
+    };
+    let Some(monitor) = select_monitor(
+      &selector,
+      self.available_monitors()?,
+      self.primary_monitor()?,
+      cursor,
+    ) else {
+      return Ok(());
+    };
+
+    let maximized = self.is_maximized()?;
+    if maximized {
+      self.unmaximize()?;
+    }
+
+    let size = self.inner_size()?.to_logical(self.scale_factor()?);
+    let rect = place(size, &monitor);
+    self.set_position(Position::Physical(rect.position))?;
+    // the window might have been resized when moved to a monitor with a different scale factor
+    self.set_size(Size::Physical(rect.size))?;
+
+    if maximized {
+      self.maximize()?;
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn monitor(name: &str, x: i32, width: u32, scale_factor: f64) -> Monitor {
+    let position = PhysicalPosition::new(x, 0);
+    let size = PhysicalSize::new(width, 1000);
+    Monitor {
+      name: Some(name.into()),
+      position,
+      size,
+      scale_factor,
+      work_area: PhysicalRect { position, size },
+    }
+  }
+
+  fn monitors() -> Vec<Monitor> {
+    vec![
+      monitor("left", 0, 1000, 1.0),
+      monitor("right", 1000, 2000, 2.0),
+    ]
+  }
+
+  fn select(selector: MonitorSelector, cursor: Option<(f64, f64)>) -> Option<String> {
+    select_monitor(
+      &selector,
+      monitors(),
+      Some(monitor("left", 0, 1000, 1.0)),
+      cursor.map(|(x, y)| PhysicalPosition::new(x, y)),
+    )
+    .and_then(|monitor| monitor.name)
+  }
+
+  #[test]
+  fn selects_monitor() {
+    assert_eq!(select(MonitorSelector::Primary, None).unwrap(), "left");
+    assert_eq!(select(MonitorSelector::Index(1), None).unwrap(), "right");
+    assert_eq!(
+      select(MonitorSelector::Name("right".into()), None).unwrap(),
+      "right"
+    );
+    assert_eq!(
+      select(MonitorSelector::ContainingCursor, Some((1500.0, 10.0))).unwrap(),
+      "right"
+    );
+    assert_eq!(
+      select(MonitorSelector::ContainingCursor, Some((999.5, 10.0))).unwrap(),
+      "left"
+    );
+  }
+
+  #[test]
+  fn falls_back_to_primary() {
+    assert_eq!(select(MonitorSelector::Index(2), None).unwrap(), "left");
+    assert_eq!(
+      select(MonitorSelector::Name("missing".into()), None).unwrap(),
+      "left"
+    );
+    assert_eq!(
+      select(MonitorSelector::ContainingCursor, None).unwrap(),
+      "left"
+    );
+    assert_eq!(
+      select(MonitorSelector::ContainingCursor, Some((-10.0, 10.0))).unwrap(),
+      "left"
+    );
+
+    // without a primary monitor the first one is used
+    let first = select_monitor(&MonitorSelector::Index(2), monitors(), None, None);
+    assert_eq!(first.unwrap().name.unwrap(), "left");
+    assert!(select_monitor(&MonitorSelector::Primary, Vec::new(), None, None).is_none());
+  }
+
+  #[test]
+  fn keeps_logical_size() {
+    let size = LogicalSize::new(400.0, 300.0);
+
+    let [left, right] = <[Monitor; 2]>::try_from(monitors()).unwrap();
+    assert_eq!(
+      place(size, &left),
+      PhysicalRect {
+        position: PhysicalPosition::new(300, 350),
+        size: PhysicalSize::new(400, 300),
+      }
+    );
+    assert_eq!(
+      place(size, &right),
+      PhysicalRect {
+        position: PhysicalPosition::new(1600, 200),
+        size: PhysicalSize::new(800, 600),
+      }
+    );
+  }
+
+  #[test]
+  fn fits_work_area() {
+    let mut monitor = monitor("taskbar", 0, 1000, 2.0);
+    monitor.work_area = PhysicalRect {
+      position: PhysicalPosition::new(0, 50),
+      size: PhysicalSize::new(1000, 900),
+    };
+
+    assert_eq!(
+      place(LogicalSize::new(400.0, 600.0), &monitor),
+      PhysicalRect {
+        position: PhysicalPosition::new(100, 50),
+        size: PhysicalSize::new(800, 900),
+      }
+    );
+  }
+}
@@ -0,0 +1,250 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Positioning of windows relative to their parent window, see [`super::WindowBuilder::position_relative`].
+
+use super::{PhysicalRect, Window};
+use crate::{
+  runtime::window::dpi::{LogicalPosition, PhysicalPosition, PhysicalSize, Position},
+  sealed::ManagerBase,
+  Runtime, WindowEvent,
+};
+
+/// An edge of the parent window, see [`RelativePosition::Anchored`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edge {
+  /// Above the parent window, horizontally centered on it.
+  Top,
+  /// Below the parent window, horizontally centered on it.
+  Bottom,
+  /// On the left of the parent window, vertically centered on it.
+  Left,
+  /// On the right of the parent window, vertically centered on it.
+  Right,
+}
+
+/// The position of a window relative to its parent window,
+/// see [`super::WindowBuilder::position_relative`] and [`Window::reposition_relative`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RelativePosition {
+  /// Centered on the parent window.
+  CenterOnParent,
+  /// Next to an edge of the parent window, outside of it.
+  Anchored {
+    /// The edge of the parent window the window is placed against.
+    anchor: Edge,
+    /// The offset from the anchored position, in the logical pixels of the parent window.
+    offset: LogicalPosition<f64>,
+  },
+}
+
+/// Places a window of the given outer size relative to the outer bounds of its parent,
+/// clamped into the work area of the parent's monitor when it is known.
+fn place(
+  position: RelativePosition,
+  parent: PhysicalRect,
+  size: PhysicalSize<u32>,
+  scale_factor: f64,
+  work_area: Option<PhysicalRect>,
+) -> PhysicalPosition<i32> {
+  let (px, py) = (parent.position.x, parent.position.y);
+  let (pw, ph) = (parent.size.width as i32, parent.size.height as i32);
+  let (w, h) = (size.width as i32, size.height as i32);
+  let center_x = px + (pw - w) / 2;
+  let center_y = py + (ph - h) / 2;
+
+  let (x, y) = match position {
+    RelativePosition::CenterOnParent => (center_x, center_y),
+    RelativePosition::Anchored { anchor, offset } => {
+      let offset: PhysicalPosition<i32> = offset.to_physical(scale_factor);
+      let (x, y) = match anchor {
+        Edge::Top => (center_x, py - h),
+        Edge::Bottom => (center_x, py + ph),
+        Edge::Left => (px - w, center_y),
+        Edge::Right => (px + pw, center_y),
+      };
+      (x + offset.x, y + offset.y)
+    }
+  };
+
+  match work_area {
+    Some(area) => {
+      let clamp = |value: i32, start: i32, length: u32, size: i32| {
+        // a window larger than the work area is aligned to its start
+        value.min(start + length as i32 - size).max(start)
+      };
+      PhysicalPosition::new(
+        clamp(x, area.position.x, area.size.width, w),
+        clamp(y, area.position.y, area.size.height, h),
+      )
+    }
+    None => PhysicalPosition::new(x, y),
+  }
+}
+
+/// Closes the window with its parent, or forgets the parent when `close` is `false`.
+pub(crate) fn attach<R: Runtime>(window: &Window<R>, parent: &Window<R>, close: bool) {
+  let label = window.label().to_string();
+  let manager = window.manager_owned();
+  parent.on_window_event(move |event| {
+    if let WindowEvent::Destroyed = event {
+      manager.window.parents.lock().unwrap().remove(&label);
+      if close {
+        if let Some(window) = manager.get_window(&label) {
+          let _ = window.destroy();
+        }
+      }
+    }
+  });
+}
+
+impl<R: Runtime> Window<R> {
+  /// The window this window was created with as [`super::WindowBuilder::parent`], if it is still open.
+  pub fn parent_window(&self) -> Option<Window<R>> {
+    let manager = self.manager();
+    let parent = manager
+      .window
+      .parents
+      .lock()
+      .unwrap()
+      .get(self.label())
+      .cloned()?;
+    manager.get_window(&parent)
+  }
+
+  /// Centers the window on its parent window, see [`Self::reposition_relative`].
+  pub fn center_on_parent(&self) -> crate::Result<()> {
+    self.reposition_relative(RelativePosition::CenterOnParent)
+  }
+
+  /// Moves the window relative to the window it was created with as [`super::WindowBuilder::parent`].
+  ///
+  /// The window is kept in the work area of the monitor of its parent.
+  /// Fails with [`crate::Error::NoParentWindow`] if the window has no parent or its parent is closed.
+  pub fn reposition_relative(&self, position: RelativePosition) -> crate::Result<()> {
+    let parent = self
+      .parent_window()
+      .ok_or_else(|| crate::Error::NoParentWindow(self.label().into()))?;
+    self.position_relative_to(&parent, position)
+  }
+
+  pub(crate) fn position_relative_to(
+    &self,
+    parent: &Window<R>,
+    position: RelativePosition,
+  ) -> crate::Result<()> {
+    let parent_rect = PhysicalRect {
+      position: parent.outer_position()?,
+      size: parent.outer_size()?,
+    };
+    let work_area = parent.current_monitor()?.map(|monitor| monitor.work_area);
+    let position = place(
+      position,
+      parent_rect,
+      self.outer_size()?,
+      parent.scale_factor()?,
+      work_area,
+    );
+    self.set_position(Position::Physical(position))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn rect(x: i32, y: i32, width: u32, height: u32) -> PhysicalRect {
+    PhysicalRect {
+      position: PhysicalPosition::new(x, y),
+      size: PhysicalSize::new(width, height),
+    }
+  }
+
+  fn anchored(anchor: Edge, x: f64, y: f64) -> RelativePosition {
+    RelativePosition::Anchored {
+      anchor,
+      offset: LogicalPosition::new(x, y),
+    }
+  }
+
+  #[test]
+  fn places_relative_to_parent() {
+    let parent = rect(100, 100, 800, 600);
+    let size = PhysicalSize::new(200, 100);
+    let place = |position| place(position, parent, size, 1.0, None);
+
+    assert_eq!(
+      place(RelativePosition::CenterOnParent),
+      PhysicalPosition::new(400, 350)
+    );
+    assert_eq!(
+      place(anchored(Edge::Top, 0.0, 0.0)),
+      PhysicalPosition::new(400, 0)
+    );
+    assert_eq!(
+      place(anchored(Edge::Bottom, 0.0, 8.0)),
+      PhysicalPosition::new(400, 708)
+    );
+    assert_eq!(
+      place(anchored(Edge::Left, -8.0, 0.0)),
+      PhysicalPosition::new(-108, 350)
+    );
+    assert_eq!(
+      place(anchored(Edge::Right, 8.0, -50.0)),
+      PhysicalPosition::new(908, 300)
+    );
+  }
+
+  #[test]
+  fn scales_offset() {
+    let position = place(
+      anchored(Edge::Bottom, 10.0, 4.0),
+      rect(0, 0, 800, 600),
+      PhysicalSize::new(200, 100),
+      2.0,
+      None,
+    );
+    assert_eq!(position, PhysicalPosition::new(320, 608));
+  }
+
+  #[test]
+  fn clamps_into_work_area() {
+    let work_area = Some(rect(0, 0, 1920, 1040));
+    let size = PhysicalSize::new(400, 300);
+
+    // below a parent at the bottom of the screen
+    assert_eq!(
+      place(
+        anchored(Edge::Bottom, 0.0, 0.0),
+        rect(100, 800, 800, 200),
+        size,
+        1.0,
+        work_area
+      ),
+      PhysicalPosition::new(300, 740)
+    );
+    // left of a parent at the left of the screen
+    assert_eq!(
+      place(
+        anchored(Edge::Left, 0.0, 0.0),
+        rect(0, 100, 800, 600),
+        size,
+        1.0,
+        work_area
+      ),
+      PhysicalPosition::new(0, 250)
+    );
+    // larger than the work area
+    assert_eq!(
+      place(
+        RelativePosition::CenterOnParent,
+        rect(0, 0, 800, 600),
+        PhysicalSize::new(2000, 300),
+        1.0,
+        work_area
+      ),
+      PhysicalPosition::new(0, 150)
+    );
+  }
+}
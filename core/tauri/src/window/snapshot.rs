@@ -0,0 +1,308 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Snapshots of the live windows as [`WindowConfig`]s, to save and restore the window layout of the app.
+
+use url::{Position, Url};
+
+use super::Window;
+use crate::{
+  ipc::origin::{is_same_origin, protocol_origin},
+  manager::AppManager,
+  sealed::ManagerBase,
+  utils::config::{WebviewBounds, WebviewConfig, WebviewDimension, WebviewUrl, WindowConfig},
+  AppHandle, Runtime, Webview,
+};
+
+/// Maps a URL loaded by a webview back to the [`WebviewUrl`] that loads it.
+///
+/// Pages of the app (the `devUrl`, the `frontendDist` URL or the `tauri` protocol) are mapped to [`WebviewUrl::App`],
+/// pages of the registered custom protocols to [`WebviewUrl::CustomProtocol`] and everything else to [`WebviewUrl::External`].
+fn webview_url<R: Runtime>(manager: &AppManager<R>, url: &Url) -> WebviewUrl {
+  let mut app_urls = vec![
+    manager.get_url().into_owned(),
+    manager.protocol_url().into_owned(),
+  ];
+  if manager.proxy_dev_server() {
+    app_urls.push(manager.proxy_url().into_owned());
+  }
+  for base in &app_urls {
+    if let Some(path) = relative_path(base, url) {
+      // an empty path loads the index, see `WebviewUrl::App`
+      let path = if path.is_empty() { "index.html" } else { path };
+      return WebviewUrl::App(path.into());
+    }
+  }
+
+  let schemes = manager
+    .webview
+    .uri_scheme_protocols
+    .lock()
+    .unwrap()
+    .keys()
+    .cloned()
+    .collect::<Vec<_>>();
+  for scheme in schemes {
+    if let Some(origin) = protocol_origin(&scheme) {
+      if let Some(path) = relative_path(&origin, url) {
+        return WebviewUrl::CustomProtocol {
+          scheme,
+          path: path.into(),
+        };
+      }
+    }
+  }
+
+  WebviewUrl::External(url.clone())
+}
+
+/// The part of `url` after the directory of `base`, including its query and fragment,
+/// or `None` if `url` is not under `base`.
+fn relative_path<'a>(base: &Url, url: &'a Url) -> Option<&'a str> {
+  if !is_same_origin(base, url) {
+    return None;
+  }
+  let directory = match base.path().rfind('/') {
+    Some(end) => &base.path()[..=end],
+    // custom scheme URLs such as `tauri://localhost` have an empty path
+    None => "",
+  };
+  url[Position::BeforePath..]
+    .strip_prefix(directory)
+    .map(|path| path.trim_start_matches('/'))
+}
+
+impl<R: Runtime> Webview<R> {
+  /// Captures the live state of the webview as a [`WebviewConfig`] for [`WindowConfig::webviews`].
+  ///
+  /// The current URL is mapped back to a [`WebviewUrl`] and the bounds are the current position and size in logical pixels.
+  /// The properties that cannot be read fall back to their configuration defaults.
+  pub fn to_config_parts(&self) -> WebviewConfig {
+    let manager = self.manager();
+    let url = self
+      .url()
+      .map(|url| webview_url(manager, &url))
+      .unwrap_or_default();
+
+    let bounds = match (self.position(), self.size(), self.window().scale_factor()) {
+      (Ok(position), Ok(size), Ok(scale_factor)) => {
+        let position = position.to_logical::<f64>(scale_factor);
+        let size = size.to_logical::<f64>(scale_factor);
+        WebviewBounds {
+          x: WebviewDimension::Logical(position.x),
+          y: WebviewDimension::Logical(position.y),
+          width: WebviewDimension::Logical(size.width),
+          height: WebviewDimension::Logical(size.height),
+        }
+      }
+      _ => WebviewBounds::default(),
+    };
+
+    WebviewConfig {
+      label: self.label().into(),
+      url,
+      bounds,
+      user_agent: None,
+      file_drop_enabled: true,
+      focus: false,
+      transparent: false,
+      accept_first_mouse: false,
+      additional_browser_args: None,
+      incognito: false,
+      proxy_url: None,
+      allowlist: None,
+      background_throttling: None,
+      spellcheck: None,
+      effects: None,
+    }
+  }
+}
+
+impl<R: Runtime> Window<R> {
+  /// Captures the live state of the window as a [`WindowConfig`], to restore it later with
+  /// [`crate::WebviewWindowBuilder::from_config`] or in the `app > windows` configuration.
+  ///
+  /// The label, title, logical inner size and outer position, decorations, always on top, theme
+  /// and the fullscreen, maximized, visible, resizable, maximizable, minimizable and closable states are captured.
+  /// The URL of a webview window is set on [`WindowConfig::url`],
+  /// the webviews of other windows are listed in [`WindowConfig::webviews`], see [`Webview::to_config_parts`].
+  ///
+  /// The properties that cannot be read on the current platform fall back to their [`WindowConfig::default`] values.
+  pub fn to_config(&self) -> WindowConfig {
+    let defaults = WindowConfig::default();
+    let scale_factor = self.scale_factor().unwrap_or(1.0);
+    let size = self
+      .inner_size()
+      .map(|size| size.to_logical::<f64>(scale_factor))
+      .ok();
+    let position = self
+      .outer_position()
+      .map(|position| position.to_logical::<f64>(scale_factor))
+      .ok();
+
+    let mut config = WindowConfig {
+      label: self.label().into(),
+      x: position.map(|position| position.x),
+      y: position.map(|position| position.y),
+      width: size.map(|size| size.width).unwrap_or(defaults.width),
+      height: size.map(|size| size.height).unwrap_or(defaults.height),
+      title: self.title().unwrap_or(defaults.title),
+      fullscreen: self.is_fullscreen().unwrap_or(defaults.fullscreen),
+      maximized: self.is_maximized().unwrap_or(defaults.maximized),
+      visible: self.is_visible().unwrap_or(defaults.visible),
+      decorations: self.is_decorated().unwrap_or(defaults.decorations),
+      resizable: self.is_resizable().unwrap_or(defaults.resizable),
+      maximizable: self.is_maximizable().unwrap_or(defaults.maximizable),
+      minimizable: self.is_minimizable().unwrap_or(defaults.minimizable),
+      closable: self.is_closable().unwrap_or(defaults.closable),
+      always_on_top: self
+        .manager
+        .window
+        .always_on_top
+        .lock()
+        .unwrap()
+        .contains(self.label()),
+      theme: self.theme().ok().or(defaults.theme),
+      ..defaults
+    };
+
+    #[cfg(desktop)]
+    {
+      config.parent = self.parent_window().map(|parent| parent.label().into());
+    }
+
+    let webviews = self.webviews();
+    if self.webview_window {
+      if let Some(webview) = webviews.first() {
+        config.url = webview.to_config_parts().url;
+      }
+    } else {
+      config.webviews = webviews
+        .iter()
+        .map(|webview| webview.to_config_parts())
+        .collect();
+      config.webviews.sort_by(|a, b| a.label.cmp(&b.label));
+    }
+
+    config
+  }
+}
+
+impl<R: Runtime> AppHandle<R> {
+  /// Captures the live state of all windows, sorted by label, see [`Window::to_config`].
+  pub fn windows_to_config(&self) -> Vec<WindowConfig> {
+    let mut windows = self
+      .manager()
+      .windows()
+      .values()
+      .map(Window::to_config)
+      .collect::<Vec<_>>();
+    windows.sort_by(|a, b| a.label.cmp(&b.label));
+    windows
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::relative_path;
+  use crate::{
+    test::{mock_builder, mock_context, noop_assets},
+    utils::config::{WebviewDimension, WebviewUrl},
+    Manager, WebviewWindowBuilder,
+  };
+  use url::Url;
+
+  #[test]
+  fn relative_paths() {
+    let url = |url: &str| Url::parse(url).unwrap();
+    let base = url("http://localhost:1420/app/index.html");
+
+    assert_eq!(
+      relative_path(
+        &base,
+        &url("http://localhost:1420/app/users/john?tab=1#top")
+      ),
+      Some("users/john?tab=1#top")
+    );
+    assert_eq!(
+      relative_path(&base, &url("http://localhost:1420/app/")),
+      Some("")
+    );
+    // outside of the base directory
+    assert_eq!(
+      relative_path(&base, &url("http://localhost:1420/other")),
+      None
+    );
+    // another origin
+    assert_eq!(
+      relative_path(&base, &url("http://localhost:5173/app/index.html")),
+      None
+    );
+    assert_eq!(
+      relative_path(
+        &url("tauri://localhost"),
+        &url("tauri://localhost/settings.html")
+      ),
+      Some("settings.html")
+    );
+  }
+
+  #[test]
+  fn maps_urls_back() {
+    let app = mock_builder()
+      .register_uri_scheme_protocol("doom", |_, _| {
+        http::Response::builder().body(Vec::new()).unwrap()
+      })
+      .build(mock_context(noop_assets()))
+      .unwrap();
+
+    let urls = [
+      ("app", WebviewUrl::App("settings.html".into())),
+      ("index", WebviewUrl::App("index.html".into())),
+      (
+        "external",
+        WebviewUrl::External("https://tauri.app/docs?page=1".parse().unwrap()),
+      ),
+      (
+        "protocol",
+        WebviewUrl::CustomProtocol {
+          scheme: "doom".into(),
+          path: "levels/e1m1".into(),
+        },
+      ),
+    ];
+    for (label, url) in &urls {
+      WebviewWindowBuilder::new(&app, *label, url.clone())
+        .always_on_top(*label == "app")
+        .build()
+        .unwrap();
+    }
+
+    let configs = app.handle().windows_to_config();
+    assert_eq!(
+      configs
+        .iter()
+        .map(|config| config.label.as_str())
+        .collect::<Vec<_>>(),
+      ["app", "external", "index", "protocol"]
+    );
+    for config in &configs {
+      let (_, url) = urls
+        .iter()
+        .find(|(label, _)| *label == config.label)
+        .unwrap();
+      assert_eq!(&config.url, url);
+      assert!(config.webviews.is_empty());
+      assert_eq!(config.always_on_top, config.label == "app");
+    }
+
+    let window = app.get_webview_window("app").unwrap();
+    window.set_always_on_top(false).unwrap();
+    assert!(!window.as_ref().window().to_config().always_on_top);
+
+    let parts = window.as_ref().to_config_parts();
+    assert_eq!(parts.label, "app");
+    assert_eq!(parts.bounds.x, WebviewDimension::Logical(0.0));
+  }
+}
@@ -0,0 +1,98 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::sync::mpsc::channel;
+use std::time::Duration;
+
+use serde_json::json;
+use tauri::{
+  automation::{AutomationCommand, AutomationEndpoint, AutomationError, CommandResponse},
+  ipc::{CallbackFn, InvokeBody},
+  test::{get_ipc_response, mock_builder, mock_context, noop_assets, AutomationClient},
+  webview::InvokeRequest,
+  EventTarget, Manager, WebviewWindowBuilder,
+};
+
+#[tauri::command]
+fn ping(value: u32) -> u32 {
+  value + 1
+}
+
+#[test]
+fn drives_webviews() {
+  let app = mock_builder()
+    .invoke_handler(tauri::generate_handler![ping])
+    .build(mock_context(noop_assets()))
+    .unwrap();
+  let webview = WebviewWindowBuilder::new(&app, "main", Default::default())
+    .build()
+    .unwrap();
+
+  // the frontend calls the IPC once it loads, handing out the invoke key
+  let response = get_ipc_response(
+    &webview,
+    InvokeRequest {
+      cmd: "ping".into(),
+      callback: CallbackFn(0),
+      error: CallbackFn(1),
+      body: InvokeBody::Json(json!({ "value": 0 })),
+      headers: Default::default(),
+    },
+  )
+  .unwrap();
+  assert_eq!(response.deserialize::<u32>().unwrap(), 1);
+
+  let mut client = AutomationClient::connect(&app.state::<AutomationEndpoint>()).unwrap();
+
+  match client.send(AutomationCommand::ListWebviews).unwrap() {
+    CommandResponse::ListWebviews { webviews } => {
+      assert_eq!(webviews.len(), 1);
+      assert_eq!(webviews[0].label, "main");
+      assert_eq!(webviews[0].window, "main");
+    }
+    response => panic!("unexpected response {response:?}"),
+  }
+
+  match client
+    .send(AutomationCommand::Invoke {
+      webview: "main".into(),
+      cmd: "ping".into(),
+      args: json!({ "value": 41 }),
+    })
+    .unwrap()
+  {
+    CommandResponse::Invoke { response } => assert_eq!(response, json!(42)),
+    response => panic!("unexpected response {response:?}"),
+  }
+
+  let (tx, rx) = channel();
+  webview.listen("automation", move |event| {
+    let _ = tx.send(event.payload().to_string());
+  });
+  client
+    .send(AutomationCommand::Emit {
+      event: "automation".into(),
+      payload: json!("hello"),
+      target: Some(EventTarget::webview_window("main")),
+    })
+    .unwrap();
+  assert_eq!(
+    rx.recv_timeout(Duration::from_secs(5)).unwrap(),
+    "\"hello\""
+  );
+
+  assert!(matches!(
+    client.send(AutomationCommand::Screenshot {
+      webview: "main".into()
+    }),
+    Err(AutomationError::Unsupported)
+  ));
+  assert!(matches!(
+    client.send(AutomationCommand::Eval {
+      webview: "missing".into(),
+      script: "return 1".into(),
+    }),
+    Err(AutomationError::WebviewNotFound { label }) if label == "missing"
+  ));
+}
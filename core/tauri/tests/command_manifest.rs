@@ -0,0 +1,143 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use serde_json::json;
+use tauri::{
+  ipc::CommandArgSchema,
+  test::{mock_builder, mock_context, noop_assets, MockRuntime},
+  AppHandle, Runtime, State, Webview,
+};
+
+#[derive(serde::Deserialize)]
+struct User {
+  #[allow(dead_code)]
+  name: String,
+}
+
+impl CommandArgSchema for User {
+  fn schema() -> serde_json::Value {
+    json!({
+      "type": "object",
+      "properties": { "name": String::schema() },
+      "required": ["name"],
+    })
+  }
+}
+
+#[derive(serde::Deserialize)]
+struct Settings {}
+
+struct Counter;
+
+#[tauri::command]
+fn greet(name: &str, times: Option<u32>) -> String {
+  name.repeat(times.unwrap_or(1) as usize)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn save_user<R: Runtime>(
+  _app: AppHandle<R>,
+  new_user: User,
+  _counter: State<'_, Counter>,
+) -> Result<(), String> {
+  let _ = new_user;
+  Ok(())
+}
+
+#[tauri::command]
+fn update_settings(_webview: Webview<MockRuntime>, settings: Settings, ids: Vec<u64>) {
+  let _ = (settings, ids);
+}
+
+mod commands {
+  #[tauri::command]
+  pub fn imported(count: u32) -> u32 {
+    count
+  }
+}
+
+use commands::imported;
+
+#[test]
+fn records_imported_commands() {
+  let app = mock_builder()
+    .invoke_handler(tauri::generate_handler![imported, commands::imported])
+    .build(mock_context(noop_assets()))
+    .unwrap();
+
+  let imported = json!({
+    "name": "imported",
+    "args": [{ "name": "count", "type": "u32", "schema": { "type": "integer" } }]
+  });
+  assert_eq!(
+    app.handle().command_manifest(),
+    json!({ "commands": [imported, imported] })
+  );
+}
+
+#[test]
+fn records_commands() {
+  let app = mock_builder()
+    .invoke_handler(tauri::generate_handler![greet, save_user, update_settings])
+    .build(mock_context(noop_assets()))
+    .unwrap();
+
+  assert_eq!(
+    app.handle().command_manifest(),
+    json!({
+      "commands": [
+        {
+          "name": "greet",
+          "args": [
+            { "name": "name", "type": "&str", "schema": { "type": "string" } },
+            {
+              "name": "times",
+              "type": "Option<u32>",
+              "schema": { "anyOf": [{ "type": "integer" }, { "type": "null" }] }
+            }
+          ]
+        },
+        {
+          "name": "save_user",
+          "args": [
+            { "name": "new_user", "type": "User", "schema": User::schema() }
+          ]
+        },
+        {
+          "name": "update_settings",
+          "args": [
+            { "name": "settings", "type": "Settings", "schema": null },
+            {
+              "name": "ids",
+              "type": "Vec<u64>",
+              "schema": { "type": "array", "items": { "type": "integer" } }
+            }
+          ]
+        }
+      ]
+    })
+  );
+
+  // handlers that are not generated with the macro have no commands
+  let app = mock_builder()
+    .invoke_handler(|_| false)
+    .build(mock_context(noop_assets()))
+    .unwrap();
+  assert_eq!(app.handle().command_manifest(), json!({ "commands": [] }));
+}
+
+#[test]
+fn exports_manifest() {
+  let path = std::env::temp_dir().join(format!("tauri-commands-{}.json", std::process::id()));
+  std::env::set_var("TAURI_EXPORT_COMMANDS", &path);
+  let app = mock_builder()
+    .invoke_handler(tauri::generate_handler![greet])
+    .build(mock_context(noop_assets()))
+    .unwrap();
+  std::env::remove_var("TAURI_EXPORT_COMMANDS");
+
+  let exported: serde_json::Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+  let _ = std::fs::remove_file(&path);
+  assert_eq!(exported, app.handle().command_manifest());
+}
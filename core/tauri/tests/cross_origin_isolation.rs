@@ -0,0 +1,80 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Loads a page with `app > security > crossOriginIsolation` enabled in a real webview
+//! and checks that it is cross-origin isolated, which is required to use `SharedArrayBuffer`.
+//!
+//! The test needs a display, so it only runs when the `TAURI_WEBVIEW_TESTS` environment variable is set,
+//! e.g. `TAURI_WEBVIEW_TESTS=1 xvfb-run cargo test -p tauri --features test --test cross_origin_isolation` on Linux.
+
+use std::{borrow::Cow, time::Duration};
+
+use tauri::{
+  test::mock_context,
+  utils::assets::{AssetKey, Assets, CspHash},
+  webview::PageLoadEvent,
+  Manager, WebviewUrl, WebviewWindowBuilder,
+};
+
+const INDEX: &[u8] = b"<!DOCTYPE html><html><body>cross-origin isolation</body></html>";
+
+struct Page;
+
+impl Assets for Page {
+  fn get(&self, key: &AssetKey) -> Option<Cow<'_, [u8]>> {
+    (key.as_ref() == "/index.html").then_some(Cow::Borrowed(INDEX))
+  }
+
+  fn iter(&self) -> Box<dyn Iterator<Item = (&&str, &&[u8])> + '_> {
+    Box::new(std::iter::empty())
+  }
+
+  fn csp_hashes(&self, _html_path: &AssetKey) -> Box<dyn Iterator<Item = CspHash<'_>> + '_> {
+    Box::new(std::iter::empty())
+  }
+}
+
+fn main() {
+  if std::env::var_os("TAURI_WEBVIEW_TESTS").is_none() {
+    println!("skipping cross_origin_isolation, set TAURI_WEBVIEW_TESTS to run it");
+    return;
+  }
+
+  let mut context = mock_context(Page);
+  context.config_mut().app.security.cross_origin_isolation = true;
+  let app = tauri::Builder::default()
+    .build(context)
+    .expect("failed to build the app");
+
+  WebviewWindowBuilder::new(&app, "main", WebviewUrl::App("index.html".into()))
+    .visible(false)
+    .on_page_load(|window, payload| {
+      if payload.event() != PageLoadEvent::Finished {
+        return;
+      }
+      tauri::async_runtime::spawn(async move {
+        let isolated = window
+          .as_ref()
+          .eval_isolated_with_result::<bool>("test", "return crossOriginIsolated;")
+          .await;
+        if let Ok(true) = isolated {
+          println!("test cross_origin_isolation ... ok");
+          window.app_handle().exit(0);
+        } else {
+          eprintln!("test cross_origin_isolation ... FAILED: crossOriginIsolated is {isolated:?}");
+          window.app_handle().exit(1);
+        }
+      });
+    })
+    .build()
+    .expect("failed to create the window");
+
+  std::thread::spawn(|| {
+    std::thread::sleep(Duration::from_secs(60));
+    eprintln!("test cross_origin_isolation ... FAILED: the page did not load");
+    std::process::exit(1);
+  });
+
+  app.run(|_, _| {});
+}
@@ -0,0 +1,126 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Loads a page with a strict Content Security Policy (`script-src 'self'`) in a real webview
+//! and checks that a listener receives an event emitted with [`EventTransport::Bridge`].
+//!
+//! The test needs a display, so it only runs when the `TAURI_WEBVIEW_TESTS` environment variable is set,
+//! e.g. `TAURI_WEBVIEW_TESTS=1 xvfb-run cargo test -p tauri --features test --test event_bridge_csp` on Linux.
+
+use std::{borrow::Cow, time::Duration};
+
+use tauri::{
+  ipc::{ScopePolicy, ScopeRequest},
+  test::mock_context,
+  utils::{
+    acl::resolved::ResolvedCommand,
+    assets::{AssetKey, Assets, CspHash},
+  },
+  AppHandle, EventTransport, Manager, Runtime, WebviewUrl, WebviewWindowBuilder,
+};
+
+const INDEX: &[u8] = br#"<!DOCTYPE html>
+<html>
+  <head>
+    <meta http-equiv="Content-Security-Policy" content="script-src 'self'" />
+    <script src="main.js"></script>
+  </head>
+  <body>event bridge</body>
+</html>"#;
+
+const MAIN: &[u8] = br#"
+let evalBlocked = false
+try {
+  eval('1')
+} catch {
+  evalBlocked = true
+}
+window.__TAURI__.event
+  .listen('ping', (event) =>
+    window.__TAURI__.core.invoke('received', { payload: event.payload })
+  )
+  .then(() => window.__TAURI__.core.invoke('ready', { evalBlocked }))
+"#;
+
+struct Page;
+
+impl Assets for Page {
+  fn get(&self, key: &AssetKey) -> Option<Cow<'_, [u8]>> {
+    match key.as_ref() {
+      "/index.html" => Some(Cow::Borrowed(INDEX)),
+      "/main.js" => Some(Cow::Borrowed(MAIN)),
+      _ => None,
+    }
+  }
+
+  fn iter(&self) -> Box<dyn Iterator<Item = (&&str, &&[u8])> + '_> {
+    Box::new(std::iter::empty())
+  }
+
+  fn csp_hashes(&self, _html_path: &AssetKey) -> Box<dyn Iterator<Item = CspHash<'_>> + '_> {
+    Box::new(std::iter::empty())
+  }
+}
+
+/// Allows the event plugin commands, which the mock context has no capability for.
+struct AllowAll;
+
+impl<R: Runtime> ScopePolicy<R> for AllowAll {
+  fn check(&self, _request: &ScopeRequest<'_, R>) -> Result<Option<ResolvedCommand>, String> {
+    Ok(None)
+  }
+}
+
+fn fail(app: &AppHandle, message: &str) {
+  eprintln!("test event_bridge_csp ... FAILED: {message}");
+  app.exit(1);
+}
+
+#[tauri::command]
+fn ready(app: AppHandle, eval_blocked: bool) {
+  if !eval_blocked {
+    fail(&app, "the Content Security Policy allows eval");
+  } else if let Err(e) = app.emit("ping", "hello") {
+    fail(&app, &format!("failed to emit the event: {e}"));
+  }
+}
+
+#[tauri::command]
+fn received(app: AppHandle, payload: String) {
+  if payload == "hello" {
+    println!("test event_bridge_csp ... ok");
+    app.exit(0);
+  } else {
+    fail(&app, &format!("the listener received {payload:?}"));
+  }
+}
+
+fn main() {
+  if std::env::var_os("TAURI_WEBVIEW_TESTS").is_none() {
+    println!("skipping event_bridge_csp, set TAURI_WEBVIEW_TESTS to run it");
+    return;
+  }
+
+  let mut context = mock_context(Page);
+  context.config_mut().app.with_global_tauri = true;
+  let app = tauri::Builder::default()
+    .event_transport(EventTransport::Bridge)
+    .scope_policy(AllowAll)
+    .invoke_handler(tauri::generate_handler![ready, received])
+    .build(context)
+    .expect("failed to build the app");
+
+  WebviewWindowBuilder::new(&app, "main", WebviewUrl::App("index.html".into()))
+    .visible(false)
+    .build()
+    .expect("failed to create the window");
+
+  std::thread::spawn(|| {
+    std::thread::sleep(Duration::from_secs(60));
+    eprintln!("test event_bridge_csp ... FAILED: the listener did not receive the event");
+    std::process::exit(1);
+  });
+
+  app.run(|_, _| {});
+}
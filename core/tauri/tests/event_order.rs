@@ -0,0 +1,129 @@
+// Copyright 2019-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Feeds out of order events to the event initialization script in a real webview
+//! and checks that the listeners receive each stream in order.
+//!
+//! The test needs a display, so it only runs when the `TAURI_WEBVIEW_TESTS` environment variable is set,
+//! e.g. `TAURI_WEBVIEW_TESTS=1 xvfb-run cargo test -p tauri --features test --test event_order` on Linux.
+
+use std::{borrow::Cow, time::Duration};
+
+use tauri::{
+  test::mock_context,
+  utils::assets::{AssetKey, Assets, CspHash},
+  AppHandle, WebviewUrl, WebviewWindowBuilder,
+};
+
+const INDEX: &[u8] = br#"<!DOCTYPE html>
+<html>
+  <head>
+    <script>
+      const emit = window['__internal_unstable_listeners_function_id__']
+      const listenersObject = '__internal_unstable_listeners_object_id__'
+      if (window[listenersObject] === void 0) {
+        Object.defineProperty(window, listenersObject, { value: Object.create(null) })
+      }
+      const received = []
+      Object.defineProperty(window[listenersObject], 'order', {
+        value: [
+          { id: 1, target: { kind: 'Any' }, handler: (event) => received.push(event.payload) }
+        ]
+      })
+
+      const target = { kind: 'Any' }
+      const send = (payload, key, epoch, index) =>
+        emit({ event: 'order', payload, sequence: { key, epoch, index } }, target)
+
+      // the stream starts at the first event the page receives
+      send('a0', 'a', 0, 0)
+      // held back until the missing events arrive
+      send('a3', 'a', 0, 3)
+      send('a2', 'a', 0, 2)
+      // the streams are independent
+      send('b5', 'b', 0, 5)
+      send('a1', 'a', 0, 1)
+      // a batch takes a single position in the stream
+      emit({ event: 'order', batch: ['a5', 'a6'], sequence: { key: 'a', epoch: 0, index: 5 } }, target)
+      send('a4', 'a', 0, 4)
+      // events evaluated before the stream started are not held back
+      send('b4', 'b', 0, 4)
+      // a new epoch restarts the stream, the events of the previous one are not held back
+      send('a-next', 'a', 1, 2)
+      send('a7', 'a', 0, 7)
+      // events without a sequence are delivered right away
+      emit({ event: 'order', payload: 'unsequenced' }, target)
+
+      window.__TAURI_INTERNALS__.invoke('result', { received })
+    </script>
+  </head>
+  <body>event order</body>
+</html>"#;
+
+const EXPECTED: &[&str] = &[
+  "a0",
+  "b5",
+  "a1",
+  "a2",
+  "a3",
+  "a4",
+  "a5",
+  "a6",
+  "b4",
+  "a-next",
+  "a7",
+  "unsequenced",
+];
+
+struct Page;
+
+impl Assets for Page {
+  fn get(&self, key: &AssetKey) -> Option<Cow<'_, [u8]>> {
+    (key.as_ref() == "/index.html").then_some(Cow::Borrowed(INDEX))
+  }
+
+  fn iter(&self) -> Box<dyn Iterator<Item = (&&str, &&[u8])> + '_> {
+    Box::new(std::iter::empty())
+  }
+
+  fn csp_hashes(&self, _html_path: &AssetKey) -> Box<dyn Iterator<Item = CspHash<'_>> + '_> {
+    Box::new(std::iter::empty())
+  }
+}
+
+#[tauri::command]
+fn result(app: AppHandle, received: Vec<String>) {
+  if received == EXPECTED {
+    println!("test event_order ... ok");
+    app.exit(0);
+  } else {
+    eprintln!("test event_order ... FAILED: the listener received {received:?}");
+    app.exit(1);
+  }
+}
+
+fn main() {
+  if std::env::var_os("TAURI_WEBVIEW_TESTS").is_none() {
+    println!("skipping event_order, set TAURI_WEBVIEW_TESTS to run it");
+    return;
+  }
+
+  let app = tauri::Builder::default()
+    .invoke_handler(tauri::generate_handler![result])
+    .build(mock_context(Page))
+    .expect("failed to build the app");
+
+  WebviewWindowBuilder::new(&app, "main", WebviewUrl::App("index.html".into()))
+    .visible(false)
+    .build()
+    .expect("failed to create the window");
+
+  std::thread::spawn(|| {
+    std::thread::sleep(Duration::from_secs(60));
+    eprintln!("test event_order ... FAILED: the page did not report the events");
+    std::process::exit(1);
+  });
+
+  app.run(|_, _| {});
+}
@@ -2,8 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
-use tauri::command;
+use tauri::{command, ipc::CancellationToken};
 
 #[derive(Debug, Deserialize)]
 #[allow(unused)]
@@ -30,6 +32,23 @@ pub fn perform_request(endpoint: String, body: RequestBody) -> ApiResponse {
   }
 }
 
+/// Runs for a few seconds, stopping early when the frontend aborts the invoke.
+#[command]
+pub async fn long_task(token: CancellationToken) -> Result<u32, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    for step in 0..100 {
+      if token.is_cancelled() {
+        log::info!("long task cancelled at step {step}");
+        return Err("cancelled".to_string());
+      }
+      std::thread::sleep(Duration::from_millis(50));
+    }
+    Ok(100)
+  })
+  .await
+  .map_err(|e| e.to_string())?
+}
+
 #[cfg(all(desktop, not(target_os = "macos")))]
 #[command]
 pub fn toggle_menu<R: tauri::Runtime>(window: tauri::Window<R>) {
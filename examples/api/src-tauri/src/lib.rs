@@ -140,6 +140,7 @@ pub fn run_app<R: Runtime, F: FnOnce(&App<R>) + Send + 'static>(
     .invoke_handler(tauri::generate_handler![
       cmd::log_operation,
       cmd::perform_request,
+      cmd::long_task,
       #[cfg(desktop)]
       cmd::toggle_menu,
       #[cfg(desktop)]
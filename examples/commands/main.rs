@@ -11,7 +11,7 @@ use commands::{cmd, invoke, message, resolver};
 use serde::Deserialize;
 use tauri::{
   command,
-  ipc::{Request, Response},
+  ipc::{Request, Response, StreamResponse},
   State, Window,
 };
 
@@ -223,6 +223,28 @@ fn raw_request(request: Request<'_>) -> Response {
   Response::new(include_bytes!("./README.md").to_vec())
 }
 
+// the frontend receives an async iterator of the file lines
+#[command]
+async fn stream_lines(path: std::path::PathBuf) -> Result<StreamResponse, String> {
+  use tokio::io::AsyncBufReadExt;
+
+  let file = tokio::fs::File::open(path)
+    .await
+    .map_err(|e| e.to_string())?;
+  let lines = tokio::io::BufReader::new(file).lines();
+  Ok(StreamResponse::try_new(futures_util::stream::unfold(
+    lines,
+    |mut lines| async move {
+      lines
+        .next_line()
+        .await
+        .map_err(|e| e.to_string())
+        .transpose()
+        .map(|line| (line, lines))
+    },
+  )))
+}
+
 fn main() {
   tauri::Builder::default()
     .manage(MyState {
@@ -233,6 +255,7 @@ fn main() {
       borrow_cmd,
       borrow_cmd_async,
       raw_request,
+      stream_lines,
       window_label,
       force_async,
       force_async_with_result,
@@ -3,6 +3,8 @@
 // SPDX-License-Identifier: MIT
 
 import { invoke } from './core'
+import { emit, listen } from './event'
+import type { UnlistenFn } from './event'
 
 /**
  * Application metadata and related APIs.
@@ -53,6 +55,43 @@ async function getTauriVersion(): Promise<string> {
   return invoke('plugin:app|tauri_version')
 }
 
+/**
+ * The environment the application runs in, meant to be included in bug reports.
+ * The fields that cannot be determined are set to `unknown`.
+ *
+ * @since 2.0.0
+ */
+interface RuntimeInfo {
+  /**
+   * The version of the webview installed on the system.
+   * On Linux, it includes the webkit2gtk library the app is linked against.
+   */
+  webviewVersion: string
+  /** The name of the Rust runtime, e.g. `wry`. */
+  runtime: string
+  /** The operating system, e.g. `linux`, `macos` or `windows`. */
+  os: string
+  /** The CPU architecture, e.g. `x86_64` or `aarch64`. */
+  arch: string
+  /** The version of Tauri. */
+  tauriVersion: string
+}
+
+/**
+ * Gets the environment the application runs in, without a round trip to the Rust side.
+ *
+ * @example
+ * ```typescript
+ * import { getRuntimeInfo } from '@tauri-apps/api/app';
+ * const { webviewVersion, os } = getRuntimeInfo();
+ * ```
+ *
+ * @since 2.0.0
+ */
+function getRuntimeInfo(): RuntimeInfo {
+  return window.__TAURI_INTERNALS__.metadata.runtimeInfo
+}
+
 /**
  * Shows the application on macOS. This function does not automatically focus any specific app window.
  *
@@ -83,4 +122,46 @@ async function hide(): Promise<void> {
   return invoke('plugin:app|app_hide')
 }
 
-export { getName, getVersion, getTauriVersion, show, hide }
+/**
+ * Runs the handler when the application is about to exit,
+ * delaying the exit until the returned promise resolves.
+ *
+ * The exit is only delayed if the Rust side calls `AppHandle::flush_webviews` in a `Builder::on_before_exit` hook,
+ * and for at most the timeout configured with `Builder::before_exit_timeout`.
+ *
+ * @example
+ * ```typescript
+ * import { onBeforeExit } from '@tauri-apps/api/app';
+ * const unlisten = await onBeforeExit(async () => {
+ *   await saveDraft();
+ * });
+ * ```
+ *
+ * @since 2.0.0
+ */
+async function onBeforeExit(
+  handler: () => void | Promise<void>
+): Promise<UnlistenFn> {
+  return listen('tauri://before-exit', async () => {
+    try {
+      await handler()
+    } finally {
+      await emit(
+        'tauri://before-exit-ack',
+        window.__TAURI_INTERNALS__.metadata.currentWebview.label
+      )
+    }
+  })
+}
+
+export type { RuntimeInfo }
+
+export {
+  getName,
+  getVersion,
+  getTauriVersion,
+  getRuntimeInfo,
+  show,
+  hide,
+  onBeforeExit
+}
@@ -9,16 +9,31 @@
  * @module
  */
 
+/**
+ * Metadata the backend sends with a command response,
+ * see `tauri::ipc::InvokeResolver::resolve_with_meta`.
+ *
+ * @since 2.0.0
+ */
+interface ResponseMeta {
+  /** The response headers. */
+  headers: Record<string, string>
+  /** The status code of the response, if any. */
+  status: number | null
+}
+
 /**
  * Transforms a callback function to a string identifier that can be passed to the backend.
  * The backend uses the identifier to `eval()` the callback.
  *
+ * The callback receives the {@link ResponseMeta} of the response as its second argument when the backend sends it.
+ *
  * @return A unique identifier associated with the callback function.
  *
  * @since 1.0.0
  */
 function transformCallback<T = unknown>(
-  callback?: (response: T) => void,
+  callback?: (response: T, meta?: ResponseMeta) => void,
   once = false
 ): number {
   return window.__TAURI_INTERNALS__.transformCallback(callback, once)
@@ -100,7 +115,16 @@ type InvokeArgs = Record<string, unknown> | number[] | ArrayBuffer | Uint8Array
  * @since 2.0.0
  */
 interface InvokeOptions {
-  headers: Headers | Record<string, string>
+  /**
+   * The request headers, read by commands with the `tauri::ipc::Headers` and `tauri::ipc::TypedHeader` arguments.
+   */
+  headers?: Headers | Record<string, string>
+  /**
+   * Aborts the invoke, rejecting the promise with the signal reason
+   * and cancelling the `tauri::ipc::CancellationToken` of the command.
+   * The command response is then discarded.
+   */
+  signal?: AbortSignal
 }
 
 /**
@@ -111,6 +135,22 @@ interface InvokeOptions {
  * await invoke('login', { user: 'tauri', password: 'poiwe3h4r5ip3yrhtew9ty' });
  * ```
  *
+ * When the command returns a stream, the promise resolves to an `AsyncIterable` of the stream items:
+ * ```typescript
+ * import { invoke } from '@tauri-apps/api/core';
+ * for await (const line of await invoke<AsyncIterable<string>>('read_lines', { path })) {
+ *   console.log(line);
+ * }
+ * ```
+ *
+ * Long-running commands can be cancelled with an `AbortSignal`:
+ * ```typescript
+ * import { invoke } from '@tauri-apps/api/core';
+ * const controller = new AbortController();
+ * const result = invoke('crunch', {}, { signal: controller.signal });
+ * controller.abort();
+ * ```
+ *
  * @param cmd The command name.
  * @param args The optional arguments to pass to the command.
  * @param options The request options.
@@ -126,6 +166,29 @@ async function invoke<T>(
   return window.__TAURI_INTERNALS__.invoke(cmd, args, options)
 }
 
+/**
+ * Synchronously calls a command registered with `tauri::Builder::sync_command`.
+ *
+ * This blocks the page until the backend responds, so it is meant for tiny hot-path queries
+ * such as reading a cached value on every keystroke. Use {@linkcode invoke} for everything else.
+ * @example
+ * ```typescript
+ * import { invokeSync } from '@tauri-apps/api/core';
+ * const enabled = invokeSync<boolean>('spellcheck_enabled');
+ * ```
+ *
+ * @param cmd The sync command name.
+ * @param args The optional JSON arguments to pass to the command.
+ * @return The backend response.
+ * @throws The command error, or an object with a `kind` of `SyncCommandTimeout` or `SyncResponseTooLarge`
+ * when the command exceeds its time budget or response size cap.
+ *
+ * @since 2.0.0
+ */
+function invokeSync<T>(cmd: string, args?: unknown): T {
+  return window.__TAURI_INTERNALS__.invokeSync(cmd, args) as T
+}
+
 /**
  * Convert a device file path to an URL that can be loaded by the webview.
  * Note that `asset:` and `http://asset.localhost` must be added to [`tauri.security.csp`](https://tauri.app/v1/api/config/#securityconfig.csp) in `tauri.conf.json`.
@@ -204,7 +267,7 @@ export class Resource {
   }
 }
 
-export type { InvokeArgs, InvokeOptions }
+export type { InvokeArgs, InvokeOptions, ResponseMeta }
 
 export {
   transformCallback,
@@ -212,5 +275,6 @@ export {
   PluginListener,
   addPluginListener,
   invoke,
+  invokeSync,
   convertFileSrc
 }
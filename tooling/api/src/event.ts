@@ -26,6 +26,8 @@ interface Event<T> {
   id: number
   /** Event payload */
   payload: T
+  /** The profile the event was emitted for, if any */
+  profile?: string
 }
 
 type EventCallback<T> = (event: Event<T>) => void
@@ -56,6 +58,7 @@ enum TauriEvent {
   WINDOW_SCALE_FACTOR_CHANGED = 'tauri://scale-change',
   WINDOW_THEME_CHANGED = 'tauri://theme-changed',
   WEBVIEW_CREATED = 'tauri://webview-created',
+  WEBVIEW_RECOVERED = 'tauri://webview-recovered',
   WEBVIEW_FILE_DROP = 'tauri://file-drop',
   WEBVIEW_FILE_DROP_HOVER = 'tauri://file-drop-hover',
   WEBVIEW_FILE_DROP_CANCELLED = 'tauri://file-drop-cancelled'
@@ -4,13 +4,20 @@
 
 /** @ignore */
 
-import type { invoke, transformCallback, convertFileSrc } from './core'
+import type {
+  invoke,
+  invokeSync,
+  transformCallback,
+  convertFileSrc
+} from './core'
+import type { RuntimeInfo } from './app'
 
 /** @ignore */
 declare global {
   interface Window {
     __TAURI_INTERNALS__: {
       invoke: typeof invoke
+      invokeSync: typeof invokeSync
       transformCallback: typeof transformCallback
       convertFileSrc: typeof convertFileSrc
       ipc: (message: {
@@ -25,6 +32,7 @@ declare global {
         currentWindow: WindowDef
         webviews: WebviewDef[]
         currentWebview: WebviewDef
+        runtimeInfo: RuntimeInfo
       }
       plugins: {
         path: {
@@ -40,6 +48,7 @@ declare global {
 interface WebviewDef {
   windowLabel: string
   label: string
+  profile?: string | null
 }
 
 /** @ignore */
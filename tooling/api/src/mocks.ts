@@ -152,7 +152,14 @@ export function mockWindows(
       windowLabel: label,
       label
     })),
-    currentWebview: { windowLabel: current, label: current }
+    currentWebview: { windowLabel: current, label: current },
+    runtimeInfo: {
+      webviewVersion: 'unknown',
+      runtime: 'mock',
+      os: 'unknown',
+      arch: 'unknown',
+      tauriVersion: 'unknown'
+    }
   }
 }
 
@@ -31,7 +31,7 @@ import {
 } from './event'
 import { invoke } from './core'
 import { Window, getCurrent as getCurrentWindow } from './window'
-import type { WindowOptions } from './window'
+import type { Theme, WindowOptions } from './window'
 
 interface FileDropPayload {
   paths: string[]
@@ -376,6 +376,22 @@ class Webview {
     ).then(({ width, height }) => new PhysicalSize(width, height))
   }
 
+  /**
+   * The theme used by the webview content, either the one forced with {@link Webview.setTheme} or the system theme.
+   * @example
+   * ```typescript
+   * import { getCurrent } from '@tauri-apps/api/webview';
+   * const theme = await getCurrent().theme();
+   * ```
+   *
+   * @returns The webview theme.
+   */
+  async theme(): Promise<Theme> {
+    return invoke('plugin:webview|webview_theme', {
+      label: this.label
+    })
+  }
+
   // Setters
 
   /**
@@ -475,8 +491,78 @@ class Webview {
     })
   }
 
+  /**
+   * Forces the `prefers-color-scheme` of the webview content.
+   * Passing `null` follows the system theme.
+   *
+   * #### Platform-specific
+   *
+   * - **Linux**: Sets the GTK dark theme preference, which affects the whole application.
+   * - **iOS / Android:** Unsupported.
+   *
+   * @example
+   * ```typescript
+   * import { getCurrent } from '@tauri-apps/api/webview';
+   * await getCurrent().setTheme('dark');
+   * ```
+   *
+   * @returns A promise indicating the success or failure of the operation.
+   */
+  async setTheme(theme: Theme | null): Promise<void> {
+    return invoke('plugin:webview|set_webview_theme', {
+      label: this.label,
+      value: theme
+    })
+  }
+
+  /**
+   * Changes how timers and rendering behave while the webview is hidden or in the background.
+   *
+   * #### Platform-specific
+   *
+   * - **macOS**: Requires macOS 14+.
+   * - **Windows / Linux / iOS / Android:** Unsupported, the promise is rejected.
+   *   On Windows the policy can only be set with the {@link WebviewOptions.backgroundThrottling} option.
+   *
+   * @example
+   * ```typescript
+   * import { getCurrent } from '@tauri-apps/api/webview';
+   * await getCurrent().setBackgroundThrottling('disabled');
+   * ```
+   *
+   * @returns A promise indicating the success or failure of the operation.
+   */
+  async setBackgroundThrottling(
+    policy: BackgroundThrottlingPolicy
+  ): Promise<void> {
+    return invoke('plugin:webview|set_webview_background_throttling', {
+      label: this.label,
+      value: policy
+    })
+  }
+
   // Listeners
 
+  /**
+   * Listen to the webview theme change, triggered by {@link Webview.setTheme}.
+   * @example
+   * ```typescript
+   * import { getCurrent } from '@tauri-apps/api/webview';
+   * const unlisten = await getCurrent().onThemeChanged(({ payload: theme }) => {
+   *   console.log('New theme: ' + theme);
+   * });
+   *
+   * // you need to call unlisten if your handler goes out of scope e.g. the component is unmounted
+   * unlisten();
+   * ```
+   *
+   * @returns A promise resolving to a function to unlisten to the event.
+   * Note that removing the listener is required if your listener goes out of scope e.g. the component is unmounted.
+   */
+  async onThemeChanged(handler: EventCallback<Theme>): Promise<UnlistenFn> {
+    return this.listen<Theme>(TauriEvent.WINDOW_THEME_CHANGED, handler)
+  }
+
   /**
    * Listen to a file drop event.
    * The listener is triggered when the user hovers the selected files on the webview,
@@ -779,6 +865,12 @@ interface WebviewOptions {
    * Whether clicking an inactive webview also clicks through to the webview on macOS.
    */
   acceptFirstMouse?: boolean
+  /**
+   * Whether the webview is focused when created. Defaults to `true`.
+   *
+   * The webview is never focused when its window is not focused or is minimized.
+   */
+  focus?: boolean
   /**
    * The user agent for the webview.
    */
@@ -801,8 +893,115 @@ interface WebviewOptions {
    * - **macOS**: Requires the `macos-proxy` feature flag and only compiles for macOS 14+.
    * */
   proxyUrl?: string
+  /**
+   * How timers and rendering behave while the webview is hidden or in the background.
+   * Uses the platform default if not set.
+   *
+   * #### Platform-specific
+   *
+   * - **Windows**: Only `disabled` has an effect.
+   * - **macOS**: Requires macOS 14+.
+   * - **Linux / iOS / Android**: Unsupported.
+   */
+  backgroundThrottling?: BackgroundThrottlingPolicy
+}
+
+/**
+ * How timers and rendering behave while the webview is hidden or in the background.
+ *
+ * - `disabled`: timers and rendering keep running at full speed.
+ * - `suspend`: the webview is suspended while hidden.
+ * - `throttle`: timers and rendering are throttled, the platform default.
+ */
+type BackgroundThrottlingPolicy = 'disabled' | 'suspend' | 'throttle'
+
+/**
+ * A message sent by another webview with {@link postMessage}.
+ */
+interface WebviewMessage {
+  /** The label of the webview that sent the message. */
+  source: WebviewLabel
+  /** The message data. */
+  data: ArrayBuffer
+}
+
+/**
+ * Sends binary data to the webview with the given label, received by its {@link onMessage} handlers.
+ *
+ * The data is sent through the IPC protocol without being serialized to JSON.
+ * The promise is rejected if the messages waiting to be received by the target webview exceed the size limit,
+ * for instance when it does not listen to messages.
+ *
+ * @example
+ * ```typescript
+ * import { postMessage } from '@tauri-apps/api/webview';
+ * await postMessage('preview', new TextEncoder().encode('hello'));
+ * ```
+ *
+ * @since 2.0.0
+ */
+async function postMessage(
+  label: WebviewLabel,
+  data: ArrayBuffer | Uint8Array
+): Promise<void> {
+  return invoke('plugin:webview|post_webview_message', data, {
+    headers: { 'Tauri-Webview-Target': label }
+  })
 }
 
-export { Webview, WebviewWindow, getCurrent, getAll }
+/** @ignore */
+const messageHandlers = new Set<(message: WebviewMessage) => void>()
+/** @ignore */
+// a single listener receives each message once and dispatches it to every handler
+let messageListener: Promise<UnlistenFn> | null = null
 
-export type { FileDropEvent, WebviewOptions }
+/**
+ * Listens to the messages sent to the current webview with {@link postMessage}.
+ *
+ * @example
+ * ```typescript
+ * import { onMessage } from '@tauri-apps/api/webview';
+ * const unlisten = await onMessage(({ source, data }) => {
+ *   console.log(`received ${data.byteLength} bytes from ${source}`);
+ * });
+ * ```
+ *
+ * @returns A promise resolving to a function to stop listening to the messages.
+ *
+ * @since 2.0.0
+ */
+async function onMessage(
+  handler: (message: WebviewMessage) => void
+): Promise<UnlistenFn> {
+  messageHandlers.add(handler)
+  if (!messageListener) {
+    messageListener = listen<{ id: number; source: WebviewLabel }>(
+      'tauri://webview-message',
+      ({ payload }) => {
+        void invoke<ArrayBuffer>(
+          'plugin:webview|take_webview_message',
+          {},
+          {
+            headers: { 'Tauri-Webview-Message-Id': payload.id.toString() }
+          }
+        ).then((data) => {
+          messageHandlers.forEach((h) => h({ source: payload.source, data }))
+        })
+      },
+      { target: getCurrent().label }
+    )
+  }
+  await messageListener
+  return () => {
+    messageHandlers.delete(handler)
+  }
+}
+
+export { Webview, WebviewWindow, getCurrent, getAll, postMessage, onMessage }
+
+export type {
+  BackgroundThrottlingPolicy,
+  FileDropEvent,
+  WebviewMessage,
+  WebviewOptions
+}